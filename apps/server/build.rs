@@ -0,0 +1,56 @@
+//! Embeds the git commit and build time, exposed by `build_info`.
+//!
+//! Both can be overridden through the environment, for builds without a git
+//! checkout (`RUSTRAK_GIT_SHA`, e.g. Docker) or reproducible builds
+//! (`SOURCE_DATE_EPOCH`).
+
+use std::process::Command;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+fn main() {
+    println!("cargo:rustc-env=RUSTRAK_GIT_SHA={}", git_sha());
+    println!("cargo:rustc-env=RUSTRAK_BUILT_AT={}", built_at());
+
+    println!("cargo:rerun-if-env-changed=RUSTRAK_GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=migrations");
+    if let Some(head) = git(&["rev-parse", "--git-path", "HEAD"]) {
+        println!("cargo:rerun-if-changed={}", head);
+    }
+    if let Some(branch) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        if let Some(git_ref) = git(&["rev-parse", "--git-path", &branch]) {
+            println!("cargo:rerun-if-changed={}", git_ref);
+        }
+    }
+}
+
+fn git_sha() -> String {
+    std::env::var("RUSTRAK_GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| git(&["rev-parse", "--short=7", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn built_at() -> String {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .and_then(|epoch| DateTime::<Utc>::from_timestamp(epoch, 0))
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Output of a git command, if git is available and it succeeds
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_string()).filter(|s| !s.is_empty())
+}
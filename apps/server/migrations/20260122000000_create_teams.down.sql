@@ -0,0 +1,5 @@
+-- Revert teams
+DROP INDEX IF EXISTS idx_projects_team;
+ALTER TABLE projects DROP COLUMN IF EXISTS team_id;
+DROP TABLE IF EXISTS team_default_channels;
+DROP TABLE IF EXISTS teams;
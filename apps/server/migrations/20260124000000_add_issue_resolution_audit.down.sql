@@ -0,0 +1,2 @@
+ALTER TABLE issues DROP COLUMN IF EXISTS resolved_by;
+ALTER TABLE issues DROP COLUMN IF EXISTS resolved_at;
@@ -0,0 +1,3 @@
+-- Who resolved an issue and when (resolved_by NULL = automated resolution)
+ALTER TABLE issues ADD COLUMN resolved_at TIMESTAMPTZ;
+ALTER TABLE issues ADD COLUMN resolved_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,6 @@
+-- Number of exceptions in the chain of the issue's events (0 for log messages)
+ALTER TABLE issues ADD COLUMN exception_chain_length INTEGER NOT NULL DEFAULT 0;
+
+-- Existing exception issues were grouped off a single exception
+UPDATE issues SET exception_chain_length = 1
+WHERE calculated_type NOT IN ('Log Message', 'Unknown');
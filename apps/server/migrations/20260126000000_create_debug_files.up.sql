@@ -0,0 +1,15 @@
+-- Debug files: Breakpad symbol files used to symbolicate native stack traces
+-- Keyed by the debug ID of the module they describe; re-uploads replace the file
+CREATE TABLE debug_files (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    debug_id VARCHAR(64) NOT NULL,
+    code_file VARCHAR(255) NOT NULL,
+    os VARCHAR(32) NOT NULL,
+    arch VARCHAR(32) NOT NULL,
+    file_size INTEGER NOT NULL,
+    data BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE(project_id, debug_id)
+);
@@ -0,0 +1,3 @@
+-- Digest window for alert rules: 0 sends alerts immediately, otherwise
+-- alerts are batched and sent as one summary per window
+ALTER TABLE alert_rules ADD COLUMN digest_minutes INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+-- Issue contexts: per-issue event counts by OS, browser and runtime
+-- Values are "name major.minor" as extracted from the event's contexts
+CREATE TABLE issue_contexts (
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    dimension VARCHAR(16) NOT NULL,
+    value VARCHAR(255) NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (issue_id, dimension, value)
+);
@@ -0,0 +1,11 @@
+-- Issue seen: when each user last opened an issue's detail
+CREATE TABLE issue_seen (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, issue_id)
+);
+
+-- For listing who has seen an issue
+CREATE INDEX idx_issue_seen_issue ON issue_seen(issue_id, seen_at DESC);
@@ -0,0 +1,15 @@
+ALTER TABLE projects ADD COLUMN sentry_key UUID UNIQUE DEFAULT gen_random_uuid();
+
+-- Keep each project's oldest key, so its original DSN keeps working
+UPDATE projects p SET sentry_key = k.key
+FROM (
+    SELECT DISTINCT ON (project_id) project_id, key
+    FROM project_keys
+    ORDER BY project_id, created_at, id
+) k
+WHERE k.project_id = p.id;
+
+ALTER TABLE projects ALTER COLUMN sentry_key SET NOT NULL;
+CREATE INDEX idx_projects_sentry_key ON projects(sentry_key);
+
+DROP TABLE IF EXISTS project_keys;
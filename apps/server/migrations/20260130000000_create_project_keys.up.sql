@@ -0,0 +1,19 @@
+-- Project keys: the sentry_key values SDKs authenticate with, several per project
+CREATE TABLE project_keys (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    key UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+    label VARCHAR(255) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_project_keys_project ON project_keys(project_id);
+
+-- Existing DSNs keep working: each project's key becomes its default key
+INSERT INTO project_keys (project_id, key, label, created_at)
+SELECT id, sentry_key, 'Default', created_at FROM projects;
+
+DROP INDEX IF EXISTS idx_projects_sentry_key;
+ALTER TABLE projects DROP COLUMN sentry_key;
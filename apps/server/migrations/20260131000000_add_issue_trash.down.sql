@@ -0,0 +1,31 @@
+-- Drop detached groupings whose key has been taken by a newer issue
+DELETE FROM groupings g
+WHERE g.detached_at IS NOT NULL
+  AND EXISTS (
+      SELECT 1 FROM groupings a
+      WHERE a.project_id = g.project_id
+        AND a.grouping_key_hash = g.grouping_key_hash
+        AND a.detached_at IS NULL
+  );
+
+DROP INDEX IF EXISTS idx_groupings_attached_key;
+ALTER TABLE groupings ADD CONSTRAINT groupings_project_id_grouping_key_hash_key
+    UNIQUE (project_id, grouping_key_hash);
+ALTER TABLE groupings DROP COLUMN detached_at;
+
+DROP INDEX IF EXISTS idx_issues_deleted_at;
+ALTER TABLE issues ADD COLUMN is_deleted BOOLEAN NOT NULL DEFAULT FALSE;
+UPDATE issues SET is_deleted = TRUE WHERE deleted_at IS NOT NULL;
+ALTER TABLE issues DROP COLUMN deleted_at;
+
+CREATE INDEX idx_issues_project_last_seen
+    ON issues(project_id, last_seen DESC)
+    WHERE NOT is_deleted;
+
+CREATE INDEX idx_issues_project_open
+    ON issues(project_id, is_resolved, is_muted, last_seen DESC)
+    WHERE NOT is_deleted;
+
+CREATE INDEX idx_issues_project_resolved
+    ON issues(project_id, is_resolved, last_seen DESC)
+    WHERE NOT is_deleted;
@@ -0,0 +1,33 @@
+-- Issue trash: deleted issues keep their events until the retention job
+-- removes them, and can be restored meanwhile
+ALTER TABLE issues ADD COLUMN deleted_at TIMESTAMPTZ;
+UPDATE issues SET deleted_at = NOW() WHERE is_deleted;
+
+-- Dropping is_deleted drops the list indexes, recreate them for deleted_at
+ALTER TABLE issues DROP COLUMN is_deleted;
+
+CREATE INDEX idx_issues_project_last_seen
+    ON issues(project_id, last_seen DESC)
+    WHERE deleted_at IS NULL;
+
+CREATE INDEX idx_issues_project_open
+    ON issues(project_id, is_resolved, is_muted, last_seen DESC)
+    WHERE deleted_at IS NULL;
+
+CREATE INDEX idx_issues_project_resolved
+    ON issues(project_id, is_resolved, last_seen DESC)
+    WHERE deleted_at IS NULL;
+
+-- For the trash view and the retention job
+CREATE INDEX idx_issues_deleted_at ON issues(project_id, deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Groupings of deleted issues are detached, so recurring events open a new
+-- issue; only attached groupings need unique keys
+ALTER TABLE groupings ADD COLUMN detached_at TIMESTAMPTZ;
+UPDATE groupings g SET detached_at = i.deleted_at
+FROM issues i
+WHERE i.id = g.issue_id AND i.deleted_at IS NOT NULL;
+
+ALTER TABLE groupings DROP CONSTRAINT groupings_project_id_grouping_key_hash_key;
+CREATE UNIQUE INDEX idx_groupings_attached_key
+    ON groupings(project_id, grouping_key_hash) WHERE detached_at IS NULL;
@@ -0,0 +1,12 @@
+-- Events accepted by ingest, so a resent or re-digested event_id is only
+-- stored once
+CREATE TABLE ingested_events (
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL,
+    ingested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Set once the event is stored; NULL while it waits in INGEST_DIR
+    digested_at TIMESTAMPTZ,
+    PRIMARY KEY (project_id, event_id)
+);
+
+CREATE INDEX idx_ingested_events_ingested_at ON ingested_events(ingested_at);
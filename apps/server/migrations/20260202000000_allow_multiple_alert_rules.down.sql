@@ -0,0 +1,9 @@
+-- Keep the oldest rule of each type so the constraint can be restored
+DELETE FROM alert_rules a
+USING alert_rules b
+WHERE a.project_id = b.project_id
+  AND a.alert_type = b.alert_type
+  AND a.id > b.id;
+
+ALTER TABLE alert_rules
+    ADD CONSTRAINT alert_rules_project_id_alert_type_key UNIQUE (project_id, alert_type);
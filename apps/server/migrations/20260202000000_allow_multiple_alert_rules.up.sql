@@ -0,0 +1,3 @@
+-- Several rules of one type per project, e.g. fatals to one channel and
+-- everything else to another; rule filters live in `conditions`
+ALTER TABLE alert_rules DROP CONSTRAINT IF EXISTS alert_rules_project_id_alert_type_key;
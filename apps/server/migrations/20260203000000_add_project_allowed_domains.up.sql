@@ -0,0 +1,2 @@
+-- Origins browser events are accepted from; empty accepts any origin
+ALTER TABLE projects ADD COLUMN allowed_domains TEXT[] NOT NULL DEFAULT '{}';
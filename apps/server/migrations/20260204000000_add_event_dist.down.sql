@@ -0,0 +1,2 @@
+DELETE FROM issue_contexts WHERE dimension = 'dist';
+ALTER TABLE events DROP COLUMN dist;
@@ -0,0 +1,2 @@
+-- Distribution of the event's release, e.g. a mobile build number
+ALTER TABLE events ADD COLUMN dist VARCHAR(64) NOT NULL DEFAULT '';
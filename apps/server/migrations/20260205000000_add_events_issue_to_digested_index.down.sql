@@ -0,0 +1,3 @@
+-- Restore the project/time index without issue_id
+DROP INDEX IF EXISTS idx_events_project_digested;
+CREATE INDEX idx_events_project_digested ON events(project_id, digested_at DESC);
@@ -0,0 +1,4 @@
+-- Cover issue_id in the project/time index so aggregating issues over a
+-- period only reads the index, not the events themselves
+DROP INDEX IF EXISTS idx_events_project_digested;
+CREATE INDEX idx_events_project_digested ON events(project_id, digested_at DESC) INCLUDE (issue_id);
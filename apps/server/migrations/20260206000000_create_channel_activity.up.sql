@@ -0,0 +1,13 @@
+-- Channel activity: audit log of channels being disabled after repeated
+-- failures and of their failure streaks being reset
+CREATE TABLE notification_channel_activity (
+    id BIGSERIAL PRIMARY KEY,
+    channel_id INTEGER REFERENCES notification_channels(id) ON DELETE SET NULL,
+    channel_name VARCHAR(255) NOT NULL,
+    action VARCHAR(50) NOT NULL CHECK (action IN ('auto_disabled', 'failures_reset')),
+    message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notification_channel_activity_channel
+    ON notification_channel_activity(channel_id, created_at DESC);
@@ -0,0 +1,2 @@
+ALTER TABLE issues DROP COLUMN is_unhandled;
+ALTER TABLE events DROP COLUMN is_unhandled;
@@ -0,0 +1,12 @@
+-- Whether an exception of the event wasn't caught (mechanism.handled = false)
+ALTER TABLE events ADD COLUMN is_unhandled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Whether any event of the issue was unhandled
+ALTER TABLE issues ADD COLUMN is_unhandled BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE events SET is_unhandled = TRUE
+WHERE jsonb_path_exists(data, '$.exception.values[*].mechanism.handled ? (@ == false)')
+   OR jsonb_path_exists(data, '$.exception[*].mechanism.handled ? (@ == false)');
+
+UPDATE issues SET is_unhandled = TRUE
+WHERE id IN (SELECT DISTINCT issue_id FROM events WHERE is_unhandled);
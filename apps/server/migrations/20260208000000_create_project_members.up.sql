@@ -0,0 +1,15 @@
+-- Project members: the users who can see a project, its issues and events.
+-- Admins see every project without being members.
+CREATE TABLE project_members (
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (role IN ('admin', 'member')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, user_id)
+);
+
+CREATE INDEX idx_project_members_user ON project_members(user_id);
+
+-- Existing users keep seeing the projects they could see so far
+INSERT INTO project_members (project_id, user_id)
+SELECT p.id, u.id FROM projects p CROSS JOIN users u WHERE NOT u.is_admin;
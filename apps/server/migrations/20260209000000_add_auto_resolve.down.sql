@@ -0,0 +1,2 @@
+ALTER TABLE issues DROP COLUMN auto_resolved;
+ALTER TABLE projects DROP COLUMN auto_resolve_days;
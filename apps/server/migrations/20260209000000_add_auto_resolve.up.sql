@@ -0,0 +1,7 @@
+-- Days without events after which a project's open issues are resolved;
+-- NULL keeps them open
+ALTER TABLE projects ADD COLUMN auto_resolve_days INTEGER CHECK (auto_resolve_days > 0);
+
+-- Whether the issue was resolved by the auto-resolve job rather than a user;
+-- a new event reopens it
+ALTER TABLE issues ADD COLUMN auto_resolved BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+ALTER TABLE issues DROP COLUMN last_event_order;
+DROP TABLE IF EXISTS project_sequences;
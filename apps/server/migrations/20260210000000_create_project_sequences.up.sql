@@ -0,0 +1,24 @@
+-- Digest order allocation, kept apart from the event counters so recounts
+-- and concurrent digests can't hand out the same number twice
+
+-- Last issue digest_order handed out per project
+CREATE TABLE project_sequences (
+    project_id INTEGER PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    last_issue_order INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO project_sequences (project_id, last_issue_order)
+SELECT p.id, COALESCE(MAX(i.digest_order), 0)
+FROM projects p
+LEFT JOIN issues i ON i.project_id = p.id
+GROUP BY p.id;
+
+-- Last event digest_order handed out per issue; events used to be numbered
+-- with digested_event_count
+ALTER TABLE issues ADD COLUMN last_event_order INTEGER NOT NULL DEFAULT 0;
+
+UPDATE issues i
+SET last_event_order = GREATEST(
+    i.digested_event_count,
+    COALESCE((SELECT MAX(e.digest_order) FROM events e WHERE e.issue_id = i.id), 0)
+);
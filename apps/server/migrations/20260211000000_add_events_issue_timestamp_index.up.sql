@@ -0,0 +1,2 @@
+-- Lists an issue's events by their own timestamp (`by=timestamp`)
+CREATE INDEX idx_events_issue_timestamp ON events(issue_id, timestamp, digest_order);
@@ -0,0 +1,2 @@
+ALTER TABLE ingested_events DROP COLUMN sampled;
+ALTER TABLE issues DROP COLUMN sample_rate;
@@ -0,0 +1,6 @@
+-- Share of an issue's new events that are stored; NULL stores them all
+ALTER TABLE issues ADD COLUMN sample_rate DOUBLE PRECISION
+    CHECK (sample_rate > 0 AND sample_rate < 1);
+
+-- Whether the event was digested but not stored, its issue being sampled
+ALTER TABLE ingested_events ADD COLUMN sampled BOOLEAN NOT NULL DEFAULT FALSE;
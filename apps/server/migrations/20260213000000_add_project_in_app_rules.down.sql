@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN in_app_exclude;
+ALTER TABLE projects DROP COLUMN in_app_include;
@@ -0,0 +1,4 @@
+-- Module prefixes whose frames are marked in_app, or not, before grouping;
+-- empty keeps the flags the SDK sent
+ALTER TABLE projects ADD COLUMN in_app_include TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE projects ADD COLUMN in_app_exclude TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_events_unpruned_digested;
+ALTER TABLE events DROP COLUMN payload_pruned;
@@ -0,0 +1,5 @@
+-- Whether the raw payload was dropped by PAYLOAD_RETENTION_DAYS; the row keeps its metadata
+ALTER TABLE events ADD COLUMN payload_pruned BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Finds the events whose payload is due for pruning
+CREATE INDEX idx_events_unpruned_digested ON events(digested_at) WHERE NOT payload_pruned;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS issue_regressions;
+ALTER TABLE issues DROP COLUMN regression_count;
@@ -0,0 +1,12 @@
+-- Times an auto-resolved issue was reopened by a new event
+ALTER TABLE issues ADD COLUMN regression_count INTEGER NOT NULL DEFAULT 0;
+
+-- Issue regressions: when each reopening happened
+CREATE TABLE issue_regressions (
+    id BIGSERIAL PRIMARY KEY,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    regressed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- For listing an issue's regressions
+CREATE INDEX idx_issue_regressions_issue ON issue_regressions(issue_id, regressed_at DESC);
@@ -0,0 +1,3 @@
+-- Order of the issue list when the request doesn't pick one
+ALTER TABLE projects ADD COLUMN default_issue_sort VARCHAR(20) NOT NULL DEFAULT 'digest_order'
+    CHECK (default_issue_sort IN ('digest_order', 'last_seen'));
@@ -0,0 +1,8 @@
+UPDATE projects SET default_issue_sort = 'digest_order' WHERE default_issue_sort = 'priority';
+ALTER TABLE projects DROP CONSTRAINT projects_default_issue_sort_check;
+ALTER TABLE projects ADD CONSTRAINT projects_default_issue_sort_check
+    CHECK (default_issue_sort IN ('digest_order', 'last_seen'));
+
+DROP TABLE IF EXISTS issue_priority_changes;
+ALTER TABLE issues DROP COLUMN priority_is_manual;
+ALTER TABLE issues DROP COLUMN priority;
@@ -0,0 +1,24 @@
+-- How much attention an issue needs; set from its first event, raised when
+-- its events spike, or set by a user, after which it's left alone
+ALTER TABLE issues ADD COLUMN priority VARCHAR(10) NOT NULL DEFAULT 'medium'
+    CHECK (priority IN ('low', 'medium', 'high'));
+ALTER TABLE issues ADD COLUMN priority_is_manual BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Issue priority changes, manual or automatic
+CREATE TABLE issue_priority_changes (
+    id BIGSERIAL PRIMARY KEY,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    old_priority VARCHAR(10) NOT NULL,
+    new_priority VARCHAR(10) NOT NULL,
+    is_manual BOOLEAN NOT NULL,
+    changed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- For listing an issue's priority changes
+CREATE INDEX idx_issue_priority_changes_issue ON issue_priority_changes(issue_id, changed_at DESC);
+
+-- Projects can list issues by priority by default
+ALTER TABLE projects DROP CONSTRAINT projects_default_issue_sort_check;
+ALTER TABLE projects ADD CONSTRAINT projects_default_issue_sort_check
+    CHECK (default_issue_sort IN ('digest_order', 'last_seen', 'priority'));
@@ -0,0 +1,2 @@
+ALTER TABLE issues DROP COLUMN resolved_pr_url;
+ALTER TABLE issues DROP COLUMN resolved_commit;
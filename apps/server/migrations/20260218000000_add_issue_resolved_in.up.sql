@@ -0,0 +1,3 @@
+-- The change that fixed an issue, as given when resolving it
+ALTER TABLE issues ADD COLUMN resolved_commit VARCHAR(100);
+ALTER TABLE issues ADD COLUMN resolved_pr_url TEXT;
@@ -0,0 +1,2 @@
+-- IANA timezone daily stats are bucketed in; NULL means UTC
+ALTER TABLE projects ADD COLUMN timezone VARCHAR(64);
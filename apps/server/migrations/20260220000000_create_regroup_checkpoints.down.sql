@@ -0,0 +1 @@
+DROP TABLE IF EXISTS regroup_checkpoints;
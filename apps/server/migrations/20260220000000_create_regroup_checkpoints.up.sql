@@ -0,0 +1,15 @@
+-- Progress of `rustrak regroup --apply`, so an interrupted run continues
+-- where it stopped; the row is removed once the regroup finishes
+CREATE TABLE regroup_checkpoints (
+    project_id INTEGER PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+
+    -- Events are moved in id order; the last one checked
+    last_event_id UUID,
+    moved_events INTEGER NOT NULL DEFAULT 0,
+
+    -- Issues all of whose events move, mapped to the issue they merge into
+    merges JSONB NOT NULL DEFAULT '{}',
+
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
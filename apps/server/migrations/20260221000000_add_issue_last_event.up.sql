@@ -0,0 +1,11 @@
+-- Latest stored event of the issue, so it's fetched without sorting the
+-- issue's events; NULL when none is stored
+ALTER TABLE issues ADD COLUMN last_event_id UUID;
+
+UPDATE issues i
+SET last_event_id = (
+    SELECT e.id FROM events e
+    WHERE e.issue_id = i.id
+    ORDER BY e.digest_order DESC
+    LIMIT 1
+);
@@ -0,0 +1,17 @@
+-- Issue users: the end users (the SDK's `user` interface) each issue's events were sent for
+-- user_key is the user's id, or their email or username when the SDK sent no id
+CREATE TABLE issue_users (
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    user_key VARCHAR(255) NOT NULL,
+    user_id VARCHAR(255),
+    email VARCHAR(255),
+    username VARCHAR(255),
+    first_seen TIMESTAMPTZ NOT NULL,
+    last_seen TIMESTAMPTZ NOT NULL,
+    event_count INTEGER NOT NULL DEFAULT 1,
+
+    PRIMARY KEY (issue_id, user_key)
+);
+
+-- For exporting the users first affected since a given time
+CREATE INDEX idx_issue_users_first_seen ON issue_users(issue_id, first_seen, user_key);
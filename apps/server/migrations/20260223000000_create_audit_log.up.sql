@@ -0,0 +1,12 @@
+-- Audit log: who accessed or changed sensitive data, and when
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    project_id INTEGER REFERENCES projects(id) ON DELETE CASCADE,
+    action VARCHAR(64) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- For listing a project's entries
+CREATE INDEX idx_audit_log_project ON audit_log(project_id, created_at DESC);
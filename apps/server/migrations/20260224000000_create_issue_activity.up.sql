@@ -0,0 +1,11 @@
+-- Issue activity: things that happened to an issue without a user doing
+-- them, such as being auto-resolved
+CREATE TABLE issue_activity (
+    id BIGSERIAL PRIMARY KEY,
+    issue_id UUID NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    kind VARCHAR(32) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- For listing an issue's activity
+CREATE INDEX idx_issue_activity_issue ON issue_activity(issue_id, created_at DESC);
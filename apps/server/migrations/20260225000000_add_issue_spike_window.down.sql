@@ -0,0 +1,2 @@
+ALTER TABLE issues DROP COLUMN spike_base_event_count;
+ALTER TABLE issues DROP COLUMN spike_window_start;
@@ -0,0 +1,5 @@
+-- Priority spikes are counted from digested_event_count: the clock hour the
+-- issue's last event was counted in, and the count when that hour started
+-- or the priority last changed, whichever came later
+ALTER TABLE issues ADD COLUMN spike_window_start TIMESTAMPTZ;
+ALTER TABLE issues ADD COLUMN spike_base_event_count INTEGER NOT NULL DEFAULT 0;
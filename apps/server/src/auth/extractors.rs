@@ -2,11 +2,12 @@ use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
 use std::future::Future;
 use std::pin::Pin;
 
-use crate::auth::sentry_auth::parse_sentry_auth_header;
-use crate::db::DbPool;
-use crate::error::AppError;
-use crate::models::{AuthToken, Project};
-use crate::services::{AuthTokenService, ProjectService};
+use crate::auth::project_cache::{CachedAuth, ProjectCache};
+use crate::auth::sentry_auth::{ingest_auth_error, SentryCredentials};
+use crate::db::{self, DbHealth, DbPool};
+use crate::error::{AppError, AppResult};
+use crate::models::{AuthToken, Project, ProjectKey};
+use crate::services::{AuthTokenService, ProjectKeyService, ProjectService};
 
 /// Extractor for Bearer token authentication (API endpoints)
 ///
@@ -86,7 +87,14 @@ impl FromRequest for BearerAuth {
 
 /// Extractor for Sentry SDK authentication (ingest endpoints)
 ///
-/// Validates project by ID from URL path and sentry_key from query param or X-Sentry-Auth header.
+/// Validates project by ID from URL path and the credentials the SDK sent,
+/// see [`SentryCredentials`]. The key must be one of the project's active keys. DSNs without a project
+/// id (no path segment, or a placeholder that isn't a positive number) are
+/// resolved from the key alone.
+/// With a [`DbHealth`] and [`ProjectCache`] registered as app data, recently
+/// authenticated keys skip the database, and the last known project is used
+/// while the database is down. Rejections carry a `WWW-Authenticate: Sentry`
+/// challenge.
 ///
 /// Usage in handlers:
 /// ```ignore
@@ -96,78 +104,173 @@ impl FromRequest for BearerAuth {
 /// ```
 pub struct SentryAuth {
     pub project: Project,
+    pub credentials: SentryCredentials,
 }
 
 impl FromRequest for SentryAuth {
-    type Error = AppError;
+    type Error = actix_web::Error;
     type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
 
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        // Extract project_id from URL path; absent or placeholder ids leave
+        // the key to identify the project
+        let project_id: Option<i32> = req
+            .match_info()
+            .get("project_id")
+            .and_then(|s| s.parse().ok())
+            .filter(|id| *id > 0);
+
+        Self::resolve(req, project_id, SentryCredentials::from_request(req))
+    }
+}
+
+impl SentryAuth {
+    /// Authenticates `credentials` for `project_id`, with the pool, health
+    /// and cache registered on `req`
+    ///
+    /// Also used for credentials that don't come from the request's URL or
+    /// headers, like the DSN of a tunneled envelope.
+    pub fn resolve(
+        req: &HttpRequest,
+        project_id: Option<i32>,
+        credentials: AppResult<SentryCredentials>,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, actix_web::Error>>>> {
         let pool = match req.app_data::<web::Data<DbPool>>().cloned() {
             Some(pool) => pool,
             None => {
                 return Box::pin(async {
-                    Err(AppError::Internal(
-                        "Database pool not configured".to_string(),
-                    ))
+                    Err(AppError::Internal("Database pool not configured".to_string()).into())
                 });
             }
         };
 
-        // Extract project_id from URL path
-        let project_id: Option<i32> = req
-            .match_info()
-            .get("project_id")
-            .and_then(|s| s.parse().ok());
-
-        // Extract sentry_key from query param
-        let query_sentry_key = req.query_string().split('&').find_map(|pair| {
-            let mut parts = pair.splitn(2, '=');
-            let key = parts.next()?;
-            let value = parts.next()?;
-            if key == "sentry_key" {
-                Some(value.to_string())
-            } else {
-                None
-            }
-        });
-
-        // Extract sentry_key from X-Sentry-Auth header
-        let header_sentry_key = req
-            .headers()
-            .get("X-Sentry-Auth")
-            .and_then(|h| h.to_str().ok())
-            .map(parse_sentry_auth_header)
-            .and_then(|map| map.get("sentry_key").cloned());
+        let db_health = req.app_data::<web::Data<DbHealth>>().cloned();
+        let project_cache = req.app_data::<web::Data<ProjectCache>>().cloned();
 
         Box::pin(async move {
-            let project_id = project_id.ok_or_else(|| {
-                AppError::Validation("Missing or invalid project_id in URL".to_string())
-            })?;
+            let credentials = credentials.map_err(ingest_auth_error)?;
+            let sentry_key = credentials.sentry_key;
 
-            // Try query param first, then header
-            let sentry_key_str = query_sentry_key.or(header_sentry_key).ok_or_else(|| {
-                AppError::Unauthorized(
-                    "Missing sentry_key in query param or X-Sentry-Auth header".to_string(),
+            // Look up project and key
+            let (project, key) = match (db_health, project_cache) {
+                (Some(db_health), Some(project_cache)) => load_project(
+                    pool.get_ref(),
+                    project_id,
+                    &sentry_key,
+                    &db_health,
+                    &project_cache,
                 )
-            })?;
+                .await
+                .map_err(ingest_auth_error)?,
+                _ => {
+                    let (project, key) = authenticate(pool.get_ref(), project_id, &sentry_key)
+                        .await
+                        .map_err(ingest_auth_error)?;
+                    (project, Some(key))
+                }
+            };
 
-            // Parse sentry_key as UUID
-            let sentry_key: uuid::Uuid = sentry_key_str
-                .parse()
-                .map_err(|_| AppError::Unauthorized("Invalid sentry_key format".to_string()))?;
+            // Update last_used_at asynchronously (fire and forget), at most
+            // once a minute per key
+            if let Some(key) = key.filter(|k| k.last_used_outdated(chrono::Utc::now())) {
+                let pool_clone = pool.clone();
+                tokio::spawn(async move {
+                    let _ = ProjectKeyService::update_last_used(pool_clone.get_ref(), key.id).await;
+                });
+            }
+
+            Ok(SentryAuth {
+                project,
+                credentials,
+            })
+        })
+    }
+}
 
-            // Look up project
-            let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+/// Loads a project and checks `sentry_key` is one of its active keys
+///
+/// Without a project id the project is the one the key belongs to.
+async fn authenticate(
+    pool: &DbPool,
+    project_id: Option<i32>,
+    sentry_key: &uuid::Uuid,
+) -> AppResult<(Project, ProjectKey)> {
+    let project = match project_id {
+        Some(project_id) => ProjectService::get_by_id(pool, project_id).await?,
+        None => ProjectService::get_by_key(pool, sentry_key)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid sentry_key".to_string()))?,
+    };
+    let key = ProjectKeyService::get_active(pool, project.id, sentry_key)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid sentry_key for project".to_string()))?;
 
-            // Validate sentry_key matches
-            if project.sentry_key != sentry_key {
-                return Err(AppError::Unauthorized(
-                    "Invalid sentry_key for project".to_string(),
-                ));
+    Ok((project, key))
+}
+
+/// Authenticates from the cache or the database, falling back to the cached
+/// project while the database is down
+///
+/// Projects served from the cache during an outage come without their key, so
+/// its last_used_at isn't updated until the database is back.
+async fn load_project(
+    pool: &DbPool,
+    project_id: Option<i32>,
+    sentry_key: &uuid::Uuid,
+    db_health: &DbHealth,
+    project_cache: &ProjectCache,
+) -> AppResult<(Project, Option<ProjectKey>)> {
+    match project_cache.lookup(project_id, sentry_key) {
+        Some(CachedAuth::Accepted(project, key)) => {
+            let now = chrono::Utc::now();
+            if key.last_used_outdated(now) {
+                project_cache.mark_used(project.id, sentry_key, now);
             }
+            return Ok((*project, Some(key)));
+        }
+        Some(CachedAuth::Rejected(e)) => return Err(e),
+        None => {}
+    }
 
-            Ok(SentryAuth { project })
-        })
+    let cached = || match project_id {
+        Some(project_id) => project_cache.get(project_id, sentry_key),
+        None => project_cache.get_by_key(sentry_key),
+    };
+
+    // Don't wait on the pool during a known outage
+    if db_health.retry_in().is_some() {
+        if let Some(project) = cached() {
+            return Ok((project, None));
+        }
+    }
+
+    match authenticate(pool, project_id, sentry_key).await {
+        Ok((project, key)) => {
+            db_health.record_success();
+            project_cache.insert(&project, &key);
+            Ok((project, Some(key)))
+        }
+        Err(AppError::Database(e)) if db::is_unavailable(&e) => {
+            db_health.record_failure();
+            cached()
+                .map(|project| (project, None))
+                .ok_or(AppError::Database(e))
+        }
+        Err(e) => {
+            match (&e, project_id) {
+                (AppError::NotFound(_), Some(project_id)) => project_cache.remove(project_id),
+                (AppError::Unauthorized(_), Some(project_id)) => {
+                    project_cache.remove_key(project_id, sentry_key)
+                }
+                (AppError::Unauthorized(_), None) => {
+                    if let Some(project) = project_cache.get_by_key(sentry_key) {
+                        project_cache.remove_key(project.id, sentry_key);
+                    }
+                }
+                _ => {}
+            }
+            project_cache.reject(project_id, sentry_key, &e);
+            Err(e)
+        }
     }
 }
@@ -1,8 +1,14 @@
 pub mod extractors;
+pub mod oidc;
+pub mod project_cache;
 pub mod sentry_auth;
 pub mod session;
+pub mod session_store;
 pub mod token;
 
 pub use extractors::{BearerAuth, SentryAuth};
+pub use oidc::{OidcClient, OidcIdentity};
+pub use project_cache::{CachedAuth, ProjectCache};
 pub use session::{clear_session, get_user_id_from_session, set_user_session, AuthenticatedUser};
+pub use session_store::SessionStorage;
 pub use token::generate_token;
@@ -0,0 +1,402 @@
+//! Dashboard login through an OpenID Connect provider.
+//!
+//! Implements the authorization code flow with PKCE. The provider's
+//! metadata is discovered on first use and its signing keys are cached;
+//! the keys are fetched again when a token names a key ID we don't know,
+//! so key rotation at the provider doesn't need a restart. Only RS256
+//! signed ID tokens are accepted.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use actix_session::Session;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::Rng;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use ring::signature::{RsaPublicKeyComponents, RSA_PKCS1_2048_8192_SHA256};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::{OnceCell, RwLock};
+
+use crate::auth::generate_token;
+use crate::config::{OidcConfig, OutboundHttpConfig};
+use crate::error::{AppError, AppResult};
+use crate::services::notification::http::build_client;
+
+/// Session key of the login waiting for the provider's callback
+const PENDING_LOGIN_KEY: &str = "oidc_login";
+
+/// Clock skew tolerated when checking the expiry of ID tokens
+const EXPIRY_LEEWAY_SECS: i64 = 60;
+
+/// The parts of the provider's discovery document we use
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+/// A public key of the provider; only RSA keys are usable
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    kty: String,
+    #[serde(default)]
+    kid: Option<String>,
+    #[serde(default)]
+    n: Option<String>,
+    #[serde(default)]
+    e: Option<String>,
+}
+
+/// A login between the redirect to the provider and its callback
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingLogin {
+    state: String,
+    nonce: String,
+    code_verifier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+/// `aud` is either a single client ID or a list of them
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Audience::One(aud) => aud == client_id,
+            Audience::Many(auds) => auds.iter().any(|aud| aud == client_id),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    exp: i64,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: Option<bool>,
+    /// Everything else, the groups claim is looked up here
+    #[serde(flatten)]
+    other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A user whose ID token passed validation
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub subject: String,
+    pub email: String,
+    pub groups: Vec<String>,
+}
+
+/// Client of the configured OpenID Connect provider
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+    metadata: OnceCell<ProviderMetadata>,
+    keys: RwLock<Vec<Jwk>>,
+}
+
+impl OidcClient {
+    /// Creates the client; the provider isn't contacted until the first login
+    pub fn new(config: OidcConfig, outbound: &OutboundHttpConfig) -> reqwest::Result<Self> {
+        Ok(Self {
+            config,
+            http: build_client(outbound, true)?,
+            metadata: OnceCell::new(),
+            keys: RwLock::new(Vec::new()),
+        })
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Starts a login and returns the provider URL to send the browser to
+    ///
+    /// The state, nonce and PKCE verifier are kept in the session until the
+    /// callback; starting another login replaces them.
+    pub async fn authorization_url(&self, session: &Session) -> AppResult<String> {
+        let metadata = self.metadata().await?;
+
+        let pending = PendingLogin {
+            state: generate_token(),
+            nonce: generate_token(),
+            code_verifier: URL_SAFE_NO_PAD.encode(rand::rng().random::<[u8; 32]>()),
+        };
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(&pending.code_verifier));
+
+        let mut url = url::Url::parse(&metadata.authorization_endpoint).map_err(|e| {
+            AppError::Internal(format!("Invalid OIDC authorization endpoint: {}", e))
+        })?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_url)
+            .append_pair("scope", "openid email profile")
+            .append_pair("state", &pending.state)
+            .append_pair("nonce", &pending.nonce)
+            .append_pair("code_challenge", &code_challenge)
+            .append_pair("code_challenge_method", "S256");
+
+        session
+            .insert(PENDING_LOGIN_KEY, &pending)
+            .map_err(|e| AppError::Internal(format!("Failed to set session: {}", e)))?;
+
+        Ok(url.into())
+    }
+
+    /// Completes the login the provider redirected back with
+    ///
+    /// Checks `state` against the login started in this session, exchanges
+    /// the code for an ID token and validates it.
+    pub async fn complete_login(
+        &self,
+        session: &Session,
+        code: &str,
+        state: &str,
+    ) -> AppResult<OidcIdentity> {
+        let pending = session
+            .remove_as::<PendingLogin>(PENDING_LOGIN_KEY)
+            .and_then(Result::ok)
+            .ok_or_else(|| AppError::Unauthorized("No OIDC login in progress".to_string()))?;
+
+        if pending.state != state {
+            return Err(AppError::Unauthorized("Invalid OIDC state".to_string()));
+        }
+
+        let id_token = self.exchange_code(code, &pending.code_verifier).await?;
+        self.verify_id_token(&id_token, &pending.nonce).await
+    }
+
+    /// Whether the identity belongs to one of the admin groups
+    pub fn is_admin(&self, identity: &OidcIdentity) -> bool {
+        identity
+            .groups
+            .iter()
+            .any(|group| self.config.admin_groups.contains(group))
+    }
+
+    async fn metadata(&self) -> AppResult<&ProviderMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer_url
+                );
+                let metadata: ProviderMetadata = self.get_json(&url, "discovery").await?;
+
+                if metadata.issuer.trim_end_matches('/') != self.config.issuer_url {
+                    return Err(AppError::Internal(format!(
+                        "OIDC provider reports issuer '{}', expected '{}'",
+                        metadata.issuer, self.config.issuer_url
+                    )));
+                }
+
+                Ok(metadata)
+            })
+            .await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        what: &str,
+    ) -> AppResult<T> {
+        self.http
+            .get(url)
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| AppError::Internal(format!("OIDC {} request failed: {}", what, e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Invalid OIDC {} response: {}", what, e)))
+    }
+
+    /// Redeems the authorization code at the token endpoint
+    async fn exchange_code(&self, code: &str, code_verifier: &str) -> AppResult<String> {
+        let metadata = self.metadata().await?;
+
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("grant_type", "authorization_code")
+            .append_pair("code", code)
+            .append_pair("redirect_uri", &self.config.redirect_url)
+            .append_pair("code_verifier", code_verifier)
+            .finish();
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .basic_auth(&self.config.client_id, Some(&self.config.client_secret))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("OIDC token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Unauthorized(format!(
+                "OIDC provider rejected the authorization code ({})",
+                response.status()
+            )));
+        }
+
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Invalid OIDC token response: {}", e)))?;
+
+        Ok(token.id_token)
+    }
+
+    /// Checks the signature and claims of an ID token
+    async fn verify_id_token(&self, id_token: &str, nonce: &str) -> AppResult<OidcIdentity> {
+        let invalid =
+            |reason: &str| AppError::Unauthorized(format!("Invalid ID token: {}", reason));
+
+        let mut parts = id_token.split('.');
+        let (Some(raw_header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("malformed"));
+        };
+        let signed = &id_token[..raw_header.len() + 1 + payload.len()];
+
+        let header: JwtHeader = decode_segment(raw_header).ok_or_else(|| invalid("malformed"))?;
+        if header.alg != "RS256" {
+            return Err(invalid("unsupported signing algorithm"));
+        }
+
+        let key = self.signing_key(header.kid.as_deref()).await?;
+        let (Some(n), Some(e)) = (
+            key.n
+                .as_deref()
+                .and_then(|n| URL_SAFE_NO_PAD.decode(n).ok()),
+            key.e
+                .as_deref()
+                .and_then(|e| URL_SAFE_NO_PAD.decode(e).ok()),
+        ) else {
+            return Err(invalid("unusable signing key"));
+        };
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| invalid("malformed"))?;
+        RsaPublicKeyComponents { n: &n, e: &e }
+            .verify(&RSA_PKCS1_2048_8192_SHA256, signed.as_bytes(), &signature)
+            .map_err(|_| invalid("bad signature"))?;
+
+        let claims: IdTokenClaims = decode_segment(payload).ok_or_else(|| invalid("malformed"))?;
+
+        let metadata = self.metadata().await?;
+        if claims.iss != metadata.issuer {
+            return Err(invalid("wrong issuer"));
+        }
+        if !claims.aud.contains(&self.config.client_id) {
+            return Err(invalid("wrong audience"));
+        }
+        if claims.exp + EXPIRY_LEEWAY_SECS < unix_now() {
+            return Err(invalid("expired"));
+        }
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(invalid("nonce mismatch"));
+        }
+
+        // Users are linked by email, so it has to be one the provider vouches for
+        let email = claims
+            .email
+            .filter(|email| !email.is_empty())
+            .ok_or_else(|| invalid("no email claim"))?;
+        // A missing claim isn't a promise either: linking on it would hand an
+        // existing account to whoever typed that address at the provider
+        if claims.email_verified != Some(true) {
+            return Err(invalid("email not verified"));
+        }
+
+        let groups = match claims.other.get(&self.config.groups_claim) {
+            Some(serde_json::Value::Array(groups)) => groups
+                .iter()
+                .filter_map(|group| group.as_str().map(String::from))
+                .collect(),
+            Some(serde_json::Value::String(group)) => vec![group.clone()],
+            _ => Vec::new(),
+        };
+
+        Ok(OidcIdentity {
+            subject: claims.sub,
+            email,
+            groups,
+        })
+    }
+
+    /// The provider key with `kid`, refreshing the key set once if it's unknown
+    async fn signing_key(&self, kid: Option<&str>) -> AppResult<Jwk> {
+        if let Some(key) = find_key(&self.keys.read().await, kid) {
+            return Ok(key);
+        }
+
+        let metadata = self.metadata().await?;
+        let set: JwkSet = self.get_json(&metadata.jwks_uri, "JWKS").await?;
+        let mut keys = self.keys.write().await;
+        *keys = set.keys;
+
+        find_key(&keys, kid)
+            .ok_or_else(|| AppError::Unauthorized("Invalid ID token: unknown signing key".into()))
+    }
+}
+
+/// The RSA key with `kid`; tokens without a key ID need a single RSA key
+fn find_key(keys: &[Jwk], kid: Option<&str>) -> Option<Jwk> {
+    let mut rsa_keys = keys.iter().filter(|key| key.kty == "RSA");
+    match kid {
+        Some(kid) => rsa_keys
+            .find(|key| key.kid.as_deref() == Some(kid))
+            .cloned(),
+        None => match (rsa_keys.next(), rsa_keys.next()) {
+            (Some(key), None) => Some(key.clone()),
+            _ => None,
+        },
+    }
+}
+
+fn decode_segment<T: serde::de::DeserializeOwned>(segment: &str) -> Option<T> {
+    let bytes = URL_SAFE_NO_PAD.decode(segment).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
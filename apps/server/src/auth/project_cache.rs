@@ -0,0 +1,218 @@
+//! Projects known to ingest authentication.
+//!
+//! [`SentryAuth`](super::SentryAuth) caches each project it loads under the
+//! key it was authenticated with. For `ttl` after loading, a project/key pair
+//! is accepted without asking the database; rejected keys are remembered
+//! for a few seconds so scanning for keys doesn't reach the database either.
+//! Key and project changes drop the affected entries right away, so revoking
+//! a key doesn't wait for the TTL.
+//!
+//! Entries older than the TTL are kept, so SDKs can still be authenticated,
+//! and their events spooled to disk, while the database is down.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{Project, ProjectKey};
+
+/// Longest time a rejected key is remembered
+const REJECTION_TTL: Duration = Duration::from_secs(5);
+
+/// Rejections kept before expired ones are pruned
+const MAX_REJECTIONS: usize = 10_000;
+
+#[derive(Debug)]
+struct Entry {
+    project: Project,
+    key: ProjectKey,
+    loaded_at: Instant,
+}
+
+#[derive(Debug)]
+struct Rejection {
+    project_missing: bool,
+    message: String,
+    rejected_at: Instant,
+}
+
+/// Result of a cached authentication
+#[derive(Debug)]
+pub enum CachedAuth {
+    Accepted(Box<Project>, ProjectKey),
+    Rejected(AppError),
+}
+
+/// Projects by id and active key, as last loaded from the database
+#[derive(Debug, Default)]
+pub struct ProjectCache {
+    ttl: Duration,
+    projects: Mutex<HashMap<(i32, Uuid), Entry>>,
+    rejections: Mutex<HashMap<(Option<i32>, Uuid), Rejection>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ProjectCache {
+    /// A cache that only serves entries during database outages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache that also serves entries younger than `ttl`
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            ..Self::default()
+        }
+    }
+
+    /// Authenticates from entries younger than the TTL
+    ///
+    /// Returns None when the database has to be asked. Counts as a hit or
+    /// a miss in the cache metrics.
+    pub fn lookup(&self, project_id: Option<i32>, key: &Uuid) -> Option<CachedAuth> {
+        let cached = self.fresh_entry(project_id, key).or_else(|| {
+            self.fresh_rejection(project_id, key)
+                .map(CachedAuth::Rejected)
+        });
+
+        let counter = if cached.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        cached
+    }
+
+    fn fresh_entry(&self, project_id: Option<i32>, key: &Uuid) -> Option<CachedAuth> {
+        let projects = self.projects.lock().unwrap();
+        let entry = match project_id {
+            Some(project_id) => projects.get(&(project_id, *key)),
+            None => projects.iter().find(|((_, k), _)| k == key).map(|(_, e)| e),
+        }?;
+
+        (entry.loaded_at.elapsed() < self.ttl)
+            .then(|| CachedAuth::Accepted(Box::new(entry.project.clone()), entry.key.clone()))
+    }
+
+    fn fresh_rejection(&self, project_id: Option<i32>, key: &Uuid) -> Option<AppError> {
+        let rejections = self.rejections.lock().unwrap();
+        let rejection = rejections.get(&(project_id, *key))?;
+
+        (rejection.rejected_at.elapsed() < self.rejection_ttl()).then(|| {
+            if rejection.project_missing {
+                AppError::NotFound(rejection.message.clone())
+            } else {
+                AppError::Unauthorized(rejection.message.clone())
+            }
+        })
+    }
+
+    fn rejection_ttl(&self) -> Duration {
+        self.ttl.min(REJECTION_TTL)
+    }
+
+    /// Last known project for an id and key, however old
+    pub fn get(&self, project_id: i32, key: &Uuid) -> Option<Project> {
+        self.projects
+            .lock()
+            .unwrap()
+            .get(&(project_id, *key))
+            .map(|entry| entry.project.clone())
+    }
+
+    /// Finds a project by key alone, for DSNs without a project id
+    pub fn get_by_key(&self, key: &Uuid) -> Option<Project> {
+        self.projects
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|((_, k), _)| k == key)
+            .map(|(_, entry)| entry.project.clone())
+    }
+
+    pub fn insert(&self, project: &Project, key: &ProjectKey) {
+        self.projects.lock().unwrap().insert(
+            (project.id, key.key),
+            Entry {
+                project: project.clone(),
+                key: key.clone(),
+                loaded_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Remembers that the database rejected a key
+    ///
+    /// Only "not found" and "unauthorized" errors are remembered.
+    pub fn reject(&self, project_id: Option<i32>, key: &Uuid, error: &AppError) {
+        let (project_missing, message) = match error {
+            AppError::NotFound(message) => (true, message.clone()),
+            AppError::Unauthorized(message) => (false, message.clone()),
+            _ => return,
+        };
+        if self.rejection_ttl().is_zero() {
+            return;
+        }
+
+        let mut rejections = self.rejections.lock().unwrap();
+        if rejections.len() >= MAX_REJECTIONS {
+            let ttl = self.rejection_ttl();
+            rejections.retain(|_, r| r.rejected_at.elapsed() < ttl);
+        }
+        if rejections.len() >= MAX_REJECTIONS {
+            rejections.clear();
+        }
+        rejections.insert(
+            (project_id, *key),
+            Rejection {
+                project_missing,
+                message,
+                rejected_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Records that a cached key's last_used_at was just updated
+    pub fn mark_used(&self, project_id: i32, key: &Uuid, at: DateTime<Utc>) {
+        if let Some(entry) = self.projects.lock().unwrap().get_mut(&(project_id, *key)) {
+            entry.key.last_used_at = Some(at);
+        }
+    }
+
+    /// Forgets one key, e.g. once it's activated, deactivated or deleted
+    pub fn remove_key(&self, project_id: i32, key: &Uuid) {
+        self.projects.lock().unwrap().remove(&(project_id, *key));
+        self.rejections.lock().unwrap().retain(|(_, k), _| k != key);
+    }
+
+    /// Forgets a project under all its keys
+    pub fn remove(&self, project_id: i32) {
+        self.projects
+            .lock()
+            .unwrap()
+            .retain(|(id, _), _| *id != project_id);
+        self.rejections
+            .lock()
+            .unwrap()
+            .retain(|(id, _), _| *id != Some(project_id));
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Lookups that had to ask the database
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
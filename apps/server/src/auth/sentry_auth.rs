@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 
+use actix_web::error::InternalError;
+use actix_web::http::header::{HeaderValue, WWW_AUTHENTICATE};
+use actix_web::{HttpRequest, ResponseError};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
 /// Parses the X-Sentry-Auth header value
 /// Format: "Sentry sentry_key=xxx, sentry_version=7, sentry_client=..."
 pub fn parse_sentry_auth_header(header_value: &str) -> HashMap<String, String> {
@@ -20,3 +27,115 @@ pub fn parse_sentry_auth_header(header_value: &str) -> HashMap<String, String> {
         })
         .collect()
 }
+
+/// Ingest credentials of a request, normalized from wherever the SDK put them
+///
+/// Accepted placements are the `sentry_key` query param, the `X-Sentry-Auth`
+/// header, and an `Authorization` header using the `Sentry` scheme with the
+/// same key/value grammar. The `sentry_secret` of legacy DSNs is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentryCredentials {
+    pub sentry_key: Uuid,
+    /// Protocol version the SDK speaks, if it says
+    pub sentry_version: Option<String>,
+    /// SDK name and version, e.g. `sentry.python/1.45.0`
+    pub sentry_client: Option<String>,
+}
+
+impl SentryCredentials {
+    /// Collects the credentials of `req`
+    ///
+    /// A missing or malformed key is `Unauthorized`. A header and the query
+    /// (or both headers) naming different keys is a `Validation` error
+    /// rather than a guess at which one the SDK meant.
+    pub fn from_request(req: &HttpRequest) -> AppResult<Self> {
+        let header = |name: &str| -> AppResult<Option<HashMap<String, String>>> {
+            let Some(value) = req.headers().get(name) else {
+                return Ok(None);
+            };
+            let value = value
+                .to_str()
+                .map_err(|_| AppError::Unauthorized(format!("Malformed {} header", name)))?;
+            Ok(Some(parse_sentry_auth_header(value)))
+        };
+
+        let sentry_auth = header("X-Sentry-Auth")?;
+        if sentry_auth.as_ref().is_some_and(HashMap::is_empty) {
+            return Err(AppError::Unauthorized(
+                "Malformed X-Sentry-Auth header, expected 'Sentry sentry_key=...'".to_string(),
+            ));
+        }
+        // Other schemes are left alone, proxies may authenticate with them
+        let authorization = header("Authorization")?.filter(|pairs| !pairs.is_empty());
+        let query: HashMap<String, String> =
+            url::form_urlencoded::parse(req.query_string().as_bytes())
+                .into_owned()
+                .collect();
+
+        let mut credentials: Option<(Self, &str)> = None;
+        for (pairs, placement) in [
+            (sentry_auth.as_ref(), "X-Sentry-Auth header"),
+            (authorization.as_ref(), "Authorization header"),
+            (Some(&query), "query"),
+        ] {
+            let Some(pairs) = pairs else { continue };
+            let Some(key) = pairs.get("sentry_key") else {
+                if placement == "query" {
+                    continue;
+                }
+                return Err(AppError::Unauthorized(format!(
+                    "Missing sentry_key in {}",
+                    placement
+                )));
+            };
+            let sentry_key: Uuid = key
+                .parse()
+                .map_err(|_| AppError::Unauthorized("Invalid sentry_key format".to_string()))?;
+
+            match &credentials {
+                Some((found, found_in)) if found.sentry_key != sentry_key => {
+                    return Err(AppError::Validation(format!(
+                        "sentry_key in {} doesn't match the one in {}",
+                        placement, found_in
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    credentials = Some((
+                        Self {
+                            sentry_key,
+                            sentry_version: pairs.get("sentry_version").cloned(),
+                            sentry_client: pairs.get("sentry_client").cloned(),
+                        },
+                        placement,
+                    ))
+                }
+            }
+        }
+
+        credentials
+            .map(|(credentials, _)| credentials)
+            .ok_or_else(|| {
+                AppError::Unauthorized(
+                    "Missing sentry_key in query param, X-Sentry-Auth or Authorization header"
+                        .to_string(),
+                )
+            })
+    }
+}
+
+/// Error response of failed ingest authentication
+///
+/// 401s carry a `WWW-Authenticate: Sentry` challenge, like Sentry's own
+/// ingest, so SDKs and proxies recognize a rejected key. Other errors are
+/// passed on unchanged.
+pub fn ingest_auth_error(e: AppError) -> actix_web::Error {
+    if !matches!(e, AppError::Unauthorized(_)) {
+        return e.into();
+    }
+    let mut response = e.error_response();
+    response
+        .headers_mut()
+        .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Sentry"));
+    InternalError::from_response(e, response).into()
+}
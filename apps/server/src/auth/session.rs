@@ -21,6 +21,9 @@ pub fn get_user_id_from_session(session: &Session) -> Option<i32> {
 }
 
 /// Clear session (logout)
+///
+/// With the Redis store, the middleware also deletes the server-side record,
+/// so the old cookie can't be replayed.
 pub fn clear_session(session: &Session) {
     session.purge();
 }
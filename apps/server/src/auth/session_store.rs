@@ -0,0 +1,93 @@
+//! Session storage backends.
+//!
+//! Sessions live in the encrypted cookie by default. With
+//! `SESSION_STORE=redis` the cookie only holds a session key and the state
+//! is kept in Redis, so a session can be revoked by deleting its record and
+//! every instance behind a load balancer sees the same sessions.
+
+use std::collections::HashMap;
+
+use actix_session::storage::{
+    CookieSessionStore, LoadError, RedisSessionStore, SaveError, SessionKey, SessionStore,
+    UpdateError,
+};
+use actix_web::cookie::time::Duration;
+
+use crate::config::SessionBackend;
+
+/// The configured session store, shared by the `SessionMiddleware` of every worker
+pub enum SessionStorage {
+    Cookie(CookieSessionStore),
+    Redis(Box<RedisSessionStore>),
+}
+
+impl SessionStorage {
+    /// Connects to the store of `backend`
+    pub async fn connect(backend: &SessionBackend) -> anyhow::Result<Self> {
+        match backend {
+            SessionBackend::Cookie => Ok(Self::Cookie(CookieSessionStore::default())),
+            SessionBackend::Redis { url } => Ok(Self::Redis(Box::new(
+                RedisSessionStore::new(url.as_str()).await?,
+            ))),
+        }
+    }
+}
+
+impl Clone for SessionStorage {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Cookie(_) => Self::Cookie(CookieSessionStore::default()),
+            Self::Redis(store) => Self::Redis(store.clone()),
+        }
+    }
+}
+
+impl SessionStore for SessionStorage {
+    async fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<Option<HashMap<String, String>>, LoadError> {
+        match self {
+            Self::Cookie(store) => store.load(session_key).await,
+            Self::Redis(store) => store.load(session_key).await,
+        }
+    }
+
+    async fn save(
+        &self,
+        session_state: HashMap<String, String>,
+        ttl: &Duration,
+    ) -> Result<SessionKey, SaveError> {
+        match self {
+            Self::Cookie(store) => store.save(session_state, ttl).await,
+            Self::Redis(store) => store.save(session_state, ttl).await,
+        }
+    }
+
+    async fn update(
+        &self,
+        session_key: SessionKey,
+        session_state: HashMap<String, String>,
+        ttl: &Duration,
+    ) -> Result<SessionKey, UpdateError> {
+        match self {
+            Self::Cookie(store) => store.update(session_key, session_state, ttl).await,
+            Self::Redis(store) => store.update(session_key, session_state, ttl).await,
+        }
+    }
+
+    async fn update_ttl(&self, session_key: &SessionKey, ttl: &Duration) -> anyhow::Result<()> {
+        match self {
+            Self::Cookie(store) => store.update_ttl(session_key, ttl).await,
+            Self::Redis(store) => store.update_ttl(session_key, ttl).await,
+        }
+    }
+
+    /// Deletes the session's Redis record; a no-op for cookie sessions
+    async fn delete(&self, session_key: &SessionKey) -> anyhow::Result<()> {
+        match self {
+            Self::Cookie(store) => store.delete(session_key).await,
+            Self::Redis(store) => store.delete(session_key).await,
+        }
+    }
+}
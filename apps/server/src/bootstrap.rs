@@ -1,15 +1,16 @@
-use log::{info, warn};
+use log::{error, info, warn};
 use sqlx::PgPool;
 use std::env;
 
+use crate::config::Environment;
 use crate::error::AppResult;
-use crate::models::CreateUserRequest;
-use crate::services::UsersService;
+use crate::models::{CreateAuthToken, CreateUserRequest};
+use crate::services::{AuthTokenService, UsersService};
 
 /// Bootstrap initial superuser from CREATE_SUPERUSER env var
 /// Format: "email:password"
-/// Only creates user if database is empty
-pub async fn create_superuser_if_needed(pool: &PgPool) -> AppResult<()> {
+/// Only creates user if database is empty, and never in production
+pub async fn create_superuser_if_needed(pool: &PgPool, environment: Environment) -> AppResult<()> {
     let create_superuser = match env::var("CREATE_SUPERUSER") {
         Ok(val) if !val.is_empty() => val,
         _ => {
@@ -18,6 +19,14 @@ pub async fn create_superuser_if_needed(pool: &PgPool) -> AppResult<()> {
         }
     };
 
+    if environment.is_production() {
+        error!(
+            "CREATE_SUPERUSER is ignored when RUSTRAK_ENV=production. \
+             Unset it and manage users through the authenticated API."
+        );
+        return Ok(());
+    }
+
     // Check if any users exist
     let user_count = UsersService::user_count(pool).await?;
     if user_count > 0 {
@@ -53,3 +62,46 @@ pub async fn create_superuser_if_needed(pool: &PgPool) -> AppResult<()> {
 
     Ok(())
 }
+
+/// Bootstrap initial API token if RUSTRAK_BOOTSTRAP_TOKEN is set
+/// Only creates a token if none exist, and never in production
+///
+/// Returns the token value, which is printed to stderr once.
+pub async fn create_token_if_needed(
+    pool: &PgPool,
+    environment: Environment,
+) -> AppResult<Option<String>> {
+    if env::var("RUSTRAK_BOOTSTRAP_TOKEN").is_err() {
+        return Ok(None);
+    }
+
+    if environment.is_production() {
+        error!(
+            "RUSTRAK_BOOTSTRAP_TOKEN is ignored when RUSTRAK_ENV=production. \
+             Unset it and create tokens through the authenticated API."
+        );
+        return Ok(None);
+    }
+
+    if AuthTokenService::has_any_token(pool).await? {
+        info!("Auth tokens already exist, skipping bootstrap");
+        return Ok(None);
+    }
+
+    let input = CreateAuthToken {
+        description: Some("Bootstrap token (created automatically)".to_string()),
+    };
+    let token = AuthTokenService::create(pool, input).await?;
+
+    // Print to stderr directly (not logs) to avoid token in log aggregators
+    eprintln!();
+    eprintln!("==============================================");
+    eprintln!("BOOTSTRAP TOKEN CREATED - SAVE THIS NOW!");
+    eprintln!("Token: {}", token.token);
+    eprintln!("This token will NOT be shown again.");
+    eprintln!("==============================================");
+    eprintln!();
+    info!("Bootstrap token created successfully");
+
+    Ok(Some(token.token))
+}
@@ -0,0 +1,27 @@
+//! Version and build information, embedded at compile time by `build.rs`.
+
+use serde::Serialize;
+
+/// Crate version, e.g. `0.4.2`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short git commit SHA, or `unknown` when built outside a git checkout
+pub const COMMIT: &str = env!("RUSTRAK_GIT_SHA");
+
+/// RFC 3339 build timestamp
+pub const BUILT_AT: &str = env!("RUSTRAK_BUILT_AT");
+
+/// Build information as reported by `GET /health`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub commit: &'static str,
+    pub built_at: &'static str,
+}
+
+/// The running server's build
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    version: VERSION,
+    commit: COMMIT,
+    built_at: BUILT_AT,
+};
@@ -8,8 +8,19 @@ pub struct Config {
     pub port: u16,
     pub database: DatabaseConfig,
     pub rate_limit: RateLimitConfig,
+    pub api_rate_limit: ApiRateLimitConfig,
     pub security: SecurityConfig,
     pub ingest_dir: Option<String>,
+    /// Gzip event files while they wait in the ingest directory
+    pub compress_stored_events: bool,
+    pub ingest: IngestConfig,
+    pub digest: DigestConfig,
+    pub pagination: PaginationConfig,
+    pub body_limits: BodyLimitConfig,
+    pub retention: RetentionConfig,
+    pub alerts: AlertConfig,
+    /// Project that Rustrak's own panics and server errors are reported into
+    pub self_monitoring_project_id: Option<i32>,
 }
 
 /// Database connection pool configuration
@@ -21,6 +32,15 @@ pub struct DatabaseConfig {
     pub acquire_timeout: Duration,
     pub idle_timeout: Duration,
     pub max_lifetime: Duration,
+    /// Accept applied migrations whose SQL differs from the binary's (emergencies only)
+    pub allow_dirty_migrations: bool,
+    /// Extra connection attempts at startup before giving up
+    pub connect_retries: u32,
+    /// Wait before the first retry; doubled after each failed attempt
+    pub connect_backoff: Duration,
+    /// Longest a single statement may run before it's cancelled; zero
+    /// disables the limit
+    pub statement_timeout: Duration,
 }
 
 /// Security configuration for production deployments
@@ -31,6 +51,295 @@ pub struct SecurityConfig {
     pub ssl_proxy: bool,
     /// Session encryption key (64 hex chars). Required when ssl_proxy=true
     pub session_secret_key: Option<String>,
+    /// Derive client IPs from X-Forwarded-For instead of the socket peer address.
+    /// Only enable behind a proxy that sets the header, clients can spoof it otherwise
+    pub trust_proxy_headers: bool,
+    /// Where session state is kept (SESSION_STORE)
+    pub session_store: SessionBackend,
+    /// Deployment environment (RUSTRAK_ENV); production refuses startup bootstrapping
+    pub environment: Environment,
+    /// Dashboard login through an OpenID Connect provider, if configured
+    pub oidc: Option<OidcConfig>,
+    /// Whether users can register and log in with a password (PASSWORD_LOGIN_ENABLED).
+    /// Can only be turned off when OIDC is configured
+    pub password_login_enabled: bool,
+    /// Origins allowed to call the API with the session cookie (CORS_ALLOWED_ORIGINS).
+    /// Empty keeps the API open to any origin, without credentials
+    pub cors_allowed_origins: Vec<String>,
+}
+
+/// OpenID Connect provider used for dashboard single sign-on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidcConfig {
+    /// Issuer URL; the provider's metadata is discovered from
+    /// `{issuer_url}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Callback URL registered with the provider, i.e. `{server}/auth/oidc/callback`
+    pub redirect_url: String,
+    /// Members of any of these groups are made admins when they log in
+    pub admin_groups: Vec<String>,
+    /// ID token claim listing the user's groups
+    pub groups_claim: String,
+    /// Where the browser is sent after a successful login
+    pub dashboard_url: String,
+}
+
+/// Deployment environment the server runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Environment {
+    #[default]
+    Development,
+    /// Credentials are never created at startup, only through the authenticated API
+    Production,
+}
+
+impl Environment {
+    /// Parses a RUSTRAK_ENV value (`development` or `production`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "development" | "dev" => Some(Self::Development),
+            "production" | "prod" => Some(Self::Production),
+            _ => None,
+        }
+    }
+
+    pub fn is_production(&self) -> bool {
+        *self == Self::Production
+    }
+}
+
+/// Where session state is kept
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SessionBackend {
+    /// In the encrypted session cookie; sessions can't be revoked server-side
+    #[default]
+    Cookie,
+    /// In Redis, with only the session key in the cookie
+    Redis { url: String },
+}
+
+/// How the envelope endpoint writes events to the ingest directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IngestMode {
+    /// Write the event before responding
+    #[default]
+    Sync,
+    /// Respond once the envelope is validated and queue the write for a
+    /// background writer; writes synchronously when the queue is full
+    FastAck,
+}
+
+impl IngestMode {
+    /// Parses an INGEST_MODE value (`sync` or `fast_ack`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sync" => Some(Self::Sync),
+            "fast_ack" => Some(Self::FastAck),
+            _ => None,
+        }
+    }
+}
+
+/// What the ingest endpoints do with events, to pause ingestion for
+/// maintenance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IngestMaintenance {
+    /// Store and digest events
+    #[default]
+    Accept,
+    /// Answer as if events were accepted, but discard and count them
+    Drop,
+    /// Answer with a 503
+    Reject,
+}
+
+impl IngestMaintenance {
+    /// Parses an INGEST_MAINTENANCE value (`accept`, `drop` or `reject`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "accept" => Some(Self::Accept),
+            "drop" => Some(Self::Drop),
+            "reject" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Drop => "drop",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+/// When event files written to the ingest directory are synced to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FsyncPolicy {
+    /// Sync every file, and its directory, before the write returns
+    #[default]
+    Always,
+    /// Sync the files written since the last run from a background task
+    Batch,
+    /// Leave syncing to the OS
+    Never,
+}
+
+impl FsyncPolicy {
+    /// Parses an INGEST_FSYNC value (`always`, `batch` or `never`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "always" => Some(Self::Always),
+            "batch" => Some(Self::Batch),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Batch => "batch",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// How event counts are compared against the ingest rate limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitAlgorithm {
+    /// Count events in the last minute or hour, up to now; a reached limit
+    /// holds for a full window
+    #[default]
+    Trailing,
+    /// Count events in the current clock-aligned window only
+    Fixed,
+    /// Add the previous window's count, weighted by how much of it still
+    /// overlaps the last full window, to the current window's count
+    Sliding,
+}
+
+impl RateLimitAlgorithm {
+    /// Parses a RATE_LIMIT_ALGORITHM value (`trailing`, `fixed` or `sliding`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "trailing" => Some(Self::Trailing),
+            "fixed" => Some(Self::Fixed),
+            "sliding" => Some(Self::Sliding),
+            _ => None,
+        }
+    }
+}
+
+/// Ingest endpoint configuration
+#[derive(Debug, Clone)]
+pub struct IngestConfig {
+    pub mode: IngestMode,
+    /// Events the background writer can hold before ingest falls back to synchronous writes
+    pub queue_size: usize,
+    /// How long an authenticated project/key pair is reused without asking the database
+    pub auth_cache_ttl: Duration,
+    /// Reject browser events from origins outside a project's allowed
+    /// domains with a 403 instead of dropping them
+    pub allowed_domains_strict: bool,
+    /// When event files are synced to disk
+    pub fsync: FsyncPolicy,
+    /// How often `FsyncPolicy::Batch` syncs the files written since its last run
+    pub fsync_interval: Duration,
+    /// Hosts besides this server's own that tunneled envelopes' DSNs may name
+    pub tunnel_allowed_hosts: Vec<String>,
+    /// Whether events are stored, dropped or rejected
+    pub maintenance: IngestMaintenance,
+}
+
+/// Event digest configuration
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// Max frames stored per stacktrace; longer stacktraces keep their top and bottom frames
+    pub max_stacktrace_frames: usize,
+    /// Max breadcrumbs stored per event; the most recent ones are kept
+    pub max_breadcrumbs: usize,
+    /// Max characters of an issue title; longer titles end in an ellipsis
+    pub max_issue_title_length: usize,
+    /// Max characters of the exception value, transaction and frame fields
+    /// stored on issues and events
+    pub max_issue_value_length: usize,
+    /// Merge events sent again under a digested event_id into the stored
+    /// event instead of dropping them
+    pub merge_duplicate_events: bool,
+    /// Max events digested at once; the rest wait in the ingest directory
+    pub concurrency: usize,
+    /// Seconds from ingest to stored above which an event is logged; 0 never logs
+    pub latency_warn_secs: u64,
+    /// Events within a clock hour, the window of the hourly rate limits,
+    /// that raise an issue's priority a level; 0 never raises it
+    pub priority_spike_events: i64,
+}
+
+/// API pagination configuration
+#[derive(Debug, Clone)]
+pub struct PaginationConfig {
+    /// Page size used when a request doesn't pass `per_page`
+    pub default_page_size: i64,
+    /// Upper bound for `per_page`; larger values are rejected
+    pub max_page_size: i64,
+    /// Page size of an issue's event list when a request doesn't pass `per_page`
+    pub events_page_size: i64,
+    /// Key cursors are signed with; falls back to SESSION_SECRET_KEY
+    pub cursor_signing_key: Option<String>,
+    /// Accept cursors issued before they were signed (deprecated, kept for one release)
+    pub allow_unsigned_cursors: bool,
+}
+
+/// Request body size limits, in bytes
+#[derive(Debug, Clone)]
+pub struct BodyLimitConfig {
+    /// Max body of the dashboard and management API
+    pub api: usize,
+    /// Max body of the ingest endpoints, after decompression
+    pub ingest: usize,
+}
+
+/// How long deleted data is kept
+#[derive(Debug, Clone)]
+pub struct RetentionConfig {
+    /// Days a deleted issue stays in the trash, restorable, before it's purged
+    pub issue_trash_days: i32,
+    /// Days the raw payload of an event is kept; its metadata stays after
+    /// that. None keeps payloads as long as the event
+    pub payload_retention_days: Option<i32>,
+}
+
+/// Alert delivery configuration
+#[derive(Debug, Clone)]
+pub struct AlertConfig {
+    /// Max notifications being sent at once; further deliveries wait their turn
+    pub dispatch_concurrency: usize,
+    /// Consecutive failed deliveries after which a channel is disabled; 0 never disables
+    pub auto_disable_after: u32,
+    /// Check Slack webhooks against Slack when a channel is created or updated
+    pub slack_validate_webhooks: bool,
+    /// HTTP client settings for the Slack and webhook notifiers
+    pub outbound: OutboundHttpConfig,
+}
+
+/// Outbound HTTP settings shared by the notifiers
+#[derive(Debug, Clone)]
+pub struct OutboundHttpConfig {
+    /// Proxy all notification requests go through
+    pub proxy: Option<String>,
+    /// Comma-separated hosts, domains and CIDRs that bypass the proxy
+    pub no_proxy: Option<String>,
+    /// Accept any TLS certificate, e.g. self-signed internal receivers
+    pub tls_insecure: bool,
+    /// Max time to establish a connection
+    pub connect_timeout: Duration,
+    /// Max time to wait for each read from the receiver
+    pub read_timeout: Duration,
+    /// Max time for a whole delivery, from connecting to the last byte
+    pub delivery_timeout: Duration,
 }
 
 /// Rate limiting configuration
@@ -44,6 +353,17 @@ pub struct RateLimitConfig {
     pub max_events_per_project_per_minute: i64,
     /// Per-project max events per hour
     pub max_events_per_project_per_hour: i64,
+    /// How event counts are compared against the limits above
+    pub algorithm: RateLimitAlgorithm,
+}
+
+/// Per-IP rate limits for the auth and token management endpoints
+#[derive(Debug, Clone)]
+pub struct ApiRateLimitConfig {
+    /// Max requests per minute to `/auth/*` from one IP (0 disables the limit)
+    pub auth_requests_per_minute: u32,
+    /// Max token creations/deletions per minute from one IP (0 disables the limit)
+    pub token_requests_per_minute: u32,
 }
 
 impl Config {
@@ -57,8 +377,21 @@ impl Config {
                 .map_err(|_| ConfigError::InvalidPort)?,
             database: DatabaseConfig::from_env()?,
             rate_limit: RateLimitConfig::from_env(),
+            api_rate_limit: ApiRateLimitConfig::from_env(),
             security: SecurityConfig::from_env()?,
             ingest_dir: env::var("INGEST_DIR").ok(),
+            compress_stored_events: env::var("COMPRESS_STORED_EVENTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            ingest: IngestConfig::from_env(),
+            digest: DigestConfig::from_env(),
+            pagination: PaginationConfig::from_env(),
+            body_limits: BodyLimitConfig::from_env(),
+            retention: RetentionConfig::from_env(),
+            alerts: AlertConfig::from_env(),
+            self_monitoring_project_id: env::var("SELF_MONITORING_PROJECT_ID")
+                .ok()
+                .and_then(|v| v.parse().ok()),
         })
     }
 }
@@ -83,6 +416,418 @@ impl RateLimitConfig {
                 .unwrap_or_else(|_| "5000".to_string())
                 .parse()
                 .unwrap_or(5000),
+            algorithm: env::var("RATE_LIMIT_ALGORITHM")
+                .ok()
+                .and_then(|v| RateLimitAlgorithm::parse(&v))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl ApiRateLimitConfig {
+    /// Default for AUTH_RATE_LIMIT_PER_MINUTE
+    pub const DEFAULT_AUTH_REQUESTS_PER_MINUTE: u32 = 30;
+
+    /// Default for TOKEN_RATE_LIMIT_PER_MINUTE
+    pub const DEFAULT_TOKEN_REQUESTS_PER_MINUTE: u32 = 60;
+
+    /// Load API rate limit configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            auth_requests_per_minute: env::var("AUTH_RATE_LIMIT_PER_MINUTE")
+                .unwrap_or_else(|_| Self::DEFAULT_AUTH_REQUESTS_PER_MINUTE.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_AUTH_REQUESTS_PER_MINUTE),
+            token_requests_per_minute: env::var("TOKEN_RATE_LIMIT_PER_MINUTE")
+                .unwrap_or_else(|_| Self::DEFAULT_TOKEN_REQUESTS_PER_MINUTE.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_TOKEN_REQUESTS_PER_MINUTE),
+        }
+    }
+}
+
+impl Default for ApiRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            auth_requests_per_minute: Self::DEFAULT_AUTH_REQUESTS_PER_MINUTE,
+            token_requests_per_minute: Self::DEFAULT_TOKEN_REQUESTS_PER_MINUTE,
+        }
+    }
+}
+
+impl IngestConfig {
+    /// Default for INGEST_QUEUE_SIZE
+    pub const DEFAULT_QUEUE_SIZE: usize = 1000;
+
+    /// Default for INGEST_AUTH_CACHE_TTL_SECS
+    pub const DEFAULT_AUTH_CACHE_TTL_SECS: u64 = 30;
+
+    /// Default for INGEST_FSYNC_INTERVAL_MS
+    pub const DEFAULT_FSYNC_INTERVAL_MS: u64 = 1000;
+
+    /// Load ingest configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            mode: env::var("INGEST_MODE")
+                .ok()
+                .and_then(|v| IngestMode::parse(&v))
+                .unwrap_or_default(),
+            queue_size: env::var("INGEST_QUEUE_SIZE")
+                .unwrap_or_else(|_| Self::DEFAULT_QUEUE_SIZE.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_QUEUE_SIZE)
+                .max(1),
+            auth_cache_ttl: Duration::from_secs(
+                env::var("INGEST_AUTH_CACHE_TTL_SECS")
+                    .unwrap_or_else(|_| Self::DEFAULT_AUTH_CACHE_TTL_SECS.to_string())
+                    .parse()
+                    .unwrap_or(Self::DEFAULT_AUTH_CACHE_TTL_SECS),
+            ),
+            allowed_domains_strict: env::var("ALLOWED_DOMAINS_STRICT")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            fsync: env::var("INGEST_FSYNC")
+                .ok()
+                .and_then(|v| FsyncPolicy::parse(&v))
+                .unwrap_or_default(),
+            fsync_interval: Duration::from_millis(
+                env::var("INGEST_FSYNC_INTERVAL_MS")
+                    .unwrap_or_else(|_| Self::DEFAULT_FSYNC_INTERVAL_MS.to_string())
+                    .parse()
+                    .unwrap_or(Self::DEFAULT_FSYNC_INTERVAL_MS)
+                    .max(1),
+            ),
+            tunnel_allowed_hosts: env::var("TUNNEL_ALLOWED_HOSTS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|host| host.trim().to_ascii_lowercase())
+                        .filter(|host| !host.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            maintenance: env::var("INGEST_MAINTENANCE")
+                .ok()
+                .and_then(|v| IngestMaintenance::parse(&v))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            mode: IngestMode::default(),
+            queue_size: Self::DEFAULT_QUEUE_SIZE,
+            auth_cache_ttl: Duration::from_secs(Self::DEFAULT_AUTH_CACHE_TTL_SECS),
+            allowed_domains_strict: false,
+            fsync: FsyncPolicy::default(),
+            fsync_interval: Duration::from_millis(Self::DEFAULT_FSYNC_INTERVAL_MS),
+            tunnel_allowed_hosts: Vec::new(),
+            maintenance: IngestMaintenance::default(),
+        }
+    }
+}
+
+impl DigestConfig {
+    /// Default for MAX_STACKTRACE_FRAMES
+    pub const DEFAULT_MAX_STACKTRACE_FRAMES: usize = 250;
+
+    /// Default for MAX_BREADCRUMBS
+    pub const DEFAULT_MAX_BREADCRUMBS: usize = 100;
+
+    /// Default for MAX_ISSUE_TITLE_LENGTH
+    pub const DEFAULT_MAX_ISSUE_TITLE_LENGTH: usize = 120;
+
+    /// Default for MAX_ISSUE_VALUE_LENGTH
+    pub const DEFAULT_MAX_ISSUE_VALUE_LENGTH: usize = 500;
+
+    /// Default for DIGEST_CONCURRENCY
+    pub const DEFAULT_CONCURRENCY: usize = 10;
+
+    /// Default for DIGEST_LATENCY_WARN_SECS
+    pub const DEFAULT_LATENCY_WARN_SECS: u64 = 60;
+
+    /// Default for PRIORITY_SPIKE_EVENTS
+    pub const DEFAULT_PRIORITY_SPIKE_EVENTS: i64 = 100;
+
+    /// Load digest configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            max_stacktrace_frames: env::var("MAX_STACKTRACE_FRAMES")
+                .unwrap_or_else(|_| Self::DEFAULT_MAX_STACKTRACE_FRAMES.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_MAX_STACKTRACE_FRAMES),
+            max_breadcrumbs: env::var("MAX_BREADCRUMBS")
+                .unwrap_or_else(|_| Self::DEFAULT_MAX_BREADCRUMBS.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_MAX_BREADCRUMBS),
+            max_issue_title_length: env::var("MAX_ISSUE_TITLE_LENGTH")
+                .unwrap_or_else(|_| Self::DEFAULT_MAX_ISSUE_TITLE_LENGTH.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_MAX_ISSUE_TITLE_LENGTH)
+                .max(1),
+            max_issue_value_length: env::var("MAX_ISSUE_VALUE_LENGTH")
+                .unwrap_or_else(|_| Self::DEFAULT_MAX_ISSUE_VALUE_LENGTH.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_MAX_ISSUE_VALUE_LENGTH)
+                .max(1),
+            merge_duplicate_events: env::var("MERGE_DUPLICATE_EVENTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            concurrency: env::var("DIGEST_CONCURRENCY")
+                .unwrap_or_else(|_| Self::DEFAULT_CONCURRENCY.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_CONCURRENCY)
+                .max(1),
+            latency_warn_secs: env::var("DIGEST_LATENCY_WARN_SECS")
+                .unwrap_or_else(|_| Self::DEFAULT_LATENCY_WARN_SECS.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_LATENCY_WARN_SECS),
+            priority_spike_events: env::var("PRIORITY_SPIKE_EVENTS")
+                .unwrap_or_else(|_| Self::DEFAULT_PRIORITY_SPIKE_EVENTS.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_PRIORITY_SPIKE_EVENTS)
+                .max(0),
+        }
+    }
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            max_stacktrace_frames: Self::DEFAULT_MAX_STACKTRACE_FRAMES,
+            max_breadcrumbs: Self::DEFAULT_MAX_BREADCRUMBS,
+            max_issue_title_length: Self::DEFAULT_MAX_ISSUE_TITLE_LENGTH,
+            max_issue_value_length: Self::DEFAULT_MAX_ISSUE_VALUE_LENGTH,
+            merge_duplicate_events: false,
+            concurrency: Self::DEFAULT_CONCURRENCY,
+            latency_warn_secs: Self::DEFAULT_LATENCY_WARN_SECS,
+            priority_spike_events: Self::DEFAULT_PRIORITY_SPIKE_EVENTS,
+        }
+    }
+}
+
+impl PaginationConfig {
+    /// Default for DEFAULT_PAGE_SIZE
+    pub const DEFAULT_PAGE_SIZE: i64 = 20;
+
+    /// Default for MAX_PAGE_SIZE
+    pub const DEFAULT_MAX_PAGE_SIZE: i64 = 100;
+
+    /// Load pagination configuration from environment variables
+    pub fn from_env() -> Self {
+        let max_page_size = env::var("MAX_PAGE_SIZE")
+            .unwrap_or_else(|_| Self::DEFAULT_MAX_PAGE_SIZE.to_string())
+            .parse()
+            .unwrap_or(Self::DEFAULT_MAX_PAGE_SIZE)
+            .max(1);
+        let default_page_size = env::var("DEFAULT_PAGE_SIZE")
+            .unwrap_or_else(|_| Self::DEFAULT_PAGE_SIZE.to_string())
+            .parse()
+            .unwrap_or(Self::DEFAULT_PAGE_SIZE)
+            .clamp(1, max_page_size);
+        let events_page_size = env::var("EVENTS_PAGE_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default_page_size)
+            .clamp(1, max_page_size);
+
+        Self {
+            default_page_size,
+            max_page_size,
+            events_page_size,
+            cursor_signing_key: env::var("CURSOR_SIGNING_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            allow_unsigned_cursors: env::var("ALLOW_UNSIGNED_CURSORS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(true),
+        }
+    }
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            default_page_size: Self::DEFAULT_PAGE_SIZE,
+            max_page_size: Self::DEFAULT_MAX_PAGE_SIZE,
+            events_page_size: Self::DEFAULT_PAGE_SIZE,
+            cursor_signing_key: None,
+            allow_unsigned_cursors: true,
+        }
+    }
+}
+
+impl BodyLimitConfig {
+    /// Default for API_MAX_BODY_SIZE (1 MiB)
+    pub const DEFAULT_API: usize = 1024 * 1024;
+
+    /// Default for INGEST_MAX_BODY_SIZE (20 MiB)
+    pub const DEFAULT_INGEST: usize = 20 * 1024 * 1024;
+
+    /// Load body size limits from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            api: env::var("API_MAX_BODY_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&limit| limit > 0)
+                .unwrap_or(Self::DEFAULT_API),
+            ingest: env::var("INGEST_MAX_BODY_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&limit| limit > 0)
+                .unwrap_or(Self::DEFAULT_INGEST),
+        }
+    }
+}
+
+impl Default for BodyLimitConfig {
+    fn default() -> Self {
+        Self {
+            api: Self::DEFAULT_API,
+            ingest: Self::DEFAULT_INGEST,
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Default for ISSUE_TRASH_DAYS
+    pub const DEFAULT_ISSUE_TRASH_DAYS: i32 = 30;
+
+    /// Load retention configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            issue_trash_days: env::var("ISSUE_TRASH_DAYS")
+                .unwrap_or_else(|_| Self::DEFAULT_ISSUE_TRASH_DAYS.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_ISSUE_TRASH_DAYS)
+                .max(1),
+            payload_retention_days: env::var("PAYLOAD_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&days: &i32| days > 0),
+        }
+    }
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            issue_trash_days: Self::DEFAULT_ISSUE_TRASH_DAYS,
+            payload_retention_days: None,
+        }
+    }
+}
+
+impl AlertConfig {
+    /// Default for ALERT_DISPATCH_CONCURRENCY
+    pub const DEFAULT_DISPATCH_CONCURRENCY: usize = 10;
+
+    /// Default for ALERT_CHANNEL_AUTO_DISABLE_AFTER
+    pub const DEFAULT_AUTO_DISABLE_AFTER: u32 = 20;
+
+    /// Default for SLACK_VALIDATE_WEBHOOKS
+    pub const DEFAULT_SLACK_VALIDATE_WEBHOOKS: bool = false;
+
+    /// Load alert configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            dispatch_concurrency: env::var("ALERT_DISPATCH_CONCURRENCY")
+                .unwrap_or_else(|_| Self::DEFAULT_DISPATCH_CONCURRENCY.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_DISPATCH_CONCURRENCY)
+                .max(1),
+            auto_disable_after: env::var("ALERT_CHANNEL_AUTO_DISABLE_AFTER")
+                .unwrap_or_else(|_| Self::DEFAULT_AUTO_DISABLE_AFTER.to_string())
+                .parse()
+                .unwrap_or(Self::DEFAULT_AUTO_DISABLE_AFTER),
+            slack_validate_webhooks: env::var("SLACK_VALIDATE_WEBHOOKS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(Self::DEFAULT_SLACK_VALIDATE_WEBHOOKS),
+            outbound: OutboundHttpConfig::from_env(),
+        }
+    }
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            dispatch_concurrency: Self::DEFAULT_DISPATCH_CONCURRENCY,
+            auto_disable_after: Self::DEFAULT_AUTO_DISABLE_AFTER,
+            slack_validate_webhooks: Self::DEFAULT_SLACK_VALIDATE_WEBHOOKS,
+            outbound: OutboundHttpConfig::default(),
+        }
+    }
+}
+
+impl OutboundHttpConfig {
+    /// Default for OUTBOUND_CONNECT_TIMEOUT_SECS
+    pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+    /// Default for OUTBOUND_READ_TIMEOUT_SECS
+    pub const DEFAULT_READ_TIMEOUT_SECS: u64 = 30;
+    /// Default for OUTBOUND_DELIVERY_TIMEOUT_SECS
+    pub const DEFAULT_DELIVERY_TIMEOUT_SECS: u64 = 60;
+
+    /// Load outbound HTTP configuration from environment variables
+    ///
+    /// A proxy reqwest can't use (not a URL, unknown scheme) is ignored with a warning.
+    pub fn from_env() -> Self {
+        let proxy = env::var("OUTBOUND_HTTP_PROXY")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .filter(|v| {
+                let valid = reqwest::Proxy::all(v.trim()).is_ok();
+                if !valid {
+                    log::warn!("Ignoring OUTBOUND_HTTP_PROXY, not a valid URL: {}", v);
+                }
+                valid
+            })
+            .map(|v| v.trim().to_string());
+
+        let seconds = |name: &str, default: u64| {
+            Duration::from_secs(
+                env::var(name)
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .filter(|&secs| secs > 0)
+                    .unwrap_or(default),
+            )
+        };
+
+        Self {
+            proxy,
+            no_proxy: env::var("OUTBOUND_NO_PROXY")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            tls_insecure: env::var("OUTBOUND_TLS_INSECURE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            connect_timeout: seconds(
+                "OUTBOUND_CONNECT_TIMEOUT_SECS",
+                Self::DEFAULT_CONNECT_TIMEOUT_SECS,
+            ),
+            read_timeout: seconds(
+                "OUTBOUND_READ_TIMEOUT_SECS",
+                Self::DEFAULT_READ_TIMEOUT_SECS,
+            ),
+            delivery_timeout: seconds(
+                "OUTBOUND_DELIVERY_TIMEOUT_SECS",
+                Self::DEFAULT_DELIVERY_TIMEOUT_SECS,
+            ),
+        }
+    }
+}
+
+impl Default for OutboundHttpConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            no_proxy: None,
+            tls_insecure: false,
+            connect_timeout: Duration::from_secs(Self::DEFAULT_CONNECT_TIMEOUT_SECS),
+            read_timeout: Duration::from_secs(Self::DEFAULT_READ_TIMEOUT_SECS),
+            delivery_timeout: Duration::from_secs(Self::DEFAULT_DELIVERY_TIMEOUT_SECS),
         }
     }
 }
@@ -120,6 +865,25 @@ impl DatabaseConfig {
                     .parse()
                     .unwrap_or(1800),
             ),
+            allow_dirty_migrations: env::var("ALLOW_DIRTY_MIGRATIONS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            connect_retries: env::var("DB_CONNECT_RETRIES")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            connect_backoff: Duration::from_millis(
+                env::var("DB_CONNECT_BACKOFF_MS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .unwrap_or(500),
+            ),
+            statement_timeout: Duration::from_secs(
+                env::var("DATABASE_STATEMENT_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+            ),
         })
     }
 }
@@ -129,6 +893,12 @@ pub enum ConfigError {
     InvalidPort,
     MissingDatabaseUrl,
     MissingSessionSecret,
+    InvalidSessionStore(String),
+    MissingRedisUrl,
+    InvalidEnvironment(String),
+    MissingOidcSetting(&'static str),
+    PasswordLoginWithoutOidc,
+    InvalidCorsOrigin(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -144,6 +914,31 @@ impl std::fmt::Display for ConfigError {
                     "SESSION_SECRET_KEY is required when SSL_PROXY is enabled"
                 )
             }
+            ConfigError::InvalidSessionStore(value) => write!(
+                f,
+                "SESSION_STORE must be 'cookie' or 'redis', got '{}'",
+                value
+            ),
+            ConfigError::MissingRedisUrl => {
+                write!(f, "REDIS_URL is required when SESSION_STORE=redis")
+            }
+            ConfigError::InvalidEnvironment(value) => write!(
+                f,
+                "RUSTRAK_ENV must be 'development' or 'production', got '{}'",
+                value
+            ),
+            ConfigError::MissingOidcSetting(name) => {
+                write!(f, "{} is required when OIDC_ISSUER_URL is set", name)
+            }
+            ConfigError::PasswordLoginWithoutOidc => write!(
+                f,
+                "PASSWORD_LOGIN_ENABLED=false requires OIDC to be configured"
+            ),
+            ConfigError::InvalidCorsOrigin(origin) => write!(
+                f,
+                "CORS_ALLOWED_ORIGINS entries must look like 'https://app.example.com', got '{}'",
+                origin
+            ),
         }
     }
 }
@@ -159,14 +954,131 @@ impl SecurityConfig {
             .map(|v| v == "true" || v == "1")
             .unwrap_or(false);
 
+        let trust_proxy_headers = env::var("TRUST_PROXY_HEADERS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
         // When SSL_PROXY is enabled, SESSION_SECRET_KEY is required
         if ssl_proxy && session_secret_key.is_none() {
             return Err(ConfigError::MissingSessionSecret);
         }
 
+        let session_store = match env::var("SESSION_STORE")
+            .map(|v| v.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Err(_) | Ok("") | Ok("cookie") => SessionBackend::Cookie,
+            Ok("redis") => SessionBackend::Redis {
+                url: env::var("REDIS_URL")
+                    .ok()
+                    .filter(|url| !url.trim().is_empty())
+                    .ok_or(ConfigError::MissingRedisUrl)?,
+            },
+            Ok(other) => return Err(ConfigError::InvalidSessionStore(other.to_string())),
+        };
+
+        let environment = match env::var("RUSTRAK_ENV") {
+            Ok(value) if !value.trim().is_empty() => Environment::parse(&value)
+                .ok_or_else(|| ConfigError::InvalidEnvironment(value.trim().to_string()))?,
+            _ => Environment::Development,
+        };
+
+        let oidc = OidcConfig::from_env()?;
+
+        // Without another way in, nobody could log in to the dashboard
+        let password_login_enabled = env::var("PASSWORD_LOGIN_ENABLED")
+            .map(|v| v != "false" && v != "0")
+            .unwrap_or(true);
+        if !password_login_enabled && oidc.is_none() {
+            return Err(ConfigError::PasswordLoginWithoutOidc);
+        }
+
+        let cors_allowed_origins = env::var("CORS_ALLOWED_ORIGINS")
+            .map(|v| {
+                v.split(',')
+                    .map(|origin| origin.trim().trim_end_matches('/').to_string())
+                    .filter(|origin| !origin.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        // Credentialed responses can't use a wildcard, so each origin is
+        // matched exactly: scheme, host and port
+        if let Some(origin) = cors_allowed_origins
+            .iter()
+            .find(|origin| !is_valid_origin(origin))
+        {
+            return Err(ConfigError::InvalidCorsOrigin(origin.clone()));
+        }
+
         Ok(Self {
             ssl_proxy,
             session_secret_key,
+            trust_proxy_headers,
+            session_store,
+            environment,
+            oidc,
+            password_login_enabled,
+            cors_allowed_origins,
         })
     }
 }
+
+impl OidcConfig {
+    /// Load the OIDC provider from environment variables
+    ///
+    /// OIDC is enabled by setting OIDC_ISSUER_URL, which then requires the
+    /// client credentials and redirect URL as well.
+    pub fn from_env() -> Result<Option<Self>, ConfigError> {
+        let Some(issuer_url) = non_empty_var("OIDC_ISSUER_URL") else {
+            return Ok(None);
+        };
+
+        let required =
+            |name: &'static str| non_empty_var(name).ok_or(ConfigError::MissingOidcSetting(name));
+
+        let admin_groups = env::var("OIDC_ADMIN_GROUPS")
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|group| !group.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(Self {
+            issuer_url: issuer_url.trim_end_matches('/').to_string(),
+            client_id: required("OIDC_CLIENT_ID")?,
+            client_secret: required("OIDC_CLIENT_SECRET")?,
+            redirect_url: required("OIDC_REDIRECT_URL")?,
+            admin_groups,
+            groups_claim: non_empty_var("OIDC_GROUPS_CLAIM")
+                .unwrap_or_else(|| "groups".to_string()),
+            dashboard_url: non_empty_var("DASHBOARD_URL")
+                .unwrap_or_else(|| "http://localhost:3000".to_string()),
+        }))
+    }
+}
+
+/// Trimmed value of an environment variable, `None` if unset or blank
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Whether `origin` is a browser origin: `http` or `https`, a host and an
+/// optional port, without a path or wildcard
+fn is_valid_origin(origin: &str) -> bool {
+    let Some(authority) = origin
+        .strip_prefix("https://")
+        .or_else(|| origin.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    !authority.is_empty()
+        && !authority
+            .chars()
+            .any(|c| matches!(c, '/' | '*' | '?' | '#' | '@') || c.is_whitespace())
+}
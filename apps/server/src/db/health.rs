@@ -0,0 +1,84 @@
+//! Database outage tracking.
+//!
+//! While the database is down every query waits for the pool's acquire
+//! timeout before failing. [`DbHealth`] remembers the outage so the readiness
+//! probe can answer immediately and the digest worker can back off, only
+//! trying the database again once the current backoff has passed.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Wait after the first failure
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest wait between attempts during an outage
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Circuit breaker over database availability, shared by all workers
+#[derive(Debug, Default)]
+pub struct DbHealth {
+    outage: Mutex<Option<Outage>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Outage {
+    since: Instant,
+    retry_at: Instant,
+    backoff: Duration,
+}
+
+impl DbHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time left before the database should be tried again, if it's down
+    pub fn retry_in(&self) -> Option<Duration> {
+        let outage = (*self.outage.lock().unwrap())?;
+        outage
+            .retry_at
+            .checked_duration_since(Instant::now())
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Records a failed attempt and returns how long to wait before the next one
+    ///
+    /// The wait starts at [`INITIAL_BACKOFF`] and doubles up to [`MAX_BACKOFF`]
+    /// with each attempt made after the previous wait. Failures of attempts
+    /// that were already underway don't extend it.
+    pub fn record_failure(&self) -> Duration {
+        let now = Instant::now();
+        let mut outage = self.outage.lock().unwrap();
+        let next = match *outage {
+            Some(current) if now < current.retry_at => return current.retry_at - now,
+            Some(current) => Outage {
+                backoff: (current.backoff * 2).min(MAX_BACKOFF),
+                retry_at: now,
+                ..current
+            },
+            None => {
+                log::warn!("Database unavailable, backing off");
+                Outage {
+                    since: now,
+                    retry_at: now,
+                    backoff: INITIAL_BACKOFF,
+                }
+            }
+        };
+        *outage = Some(Outage {
+            retry_at: now + next.backoff,
+            ..next
+        });
+        next.backoff
+    }
+
+    /// Records a successful query, ending the outage
+    pub fn record_success(&self) {
+        if let Some(outage) = self.outage.lock().unwrap().take() {
+            log::info!(
+                "Database available again after {:?}",
+                outage.since.elapsed()
+            );
+        }
+    }
+}
@@ -1,48 +1,312 @@
+pub mod health;
+
+use sqlx::migrate::{MigrateError, Migrator};
 use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::Connection;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use crate::config::DatabaseConfig;
 
+pub use health::DbHealth;
+
 /// Type alias for the PostgreSQL connection pool
 pub type DbPool = PgPool;
 
+/// Longest wait between startup connection attempts
+const MAX_CONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
 /// Creates a new database connection pool with the provided configuration
+///
+/// Failed connections are retried `connect_retries` times, waiting
+/// `connect_backoff` (doubled after each attempt) in between, so the server
+/// can start before the database accepts connections. Every connection
+/// cancels statements running longer than `statement_timeout`.
 pub async fn create_pool(config: &DatabaseConfig) -> Result<DbPool, sqlx::Error> {
     log::info!("Connecting to database...");
 
-    let pool = PgPoolOptions::new()
+    let attempts = config.connect_retries + 1;
+    let mut backoff = config.connect_backoff;
+    let mut attempt = 1;
+    let pool = loop {
+        match connect(config).await {
+            Ok(pool) => break pool,
+            Err(e) if attempt < attempts => {
+                log::warn!(
+                    "Database connection attempt {}/{} failed: {}. Retrying in {:?}",
+                    attempt,
+                    attempts,
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_CONNECT_BACKOFF);
+                attempt += 1;
+            }
+            Err(e) => {
+                log::error!(
+                    "Database connection attempt {}/{} failed: {}",
+                    attempt,
+                    attempts,
+                    e
+                );
+                return Err(e);
+            }
+        }
+    };
+
+    log::info!(
+        "Database connection pool established (max: {}, min: {})",
+        config.max_connections,
+        config.min_connections
+    );
+
+    Ok(pool)
+}
+
+async fn connect(config: &DatabaseConfig) -> Result<DbPool, sqlx::Error> {
+    let statement_timeout_ms = config.statement_timeout.as_millis();
+
+    PgPoolOptions::new()
         .max_connections(config.max_connections)
         .min_connections(config.min_connections)
         .acquire_timeout(config.acquire_timeout)
         .idle_timeout(Some(config.idle_timeout))
         .max_lifetime(Some(config.max_lifetime))
-        .after_connect(|conn, _meta| {
+        .after_connect(move |conn, _meta| {
             Box::pin(async move {
                 // Set timezone to UTC for all connections
                 sqlx::query("SET timezone = 'UTC'")
-                    .execute(conn)
+                    .execute(&mut *conn)
+                    .await?;
+                // Cancel runaway queries before they hold the connection for good
+                sqlx::query(&format!("SET statement_timeout = {}", statement_timeout_ms))
+                    .execute(&mut *conn)
                     .await?;
                 Ok(())
             })
         })
         .connect(&config.url)
+        .await
+}
+
+/// Lifts the statement timeout until the transaction ends
+///
+/// For maintenance statements that scan whole tables, like recounts and
+/// purges, which are expected to outlast `statement_timeout`.
+pub async fn disable_statement_timeout(tx: &mut sqlx::PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query("SET LOCAL statement_timeout = 0")
+        .execute(tx)
         .await?;
+    Ok(())
+}
 
-    log::info!(
-        "Database connection pool established (max: {}, min: {})",
-        config.max_connections,
-        config.min_connections
-    );
+/// True if the error means the database can't be reached, as opposed to a
+/// failing query
+pub fn is_unavailable(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => true,
+        // admin_shutdown, crash_shutdown, cannot_connect_now
+        sqlx::Error::Database(db) => matches!(
+            db.code().as_deref(),
+            Some("57P01") | Some("57P02") | Some("57P03")
+        ),
+        _ => false,
+    }
+}
 
-    Ok(pool)
+/// Migrations embedded in the binary
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// A migration, identified by version and description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+}
+
+impl std::fmt::Display for MigrationInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.version, self.description)
+    }
+}
+
+/// How the migrations applied to a database compare to a migrator's
+#[derive(Debug, Default)]
+pub struct MigrationState {
+    /// Migrations not applied yet
+    pub pending: Vec<MigrationInfo>,
+    /// Applied migrations the migrator doesn't know, left by a newer server
+    pub unknown: Vec<MigrationInfo>,
+    /// Applied migrations whose SQL differs from the migrator's
+    pub modified: Vec<MigrationInfo>,
+}
+
+/// Migration failures that need an operator
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(
+        "Database is ahead of binary v{binary_version}: unknown migration(s) {}. \
+         Upgrade the server to a version that includes them",
+        join(.migrations)
+    )]
+    DatabaseAhead {
+        binary_version: &'static str,
+        migrations: Vec<MigrationInfo>,
+    },
+
+    #[error(
+        "Applied migration(s) {} differ from this binary's. Restore the database \
+         from a backup or run the matching server version; set \
+         ALLOW_DIRTY_MIGRATIONS=true to accept the binary's migrations anyway",
+        join(.migrations)
+    )]
+    Modified { migrations: Vec<MigrationInfo> },
+
+    #[error("Migration failed: {0}")]
+    Migrate(#[from] MigrateError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+fn join(migrations: &[MigrationInfo]) -> String {
+    migrations
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl MigrationState {
+    /// Fails if the database is ahead of the migrator, or has modified
+    /// migrations and `allow_dirty` is off
+    pub fn check(&self, allow_dirty: bool) -> Result<(), MigrationError> {
+        if !self.unknown.is_empty() {
+            return Err(MigrationError::DatabaseAhead {
+                binary_version: env!("CARGO_PKG_VERSION"),
+                migrations: self.unknown.clone(),
+            });
+        }
+
+        if !self.modified.is_empty() && !allow_dirty {
+            return Err(MigrationError::Modified {
+                migrations: self.modified.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AppliedMigrationRow {
+    version: i64,
+    description: String,
+    checksum: Vec<u8>,
+}
+
+/// Compares the migrations applied to the database with the migrator's
+pub async fn inspect_migrations(
+    pool: &DbPool,
+    migrator: &Migrator,
+) -> Result<MigrationState, sqlx::Error> {
+    let table_exists: bool =
+        sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+
+    let applied: Vec<AppliedMigrationRow> = if table_exists {
+        sqlx::query_as(
+            "SELECT version, description, checksum FROM _sqlx_migrations ORDER BY version",
+        )
+        .fetch_all(pool)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let applied_by_version: HashMap<i64, &AppliedMigrationRow> =
+        applied.iter().map(|m| (m.version, m)).collect();
+    let known: HashSet<i64> = migrator.iter().map(|m| m.version).collect();
+
+    let mut state = MigrationState::default();
+    for migration in migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+    {
+        let info = MigrationInfo {
+            version: migration.version,
+            description: migration.description.to_string(),
+        };
+        match applied_by_version.get(&migration.version) {
+            None => state.pending.push(info),
+            Some(row) if row.checksum != *migration.checksum => state.modified.push(info),
+            Some(_) => {}
+        }
+    }
+    state.unknown = applied
+        .iter()
+        .filter(|m| !known.contains(&m.version))
+        .map(|m| MigrationInfo {
+            version: m.version,
+            description: m.description.clone(),
+        })
+        .collect();
+
+    Ok(state)
 }
 
 /// Runs all pending database migrations
-pub async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::migrate::MigrateError> {
+///
+/// Refuses to run against a database migrated by a newer server. Modified
+/// migrations are an error unless `allow_dirty` is set, in which case the
+/// stored checksums are replaced with the binary's.
+pub async fn run_migrations(pool: &DbPool, allow_dirty: bool) -> Result<(), MigrationError> {
+    run_migrator(pool, &MIGRATOR, allow_dirty).await
+}
+
+/// Runs the pending migrations of `migrator`; see [`run_migrations`]
+pub async fn run_migrator(
+    pool: &DbPool,
+    migrator: &Migrator,
+    allow_dirty: bool,
+) -> Result<(), MigrationError> {
     log::info!("Running database migrations...");
 
-    sqlx::migrate!("./migrations").run(pool).await?;
+    let state = inspect_migrations(pool, migrator).await?;
+    state.check(allow_dirty)?;
 
-    log::info!("Database migrations completed successfully");
+    for modified in &state.modified {
+        log::warn!(
+            "Migration {} differs from the applied one; accepting it because \
+             ALLOW_DIRTY_MIGRATIONS is set",
+            modified
+        );
+        let migration = migrator
+            .iter()
+            .find(|m| m.version == modified.version && !m.migration_type.is_down_migration())
+            .expect("modified migrations come from the migrator");
+        sqlx::query("UPDATE _sqlx_migrations SET checksum = $1 WHERE version = $2")
+            .bind(&*migration.checksum)
+            .bind(migration.version)
+            .execute(pool)
+            .await?;
+    }
+
+    // On a connection of its own without the statement timeout, as building
+    // an index on a large table can take longer
+    let mut conn = pool.acquire().await?.detach();
+    sqlx::query("SET statement_timeout = 0")
+        .execute(&mut conn)
+        .await?;
+    migrator.run(&mut conn).await?;
+    conn.close().await?;
+
+    log::info!(
+        "Database migrations completed successfully ({} applied)",
+        state.pending.len()
+    );
     Ok(())
 }
 
@@ -0,0 +1,256 @@
+//! Breakpad text symbol files (`.sym`).
+//!
+//! Only the records needed to resolve addresses are read: `MODULE`, `FILE`,
+//! `FUNC` with its line records, and `PUBLIC`. `STACK`, `INFO` and inline
+//! records are skipped.
+
+use std::collections::HashMap;
+
+use crate::error::{AppError, AppResult};
+
+/// Maximum symbol file size (100MB)
+pub const MAX_SYMBOL_FILE_SIZE: usize = 100 * 1024 * 1024;
+
+/// The module a symbol file describes (its `MODULE` record)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolModule {
+    pub os: String,
+    pub arch: String,
+    /// Debug ID in the format used by `debug_meta.images`
+    pub debug_id: String,
+    pub name: String,
+}
+
+/// A function resolved from a symbol file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol<'a> {
+    pub function: &'a str,
+    /// Offset of the function start in the module
+    pub symbol_addr: u64,
+    pub filename: Option<&'a str>,
+    pub lineno: Option<u32>,
+}
+
+#[derive(Debug)]
+struct Function {
+    address: u64,
+    size: u64,
+    name: String,
+    lines: Vec<Line>,
+}
+
+#[derive(Debug)]
+struct Line {
+    address: u64,
+    size: u64,
+    line: u32,
+    file: u32,
+}
+
+#[derive(Debug)]
+struct Public {
+    address: u64,
+    name: String,
+}
+
+/// A parsed symbol file; addresses are offsets from the module base
+#[derive(Debug)]
+pub struct SymbolFile {
+    pub module: SymbolModule,
+    files: HashMap<u32, String>,
+    functions: Vec<Function>,
+    publics: Vec<Public>,
+}
+
+impl SymbolFile {
+    /// Parses a whole symbol file
+    pub fn parse(text: &str) -> AppResult<Self> {
+        let mut lines = text.lines().enumerate();
+        let (_, header) = lines.next().ok_or_else(|| invalid(1, "empty file"))?;
+
+        let mut symbols = SymbolFile {
+            module: parse_module(header)?,
+            files: HashMap::new(),
+            functions: Vec::new(),
+            publics: Vec::new(),
+        };
+
+        for (i, line) in lines {
+            let line_no = i + 1;
+            let line = line.trim_end();
+            let (record, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+            match record {
+                "FILE" => {
+                    let (number, name) = rest
+                        .split_once(' ')
+                        .ok_or_else(|| invalid(line_no, "malformed FILE record"))?;
+                    let number = number
+                        .parse()
+                        .map_err(|_| invalid(line_no, "malformed FILE record"))?;
+                    symbols.files.insert(number, name.to_string());
+                }
+                "FUNC" => {
+                    let rest = rest.strip_prefix("m ").unwrap_or(rest);
+                    let mut parts = rest.splitn(4, ' ');
+                    let (Some(address), Some(size), Some(_), Some(name)) =
+                        (parts.next(), parts.next(), parts.next(), parts.next())
+                    else {
+                        return Err(invalid(line_no, "malformed FUNC record"));
+                    };
+                    symbols.functions.push(Function {
+                        address: hex(address, line_no)?,
+                        size: hex(size, line_no)?,
+                        name: name.to_string(),
+                        lines: Vec::new(),
+                    });
+                }
+                "PUBLIC" => {
+                    let rest = rest.strip_prefix("m ").unwrap_or(rest);
+                    let mut parts = rest.splitn(3, ' ');
+                    let (Some(address), Some(_), Some(name)) =
+                        (parts.next(), parts.next(), parts.next())
+                    else {
+                        return Err(invalid(line_no, "malformed PUBLIC record"));
+                    };
+                    symbols.publics.push(Public {
+                        address: hex(address, line_no)?,
+                        name: name.to_string(),
+                    });
+                }
+                "STACK" | "INFO" | "INLINE" | "INLINE_ORIGIN" | "" => {}
+                _ => {
+                    // Line record of the preceding FUNC: address size line file
+                    let fields: Vec<&str> = line.split(' ').collect();
+                    let [address, size, lineno, file] = fields[..] else {
+                        return Err(invalid(line_no, "unknown record"));
+                    };
+                    let function = symbols
+                        .functions
+                        .last_mut()
+                        .ok_or_else(|| invalid(line_no, "line record outside of FUNC"))?;
+                    function.lines.push(Line {
+                        address: hex(address, line_no)?,
+                        size: hex(size, line_no)?,
+                        line: lineno
+                            .parse()
+                            .map_err(|_| invalid(line_no, "malformed line record"))?,
+                        file: file
+                            .parse()
+                            .map_err(|_| invalid(line_no, "malformed line record"))?,
+                    });
+                }
+            }
+        }
+
+        symbols.functions.sort_by_key(|f| f.address);
+        for function in &mut symbols.functions {
+            function.lines.sort_by_key(|l| l.address);
+        }
+        symbols.publics.sort_by_key(|p| p.address);
+
+        Ok(symbols)
+    }
+
+    /// Resolves an offset from the module base
+    ///
+    /// `FUNC` records (with file and line) win; otherwise the nearest
+    /// preceding `PUBLIC` symbol is used, unless a function ends in between.
+    pub fn lookup(&self, offset: u64) -> Option<Symbol<'_>> {
+        let preceding = self.functions.partition_point(|f| f.address <= offset);
+        let function = preceding.checked_sub(1).map(|i| &self.functions[i]);
+
+        if let Some(function) = function.filter(|f| offset - f.address < f.size.max(1)) {
+            let line = function
+                .lines
+                .partition_point(|l| l.address <= offset)
+                .checked_sub(1)
+                .map(|i| &function.lines[i])
+                .filter(|l| offset - l.address < l.size.max(1));
+
+            return Some(Symbol {
+                function: &function.name,
+                symbol_addr: function.address,
+                filename: line
+                    .and_then(|l| self.files.get(&l.file))
+                    .map(String::as_str),
+                lineno: line.map(|l| l.line),
+            });
+        }
+
+        let public = self
+            .publics
+            .partition_point(|p| p.address <= offset)
+            .checked_sub(1)
+            .map(|i| &self.publics[i])?;
+        if function.is_some_and(|f| f.address > public.address) {
+            return None;
+        }
+
+        Some(Symbol {
+            function: &public.name,
+            symbol_addr: public.address,
+            filename: None,
+            lineno: None,
+        })
+    }
+}
+
+/// Parses a `MODULE <os> <arch> <id> <name>` record
+fn parse_module(line: &str) -> AppResult<SymbolModule> {
+    let mut parts = line.trim_end().splitn(5, ' ');
+    let (Some("MODULE"), Some(os), Some(arch), Some(id), Some(name)) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return Err(invalid(1, "missing MODULE record"));
+    };
+
+    Ok(SymbolModule {
+        os: os.to_string(),
+        arch: arch.to_string(),
+        debug_id: debug_id_from_breakpad(id).ok_or_else(|| invalid(1, "malformed module id"))?,
+        name: name.to_string(),
+    })
+}
+
+/// Converts a Breakpad module id (GUID hex + age hex) to a debug ID
+///
+/// "6EDC6ACDB282125843FD59DA9C81BD830" -> "6edc6acd-b282-1258-43fd-59da9c81bd83"
+pub fn debug_id_from_breakpad(id: &str) -> Option<String> {
+    if id.len() < 33 || id.len() > 40 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let id = id.to_ascii_lowercase();
+    let (guid, age) = id.split_at(32);
+    let guid = format!(
+        "{}-{}-{}-{}-{}",
+        &guid[..8],
+        &guid[8..12],
+        &guid[12..16],
+        &guid[16..20],
+        &guid[20..]
+    );
+
+    let age = u32::from_str_radix(age, 16).ok()?;
+    if age == 0 {
+        Some(guid)
+    } else {
+        Some(format!("{}-{:x}", guid, age))
+    }
+}
+
+fn hex(value: &str, line_no: usize) -> AppResult<u64> {
+    u64::from_str_radix(value, 16).map_err(|_| invalid(line_no, "malformed address"))
+}
+
+fn invalid(line_no: usize, reason: &str) -> AppError {
+    AppError::Validation(format!(
+        "Invalid symbol file: {} (line {})",
+        reason, line_no
+    ))
+}
@@ -0,0 +1,103 @@
+//! Extraction of the OS, browser, runtime and dist an event was seen on.
+//!
+//! Values are the context's `name` followed by its `version` cut down to
+//! `major.minor`, e.g. `contexts.browser = {"name": "Chrome", "version":
+//! "120.0.6099.109"}` becomes `Chrome 120.0`. Contexts without a name are
+//! skipped. The dist is the event's top-level `dist`, which mobile SDKs send
+//! to tell builds of the same release apart.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Max characters stored per context value
+pub const MAX_CONTEXT_VALUE_LENGTH: usize = 255;
+
+/// Max characters of a dist, as stored on events
+pub const MAX_DIST_LENGTH: usize = 64;
+
+/// Context an issue's events are broken down by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextDimension {
+    Os,
+    Browser,
+    Runtime,
+    Dist,
+}
+
+impl ContextDimension {
+    /// Dimensions read from the event's `contexts`
+    pub const CONTEXTS: [ContextDimension; 3] = [
+        ContextDimension::Os,
+        ContextDimension::Browser,
+        ContextDimension::Runtime,
+    ];
+
+    /// Key of the dimension in the event's `contexts` and in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContextDimension::Os => "os",
+            ContextDimension::Browser => "browser",
+            ContextDimension::Runtime => "runtime",
+            ContextDimension::Dist => "dist",
+        }
+    }
+}
+
+/// Extracts the normalized value of each dimension present in the event
+pub fn extract_contexts(event_data: &Value) -> Vec<(ContextDimension, String)> {
+    let mut values: Vec<_> = match event_data.get("contexts") {
+        Some(contexts) => ContextDimension::CONTEXTS
+            .iter()
+            .filter_map(|dimension| {
+                let context = contexts.get(dimension.as_str())?;
+                context_value(context).map(|value| (*dimension, value))
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if let Some(dist) = extract_dist(event_data) {
+        values.push((ContextDimension::Dist, dist));
+    }
+    values
+}
+
+/// The event's `dist`, trimmed and cut to [`MAX_DIST_LENGTH`] characters
+pub fn extract_dist(event_data: &Value) -> Option<String> {
+    let dist = event_data.get("dist")?.as_str()?.trim();
+    if dist.is_empty() {
+        return None;
+    }
+    Some(dist.chars().take(MAX_DIST_LENGTH).collect())
+}
+
+/// Formats a context as "name major.minor", or just the name without a version
+fn context_value(context: &Value) -> Option<String> {
+    let name = context.get("name")?.as_str()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let version = context
+        .get("version")
+        .and_then(|v| v.as_str())
+        .and_then(normalize_version);
+
+    let value = match version {
+        Some(version) => format!("{} {}", name, version),
+        None => name.to_string(),
+    };
+
+    Some(value.chars().take(MAX_CONTEXT_VALUE_LENGTH).collect())
+}
+
+/// Cuts a version down to `major.minor`
+///
+/// Only the first word is considered, so `22.04.3 LTS` becomes `22.04`.
+pub fn normalize_version(version: &str) -> Option<String> {
+    let version = version.split_whitespace().next()?;
+    let normalized = version.splitn(3, '.').take(2).collect::<Vec<_>>().join(".");
+
+    Some(normalized)
+}
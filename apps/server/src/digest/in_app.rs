@@ -0,0 +1,86 @@
+//! Per-project rules that override the `in_app` flag SDKs set on frames.
+//!
+//! The culprit of an issue is its last in-app frame, so an SDK that marks
+//! framework or vendored code as in-app makes issues point at that code.
+//! Projects list module prefixes in `in_app_include` and `in_app_exclude`;
+//! frames matching neither keep the SDK's flag.
+
+use serde_json::Value;
+
+/// Max prefixes in each of a project's in-app lists
+pub const MAX_IN_APP_PREFIXES: usize = 50;
+
+/// Sets the `in_app` flag of every frame matching a project's prefixes
+///
+/// A frame matches by its `module`, or its `filename` when it has no module.
+/// Include wins over exclude, so a project can exclude a framework and still
+/// include one of its packages. Covers `exception.values[]`,
+/// `threads.values[]` and the top-level `stacktrace`.
+///
+/// Returns true if any flag changed.
+pub fn apply_in_app_rules(event_data: &mut Value, include: &[String], exclude: &[String]) -> bool {
+    if include.is_empty() && exclude.is_empty() {
+        return false;
+    }
+
+    let mut changed = false;
+
+    for key in ["exception", "threads"] {
+        if let Some(values) = event_data
+            .get_mut(key)
+            .and_then(|v| v.get_mut("values"))
+            .and_then(|v| v.as_array_mut())
+        {
+            for value in values {
+                if let Some(stacktrace) = value.get_mut("stacktrace") {
+                    changed |= apply_to_stacktrace(stacktrace, include, exclude);
+                }
+            }
+        }
+    }
+
+    if let Some(stacktrace) = event_data.get_mut("stacktrace") {
+        changed |= apply_to_stacktrace(stacktrace, include, exclude);
+    }
+
+    changed
+}
+
+fn apply_to_stacktrace(stacktrace: &mut Value, include: &[String], exclude: &[String]) -> bool {
+    let Some(frames) = stacktrace.get_mut("frames").and_then(|f| f.as_array_mut()) else {
+        return false;
+    };
+
+    let mut changed = false;
+    for frame in frames {
+        let Some(in_app) = frame_in_app(frame, include, exclude) else {
+            continue;
+        };
+        if frame.get("in_app").and_then(|v| v.as_bool()) != Some(in_app) {
+            if let Some(frame) = frame.as_object_mut() {
+                frame.insert("in_app".to_string(), Value::Bool(in_app));
+                changed = true;
+            }
+        }
+    }
+
+    changed
+}
+
+/// The flag the rules give a frame; None when no prefix matches
+fn frame_in_app(frame: &Value, include: &[String], exclude: &[String]) -> Option<bool> {
+    let name = frame
+        .get("module")
+        .and_then(|m| m.as_str())
+        .filter(|m| !m.is_empty())
+        .or_else(|| frame.get("filename").and_then(|f| f.as_str()))?;
+
+    let matches = |prefixes: &[String]| prefixes.iter().any(|p| name.starts_with(p.as_str()));
+    if matches(include) {
+        Some(true)
+    } else if matches(exclude) {
+        Some(false)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,132 @@
+//! Time from ingest to stored event.
+//!
+//! Every stored event counts the time from when its envelope was accepted
+//! to when its row was inserted, in fixed buckets exported by `/metrics` and
+//! in a window of recent samples the digest status takes its p50/p95 from.
+//! Nothing is persisted: both start empty whenever the server starts, which
+//! Prometheus handles like any counter reset.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Upper bounds of the histogram buckets, in seconds; `+Inf` is implied
+pub const LATENCY_BUCKETS: [f64; 12] = [
+    0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0,
+];
+
+/// Latest latencies the percentiles are computed from
+pub const RECENT_LATENCY_SAMPLES: usize = 1000;
+
+static GLOBAL: LazyLock<Arc<DigestLatency>> = LazyLock::new(|| Arc::new(DigestLatency::new()));
+
+/// Histogram of ingest-to-stored latencies
+#[derive(Debug, Default)]
+pub struct DigestLatency {
+    inner: Mutex<Histogram>,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Events per bucket, not cumulative; the last one is `+Inf`
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: f64,
+    recent: VecDeque<f64>,
+}
+
+/// Cumulative bucket counts, as exported to Prometheus
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencySnapshot {
+    /// `(upper bound, events at or under it)`, `+Inf` last
+    pub buckets: Vec<(f64, u64)>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+/// Percentiles of the recent latencies, for the digest status
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    /// Latencies the percentiles are based on
+    pub samples: usize,
+    pub p50_secs: Option<f64>,
+    pub p95_secs: Option<f64>,
+}
+
+impl DigestLatency {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The histogram fed by the digest worker
+    pub fn global() -> Arc<DigestLatency> {
+        GLOBAL.clone()
+    }
+
+    /// Counts one stored event
+    pub fn record(&self, latency: Duration) {
+        let secs = latency.as_secs_f64();
+        let mut histogram = self.inner.lock().unwrap();
+        histogram.buckets[bucket_index(secs)] += 1;
+        histogram.sum += secs;
+        if histogram.recent.len() == RECENT_LATENCY_SAMPLES {
+            histogram.recent.pop_front();
+        }
+        histogram.recent.push_back(secs);
+    }
+
+    pub fn snapshot(&self) -> LatencySnapshot {
+        let histogram = self.inner.lock().unwrap();
+        let mut count = 0;
+        let buckets = LATENCY_BUCKETS
+            .iter()
+            .copied()
+            .chain([f64::INFINITY])
+            .zip(histogram.buckets)
+            .map(|(bound, events)| {
+                count += events;
+                (bound, count)
+            })
+            .collect();
+
+        LatencySnapshot {
+            buckets,
+            count,
+            sum: histogram.sum,
+        }
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        let mut recent: Vec<f64> = self.inner.lock().unwrap().recent.iter().copied().collect();
+        recent.sort_by(f64::total_cmp);
+
+        LatencySummary {
+            samples: recent.len(),
+            p50_secs: percentile(&recent, 0.5),
+            p95_secs: percentile(&recent, 0.95),
+        }
+    }
+}
+
+/// Time an event accepted at `ingested_at` took to be stored at `stored_at`
+///
+/// A clock that went backwards counts as no time at all.
+pub fn since_ingest(ingested_at: DateTime<Utc>, stored_at: DateTime<Utc>) -> Duration {
+    (stored_at - ingested_at).to_std().unwrap_or_default()
+}
+
+/// Index of the bucket a latency of `secs` falls in
+pub fn bucket_index(secs: f64) -> usize {
+    LATENCY_BUCKETS
+        .iter()
+        .position(|&bound| secs <= bound)
+        .unwrap_or(LATENCY_BUCKETS.len())
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], quantile: f64) -> Option<f64> {
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
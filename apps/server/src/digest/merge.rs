@@ -0,0 +1,41 @@
+//! Merging of event updates sent under an already digested event_id.
+//!
+//! Some SDKs report an event in two phases: the error first, then extra
+//! context under the same event_id. With `MERGE_DUPLICATE_EVENTS` the
+//! second payload is merged into the stored one instead of being dropped.
+
+use serde_json::Value;
+
+/// Merges `update` into `stored`
+///
+/// Objects are merged key by key, recursively; any other non-null value in
+/// the update (strings, numbers, arrays) replaces the stored one. Nulls in
+/// the update never remove data. Returns true if `stored` changed.
+pub fn merge_event_data(stored: &mut Value, update: &Value) -> bool {
+    match (stored, update) {
+        (_, Value::Null) => false,
+        (Value::Object(stored), Value::Object(update)) => {
+            let mut changed = false;
+            for (key, value) in update {
+                if value.is_null() {
+                    continue;
+                }
+                match stored.get_mut(key) {
+                    Some(existing) => changed |= merge_event_data(existing, value),
+                    None => {
+                        stored.insert(key.clone(), value.clone());
+                        changed = true;
+                    }
+                }
+            }
+            changed
+        }
+        (stored, update) => {
+            if stored == update {
+                return false;
+            }
+            *stored = update.clone();
+            true
+        }
+    }
+}
@@ -1,3 +1,27 @@
+pub mod breakpad;
+pub mod contexts;
+pub mod in_app;
+pub mod latency;
+pub mod merge;
+pub mod runner;
+pub mod sampling;
+pub mod stacktrace;
+pub mod symbol_cache;
+pub mod symbolicate;
+pub mod trim;
+pub mod users;
 pub mod worker;
 
-pub use worker::process_event;
+pub use breakpad::SymbolFile;
+pub use contexts::{extract_contexts, extract_dist, ContextDimension};
+pub use in_app::apply_in_app_rules;
+pub use latency::{DigestLatency, LatencySnapshot, LatencySummary};
+pub use merge::merge_event_data;
+pub use runner::{DigestError, DigestRunner, DigestStatus, DrainJob, DrainState};
+pub use sampling::keeps_event;
+pub use stacktrace::truncate_stacktraces;
+pub use symbol_cache::SymbolCache;
+pub use symbolicate::{referenced_debug_ids, symbolicate_event};
+pub use trim::trim_event;
+pub use users::{extract_user, EventUser};
+pub use worker::{process_event, process_event_with_retry, spawn_digest};
@@ -0,0 +1,411 @@
+//! Concurrency and progress of background digests.
+//!
+//! Every stored event is digested from its own task, but only
+//! `DIGEST_CONCURRENCY` of them may run at once; the rest wait for a permit
+//! with their file in the ingest directory. The limit can be changed at
+//! runtime, which is how an admin drain works through a backlog faster:
+//! it raises the limit until the backlog is empty or its time is up, then
+//! puts the old limit back.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+use crate::config::{DigestConfig, RateLimitConfig};
+use crate::db::{DbHealth, DbPool};
+use crate::digest::{process_event_with_retry, DigestLatency, LatencySummary};
+use crate::error::{AppError, AppResult};
+use crate::ingest::EventMetadata;
+
+static GLOBAL: OnceLock<Arc<DigestRunner>> = OnceLock::new();
+
+/// How often a drain checks whether the backlog is empty
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Window of `processed_last_minute`
+const PROCESSED_WINDOW_SECS: u64 = 60;
+
+/// Runs background digests with a limit that can change at runtime
+#[derive(Debug)]
+pub struct DigestRunner {
+    permits: Arc<Semaphore>,
+    concurrency: Mutex<usize>,
+    /// Events spooled and not digested yet, waiting or running
+    backlog: AtomicUsize,
+    /// Events queued by the fast_ack writer, not spooled yet
+    queued: AtomicUsize,
+    /// Events digested since start, successfully or not
+    processed: AtomicU64,
+    /// Digests finished per second over the last minute
+    recent: Mutex<VecDeque<(u64, u64)>>,
+    last_error: Mutex<Option<DigestError>>,
+    drain: Mutex<Option<DrainJob>>,
+}
+
+/// The most recent digest failure
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestError {
+    pub event_id: String,
+    pub message: String,
+    pub at: DateTime<Utc>,
+}
+
+/// State of a drain job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrainState {
+    Running,
+    /// The backlog was emptied
+    Completed,
+    /// The max duration elapsed first
+    TimedOut,
+}
+
+/// A temporary raise of the digest concurrency to work through the backlog
+#[derive(Debug, Clone, Serialize)]
+pub struct DrainJob {
+    pub id: Uuid,
+    pub state: DrainState,
+    /// Concurrency while the drain runs
+    pub concurrency: usize,
+    /// Concurrency put back once it's over
+    pub previous_concurrency: usize,
+    pub backlog_at_start: usize,
+    /// Events digested since the drain started
+    pub processed: u64,
+    pub max_duration_secs: u64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    processed_at_start: u64,
+}
+
+/// Snapshot for `GET /api/admin/digest/status`
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestStatus {
+    /// Events waiting in the ingest directory or being digested
+    pub backlog: usize,
+    /// Events acknowledged in fast_ack mode and not written to the ingest
+    /// directory yet
+    pub queued: usize,
+    pub processed_last_minute: u64,
+    pub concurrency: usize,
+    /// Time from ingest to stored of the latest events
+    pub latency: LatencySummary,
+    pub last_error: Option<DigestError>,
+    /// The latest drain, running or finished
+    pub drain: Option<DrainJob>,
+}
+
+impl DigestRunner {
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency)),
+            concurrency: Mutex::new(concurrency),
+            backlog: AtomicUsize::new(0),
+            queued: AtomicUsize::new(0),
+            processed: AtomicU64::new(0),
+            recent: Mutex::new(VecDeque::new()),
+            last_error: Mutex::new(None),
+            drain: Mutex::new(None),
+        }
+    }
+
+    /// Sets up the global runner from config
+    ///
+    /// Must run before the first event is digested; later calls are ignored.
+    pub fn init(config: &DigestConfig) {
+        if GLOBAL.set(Arc::new(Self::new(config.concurrency))).is_err() {
+            log::warn!("Digest runner already initialized");
+        }
+    }
+
+    /// The runner used for ingested events, with the default limit if
+    /// `init` was never called
+    pub fn global() -> Arc<DigestRunner> {
+        GLOBAL
+            .get_or_init(|| Arc::new(Self::new(DigestConfig::default().concurrency)))
+            .clone()
+    }
+
+    /// Max digests running at once
+    pub fn concurrency(&self) -> usize {
+        *self.concurrency.lock().unwrap()
+    }
+
+    /// Changes the max digests running at once
+    ///
+    /// Lowering it doesn't interrupt running digests; new ones wait until
+    /// enough of those have finished. Zero pauses digestion.
+    pub fn set_concurrency(&self, concurrency: usize) {
+        let mut current = self.concurrency.lock().unwrap();
+        if concurrency > *current {
+            self.permits.add_permits(concurrency - *current);
+        } else if concurrency < *current {
+            // Take the excess permits out of circulation, as they come back
+            // if they're in use. Waiters are served in order, so digests
+            // queued after this wait for it.
+            let excess = (*current - concurrency) as u32;
+            match self.permits.clone().try_acquire_many_owned(excess) {
+                Ok(permits) => permits.forget(),
+                Err(_) => {
+                    let permits = self.permits.clone();
+                    // No runtime left means the server is shutting down
+                    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                        runtime.spawn(async move {
+                            if let Ok(permits) = permits.acquire_many_owned(excess).await {
+                                permits.forget();
+                            }
+                        });
+                    }
+                }
+            }
+        }
+        *current = concurrency;
+    }
+
+    /// Digest slots currently free
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+
+    /// Events spooled and not digested yet
+    pub fn backlog(&self) -> usize {
+        self.backlog.load(Ordering::SeqCst)
+    }
+
+    /// Events queued by the fast_ack writer, not spooled yet
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Counts an event handed to the fast_ack writer
+    pub fn add_queued(&self) {
+        self.queued.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Counts an event off the fast_ack writer's queue, once it's spooled
+    /// and its digest spawned, or failed to be written
+    pub fn remove_queued(&self) {
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Events not digested yet, whether spooled or still queued
+    fn pending(&self) -> usize {
+        self.backlog() + self.queued()
+    }
+
+    /// Events digested over the last minute
+    pub fn processed_last_minute(&self) -> u64 {
+        let now = unix_secs();
+        let mut recent = self.recent.lock().unwrap();
+        prune(&mut recent, now);
+        recent.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn status(&self) -> DigestStatus {
+        DigestStatus {
+            backlog: self.backlog(),
+            queued: self.queued(),
+            processed_last_minute: self.processed_last_minute(),
+            concurrency: self.concurrency(),
+            latency: DigestLatency::global().summary(),
+            last_error: self.last_error.lock().unwrap().clone(),
+            drain: self.drain_job(),
+        }
+    }
+
+    /// The latest drain, with its progress so far if it's still running
+    pub fn drain_job(&self) -> Option<DrainJob> {
+        let mut job = self.drain.lock().unwrap().clone()?;
+        if job.state == DrainState::Running {
+            job.processed = self.processed.load(Ordering::SeqCst) - job.processed_at_start;
+        }
+        Some(job)
+    }
+
+    /// Digests a stored event in the background once a permit is free,
+    /// waiting out database outages
+    pub fn spawn(
+        self: &Arc<Self>,
+        pool: &DbPool,
+        rate_limit_config: &RateLimitConfig,
+        digest_config: &DigestConfig,
+        db_health: Arc<DbHealth>,
+        ingest_dir: PathBuf,
+        metadata: EventMetadata,
+    ) {
+        let runner = self.clone();
+        let pool = pool.clone();
+        let rate_limit_config = rate_limit_config.clone();
+        let digest_config = digest_config.clone();
+        self.backlog.fetch_add(1, Ordering::SeqCst);
+
+        tokio::spawn(async move {
+            let permit = runner.acquire().await;
+            let result = process_event_with_retry(
+                &pool,
+                &metadata,
+                &ingest_dir,
+                &rate_limit_config,
+                &digest_config,
+                &db_health,
+            )
+            .await;
+            drop(permit);
+
+            runner.finish(&metadata, result);
+        });
+    }
+
+    /// Raises the concurrency to `concurrency` until the backlog is empty
+    /// or `max_duration` has passed, then puts the old value back
+    ///
+    /// Only one drain runs at a time. Progress is reported by [`Self::status`].
+    pub fn start_drain(
+        self: &Arc<Self>,
+        concurrency: usize,
+        max_duration: Duration,
+    ) -> AppResult<DrainJob> {
+        let job = {
+            let mut drain = self.drain.lock().unwrap();
+            if let Some(job) = drain.as_ref().filter(|j| j.state == DrainState::Running) {
+                return Err(AppError::Conflict(format!(
+                    "Drain {} is already running",
+                    job.id
+                )));
+            }
+
+            let job = DrainJob {
+                id: Uuid::new_v4(),
+                state: DrainState::Running,
+                concurrency,
+                previous_concurrency: self.concurrency(),
+                backlog_at_start: self.pending(),
+                processed: 0,
+                max_duration_secs: max_duration.as_secs(),
+                started_at: Utc::now(),
+                finished_at: None,
+                processed_at_start: self.processed.load(Ordering::SeqCst),
+            };
+            *drain = Some(job.clone());
+            job
+        };
+
+        log::info!(
+            "Draining {} spooled events with concurrency {} (was {})",
+            job.backlog_at_start,
+            job.concurrency,
+            job.previous_concurrency
+        );
+        let restore = RestoreConcurrency {
+            runner: self.clone(),
+            concurrency: job.previous_concurrency,
+        };
+        self.set_concurrency(concurrency);
+
+        let runner = self.clone();
+        tokio::spawn(async move {
+            let deadline = Instant::now() + max_duration;
+            let state = loop {
+                if runner.pending() == 0 {
+                    break DrainState::Completed;
+                }
+                if Instant::now() >= deadline {
+                    break DrainState::TimedOut;
+                }
+                tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+            };
+
+            // Put the old concurrency back before reporting the drain as done
+            drop(restore);
+            runner.finish_drain(state);
+        });
+
+        Ok(job)
+    }
+
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("digest semaphore is never closed")
+    }
+
+    fn finish(&self, metadata: &EventMetadata, result: AppResult<()>) {
+        self.backlog.fetch_sub(1, Ordering::SeqCst);
+        self.processed.fetch_add(1, Ordering::SeqCst);
+
+        let now = unix_secs();
+        {
+            let mut recent = self.recent.lock().unwrap();
+            prune(&mut recent, now);
+            match recent.back_mut() {
+                Some((second, count)) if *second == now => *count += 1,
+                _ => recent.push_back((now, 1)),
+            }
+        }
+
+        if let Err(e) = result {
+            log::error!("Failed to digest event {}: {:?}", metadata.event_id, e);
+            *self.last_error.lock().unwrap() = Some(DigestError {
+                event_id: metadata.event_id.clone(),
+                message: e.to_string(),
+                at: Utc::now(),
+            });
+        }
+    }
+
+    fn finish_drain(&self, state: DrainState) {
+        let processed = self.processed.load(Ordering::SeqCst);
+        let mut drain = self.drain.lock().unwrap();
+        if let Some(job) = drain.as_mut() {
+            job.state = state;
+            job.processed = processed - job.processed_at_start;
+            job.finished_at = Some(Utc::now());
+            log::info!(
+                "Drain {} finished ({:?}) after {} events, concurrency back to {}",
+                job.id,
+                state,
+                job.processed,
+                job.previous_concurrency
+            );
+        }
+    }
+}
+
+/// Puts the concurrency back when a drain ends, even if its task is dropped
+struct RestoreConcurrency {
+    runner: Arc<DigestRunner>,
+    concurrency: usize,
+}
+
+impl Drop for RestoreConcurrency {
+    fn drop(&mut self) {
+        self.runner.set_concurrency(self.concurrency);
+    }
+}
+
+fn unix_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn prune(recent: &mut VecDeque<(u64, u64)>, now: u64) {
+    while recent
+        .front()
+        .is_some_and(|(second, _)| now.saturating_sub(*second) >= PROCESSED_WINDOW_SECS)
+    {
+        recent.pop_front();
+    }
+}
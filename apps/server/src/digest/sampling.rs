@@ -0,0 +1,36 @@
+//! Sampling of the events of noisy issues.
+//!
+//! An issue with a `sample_rate` only stores that share of its new events;
+//! the others are counted in `digested_event_count` and dropped. Whether an
+//! event is kept depends only on its event_id, so an event sent again gets
+//! the same answer.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use uuid::Uuid;
+
+/// Events counted but not stored because their issue is sampled
+static SAMPLED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Whether an event of an issue sampled at `sample_rate` is stored
+///
+/// Issues without a rate store every event.
+pub fn keeps_event(event_id: Uuid, sample_rate: Option<f64>) -> bool {
+    let Some(rate) = sample_rate else {
+        return true;
+    };
+    // The event_id is random, so its first 8 bytes are a uniform fraction
+    let bytes: [u8; 8] = event_id.as_bytes()[..8].try_into().unwrap();
+    let fraction = u64::from_be_bytes(bytes) as f64 / u64::MAX as f64;
+    fraction < rate
+}
+
+/// Counts an event dropped by sampling
+pub fn record_sampled() {
+    SAMPLED_EVENTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Events dropped by sampling since startup
+pub fn sampled_count() -> u64 {
+    SAMPLED_EVENTS.load(Ordering::Relaxed)
+}
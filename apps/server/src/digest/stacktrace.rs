@@ -0,0 +1,123 @@
+//! Stacktrace truncation applied before events are stored.
+
+use serde_json::{json, Value};
+
+/// Truncates every stacktrace in the event to at most `max_frames` frames.
+///
+/// Over-long stacktraces keep their top and bottom frames with a marker frame in
+/// between, and record the omitted range in `frames_omitted` (Sentry protocol).
+/// Covers `exception.values[]`, `threads.values[]` and the top-level `stacktrace`.
+///
+/// Returns true if any stacktrace was truncated.
+pub fn truncate_stacktraces(event_data: &mut Value, max_frames: usize) -> bool {
+    let mut truncated = false;
+
+    for key in ["exception", "threads"] {
+        if let Some(values) = event_data
+            .get_mut(key)
+            .and_then(|v| v.get_mut("values"))
+            .and_then(|v| v.as_array_mut())
+        {
+            for value in values {
+                if let Some(stacktrace) = value.get_mut("stacktrace") {
+                    truncated |= truncate_stacktrace(stacktrace, max_frames);
+                }
+            }
+        }
+    }
+
+    if let Some(stacktrace) = event_data.get_mut("stacktrace") {
+        truncated |= truncate_stacktrace(stacktrace, max_frames);
+    }
+
+    truncated
+}
+
+/// Truncates a single stacktrace object in place
+fn truncate_stacktrace(stacktrace: &mut Value, max_frames: usize) -> bool {
+    let Some(frames) = stacktrace.get_mut("frames").and_then(|f| f.as_array_mut()) else {
+        return false;
+    };
+
+    if max_frames == 0 || frames.len() <= max_frames {
+        return false;
+    }
+
+    // Frames are ordered oldest first, so the bottom half holds the crash site
+    let keep_top = max_frames / 2;
+    let keep_bottom = max_frames - keep_top;
+    let omitted_end = frames.len() - keep_bottom;
+    let omitted = omitted_end - keep_top;
+
+    let marker = json!({
+        "function": format!("<{} frames omitted>", omitted),
+        "in_app": false,
+    });
+    frames.splice(keep_top..omitted_end, std::iter::once(marker));
+
+    stacktrace["frames_omitted"] = json!([keep_top, omitted_end]);
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacktrace(count: usize) -> Value {
+        let frames: Vec<Value> = (0..count)
+            .map(|i| json!({ "function": format!("f{}", i) }))
+            .collect();
+        json!({ "frames": frames })
+    }
+
+    #[test]
+    fn test_short_stacktrace_untouched() {
+        let mut event = json!({ "exception": { "values": [{ "stacktrace": stacktrace(5) }] } });
+        let original = event.clone();
+
+        assert!(!truncate_stacktraces(&mut event, 5));
+        assert_eq!(event, original);
+    }
+
+    #[test]
+    fn test_long_stacktrace_keeps_top_and_bottom() {
+        let mut event = json!({ "exception": { "values": [{ "stacktrace": stacktrace(100) }] } });
+
+        assert!(truncate_stacktraces(&mut event, 10));
+
+        let st = &event["exception"]["values"][0]["stacktrace"];
+        let frames = st["frames"].as_array().unwrap();
+        assert_eq!(frames.len(), 11);
+        assert_eq!(frames[0]["function"], "f0");
+        assert_eq!(frames[4]["function"], "f4");
+        assert_eq!(frames[5]["function"], "<90 frames omitted>");
+        assert_eq!(frames[6]["function"], "f95");
+        assert_eq!(frames[10]["function"], "f99");
+        assert_eq!(st["frames_omitted"], json!([5, 95]));
+    }
+
+    #[test]
+    fn test_threads_and_top_level_stacktrace() {
+        let mut event = json!({
+            "threads": { "values": [{ "stacktrace": stacktrace(20) }] },
+            "stacktrace": stacktrace(20),
+        });
+
+        assert!(truncate_stacktraces(&mut event, 4));
+        assert_eq!(
+            event["threads"]["values"][0]["stacktrace"]["frames"]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+        assert_eq!(event["stacktrace"]["frames"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_zero_disables_truncation() {
+        let mut event = json!({ "stacktrace": stacktrace(1000) });
+        assert!(!truncate_stacktraces(&mut event, 0));
+    }
+}
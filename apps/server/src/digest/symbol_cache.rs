@@ -0,0 +1,114 @@
+//! Parsed symbol files kept between digests.
+//!
+//! Parsing a large Breakpad file takes far longer than symbolicating a
+//! crash with it, and a crashing release sends the same modules over and
+//! over. The cache holds the last used files per project and debug ID,
+//! dropping the least recently used one when it's full. Each entry is tagged
+//! with the upload time of its file: a file replaced on another instance has
+//! a newer time and is parsed again, uploads and deletes on this instance
+//! drop the entry right away.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+
+use crate::digest::breakpad::SymbolFile;
+
+/// Parsed symbol files kept by the global cache
+pub const SYMBOL_CACHE_CAPACITY: usize = 32;
+
+static GLOBAL: LazyLock<SymbolCache> = LazyLock::new(|| SymbolCache::new(SYMBOL_CACHE_CAPACITY));
+
+struct Entry {
+    uploaded_at: DateTime<Utc>,
+    file: Arc<SymbolFile>,
+    used_at: Instant,
+}
+
+/// Parsed symbol files by project and debug ID
+pub struct SymbolCache {
+    capacity: usize,
+    entries: Mutex<HashMap<(i32, String), Entry>>,
+}
+
+impl SymbolCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cache shared by the digest workers
+    pub fn global() -> &'static SymbolCache {
+        &GLOBAL
+    }
+
+    /// The parsed file, if it's cached for the upload at `uploaded_at`
+    pub fn get(
+        &self,
+        project_id: i32,
+        debug_id: &str,
+        uploaded_at: DateTime<Utc>,
+    ) -> Option<Arc<SymbolFile>> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(&(project_id, debug_id.to_string()))?;
+        if entry.uploaded_at != uploaded_at {
+            return None;
+        }
+        entry.used_at = Instant::now();
+        Some(entry.file.clone())
+    }
+
+    /// Caches a parsed file, evicting the least recently used one when full
+    pub fn insert(
+        &self,
+        project_id: i32,
+        debug_id: &str,
+        uploaded_at: DateTime<Utc>,
+        file: Arc<SymbolFile>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let key = (project_id, debug_id.to_string());
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                uploaded_at,
+                file,
+                used_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops the file of a debug ID, e.g. once it's replaced or deleted
+    pub fn remove(&self, project_id: i32, debug_id: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .remove(&(project_id, debug_id.to_string()));
+    }
+
+    /// Number of cached files
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,222 @@
+//! Symbolication of native stack frames with uploaded debug files.
+//!
+//! Frames with an `instruction_addr` are matched to the images listed in
+//! `debug_meta.images`. Every matched frame gets its image as `package` and
+//! its offset in the image as `data.module_offset`; frames whose image has
+//! symbols also get `function`, `filename` and `lineno`. Frames that cannot
+//! be resolved keep their addresses, and `data.symbolicator_status` tells
+//! what happened ("symbolicated", "missing" or "unknown_image").
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::digest::breakpad::SymbolFile;
+
+/// A loaded module of the crashed process
+struct Image {
+    debug_id: Option<String>,
+    code_file: Option<String>,
+    address: u64,
+    size: u64,
+}
+
+/// Debug IDs of the images that contain at least one frame of the event
+pub fn referenced_debug_ids(event_data: &Value) -> Vec<String> {
+    let images = get_images(event_data);
+    let mut debug_ids: Vec<String> = native_frames(event_data)
+        .filter_map(|frame| parse_addr(frame.get("instruction_addr")?))
+        .filter_map(|addr| find_image(&images, addr)?.debug_id.clone())
+        .collect();
+
+    debug_ids.sort();
+    debug_ids.dedup();
+    debug_ids
+}
+
+/// Symbolicates all native frames of the event in place
+///
+/// `symbols` maps debug IDs to their symbol files. Returns true if any frame
+/// was resolved.
+pub fn symbolicate_event(
+    event_data: &mut Value,
+    symbols: &HashMap<String, Arc<SymbolFile>>,
+) -> bool {
+    let images = get_images(event_data);
+    if images.is_empty() {
+        return false;
+    }
+
+    let mut resolved = false;
+    for frames in stacktraces_mut(event_data) {
+        let crashing = frames.len().saturating_sub(1);
+        for (i, frame) in frames.iter_mut().enumerate() {
+            let Some(addr) = frame.get("instruction_addr").and_then(parse_addr) else {
+                continue;
+            };
+
+            let Some(image) = find_image(&images, addr) else {
+                set_status(frame, "unknown_image");
+                continue;
+            };
+            let offset = addr - image.address;
+
+            if let Some(code_file) = &image.code_file {
+                if frame.get("package").is_none() {
+                    frame["package"] = json!(code_file);
+                }
+            }
+            frame_data(frame).insert("module_offset".to_string(), json!(format_addr(offset)));
+
+            // Callers hold return addresses, which point past the call instruction
+            let lookup_offset = if i == crashing {
+                offset
+            } else {
+                offset.saturating_sub(1)
+            };
+
+            let symbol = image
+                .debug_id
+                .as_ref()
+                .and_then(|debug_id| symbols.get(debug_id))
+                .and_then(|symbols| symbols.lookup(lookup_offset));
+
+            let Some(symbol) = symbol else {
+                set_status(frame, "missing");
+                continue;
+            };
+
+            frame["function"] = json!(symbol.function);
+            frame["symbol_addr"] = json!(format_addr(image.address + symbol.symbol_addr));
+            if let Some(filename) = symbol.filename {
+                frame["filename"] = json!(filename);
+            }
+            if let Some(lineno) = symbol.lineno {
+                frame["lineno"] = json!(lineno);
+            }
+            set_status(frame, "symbolicated");
+            resolved = true;
+        }
+    }
+
+    resolved
+}
+
+impl Image {
+    fn contains(&self, addr: u64) -> bool {
+        addr >= self.address && addr - self.address < self.size
+    }
+}
+
+/// Reads `debug_meta.images`, skipping entries without a usable address range
+fn get_images(event_data: &Value) -> Vec<Image> {
+    let Some(images) = event_data
+        .get("debug_meta")
+        .and_then(|d| d.get("images"))
+        .and_then(|i| i.as_array())
+    else {
+        return Vec::new();
+    };
+
+    images
+        .iter()
+        .filter_map(|image| {
+            Some(Image {
+                debug_id: image
+                    .get("debug_id")
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.to_ascii_lowercase()),
+                code_file: image
+                    .get("code_file")
+                    .and_then(|c| c.as_str())
+                    .map(str::to_string),
+                address: parse_addr(image.get("image_addr")?)?,
+                size: image.get("image_size").and_then(|s| s.as_u64())?,
+            })
+        })
+        .collect()
+}
+
+fn find_image(images: &[Image], addr: u64) -> Option<&Image> {
+    images.iter().find(|image| image.contains(addr))
+}
+
+/// Frames of all stacktraces in the event
+fn native_frames(event_data: &Value) -> impl Iterator<Item = &Value> {
+    let mut stacktraces: Vec<&Value> = Vec::new();
+    for key in ["exception", "threads"] {
+        if let Some(values) = event_data
+            .get(key)
+            .and_then(|v| v.get("values"))
+            .and_then(|v| v.as_array())
+        {
+            stacktraces.extend(values.iter().filter_map(|v| v.get("stacktrace")));
+        }
+    }
+    stacktraces.extend(event_data.get("stacktrace"));
+
+    stacktraces
+        .into_iter()
+        .filter_map(|st| st.get("frames").and_then(|f| f.as_array()))
+        .flatten()
+}
+
+/// Frame lists of all stacktraces in the event, mutably
+fn stacktraces_mut(event_data: &mut Value) -> Vec<&mut Vec<Value>> {
+    let Some(event) = event_data.as_object_mut() else {
+        return Vec::new();
+    };
+
+    let mut frames = Vec::new();
+    for (key, value) in event.iter_mut() {
+        let stacktraces: Vec<&mut Value> = match key.as_str() {
+            "exception" | "threads" => value
+                .get_mut("values")
+                .and_then(|v| v.as_array_mut())
+                .map(|values| {
+                    values
+                        .iter_mut()
+                        .filter_map(|v| v.get_mut("stacktrace"))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            "stacktrace" => vec![value],
+            _ => continue,
+        };
+        frames.extend(
+            stacktraces
+                .into_iter()
+                .filter_map(|st| st.get_mut("frames").and_then(|f| f.as_array_mut())),
+        );
+    }
+    frames
+}
+
+/// Addresses are hex strings ("0x1a2b") or plain numbers
+fn parse_addr(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => {
+            let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))?;
+            u64::from_str_radix(hex, 16).ok()
+        }
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+fn format_addr(addr: u64) -> String {
+    format!("0x{:x}", addr)
+}
+
+/// The frame's `data` object, created if needed
+fn frame_data(frame: &mut Value) -> &mut serde_json::Map<String, Value> {
+    if !frame.get("data").is_some_and(|d| d.is_object()) {
+        frame["data"] = json!({});
+    }
+    frame["data"].as_object_mut().expect("data is an object")
+}
+
+fn set_status(frame: &mut Value, status: &str) {
+    frame_data(frame).insert("symbolicator_status".to_string(), json!(status));
+}
@@ -0,0 +1,254 @@
+//! Size trimming of breadcrumbs and context data before events are stored.
+//!
+//! Everything that gets trimmed is recorded in the event's `_meta` object,
+//! following Sentry's convention of mirroring the path of the trimmed value
+//! with the annotation under an empty key:
+//!
+//! ```json
+//! { "_meta": { "breadcrumbs": { "values": { "": { "len": 1000 } } } } }
+//! ```
+//!
+//! `len` is the original length (items or characters); removed values carry
+//! `"rem": [["!limit", "x"]]`.
+
+use serde_json::{json, Map, Value};
+
+/// Max characters kept per string in breadcrumbs, `extra` and `contexts`
+pub const MAX_STRING_LENGTH: usize = 8192;
+
+/// Max nesting depth kept in breadcrumbs, `extra` and `contexts`
+pub const MAX_DEPTH: usize = 5;
+
+/// Trims breadcrumbs to the most recent `max_breadcrumbs`, truncates long
+/// strings and depth-limits `extra`/`contexts`, annotating `_meta`.
+///
+/// Grouped fields (exception, message, transaction) are never touched.
+/// Returns true if anything was trimmed.
+pub fn trim_event(event_data: &mut Value, max_breadcrumbs: usize) -> bool {
+    let Some(event) = event_data.as_object_mut() else {
+        return false;
+    };
+
+    let mut meta = match event.remove("_meta") {
+        Some(Value::Object(meta)) => Value::Object(meta),
+        _ => json!({}),
+    };
+    let mut trimmed = false;
+
+    // Breadcrumbs come as { "values": [...] } or as a bare array
+    if let Some(breadcrumbs) = event.get_mut("breadcrumbs") {
+        let (crumbs, path): (Option<&mut Vec<Value>>, Vec<String>) = if breadcrumbs.is_array() {
+            (breadcrumbs.as_array_mut(), vec!["breadcrumbs".to_string()])
+        } else {
+            (
+                breadcrumbs.get_mut("values").and_then(|v| v.as_array_mut()),
+                vec!["breadcrumbs".to_string(), "values".to_string()],
+            )
+        };
+
+        if let Some(crumbs) = crumbs {
+            if crumbs.len() > max_breadcrumbs {
+                let original_len = crumbs.len();
+                // Keep the most recent ones (last in the list)
+                crumbs.drain(..original_len - max_breadcrumbs);
+                annotate(&mut meta, &path, json!({ "len": original_len }));
+                trimmed = true;
+            }
+
+            for (i, crumb) in crumbs.iter_mut().enumerate() {
+                let mut crumb_path = path.clone();
+                crumb_path.push(i.to_string());
+                trimmed |= trim_value(crumb, &mut crumb_path, 0, &mut meta);
+            }
+        }
+    }
+
+    for key in ["extra", "contexts"] {
+        if let Some(value) = event.get_mut(key) {
+            trimmed |= trim_value(value, &mut vec![key.to_string()], 0, &mut meta);
+        }
+    }
+
+    if meta.as_object().is_some_and(|m| !m.is_empty()) {
+        event.insert("_meta".to_string(), meta);
+    }
+
+    trimmed
+}
+
+/// Truncates strings and removes values nested deeper than `MAX_DEPTH`
+fn trim_value(value: &mut Value, path: &mut Vec<String>, depth: usize, meta: &mut Value) -> bool {
+    match value {
+        Value::String(s) => {
+            let len = s.chars().count();
+            if len <= MAX_STRING_LENGTH {
+                return false;
+            }
+            *s = s.chars().take(MAX_STRING_LENGTH).collect();
+            annotate(meta, path, json!({ "len": len }));
+            true
+        }
+        Value::Object(_) | Value::Array(_) if depth >= MAX_DEPTH => {
+            *value = Value::Null;
+            annotate(meta, path, json!({ "rem": [["!limit", "x"]] }));
+            true
+        }
+        Value::Object(map) => {
+            let mut trimmed = false;
+            for (key, child) in map.iter_mut() {
+                path.push(key.clone());
+                trimmed |= trim_value(child, path, depth + 1, meta);
+                path.pop();
+            }
+            trimmed
+        }
+        Value::Array(items) => {
+            let mut trimmed = false;
+            for (i, child) in items.iter_mut().enumerate() {
+                path.push(i.to_string());
+                trimmed |= trim_value(child, path, depth + 1, meta);
+                path.pop();
+            }
+            trimmed
+        }
+        _ => false,
+    }
+}
+
+/// Records an annotation at `path` in the `_meta` tree
+fn annotate(meta: &mut Value, path: &[String], annotation: Value) {
+    let mut node = meta;
+    for segment in path {
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        node = node
+            .as_object_mut()
+            .expect("node is an object")
+            .entry(segment.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+
+    if !node.is_object() {
+        *node = Value::Object(Map::new());
+    }
+    node.as_object_mut()
+        .expect("node is an object")
+        .insert(String::new(), annotation);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breadcrumbs(count: usize) -> Vec<Value> {
+        (0..count)
+            .map(|i| json!({ "message": format!("crumb {}", i) }))
+            .collect()
+    }
+
+    #[test]
+    fn test_small_event_untouched() {
+        let mut event = json!({
+            "breadcrumbs": { "values": breadcrumbs(3) },
+            "extra": { "user": { "plan": "pro" } },
+        });
+        let original = event.clone();
+
+        assert!(!trim_event(&mut event, 100));
+        assert_eq!(event, original);
+    }
+
+    #[test]
+    fn test_keeps_most_recent_breadcrumbs() {
+        let mut event = json!({ "breadcrumbs": { "values": breadcrumbs(10) } });
+
+        assert!(trim_event(&mut event, 3));
+
+        let values = event["breadcrumbs"]["values"].as_array().unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0]["message"], "crumb 7");
+        assert_eq!(values[2]["message"], "crumb 9");
+        assert_eq!(event["_meta"]["breadcrumbs"]["values"][""]["len"], 10);
+    }
+
+    #[test]
+    fn test_bare_breadcrumb_array() {
+        let mut event = json!({ "breadcrumbs": breadcrumbs(5) });
+
+        assert!(trim_event(&mut event, 2));
+        assert_eq!(event["breadcrumbs"].as_array().unwrap().len(), 2);
+        assert_eq!(event["_meta"]["breadcrumbs"][""]["len"], 5);
+    }
+
+    #[test]
+    fn test_truncates_long_strings() {
+        let long = "x".repeat(MAX_STRING_LENGTH + 10);
+        let mut event = json!({ "extra": { "payload": long } });
+
+        assert!(trim_event(&mut event, 100));
+        assert_eq!(
+            event["extra"]["payload"].as_str().unwrap().len(),
+            MAX_STRING_LENGTH
+        );
+        assert_eq!(
+            event["_meta"]["extra"]["payload"][""]["len"],
+            MAX_STRING_LENGTH + 10
+        );
+    }
+
+    #[test]
+    fn test_truncates_multibyte_strings_on_char_boundary() {
+        let long = "é".repeat(MAX_STRING_LENGTH + 1);
+        let mut event = json!({ "contexts": { "app": { "name": long } } });
+
+        assert!(trim_event(&mut event, 100));
+        assert_eq!(
+            event["contexts"]["app"]["name"]
+                .as_str()
+                .unwrap()
+                .chars()
+                .count(),
+            MAX_STRING_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_depth_limits_nested_context() {
+        let mut event = json!({
+            "extra": { "a": { "b": { "c": { "d": { "e": { "f": 1 } } } } } },
+        });
+
+        assert!(trim_event(&mut event, 100));
+        assert!(event["extra"]["a"]["b"]["c"]["d"]["e"].is_null());
+        assert_eq!(
+            event["_meta"]["extra"]["a"]["b"]["c"]["d"]["e"][""]["rem"],
+            json!([["!limit", "x"]])
+        );
+    }
+
+    #[test]
+    fn test_grouped_fields_untouched() {
+        let long = "x".repeat(MAX_STRING_LENGTH + 1);
+        let mut event = json!({
+            "exception": { "values": [{ "type": "Error", "value": long }] },
+            "message": long,
+        });
+        let original = event.clone();
+
+        assert!(!trim_event(&mut event, 100));
+        assert_eq!(event, original);
+    }
+
+    #[test]
+    fn test_preserves_existing_meta() {
+        let mut event = json!({
+            "_meta": { "request": { "": { "len": 42 } } },
+            "breadcrumbs": { "values": breadcrumbs(5) },
+        });
+
+        assert!(trim_event(&mut event, 1));
+        assert_eq!(event["_meta"]["request"][""]["len"], 42);
+        assert_eq!(event["_meta"]["breadcrumbs"]["values"][""]["len"], 5);
+    }
+}
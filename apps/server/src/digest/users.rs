@@ -0,0 +1,59 @@
+//! Extraction of the end user an event was sent for.
+//!
+//! Reads the SDK's `user` interface, e.g. `{"id": "42", "email":
+//! "ada@example.com", "username": "ada"}`. Mobile SDKs often send only an
+//! id, such as a device id; numeric ids are kept as text. Users are told
+//! apart by their id, or their email or username when there's no id. An
+//! IP address alone doesn't make a user.
+
+use serde_json::Value;
+
+/// Max characters stored per user field
+pub const MAX_USER_FIELD_LENGTH: usize = 255;
+
+/// End user of an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventUser {
+    pub id: Option<String>,
+    pub email: Option<String>,
+    pub username: Option<String>,
+}
+
+impl EventUser {
+    /// Identifies the user across events: the id, else the email, else the username
+    pub fn key(&self) -> &str {
+        self.id
+            .as_deref()
+            .or(self.email.as_deref())
+            .or(self.username.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// The event's user, when it has an id, email or username
+pub fn extract_user(event_data: &Value) -> Option<EventUser> {
+    let user = event_data.get("user")?;
+    let user = EventUser {
+        id: user_field(user, "id"),
+        email: user_field(user, "email"),
+        username: user_field(user, "username"),
+    };
+
+    if user.key().is_empty() {
+        return None;
+    }
+    Some(user)
+}
+
+/// A field of the user interface, trimmed and cut to [`MAX_USER_FIELD_LENGTH`]
+fn user_field(user: &Value, field: &str) -> Option<String> {
+    let value = match user.get(field)? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    if value.is_empty() {
+        return None;
+    }
+    Some(value.chars().take(MAX_USER_FIELD_LENGTH).collect())
+}
@@ -1,105 +1,229 @@
 use chrono::Utc;
 use sqlx::{PgPool, Postgres, Transaction};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
-use crate::config::RateLimitConfig;
+use crate::config::{Config, DigestConfig, RateLimitConfig};
+use crate::db::{self, DbHealth};
+use crate::digest::{
+    apply_in_app_rules, extract_contexts, extract_user, keeps_event, latency, referenced_debug_ids,
+    sampling, symbolicate_event, trim_event, truncate_stacktraces, DigestLatency, DigestRunner,
+};
 use crate::error::{AppError, AppResult};
 use crate::ingest::{delete_event, read_event, EventMetadata};
-use crate::models::{Grouping, Issue};
+use crate::models::{Grouping, Issue, IssuePriority};
 use crate::services::{
-    calculate_grouping_key, get_denormalized_fields, hash_grouping_key, AlertService,
-    DenormalizedFields, EventService, ProjectService, RateLimitService,
+    calculate_fallback_grouping_key, calculate_grouping_key, get_denormalized_fields,
+    hash_grouping_key, AlertService, DebugFileService, DenormalizedFields, EventService,
+    IngestedEventService, IssueChange, IssueContextService, IssueService, IssueStream,
+    IssueStreamEvent, IssueUserService, LiveRates, ProjectSequenceService, ProjectService,
+    RateLimitService,
 };
 
+/// What digesting an event did to its issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IssueOutcome {
+    Created,
+    Updated,
+    /// The issue had been resolved and was reopened
+    Regressed,
+}
+
+/// Counts the time a stored event took from ingest, logging it when slow
+fn record_latency(metadata: &EventMetadata, digest_config: &DigestConfig) {
+    let latency = latency::since_ingest(metadata.ingested_at, Utc::now());
+    DigestLatency::global().record(latency);
+
+    let warn_after = digest_config.latency_warn_secs;
+    if warn_after > 0 && latency > Duration::from_secs(warn_after) {
+        log::warn!(
+            "Event {} of project {} took {:.1}s from ingest to stored",
+            metadata.event_id,
+            metadata.project_id,
+            latency.as_secs_f64()
+        );
+    }
+}
+
 /// Processes an event from temporary storage
 pub async fn process_event(
     pool: &PgPool,
     metadata: &EventMetadata,
     ingest_dir: &Path,
     rate_limit_config: &RateLimitConfig,
+    digest_config: &DigestConfig,
 ) -> AppResult<()> {
     let _digested_at = Utc::now();
 
     // 0. Double-check rate limits (for backlog scenarios)
     let project = ProjectService::get_by_id(pool, metadata.project_id).await?;
-    if let Some(_exceeded) = RateLimitService::check_quota(pool, &project).await? {
+    if let Some(_exceeded) = RateLimitService::check_quota(pool, project.id).await? {
         log::warn!(
             "Event {} discarded due to quota exceeded (backlog)",
             metadata.event_id
         );
-        delete_event(ingest_dir, &metadata.event_id).await?;
+        // Let the SDK send it again once the quota resets; an update keeps
+        // the record of the event it was meant for
+        if let (Ok(event_id), None) = (Uuid::parse_str(&metadata.event_id), &metadata.spool_id) {
+            IngestedEventService::forget(pool, metadata.project_id, event_id).await?;
+        }
+        delete_event(ingest_dir, metadata.spool_id()).await?;
         return Ok(());
     }
 
     // 1. Read event from filesystem
-    let event_bytes = read_event(ingest_dir, &metadata.event_id).await?;
-    let event_data: serde_json::Value = serde_json::from_slice(&event_bytes)
+    let event_bytes = read_event(ingest_dir, metadata.spool_id()).await?;
+    let mut event_data: serde_json::Value = serde_json::from_slice(&event_bytes)
         .map_err(|e| AppError::Internal(format!("Invalid event JSON: {}", e)))?;
 
     // 2. Parse event_id as UUID
     let event_id = Uuid::parse_str(&metadata.event_id)
         .map_err(|_| AppError::Validation("Invalid event_id".to_string()))?;
 
-    // 3. Check for duplicates
-    if EventService::exists(pool, metadata.project_id, event_id).await? {
-        log::warn!("Duplicate event_id: {}", metadata.event_id);
-        delete_event(ingest_dir, &metadata.event_id).await?;
+    // 3. Check for duplicates, e.g. a file digested again after a restart
+    if IngestedEventService::is_digested(pool, metadata.project_id, event_id).await? {
+        if digest_config.merge_duplicate_events {
+            merge_update(pool, metadata, event_id, event_data, digest_config).await?;
+        } else {
+            log::warn!("Duplicate event_id: {}", metadata.event_id);
+        }
+        delete_event(ingest_dir, metadata.spool_id()).await?;
         return Ok(());
     }
 
+    // Trim breadcrumbs and context data; grouped fields are left untouched
+    if trim_event(&mut event_data, digest_config.max_breadcrumbs) {
+        log::debug!("Trimmed oversized data of event {}", metadata.event_id);
+    }
+
+    // Resolve native frames with the project's uploaded debug files
+    let debug_ids = referenced_debug_ids(&event_data);
+    let symbols = DebugFileService::load_symbols(pool, metadata.project_id, &debug_ids).await?;
+    if symbolicate_event(&mut event_data, &symbols) {
+        log::debug!("Symbolicated native frames of event {}", metadata.event_id);
+    }
+
+    // Correct the SDK's in_app flags before the culprit frame is picked
+    if apply_in_app_rules(
+        &mut event_data,
+        &project.in_app_include,
+        &project.in_app_exclude,
+    ) {
+        log::debug!("Applied in-app rules to event {}", metadata.event_id);
+    }
+
     // 4. Calculate grouping key and hash
     let grouping_key = calculate_grouping_key(&event_data);
     let grouping_key_hash = hash_grouping_key(&grouping_key);
+    let fallback_grouping_key = calculate_fallback_grouping_key(&event_data);
 
     // 5. Extract denormalized fields
-    let denormalized = get_denormalized_fields(&event_data);
+    let denormalized =
+        get_denormalized_fields(&event_data).capped(digest_config.max_issue_value_length);
+
+    // Truncate over-long stacktraces only after grouping, so the key sees every frame
+    if truncate_stacktraces(&mut event_data, digest_config.max_stacktrace_frames) {
+        log::debug!("Truncated stacktrace(s) of event {}", metadata.event_id);
+    }
 
-    // 6. Find or create Grouping/Issue (within a transaction with advisory lock)
-    let (issue, grouping, issue_created) = find_or_create_issue_and_grouping_with_lock(
+    // 6. Find or create Grouping/Issue (within a transaction with advisory lock).
+    // The event's row id is picked up front so the issue can point at it in
+    // the same update
+    let event_row_id = Uuid::new_v4();
+    let spike_window = IssueService::priority_spike_window(digest_config.priority_spike_events);
+    let (mut issue, grouping, outcome, stored) = find_or_create_issue_and_grouping_with_lock(
         pool,
+        event_id,
+        event_row_id,
         metadata.project_id,
         &grouping_key,
         &grouping_key_hash,
+        fallback_grouping_key.as_deref(),
         metadata.ingested_at,
+        spike_window.start(Utc::now()),
         &denormalized,
         event_data.get("level").and_then(|l| l.as_str()),
         event_data.get("platform").and_then(|p| p.as_str()),
     )
     .await?;
+    let issue_created = outcome == IssueOutcome::Created;
 
-    // 7. Create Event
-    let digest_order = if issue_created {
-        1
-    } else {
-        issue.digested_event_count
-    };
+    if stored {
+        // 7. Create Event, numbered with the order reserved on the issue
+        let digest_order = issue.last_event_order;
 
-    EventService::create(
-        pool,
-        event_id,
-        metadata.project_id,
-        issue.id,
-        grouping.id,
-        &event_data,
-        metadata.ingested_at,
-        &denormalized,
-        digest_order,
-        metadata.remote_addr.as_deref(),
-    )
-    .await?;
+        EventService::create(
+            pool,
+            event_row_id,
+            event_id,
+            metadata.project_id,
+            issue.id,
+            grouping.id,
+            &event_data,
+            metadata.ingested_at,
+            &denormalized,
+            digest_order,
+            metadata.remote_addr.as_deref(),
+        )
+        .await?;
+        record_latency(metadata, digest_config);
 
-    // 8. Update project counters and rate limit state
-    sqlx::query("UPDATE projects SET stored_event_count = stored_event_count + 1 WHERE id = $1")
+        IngestedEventService::mark_digested(
+            pool,
+            metadata.project_id,
+            event_id,
+            metadata.ingested_at,
+        )
+        .await?;
+
+        // Count the OS/browser/runtime the event was seen on
+        IssueContextService::record(pool, issue.id, &extract_contexts(&event_data)).await?;
+
+        // Track the end user it was sent for, for the affected users export
+        if let Some(user) = extract_user(&event_data) {
+            IssueUserService::record(pool, issue.id, &user, metadata.ingested_at).await?;
+        }
+
+        // A spike of events raises the issue's priority
+        if let Some(raised) =
+            IssueService::raise_priority_on_spike(pool, &issue, &spike_window).await?
+        {
+            log::info!("Issue {} raised to {} priority", raised.id, raised.priority);
+            issue = raised;
+        }
+
+        // 8. Update project counters and rate limit state
+        sqlx::query(
+            "UPDATE projects SET stored_event_count = stored_event_count + 1 WHERE id = $1",
+        )
         .bind(metadata.project_id)
         .execute(pool)
         .await?;
+    } else {
+        // Sampled out: counted on the issue, but not stored
+        IngestedEventService::mark_sampled(
+            pool,
+            metadata.project_id,
+            event_id,
+            metadata.ingested_at,
+        )
+        .await?;
+        sampling::record_sampled();
+        log::debug!(
+            "Event {} sampled out of issue {}",
+            metadata.event_id,
+            issue.id
+        );
+    }
+    LiveRates::global().record_digested(metadata.project_id);
 
     // Update rate limiting quotas (handles digested_event_count)
     RateLimitService::update_quota_state(pool, metadata.project_id, rate_limit_config).await?;
 
     // 9. Delete temporary file
-    delete_event(ingest_dir, &metadata.event_id).await?;
+    delete_event(ingest_dir, metadata.spool_id()).await?;
 
     log::info!(
         "Digested event {} -> issue {} ({})",
@@ -108,42 +232,172 @@ pub async fn process_event(
         if issue_created { "new" } else { "existing" }
     );
 
-    // 10. Trigger alerts for new issues
-    if issue_created {
-        let pool = pool.clone();
-        let project = project.clone();
-        let issue = issue.clone();
+    // Push the change to live issue streams
+    let change = if issue_created {
+        IssueChange::Created
+    } else {
+        IssueChange::Updated
+    };
+    IssueStream::global().publish(IssueStreamEvent::new(
+        change,
+        &issue,
+        digest_config.max_issue_title_length,
+    ));
+
+    // 10. Trigger alerts for new and regressed issues. Awaited rather than
+    // spawned, so an issue storm waits for free delivery slots instead of
+    // piling up alert tasks.
+    if outcome != IssueOutcome::Updated {
+        let environment = event_data.get("environment").and_then(|e| e.as_str());
+        let max_title_length = digest_config.max_issue_title_length;
         let dashboard_url =
             std::env::var("DASHBOARD_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
 
-        tokio::spawn(async move {
-            if let Err(e) =
-                AlertService::trigger_new_issue_alert(&pool, &project, &issue, &dashboard_url).await
-            {
-                log::error!("Failed to trigger new issue alert: {}", e);
-            }
-        });
+        let result = if outcome == IssueOutcome::Created {
+            AlertService::trigger_new_issue_alert(
+                pool,
+                &project,
+                &issue,
+                environment,
+                &dashboard_url,
+                max_title_length,
+            )
+            .await
+        } else {
+            AlertService::trigger_regression_alert(
+                pool,
+                &project,
+                &issue,
+                environment,
+                &dashboard_url,
+                max_title_length,
+            )
+            .await
+        };
+        if let Err(e) = result {
+            log::error!("Failed to trigger {:?} alert: {}", outcome, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Merges an event sent again under a digested event_id into the stored one
+async fn merge_update(
+    pool: &PgPool,
+    metadata: &EventMetadata,
+    event_id: Uuid,
+    mut update: serde_json::Value,
+    digest_config: &DigestConfig,
+) -> AppResult<()> {
+    trim_event(&mut update, digest_config.max_breadcrumbs);
+    truncate_stacktraces(&mut update, digest_config.max_stacktrace_frames);
+
+    match EventService::merge_update(
+        pool,
+        metadata.project_id,
+        event_id,
+        &update,
+        metadata.ingested_at,
+    )
+    .await?
+    {
+        Some(event) => {
+            log::info!("Merged update into event {}", metadata.event_id);
+            let issue = IssueService::get_by_id(pool, event.issue_id).await?;
+            IssueStream::global().publish(IssueStreamEvent::new(
+                IssueChange::Updated,
+                &issue,
+                digest_config.max_issue_title_length,
+            ));
+        }
+        None => log::warn!(
+            "Event {} is no longer stored, dropping its update",
+            metadata.event_id
+        ),
     }
 
     Ok(())
 }
 
+/// Processes an event, waiting out database outages
+///
+/// While the database can't be reached the event stays in the ingest
+/// directory and is retried with the backoff tracked by `db_health`.
+pub async fn process_event_with_retry(
+    pool: &PgPool,
+    metadata: &EventMetadata,
+    ingest_dir: &Path,
+    rate_limit_config: &RateLimitConfig,
+    digest_config: &DigestConfig,
+    db_health: &DbHealth,
+) -> AppResult<()> {
+    loop {
+        if let Some(wait) = db_health.retry_in() {
+            tokio::time::sleep(wait).await;
+            continue;
+        }
+
+        match process_event(pool, metadata, ingest_dir, rate_limit_config, digest_config).await {
+            Err(AppError::Database(e)) if db::is_unavailable(&e) => {
+                let wait = db_health.record_failure();
+                log::warn!(
+                    "Database unavailable while digesting event {}, retrying in {:?}",
+                    metadata.event_id,
+                    wait
+                );
+            }
+            result => {
+                db_health.record_success();
+                return result;
+            }
+        }
+    }
+}
+
+/// Digests a stored event in the background, waiting out database outages
+///
+/// Runs on the global [`DigestRunner`], so at most `DIGEST_CONCURRENCY`
+/// events are digested at once.
+pub fn spawn_digest(
+    pool: &PgPool,
+    config: &Config,
+    db_health: Arc<DbHealth>,
+    ingest_dir: PathBuf,
+    metadata: EventMetadata,
+) {
+    DigestRunner::global().spawn(
+        pool,
+        &config.rate_limit,
+        &config.digest,
+        db_health,
+        ingest_dir,
+        metadata,
+    );
+}
+
 /// Finds an existing grouping or creates a new one along with its issue.
-/// Uses a PostgreSQL advisory lock per project to prevent race conditions
-/// when creating new issues with sequential digest_order values.
+/// Uses a PostgreSQL advisory lock per project so concurrent events with the
+/// same grouping key don't create two issues; digest orders come from the
+/// project's sequence.
 ///
 /// Advisory locks are automatically released when the transaction commits or rolls back.
 /// Different projects can process events concurrently (locks are per-project).
+#[allow(clippy::too_many_arguments)]
 async fn find_or_create_issue_and_grouping_with_lock(
     pool: &PgPool,
+    event_id: Uuid,
+    event_row_id: Uuid,
     project_id: i32,
     grouping_key: &str,
     grouping_key_hash: &str,
+    fallback_grouping_key: Option<&str>,
     timestamp: chrono::DateTime<Utc>,
+    spike_window_start: chrono::DateTime<Utc>,
     denormalized: &DenormalizedFields,
     level: Option<&str>,
     platform: Option<&str>,
-) -> AppResult<(Issue, Grouping, bool)> {
+) -> AppResult<(Issue, Grouping, IssueOutcome, bool)> {
     // Start a transaction
     let mut tx = pool.begin().await?;
 
@@ -158,10 +412,14 @@ async fn find_or_create_issue_and_grouping_with_lock(
     // Now we have exclusive access to issue creation for this project
     let result = find_or_create_issue_and_grouping_inner(
         &mut tx,
+        event_id,
+        event_row_id,
         project_id,
         grouping_key,
         grouping_key_hash,
+        fallback_grouping_key,
         timestamp,
+        spike_window_start,
         denormalized,
         level,
         platform,
@@ -169,10 +427,10 @@ async fn find_or_create_issue_and_grouping_with_lock(
     .await;
 
     match result {
-        Ok((issue, grouping, created)) => {
+        Ok((issue, grouping, outcome, stored)) => {
             // Commit the transaction (releases the advisory lock)
             tx.commit().await?;
-            Ok((issue, grouping, created))
+            Ok((issue, grouping, outcome, stored))
         }
         Err(e) => {
             // Rollback on error (also releases the advisory lock)
@@ -183,68 +441,133 @@ async fn find_or_create_issue_and_grouping_with_lock(
 }
 
 /// Inner function that performs the actual find-or-create logic within a transaction
+///
+/// Also tells whether the event is stored, or only counted because the issue
+/// samples it out. A stored event becomes the issue's latest one, under
+/// `event_row_id`; the pointer is followed only once the event row exists.
+/// Every event is counted towards a priority spike in the window starting
+/// at `spike_window_start`.
+#[allow(clippy::too_many_arguments)]
 async fn find_or_create_issue_and_grouping_inner(
     tx: &mut Transaction<'_, Postgres>,
+    event_id: Uuid,
+    event_row_id: Uuid,
     project_id: i32,
     grouping_key: &str,
     grouping_key_hash: &str,
+    fallback_grouping_key: Option<&str>,
     timestamp: chrono::DateTime<Utc>,
+    spike_window_start: chrono::DateTime<Utc>,
     denormalized: &DenormalizedFields,
     level: Option<&str>,
     platform: Option<&str>,
-) -> AppResult<(Issue, Grouping, bool)> {
+) -> AppResult<(Issue, Grouping, IssueOutcome, bool)> {
     // Try to find existing grouping
-    let existing_grouping: Option<Grouping> = sqlx::query_as(
-        r#"
-        SELECT * FROM groupings
-        WHERE project_id = $1 AND grouping_key_hash = $2
-        "#,
-    )
-    .bind(project_id)
-    .bind(grouping_key_hash)
-    .fetch_optional(&mut **tx)
-    .await?;
+    let mut existing_grouping = find_grouping(tx, project_id, grouping_key_hash).await?;
+
+    // A native event grouped before its symbols were uploaded is found under
+    // its fallback key; the symbolicated key is linked to the same issue
+    if existing_grouping.is_none() {
+        if let Some(fallback_key) = fallback_grouping_key {
+            let fallback_hash = hash_grouping_key(fallback_key);
+            if let Some(fallback) = find_grouping(tx, project_id, &fallback_hash).await? {
+                existing_grouping = Some(
+                    insert_grouping(
+                        tx,
+                        project_id,
+                        fallback.issue_id,
+                        grouping_key,
+                        grouping_key_hash,
+                    )
+                    .await?,
+                );
+            }
+        }
+    }
 
     if let Some(grouping) = existing_grouping {
-        // Grouping exists, update issue
+        // The error is back in a resolved issue, however it was resolved: it's
+        // reopened and counted as a regression
+        let (regressed, sample_rate): (bool, Option<f64>) =
+            sqlx::query_as("SELECT is_resolved, sample_rate FROM issues WHERE id = $1 FOR UPDATE")
+                .bind(grouping.issue_id)
+                .fetch_one(&mut **tx)
+                .await?;
+        let stored = keeps_event(event_id, sample_rate);
+
+        // Grouping exists, update issue. The culprit fields follow the most
+        // recent event, so an event digested late doesn't overwrite them.
+        // Sampled-out events only count towards digested_event_count.
         let issue: Issue = sqlx::query_as(
             r#"
             UPDATE issues
             SET last_seen = $2,
                 digested_event_count = digested_event_count + 1,
-                stored_event_count = stored_event_count + 1
+                stored_event_count = stored_event_count + $8,
+                last_event_order = last_event_order + $8,
+                last_event_id = CASE WHEN $8 = 1 THEN $9 ELSE last_event_id END,
+                transaction = CASE WHEN $2 >= last_seen THEN $3 ELSE transaction END,
+                last_frame_filename = CASE WHEN $2 >= last_seen THEN $4 ELSE last_frame_filename END,
+                last_frame_module = CASE WHEN $2 >= last_seen THEN $5 ELSE last_frame_module END,
+                last_frame_function = CASE WHEN $2 >= last_seen THEN $6 ELSE last_frame_function END,
+                is_unhandled = is_unhandled OR $7,
+                is_resolved = FALSE,
+                resolved_at = NULL,
+                resolved_by = NULL,
+                resolved_commit = NULL,
+                resolved_pr_url = NULL,
+                regression_count = regression_count + is_resolved::int,
+                auto_resolved = FALSE,
+                spike_base_event_count = CASE
+                    WHEN spike_window_start IS DISTINCT FROM $10 THEN digested_event_count
+                    ELSE spike_base_event_count
+                END,
+                spike_window_start = $10
             WHERE id = $1
             RETURNING *
             "#,
         )
         .bind(grouping.issue_id)
         .bind(timestamp)
+        .bind(&denormalized.transaction)
+        .bind(&denormalized.last_frame_filename)
+        .bind(&denormalized.last_frame_module)
+        .bind(&denormalized.last_frame_function)
+        .bind(denormalized.is_unhandled)
+        .bind(stored as i32)
+        .bind(event_row_id)
+        .bind(spike_window_start)
         .fetch_one(&mut **tx)
         .await?;
 
-        return Ok((issue, grouping, false));
+        let outcome = if regressed {
+            sqlx::query("INSERT INTO issue_regressions (issue_id) VALUES ($1)")
+                .bind(issue.id)
+                .execute(&mut **tx)
+                .await?;
+            IssueOutcome::Regressed
+        } else {
+            IssueOutcome::Updated
+        };
+        return Ok((issue, grouping, outcome, stored));
     }
 
-    // Get the next digest_order for this project (safe because we hold the advisory lock)
-    let max_order: Option<i32> =
-        sqlx::query_scalar("SELECT MAX(digest_order) FROM issues WHERE project_id = $1")
-            .bind(project_id)
-            .fetch_one(&mut **tx)
-            .await?;
-
-    let digest_order = max_order.unwrap_or(0) + 1;
+    let digest_order = ProjectSequenceService::reserve_issue_orders(tx, project_id, 1)
+        .await?
+        .start;
 
     // Create new issue
     let issue: Issue = sqlx::query_as(
         r#"
         INSERT INTO issues (
             project_id, digest_order, first_seen, last_seen,
-            digested_event_count, stored_event_count,
+            digested_event_count, stored_event_count, last_event_order,
             calculated_type, calculated_value, transaction,
             last_frame_filename, last_frame_module, last_frame_function,
-            level, platform
+            level, platform, exception_chain_length, is_unhandled, priority, last_event_id,
+            spike_window_start
         )
-        VALUES ($1, $2, $3, $3, 1, 1, $4, $5, $6, $7, $8, $9, $10, $11)
+        VALUES ($1, $2, $3, $3, 1, 1, 1, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
         RETURNING *
         "#,
     )
@@ -259,11 +582,56 @@ async fn find_or_create_issue_and_grouping_inner(
     .bind(&denormalized.last_frame_function)
     .bind(level)
     .bind(platform)
+    .bind(denormalized.exception_chain_length)
+    .bind(denormalized.is_unhandled)
+    .bind(IssuePriority::from_event(level, denormalized.is_unhandled))
+    .bind(event_row_id)
+    .bind(spike_window_start)
     .fetch_one(&mut **tx)
     .await?;
 
     // Create new grouping
-    let grouping: Grouping = sqlx::query_as(
+    let grouping =
+        insert_grouping(tx, project_id, issue.id, grouping_key, grouping_key_hash).await?;
+
+    // Also register the fallback key, so unsymbolicated events of this crash match
+    if let Some(fallback_key) = fallback_grouping_key {
+        let fallback_hash = hash_grouping_key(fallback_key);
+        insert_grouping(tx, project_id, issue.id, fallback_key, &fallback_hash).await?;
+    }
+
+    Ok((issue, grouping, IssueOutcome::Created, true))
+}
+
+/// Looks up a grouping by its key hash, skipping those of deleted issues
+async fn find_grouping(
+    tx: &mut Transaction<'_, Postgres>,
+    project_id: i32,
+    grouping_key_hash: &str,
+) -> AppResult<Option<Grouping>> {
+    let grouping = sqlx::query_as(
+        r#"
+        SELECT * FROM groupings
+        WHERE project_id = $1 AND grouping_key_hash = $2 AND detached_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .bind(grouping_key_hash)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(grouping)
+}
+
+/// Creates a grouping that maps a key to an issue
+async fn insert_grouping(
+    tx: &mut Transaction<'_, Postgres>,
+    project_id: i32,
+    issue_id: Uuid,
+    grouping_key: &str,
+    grouping_key_hash: &str,
+) -> AppResult<Grouping> {
+    let grouping = sqlx::query_as(
         r#"
         INSERT INTO groupings (project_id, issue_id, grouping_key, grouping_key_hash)
         VALUES ($1, $2, $3, $4)
@@ -271,11 +639,11 @@ async fn find_or_create_issue_and_grouping_inner(
         "#,
     )
     .bind(project_id)
-    .bind(issue.id)
+    .bind(issue_id)
     .bind(grouping_key)
     .bind(grouping_key_hash)
     .fetch_one(&mut **tx)
     .await?;
 
-    Ok((issue, grouping, true))
+    Ok(grouping)
 }
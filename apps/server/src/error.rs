@@ -1,6 +1,56 @@
-use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError, UrlencodedError};
+use actix_web::{http::StatusCode, web, HttpResponse, ResponseError};
 use serde::Serialize;
 
+use crate::config::BodyLimitConfig;
+use crate::middleware::request_id;
+
+/// Stable, machine-readable error codes returned in `error.code`
+///
+/// Codes never change meaning once released; new ones may be added.
+pub mod codes {
+    pub const NOT_FOUND: &str = "not_found";
+    pub const VALIDATION: &str = "validation.invalid";
+    pub const VALIDATION_REQUIRED: &str = "validation.required";
+    pub const VALIDATION_TOO_LONG: &str = "validation.too_long";
+    pub const VALIDATION_INVALID_FORMAT: &str = "validation.invalid_format";
+    pub const VALIDATION_EMAIL_TAKEN: &str = "validation.email_taken";
+    pub const CONFLICT: &str = "conflict";
+    pub const CONFLICT_NAME_TAKEN: &str = "conflict.name_taken";
+    pub const CONFLICT_SLUG_TAKEN: &str = "conflict.slug_taken";
+    pub const CONFLICT_IN_USE: &str = "conflict.in_use";
+    pub const UNAUTHORIZED: &str = "unauthorized";
+    pub const FORBIDDEN: &str = "forbidden";
+    pub const PAYLOAD_TOO_LARGE: &str = "payload_too_large";
+    pub const UNSUPPORTED_MEDIA_TYPE: &str = "unsupported_media_type";
+    pub const RATE_LIMITED: &str = "rate_limited";
+    pub const UNAVAILABLE: &str = "unavailable";
+    pub const DATABASE: &str = "internal.database";
+    pub const INTERNAL: &str = "internal";
+}
+
+/// Every code in [`codes`], for API specs and clients
+pub const ERROR_CODES: &[&str] = &[
+    codes::NOT_FOUND,
+    codes::VALIDATION,
+    codes::VALIDATION_REQUIRED,
+    codes::VALIDATION_TOO_LONG,
+    codes::VALIDATION_INVALID_FORMAT,
+    codes::VALIDATION_EMAIL_TAKEN,
+    codes::CONFLICT,
+    codes::CONFLICT_NAME_TAKEN,
+    codes::CONFLICT_SLUG_TAKEN,
+    codes::CONFLICT_IN_USE,
+    codes::UNAUTHORIZED,
+    codes::FORBIDDEN,
+    codes::PAYLOAD_TOO_LARGE,
+    codes::UNSUPPORTED_MEDIA_TYPE,
+    codes::RATE_LIMITED,
+    codes::UNAVAILABLE,
+    codes::DATABASE,
+    codes::INTERNAL,
+];
+
 /// JSON error response structure
 #[derive(Serialize)]
 pub struct ErrorResponse {
@@ -9,9 +59,21 @@ pub struct ErrorResponse {
 
 #[derive(Serialize)]
 pub struct ErrorDetail {
-    #[serde(rename = "type")]
-    pub error_type: String,
+    /// One of [`ERROR_CODES`]
+    pub code: &'static str,
     pub message: String,
+    /// Request field the error is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<&'static str>,
+    /// Seconds to wait before retrying (rate limits and unavailability only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+    /// Names of the resources that still use the one being deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependents: Option<Vec<String>>,
+    /// Id of the failed request, as in the `X-Request-Id` response header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 /// Application errors
@@ -23,15 +85,52 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// A request field failed validation
+    #[error("Validation error: {message}")]
+    InvalidField {
+        field: &'static str,
+        code: &'static str,
+        message: String,
+    },
+
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// A request field clashes with an existing resource
+    #[error("Conflict: {message}")]
+    FieldConflict {
+        field: &'static str,
+        code: &'static str,
+        message: String,
+    },
+
+    /// The resource is still used by others, named in `dependents`
+    #[error("Conflict: {message}")]
+    InUse {
+        message: String,
+        dependents: Vec<String>,
+    },
+
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Payload too large: {0}")]
     PayloadTooLarge(String),
 
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    /// Too many requests; holds the seconds until the limit resets
+    #[error("Rate limit exceeded, retry in {0}s")]
+    RateLimited(u64),
+
+    /// The service is paused; holds the seconds to wait before retrying
+    #[error("Service unavailable: {message}")]
+    Unavailable { message: String, retry_after: u64 },
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -39,40 +138,205 @@ pub enum AppError {
     Internal(String),
 }
 
+impl AppError {
+    /// Validation error about one request field
+    pub fn invalid_field(
+        field: &'static str,
+        code: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        AppError::InvalidField {
+            field,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Conflict caused by one request field
+    pub fn field_conflict(
+        field: &'static str,
+        code: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        AppError::FieldConflict {
+            field,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Attributes a generic validation error to a request field
+    ///
+    /// Other errors are returned unchanged.
+    pub fn for_field(self, field: &'static str) -> Self {
+        match self {
+            AppError::Validation(message) => AppError::InvalidField {
+                field,
+                code: codes::VALIDATION,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// The stable code sent as `error.code`
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => codes::NOT_FOUND,
+            AppError::Validation(_) => codes::VALIDATION,
+            AppError::InvalidField { code, .. } => code,
+            AppError::Conflict(_) => codes::CONFLICT,
+            AppError::FieldConflict { code, .. } => code,
+            AppError::InUse { .. } => codes::CONFLICT_IN_USE,
+            AppError::Unauthorized(_) => codes::UNAUTHORIZED,
+            AppError::Forbidden(_) => codes::FORBIDDEN,
+            AppError::PayloadTooLarge(_) => codes::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => codes::UNSUPPORTED_MEDIA_TYPE,
+            AppError::RateLimited(_) => codes::RATE_LIMITED,
+            AppError::Unavailable { .. } => codes::UNAVAILABLE,
+            AppError::Database(_) => codes::DATABASE,
+            AppError::Internal(_) => codes::INTERNAL,
+        }
+    }
+
+    /// The request field the error is about, if any
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            AppError::InvalidField { field, .. } | AppError::FieldConflict { field, .. } => {
+                Some(field)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl ResponseError for AppError {
     fn status_code(&self) -> StatusCode {
         match self {
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
-            AppError::Validation(_) => StatusCode::BAD_REQUEST,
-            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Validation(_) | AppError::InvalidField { .. } => StatusCode::BAD_REQUEST,
+            AppError::Conflict(_) | AppError::FieldConflict { .. } | AppError::InUse { .. } => {
+                StatusCode::CONFLICT
+            }
             AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
             AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Unavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
             AppError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        let error_type = match self {
-            AppError::NotFound(_) => "NotFound",
-            AppError::Validation(_) => "ValidationError",
-            AppError::Conflict(_) => "Conflict",
-            AppError::Unauthorized(_) => "Unauthorized",
-            AppError::PayloadTooLarge(_) => "PayloadTooLarge",
-            AppError::Database(_) => "DatabaseError",
-            AppError::Internal(_) => "InternalError",
+        let retry_after = match self {
+            AppError::RateLimited(seconds) => Some(*seconds),
+            AppError::Unavailable { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+
+        let dependents = match self {
+            AppError::InUse { dependents, .. } => Some(dependents.clone()),
+            _ => None,
         };
 
         let response = ErrorResponse {
             error: ErrorDetail {
-                error_type: error_type.to_string(),
+                code: self.code(),
                 message: self.to_string(),
+                field: self.field(),
+                retry_after,
+                dependents,
+                request_id: request_id::current(),
             },
         };
 
-        HttpResponse::build(self.status_code()).json(response)
+        let mut builder = HttpResponse::build(self.status_code());
+        if let Some(seconds) = retry_after {
+            builder.insert_header(("Retry-After", seconds.to_string()));
+        }
+        builder.json(response)
+    }
+}
+
+impl From<JsonPayloadError> for AppError {
+    fn from(err: JsonPayloadError) -> Self {
+        match err {
+            JsonPayloadError::OverflowKnownLength { .. } | JsonPayloadError::Overflow { .. } => {
+                AppError::PayloadTooLarge(err.to_string())
+            }
+            JsonPayloadError::ContentType => {
+                AppError::UnsupportedMediaType("Expected Content-Type: application/json".into())
+            }
+            JsonPayloadError::Deserialize(err) => {
+                AppError::Validation(format!("Invalid JSON body: {}", err))
+            }
+            err => AppError::Validation(err.to_string()),
+        }
+    }
+}
+
+impl From<UrlencodedError> for AppError {
+    fn from(err: UrlencodedError) -> Self {
+        match err {
+            UrlencodedError::Overflow { .. } => AppError::PayloadTooLarge(err.to_string()),
+            UrlencodedError::ContentType => AppError::UnsupportedMediaType(
+                "Expected Content-Type: application/x-www-form-urlencoded".into(),
+            ),
+            err => AppError::Validation(err.to_string()),
+        }
     }
 }
 
+impl From<QueryPayloadError> for AppError {
+    fn from(err: QueryPayloadError) -> Self {
+        match err {
+            QueryPayloadError::Deserialize(err) => {
+                AppError::Validation(format!("Invalid query string: {}", err))
+            }
+            err => AppError::Validation(err.to_string()),
+        }
+    }
+}
+
+impl From<PathError> for AppError {
+    fn from(err: PathError) -> Self {
+        // A path that doesn't parse names nothing that exists
+        match err {
+            PathError::Deserialize(err) => AppError::NotFound(format!("Invalid path: {}", err)),
+            err => AppError::NotFound(err.to_string()),
+        }
+    }
+}
+
+/// Extractor configs that answer malformed bodies, query strings and paths
+/// with the JSON error envelope instead of actix's plain-text errors
+///
+/// Every body gets the API's small limit; the ingest routes set their own,
+/// larger one for the raw bodies they read.
+pub fn configure_extractors(limits: BodyLimitConfig) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.app_data(
+            web::JsonConfig::default()
+                .limit(limits.api)
+                .error_handler(|err, _| AppError::from(err).into()),
+        )
+        .app_data(
+            web::FormConfig::default()
+                .limit(limits.api)
+                .error_handler(|err, _| AppError::from(err).into()),
+        )
+        .app_data(web::PayloadConfig::new(limits.api))
+        .app_data(web::QueryConfig::default().error_handler(|err, _| AppError::from(err).into()))
+        .app_data(web::PathConfig::default().error_handler(|err, _| AppError::from(err).into()));
+    }
+}
+
+/// Answers requests that match no route
+pub async fn not_found() -> AppResult<HttpResponse> {
+    Err(AppError::NotFound("No route matches this path".into()))
+}
+
 /// Result type alias for handlers
 pub type AppResult<T> = Result<T, AppError>;
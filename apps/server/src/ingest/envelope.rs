@@ -38,7 +38,7 @@ pub struct ItemHeaders {
     /// Payload length in bytes (optional)
     pub length: Option<usize>,
 
-    /// Content type of the payload
+    /// Content type of the payload (JSON or MessagePack for structured items)
     pub content_type: Option<String>,
 }
 
@@ -46,6 +46,7 @@ pub struct ItemHeaders {
 #[derive(Debug)]
 pub struct EnvelopeItem {
     pub headers: ItemHeaders,
+    /// Payload bytes, with structured items (events, sessions, ...) decoded to JSON
     pub payload: Vec<u8>,
 }
 
@@ -63,4 +64,15 @@ pub struct EventMetadata {
     pub project_id: i32,
     pub ingested_at: DateTime<Utc>,
     pub remote_addr: Option<String>,
+    /// File the event is spooled under when it isn't its event_id, e.g. an
+    /// update merged into an event that was already ingested
+    #[serde(default)]
+    pub spool_id: Option<String>,
+}
+
+impl EventMetadata {
+    /// Id of the event's file in the ingest directory
+    pub fn spool_id(&self) -> &str {
+        self.spool_id.as_deref().unwrap_or(&self.event_id)
+    }
 }
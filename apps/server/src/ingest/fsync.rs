@@ -0,0 +1,150 @@
+//! Durability of the files written to the ingest directory.
+//!
+//! `INGEST_FSYNC` picks when event files reach the disk: `always` syncs each
+//! file and its directory before the write returns, `batch` leaves that to a
+//! task running every `INGEST_FSYNC_INTERVAL_MS`, and `never` leaves it to
+//! the OS. On a crash, `batch` loses at most the events written since its
+//! last run; `never` loses whatever the OS hadn't flushed yet. A normal
+//! shutdown syncs everything pending in every mode.
+
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+
+use crate::config::{FsyncPolicy, IngestConfig};
+
+static WRITER: OnceLock<Arc<FsyncWriter>> = OnceLock::new();
+
+/// Sets the policy of the shared writer, starting the batch task if needed
+///
+/// Must run before the first event is stored; later calls are ignored.
+pub fn init(config: &IngestConfig) {
+    let writer = Arc::new(FsyncWriter::new(config.fsync));
+    if WRITER.set(writer.clone()).is_err() {
+        log::warn!("Ingest fsync policy already initialized");
+        return;
+    }
+
+    if config.fsync == FsyncPolicy::Batch {
+        log::info!(
+            "Ingest fsync policy batch, syncing every {}ms",
+            config.fsync_interval.as_millis()
+        );
+        writer.spawn_flusher(config.fsync_interval);
+    } else {
+        log::info!("Ingest fsync policy {}", config.fsync.as_str());
+    }
+}
+
+/// The shared writer, syncing every write if `init` was never called
+pub fn writer() -> Arc<FsyncWriter> {
+    WRITER
+        .get_or_init(|| Arc::new(FsyncWriter::new(FsyncPolicy::default())))
+        .clone()
+}
+
+/// Writes files with a [`FsyncPolicy`]
+pub struct FsyncWriter {
+    policy: FsyncPolicy,
+    /// Files written since the last batch sync
+    pending: Mutex<Vec<PathBuf>>,
+}
+
+impl FsyncWriter {
+    pub fn new(policy: FsyncPolicy) -> Self {
+        Self {
+            policy,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn policy(&self) -> FsyncPolicy {
+        self.policy
+    }
+
+    /// Creates or replaces the file at `path`, synced as the policy says
+    pub async fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut file = fs::File::create(path).await?;
+        file.write_all(contents).await?;
+        file.flush().await?;
+
+        match self.policy {
+            FsyncPolicy::Always => {
+                file.sync_all().await?;
+                if let Some(dir) = path.parent() {
+                    sync_dir(dir).await?;
+                }
+            }
+            FsyncPolicy::Batch => self.pending.lock().unwrap().push(path.to_path_buf()),
+            FsyncPolicy::Never => {}
+        }
+        Ok(())
+    }
+
+    /// Number of files waiting for the batch sync
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Syncs the files written since the last call, and their directories
+    ///
+    /// Files already digested and deleted are skipped. Returns the number of
+    /// files synced; failures are logged, as there's no request to fail.
+    pub async fn flush(&self) -> usize {
+        let paths = std::mem::take(&mut *self.pending.lock().unwrap());
+        let mut dirs = BTreeSet::new();
+        let mut synced = 0;
+
+        for path in paths {
+            let result = match fs::File::open(&path).await {
+                Ok(file) => file.sync_all().await,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => synced += 1,
+                Err(e) => log::error!("Failed to sync {}: {}", path.display(), e),
+            }
+            if let Some(dir) = path.parent() {
+                dirs.insert(dir.to_path_buf());
+            }
+        }
+
+        for dir in dirs {
+            if let Err(e) = sync_dir(&dir).await {
+                log::error!("Failed to sync {}: {}", dir.display(), e);
+            }
+        }
+        synced
+    }
+
+    /// Runs [`Self::flush`] every `interval`
+    pub fn spawn_flusher(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.flush().await;
+            }
+        })
+    }
+}
+
+/// Syncs a directory, so the files created in it survive a crash
+#[cfg(unix)]
+async fn sync_dir(dir: &Path) -> io::Result<()> {
+    fs::File::open(dir).await?.sync_all().await
+}
+
+/// Directories can't be opened for syncing on this platform
+#[cfg(not(unix))]
+async fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! Ingest maintenance mode: pausing ingestion without failing the SDKs.
+//!
+//! With `INGEST_MAINTENANCE=drop` the ingest endpoints validate events and
+//! answer as usual but store nothing, so SDKs don't retry or buffer them.
+//! With `reject` they answer 503 and SDKs back off.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Events discarded while ingestion is in drop mode
+static DROPPED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Counts events discarded in drop mode
+pub fn record_dropped(count: u64) {
+    DROPPED_EVENTS.fetch_add(count, Ordering::Relaxed);
+}
+
+/// Events discarded in drop mode since startup
+pub fn dropped_count() -> u64 {
+    DROPPED_EVENTS.load(Ordering::Relaxed)
+}
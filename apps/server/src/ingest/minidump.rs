@@ -0,0 +1,399 @@
+//! Minidump header parsing for native crash reports.
+//!
+//! Only the stream directory is read: the exception, system info and module
+//! list streams. There is no stackwalking; the synthetic event carries the
+//! exception with the crash address as its only frame, and the loaded
+//! modules. It groups by exception code and crashing module.
+
+use serde_json::{json, Value};
+
+use crate::error::{AppError, AppResult};
+
+/// Maximum minidump size (100MB)
+pub const MAX_MINIDUMP_SIZE: usize = 100 * 1024 * 1024;
+
+/// "MDMP" in little endian
+const MINIDUMP_SIGNATURE: u32 = 0x504d_444d;
+
+const MODULE_LIST_STREAM: u32 = 4;
+const EXCEPTION_STREAM: u32 = 6;
+const SYSTEM_INFO_STREAM: u32 = 7;
+
+/// Size of a MINIDUMP_MODULE entry
+const MODULE_SIZE: usize = 108;
+
+/// CodeView record signatures ("RSDS" for PDB 7.0, "BpEL" for Breakpad ELF build ids)
+const CV_SIGNATURE_RSDS: u32 = 0x5344_5352;
+const CV_SIGNATURE_ELF: u32 = 0x4c45_7042;
+
+/// Upper bound on modules read, guards against corrupt counts
+const MAX_MODULES: usize = 4096;
+
+/// Operating system of the crashed process (MINIDUMP_SYSTEM_INFO.PlatformId)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinidumpOs {
+    Windows,
+    MacOs,
+    Ios,
+    Linux,
+    Android,
+}
+
+impl MinidumpOs {
+    fn from_platform_id(id: u32) -> Option<Self> {
+        match id {
+            2 => Some(MinidumpOs::Windows),
+            0x8101 => Some(MinidumpOs::MacOs),
+            0x8102 => Some(MinidumpOs::Ios),
+            0x8201 => Some(MinidumpOs::Linux),
+            0x8203 => Some(MinidumpOs::Android),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MinidumpOs::Windows => "Windows",
+            MinidumpOs::MacOs => "macOS",
+            MinidumpOs::Ios => "iOS",
+            MinidumpOs::Linux => "Linux",
+            MinidumpOs::Android => "Android",
+        }
+    }
+
+    /// Debug image type used in `debug_meta.images`
+    fn image_type(&self) -> &'static str {
+        match self {
+            MinidumpOs::Windows => "pe",
+            MinidumpOs::MacOs | MinidumpOs::Ios => "macho",
+            MinidumpOs::Linux | MinidumpOs::Android => "elf",
+        }
+    }
+}
+
+/// The exception that crashed the process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinidumpException {
+    pub thread_id: u32,
+    pub code: u32,
+    pub address: u64,
+}
+
+/// A module loaded in the crashed process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinidumpModule {
+    pub base_address: u64,
+    pub size: u32,
+    pub name: String,
+    pub debug_id: Option<String>,
+}
+
+impl MinidumpModule {
+    fn contains(&self, address: u64) -> bool {
+        address >= self.base_address && address - self.base_address < self.size as u64
+    }
+
+    /// File name without its directory
+    pub fn file_name(&self) -> &str {
+        self.name.rsplit(['/', '\\']).next().unwrap_or(&self.name)
+    }
+}
+
+/// What the minidump header tells about a crash
+#[derive(Debug, Clone, Default)]
+pub struct MinidumpInfo {
+    pub os: Option<MinidumpOs>,
+    pub exception: Option<MinidumpException>,
+    pub modules: Vec<MinidumpModule>,
+}
+
+impl MinidumpInfo {
+    /// Module the crashing instruction belongs to
+    pub fn crashing_module(&self) -> Option<&MinidumpModule> {
+        let address = self.exception.as_ref()?.address;
+        self.modules.iter().find(|m| m.contains(address))
+    }
+
+    /// Readable name of the exception code, e.g. "SIGSEGV" or "EXCEPTION_ACCESS_VIOLATION"
+    pub fn exception_name(&self) -> Option<String> {
+        let code = self.exception.as_ref()?.code;
+        let name = match self.os {
+            Some(MinidumpOs::Windows) => windows_exception_name(code),
+            Some(MinidumpOs::MacOs | MinidumpOs::Ios) => mac_exception_name(code),
+            Some(MinidumpOs::Linux | MinidumpOs::Android) => signal_name(code),
+            None => None,
+        };
+        Some(name.map_or_else(|| format!("0x{:08x}", code), str::to_string))
+    }
+}
+
+/// Parses the stream directory of a minidump
+pub fn parse_minidump(data: &[u8]) -> AppResult<MinidumpInfo> {
+    let reader = Reader(data);
+
+    if reader.u32(0)? != MINIDUMP_SIGNATURE {
+        return Err(invalid("missing MDMP signature"));
+    }
+    let stream_count = reader.u32(8)? as usize;
+    let directory = reader.u32(12)? as usize;
+
+    let mut info = MinidumpInfo::default();
+
+    for i in 0..stream_count {
+        let entry = directory + i * 12;
+        let stream_type = reader.u32(entry)?;
+        let rva = reader.u32(entry + 8)? as usize;
+
+        match stream_type {
+            EXCEPTION_STREAM => {
+                // ThreadId, alignment, then MINIDUMP_EXCEPTION
+                info.exception = Some(MinidumpException {
+                    thread_id: reader.u32(rva)?,
+                    code: reader.u32(rva + 8)?,
+                    address: reader.u64(rva + 24)?,
+                });
+            }
+            SYSTEM_INFO_STREAM => {
+                info.os = MinidumpOs::from_platform_id(reader.u32(rva + 20)?);
+            }
+            MODULE_LIST_STREAM => {
+                let count = reader.u32(rva)? as usize;
+                if count > MAX_MODULES {
+                    return Err(invalid("too many modules"));
+                }
+                for m in 0..count {
+                    info.modules.push(reader.module(rva + 4 + m * MODULE_SIZE)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(info)
+}
+
+/// Builds the event stored for a minidump upload
+///
+/// `sentry` is the optional event JSON sent along with the minidump; its
+/// fields are kept, and a custom `fingerprint` in it wins over the default
+/// grouping by exception code and crashing module.
+pub fn build_event(info: &MinidumpInfo, event_id: &str, sentry: Option<Value>) -> Value {
+    let mut event = match sentry {
+        Some(Value::Object(map)) => Value::Object(map),
+        _ => json!({}),
+    };
+
+    let exception_name = info
+        .exception_name()
+        .unwrap_or_else(|| "Minidump".to_string());
+    let crashing_module = info.crashing_module().map(|m| m.file_name().to_string());
+
+    let value = match &crashing_module {
+        Some(module) => format!("Fatal Error: {} in {}", exception_name, module),
+        None => format!("Fatal Error: {}", exception_name),
+    };
+
+    let mut exception = json!({
+        "type": exception_name,
+        "value": value,
+        "mechanism": {
+            "type": "minidump",
+            "handled": false,
+            "synthetic": true,
+        },
+    });
+    if let Some(e) = &info.exception {
+        exception["thread_id"] = json!(e.thread_id);
+        exception["mechanism"]["meta"] = json!({
+            "exception_code": e.code,
+            "address": format!("0x{:x}", e.address),
+        });
+        // Symbolicated like any native frame once debug files are uploaded
+        exception["stacktrace"] = json!({
+            "frames": [{ "instruction_addr": format!("0x{:x}", e.address) }],
+        });
+    }
+
+    event["event_id"] = json!(event_id);
+    event["platform"] = json!("native");
+    event["level"] = json!("fatal");
+    event["exception"] = json!({ "values": [exception] });
+
+    if event.get("timestamp").is_none() {
+        event["timestamp"] = json!(chrono::Utc::now().timestamp() as f64);
+    }
+    if event.get("fingerprint").is_none() {
+        event["fingerprint"] = json!([
+            "minidump",
+            exception_name,
+            crashing_module.as_deref().unwrap_or("<unknown>"),
+        ]);
+    }
+    if let Some(os) = info.os {
+        let contexts = event
+            .as_object_mut()
+            .expect("event is an object")
+            .entry("contexts")
+            .or_insert_with(|| json!({}));
+        if let Some(contexts) = contexts.as_object_mut() {
+            contexts
+                .entry("os")
+                .or_insert_with(|| json!({ "name": os.name() }));
+        }
+    }
+
+    let image_type = info.os.map_or("symbolic", |os| os.image_type());
+    let images: Vec<Value> = info
+        .modules
+        .iter()
+        .map(|m| {
+            let mut image = json!({
+                "type": image_type,
+                "code_file": m.name,
+                "image_addr": format!("0x{:x}", m.base_address),
+                "image_size": m.size,
+            });
+            if let Some(debug_id) = &m.debug_id {
+                image["debug_id"] = json!(debug_id);
+            }
+            image
+        })
+        .collect();
+    event["debug_meta"] = json!({ "images": images });
+
+    event
+}
+
+fn invalid(reason: &str) -> AppError {
+    AppError::Validation(format!("Invalid minidump: {}", reason))
+}
+
+/// Bounds-checked little endian reads
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn bytes(&self, offset: usize, len: usize) -> AppResult<&[u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.0.get(offset..end))
+            .ok_or_else(|| invalid("truncated data"))
+    }
+
+    fn u32(&self, offset: usize) -> AppResult<u32> {
+        let bytes = self.bytes(offset, 4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("4 bytes")))
+    }
+
+    fn u64(&self, offset: usize) -> AppResult<u64> {
+        let bytes = self.bytes(offset, 8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    /// Reads a MINIDUMP_STRING (byte length + UTF-16LE)
+    fn string(&self, offset: usize) -> AppResult<String> {
+        let len = self.u32(offset)? as usize;
+        let units: Vec<u16> = self
+            .bytes(offset + 4, len)?
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    /// Reads a MINIDUMP_MODULE entry
+    fn module(&self, offset: usize) -> AppResult<MinidumpModule> {
+        let cv_size = self.u32(offset + 76)? as usize;
+        let cv_rva = self.u32(offset + 80)? as usize;
+
+        Ok(MinidumpModule {
+            base_address: self.u64(offset)?,
+            size: self.u32(offset + 8)?,
+            name: self.string(self.u32(offset + 20)? as usize)?,
+            debug_id: self.debug_id(cv_rva, cv_size),
+        })
+    }
+
+    /// Debug ID from a CodeView record, if it is one we understand
+    fn debug_id(&self, rva: usize, size: usize) -> Option<String> {
+        if size < 20 {
+            return None;
+        }
+        match self.u32(rva).ok()? {
+            CV_SIGNATURE_RSDS if size >= 24 => {
+                let guid = self.bytes(rva + 4, 16).ok()?;
+                let age = self.u32(rva + 20).ok()?;
+                Some(format_debug_id(guid, age))
+            }
+            CV_SIGNATURE_ELF => {
+                // The first 16 bytes of the build id, read like a GUID
+                let build_id = self.bytes(rva + 4, 16).ok()?;
+                Some(format_debug_id(build_id, 0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Formats GUID bytes (mixed endian) and age as a Sentry debug ID
+fn format_debug_id(guid: &[u8], age: u32) -> String {
+    let data1 = u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]);
+    let data2 = u16::from_le_bytes([guid[4], guid[5]]);
+    let data3 = u16::from_le_bytes([guid[6], guid[7]]);
+    let id = format!(
+        "{:08x}-{:04x}-{:04x}-{}-{}",
+        data1,
+        data2,
+        data3,
+        hex::encode(&guid[8..10]),
+        hex::encode(&guid[10..16])
+    );
+
+    if age == 0 {
+        id
+    } else {
+        format!("{}-{:x}", id, age)
+    }
+}
+
+fn windows_exception_name(code: u32) -> Option<&'static str> {
+    Some(match code {
+        0x8000_0003 => "EXCEPTION_BREAKPOINT",
+        0xc000_0005 => "EXCEPTION_ACCESS_VIOLATION",
+        0xc000_001d => "EXCEPTION_ILLEGAL_INSTRUCTION",
+        0xc000_008e => "EXCEPTION_FLT_DIVIDE_BY_ZERO",
+        0xc000_0094 => "EXCEPTION_INT_DIVIDE_BY_ZERO",
+        0xc000_00fd => "EXCEPTION_STACK_OVERFLOW",
+        0xc000_0409 => "STATUS_STACK_BUFFER_OVERRUN",
+        0xc000_0374 => "STATUS_HEAP_CORRUPTION",
+        0xe06d_7363 => "Unhandled C++ Exception",
+        _ => return None,
+    })
+}
+
+fn mac_exception_name(code: u32) -> Option<&'static str> {
+    Some(match code {
+        1 => "EXC_BAD_ACCESS",
+        2 => "EXC_BAD_INSTRUCTION",
+        3 => "EXC_ARITHMETIC",
+        5 => "EXC_SOFTWARE",
+        6 => "EXC_BREAKPOINT",
+        10 => "EXC_CRASH",
+        11 => "EXC_RESOURCE",
+        12 => "EXC_GUARD",
+        _ => return None,
+    })
+}
+
+fn signal_name(code: u32) -> Option<&'static str> {
+    Some(match code {
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        7 => "SIGBUS",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        _ => return None,
+    })
+}
@@ -1,9 +1,23 @@
 pub mod decompression;
 pub mod envelope;
+pub mod fsync;
+pub mod maintenance;
+pub mod minidump;
+pub mod origin;
 pub mod parser;
+pub mod remote_addr;
 pub mod storage;
+pub mod tunnel;
+pub mod writer;
 
 pub use decompression::{decompress_body, get_content_encoding};
 pub use envelope::EventMetadata;
-pub use parser::EnvelopeParser;
-pub use storage::{delete_event, get_ingest_dir, read_event, store_event};
+pub use minidump::{build_event, parse_minidump, MinidumpInfo};
+pub use parser::{EnvelopeParser, PayloadFormat};
+pub use remote_addr::remote_addr;
+pub use storage::{
+    count_events, delete_event, delete_minidump, get_ingest_dir, list_minidumps, read_event,
+    store_event, store_minidump, StoredMinidump,
+};
+pub use tunnel::TunnelDsn;
+pub use writer::{IngestWriter, SpoolJob};
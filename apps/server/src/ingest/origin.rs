@@ -0,0 +1,192 @@
+//! Allowed domains: the origins a project accepts browser events from.
+//!
+//! A project with a non-empty `allowed_domains` list only accepts browser
+//! events whose `Origin` (or `Referer`) header and `request.url` host match
+//! one of its patterns. Server-side SDKs send neither an `Origin` header nor
+//! the `javascript` platform and are never checked, since their
+//! `request.url` is the server's own host.
+//!
+//! Patterns are hostnames where `*` matches any run of characters, e.g.
+//! `*.example.com`, which also matches `example.com` itself. A pattern with
+//! a port, e.g. `localhost:3000`, only matches that port.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use actix_web::{http::header, HttpRequest};
+use serde_json::Value;
+
+use crate::error::{codes, AppError, AppResult};
+
+/// Events dropped because their origin isn't allowed
+static FILTERED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Max length of a pattern, the longest a hostname can be
+const MAX_PATTERN_LENGTH: usize = 253;
+
+/// Counts an event dropped by the allowed domains check
+pub fn record_filtered() {
+    FILTERED_EVENTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Events dropped by the allowed domains check since startup
+pub fn filtered_count() -> u64 {
+    FILTERED_EVENTS.load(Ordering::Relaxed)
+}
+
+/// Host (with its port, if any) of the `Origin` header, or of the `Referer`
+/// when there's no usable origin
+///
+/// Sandboxed pages send `Origin: null`, which is ignored.
+pub fn request_origin(req: &HttpRequest) -> Option<String> {
+    [header::ORIGIN, header::REFERER].iter().find_map(|name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| *value != "null")
+            .and_then(url_host)
+    })
+}
+
+/// Host of the event's `request.url`
+pub fn event_origin(event: &Value) -> Option<String> {
+    event
+        .pointer("/request/url")
+        .and_then(|url| url.as_str())
+        .and_then(url_host)
+}
+
+/// Host and explicit port of a URL, lowercased
+fn url_host(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    })
+}
+
+/// Whether an event may be ingested for a project with `allowed` domains
+///
+/// `origin` is the request's origin, see [`request_origin`]. Every host
+/// known for a browser event must match; one with no host at all is
+/// accepted, as there's nothing to check it against.
+pub fn is_event_allowed(allowed: &[String], origin: Option<&str>, event: &Value) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+    let is_browser = origin.is_some() || event["platform"].as_str() == Some("javascript");
+    if !is_browser {
+        return true;
+    }
+
+    let event_host = event_origin(event);
+    origin
+        .into_iter()
+        .chain(event_host.as_deref())
+        .all(|host| is_host_allowed(allowed, host))
+}
+
+/// Whether `host` (optionally with `:port`) matches one of the patterns
+pub fn is_host_allowed(allowed: &[String], host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let bare_host = host.split(':').next().unwrap_or(&host);
+    allowed.iter().any(|pattern| {
+        if pattern.contains(':') {
+            matches_domain(pattern, &host)
+        } else {
+            matches_domain(pattern, bare_host)
+        }
+    })
+}
+
+/// Matches a hostname against a pattern where `*` is any run of characters
+///
+/// A leading `*.` also matches the bare domain.
+pub fn matches_domain(pattern: &str, host: &str) -> bool {
+    if let Some(domain) = pattern.strip_prefix("*.") {
+        if host == domain {
+            return true;
+        }
+    }
+    glob_match(pattern.as_bytes(), host.as_bytes())
+}
+
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text it has consumed up to
+    let mut backtrack = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, consumed)) = backtrack {
+            p = star + 1;
+            t = consumed + 1;
+            backtrack = Some((star, consumed + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Normalizes allowed domain patterns for storage
+///
+/// Patterns are trimmed, lowercased and deduplicated. A pattern matching
+/// every origin, like `*`, is rejected unless `confirm_wildcard` is set.
+pub fn validate_allowed_domains(
+    patterns: &[String],
+    confirm_wildcard: bool,
+) -> AppResult<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::with_capacity(patterns.len());
+    for pattern in patterns {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        if pattern.is_empty() {
+            return Err(AppError::invalid_field(
+                "allowed_domains",
+                codes::VALIDATION_REQUIRED,
+                "Allowed domains cannot be empty",
+            ));
+        }
+        if pattern.len() > MAX_PATTERN_LENGTH {
+            return Err(AppError::invalid_field(
+                "allowed_domains",
+                codes::VALIDATION_TOO_LONG,
+                format!(
+                    "Allowed domains cannot exceed {} characters",
+                    MAX_PATTERN_LENGTH
+                ),
+            ));
+        }
+        if pattern.contains("://")
+            || pattern.contains('/')
+            || pattern.chars().any(char::is_whitespace)
+        {
+            return Err(AppError::invalid_field(
+                "allowed_domains",
+                codes::VALIDATION_INVALID_FORMAT,
+                format!(
+                    "'{}' must be a hostname like 'example.com' or '*.example.com', without scheme or path",
+                    pattern
+                ),
+            ));
+        }
+        if !confirm_wildcard && pattern.chars().all(|c| c == '*' || c == '.') {
+            return Err(AppError::invalid_field(
+                "allowed_domains",
+                codes::VALIDATION,
+                format!(
+                    "'{}' allows every origin; set confirm_wildcard to accept it",
+                    pattern
+                ),
+            ));
+        }
+        if !normalized.contains(&pattern) {
+            normalized.push(pattern);
+        }
+    }
+    Ok(normalized)
+}
@@ -4,10 +4,63 @@ use crate::ingest::envelope::{EnvelopeHeaders, EnvelopeItem, ItemHeaders, Parsed
 /// Maximum header size (8KB)
 const MAX_HEADER_SIZE: usize = 8 * 1024;
 
+/// UTF-8 byte order mark, which some clients prepend to the envelope
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
 /// Maximum event size (1MB)
-const MAX_EVENT_SIZE: usize = 1024 * 1024;
+pub const MAX_EVENT_SIZE: usize = 1024 * 1024;
+
+/// Item types whose payload is a structured document, sent as JSON or MessagePack
+const STRUCTURED_ITEM_TYPES: &[&str] = &[
+    "event",
+    "transaction",
+    "session",
+    "sessions",
+    "user_report",
+    "client_report",
+    "check_in",
+];
+
+/// Encoding of a structured item payload, from its `content_type` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadFormat {
+    Json,
+    MessagePack,
+}
+
+impl PayloadFormat {
+    /// Detects the format from a content type, JSON when none is given
+    ///
+    /// Parameters such as `; charset=utf-8` are ignored.
+    pub fn from_content_type(content_type: Option<&str>) -> AppResult<Self> {
+        let Some(content_type) = content_type else {
+            return Ok(PayloadFormat::Json);
+        };
+
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "application/json" => Ok(PayloadFormat::Json),
+            "application/x-msgpack" | "application/msgpack" | "application/vnd.msgpack" => {
+                Ok(PayloadFormat::MessagePack)
+            }
+            _ => Err(AppError::Validation(format!(
+                "Unsupported item content_type '{}', expected application/json or application/x-msgpack",
+                content_type
+            ))),
+        }
+    }
+}
 
 /// Sentry envelope parser
+///
+/// Lines may end in `\n` or `\r\n`, and a leading UTF-8 byte order mark is
+/// ignored. Parse errors give the byte offset into the envelope where
+/// parsing failed.
 pub struct EnvelopeParser<'a> {
     data: &'a [u8],
     position: usize,
@@ -20,6 +73,10 @@ impl<'a> EnvelopeParser<'a> {
 
     /// Parses the complete envelope
     pub fn parse(&mut self) -> AppResult<ParsedEnvelope> {
+        if self.data[self.position..].starts_with(UTF8_BOM) {
+            self.position += UTF8_BOM.len();
+        }
+
         // 1. Parse envelope headers (first line)
         let headers = self.parse_envelope_headers()?;
 
@@ -35,86 +92,145 @@ impl<'a> EnvelopeParser<'a> {
     }
 
     fn parse_envelope_headers(&mut self) -> AppResult<EnvelopeHeaders> {
-        let line = self.read_line(MAX_HEADER_SIZE)?;
+        let start = self.position;
+        let line = self.read_header_line()?;
 
         if line.is_empty() {
             return Err(AppError::Validation("Empty envelope headers".to_string()));
         }
 
-        serde_json::from_slice(&line)
-            .map_err(|e| AppError::Validation(format!("Invalid envelope headers JSON: {}", e)))
+        serde_json::from_slice(line).map_err(|e| {
+            AppError::Validation(format!(
+                "Invalid envelope headers JSON at byte {}: {}",
+                json_error_position(start, &e),
+                e
+            ))
+        })
     }
 
     fn parse_item(&mut self) -> AppResult<Option<EnvelopeItem>> {
         // Read item headers
-        let header_line = self.read_line(MAX_HEADER_SIZE)?;
+        let header_start = self.position;
+        let header_line = self.read_header_line()?;
 
         if header_line.is_empty() {
             return Ok(None);
         }
 
-        let headers: ItemHeaders = serde_json::from_slice(&header_line)
-            .map_err(|e| AppError::Validation(format!("Invalid item headers JSON: {}", e)))?;
+        let headers: ItemHeaders = serde_json::from_slice(header_line).map_err(|e| {
+            AppError::Validation(format!(
+                "Invalid item headers JSON at byte {}: {}",
+                json_error_position(header_start, &e),
+                e
+            ))
+        })?;
 
         // Read payload
+        let payload_start = self.position;
         let payload = if let Some(length) = headers.length {
             // Explicit length
             if length > MAX_EVENT_SIZE {
                 return Err(AppError::PayloadTooLarge(format!(
-                    "Item payload exceeds {} bytes",
-                    MAX_EVENT_SIZE
+                    "Item payload at byte {} exceeds {} bytes",
+                    payload_start, MAX_EVENT_SIZE
                 )));
             }
             let payload = self.read_bytes(length)?;
 
-            // Consume newline after payload (if exists)
-            self.skip_newline();
+            // The payload must end at a newline (or the end of the envelope);
+            // anything else means the length is wrong and the next item's
+            // boundary can't be trusted
+            if !self.at_eof() && !self.skip_newline() {
+                return Err(AppError::Validation(format!(
+                    "Item length {} doesn't match its payload at byte {}",
+                    length, self.position
+                )));
+            }
 
             payload
         } else {
             // Read until newline
-            self.read_line(MAX_EVENT_SIZE)?
+            self.read_line(MAX_EVENT_SIZE).ok_or_else(|| {
+                AppError::PayloadTooLarge(format!(
+                    "Item payload at byte {} exceeds {} bytes",
+                    payload_start, MAX_EVENT_SIZE
+                ))
+            })?
+        };
+
+        // Other item types (attachments, minidumps, ...) are kept as raw
+        // bytes, whatever their encoding
+        let payload = if STRUCTURED_ITEM_TYPES.contains(&headers.item_type.as_str()) {
+            decode_payload(&headers, payload, payload_start)?
+        } else {
+            payload.to_vec()
         };
 
         Ok(Some(EnvelopeItem { headers, payload }))
     }
 
-    fn read_line(&mut self, max_size: usize) -> AppResult<Vec<u8>> {
+    /// Reads an envelope or item headers line
+    ///
+    /// Headers are small, so an overlong line is a malformed envelope (400)
+    /// rather than an oversized one.
+    fn read_header_line(&mut self) -> AppResult<&'a [u8]> {
         let start = self.position;
-        let mut end = self.position;
+        self.read_line(MAX_HEADER_SIZE).ok_or_else(|| {
+            AppError::Validation(format!(
+                "Header line at byte {} exceeds {} bytes",
+                start, MAX_HEADER_SIZE
+            ))
+        })
+    }
 
-        while end < self.data.len() && self.data[end] != b'\n' {
-            end += 1;
-            if end - start > max_size {
-                return Err(AppError::PayloadTooLarge(format!(
-                    "Line exceeds {} bytes",
-                    max_size
-                )));
-            }
-        }
+    /// Reads up to the next newline, without the `\n` or `\r\n`
+    ///
+    /// Returns None if the line is longer than `max_size`.
+    fn read_line(&mut self, max_size: usize) -> Option<&'a [u8]> {
+        let data = self.data;
+        let rest = &data[self.position..];
+        let (line, consumed) = match rest.iter().position(|&b| b == b'\n') {
+            Some(newline) => (&rest[..newline], newline + 1),
+            None => (rest, rest.len()),
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
 
-        let line = self.data[start..end].to_vec();
-        self.position = if end < self.data.len() { end + 1 } else { end };
+        if line.len() > max_size {
+            return None;
+        }
 
-        Ok(line)
+        self.position += consumed;
+        Some(line)
     }
 
-    fn read_bytes(&mut self, length: usize) -> AppResult<Vec<u8>> {
-        if self.position + length > self.data.len() {
-            return Err(AppError::Validation(
-                "Unexpected EOF while reading item payload".to_string(),
-            ));
+    fn read_bytes(&mut self, length: usize) -> AppResult<&'a [u8]> {
+        let data = self.data;
+        let remaining = data.len() - self.position;
+        if length > remaining {
+            return Err(AppError::Validation(format!(
+                "Unexpected EOF while reading item payload at byte {}: expected {} bytes, found {}",
+                self.position, length, remaining
+            )));
         }
 
-        let bytes = self.data[self.position..self.position + length].to_vec();
+        let bytes = &data[self.position..self.position + length];
         self.position += length;
 
         Ok(bytes)
     }
 
-    fn skip_newline(&mut self) {
-        if self.position < self.data.len() && self.data[self.position] == b'\n' {
+    /// Consumes a `\n` or `\r\n` at the current position, returning whether
+    /// there was one
+    fn skip_newline(&mut self) -> bool {
+        let rest = &self.data[self.position..];
+        if rest.starts_with(b"\n") {
             self.position += 1;
+            true
+        } else if rest.starts_with(b"\r\n") {
+            self.position += 2;
+            true
+        } else {
+            false
         }
     }
 
@@ -122,3 +238,39 @@ impl<'a> EnvelopeParser<'a> {
         self.position >= self.data.len()
     }
 }
+
+/// Byte offset into the envelope of a JSON error in a line starting at
+/// `line_start`
+fn json_error_position(line_start: usize, error: &serde_json::Error) -> usize {
+    // Header lines are single-line JSON, and serde_json counts columns in bytes
+    line_start + error.column().saturating_sub(1)
+}
+
+/// Converts a structured item payload to JSON, so later stages only see JSON
+///
+/// JSON payloads are passed through unchanged, apart from a leading byte
+/// order mark; they're validated where used.
+fn decode_payload(headers: &ItemHeaders, payload: &[u8], position: usize) -> AppResult<Vec<u8>> {
+    match PayloadFormat::from_content_type(headers.content_type.as_deref())? {
+        PayloadFormat::Json => Ok(payload.strip_prefix(UTF8_BOM).unwrap_or(payload).to_vec()),
+        PayloadFormat::MessagePack => {
+            let value: serde_json::Value = rmp_serde::from_slice(payload).map_err(|e| {
+                AppError::Validation(format!(
+                    "Invalid MessagePack {} payload at byte {}: {}",
+                    headers.item_type, position, e
+                ))
+            })?;
+            let json = serde_json::to_vec(&value)
+                .map_err(|e| AppError::Internal(format!("Failed to encode payload: {}", e)))?;
+
+            if json.len() > MAX_EVENT_SIZE {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "Item payload at byte {} exceeds {} bytes",
+                    position, MAX_EVENT_SIZE
+                )));
+            }
+
+            Ok(json)
+        }
+    }
+}
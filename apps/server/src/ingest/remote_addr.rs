@@ -0,0 +1,58 @@
+//! Client IP resolution.
+//!
+//! By default the socket peer address is used and forwarded headers are
+//! ignored, since any client can set them. With `TRUST_PROXY_HEADERS`
+//! enabled, `X-Forwarded-For` is read from the right, skipping proxy hops on
+//! loopback and private networks; the first other address is the client.
+
+use actix_web::HttpRequest;
+use std::net::{IpAddr, SocketAddr};
+
+/// Resolves the client IP of a request
+pub fn remote_addr(req: &HttpRequest, trust_proxy_headers: bool) -> Option<String> {
+    if trust_proxy_headers {
+        let forwarded = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|value| value.to_str().ok())
+            .and_then(client_ip_from_forwarded_for);
+        if let Some(ip) = forwarded {
+            return Some(ip.to_string());
+        }
+    }
+
+    req.peer_addr().map(|addr| addr.ip().to_string())
+}
+
+/// Picks the client from an `X-Forwarded-For` value
+///
+/// Returns the rightmost address outside loopback and private networks, or
+/// the leftmost address when every hop is internal.
+pub fn client_ip_from_forwarded_for(value: &str) -> Option<IpAddr> {
+    let hops: Vec<IpAddr> = value.split(',').filter_map(parse_hop).collect();
+
+    hops.iter()
+        .rev()
+        .find(|ip| !is_internal(ip))
+        .or_else(|| hops.first())
+        .copied()
+}
+
+/// Parses a hop, which some proxies write with a port
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop = hop.trim();
+    hop.parse::<IpAddr>()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// Loopback, private and link-local addresses, where proxies usually run
+fn is_internal(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal(&IpAddr::V4(ip)),
+            None => ip.is_loopback() || ip.is_unique_local() || ip.is_unicast_link_local(),
+        },
+    }
+}
@@ -1,12 +1,21 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio::fs;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
+use crate::ingest::fsync;
 
 /// Default base directory for pending events
 const DEFAULT_INGEST_DIR: &str = "/tmp/rustrak/ingest";
 
+/// Magic bytes at the start of gzip data
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
 /// Gets the file path for an event_id
 pub fn get_event_path(base_dir: &Path, event_id: &str) -> AppResult<PathBuf> {
     // Validate that event_id is a valid UUID (security)
@@ -19,8 +28,15 @@ pub fn get_event_path(base_dir: &Path, event_id: &str) -> AppResult<PathBuf> {
     Ok(base_dir.join(filename))
 }
 
-/// Saves the event to the filesystem
-pub async fn store_event(base_dir: &Path, event_id: &str, event_data: &[u8]) -> AppResult<PathBuf> {
+/// Saves the event to the filesystem, gzipped if `compress` is set
+///
+/// The file is synced to disk as `INGEST_FSYNC` says, see [`fsync`].
+pub async fn store_event(
+    base_dir: &Path,
+    event_id: &str,
+    event_data: &[u8],
+    compress: bool,
+) -> AppResult<PathBuf> {
     // Create directory if it doesn't exist
     fs::create_dir_all(base_dir)
         .await
@@ -28,20 +44,173 @@ pub async fn store_event(base_dir: &Path, event_id: &str, event_data: &[u8]) ->
 
     let path = get_event_path(base_dir, event_id)?;
 
-    fs::write(&path, event_data)
+    let compressed;
+    let contents = if compress {
+        compressed = gzip(event_data)?;
+        compressed.as_slice()
+    } else {
+        event_data
+    };
+
+    fsync::writer()
+        .write(&path, contents)
         .await
         .map_err(|e| AppError::Internal(format!("Failed to write event file: {}", e)))?;
 
     Ok(path)
 }
 
+fn gzip(data: &[u8]) -> AppResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| AppError::Internal(format!("Failed to compress event: {}", e)))
+}
+
+/// Gets the file path for the minidump of an event_id
+///
+/// Minidumps live in a `minidumps/{project_id}` subdirectory and outlive the
+/// event file; the retention job deletes them along with the event's payload.
+pub fn get_minidump_path(base_dir: &Path, project_id: i32, event_id: &str) -> AppResult<PathBuf> {
+    let uuid = Uuid::parse_str(event_id)
+        .map_err(|_| AppError::Validation("Invalid event_id format".to_string()))?;
+
+    Ok(base_dir
+        .join("minidumps")
+        .join(project_id.to_string())
+        .join(format!("{}.dmp", uuid.as_simple())))
+}
+
+/// Saves a minidump to the filesystem
+pub async fn store_minidump(
+    base_dir: &Path,
+    project_id: i32,
+    event_id: &str,
+    data: &[u8],
+) -> AppResult<PathBuf> {
+    let path = get_minidump_path(base_dir, project_id, event_id)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await.map_err(|e| {
+            AppError::Internal(format!("Failed to create minidump directory: {}", e))
+        })?;
+    }
+
+    fsync::writer()
+        .write(&path, data)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write minidump file: {}", e)))?;
+
+    Ok(path)
+}
+
+/// A minidump found on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredMinidump {
+    pub project_id: i32,
+    pub event_id: Uuid,
+    /// When the file was written
+    pub modified: SystemTime,
+}
+
+/// Lists the stored minidumps
+///
+/// Files and directories that aren't named after a project id and event_id
+/// are skipped.
+pub async fn list_minidumps(base_dir: &Path) -> AppResult<Vec<StoredMinidump>> {
+    let mut minidumps = Vec::new();
+    for (project_dir, name) in read_dir_names(&base_dir.join("minidumps")).await? {
+        let Ok(project_id) = name.parse::<i32>() else {
+            continue;
+        };
+        for (path, name) in read_dir_names(&project_dir).await? {
+            let Some(event_id) = name
+                .strip_suffix(".dmp")
+                .and_then(|stem| Uuid::try_parse(stem).ok())
+            else {
+                continue;
+            };
+            // A file deleted meanwhile is simply left out
+            if let Ok(modified) = fs::metadata(&path).await.and_then(|m| m.modified()) {
+                minidumps.push(StoredMinidump {
+                    project_id,
+                    event_id,
+                    modified,
+                });
+            }
+        }
+    }
+
+    Ok(minidumps)
+}
+
+/// The entries of a directory with their names; none if it doesn't exist
+async fn read_dir_names(dir: &Path) -> AppResult<Vec<(PathBuf, String)>> {
+    let read_error =
+        |e: std::io::Error| AppError::Internal(format!("Failed to read {}: {}", dir.display(), e));
+
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(read_error(e)),
+    };
+
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(read_error)? {
+        if let Some(name) = entry.file_name().to_str() {
+            names.push((entry.path(), name.to_string()));
+        }
+    }
+
+    Ok(names)
+}
+
+/// Number of event files waiting in the ingest directory
+///
+/// Includes events no digest was spawned for, e.g. ones left by a restart.
+pub async fn count_events(base_dir: &Path) -> AppResult<usize> {
+    Ok(read_dir_names(base_dir)
+        .await?
+        .iter()
+        .filter(|(_, name)| name.ends_with(".json"))
+        .count())
+}
+
+/// Deletes the minidump of an event_id, if there is one
+pub async fn delete_minidump(base_dir: &Path, project_id: i32, event_id: &str) -> AppResult<()> {
+    let path = get_minidump_path(base_dir, project_id, event_id)?;
+
+    match fs::remove_file(&path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::Internal(format!(
+            "Failed to delete minidump file: {}",
+            e
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Reads an event from the filesystem
+///
+/// Gzipped files are detected by their magic bytes, so files written before
+/// or after toggling compression both load.
 pub async fn read_event(base_dir: &Path, event_id: &str) -> AppResult<Vec<u8>> {
     let path = get_event_path(base_dir, event_id)?;
 
-    fs::read(&path)
+    let data = fs::read(&path)
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to read event file: {}", e)))
+        .map_err(|e| AppError::Internal(format!("Failed to read event file: {}", e)))?;
+
+    if !data.starts_with(&GZIP_MAGIC) {
+        return Ok(data);
+    }
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(data.as_slice())
+        .read_to_end(&mut decompressed)
+        .map_err(|e| AppError::Internal(format!("Failed to decompress event file: {}", e)))?;
+
+    Ok(decompressed)
 }
 
 /// Deletes an event from the filesystem
@@ -81,6 +250,70 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_get_minidump_path() {
+        let base = Path::new("/tmp/test");
+        let path = get_minidump_path(base, 7, "9ec79c33-ec99-42ab-8353-589fcb2e04dc").unwrap();
+        assert_eq!(
+            path,
+            PathBuf::from("/tmp/test/minidumps/7/9ec79c33ec9942ab8353589fcb2e04dc.dmp")
+        );
+    }
+
+    const EVENT_ID: &str = "9ec79c33-ec99-42ab-8353-589fcb2e04dc";
+    const EVENT: &[u8] = br#"{"event_id":"9ec79c33ec9942ab8353589fcb2e04dc","message":"hello"}"#;
+
+    #[actix_web::test]
+    async fn test_store_and_read_compressed_event() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let path = store_event(dir.path(), EVENT_ID, EVENT, true)
+            .await
+            .unwrap();
+
+        let on_disk = std::fs::read(path).unwrap();
+        assert!(on_disk.starts_with(&GZIP_MAGIC));
+        assert_eq!(read_event(dir.path(), EVENT_ID).await.unwrap(), EVENT);
+    }
+
+    #[actix_web::test]
+    async fn test_store_and_read_uncompressed_event() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let path = store_event(dir.path(), EVENT_ID, EVENT, false)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(path).unwrap(), EVENT);
+        assert_eq!(read_event(dir.path(), EVENT_ID).await.unwrap(), EVENT);
+    }
+
+    #[actix_web::test]
+    async fn test_read_legacy_event_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        // Written directly, as before compression existed
+        let path = get_event_path(dir.path(), EVENT_ID).unwrap();
+        std::fs::write(&path, EVENT).unwrap();
+
+        assert_eq!(read_event(dir.path(), EVENT_ID).await.unwrap(), EVENT);
+    }
+
+    #[actix_web::test]
+    async fn test_count_events_skips_minidumps() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(count_events(&dir.path().join("missing")).await.unwrap(), 0);
+
+        store_event(dir.path(), EVENT_ID, EVENT, true)
+            .await
+            .unwrap();
+        store_minidump(dir.path(), 1, EVENT_ID, b"MDMP")
+            .await
+            .unwrap();
+
+        assert_eq!(count_events(dir.path()).await.unwrap(), 1);
+    }
+
     #[test]
     fn test_get_ingest_dir_default() {
         let dir = get_ingest_dir(None);
@@ -0,0 +1,63 @@
+//! Envelopes forwarded by an app's own backend (the SDKs' `tunnel` option).
+//!
+//! Browser ad blockers drop requests to anything that looks like Sentry, so
+//! the SDK can post envelopes to the app instead, which passes them on
+//! unchanged. The project and key then come from the `dsn` in the envelope
+//! header rather than the URL. Only DSNs pointing at this server (or an
+//! allowed host) are accepted, so the endpoint can't relay events into
+//! projects of other Sentry-compatible servers.
+
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
+/// Project and key of a tunneled envelope's DSN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TunnelDsn {
+    /// Lowercase host name, without the port
+    pub host: String,
+    pub project_id: i32,
+    pub sentry_key: Uuid,
+}
+
+impl TunnelDsn {
+    /// Parses `{scheme}://{key}[:{secret}]@{host}[:{port}][/{path}]/{project_id}`
+    pub fn parse(dsn: &str) -> AppResult<Self> {
+        let invalid = |reason: &str| AppError::Validation(format!("Invalid dsn, {}", reason));
+
+        let url = url::Url::parse(dsn).map_err(|_| invalid("not a URL"))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| invalid("missing host"))?
+            .to_ascii_lowercase();
+        let sentry_key = url
+            .username()
+            .parse()
+            .map_err(|_| invalid("missing or malformed public key"))?;
+        let project_id = url
+            .path_segments()
+            .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+            .and_then(|id| id.parse().ok())
+            .filter(|id| *id > 0)
+            .ok_or_else(|| invalid("missing project id"))?;
+
+        Ok(Self {
+            host,
+            project_id,
+            sentry_key,
+        })
+    }
+
+    /// Whether the DSN points at the host the tunnel request was sent to,
+    /// or one of `allowed_hosts`
+    ///
+    /// `request_host` may carry a port, which is ignored like the DSN's.
+    pub fn host_allowed(&self, request_host: &str, allowed_hosts: &[String]) -> bool {
+        let request_host = request_host
+            .rsplit_once(':')
+            .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+            .map_or(request_host, |(host, _)| host);
+
+        request_host.eq_ignore_ascii_case(&self.host) || allowed_hosts.contains(&self.host)
+    }
+}
@@ -0,0 +1,185 @@
+//! Background ingest writer for `INGEST_MODE=fast_ack`.
+//!
+//! The envelope endpoint hands validated events to a bounded queue and
+//! responds right away; a single task writes them to the ingest directory and
+//! spawns their digest. When the queue is full the endpoint writes the event
+//! itself, so a slow disk slows ingest down instead of losing events.
+//!
+//! Queued events were already acknowledged, so a failed write is retried
+//! until it succeeds rather than dropped. Meanwhile the queue fills up and
+//! the endpoint's own writes report the failure to SDKs, which retry later.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::config::Config;
+use crate::db::{DbHealth, DbPool};
+use crate::digest::{spawn_digest, DigestRunner};
+use crate::ingest::{get_ingest_dir, store_event, EventMetadata};
+
+/// Wait before writing a failed event again, doubled up to [`WRITE_RETRY_MAX`]
+const WRITE_RETRY_INITIAL: Duration = Duration::from_millis(100);
+const WRITE_RETRY_MAX: Duration = Duration::from_secs(5);
+
+/// Writes of an event once shutting down, so a broken disk can't hold the
+/// shutdown forever
+const SHUTDOWN_WRITE_ATTEMPTS: u32 = 3;
+
+/// An event waiting to be written to the ingest directory
+#[derive(Debug)]
+pub struct SpoolJob {
+    pub metadata: EventMetadata,
+    pub payload: Vec<u8>,
+}
+
+/// Handle to the background writer task, shared by all workers
+pub struct IngestWriter {
+    sender: mpsc::Sender<SpoolJob>,
+    /// Counts the queued events into the digest status
+    runner: Arc<DigestRunner>,
+    /// Set once shutting down, which bounds the retries of failed writes
+    stopping: Arc<AtomicBool>,
+    task: Mutex<Option<(oneshot::Sender<()>, JoinHandle<()>)>>,
+}
+
+impl IngestWriter {
+    /// Starts the writer task with a queue of `config.ingest.queue_size` events
+    pub fn start(pool: DbPool, config: Config, db_health: Arc<DbHealth>) -> Self {
+        let (sender, mut receiver) = mpsc::channel(config.ingest.queue_size.max(1));
+        let (stop, mut stopped) = oneshot::channel();
+        let runner = DigestRunner::global();
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let queue = runner.clone();
+        let writer_stopping = stopping.clone();
+        let task = tokio::spawn(async move {
+            let stopping = writer_stopping;
+            let ingest_dir = get_ingest_dir(config.ingest_dir.as_deref());
+            loop {
+                tokio::select! {
+                    job = receiver.recv() => match job {
+                        Some(job) => {
+                            write(&pool, &config, &db_health, &ingest_dir, &stopping, job).await;
+                            queue.remove_queued();
+                        }
+                        None => break,
+                    },
+                    _ = &mut stopped => {
+                        // Refuse new events, then write everything already queued
+                        receiver.close();
+                        while let Some(job) = receiver.recv().await {
+                            write(&pool, &config, &db_health, &ingest_dir, &stopping, job).await;
+                            queue.remove_queued();
+                        }
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            sender,
+            runner,
+            stopping,
+            task: Mutex::new(Some((stop, task))),
+        }
+    }
+
+    /// Queues an event, handing it back if the queue is full or shut down
+    pub fn try_enqueue(&self, job: SpoolJob) -> Result<(), SpoolJob> {
+        // Counted first, so the writer can't take it off before it's on
+        self.runner.add_queued();
+        self.sender.try_send(job).map_err(|e| {
+            self.runner.remove_queued();
+            match e {
+                mpsc::error::TrySendError::Full(job) | mpsc::error::TrySendError::Closed(job) => {
+                    job
+                }
+            }
+        })
+    }
+
+    /// Number of events waiting to be written
+    pub fn queue_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Number of events the queue can hold
+    pub fn queue_capacity(&self) -> usize {
+        self.sender.max_capacity()
+    }
+
+    /// Stops accepting events and waits until every queued event is written
+    pub async fn shutdown(&self) {
+        let Some((stop, task)) = self.task.lock().unwrap().take() else {
+            return;
+        };
+
+        let queued = self.queue_depth();
+        if queued > 0 {
+            log::info!("Writing {} queued events before shutdown", queued);
+        }
+
+        self.stopping.store(true, Ordering::SeqCst);
+        let _ = stop.send(());
+        if let Err(e) = task.await {
+            log::error!("Ingest writer task failed: {}", e);
+        }
+    }
+}
+
+/// Writes an event and spawns its digest, retrying a failed write until it
+/// succeeds or, once shutting down, [`SHUTDOWN_WRITE_ATTEMPTS`] are used up
+async fn write(
+    pool: &DbPool,
+    config: &Config,
+    db_health: &Arc<DbHealth>,
+    ingest_dir: &Path,
+    stopping: &AtomicBool,
+    job: SpoolJob,
+) {
+    let SpoolJob { metadata, payload } = job;
+
+    let mut attempts = 0;
+    let mut wait = WRITE_RETRY_INITIAL;
+    while let Err(e) = store_event(
+        ingest_dir,
+        metadata.spool_id(),
+        &payload,
+        config.compress_stored_events,
+    )
+    .await
+    {
+        attempts += 1;
+        if stopping.load(Ordering::SeqCst) && attempts >= SHUTDOWN_WRITE_ATTEMPTS {
+            log::error!(
+                "Failed to write event {} before shutdown, it is lost: {}",
+                metadata.event_id,
+                e
+            );
+            return;
+        }
+
+        log::warn!(
+            "Failed to write event {}, retrying in {:?}: {}",
+            metadata.event_id,
+            wait,
+            e
+        );
+        tokio::time::sleep(wait).await;
+        wait = (wait * 2).min(WRITE_RETRY_MAX);
+    }
+
+    spawn_digest(
+        pool,
+        config,
+        db_health.clone(),
+        ingest_dir.to_path_buf(),
+        metadata,
+    );
+}
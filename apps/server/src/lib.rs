@@ -4,6 +4,7 @@
 
 pub mod auth;
 pub mod bootstrap;
+pub mod build_info;
 pub mod config;
 pub mod db;
 pub mod digest;
@@ -1,14 +1,30 @@
-use actix_cors::Cors;
-use actix_session::{storage::CookieSessionStore, SessionMiddleware};
+use std::time::Duration;
+
+use actix_session::SessionMiddleware;
 use actix_web::{cookie::Key, middleware, web, App, HttpServer};
 
+use rustrak::auth::{OidcClient, ProjectCache, SessionStorage};
 use rustrak::bootstrap;
-use rustrak::config;
+use rustrak::build_info;
+use rustrak::config::{self, IngestMaintenance, IngestMode, SessionBackend};
 use rustrak::db;
+use rustrak::digest::DigestRunner;
+use rustrak::error;
+use rustrak::ingest::{fsync, get_ingest_dir, IngestWriter};
 use rustrak::middleware::auth::RequireAuth;
-use rustrak::models;
+use rustrak::middleware::compress::skip_streaming_compression;
+use rustrak::middleware::cors::CorsPolicy;
+use rustrak::middleware::rate_limit::ApiRateLimiter;
+use rustrak::middleware::request_id::{self, request_id};
+use rustrak::middleware::self_monitoring::CaptureServerErrors;
+use rustrak::middleware::version::version_header;
+use rustrak::pagination::CursorSigner;
 use rustrak::routes;
-use rustrak::services::AuthTokenService;
+use rustrak::services::notification;
+use rustrak::services::{
+    AlertDispatchLimiter, AlertService, AutoResolveService, IssueService, ProjectService,
+    RegroupService, RegroupTarget, RetentionService, SelfMonitor,
+};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -16,7 +32,9 @@ async fn main() -> std::io::Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize logging
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+    env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("info"))
+        .format(request_id::log_format)
+        .init();
 
     // Load configuration
     let config = config::Config::from_env().map_err(|e| {
@@ -24,7 +42,36 @@ async fn main() -> std::io::Result<()> {
         std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string())
     })?;
 
-    log::info!("Starting Rustrak server on {}:{}", config.host, config.port);
+    // `rustrak migrate [--dry-run]` only runs the migrations
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "migrate") {
+        let dry_run = args.iter().any(|arg| arg == "--dry-run");
+        return migrate(&config, dry_run).await;
+    }
+
+    // `rustrak backfill-culprits` refreshes issue culprits from their latest event
+    if args.first().is_some_and(|arg| arg == "backfill-culprits") {
+        return backfill_culprits(&config).await;
+    }
+
+    // `rustrak truncate-values` cuts stored values to MAX_ISSUE_VALUE_LENGTH
+    if args.first().is_some_and(|arg| arg == "truncate-values") {
+        return truncate_values(&config).await;
+    }
+
+    // `rustrak regroup --project <id> [--dry-run | --apply]` recomputes grouping keys
+    if args.first().is_some_and(|arg| arg == "regroup") {
+        return regroup(&config, &args[1..]).await;
+    }
+
+    routes::health::mark_started();
+    log::info!(
+        "Starting Rustrak server {} ({}) on {}:{}",
+        build_info::VERSION,
+        build_info::COMMIT,
+        config.host,
+        config.port
+    );
 
     // Create database pool
     let db_pool = db::create_pool(&config.database).await.map_err(|e| {
@@ -33,19 +80,64 @@ async fn main() -> std::io::Result<()> {
     })?;
 
     // Run migrations
-    db::run_migrations(&db_pool).await.map_err(|e| {
-        log::error!("Migration error: {}", e);
-        std::io::Error::other(e.to_string())
-    })?;
+    db::run_migrations(&db_pool, config.database.allow_dirty_migrations)
+        .await
+        .map_err(migration_error)?;
 
-    // Bootstrap: create initial token if none exist
-    bootstrap_token(&db_pool).await;
+    // Bootstrap: create initial token if none exist (never in production)
+    if let Err(e) = bootstrap::create_token_if_needed(&db_pool, config.security.environment).await {
+        log::error!("Failed to create bootstrap token: {}", e);
+    }
 
-    // Bootstrap: create superuser if CREATE_SUPERUSER is set
-    if let Err(e) = bootstrap::create_superuser_if_needed(&db_pool).await {
+    // Bootstrap: create superuser if CREATE_SUPERUSER is set (never in production)
+    if let Err(e) =
+        bootstrap::create_superuser_if_needed(&db_pool, config.security.environment).await
+    {
         log::error!("Failed to create superuser: {}", e);
     }
 
+    // Purge issues whose trash window has passed
+    RetentionService::spawn(
+        db_pool.clone(),
+        config.retention.clone(),
+        get_ingest_dir(config.ingest_dir.as_deref()),
+    );
+
+    // Resolve issues that went quiet in projects with auto_resolve_days
+    AutoResolveService::spawn(db_pool.clone());
+
+    // Bound concurrent digests and alert deliveries, and disable channels
+    // that keep failing
+    DigestRunner::init(&config.digest);
+    AlertDispatchLimiter::init(&config.alerts);
+    AlertService::init(&config.alerts);
+    notification::http::init(&config.alerts.outbound);
+    notification::SlackNotifier::init(&config.alerts);
+
+    // Pagination cursors are signed so they can't be forged or reused elsewhere
+    CursorSigner::init(
+        &config.pagination,
+        config.security.session_secret_key.as_deref(),
+    );
+
+    // Report our own panics and server errors into a project
+    let self_monitor = SelfMonitor::from_config(&db_pool, &config);
+    if let Some(monitor) = &self_monitor {
+        match ProjectService::get_by_id(&db_pool, monitor.project_id()).await {
+            Ok(project) => log::info!(
+                "Self-monitoring enabled, reporting errors into project {}",
+                project.name
+            ),
+            Err(e) => log::warn!(
+                "Self-monitoring project {} not found ({}), errors won't be reported",
+                monitor.project_id(),
+                e
+            ),
+        }
+        monitor.clone().install_panic_hook();
+    }
+    let self_monitor = self_monitor.map(web::Data::new);
+
     // Session secret key from config or generate random (with warning)
     let secret_key = match &config.security.session_secret_key {
         Some(key) => key.clone(),
@@ -61,40 +153,111 @@ async fn main() -> std::io::Result<()> {
 
     let key = Key::from(secret_key.as_bytes());
 
+    // Session state lives in the cookie unless SESSION_STORE=redis
+    let session_storage = SessionStorage::connect(&config.security.session_store)
+        .await
+        .map_err(|e| {
+            log::error!("Session store error: {}", e);
+            std::io::Error::other(e.to_string())
+        })?;
+    if let SessionBackend::Redis { .. } = config.security.session_store {
+        log::info!("Storing sessions in Redis");
+    }
+
+    // Auth/token rate limit counters, shared by all workers
+    let api_rate_limiter = web::Data::new(ApiRateLimiter::new(config.api_rate_limit.clone()));
+
+    // Database outage tracking, and the projects ingest authenticates from
+    // recently or while the database is down
+    let db_health = web::Data::new(db::DbHealth::new());
+    let project_cache = web::Data::new(ProjectCache::with_ttl(config.ingest.auth_cache_ttl));
+
+    // Background spool writer for fast_ack ingest
+    let ingest_writer = (config.ingest.mode == IngestMode::FastAck).then(|| {
+        log::info!(
+            "Ingest mode fast_ack, queueing up to {} events",
+            config.ingest.queue_size
+        );
+        web::Data::new(IngestWriter::start(
+            db_pool.clone(),
+            config.clone(),
+            db_health.clone().into_inner(),
+        ))
+    });
+
+    // When event files reach the disk
+    fsync::init(&config.ingest);
+
+    if config.ingest.maintenance != IngestMaintenance::Accept {
+        log::warn!(
+            "Ingest maintenance mode {}, incoming events won't be stored",
+            config.ingest.maintenance.as_str()
+        );
+    }
+
+    if !config.security.cors_allowed_origins.is_empty() {
+        log::info!(
+            "Allowing credentialed API requests from {}",
+            config.security.cors_allowed_origins.join(", ")
+        );
+    }
+
+    // Dashboard single sign-on
+    let oidc_client = match &config.security.oidc {
+        Some(oidc) => {
+            log::info!("OIDC login enabled with issuer {}", oidc.issuer_url);
+            let client = OidcClient::new(oidc.clone(), &config.alerts.outbound)
+                .expect("Failed to create OIDC HTTP client");
+            Some(web::Data::new(client))
+        }
+        None => None,
+    };
+
     // Clone values for the closure
+    let shutdown_writer = ingest_writer.clone();
     let host = config.host.clone();
     let port = config.port;
 
     let server = HttpServer::new(move || {
-        // CORS configuration - permissive for event ingestion
-        // Sentry SDKs can send from any origin. CORS protects the user from
-        // site A sending to site B, but in error tracking the app intentionally
-        // sends data to Rustrak. There's nothing to protect.
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
-            .allowed_headers(vec![
-                actix_web::http::header::AUTHORIZATION,
-                actix_web::http::header::ACCEPT,
-                actix_web::http::header::CONTENT_TYPE,
-                actix_web::http::header::CONTENT_ENCODING,
-                // Headers used by Sentry SDKs
-                actix_web::http::header::HeaderName::from_static("x-sentry-auth"),
-                actix_web::http::header::HeaderName::from_static("sentry-trace"),
-                actix_web::http::header::HeaderName::from_static("baggage"),
-            ])
-            .max_age(3600);
-
-        App::new()
+        // Any origin for ingest, credentialed allowed origins for the API
+        let cors = CorsPolicy::new(&config.security.cors_allowed_origins);
+
+        let mut app = App::new();
+        if let Some(writer) = &ingest_writer {
+            app = app.app_data(writer.clone());
+        }
+        if let Some(monitor) = &self_monitor {
+            app = app.app_data(monitor.clone());
+        }
+        if let Some(oidc) = &oidc_client {
+            app = app.app_data(oidc.clone());
+        }
+
+        app
             // Share database pool and config with all handlers
             .app_data(web::Data::new(db_pool.clone()))
             .app_data(web::Data::new(config.clone()))
+            .app_data(api_rate_limiter.clone())
+            .app_data(db_health.clone())
+            .app_data(project_cache.clone())
+            // JSON errors for malformed bodies, query strings and paths
+            .configure(error::configure_extractors(config.body_limits.clone()))
             // Middleware
-            .wrap(middleware::Logger::default())
+            .wrap(
+                middleware::Logger::new(
+                    r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T request_id=%{request_id}xi"#,
+                )
+                .custom_request_replace("request_id", |_| {
+                    request_id::current().unwrap_or_default()
+                }),
+            )
+            .wrap(CaptureServerErrors)
+            // Inside Compress, so streamed responses aren't buffered by it
+            .wrap(middleware::from_fn(skip_streaming_compression))
             .wrap(middleware::Compress::default())
             .wrap(cors) // CORS must be before SessionMiddleware
             .wrap(
-                SessionMiddleware::builder(CookieSessionStore::default(), key.clone())
+                SessionMiddleware::builder(session_storage.clone(), key.clone())
                     .cookie_name("rustrak_session".to_string())
                     .cookie_secure(config.security.ssl_proxy)
                     .cookie_http_only(true)
@@ -103,6 +266,10 @@ async fn main() -> std::io::Result<()> {
             )
             // Authentication middleware (must be after SessionMiddleware)
             .wrap(RequireAuth)
+            // Version header on every response, including auth failures
+            .wrap(version_header())
+            // Outermost, so every response and error carries the request id
+            .wrap(middleware::from_fn(request_id))
             // Health check routes (no auth required)
             .service(
                 web::scope("/health")
@@ -111,21 +278,28 @@ async fn main() -> std::io::Result<()> {
             )
             // Root health check alias
             .route("/health", web::get().to(routes::health::liveness))
+            .configure(routes::metrics::configure)
             // Auth routes (public - no Bearer auth required)
             .configure(routes::auth::configure)
             // API routes (auth required)
             // More specific routes first: events > issues > alert-rules > projects
             .configure(routes::events::configure)
             .configure(routes::issues::configure)
+            .configure(routes::debug_files::configure)
             .configure(routes::alerts::configure_rules)
             .configure(routes::alerts::configure_history)
             // Then generic projects/tokens routes
             .configure(routes::projects::configure)
             .configure(routes::tokens::configure)
+            .configure(routes::teams::configure)
+            .configure(routes::saved_searches::configure)
+            .configure(routes::me::configure)
             // Alert channels (global, not nested under projects)
             .configure(routes::alerts::configure_channels)
+            .configure(routes::admin::configure)
             // Ingest routes (Sentry SDK auth)
-            .configure(routes::ingest::configure)
+            .configure(routes::ingest::configure(config.body_limits.ingest))
+            .default_service(web::to(error::not_found))
     })
     .bind((host.as_str(), port))?
     .shutdown_timeout(30)
@@ -139,7 +313,190 @@ async fn main() -> std::io::Result<()> {
         server_handle.stop(true).await;
     });
 
-    server.await
+    let result = server.await;
+
+    // Write events acknowledged in fast_ack mode before exiting
+    if let Some(writer) = shutdown_writer {
+        writer.shutdown().await;
+    }
+    // Sync the events written since the last batch sync
+    fsync::writer().flush().await;
+
+    result
+}
+
+/// Migrate subcommand: applies pending migrations, or lists them with `--dry-run`
+async fn migrate(config: &config::Config, dry_run: bool) -> std::io::Result<()> {
+    let db_pool = db::create_pool(&config.database).await.map_err(|e| {
+        log::error!("Database pool error: {}", e);
+        std::io::Error::other(e.to_string())
+    })?;
+    let allow_dirty = config.database.allow_dirty_migrations;
+
+    if !dry_run {
+        return db::run_migrations(&db_pool, allow_dirty)
+            .await
+            .map_err(migration_error);
+    }
+
+    let state = db::inspect_migrations(&db_pool, &db::MIGRATOR)
+        .await
+        .map_err(|e| migration_error(e.into()))?;
+    state.check(allow_dirty).map_err(migration_error)?;
+
+    for migration in &state.modified {
+        println!("Would accept modified migration {}", migration);
+    }
+    if state.pending.is_empty() {
+        println!("No pending migrations");
+    } else {
+        println!("Pending migrations:");
+        for migration in &state.pending {
+            println!("  {}", migration);
+        }
+    }
+
+    Ok(())
+}
+
+/// Pool of the maintenance subcommands, without the statement timeout
+///
+/// They go through whole tables, which takes longer than server queries may.
+async fn maintenance_pool(config: &config::Config) -> std::io::Result<db::DbPool> {
+    let database = config::DatabaseConfig {
+        statement_timeout: Duration::ZERO,
+        ..config.database.clone()
+    };
+    db::create_pool(&database).await.map_err(|e| {
+        log::error!("Database pool error: {}", e);
+        std::io::Error::other(e.to_string())
+    })
+}
+
+/// Backfill-culprits subcommand: copies each issue's latest culprit fields onto it
+async fn backfill_culprits(config: &config::Config) -> std::io::Result<()> {
+    let db_pool = maintenance_pool(config).await?;
+
+    let updated = IssueService::backfill_culprits(&db_pool)
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Updated the culprit of {} issue(s)", updated);
+
+    Ok(())
+}
+
+/// Truncate-values subcommand: cuts issue and event values stored before
+/// MAX_ISSUE_VALUE_LENGTH applied, or while it was higher
+async fn truncate_values(config: &config::Config) -> std::io::Result<()> {
+    let db_pool = maintenance_pool(config).await?;
+
+    let (issues, events) =
+        IssueService::truncate_values(&db_pool, config.digest.max_issue_value_length)
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!(
+        "Truncated the values of {} issue(s) and {} event(s) to {} characters",
+        issues, events, config.digest.max_issue_value_length
+    );
+
+    Ok(())
+}
+
+/// Regroup subcommand: recomputes the grouping keys of a project's events and
+/// shows how issues would merge and split, or regroups them with `--apply`
+async fn regroup(config: &config::Config, args: &[String]) -> std::io::Result<()> {
+    let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+    let project_id: i32 = args
+        .iter()
+        .position(|arg| arg == "--project")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| invalid("Usage: rustrak regroup --project <id> [--dry-run | --apply]"))?;
+    let apply = args.iter().any(|arg| arg == "--apply");
+    if apply && args.iter().any(|arg| arg == "--dry-run") {
+        return Err(invalid("--apply and --dry-run can't be combined"));
+    }
+
+    let db_pool = maintenance_pool(config).await?;
+    let to_io = |e: error::AppError| std::io::Error::other(e.to_string());
+    ProjectService::get_by_id(&db_pool, project_id)
+        .await
+        .map_err(to_io)?;
+
+    if apply {
+        let outcome =
+            RegroupService::apply(&db_pool, project_id, config.digest.max_issue_value_length)
+                .await
+                .map_err(to_io)?;
+        println!(
+            "{} project {}: moved {} event(s), created {} issue(s), deleted {} merged issue(s)",
+            if outcome.resumed {
+                "Finished regrouping"
+            } else {
+                "Regrouped"
+            },
+            project_id,
+            outcome.moved_events,
+            outcome.new_issues,
+            outcome.deleted_issues
+        );
+        return Ok(());
+    }
+
+    if let Some(moved) = RegroupService::checkpoint(&db_pool, project_id)
+        .await
+        .map_err(to_io)?
+    {
+        println!(
+            "An interrupted regroup of project {} moved {} event(s); --apply continues it",
+            project_id, moved
+        );
+    }
+
+    let plan = RegroupService::plan(&db_pool, project_id)
+        .await
+        .map_err(to_io)?;
+    println!(
+        "Project {}: {} event(s) regrouped, {} without payload left in place",
+        project_id, plan.events, plan.pruned_events
+    );
+
+    let target_label = |target: &RegroupTarget| match target {
+        RegroupTarget::Issue { digest_order, .. } => format!("#{}", digest_order),
+        RegroupTarget::New { title } => format!("new issue \"{}\"", title),
+    };
+    for issue in &plan.issues {
+        let merge = issue
+            .merged_into
+            .as_ref()
+            .map(|target| format!(", merges into {}", target_label(target)))
+            .unwrap_or_default();
+        println!(
+            "#{} {} ({} event(s)){}",
+            issue.digest_order, issue.title, issue.events, merge
+        );
+        if issue.staying > 0 {
+            println!("  {:>8} stay", issue.staying);
+        }
+        for m in &issue.moves {
+            println!("  {:>8} -> {}", m.events, target_label(&m.target));
+        }
+    }
+
+    println!(
+        "{} issue(s) merge, {} split, {} new issue(s), {} event(s) move; run with --apply to regroup",
+        plan.merged_issues(),
+        plan.split_issues(),
+        plan.new_issues,
+        plan.moved_events()
+    );
+
+    Ok(())
+}
+
+fn migration_error(e: db::MigrationError) -> std::io::Error {
+    log::error!("Migration error: {}", e);
+    std::io::Error::other(e.to_string())
 }
 
 /// Wait for shutdown signal (Ctrl+C or SIGTERM)
@@ -176,44 +533,3 @@ async fn shutdown_signal() {
         _ = terminate => {},
     }
 }
-
-/// Bootstrap: create initial token if none exist and RUSTRAK_BOOTSTRAP_TOKEN is set
-async fn bootstrap_token(pool: &db::DbPool) {
-    // Check if bootstrap is requested via env var
-    if std::env::var("RUSTRAK_BOOTSTRAP_TOKEN").is_err() {
-        return;
-    }
-
-    // Check if any tokens exist
-    match AuthTokenService::has_any_token(pool).await {
-        Ok(true) => {
-            log::info!("Auth tokens already exist, skipping bootstrap");
-        }
-        Ok(false) => {
-            // Create bootstrap token
-            let input = models::CreateAuthToken {
-                description: Some("Bootstrap token (created automatically)".to_string()),
-            };
-
-            match AuthTokenService::create(pool, input).await {
-                Ok(token) => {
-                    // Print to stderr directly (not logs) to avoid token in log aggregators
-                    eprintln!();
-                    eprintln!("==============================================");
-                    eprintln!("BOOTSTRAP TOKEN CREATED - SAVE THIS NOW!");
-                    eprintln!("Token: {}", token.token);
-                    eprintln!("This token will NOT be shown again.");
-                    eprintln!("==============================================");
-                    eprintln!();
-                    log::info!("Bootstrap token created successfully");
-                }
-                Err(e) => {
-                    log::error!("Failed to create bootstrap token: {}", e);
-                }
-            }
-        }
-        Err(e) => {
-            log::error!("Failed to check for existing tokens: {}", e);
-        }
-    }
-}
@@ -2,13 +2,14 @@ use actix_session::Session;
 use actix_web::{
     body::{EitherBody, MessageBody},
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, FromRequest, HttpResponse,
+    Error, FromRequest, ResponseError,
 };
 use std::future::{ready, Ready};
 use std::pin::Pin;
 use std::rc::Rc;
 
 use crate::auth;
+use crate::error::AppError;
 
 /// Middleware to require authentication for routes
 pub struct RequireAuth;
@@ -56,8 +57,11 @@ where
         // - /api/* - API routes (authentication handled by extractors:
         //            SentryAuth for ingest, AuthenticatedUser for management)
         // - /health - health check routes
-        let is_exempt =
-            path.starts_with("/auth") || path.starts_with("/api/") || path.starts_with("/health");
+        // - /metrics - Prometheus scrape endpoint
+        let is_exempt = path.starts_with("/auth")
+            || path.starts_with("/api/")
+            || path.starts_with("/health")
+            || path == "/metrics";
 
         if is_exempt {
             let service = Rc::clone(&self.service);
@@ -81,10 +85,8 @@ where
                     } else {
                         // Not authenticated
                         let (http_req, _) = req.into_parts();
-                        let response = HttpResponse::Unauthorized()
-                            .json(serde_json::json!({
-                                "error": "Not authenticated"
-                            }))
+                        let response = AppError::Unauthorized("Not authenticated".to_string())
+                            .error_response()
                             .map_into_boxed_body();
                         Ok(ServiceResponse::new(http_req, response).map_into_right_body())
                     }
@@ -92,10 +94,8 @@ where
                 Err(_) => {
                     // Session error
                     let (http_req, _) = req.into_parts();
-                    let response = HttpResponse::Unauthorized()
-                        .json(serde_json::json!({
-                            "error": "Session error"
-                        }))
+                    let response = AppError::Unauthorized("Session error".to_string())
+                        .error_response()
                         .map_into_boxed_body();
                     Ok(ServiceResponse::new(http_req, response).map_into_right_body())
                 }
@@ -0,0 +1,46 @@
+//! Compression opt-out for streaming responses.
+//!
+//! `Compress` negotiates gzip, brotli or zstd for every response, and an
+//! encoder holds data back until it has enough to compress. For server-sent
+//! events and NDJSON that means lines don't reach the client as they're
+//! written. Responses of those content types are marked
+//! `Content-Encoding: identity`, which `Compress` leaves alone; this has to
+//! run inside it, i.e. be wrapped before it.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_TYPE};
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+/// Content types written a piece at a time, sent uncompressed
+pub const STREAMING_CONTENT_TYPES: &[&str] = &["text/event-stream", "application/x-ndjson"];
+
+/// Whether a response of `content_type` is streamed
+pub fn is_streaming(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    STREAMING_CONTENT_TYPES
+        .iter()
+        .any(|streaming| mime.eq_ignore_ascii_case(streaming))
+}
+
+/// Middleware keeping `Compress` off streaming responses
+///
+/// Use with `middleware::from_fn`.
+pub async fn skip_streaming_compression(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let mut res = next.call(req).await?;
+
+    let headers = res.headers_mut();
+    let streaming = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(is_streaming);
+    if streaming && !headers.contains_key(CONTENT_ENCODING) {
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,145 @@
+//! CORS middleware.
+//!
+//! Sentry SDKs send events from any origin. CORS protects the user from site A
+//! sending to site B, but in error tracking the app intentionally sends data to
+//! Rustrak, so the ingest endpoints accept every origin, without credentials.
+//!
+//! The rest of the API is different once a dashboard on another origin logs in
+//! with the session cookie: browsers only send it when the response names that
+//! origin and allows credentials, which a wildcard can't do. With
+//! `CORS_ALLOWED_ORIGINS` set, those routes answer the listed origins only.
+
+use actix_cors::{Cors, CorsMiddleware};
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{self, HeaderName},
+    Error,
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use crate::routes::ingest::is_ingest_path;
+
+/// CORS for the whole app: origin-agnostic on the ingest endpoints, and
+/// restricted to the allowed origins, with credentials, everywhere else
+pub struct CorsPolicy {
+    api: Cors,
+    ingest: Cors,
+}
+
+impl CorsPolicy {
+    /// Policy allowing `allowed_origins` to make credentialed API requests;
+    /// empty allows any origin, without credentials
+    pub fn new(allowed_origins: &[String]) -> Self {
+        let api = if allowed_origins.is_empty() {
+            base().allow_any_origin()
+        } else {
+            allowed_origins
+                .iter()
+                .fold(base(), |cors, origin| cors.allowed_origin(origin))
+                .supports_credentials()
+        };
+
+        Self {
+            api,
+            ingest: base().allow_any_origin(),
+        }
+    }
+}
+
+/// Methods, headers and max age shared by both policies
+fn base() -> Cors {
+    Cors::default()
+        .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
+        .allowed_headers(vec![
+            header::AUTHORIZATION,
+            header::ACCEPT,
+            header::CONTENT_TYPE,
+            header::CONTENT_ENCODING,
+            // Headers used by Sentry SDKs
+            HeaderName::from_static("x-sentry-auth"),
+            HeaderName::from_static("sentry-trace"),
+            HeaderName::from_static("baggage"),
+            HeaderName::from_static("x-request-id"),
+        ])
+        .expose_headers(vec![HeaderName::from_static("x-request-id")])
+        .max_age(3600)
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CorsPolicy
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CorsPolicyMiddleware<S>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Transform, Self::InitError>>>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        // Both policies wrap the same inner service
+        let service = Rc::new(service);
+        let api = self.api.new_transform(Shared(Rc::clone(&service)));
+        let ingest = self.ingest.new_transform(Shared(service));
+
+        Box::pin(async move {
+            Ok(CorsPolicyMiddleware {
+                api: api.await?,
+                ingest: ingest.await?,
+            })
+        })
+    }
+}
+
+pub struct CorsPolicyMiddleware<S> {
+    api: CorsMiddleware<Shared<S>>,
+    ingest: CorsMiddleware<Shared<S>>,
+}
+
+impl<S, B> Service<ServiceRequest> for CorsPolicyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = <CorsMiddleware<Shared<S>> as Service<ServiceRequest>>::Future;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.api.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if is_ingest_path(req.path()) {
+            self.ingest.call(req)
+        } else {
+            self.api.call(req)
+        }
+    }
+}
+
+/// Inner service shared by the two CORS middlewares
+pub struct Shared<S>(Rc<S>);
+
+impl<S> Service<ServiceRequest> for Shared<S>
+where
+    S: Service<ServiceRequest>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        self.0.call(req)
+    }
+}
@@ -1 +1,7 @@
 pub mod auth;
+pub mod compress;
+pub mod cors;
+pub mod rate_limit;
+pub mod request_id;
+pub mod self_monitoring;
+pub mod version;
@@ -0,0 +1,198 @@
+//! Per-IP rate limiting for the auth and token management endpoints.
+//!
+//! Requests are counted per client IP in fixed one-minute windows, with a
+//! separate namespace and limit per [`RateLimitScope`]. Only mutations are
+//! counted; reads such as `GET /auth/me` pass through.
+//!
+//! Counters live in memory, shared by all workers of the process. Ingest is
+//! limited separately by project quotas (see `RateLimitService`).
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web, Error, ResponseError,
+};
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{ApiRateLimitConfig, Config};
+use crate::error::AppError;
+use crate::ingest::remote_addr;
+
+/// Length of a rate limit window
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Expired windows are pruned once this many keys are tracked
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Group of endpoints sharing a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitScope {
+    /// `/auth/*`
+    Auth,
+    /// `/api/tokens`
+    Tokens,
+}
+
+impl RateLimitScope {
+    fn namespace(&self) -> &'static str {
+        match self {
+            RateLimitScope::Auth => "auth",
+            RateLimitScope::Tokens => "tokens",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+/// Fixed-window request counters keyed by scope and client IP
+#[derive(Debug)]
+pub struct ApiRateLimiter {
+    config: ApiRateLimitConfig,
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+impl ApiRateLimiter {
+    pub fn new(config: ApiRateLimitConfig) -> Self {
+        Self {
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn limit(&self, scope: RateLimitScope) -> u32 {
+        match scope {
+            RateLimitScope::Auth => self.config.auth_requests_per_minute,
+            RateLimitScope::Tokens => self.config.token_requests_per_minute,
+        }
+    }
+
+    /// Counts a request from `ip`
+    ///
+    /// Returns the seconds until the window resets when the request is over
+    /// the limit.
+    pub fn check(&self, scope: RateLimitScope, ip: &str) -> Result<(), u64> {
+        let limit = self.limit(scope);
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= PRUNE_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started) < WINDOW);
+        }
+
+        let window = windows
+            .entry(format!("{}:{}", scope.namespace(), ip))
+            .or_insert(Window {
+                started: now,
+                count: 0,
+            });
+        if now.duration_since(window.started) >= WINDOW {
+            window.started = now;
+            window.count = 0;
+        }
+
+        if window.count >= limit {
+            let remaining = WINDOW.saturating_sub(now.duration_since(window.started));
+            return Err(remaining.as_secs().max(1));
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+}
+
+/// Middleware limiting mutations per client IP
+///
+/// Uses the [`ApiRateLimiter`] registered as app data; without one, requests
+/// pass through.
+pub struct RateLimit {
+    scope: RateLimitScope,
+}
+
+impl RateLimit {
+    pub fn new(scope: RateLimitScope) -> Self {
+        Self { scope }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            scope: self.scope,
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    scope: RateLimitScope,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let is_read = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        let limiter = req.app_data::<web::Data<ApiRateLimiter>>().cloned();
+
+        if let (false, Some(limiter)) = (is_read, limiter) {
+            let trust_proxy_headers = req
+                .app_data::<web::Data<Config>>()
+                .is_some_and(|config| config.security.trust_proxy_headers);
+            let ip = remote_addr(req.request(), trust_proxy_headers)
+                .unwrap_or_else(|| "unknown".to_string());
+
+            if let Err(retry_after) = limiter.check(self.scope, &ip) {
+                log::warn!(
+                    "Rate limit exceeded for {} requests from {}: retry_after={}s",
+                    self.scope.namespace(),
+                    ip,
+                    retry_after
+                );
+                let (http_req, _) = req.into_parts();
+                let response = AppError::RateLimited(retry_after)
+                    .error_response()
+                    .map_into_boxed_body();
+                return Box::pin(async move {
+                    Ok(ServiceResponse::new(http_req, response).map_into_right_body())
+                });
+            }
+        }
+
+        let service = Rc::clone(&self.service);
+        Box::pin(async move { service.call(req).await.map(|res| res.map_into_left_body()) })
+    }
+}
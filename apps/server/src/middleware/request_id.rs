@@ -0,0 +1,92 @@
+//! Request ids for correlating client errors with server logs.
+//!
+//! Every request gets an id: the client's `X-Request-Id` when it sent a
+//! usable one, a new UUID otherwise. The id is echoed in the response
+//! header, returned as `error.request_id` in error bodies, and appended to
+//! every log line written while the request is handled. This has to be the
+//! outermost middleware, so errors raised by the others carry the id too.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::InternalError;
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::Error;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Longest client-supplied id that's kept; longer ones are replaced
+pub const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the request being handled, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// The client's id if it's usable, else a new one
+///
+/// Ids are limited to printable ASCII without spaces, so they can't break
+/// up log lines or headers.
+pub fn accept_or_generate(incoming: Option<&str>) -> String {
+    incoming
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .filter(|id| id.bytes().all(|b| b.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Middleware assigning and echoing request ids
+///
+/// Use with `middleware::from_fn`.
+pub async fn request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let header = HeaderName::from_static("x-request-id");
+    let id = accept_or_generate(req.headers().get(&header).and_then(|v| v.to_str().ok()));
+    let value = HeaderValue::from_str(&id).expect("request ids are printable ASCII");
+
+    REQUEST_ID
+        .scope(id, async move {
+            match next.call(req).await {
+                Ok(mut res) => {
+                    res.headers_mut().insert(header, value);
+                    Ok(res)
+                }
+                // Rendered here, while the id is set, rather than by the server
+                Err(err) => {
+                    let mut res = err.error_response();
+                    res.headers_mut().insert(header, value);
+                    Err(InternalError::from_response(err, res).into())
+                }
+            }
+        })
+        .await
+}
+
+/// Log line format of `env_logger`'s default, plus the request id
+pub fn log_format(
+    buf: &mut env_logger::fmt::Formatter,
+    record: &log::Record,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let level = buf.default_level_style(record.level());
+    write!(
+        buf,
+        "[{} {level}{:<5}{level:#} {}] {}",
+        buf.timestamp(),
+        record.level(),
+        record.target(),
+        record.args()
+    )?;
+    if let Some(id) = current() {
+        write!(buf, " request_id={}", id)?;
+    }
+    writeln!(buf)
+}
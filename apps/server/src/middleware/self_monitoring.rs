@@ -0,0 +1,90 @@
+//! Server error capture for self-monitoring.
+//!
+//! Every 5xx response is reported into the self-monitoring project when a
+//! [`SelfMonitor`] is registered as app data; otherwise the middleware does
+//! nothing. Handler errors keep their `AppError` variant as the exception
+//! type, so database errors group apart from other failures.
+
+use actix_web::{
+    body::MessageBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+
+use crate::error::AppError;
+use crate::services::self_monitoring::{CapturedRequest, SelfMonitor};
+
+/// Middleware reporting server errors to the [`SelfMonitor`]
+pub struct CaptureServerErrors;
+
+impl<S, B> Transform<S, ServiceRequest> for CaptureServerErrors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = CaptureServerErrorsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CaptureServerErrorsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct CaptureServerErrorsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for CaptureServerErrorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(monitor) = req.app_data::<web::Data<SelfMonitor>>().cloned() else {
+            let service = Rc::clone(&self.service);
+            return Box::pin(async move { service.call(req).await });
+        };
+
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            let res = service.call(req).await?;
+            let status = res.status();
+            if status.is_server_error() {
+                let (error_type, message) = match res
+                    .response()
+                    .error()
+                    .and_then(|e| e.as_error::<AppError>())
+                {
+                    Some(e @ AppError::Database(_)) => ("DatabaseError", e.to_string()),
+                    Some(e @ AppError::Internal(_)) => ("InternalError", e.to_string()),
+                    Some(e) => ("ServerError", e.to_string()),
+                    None => ("ServerError", format!("{} response", status)),
+                };
+                // The query string is left out, it may hold a sentry_key
+                let request = CapturedRequest {
+                    method: res.request().method().to_string(),
+                    path: res.request().path().to_string(),
+                    route: res.request().match_pattern(),
+                };
+                monitor.capture_error(error_type, &message, Some(&request));
+            }
+            Ok(res)
+        })
+    }
+}
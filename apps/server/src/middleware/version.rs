@@ -0,0 +1,15 @@
+//! Version header middleware.
+//!
+//! Adds `X-Rustrak-Version` to every response so clients can tell which
+//! server they're talking to without an extra request.
+
+use actix_web::middleware::DefaultHeaders;
+
+use crate::build_info;
+
+pub const VERSION_HEADER: &str = "X-Rustrak-Version";
+
+/// Middleware adding the [`VERSION_HEADER`] to every response
+pub fn version_header() -> DefaultHeaders {
+    DefaultHeaders::new().add((VERSION_HEADER, build_info::VERSION))
+}
@@ -9,6 +9,8 @@ use sqlx::FromRow;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::models::{Issue, IssuePriority};
+
 // =============================================================================
 // Channel Type Enum
 // =============================================================================
@@ -92,6 +94,91 @@ pub struct NotificationChannel {
     pub updated_at: DateTime<Utc>,
 }
 
+impl NotificationChannel {
+    /// The channel as shown in API responses, with the webhook secret and
+    /// header values replaced by [`MASKED_HEADER_VALUE`]
+    pub fn masked(mut self) -> Self {
+        if self.channel_type == ChannelType::Webhook {
+            if let Some(secret) = self.config.get_mut("secret") {
+                if secret.as_str().is_some_and(|s| !s.is_empty()) {
+                    *secret = serde_json::Value::from(MASKED_HEADER_VALUE);
+                }
+            }
+            if let Some(headers) = self
+                .config
+                .get_mut("headers")
+                .and_then(|h| h.as_object_mut())
+            {
+                for value in headers.values_mut() {
+                    *value = serde_json::Value::from(MASKED_HEADER_VALUE);
+                }
+            }
+        }
+        self
+    }
+
+    /// Puts back the stored secret and header values in `config` that are
+    /// still masked, so a config read from the API can be sent back unchanged
+    pub fn unmask(&self, config: &mut serde_json::Value) {
+        if let Some(secret) = config.get_mut("secret") {
+            if secret.as_str() == Some(MASKED_HEADER_VALUE) {
+                if let Some(stored) = self.config.get("secret") {
+                    *secret = stored.clone();
+                }
+            }
+        }
+
+        let stored = self.config.get("headers").and_then(|h| h.as_object());
+        let Some(headers) = config.get_mut("headers").and_then(|h| h.as_object_mut()) else {
+            return;
+        };
+        for (name, value) in headers.iter_mut() {
+            if value.as_str() == Some(MASKED_HEADER_VALUE) {
+                if let Some(stored) = stored.and_then(|s| s.get(name)) {
+                    *value = stored.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Delivery health of a notification channel
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ChannelHealth {
+    pub id: i32,
+    pub name: String,
+    pub channel_type: ChannelType,
+    pub is_enabled: bool,
+    /// Consecutive failed deliveries since the last success or reset
+    pub failure_streak: i32,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    pub last_failure_message: Option<String>,
+    /// When the channel was disabled for failing, if it still is
+    pub auto_disabled_at: Option<DateTime<Utc>>,
+}
+
+/// Delivery stats of a notification channel over a period, from its
+/// alert history
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ChannelStats {
+    pub channel_id: i32,
+    /// Window the deliveries were counted in, e.g. `24h`
+    #[sqlx(default)]
+    pub period: String,
+    /// Alerts the channel tried to deliver, not counting skipped ones
+    pub attempts: i64,
+    /// Alerts delivered
+    pub successes: i64,
+    /// Alerts that failed, including ones waiting for a retry
+    pub failures: i64,
+    /// Consecutive failed deliveries since the last success or reset
+    pub failure_count: i32,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Successes out of finished deliveries, null when there were none
+    pub success_rate: Option<f64>,
+}
+
 /// DTO for creating a notification channel
 #[derive(Debug, Deserialize)]
 pub struct CreateNotificationChannel {
@@ -114,18 +201,72 @@ pub struct UpdateNotificationChannel {
     pub is_enabled: Option<bool>,
 }
 
+/// Query parameters for deleting a notification channel
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteChannelQuery {
+    /// Detach the channel from the alert rules using it and delete it anyway
+    #[serde(default)]
+    pub force: bool,
+}
+
 // =============================================================================
 // Channel Configuration Types
 // =============================================================================
 
+/// Shown instead of webhook secrets and header values in channel responses
+pub const MASKED_HEADER_VALUE: &str = "********";
+
 /// Webhook channel configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookConfig {
     pub url: String,
+    /// Key of the `X-Rustrak-Signature` HMAC; masked in responses
     #[serde(default)]
     pub secret: Option<String>,
+    /// HTTP method of the delivery
+    #[serde(default)]
+    pub method: WebhookMethod,
+    /// Extra headers, e.g. a receiver's API key; masked in responses
     #[serde(default)]
     pub headers: Option<HashMap<String, String>>,
+    /// Shape of the body sent when there's no template
+    #[serde(default)]
+    pub payload_style: WebhookPayloadStyle,
+    /// Set to false to accept any certificate from this receiver
+    #[serde(default = "default_verify_tls")]
+    pub verify_tls: bool,
+    /// Max seconds for a delivery to this receiver, instead of
+    /// `OUTBOUND_DELIVERY_TIMEOUT_SECS`
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Handlebars template rendering the JSON body, instead of the payload
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+fn default_verify_tls() -> bool {
+    true
+}
+
+/// HTTP method a webhook is delivered with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WebhookMethod {
+    #[default]
+    Post,
+    Put,
+}
+
+/// Body of a webhook delivery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookPayloadStyle {
+    /// The alert payload as is
+    #[default]
+    Full,
+    /// The payload flattened to a single object of strings, with nested keys
+    /// joined by `_`, for receivers that can't read nested JSON
+    Flat,
 }
 
 /// Email channel configuration
@@ -154,6 +295,10 @@ pub struct SlackConfig {
     pub username: Option<String>,
     #[serde(default)]
     pub icon_emoji: Option<String>,
+    /// Handlebars template rendering the message text, instead of the
+    /// built-in blocks
+    #[serde(default)]
+    pub template: Option<String>,
 }
 
 // =============================================================================
@@ -170,11 +315,72 @@ pub struct AlertRule {
     pub is_enabled: bool,
     pub conditions: serde_json::Value,
     pub cooldown_minutes: i32,
+    /// Digest window in minutes; 0 sends every alert immediately
+    pub digest_minutes: i32,
     pub last_triggered_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Issue levels from least to most severe
+pub const ALERT_LEVELS: &[&str] = &["debug", "info", "warning", "error", "fatal"];
+
+/// Parsed `conditions` of an alert rule
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AlertConditions {
+    #[serde(default)]
+    pub filters: Option<AlertFilters>,
+}
+
+/// Limits which issues trigger a rule; every filter that is set must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertFilters {
+    /// Least severe level that triggers, e.g. `error` for errors and fatals
+    #[serde(default)]
+    pub min_level: Option<String>,
+    /// Environments that trigger; empty for any
+    #[serde(default)]
+    pub environments: Vec<String>,
+    /// Text the issue title must contain, ignoring case
+    #[serde(default)]
+    pub title_contains: Option<String>,
+}
+
+impl AlertFilters {
+    /// Whether an issue, seen in `environment`, passes the filters
+    ///
+    /// Issues without a level count as errors, like in the SDKs.
+    pub fn matches(&self, issue: &Issue, environment: Option<&str>) -> bool {
+        if let Some(min_level) = &self.min_level {
+            let level = issue.level.as_deref().unwrap_or("error");
+            match (level_rank(level), level_rank(min_level)) {
+                (Some(level), Some(min_level)) if level >= min_level => {}
+                _ => return false,
+            }
+        }
+
+        if !self.environments.is_empty()
+            && !environment.is_some_and(|env| self.environments.iter().any(|e| e == env))
+        {
+            return false;
+        }
+
+        if let Some(text) = &self.title_contains {
+            let title = issue.title(usize::MAX).to_lowercase();
+            if !title.contains(&text.to_lowercase()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn level_rank(level: &str) -> Option<usize> {
+    ALERT_LEVELS.iter().position(|l| *l == level)
+}
+
 /// DTO for creating an alert rule
 #[derive(Debug, Deserialize)]
 pub struct CreateAlertRule {
@@ -185,6 +391,8 @@ pub struct CreateAlertRule {
     #[serde(default)]
     pub cooldown_minutes: i32,
     #[serde(default)]
+    pub digest_minutes: i32,
+    #[serde(default)]
     pub channel_ids: Vec<i32>,
 }
 
@@ -199,6 +407,7 @@ pub struct UpdateAlertRule {
     pub is_enabled: Option<bool>,
     pub conditions: Option<serde_json::Value>,
     pub cooldown_minutes: Option<i32>,
+    pub digest_minutes: Option<i32>,
     pub channel_ids: Option<Vec<i32>>,
 }
 
@@ -212,6 +421,7 @@ pub struct AlertRuleResponse {
     pub is_enabled: bool,
     pub conditions: serde_json::Value,
     pub cooldown_minutes: i32,
+    pub digest_minutes: i32,
     pub last_triggered_at: Option<DateTime<Utc>>,
     pub channel_ids: Vec<i32>,
     pub created_at: DateTime<Utc>,
@@ -229,6 +439,7 @@ impl AlertRule {
             is_enabled: self.is_enabled,
             conditions: self.conditions.clone(),
             cooldown_minutes: self.cooldown_minutes,
+            digest_minutes: self.digest_minutes,
             last_triggered_at: self.last_triggered_at,
             channel_ids,
             created_at: self.created_at,
@@ -267,8 +478,74 @@ pub struct AlertHistory {
 // =============================================================================
 
 /// Payload sent to notification channels
+///
+/// Serialized untagged, so single-issue alerts keep their original shape and
+/// digests carry an `issues` list instead of `issue`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AlertPayload {
+    /// A single issue, sent as soon as it triggers
+    Issue(IssueAlert),
+    /// Several issues batched over a rule's digest window
+    Digest(DigestAlert),
+}
+
+impl AlertPayload {
+    /// Unique alert ID for idempotency
+    pub fn alert_id(&self) -> &str {
+        match self {
+            AlertPayload::Issue(alert) => &alert.alert_id,
+            AlertPayload::Digest(alert) => &alert.alert_id,
+        }
+    }
+
+    /// Type of alert (new_issue, regression, unmute)
+    pub fn alert_type(&self) -> &str {
+        match self {
+            AlertPayload::Issue(alert) => &alert.alert_type,
+            AlertPayload::Digest(alert) => &alert.alert_type,
+        }
+    }
+
+    /// Project the alert belongs to
+    pub fn project(&self) -> &ProjectInfo {
+        match self {
+            AlertPayload::Issue(alert) => &alert.project,
+            AlertPayload::Digest(alert) => &alert.project,
+        }
+    }
+
+    /// Alert sent by the channel test, also used to check templates
+    pub fn sample() -> Self {
+        AlertPayload::Issue(IssueAlert {
+            alert_id: format!("test-{}", Utc::now().timestamp_millis()),
+            alert_type: "test".to_string(),
+            triggered_at: Utc::now(),
+            project: ProjectInfo {
+                id: 0,
+                name: "Test Project".to_string(),
+                slug: "test-project".to_string(),
+            },
+            issue: IssueInfo {
+                id: "00000000-0000-0000-0000-000000000000".to_string(),
+                short_id: "TEST-1".to_string(),
+                title: "This is a test alert from Rustrak".to_string(),
+                level: Some("info".to_string()),
+                first_seen: Utc::now(),
+                last_seen: Utc::now(),
+                event_count: 1,
+                is_unhandled: false,
+                priority: IssuePriority::Medium,
+            },
+            issue_url: "https://example.com/test".to_string(),
+            actor: "Rustrak Test".to_string(),
+        })
+    }
+}
+
+/// Alert for a single issue
 #[derive(Debug, Clone, Serialize)]
-pub struct AlertPayload {
+pub struct IssueAlert {
     /// Unique alert ID for idempotency
     pub alert_id: String,
     /// Type of alert (new_issue, regression, unmute)
@@ -285,6 +562,36 @@ pub struct AlertPayload {
     pub actor: String,
 }
 
+/// Summary of the issues that triggered a rule during its digest window
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestAlert {
+    /// Unique alert ID for idempotency
+    pub alert_id: String,
+    /// Type of alert (new_issue, regression, unmute)
+    pub alert_type: String,
+    /// Timestamp when the digest was sent
+    pub triggered_at: DateTime<Utc>,
+    /// Timestamp of the first issue in the window
+    pub window_start: DateTime<Utc>,
+    /// Project information
+    pub project: ProjectInfo,
+    /// Issues in the order they triggered
+    pub issues: Vec<DigestIssue>,
+    /// URL to view the project's issues in the dashboard
+    pub project_url: String,
+    /// Actor that triggered the alert
+    pub actor: String,
+}
+
+/// Issue entry of a digest
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestIssue {
+    #[serde(flatten)]
+    pub issue: IssueInfo,
+    /// URL to view the issue in the dashboard
+    pub issue_url: String,
+}
+
 /// Project information for alert payload
 #[derive(Debug, Clone, Serialize)]
 pub struct ProjectInfo {
@@ -303,4 +610,7 @@ pub struct IssueInfo {
     pub first_seen: DateTime<Utc>,
     pub last_seen: DateTime<Utc>,
     pub event_count: i32,
+    /// Whether any event of the issue was unhandled, i.e. a crash
+    pub is_unhandled: bool,
+    pub priority: IssuePriority,
 }
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Access to sensitive data that is recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    /// Download of the end users affected by an issue
+    #[serde(rename = "issue_users.export")]
+    IssueUsersExport,
+}
+
+impl AuditAction {
+    /// Name of the action in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::IssueUsersExport => "issue_users.export",
+        }
+    }
+}
+
+/// An entry of the audit log
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    /// User who did it; unset once the user is deleted
+    pub user_id: Option<i32>,
+    pub project_id: Option<i32>,
+    pub action: String,
+    /// What the action applied to, like the issue and filters of an export
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
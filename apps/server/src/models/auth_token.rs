@@ -19,6 +19,15 @@ pub struct CreateAuthToken {
     pub description: Option<String>,
 }
 
+/// DTO for updating a token
+///
+/// An empty description clears it; fields left out are unchanged.
+#[derive(Debug, Deserialize)]
+pub struct UpdateAuthToken {
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
 /// Response that includes the full token (only on creation)
 #[derive(Debug, Serialize)]
 pub struct AuthTokenCreatedResponse {
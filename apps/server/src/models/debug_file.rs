@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// Debug file - a Breakpad symbol file for one module (contents not loaded)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DebugFile {
+    pub id: i32,
+    pub project_id: i32,
+    /// Debug ID of the module, in the format used by `debug_meta.images`
+    pub debug_id: String,
+    /// Module name from the symbol file, e.g. "app.exe" or "libfoo.so"
+    pub code_file: String,
+    pub os: String,
+    pub arch: String,
+    pub file_size: i32,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use ipnetwork::IpNetwork;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
@@ -31,6 +31,13 @@ pub struct Event {
     pub sdk_version: String,
     pub remote_addr: Option<IpNetwork>,
     pub digest_order: i32,
+    /// Distribution of the release, e.g. a mobile build number; empty if unset
+    pub dist: String,
+    /// Whether an exception of the event wasn't caught
+    pub is_unhandled: bool,
+    /// Whether the raw payload was dropped by payload retention, leaving
+    /// `data` empty
+    pub payload_pruned: bool,
 }
 
 /// Response for API (list view)
@@ -44,7 +51,10 @@ pub struct EventResponse {
     pub level: String,
     pub platform: String,
     pub release: String,
+    pub dist: String,
     pub environment: String,
+    pub is_unhandled: bool,
+    pub payload_pruned: bool,
 }
 
 /// Response for API (full detail)
@@ -59,13 +69,46 @@ pub struct EventDetailResponse {
     pub level: String,
     pub platform: String,
     pub release: String,
+    pub dist: String,
     pub environment: String,
+    pub is_unhandled: bool,
     pub server_name: String,
     pub sdk_name: String,
     pub sdk_version: String,
+    pub payload_pruned: bool,
+    /// Full event payload; null once pruned
     pub data: serde_json::Value,
 }
 
+/// Number of an issue's events at one level
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventLevelCount {
+    pub level: String,
+    pub count: i64,
+}
+
+/// An issue's events broken down by level, most frequent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LevelBreakdownResponse {
+    pub total: i64,
+    pub levels: Vec<EventLevelCount>,
+}
+
+/// Number of an issue's events in one transaction
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventTransactionCount {
+    pub transaction: String,
+    pub count: i64,
+}
+
+/// Transactions an issue's events occurred in, most affected first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionBreakdownResponse {
+    /// Events that reported a transaction, including those not listed
+    pub total: i64,
+    pub transactions: Vec<EventTransactionCount>,
+}
+
 impl Event {
     /// Generates the event title from type and value
     pub fn title(&self) -> String {
@@ -88,7 +131,10 @@ impl Event {
             level: self.level.clone(),
             platform: self.platform.clone(),
             release: self.release.clone(),
+            dist: self.dist.clone(),
             environment: self.environment.clone(),
+            is_unhandled: self.is_unhandled,
+            payload_pruned: self.payload_pruned,
         }
     }
 
@@ -104,11 +150,18 @@ impl Event {
             level: self.level.clone(),
             platform: self.platform.clone(),
             release: self.release.clone(),
+            dist: self.dist.clone(),
             environment: self.environment.clone(),
+            is_unhandled: self.is_unhandled,
             server_name: self.server_name.clone(),
             sdk_name: self.sdk_name.clone(),
             sdk_version: self.sdk_version.clone(),
-            data: self.data.clone(),
+            payload_pruned: self.payload_pruned,
+            data: if self.payload_pruned {
+                serde_json::Value::Null
+            } else {
+                self.data.clone()
+            },
         }
     }
 }
@@ -3,6 +3,9 @@ use serde::Serialize;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// Separator between the components of a grouping key (diamond character)
+pub const GROUPING_SEPARATOR: &str = " ⋄ ";
+
 /// Grouping model - maps a grouping key to an issue
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct Grouping {
@@ -13,3 +16,31 @@ pub struct Grouping {
     pub grouping_key_hash: String,
     pub created_at: DateTime<Utc>,
 }
+
+/// A grouping key of an issue, for debugging how events are grouped
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupingResponse {
+    /// Readable key, e.g. `ValueError: bad input ⋄ /checkout`
+    pub grouping_key: String,
+    /// The key split into its parts: title, transaction, exception chain,
+    /// crashing frame, or the custom fingerprint's parts
+    pub components: Vec<String>,
+    /// SHA256 of the key, used to match new events
+    pub grouping_key_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Grouping {
+    pub fn to_response(&self) -> GroupingResponse {
+        GroupingResponse {
+            grouping_key: self.grouping_key.clone(),
+            components: self
+                .grouping_key
+                .split(GROUPING_SEPARATOR)
+                .map(str::to_string)
+                .collect(),
+            grouping_key_hash: self.grouping_key_hash.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
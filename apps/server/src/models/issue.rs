@@ -3,6 +3,13 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::error::{codes, AppError, AppResult};
+use crate::models::event::EventDetailResponse;
+use crate::models::{EventCounts, GroupingResponse};
+
+/// Stored as the transaction of events that don't report one
+pub const NO_TRANSACTION: &str = "<no transaction>";
+
 /// Issue model - a group of similar events
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct Issue {
@@ -13,6 +20,12 @@ pub struct Issue {
     pub last_seen: DateTime<Utc>,
     pub digested_event_count: i32,
     pub stored_event_count: i32,
+    /// Digest order of the issue's latest event; unlike the counters it
+    /// never goes down
+    pub last_event_order: i32,
+    /// Latest stored event, kept by the digest worker; NULL when none is
+    /// stored
+    pub last_event_id: Option<Uuid>,
     pub calculated_type: String,
     pub calculated_value: String,
     pub transaction: String,
@@ -23,7 +36,104 @@ pub struct Issue {
     pub platform: Option<String>,
     pub is_resolved: bool,
     pub is_muted: bool,
-    pub is_deleted: bool,
+    /// When the issue was moved to the trash; NULL while it's live
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// User who resolved the issue; NULL for automated resolutions
+    pub resolved_by: Option<i32>,
+    /// Resolved by the auto-resolve job after going quiet; a new event
+    /// reopens it
+    pub auto_resolved: bool,
+    /// Commit that fixed the issue, as given when resolving it
+    pub resolved_commit: Option<String>,
+    /// Pull request that fixed the issue, as given when resolving it
+    pub resolved_pr_url: Option<String>,
+    /// Number of chained exceptions; the title shows the root cause
+    pub exception_chain_length: i32,
+    /// Whether any event of the issue was unhandled, i.e. a crash
+    pub is_unhandled: bool,
+    /// Share of new events that are stored; NULL stores them all
+    pub sample_rate: Option<f64>,
+    /// Times the issue was reopened by a new event after being resolved
+    pub regression_count: i32,
+    pub priority: IssuePriority,
+    /// Whether a user set the priority; it's then never changed automatically
+    pub priority_is_manual: bool,
+    /// Clock hour the last event was counted in towards a priority spike
+    #[serde(skip)]
+    pub spike_window_start: Option<DateTime<Utc>>,
+    /// `digested_event_count` when that hour started or the priority last
+    /// changed; the events since make up the spike
+    #[serde(skip)]
+    pub spike_base_event_count: i32,
+}
+
+/// How much attention an issue needs
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum IssuePriority {
+    Low,
+    Medium,
+    High,
+}
+
+impl IssuePriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssuePriority::Low => "low",
+            IssuePriority::Medium => "medium",
+            IssuePriority::High => "high",
+        }
+    }
+
+    /// Priority of a new issue, from its first event
+    ///
+    /// Unhandled fatal errors are high, warnings and below low, the rest
+    /// medium.
+    pub fn from_event(level: Option<&str>, is_unhandled: bool) -> Self {
+        match level {
+            Some("fatal") if is_unhandled => IssuePriority::High,
+            Some("warning" | "info" | "debug") => IssuePriority::Low,
+            _ => IssuePriority::Medium,
+        }
+    }
+
+    /// The next priority up, None for high
+    pub fn raised(&self) -> Option<Self> {
+        match self {
+            IssuePriority::Low => Some(IssuePriority::Medium),
+            IssuePriority::Medium => Some(IssuePriority::High),
+            IssuePriority::High => None,
+        }
+    }
+}
+
+impl std::fmt::Display for IssuePriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A change of an issue's priority
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IssuePriorityChange {
+    pub old_priority: IssuePriority,
+    pub new_priority: IssuePriority,
+    /// Set by a user rather than raised on an event spike
+    pub is_manual: bool,
+    /// User who set it; null for automatic changes
+    pub changed_by: Option<i32>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Something that happened to an issue, such as `auto_resolved`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IssueActivity {
+    pub kind: String,
+    pub created_at: DateTime<Utc>,
 }
 
 /// Response for API
@@ -34,13 +144,208 @@ pub struct IssueResponse {
     pub short_id: String,
     pub title: String,
     pub value: String,
+    /// Where the error happened, e.g. the transaction or `module in function`
+    pub culprit: Option<String>,
     pub first_seen: DateTime<Utc>,
     pub last_seen: DateTime<Utc>,
+    /// Events digested into the issue, including those sampled out
     pub event_count: i32,
+    /// Events of the issue that are stored
+    pub stored_event_count: i32,
+    /// Latest stored event; null when none is stored
+    pub last_event_id: Option<Uuid>,
+    /// Share of new events that are stored; null stores them all
+    pub sample_rate: Option<f64>,
     pub level: Option<String>,
     pub platform: Option<String>,
     pub is_resolved: bool,
     pub is_muted: bool,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<i32>,
+    /// Whether the issue was resolved for going quiet rather than by a user
+    pub auto_resolved: bool,
+    /// Commit that fixed the issue; null unless given when resolving it
+    pub resolved_commit: Option<String>,
+    /// Pull request that fixed the issue; null unless given when resolving it
+    pub resolved_pr_url: Option<String>,
+    /// Times the issue was reopened by a new event after being resolved
+    pub regression_count: i32,
+    /// When the issue was reopened, most recent first; only included in the
+    /// issue detail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regressed_at: Option<Vec<DateTime<Utc>>>,
+    pub priority: IssuePriority,
+    /// Whether a user set the priority; it's then never changed automatically
+    pub priority_is_manual: bool,
+    /// Priority changes, most recent first; only included in the issue detail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_history: Option<Vec<IssuePriorityChange>>,
+    /// Activity of the issue, most recent first; only included in the
+    /// issue detail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity: Option<Vec<IssueActivity>>,
+    pub exception_chain_length: i32,
+    /// Whether any event of the issue was unhandled, i.e. a crash
+    pub is_unhandled: bool,
+    /// When the issue was deleted; only included for issues in the trash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Whether the requesting user has opened the issue
+    pub is_seen: bool,
+    /// Users who have opened the issue; only included in the issue detail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seen_by: Option<Vec<IssueSeenBy>>,
+    /// Grouping keys routing events to the issue; only included in the
+    /// issue detail with `?include=grouping`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groupings: Option<Vec<GroupingResponse>>,
+    /// Latest stored event with its data; only included in the issue detail
+    /// with `?include=latest_event`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_event: Option<EventDetailResponse>,
+    /// Events per hour over the last 24 hours, oldest first; only included
+    /// in the issue list with `?include=sparkline`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparkline: Option<Vec<HourlyEventCount>>,
+}
+
+/// Events of an issue in one hour, in UTC
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct HourlyEventCount {
+    pub hour: DateTime<Utc>,
+    pub events: i64,
+}
+
+/// Counters of an issue before and after a recount
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueRecount {
+    pub issue_id: Uuid,
+    pub before: EventCounts,
+    pub after: EventCounts,
+}
+
+/// A user who has opened an issue
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IssueSeenBy {
+    pub user_id: i32,
+    pub email: String,
+    /// Last time the user opened the issue
+    pub seen_at: DateTime<Utc>,
+}
+
+/// An end user an issue's events were sent for, from the SDK's `user`
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct IssueUser {
+    /// The user's id, or their email or username without one
+    pub user_key: String,
+    pub user_id: Option<String>,
+    pub email: Option<String>,
+    pub username: Option<String>,
+    /// First event of the issue sent for the user
+    pub first_seen: DateTime<Utc>,
+    /// Latest event of the issue sent for the user
+    pub last_seen: DateTime<Utc>,
+    pub event_count: i32,
+}
+
+/// Query parameters for the affected users CSV export
+#[derive(Debug, Default, Deserialize)]
+pub struct IssueUsersExportQuery {
+    /// Only users first affected at or after this time
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Count of an issue's events seen with one context value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueContextValue {
+    pub value: String,
+    pub count: i32,
+    /// Share of the events in the dimension, in percent
+    pub percentage: f64,
+}
+
+/// Most common values of one context dimension
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueContextBreakdown {
+    /// Events that reported this context
+    pub total: i64,
+    pub values: Vec<IssueContextValue>,
+}
+
+/// "Seen on" breakdowns of an issue
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueContextsResponse {
+    pub os: IssueContextBreakdown,
+    pub browser: IssueContextBreakdown,
+    pub runtime: IssueContextBreakdown,
+    /// Distributions (builds) of the release the events were sent from
+    pub dist: IssueContextBreakdown,
+}
+
+/// Issues sharing a type or transaction, with their events in a period
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IssueAggregate {
+    /// The shared `calculated_type` or `transaction`
+    pub value: String,
+    /// Issues of the group with events in the period
+    pub issue_count: i64,
+    /// Events of those issues in the period
+    pub event_count: i64,
+    /// Issue of the group seen most recently, to drill down into
+    pub latest_issue_id: Uuid,
+}
+
+/// Response of the issue aggregation endpoint
+#[derive(Debug, Serialize)]
+pub struct IssueAggregateResponse {
+    pub group_by: &'static str,
+    pub period: String,
+    pub groups: Vec<IssueAggregate>,
+}
+
+/// Query parameters for the issue detail endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct IssueDetailQuery {
+    /// Comma-separated extra fields; `grouping` adds the grouping keys,
+    /// `latest_event` the latest stored event
+    pub include: Option<String>,
+}
+
+impl IssueDetailQuery {
+    /// Whether `field` was asked for in `include`
+    pub fn includes(&self, field: &str) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|include| include.split(',').any(|f| f.trim() == field))
+    }
+}
+
+/// Query parameters for the issue bundle download
+#[derive(Debug, Default, Deserialize)]
+pub struct IssueBundleQuery {
+    /// Number of most recent events to embed
+    pub events: Option<i64>,
+}
+
+impl IssueBundleQuery {
+    pub const DEFAULT_EVENTS: i64 = 10;
+    pub const MAX_EVENTS: i64 = 50;
+
+    /// Requested event count, clamped to `0..=MAX_EVENTS`
+    pub fn event_count(&self) -> i64 {
+        self.events
+            .unwrap_or(Self::DEFAULT_EVENTS)
+            .clamp(0, Self::MAX_EVENTS)
+    }
+}
+
+/// Self-contained JSON document of an issue and its latest events
+#[derive(Debug, Serialize)]
+pub struct IssueBundle {
+    pub exported_at: DateTime<Utc>,
+    pub issue: IssueResponse,
+    /// Most recent events first, with their full data
+    pub events: Vec<EventDetailResponse>,
 }
 
 /// Request to update issue state
@@ -48,17 +353,121 @@ pub struct IssueResponse {
 pub struct UpdateIssueState {
     pub is_resolved: Option<bool>,
     pub is_muted: Option<bool>,
+    /// Share of new events to store, above 0 and up to 1; 1 stores them all
+    pub sample_rate: Option<f64>,
+    /// Sets the priority, which then stays as set
+    pub priority: Option<IssuePriority>,
+}
+
+/// Request to resolve an issue, with the change that fixed it
+#[derive(Debug, Default, Deserialize)]
+pub struct ResolveIssue {
+    /// Commit SHA or other reference of the fix
+    pub commit: Option<String>,
+    /// URL of the pull request with the fix
+    pub pr_url: Option<String>,
+}
+
+impl ResolveIssue {
+    /// Longest commit reference accepted
+    pub const MAX_COMMIT_LEN: usize = 100;
+
+    /// Longest pull request URL accepted
+    pub const MAX_PR_URL_LEN: usize = 2048;
+
+    /// Checks the references, trimmed; blank ones count as not given
+    pub fn validate(&self) -> AppResult<Self> {
+        let commit = self
+            .commit
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+        if let Some(commit) = commit {
+            if commit.chars().count() > Self::MAX_COMMIT_LEN {
+                return Err(AppError::invalid_field(
+                    "commit",
+                    codes::VALIDATION_TOO_LONG,
+                    format!("commit cannot exceed {} characters", Self::MAX_COMMIT_LEN),
+                ));
+            }
+            if commit.chars().any(char::is_whitespace) {
+                return Err(AppError::invalid_field(
+                    "commit",
+                    codes::VALIDATION_INVALID_FORMAT,
+                    "commit cannot contain spaces",
+                ));
+            }
+        }
+
+        let pr_url = self
+            .pr_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty());
+        if let Some(pr_url) = pr_url {
+            if pr_url.len() > Self::MAX_PR_URL_LEN {
+                return Err(AppError::invalid_field(
+                    "pr_url",
+                    codes::VALIDATION_TOO_LONG,
+                    format!("pr_url cannot exceed {} characters", Self::MAX_PR_URL_LEN),
+                ));
+            }
+            let is_http = url::Url::parse(pr_url)
+                .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.has_host());
+            if !is_http {
+                return Err(AppError::invalid_field(
+                    "pr_url",
+                    codes::VALIDATION_INVALID_FORMAT,
+                    "pr_url must be an http(s) URL",
+                ));
+            }
+        }
+
+        Ok(Self {
+            commit: commit.map(str::to_string),
+            pr_url: pr_url.map(str::to_string),
+        })
+    }
 }
 
 impl Issue {
     /// Generates the issue title from type and value
-    pub fn title(&self) -> String {
-        if self.calculated_value.is_empty() {
+    ///
+    /// Titles longer than `max_length` characters are cut and end in `…`;
+    /// the full value stays in `calculated_value`.
+    pub fn title(&self, max_length: usize) -> String {
+        let title = if self.calculated_value.is_empty() {
             self.calculated_type.clone()
         } else {
             let first_line = self.calculated_value.lines().next().unwrap_or("");
             format!("{}: {}", self.calculated_type, first_line)
+        };
+
+        if title.chars().count() <= max_length {
+            return title;
+        }
+        let kept: String = title.chars().take(max_length.saturating_sub(1)).collect();
+        format!("{}…", kept.trim_end())
+    }
+
+    /// Describes where the error happened, from the latest event
+    ///
+    /// Prefers the transaction, then the top in-app frame as
+    /// `module in function`, then the frame's filename.
+    pub fn culprit(&self) -> Option<String> {
+        if !self.transaction.is_empty() && self.transaction != NO_TRANSACTION {
+            return Some(self.transaction.clone());
+        }
+        if !self.last_frame_module.is_empty() && !self.last_frame_function.is_empty() {
+            return Some(format!(
+                "{} in {}",
+                self.last_frame_module, self.last_frame_function
+            ));
+        }
+        if !self.last_frame_filename.is_empty() {
+            return Some(self.last_frame_filename.clone());
         }
+        None
     }
 
     /// Generates the short_id (e.g., "PROJECT-1")
@@ -67,20 +476,43 @@ impl Issue {
     }
 
     /// Converts to API response format
-    pub fn to_response(&self, project_slug: &str) -> IssueResponse {
+    pub fn to_response(&self, project_slug: &str, max_title_length: usize) -> IssueResponse {
         IssueResponse {
             id: self.id,
             project_id: self.project_id,
             short_id: self.short_id(project_slug),
-            title: self.title(),
+            title: self.title(max_title_length),
             value: self.calculated_value.clone(),
+            culprit: self.culprit(),
             first_seen: self.first_seen,
             last_seen: self.last_seen,
             event_count: self.digested_event_count,
+            stored_event_count: self.stored_event_count,
+            last_event_id: self.last_event_id,
+            sample_rate: self.sample_rate,
             level: self.level.clone(),
             platform: self.platform.clone(),
             is_resolved: self.is_resolved,
             is_muted: self.is_muted,
+            resolved_at: self.resolved_at,
+            resolved_by: self.resolved_by,
+            auto_resolved: self.auto_resolved,
+            resolved_commit: self.resolved_commit.clone(),
+            resolved_pr_url: self.resolved_pr_url.clone(),
+            regression_count: self.regression_count,
+            regressed_at: None,
+            priority: self.priority,
+            priority_is_manual: self.priority_is_manual,
+            priority_history: None,
+            activity: None,
+            exception_chain_length: self.exception_chain_length,
+            is_unhandled: self.is_unhandled,
+            deleted_at: self.deleted_at,
+            is_seen: false,
+            seen_by: None,
+            groupings: None,
+            latest_event: None,
+            sparkline: None,
         }
     }
 }
@@ -1,21 +1,52 @@
 pub mod alert;
+pub mod audit_log;
 pub mod auth_token;
+pub mod debug_file;
 pub mod event;
 pub mod grouping;
 pub mod installation;
 pub mod issue;
 pub mod project;
+pub mod project_key;
+pub mod project_member;
+pub mod saved_search;
+pub mod team;
 pub mod user;
 
 pub use alert::{
-    AlertHistory, AlertPayload, AlertRule, AlertRuleResponse, AlertStatus, AlertType, ChannelType,
-    CreateAlertRule, CreateNotificationChannel, EmailConfig, IssueInfo, NotificationChannel,
-    ProjectInfo, SlackConfig, UpdateAlertRule, UpdateNotificationChannel, WebhookConfig,
+    AlertConditions, AlertFilters, AlertHistory, AlertPayload, AlertRule, AlertRuleResponse,
+    AlertStatus, AlertType, ChannelHealth, ChannelStats, ChannelType, CreateAlertRule,
+    CreateNotificationChannel, DeleteChannelQuery, DigestAlert, DigestIssue, EmailConfig,
+    IssueAlert, IssueInfo, NotificationChannel, ProjectInfo, SlackConfig, UpdateAlertRule,
+    UpdateNotificationChannel, WebhookConfig, WebhookMethod, WebhookPayloadStyle,
+    MASKED_HEADER_VALUE,
 };
-pub use auth_token::{AuthToken, CreateAuthToken};
-pub use event::Event;
-pub use grouping::Grouping;
+pub use audit_log::{AuditAction, AuditLogEntry};
+pub use auth_token::{AuthToken, CreateAuthToken, UpdateAuthToken};
+pub use debug_file::DebugFile;
+pub use event::{
+    Event, EventLevelCount, EventTransactionCount, LevelBreakdownResponse,
+    TransactionBreakdownResponse,
+};
+pub use grouping::{Grouping, GroupingResponse};
 pub use installation::Installation;
-pub use issue::{Issue, UpdateIssueState};
-pub use project::{CreateProject, Project, UpdateProject};
-pub use user::{CreateUserRequest, LoginRequest, User};
+pub use issue::{
+    HourlyEventCount, Issue, IssueActivity, IssueAggregate, IssueAggregateResponse, IssueBundle,
+    IssueBundleQuery, IssueContextBreakdown, IssueContextValue, IssueContextsResponse,
+    IssueDetailQuery, IssuePriority, IssuePriorityChange, IssueRecount, IssueResponse, IssueSeenBy,
+    IssueUser, IssueUsersExportQuery, ResolveIssue, UpdateIssueState,
+};
+pub use project::{
+    CreateProject, DailyEventCount, DailyStatsQuery, DailyStatsResponse, EventCounts,
+    OnboardingQuery, Project, ProjectOnboarding, ProjectOverview, ProjectRecount, ProjectStats,
+    SdkSummary, UpdateProject,
+};
+pub use project_key::{
+    CreateProjectKey, ProjectKey, ProjectKeyCreatedResponse, ProjectKeyResponse, UpdateProjectKey,
+};
+pub use project_member::{AddProjectMember, ProjectMember, ProjectRole};
+pub use saved_search::{
+    CreateSavedSearch, ListSavedSearchesQuery, SavedSearch, SavedSearchResponse,
+};
+pub use team::{CreateTeam, DeleteTeamQuery, Team, TeamResponse, TeamSummary, UpdateTeam};
+pub use user::{Actor, CreateUserRequest, LoginRequest, User, UserOverview};
@@ -1,7 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use uuid::Uuid;
+
+use crate::models::{IssueRecount, ProjectKey, ProjectKeyResponse, TeamSummary};
+use crate::pagination::IssueSort;
 
 /// Project model for reading from the database
 #[derive(Debug, Clone, Serialize, FromRow)]
@@ -9,7 +11,6 @@ pub struct Project {
     pub id: i32,
     pub name: String,
     pub slug: String,
-    pub sentry_key: Uuid,
     pub stored_event_count: i32,
     pub digested_event_count: i32,
     pub created_at: DateTime<Utc>,
@@ -22,6 +23,19 @@ pub struct Project {
     pub quota_exceeded_reason: Option<String>,
     #[serde(skip_serializing)]
     pub next_quota_check: i64,
+    pub team_id: Option<i32>,
+    /// Origin patterns browser events are accepted from; empty accepts any
+    pub allowed_domains: Vec<String>,
+    /// Days without events after which open issues are resolved
+    pub auto_resolve_days: Option<i32>,
+    /// Module prefixes whose frames are always in-app
+    pub in_app_include: Vec<String>,
+    /// Module prefixes whose frames are never in-app, unless included
+    pub in_app_exclude: Vec<String>,
+    /// Order of the issue list when the request doesn't pass `sort`
+    pub default_issue_sort: IssueSort,
+    /// IANA timezone daily stats are bucketed in; UTC when unset
+    pub timezone: Option<String>,
 }
 
 /// DTO for creating a new project
@@ -30,55 +44,173 @@ pub struct CreateProject {
     pub name: String,
     #[serde(default)]
     pub slug: Option<String>,
+    /// Team to create the project in (inherits the team's default channels)
+    #[serde(default)]
+    pub team_id: Option<i32>,
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`)
+fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 /// DTO for updating a project
 #[derive(Debug, Deserialize)]
 pub struct UpdateProject {
     pub name: Option<String>,
+    /// Moves the project to another team; `null` takes it out of its team
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub team_id: Option<Option<i32>>,
+    /// Replaces the allowed domains; an empty list accepts any origin
+    pub allowed_domains: Option<Vec<String>>,
+    /// Accepts a pattern matching every origin, like `*`, in `allowed_domains`
+    #[serde(default)]
+    pub confirm_wildcard: bool,
+    /// Days without events after which open issues are resolved; 0 turns
+    /// auto-resolving off
+    pub auto_resolve_days: Option<i32>,
+    /// Replaces the module prefixes whose frames are always in-app
+    pub in_app_include: Option<Vec<String>>,
+    /// Replaces the module prefixes whose frames are never in-app
+    pub in_app_exclude: Option<Vec<String>>,
+    /// Order of the issue list when the request doesn't pass `sort`
+    pub default_issue_sort: Option<IssueSort>,
+    /// IANA timezone name, e.g. `Asia/Tokyo`; an empty string resets it to UTC
+    pub timezone: Option<String>,
 }
 
-/// Response with DSN included
+/// Response with the project's keys (masked)
 #[derive(Debug, Serialize)]
 pub struct ProjectResponse {
     pub id: i32,
     pub name: String,
     pub slug: String,
-    pub sentry_key: Uuid,
-    pub dsn: String,
+    pub keys: Vec<ProjectKeyResponse>,
+    /// DSN of the default key, only returned when the project is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dsn: Option<String>,
     pub stored_event_count: i32,
     pub digested_event_count: i32,
+    pub team: Option<TeamSummary>,
+    pub allowed_domains: Vec<String>,
+    pub auto_resolve_days: Option<i32>,
+    pub in_app_include: Vec<String>,
+    pub in_app_exclude: Vec<String>,
+    pub default_issue_sort: IssueSort,
+    pub timezone: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Issue and event counts; only included in the project list with `?include=stats`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ProjectStats>,
 }
 
-impl Project {
-    /// Builds the DSN for this project
-    pub fn dsn(&self, base_url: &str) -> String {
-        let key = self.sentry_key.simple().to_string();
-        let host = base_url
-            .trim_start_matches("http://")
-            .trim_start_matches("https://");
-        let scheme = if base_url.starts_with("https") {
-            "https"
-        } else {
-            "http"
-        };
-        format!("{scheme}://{key}@{host}/{}", self.id)
-    }
+/// Activity summary of a project for the dashboard
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, FromRow)]
+pub struct ProjectStats {
+    /// Issues that are neither resolved, muted nor in the trash
+    pub open_issue_count: i64,
+    /// Events digested in the last 24 hours
+    pub events_24h: i64,
+}
+
+/// A project on the dashboard home page, with its activity
+#[derive(Debug, Serialize)]
+pub struct ProjectOverview {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub stats: ProjectStats,
+}
+
+/// Event counters of a project or issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, FromRow)]
+pub struct EventCounts {
+    pub stored_event_count: i32,
+    pub digested_event_count: i32,
+}
+
+/// Counters of a project before and after a recount
+#[derive(Debug, Serialize)]
+pub struct ProjectRecount {
+    pub project_id: i32,
+    pub before: EventCounts,
+    pub after: EventCounts,
+    /// Issues whose counters were wrong, with their old and new values
+    pub issues: Vec<IssueRecount>,
+}
 
-    /// Converts to ProjectResponse with DSN
-    pub fn to_response(&self, base_url: &str) -> ProjectResponse {
+impl Project {
+    /// Converts to ProjectResponse with masked keys and team reference
+    pub fn to_response(&self, keys: &[ProjectKey], team: Option<TeamSummary>) -> ProjectResponse {
         ProjectResponse {
             id: self.id,
             name: self.name.clone(),
             slug: self.slug.clone(),
-            sentry_key: self.sentry_key,
-            dsn: self.dsn(base_url),
+            keys: keys.iter().map(|k| k.to_response()).collect(),
+            dsn: None,
             stored_event_count: self.stored_event_count,
             digested_event_count: self.digested_event_count,
+            team,
+            allowed_domains: self.allowed_domains.clone(),
+            auto_resolve_days: self.auto_resolve_days,
+            in_app_include: self.in_app_include.clone(),
+            in_app_exclude: self.in_app_exclude.clone(),
+            default_issue_sort: self.default_issue_sort,
+            timezone: self.timezone.clone(),
             created_at: self.created_at,
             updated_at: self.updated_at,
+            stats: None,
         }
     }
 }
+
+/// Query parameters for the onboarding endpoint
+#[derive(Debug, Deserialize)]
+pub struct OnboardingQuery {
+    /// Seconds to wait for the first event before answering
+    pub wait: Option<u64>,
+}
+
+/// Query parameters for a project's daily stats
+#[derive(Debug, Deserialize)]
+pub struct DailyStatsQuery {
+    /// Days to return, ending today (default: 14)
+    pub days: Option<i64>,
+    /// IANA timezone overriding the project's for this request
+    pub tz: Option<String>,
+}
+
+/// Events of a project on one day of its timezone
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailyEventCount {
+    pub date: NaiveDate,
+    pub events: i64,
+}
+
+/// Daily event counts of a project, oldest day first
+#[derive(Debug, Serialize)]
+pub struct DailyStatsResponse {
+    /// Timezone the days start and end in
+    pub timezone: String,
+    pub days: Vec<DailyEventCount>,
+}
+
+/// Whether a project has received its first event, for the setup page
+#[derive(Debug, Serialize)]
+pub struct ProjectOnboarding {
+    pub received_first_event: bool,
+    pub first_event_at: Option<DateTime<Utc>>,
+    pub sdk: Option<SdkSummary>,
+}
+
+/// SDK that sent a project's first event
+#[derive(Debug, Serialize)]
+pub struct SdkSummary {
+    pub name: String,
+    pub version: String,
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// ProjectKey model - a sentry_key SDKs authenticate with
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProjectKey {
+    pub id: i32,
+    pub project_id: i32,
+    pub key: Uuid,
+    pub label: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// DTO for creating a new key
+#[derive(Debug, Deserialize)]
+pub struct CreateProjectKey {
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// DTO for updating a key
+#[derive(Debug, Deserialize)]
+pub struct UpdateProjectKey {
+    pub is_active: bool,
+}
+
+/// Response that includes the full key and DSN (only on creation)
+#[derive(Debug, Serialize)]
+pub struct ProjectKeyCreatedResponse {
+    pub id: i32,
+    pub label: String,
+    pub key: Uuid, // Only shown once!
+    pub dsn: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response for listing (key is masked)
+#[derive(Debug, Serialize)]
+pub struct ProjectKeyResponse {
+    pub id: i32,
+    pub label: String,
+    pub key_prefix: String, // First 8 chars only
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectKey {
+    /// How often last_used_at is updated while a key is in use
+    pub const LAST_USED_INTERVAL: Duration = Duration::seconds(60);
+
+    /// Whether last_used_at is old enough to be updated at `now`
+    pub fn last_used_outdated(&self, now: DateTime<Utc>) -> bool {
+        self.last_used_at
+            .is_none_or(|last_used| now - last_used >= Self::LAST_USED_INTERVAL)
+    }
+
+    /// Builds the DSN for this key
+    pub fn dsn(&self, base_url: &str) -> String {
+        let key = self.key.simple().to_string();
+        let host = base_url
+            .trim_start_matches("http://")
+            .trim_start_matches("https://");
+        let scheme = if base_url.starts_with("https") {
+            "https"
+        } else {
+            "http"
+        };
+        format!("{scheme}://{key}@{host}/{}", self.project_id)
+    }
+
+    /// Mask the key for display (show first 8 chars)
+    pub fn to_response(&self) -> ProjectKeyResponse {
+        ProjectKeyResponse {
+            id: self.id,
+            label: self.label.clone(),
+            key_prefix: format!("{}...", &self.key.simple().to_string()[..8]),
+            is_active: self.is_active,
+            created_at: self.created_at,
+            last_used_at: self.last_used_at,
+        }
+    }
+
+    /// Full response with key and DSN (only for creation)
+    pub fn to_created_response(&self, base_url: &str) -> ProjectKeyCreatedResponse {
+        ProjectKeyCreatedResponse {
+            id: self.id,
+            label: self.label.clone(),
+            key: self.key,
+            dsn: self.dsn(base_url),
+            is_active: self.is_active,
+            created_at: self.created_at,
+        }
+    }
+}
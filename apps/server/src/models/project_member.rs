@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Role of a user in a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectRole {
+    /// Can also add and remove members
+    Admin,
+    #[default]
+    Member,
+}
+
+/// ProjectMember model - a user who can see a project
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ProjectMember {
+    pub project_id: i32,
+    pub user_id: i32,
+    /// Email of the user, for display
+    pub email: String,
+    pub role: ProjectRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for adding a member to a project
+#[derive(Debug, Deserialize)]
+pub struct AddProjectMember {
+    pub user_id: i32,
+    #[serde(default)]
+    pub role: ProjectRole,
+}
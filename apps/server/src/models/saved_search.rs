@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Saved search - a named set of issues-list query parameters
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SavedSearch {
+    pub id: i32,
+    /// Owner; NULL for searches shared with everyone
+    pub user_id: Option<i32>,
+    /// Project scope; NULL for searches usable in any project
+    pub project_id: Option<i32>,
+    pub name: String,
+    /// Stored query parameters, as accepted by the issues list endpoint
+    pub query: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for creating a saved search
+#[derive(Debug, Deserialize)]
+pub struct CreateSavedSearch {
+    pub name: String,
+    #[serde(default)]
+    pub project_id: Option<i32>,
+    /// Shared searches are visible to everyone (admins only)
+    #[serde(default)]
+    pub shared: bool,
+    pub query: serde_json::Value,
+}
+
+/// Query parameters for listing saved searches
+#[derive(Debug, Deserialize)]
+pub struct ListSavedSearchesQuery {
+    /// Only searches usable in this project (global ones included)
+    pub project_id: Option<i32>,
+}
+
+/// Response for API
+#[derive(Debug, Serialize)]
+pub struct SavedSearchResponse {
+    pub id: i32,
+    pub name: String,
+    pub project_id: Option<i32>,
+    pub shared: bool,
+    pub query: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedSearch {
+    /// Returns true if the search is shared with everyone
+    pub fn is_shared(&self) -> bool {
+        self.user_id.is_none()
+    }
+
+    /// Converts to response
+    pub fn to_response(&self) -> SavedSearchResponse {
+        SavedSearchResponse {
+            id: self.id,
+            name: self.name.clone(),
+            project_id: self.project_id,
+            shared: self.is_shared(),
+            query: self.query.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
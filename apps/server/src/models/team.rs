@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Team model - organizational grouping for projects
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Team {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// DTO for creating a new team
+#[derive(Debug, Deserialize)]
+pub struct CreateTeam {
+    pub name: String,
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// Notification channels copied into projects created in this team
+    #[serde(default)]
+    pub default_channel_ids: Vec<i32>,
+}
+
+/// DTO for updating a team
+#[derive(Debug, Deserialize)]
+pub struct UpdateTeam {
+    pub name: Option<String>,
+    pub default_channel_ids: Option<Vec<i32>>,
+}
+
+/// Query parameters for deleting a team
+#[derive(Debug, Deserialize)]
+pub struct DeleteTeamQuery {
+    /// Team that receives the projects of the deleted team
+    pub reassign_to: Option<i32>,
+}
+
+/// Response for API including default channel IDs
+#[derive(Debug, Serialize)]
+pub struct TeamResponse {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub default_channel_ids: Vec<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Compact team reference embedded in project responses
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamSummary {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+}
+
+impl Team {
+    /// Converts to response with default channel IDs
+    pub fn to_response(&self, default_channel_ids: Vec<i32>) -> TeamResponse {
+        TeamResponse {
+            id: self.id,
+            name: self.name.clone(),
+            slug: self.slug.clone(),
+            default_channel_ids,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+
+    /// Converts to the compact form used in project responses
+    pub fn to_summary(&self) -> TeamSummary {
+        TeamSummary {
+            id: self.id,
+            name: self.name.clone(),
+            slug: self.slug.clone(),
+        }
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 use crate::error::AppError;
+use crate::models::{IssueResponse, ProjectOverview};
 
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct User {
@@ -20,6 +21,35 @@ pub struct User {
     pub last_login: Option<DateTime<Utc>>,
 }
 
+/// Who performs a state change: a logged-in user or the system itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Actor {
+    User(i32),
+    /// Automated changes (background jobs, release-based resolution)
+    System,
+}
+
+impl Actor {
+    /// User ID to record, NULL for the system actor
+    pub fn user_id(&self) -> Option<i32> {
+        match self {
+            Actor::User(id) => Some(*id),
+            Actor::System => None,
+        }
+    }
+}
+
+/// Response of `GET /api/me/overview`
+#[derive(Debug, Serialize)]
+pub struct UserOverview {
+    /// Most recently seen open issues of the projects the caller can see
+    pub issues: Vec<IssueResponse>,
+    /// Projects the user is a member of; not included for API tokens,
+    /// which don't belong to a user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<ProjectOverview>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateUserRequest {
     pub email: String,
@@ -1,9 +1,204 @@
+//! Opaque cursors for keyset pagination.
+//!
+//! A cursor is the JSON serialization of [`IssueCursor`] or [`EventCursor`],
+//! encoded as URL-safe base64 without padding, followed by a `.` and its
+//! signature (see [`CursorSigner`]). Clients must treat it as opaque: take
+//! `next_cursor` from a response and pass it back unchanged as `?cursor=`.
+//! Cursors that fail to decode, aren't signed for the list they're used on,
+//! or decode to inconsistent values, are rejected with [`CursorError`], which
+//! maps to `400 Bad Request`.
+
+use std::sync::OnceLock;
+
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
 use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
+use crate::config::PaginationConfig;
+use crate::error::AppError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Bytes of the HMAC kept in a cursor, enough to make forging impractical
+const SIGNATURE_LEN: usize = 16;
+
+/// Longest cursor accepted; the ones we issue stay under 200 bytes
+const MAX_CURSOR_LEN: usize = 256;
+
+static SIGNER: OnceLock<CursorSigner> = OnceLock::new();
+
+/// Errors produced when encoding or decoding a cursor
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CursorError {
+    /// Not valid URL-safe base64 or not UTF-8 (e.g. truncated or mangled)
+    #[error("Invalid cursor encoding")]
+    Encoding,
+
+    /// Decoded, but not a cursor of the expected shape
+    #[error("Invalid cursor format")]
+    Format,
+
+    /// Well-formed, but its values are inconsistent (e.g. tampered with)
+    #[error("Invalid cursor: {0}")]
+    Invalid(&'static str),
+
+    /// Unsigned, or not signed by us for this list
+    #[error("Invalid cursor signature")]
+    Signature,
+
+    /// The cursor could not be serialized
+    #[error("Cursor serialization failed: {0}")]
+    Serialization(String),
+}
+
+impl From<CursorError> for AppError {
+    fn from(err: CursorError) -> Self {
+        match err {
+            CursorError::Serialization(_) => AppError::Internal(err.to_string()),
+            _ => AppError::Validation(err.to_string()),
+        }
+    }
+}
+
+/// Signs cursors so clients can't forge them or reuse them on another list
+///
+/// The signature is a truncated HMAC-SHA256 of the cursor's scope (the
+/// project or issue being paged through) and its payload. The scope itself
+/// isn't carried in the cursor: one presented on another project or issue
+/// simply fails verification.
+pub struct CursorSigner {
+    key: Vec<u8>,
+    allow_unsigned: bool,
+}
+
+impl CursorSigner {
+    pub fn new(key: &[u8], allow_unsigned: bool) -> Self {
+        Self {
+            key: key.to_vec(),
+            allow_unsigned,
+        }
+    }
+
+    /// Sets up the global signer from config
+    ///
+    /// Without CURSOR_SIGNING_KEY or SESSION_SECRET_KEY a random key is
+    /// used, so cursors stop working when the server restarts.
+    pub fn init(config: &PaginationConfig, session_secret_key: Option<&str>) {
+        let key = config.cursor_signing_key.as_deref().or(session_secret_key);
+        let signer = match key {
+            Some(key) => Self::new(key.as_bytes(), config.allow_unsigned_cursors),
+            None => {
+                log::warn!(
+                    "Neither CURSOR_SIGNING_KEY nor SESSION_SECRET_KEY is set, \
+                     pagination cursors won't survive a restart"
+                );
+                Self::random(config.allow_unsigned_cursors)
+            }
+        };
+
+        if SIGNER.set(signer).is_err() {
+            log::warn!("Cursor signer already initialized");
+        }
+    }
+
+    /// The signer used for cursors, with a random key if `init` was never called
+    pub fn global() -> &'static CursorSigner {
+        SIGNER.get_or_init(|| Self::random(PaginationConfig::default().allow_unsigned_cursors))
+    }
+
+    fn random(allow_unsigned: bool) -> Self {
+        let key: [u8; 32] = rand::random();
+        Self::new(&key, allow_unsigned)
+    }
+
+    /// Appends the signature of `payload` within `scope`
+    pub fn sign(&self, payload: &str, scope: &str) -> String {
+        let signature = self.mac(payload, scope).finalize().into_bytes();
+        format!(
+            "{}.{}",
+            payload,
+            URL_SAFE_NO_PAD.encode(&signature[..SIGNATURE_LEN])
+        )
+    }
+
+    /// Checks a cursor's signature within `scope` and returns its payload
+    ///
+    /// Unsigned cursors are returned as they are while they're still allowed.
+    pub fn verify<'a>(&self, cursor: &'a str, scope: &str) -> Result<&'a str, CursorError> {
+        let Some((payload, signature)) = cursor.split_once('.') else {
+            return if self.allow_unsigned {
+                Ok(cursor)
+            } else {
+                Err(CursorError::Signature)
+            };
+        };
+
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| CursorError::Signature)?;
+        if signature.len() != SIGNATURE_LEN {
+            return Err(CursorError::Signature);
+        }
+
+        self.mac(payload, scope)
+            .verify_truncated_left(&signature)
+            .map_err(|_| CursorError::Signature)?;
+        Ok(payload)
+    }
+
+    fn mac(&self, payload: &str, scope: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC can take key of any size");
+        mac.update(scope.as_bytes());
+        mac.update(b".");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+/// Scope of an issue cursor: the project whose issues it pages through
+fn project_scope(project_id: i32) -> String {
+    format!("project:{}", project_id)
+}
+
+/// Scope of an event cursor: the issue whose events it pages through
+fn issue_scope(issue_id: Uuid) -> String {
+    format!("issue:{}", issue_id)
+}
+
+/// Serializes a cursor to JSON, encodes it as URL-safe base64 and signs it
+fn encode_cursor<T: Serialize>(cursor: &T, scope: &str) -> Result<String, CursorError> {
+    let json =
+        serde_json::to_string(cursor).map_err(|e| CursorError::Serialization(e.to_string()))?;
+    let payload = URL_SAFE_NO_PAD.encode(json.as_bytes());
+    Ok(CursorSigner::global().sign(&payload, scope))
+}
+
+/// Verifies the signature, decodes URL-safe base64 and deserializes the cursor JSON
+fn decode_cursor<T: DeserializeOwned>(s: &str, scope: &str) -> Result<T, CursorError> {
+    if s.len() > MAX_CURSOR_LEN {
+        return Err(CursorError::Encoding);
+    }
+
+    let payload = CursorSigner::global().verify(s, scope)?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| CursorError::Encoding)?;
+
+    let json = String::from_utf8(bytes).map_err(|_| CursorError::Encoding)?;
+
+    serde_json::from_str(&json).map_err(|_| CursorError::Format)
+}
+
+/// Checks that an order direction is "asc" or "desc"
+fn validate_order(order: &str) -> Result<(), CursorError> {
+    match order {
+        "asc" | "desc" => Ok(()),
+        _ => Err(CursorError::Invalid("unknown order")),
+    }
+}
 
 /// Cursor for paginating Issues
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,58 +240,85 @@ impl IssueCursor {
         self
     }
 
-    pub fn encode(&self) -> AppResult<String> {
-        let json = serde_json::to_string(self)
-            .map_err(|e| AppError::Internal(format!("Cursor serialization failed: {}", e)))?;
-        Ok(URL_SAFE_NO_PAD.encode(json.as_bytes()))
+    /// Encodes the cursor into its opaque string form, valid for `project_id`'s issues
+    pub fn encode(&self, project_id: i32) -> Result<String, CursorError> {
+        encode_cursor(self, &project_scope(project_id))
     }
 
-    pub fn decode(s: &str) -> AppResult<Self> {
-        let bytes = URL_SAFE_NO_PAD
-            .decode(s)
-            .map_err(|_| AppError::Validation("Invalid cursor encoding".to_string()))?;
-
-        let json = String::from_utf8(bytes)
-            .map_err(|_| AppError::Validation("Invalid cursor encoding".to_string()))?;
+    /// Decodes a cursor string, rejecting malformed or inconsistent cursors
+    /// and cursors issued for another project
+    pub fn decode(s: &str, project_id: i32) -> Result<Self, CursorError> {
+        let cursor: Self = decode_cursor(s, &project_scope(project_id))?;
+        validate_order(&cursor.order)?;
 
-        serde_json::from_str(&json)
-            .map_err(|_| AppError::Validation("Invalid cursor format".to_string()))
+        match cursor.sort.as_str() {
+            "digest_order" if cursor.last_digest_order.is_none() => {
+                Err(CursorError::Invalid("missing last_digest_order"))
+            }
+            "last_seen" if cursor.last_seen.is_none() || cursor.last_id.is_none() => {
+                Err(CursorError::Invalid("missing last_seen or last_id"))
+            }
+            "digest_order" | "last_seen" => Ok(cursor),
+            _ => Err(CursorError::Invalid("unknown sort")),
+        }
     }
 }
 
 /// Cursor for paginating Events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventCursor {
+    /// Ordering: "received" or "timestamp"; cursors issued before it existed
+    /// are "received"
+    #[serde(default = "default_event_sort")]
+    pub by: String,
     /// Direction: "asc" or "desc"
     pub order: String,
-    /// Last digest_order value seen
+    /// Last digest_order value seen (tie-breaker for timestamp ordering)
     pub last_digest_order: i32,
+    /// Last event timestamp seen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_timestamp: Option<DateTime<Utc>>,
+}
+
+fn default_event_sort() -> String {
+    "received".to_string()
 }
 
 impl EventCursor {
     pub fn new(order: &str, last_digest_order: i32) -> Self {
         Self {
+            by: default_event_sort(),
             order: order.to_string(),
             last_digest_order,
+            last_timestamp: None,
         }
     }
 
-    pub fn encode(&self) -> AppResult<String> {
-        let json = serde_json::to_string(self)
-            .map_err(|e| AppError::Internal(format!("Cursor serialization failed: {}", e)))?;
-        Ok(URL_SAFE_NO_PAD.encode(json.as_bytes()))
+    /// Cursor of the timestamp ordering
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.by = "timestamp".to_string();
+        self.last_timestamp = Some(timestamp);
+        self
     }
 
-    pub fn decode(s: &str) -> AppResult<Self> {
-        let bytes = URL_SAFE_NO_PAD
-            .decode(s)
-            .map_err(|_| AppError::Validation("Invalid cursor encoding".to_string()))?;
+    /// Encodes the cursor into its opaque string form, valid for `issue_id`'s events
+    pub fn encode(&self, issue_id: Uuid) -> Result<String, CursorError> {
+        encode_cursor(self, &issue_scope(issue_id))
+    }
 
-        let json = String::from_utf8(bytes)
-            .map_err(|_| AppError::Validation("Invalid cursor encoding".to_string()))?;
+    /// Decodes a cursor string, rejecting malformed or inconsistent cursors
+    /// and cursors issued for another issue
+    pub fn decode(s: &str, issue_id: Uuid) -> Result<Self, CursorError> {
+        let cursor: Self = decode_cursor(s, &issue_scope(issue_id))?;
+        validate_order(&cursor.order)?;
 
-        serde_json::from_str(&json)
-            .map_err(|_| AppError::Validation("Invalid cursor format".to_string()))
+        match cursor.by.as_str() {
+            "timestamp" if cursor.last_timestamp.is_none() => {
+                Err(CursorError::Invalid("missing last_timestamp"))
+            }
+            "received" | "timestamp" => Ok(cursor),
+            _ => Err(CursorError::Invalid("unknown ordering")),
+        }
     }
 }
 
@@ -108,8 +330,8 @@ mod tests {
     fn test_issue_cursor_encode_decode() {
         let cursor = IssueCursor::new("digest_order", "desc").with_digest_order(42);
 
-        let encoded = cursor.encode().unwrap();
-        let decoded = IssueCursor::decode(&encoded).unwrap();
+        let encoded = cursor.encode(1).unwrap();
+        let decoded = IssueCursor::decode(&encoded, 1).unwrap();
 
         assert_eq!(decoded.sort, "digest_order");
         assert_eq!(decoded.order, "desc");
@@ -119,9 +341,10 @@ mod tests {
     #[test]
     fn test_event_cursor_encode_decode() {
         let cursor = EventCursor::new("asc", 100);
+        let issue_id = Uuid::new_v4();
 
-        let encoded = cursor.encode().unwrap();
-        let decoded = EventCursor::decode(&encoded).unwrap();
+        let encoded = cursor.encode(issue_id).unwrap();
+        let decoded = EventCursor::decode(&encoded, issue_id).unwrap();
 
         assert_eq!(decoded.order, "asc");
         assert_eq!(decoded.last_digest_order, 100);
@@ -129,7 +352,7 @@ mod tests {
 
     #[test]
     fn test_invalid_cursor() {
-        let result = IssueCursor::decode("not-valid-base64!!!");
+        let result = IssueCursor::decode("not-valid-base64!!!", 1);
         assert!(result.is_err());
     }
 }
@@ -1,11 +1,12 @@
 pub mod cursor;
 
-pub use cursor::{EventCursor, IssueCursor};
+pub use cursor::{CursorError, CursorSigner, EventCursor, IssueCursor};
 
 use serde::{Deserialize, Serialize};
 
-/// Default page size for pagination
-pub const PAGE_SIZE: i64 = 20;
+use crate::config::PaginationConfig;
+use crate::error::{codes, AppError, AppResult};
+use crate::models::{AlertStatus, IssuePriority};
 
 /// Paginated response wrapper (cursor-based)
 #[derive(Debug, Serialize)]
@@ -50,14 +51,17 @@ impl<T> OffsetPaginatedResponse<T> {
 }
 
 /// Sort mode for issues listing
-#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
 #[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
 pub enum IssueSort {
     /// Sort by digest_order (stable, unique per project)
     #[default]
     DigestOrder,
     /// Sort by last_seen (activity-based, may reorder)
     LastSeen,
+    /// Sort by priority, then by last_seen; offset pagination only
+    Priority,
 }
 
 impl IssueSort {
@@ -65,6 +69,7 @@ impl IssueSort {
         match self {
             IssueSort::DigestOrder => "digest_order",
             IssueSort::LastSeen => "last_seen",
+            IssueSort::Priority => "priority",
         }
     }
 }
@@ -75,6 +80,32 @@ impl std::fmt::Display for IssueSort {
     }
 }
 
+/// Field events of an issue are ordered by
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSort {
+    /// By digest_order, the order Rustrak processed them in
+    #[default]
+    Received,
+    /// By the event's own timestamp, which can differ under backpressure
+    Timestamp,
+}
+
+impl EventSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventSort::Received => "received",
+            EventSort::Timestamp => "timestamp",
+        }
+    }
+}
+
+impl std::fmt::Display for EventSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Sort order direction
 #[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -111,13 +142,13 @@ pub struct ListIssuesQuery {
     #[serde(default = "default_page")]
     pub page: i64,
 
-    /// Items per page (default: 20)
-    #[serde(default = "default_per_page")]
-    pub per_page: i64,
+    /// Items per page (default and max come from `PaginationConfig`)
+    #[serde(default)]
+    pub per_page: Option<i64>,
 
-    /// Sort mode (default: last_seen)
+    /// Sort mode (default: the project's `default_issue_sort`)
     #[serde(default)]
-    pub sort: IssueSort,
+    pub sort: Option<IssueSort>,
 
     /// Sort order direction (default: desc)
     #[serde(default)]
@@ -126,14 +157,137 @@ pub struct ListIssuesQuery {
     /// Filter: open (not resolved, not muted), resolved, muted, all
     #[serde(default)]
     pub filter: IssueFilter,
+
+    /// Saved search whose stored parameters apply (explicit ones take precedence)
+    #[serde(default)]
+    pub saved_search: Option<i32>,
+
+    /// Only issues with events of this distribution
+    #[serde(default)]
+    pub dist: Option<String>,
+
+    /// `false` for issues with an unhandled event (crashes), `true` for
+    /// issues whose events were all handled
+    #[serde(default)]
+    pub handled: Option<bool>,
+
+    /// Only issues of this priority
+    #[serde(default)]
+    pub priority: Option<IssuePriority>,
+
+    /// Comma-separated extra fields; `sparkline` adds hourly event counts
+    #[serde(default)]
+    pub include: Option<String>,
 }
 
+/// Largest page the issue list attaches sparklines to
+pub const MAX_SPARKLINE_PAGE_SIZE: i64 = 50;
+
 fn default_page() -> i64 {
     1
 }
 
-fn default_per_page() -> i64 {
-    PAGE_SIZE
+/// Resolves a requested page size: `default` when absent, at least 1, and a
+/// 400 naming `per_page` when it's over the configured max
+fn page_size(per_page: Option<i64>, default: i64, config: &PaginationConfig) -> AppResult<i64> {
+    let per_page = per_page.unwrap_or(default);
+    if per_page > config.max_page_size {
+        return Err(AppError::invalid_field(
+            "per_page",
+            codes::VALIDATION,
+            format!("per_page must be at most {}", config.max_page_size),
+        ));
+    }
+    Ok(per_page.max(1))
+}
+
+impl ListIssuesQuery {
+    /// Whether `field` was asked for in `include`
+    pub fn includes(&self, field: &str) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|include| include.split(',').any(|f| f.trim() == field))
+    }
+
+    /// Page size to use; at least 1, rejected above `max_page_size`
+    pub fn per_page(&self, config: &PaginationConfig) -> AppResult<i64> {
+        page_size(self.per_page, config.default_page_size, config)
+    }
+
+    /// Sort mode to use, `project_default` unless the request passed one
+    pub fn sort(&self, project_default: IssueSort) -> IssueSort {
+        self.sort.unwrap_or(project_default)
+    }
+}
+
+/// Default `period` for aggregating issues
+pub const DEFAULT_AGGREGATE_PERIOD: &str = "7d";
+
+/// Longest `period` issues can be aggregated over, in days
+pub const MAX_AGGREGATE_PERIOD_DAYS: i64 = 90;
+
+/// Issue column issues are aggregated by
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueGroupBy {
+    CalculatedType,
+    Transaction,
+}
+
+impl IssueGroupBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueGroupBy::CalculatedType => "calculated_type",
+            IssueGroupBy::Transaction => "transaction",
+        }
+    }
+}
+
+/// Query parameters for aggregating issues
+///
+/// `filter` and `dist` work as in [`ListIssuesQuery`].
+#[derive(Debug, Deserialize)]
+pub struct AggregateIssuesQuery {
+    pub group_by: IssueGroupBy,
+
+    /// Window events are counted in, e.g. `24h` or `7d` (default: 7d)
+    #[serde(default)]
+    pub period: Option<String>,
+
+    #[serde(default)]
+    pub filter: IssueFilter,
+
+    #[serde(default)]
+    pub dist: Option<String>,
+}
+
+impl AggregateIssuesQuery {
+    /// The period, between an hour and [`MAX_AGGREGATE_PERIOD_DAYS`]
+    pub fn period(&self) -> AppResult<chrono::Duration> {
+        let period = self.period.as_deref().unwrap_or(DEFAULT_AGGREGATE_PERIOD);
+        parse_period(period)
+            .filter(|p| {
+                *p >= chrono::Duration::hours(1)
+                    && *p <= chrono::Duration::days(MAX_AGGREGATE_PERIOD_DAYS)
+            })
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Invalid period '{}', expected hours or days like '24h' or '7d', up to {}d",
+                    period, MAX_AGGREGATE_PERIOD_DAYS
+                ))
+            })
+    }
+}
+
+/// Parses a number of hours (`24h`) or days (`7d`)
+fn parse_period(period: &str) -> Option<chrono::Duration> {
+    let unit_at = period.len().checked_sub(1)?;
+    let amount: i64 = period.get(..unit_at)?.parse().ok()?;
+    match &period[unit_at..] {
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        _ => None,
+    }
 }
 
 /// Filter for issues listing
@@ -158,8 +312,27 @@ pub struct ListEventsQuery {
     #[serde(default)]
     pub order: SortOrder,
 
+    /// Field to order by (default: received)
+    #[serde(default)]
+    pub by: EventSort,
+
     /// Pagination cursor
     pub cursor: Option<String>,
+
+    /// Events per page (default: `PaginationConfig::events_page_size`)
+    #[serde(default)]
+    pub per_page: Option<i64>,
+
+    /// Only events of this distribution
+    #[serde(default)]
+    pub dist: Option<String>,
+}
+
+impl ListEventsQuery {
+    /// Page size to use; at least 1, rejected above `max_page_size`
+    pub fn per_page(&self, config: &PaginationConfig) -> AppResult<i64> {
+        page_size(self.per_page, config.events_page_size, config)
+    }
 }
 
 /// Query parameters for listing projects (offset-based)
@@ -169,11 +342,149 @@ pub struct ListProjectsQuery {
     #[serde(default = "default_page")]
     pub page: i64,
 
-    /// Items per page (default: 20)
-    #[serde(default = "default_per_page")]
-    pub per_page: i64,
+    /// Items per page (default and max come from `PaginationConfig`)
+    #[serde(default)]
+    pub per_page: Option<i64>,
 
     /// Sort order direction (default: desc = newest first)
     #[serde(default)]
     pub order: SortOrder,
+
+    /// Only list projects of the team with this slug
+    #[serde(default)]
+    pub team: Option<String>,
+
+    /// Comma-separated extra fields; `stats` adds issue and event counts
+    #[serde(default)]
+    pub include: Option<String>,
+}
+
+impl ListProjectsQuery {
+    /// Whether `field` was asked for in `include`
+    pub fn includes(&self, field: &str) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|include| include.split(',').any(|f| f.trim() == field))
+    }
+
+    /// Page size to use; at least 1, rejected above `max_page_size`
+    pub fn per_page(&self, config: &PaginationConfig) -> AppResult<i64> {
+        page_size(self.per_page, config.default_page_size, config)
+    }
+}
+
+/// Response shape of the token list
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListFormat {
+    /// `OffsetPaginatedResponse`
+    #[default]
+    Paginated,
+    /// Bare array of every item (deprecated, kept for one release)
+    Flat,
+}
+
+/// Query parameters for listing tokens (offset-based)
+#[derive(Debug, Deserialize)]
+pub struct ListTokensQuery {
+    /// Page number (1-indexed, default: 1)
+    #[serde(default = "default_page")]
+    pub page: i64,
+
+    /// Items per page (default and max come from `PaginationConfig`)
+    #[serde(default)]
+    pub per_page: Option<i64>,
+
+    /// Response shape (default: paginated)
+    #[serde(default)]
+    pub format: ListFormat,
+}
+
+impl ListTokensQuery {
+    /// Page size to use; at least 1, rejected above `max_page_size`
+    pub fn per_page(&self, config: &PaginationConfig) -> AppResult<i64> {
+        page_size(self.per_page, config.default_page_size, config)
+    }
+}
+
+/// Query parameters for listing a project's deleted issues (offset-based)
+#[derive(Debug, Deserialize)]
+pub struct ListTrashQuery {
+    /// Page number (1-indexed, default: 1)
+    #[serde(default = "default_page")]
+    pub page: i64,
+
+    /// Items per page (default and max come from `PaginationConfig`)
+    #[serde(default)]
+    pub per_page: Option<i64>,
+}
+
+impl ListTrashQuery {
+    /// Page size to use; at least 1, rejected above `max_page_size`
+    pub fn per_page(&self, config: &PaginationConfig) -> AppResult<i64> {
+        page_size(self.per_page, config.default_page_size, config)
+    }
+}
+
+/// Query parameters for listing alert history (offset-based)
+#[derive(Debug, Deserialize)]
+pub struct ListAlertHistoryQuery {
+    /// Page number (1-indexed, default: 1)
+    #[serde(default = "default_page")]
+    pub page: i64,
+
+    /// Items per page (default and max come from `PaginationConfig`)
+    #[serde(default)]
+    pub per_page: Option<i64>,
+
+    /// Only entries with this delivery status
+    #[serde(default)]
+    pub status: Option<AlertStatus>,
+
+    /// Only entries sent to this channel
+    #[serde(default)]
+    pub channel_id: Option<i32>,
+}
+
+impl ListAlertHistoryQuery {
+    /// Page size to use; at least 1, rejected above `max_page_size`
+    pub fn per_page(&self, config: &PaginationConfig) -> AppResult<i64> {
+        page_size(self.per_page, config.default_page_size, config)
+    }
+}
+
+/// Default `period` for channel delivery stats
+pub const DEFAULT_CHANNEL_STATS_PERIOD: &str = "24h";
+
+/// Query parameters for a channel's delivery stats
+#[derive(Debug, Default, Deserialize)]
+pub struct ChannelStatsQuery {
+    /// Window deliveries are counted in, e.g. `24h` or `7d` (default: 24h)
+    #[serde(default)]
+    pub period: Option<String>,
+}
+
+impl ChannelStatsQuery {
+    /// The period as given, or the default
+    pub fn period_str(&self) -> &str {
+        self.period
+            .as_deref()
+            .unwrap_or(DEFAULT_CHANNEL_STATS_PERIOD)
+    }
+
+    /// The period, between an hour and [`MAX_AGGREGATE_PERIOD_DAYS`]
+    pub fn period(&self) -> AppResult<chrono::Duration> {
+        let period = self.period_str();
+        parse_period(period)
+            .filter(|p| {
+                *p >= chrono::Duration::hours(1)
+                    && *p <= chrono::Duration::days(MAX_AGGREGATE_PERIOD_DAYS)
+            })
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Invalid period '{}', expected hours or days like '24h' or '7d', up to {}d",
+                    period, MAX_AGGREGATE_PERIOD_DAYS
+                ))
+            })
+    }
 }
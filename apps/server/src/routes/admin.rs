@@ -0,0 +1,168 @@
+//! Admin routes.
+//!
+//! - POST /api/admin/self-test-error - Fail on purpose to test self-monitoring
+//! - GET /api/admin/digest/status - Digest backlog, throughput and latest drain
+//! - POST /api/admin/digest/drain - Raise the digest concurrency until the backlog is empty
+//! - POST /api/admin/projects/{id}/recount - Recompute a project's event counters
+
+use std::time::Duration;
+
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::auth::AuthenticatedUser;
+use crate::config::Config;
+use crate::db::DbPool;
+use crate::digest::{DigestRunner, DigestStatus};
+use crate::error::{codes, AppError, AppResult};
+use crate::ingest::{count_events, get_ingest_dir};
+use crate::services::{ProjectService, SelfMonitor};
+
+/// Upper bound for a drain's `concurrency`
+pub const MAX_DRAIN_CONCURRENCY: usize = 256;
+
+/// Default and upper bound for a drain's `max_duration_secs`
+pub const DEFAULT_DRAIN_DURATION_SECS: u64 = 300;
+pub const MAX_DRAIN_DURATION_SECS: u64 = 3600;
+
+#[derive(Debug, Deserialize)]
+pub struct DigestStatusQuery {
+    /// Fail with 404 unless this is the latest drain
+    pub job_id: Option<Uuid>,
+}
+
+/// Body of `GET /api/admin/digest/status`
+#[derive(Debug, Serialize)]
+pub struct DigestStatusResponse {
+    #[serde(flatten)]
+    pub status: DigestStatus,
+    /// Event files waiting in the ingest directory, including ones no
+    /// digest is running for
+    pub spooled: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DrainRequest {
+    /// Digests running at once until the drain ends
+    pub concurrency: usize,
+    /// Stop raising the concurrency after this long, even with a backlog left
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+}
+
+fn require_admin(user: &AuthenticatedUser, action: &str) -> AppResult<()> {
+    if !user.0.is_admin {
+        return Err(AppError::Forbidden(format!("Only admins can {}", action)));
+    }
+    Ok(())
+}
+
+/// POST /api/admin/self-test-error
+///
+/// Always fails with a 500, which self-monitoring reports as an issue in
+/// its project.
+pub async fn self_test_error(
+    monitor: Option<web::Data<SelfMonitor>>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    require_admin(&user, "trigger a test error")?;
+    let Some(monitor) = monitor else {
+        return Err(AppError::Validation(
+            "Self-monitoring is disabled, set SELF_MONITORING_PROJECT_ID".to_string(),
+        ));
+    };
+
+    Err(AppError::Internal(format!(
+        "Self-monitoring test error for project {}",
+        monitor.project_id()
+    )))
+}
+
+/// GET /api/admin/digest/status
+///
+/// Pass `?job_id=` to poll a drain; it 404s once a newer drain replaced it.
+pub async fn digest_status(
+    config: web::Data<Config>,
+    user: AuthenticatedUser,
+    query: web::Query<DigestStatusQuery>,
+) -> AppResult<HttpResponse> {
+    require_admin(&user, "view the digest status")?;
+
+    let status = DigestRunner::global().status();
+    if let Some(job_id) = query.job_id {
+        if status.drain.as_ref().map(|job| job.id) != Some(job_id) {
+            return Err(AppError::NotFound(format!("Drain {}", job_id)));
+        }
+    }
+
+    let spooled = count_events(&get_ingest_dir(config.ingest_dir.as_deref())).await?;
+    Ok(HttpResponse::Ok().json(DigestStatusResponse { status, spooled }))
+}
+
+/// POST /api/admin/digest/drain
+///
+/// Responds 202 with the drain job right away; the concurrency goes back to
+/// its previous value when the backlog is empty or the time is up.
+pub async fn drain_digest(
+    user: AuthenticatedUser,
+    body: web::Json<DrainRequest>,
+) -> AppResult<HttpResponse> {
+    require_admin(&user, "drain the digest backlog")?;
+
+    let DrainRequest {
+        concurrency,
+        max_duration_secs,
+    } = body.into_inner();
+    if !(1..=MAX_DRAIN_CONCURRENCY).contains(&concurrency) {
+        return Err(AppError::invalid_field(
+            "concurrency",
+            codes::VALIDATION,
+            format!(
+                "Concurrency must be between 1 and {}",
+                MAX_DRAIN_CONCURRENCY
+            ),
+        ));
+    }
+    let max_duration_secs = max_duration_secs.unwrap_or(DEFAULT_DRAIN_DURATION_SECS);
+    if !(1..=MAX_DRAIN_DURATION_SECS).contains(&max_duration_secs) {
+        return Err(AppError::invalid_field(
+            "max_duration_secs",
+            codes::VALIDATION,
+            format!(
+                "Max duration must be between 1 and {} seconds",
+                MAX_DRAIN_DURATION_SECS
+            ),
+        ));
+    }
+
+    let job =
+        DigestRunner::global().start_drain(concurrency, Duration::from_secs(max_duration_secs))?;
+    Ok(HttpResponse::Accepted().json(job))
+}
+
+/// POST /api/admin/projects/{id}/recount
+///
+/// Recomputes the event counters of the project and its issues from the
+/// stored events, and responds with their values before and after.
+pub async fn recount_project(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    require_admin(&user, "recount event counters")?;
+    let recount = ProjectService::recount(pool.get_ref(), path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(recount))
+}
+
+/// Configure admin routes
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/admin")
+            .route("/self-test-error", web::post().to(self_test_error))
+            .route("/digest/status", web::get().to(digest_status))
+            .route("/digest/drain", web::post().to(drain_digest))
+            .route("/projects/{id}/recount", web::post().to(recount_project)),
+    );
+}
@@ -7,6 +7,9 @@
 //! - PATCH /api/alert-channels/{id} - Update channel
 //! - DELETE /api/alert-channels/{id} - Delete channel
 //! - POST /api/alert-channels/{id}/test - Test channel
+//! - GET /api/alert-channels/health - Delivery health of every channel
+//! - GET /api/alert-channels/{id}/stats - Delivery success rate of a channel
+//! - POST /api/alert-channels/{id}/reset-failures - Clear failure streak and enable
 //!
 //! ## Alert Rules (Per-Project)
 //! - GET /api/projects/{project_id}/alert-rules - List rules
@@ -16,20 +19,22 @@
 //! - DELETE /api/projects/{project_id}/alert-rules/{rule_id} - Delete rule
 //!
 //! ## Alert History
-//! - GET /api/projects/{project_id}/alert-history - List history
+//! - GET /api/projects/{project_id}/alert-history - List history (paginated, filterable)
 
 use actix_web::{web, HttpResponse};
 use chrono::Utc;
 use serde::Deserialize;
 
 use crate::auth::AuthenticatedUser;
+use crate::config::Config;
 use crate::db::DbPool;
 use crate::error::AppResult;
 use crate::models::{
-    AlertPayload, CreateAlertRule, CreateNotificationChannel, IssueInfo, ProjectInfo,
-    UpdateAlertRule, UpdateNotificationChannel,
+    AlertPayload, CreateAlertRule, CreateNotificationChannel, DeleteChannelQuery, UpdateAlertRule,
+    UpdateNotificationChannel,
 };
-use crate::services::{create_dispatcher, AlertService, ProjectService};
+use crate::pagination::{ChannelStatsQuery, ListAlertHistoryQuery, OffsetPaginatedResponse};
+use crate::services::{create_dispatcher, AlertService, ProjectMemberService, ProjectService};
 
 // =============================================================================
 // Notification Channel Endpoints
@@ -41,6 +46,7 @@ pub async fn list_channels(
     _user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let channels = AlertService::list_channels(pool.get_ref()).await?;
+    let channels: Vec<_> = channels.into_iter().map(|c| c.masked()).collect();
     Ok(HttpResponse::Ok().json(channels))
 }
 
@@ -51,7 +57,7 @@ pub async fn create_channel(
     body: web::Json<CreateNotificationChannel>,
 ) -> AppResult<HttpResponse> {
     let channel = AlertService::create_channel(pool.get_ref(), body.into_inner()).await?;
-    Ok(HttpResponse::Created().json(channel))
+    Ok(HttpResponse::Created().json(channel.masked()))
 }
 
 /// GET /api/alert-channels/{id}
@@ -61,7 +67,7 @@ pub async fn get_channel(
     path: web::Path<i32>,
 ) -> AppResult<HttpResponse> {
     let channel = AlertService::get_channel(pool.get_ref(), path.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(channel))
+    Ok(HttpResponse::Ok().json(channel.masked()))
 }
 
 /// PATCH /api/alert-channels/{id}
@@ -73,19 +79,61 @@ pub async fn update_channel(
 ) -> AppResult<HttpResponse> {
     let channel =
         AlertService::update_channel(pool.get_ref(), path.into_inner(), body.into_inner()).await?;
-    Ok(HttpResponse::Ok().json(channel))
+    Ok(HttpResponse::Ok().json(channel.masked()))
 }
 
 /// DELETE /api/alert-channels/{id}
+///
+/// Returns 409 naming the alert rules that use the channel, unless
+/// `?force=true` is passed to detach it from them.
 pub async fn delete_channel(
     pool: web::Data<DbPool>,
     _user: AuthenticatedUser,
     path: web::Path<i32>,
+    query: web::Query<DeleteChannelQuery>,
 ) -> AppResult<HttpResponse> {
-    AlertService::delete_channel(pool.get_ref(), path.into_inner()).await?;
+    AlertService::delete_channel(pool.get_ref(), path.into_inner(), query.force).await?;
     Ok(HttpResponse::NoContent().finish())
 }
 
+/// GET /api/alert-channels/health
+pub async fn channel_health(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let health = AlertService::channel_health(pool.get_ref()).await?;
+    Ok(HttpResponse::Ok().json(health))
+}
+
+/// GET /api/alert-channels/{id}/stats
+///
+/// Counts the channel's deliveries of alerts created in `?period=` (default
+/// 24h) and their success rate.
+pub async fn channel_stats(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+    path: web::Path<i32>,
+    query: web::Query<ChannelStatsQuery>,
+) -> AppResult<HttpResponse> {
+    let since = Utc::now() - query.period()?;
+    let mut stats = AlertService::channel_stats(pool.get_ref(), path.into_inner(), since).await?;
+    stats.period = query.period_str().to_string();
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+/// POST /api/alert-channels/{id}/reset-failures
+///
+/// Re-enables a channel disabled after repeated failures, starting its
+/// failure streak from zero.
+pub async fn reset_channel_failures(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+    path: web::Path<i32>,
+) -> AppResult<HttpResponse> {
+    let channel = AlertService::reset_channel_failures(pool.get_ref(), path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(channel.masked()))
+}
+
 /// POST /api/alert-channels/{id}/test
 pub async fn test_channel(
     pool: web::Data<DbPool>,
@@ -94,28 +142,7 @@ pub async fn test_channel(
 ) -> AppResult<HttpResponse> {
     let channel = AlertService::get_channel(pool.get_ref(), path.into_inner()).await?;
 
-    // Create a test payload
-    let test_payload = AlertPayload {
-        alert_id: format!("test-{}", Utc::now().timestamp_millis()),
-        alert_type: "test".to_string(),
-        triggered_at: Utc::now(),
-        project: ProjectInfo {
-            id: 0,
-            name: "Test Project".to_string(),
-            slug: "test-project".to_string(),
-        },
-        issue: IssueInfo {
-            id: "00000000-0000-0000-0000-000000000000".to_string(),
-            short_id: "TEST-1".to_string(),
-            title: "This is a test alert from Rustrak".to_string(),
-            level: Some("info".to_string()),
-            first_seen: Utc::now(),
-            last_seen: Utc::now(),
-            event_count: 1,
-        },
-        issue_url: "https://example.com/test".to_string(),
-        actor: "Rustrak Test".to_string(),
-    };
+    let test_payload = AlertPayload::sample();
 
     // Send test notification
     let dispatcher = create_dispatcher(channel.channel_type);
@@ -141,10 +168,11 @@ pub async fn test_channel(
 /// GET /api/projects/{project_id}/alert-rules
 pub async fn list_rules(
     pool: web::Data<DbPool>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     path: web::Path<i32>,
 ) -> AppResult<HttpResponse> {
     let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify project exists
     let _ = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
@@ -164,11 +192,12 @@ pub async fn list_rules(
 /// POST /api/projects/{project_id}/alert-rules
 pub async fn create_rule(
     pool: web::Data<DbPool>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     path: web::Path<i32>,
     body: web::Json<CreateAlertRule>,
 ) -> AppResult<HttpResponse> {
     let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify project exists
     let _ = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
@@ -188,10 +217,11 @@ pub struct RulePath {
 /// GET /api/projects/{project_id}/alert-rules/{rule_id}
 pub async fn get_rule(
     pool: web::Data<DbPool>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     path: web::Path<RulePath>,
 ) -> AppResult<HttpResponse> {
     let params = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, params.project_id).await?;
 
     // Verify project exists
     let _ = ProjectService::get_by_id(pool.get_ref(), params.project_id).await?;
@@ -213,11 +243,12 @@ pub async fn get_rule(
 /// PATCH /api/projects/{project_id}/alert-rules/{rule_id}
 pub async fn update_rule(
     pool: web::Data<DbPool>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     path: web::Path<RulePath>,
     body: web::Json<UpdateAlertRule>,
 ) -> AppResult<HttpResponse> {
     let params = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, params.project_id).await?;
 
     // Verify project exists
     let _ = ProjectService::get_by_id(pool.get_ref(), params.project_id).await?;
@@ -239,10 +270,11 @@ pub async fn update_rule(
 /// DELETE /api/projects/{project_id}/alert-rules/{rule_id}
 pub async fn delete_rule(
     pool: web::Data<DbPool>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     path: web::Path<RulePath>,
 ) -> AppResult<HttpResponse> {
     let params = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, params.project_id).await?;
 
     // Verify project exists
     let _ = ProjectService::get_by_id(pool.get_ref(), params.project_id).await?;
@@ -264,32 +296,37 @@ pub async fn delete_rule(
 // Alert History Endpoints
 // =============================================================================
 
-#[derive(Deserialize)]
-pub struct HistoryQuery {
-    #[serde(default = "default_limit")]
-    pub limit: i64,
-}
-
-fn default_limit() -> i64 {
-    50
-}
-
 /// GET /api/projects/{project_id}/alert-history
 pub async fn list_history(
     pool: web::Data<DbPool>,
-    _user: AuthenticatedUser,
+    config: web::Data<Config>,
+    user: AuthenticatedUser,
     path: web::Path<i32>,
-    query: web::Query<HistoryQuery>,
+    query: web::Query<ListAlertHistoryQuery>,
 ) -> AppResult<HttpResponse> {
     let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify project exists
     let _ = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
 
-    let limit = query.limit.min(100).max(1);
-    let history = AlertService::list_history(pool.get_ref(), project_id, limit).await?;
-
-    Ok(HttpResponse::Ok().json(history))
+    let per_page = query.per_page(&config.pagination)?;
+    let (history, total_count) = AlertService::list_history_paginated(
+        pool.get_ref(),
+        project_id,
+        query.status,
+        query.channel_id,
+        query.page,
+        per_page,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(OffsetPaginatedResponse::new(
+        history,
+        total_count,
+        query.page,
+        per_page,
+    )))
 }
 
 // =============================================================================
@@ -302,10 +339,16 @@ pub fn configure_channels(cfg: &mut web::ServiceConfig) {
         web::scope("/api/alert-channels")
             .route("", web::get().to(list_channels))
             .route("", web::post().to(create_channel))
+            .route("/health", web::get().to(channel_health))
             .route("/{id}", web::get().to(get_channel))
             .route("/{id}", web::patch().to(update_channel))
             .route("/{id}", web::delete().to(delete_channel))
-            .route("/{id}/test", web::post().to(test_channel)),
+            .route("/{id}/test", web::post().to(test_channel))
+            .route("/{id}/stats", web::get().to(channel_stats))
+            .route(
+                "/{id}/reset-failures",
+                web::post().to(reset_channel_failures),
+            ),
     );
 }
 
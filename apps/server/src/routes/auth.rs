@@ -1,9 +1,12 @@
 use actix_session::Session;
+use actix_web::http::header::LOCATION;
 use actix_web::{web, HttpResponse, Responder};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::auth::{self, AuthenticatedUser};
-use crate::error::{AppError, AppResult};
+use crate::auth::{self, AuthenticatedUser, OidcClient};
+use crate::config::Config;
+use crate::error::{codes, AppError, AppResult};
+use crate::middleware::rate_limit::{RateLimit, RateLimitScope};
 use crate::models::{CreateUserRequest, LoginRequest, User};
 use crate::services::UsersService;
 
@@ -19,6 +22,22 @@ struct UserResponse {
     is_admin: bool,
 }
 
+/// Ways to log in, so the dashboard knows which to offer
+#[derive(Serialize)]
+struct LoginMethodsResponse {
+    password: bool,
+    oidc: bool,
+}
+
+/// Query of the provider's redirect back to `/auth/oidc/callback`
+#[derive(Deserialize)]
+pub struct OidcCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
 impl From<User> for UserResponse {
     fn from(user: User) -> Self {
         Self {
@@ -70,21 +89,45 @@ fn is_valid_email(email: &str) -> bool {
     true
 }
 
+fn ensure_password_login(config: &Config) -> AppResult<()> {
+    if !config.security.password_login_enabled {
+        return Err(AppError::Forbidden(
+            "Password login is disabled, log in through SSO".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn oidc_client(oidc: Option<web::Data<OidcClient>>) -> AppResult<web::Data<OidcClient>> {
+    oidc.ok_or_else(|| AppError::NotFound("OIDC login is not configured".to_string()))
+}
+
 /// POST /auth/register
 /// Create new user account
 pub async fn register(
     pool: web::Data<sqlx::PgPool>,
+    config: web::Data<Config>,
     session: Session,
     req: web::Json<CreateUserRequest>,
 ) -> AppResult<impl Responder> {
+    ensure_password_login(&config)?;
+
     // Validate email format
     if !is_valid_email(&req.email) {
-        return Err(AppError::Validation("Invalid email format".to_string()));
+        return Err(AppError::invalid_field(
+            "email",
+            codes::VALIDATION_INVALID_FORMAT,
+            "Invalid email format",
+        ));
     }
 
     // Validate password is provided
     if req.password.is_empty() {
-        return Err(AppError::Validation("Password is required".to_string()));
+        return Err(AppError::invalid_field(
+            "password",
+            codes::VALIDATION_REQUIRED,
+            "Password is required",
+        ));
     }
 
     // Create user (non-admin by default)
@@ -100,9 +143,12 @@ pub async fn register(
 /// Authenticate user and create session
 pub async fn login(
     pool: web::Data<sqlx::PgPool>,
+    config: web::Data<Config>,
     session: Session,
     req: web::Json<LoginRequest>,
 ) -> AppResult<impl Responder> {
+    ensure_password_login(&config)?;
+
     // Get user by email
     let user = UsersService::get_by_email(pool.get_ref(), &req.email)
         .await?
@@ -140,13 +186,91 @@ pub async fn get_current_user(user: AuthenticatedUser) -> impl Responder {
     HttpResponse::Ok().json(UserResponse::from(user.0))
 }
 
+/// GET /auth/methods
+/// Login methods enabled on this server
+pub async fn login_methods(
+    config: web::Data<Config>,
+    oidc: Option<web::Data<OidcClient>>,
+) -> impl Responder {
+    HttpResponse::Ok().json(LoginMethodsResponse {
+        password: config.security.password_login_enabled,
+        oidc: oidc.is_some(),
+    })
+}
+
+/// GET /auth/oidc/login
+/// Redirect to the OIDC provider to log in
+pub async fn oidc_login(
+    oidc: Option<web::Data<OidcClient>>,
+    session: Session,
+) -> AppResult<impl Responder> {
+    let oidc = oidc_client(oidc)?;
+    let url = oidc.authorization_url(&session).await?;
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, url))
+        .finish())
+}
+
+/// GET /auth/oidc/callback
+/// Complete an OIDC login and create the session
+///
+/// The user is linked by email, and created if there's none with it yet.
+pub async fn oidc_callback(
+    pool: web::Data<sqlx::PgPool>,
+    oidc: Option<web::Data<OidcClient>>,
+    session: Session,
+    query: web::Query<OidcCallbackQuery>,
+) -> AppResult<impl Responder> {
+    let oidc = oidc_client(oidc)?;
+
+    if let Some(error) = &query.error {
+        return Err(AppError::Unauthorized(format!(
+            "OIDC login failed: {}",
+            query.error_description.as_deref().unwrap_or(error)
+        )));
+    }
+    let (Some(code), Some(state)) = (&query.code, &query.state) else {
+        return Err(AppError::Unauthorized(
+            "Missing OIDC code or state".to_string(),
+        ));
+    };
+
+    let identity = oidc.complete_login(&session, code, state).await?;
+    let user = UsersService::find_or_create_sso_user(
+        pool.get_ref(),
+        &identity.email,
+        oidc.is_admin(&identity),
+    )
+    .await?;
+
+    // Check if user is active
+    if !user.is_active {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
+    }
+
+    // Update last login
+    UsersService::update_last_login(pool.get_ref(), user.id).await?;
+
+    // Set session
+    auth::set_user_session(&session, user.id)?;
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, oidc.config().dashboard_url.clone()))
+        .finish())
+}
+
 /// Configure auth routes
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
+            .wrap(RateLimit::new(RateLimitScope::Auth))
             .route("/register", web::post().to(register))
             .route("/login", web::post().to(login))
             .route("/logout", web::post().to(logout))
-            .route("/me", web::get().to(get_current_user)),
+            .route("/me", web::get().to(get_current_user))
+            .route("/methods", web::get().to(login_methods))
+            .route("/oidc/login", web::get().to(oidc_login))
+            .route("/oidc/callback", web::get().to(oidc_callback)),
     );
 }
@@ -0,0 +1,95 @@
+//! Debug file routes.
+//!
+//! - GET /api/projects/{project_id}/files/dsyms - List debug files
+//! - POST /api/projects/{project_id}/files/dsyms - Upload a Breakpad symbol file
+//! - DELETE /api/projects/{project_id}/files/dsyms/{id} - Delete debug file
+//!
+//! Uploads are multipart forms with the `.sym` file in a `file` part. Native
+//! events are symbolicated with these files when they are digested.
+
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use futures_util::StreamExt;
+
+use crate::auth::AuthenticatedUser;
+use crate::db::DbPool;
+use crate::digest::breakpad::MAX_SYMBOL_FILE_SIZE;
+use crate::error::{AppError, AppResult};
+use crate::services::{DebugFileService, ProjectMemberService, ProjectService};
+
+/// GET /api/projects/{project_id}/files/dsyms
+pub async fn list_debug_files(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let files = DebugFileService::list(pool.get_ref(), project_id).await?;
+    Ok(HttpResponse::Ok().json(files))
+}
+
+/// POST /api/projects/{project_id}/files/dsyms
+pub async fn upload_debug_file(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+    mut payload: Multipart,
+) -> AppResult<HttpResponse> {
+    let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let mut file = None;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(multipart_error)?;
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(multipart_error)?;
+            if data.len() + chunk.len() > MAX_SYMBOL_FILE_SIZE {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "Symbol file exceeds {} bytes",
+                    MAX_SYMBOL_FILE_SIZE
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        file = Some(data);
+    }
+
+    let file = file.ok_or_else(|| AppError::Validation("Missing file part".to_string()))?;
+    let debug_file = DebugFileService::upload(pool.get_ref(), project_id, &file).await?;
+    Ok(HttpResponse::Created().json(debug_file))
+}
+
+/// DELETE /api/projects/{project_id}/files/dsyms/{id}
+pub async fn delete_debug_file(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, i32)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    DebugFileService::delete(pool.get_ref(), project_id, id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+fn multipart_error(e: actix_multipart::MultipartError) -> AppError {
+    AppError::Validation(format!("Invalid multipart body: {}", e))
+}
+
+/// Configure debug file routes
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/projects/{project_id}/files/dsyms")
+            .route("", web::get().to(list_debug_files))
+            .route("", web::post().to(upload_debug_file))
+            .route("/{id}", web::delete().to(delete_debug_file)),
+    );
+}
@@ -2,20 +2,24 @@ use actix_web::{web, HttpResponse};
 use uuid::Uuid;
 
 use crate::auth::AuthenticatedUser;
+use crate::config::Config;
 use crate::db::DbPool;
 use crate::error::{AppError, AppResult};
-use crate::pagination::{EventCursor, ListEventsQuery, PaginatedResponse, PAGE_SIZE};
-use crate::services::{EventService, IssueService};
+use crate::models::Event;
+use crate::pagination::{EventCursor, EventSort, ListEventsQuery, PaginatedResponse};
+use crate::services::{EventService, IssueService, ProjectMemberService};
 
 /// GET /api/projects/{project_id}/issues/{issue_id}/events
 /// Lists events for an issue with cursor-based pagination
 pub async fn list_events(
     pool: web::Data<DbPool>,
+    config: web::Data<Config>,
     path: web::Path<(i32, Uuid)>,
     query: web::Query<ListEventsQuery>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify issue exists and belongs to the project
     let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
@@ -27,16 +31,18 @@ pub async fn list_events(
     let cursor = query
         .cursor
         .as_ref()
-        .map(|c| EventCursor::decode(c))
+        .map(|c| EventCursor::decode(c, issue_id))
         .transpose()?;
 
     // Execute paginated query
     let (events, has_more) = EventService::list_paginated(
         pool.get_ref(),
         issue_id,
+        query.by,
         query.order,
         cursor.as_ref(),
-        PAGE_SIZE,
+        query.dist.as_deref(),
+        query.per_page(&config.pagination)?,
     )
     .await?;
 
@@ -47,7 +53,14 @@ pub async fn list_events(
     let next_cursor = if has_more {
         events
             .last()
-            .map(|last| EventCursor::new(query.order.as_str(), last.digest_order).encode())
+            .map(|last| {
+                let cursor = EventCursor::new(query.order.as_str(), last.digest_order);
+                match query.by {
+                    EventSort::Received => cursor,
+                    EventSort::Timestamp => cursor.with_timestamp(last.timestamp),
+                }
+                .encode(issue_id)
+            })
             .transpose()?
     } else {
         None
@@ -61,9 +74,10 @@ pub async fn list_events(
 pub async fn get_event(
     pool: web::Data<DbPool>,
     path: web::Path<(i32, Uuid, Uuid)>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let (project_id, issue_id, event_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify issue exists and belongs to the project
     let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
@@ -77,8 +91,35 @@ pub async fn get_event(
         return Err(AppError::NotFound(format!("Event {} not found", event_id)));
     }
 
-    // Return full detail response (includes data field)
-    Ok(HttpResponse::Ok().json(event.to_detail_response()))
+    Ok(detail_response(&event))
+}
+
+/// Full detail response (includes data field)
+///
+/// Events whose payload was pruned answer 410 Gone with the metadata that's left.
+fn detail_response(event: &Event) -> HttpResponse {
+    if event.payload_pruned {
+        HttpResponse::Gone().json(event.to_detail_response())
+    } else {
+        HttpResponse::Ok().json(event.to_detail_response())
+    }
+}
+
+/// GET /api/projects/{project_id}/events/{event_id}
+/// Looks up an event by its SDK event_id without knowing the issue
+///
+/// The response includes the owning `issue_id`.
+pub async fn get_project_event(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, Uuid)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, event_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+
+    let event = EventService::get_by_event_id(pool.get_ref(), project_id, event_id).await?;
+
+    Ok(detail_response(&event))
 }
 
 /// Configure event routes
@@ -88,4 +129,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("", web::get().to(list_events))
             .route("/{event_id}", web::get().to(get_event)),
     );
+    cfg.service(
+        web::resource("/api/projects/{project_id}/events/{event_id}")
+            .route(web::get().to(get_project_event)),
+    );
 }
@@ -1,11 +1,30 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
 use actix_web::{http::StatusCode, web, HttpResponse};
 use serde::Serialize;
 
-use crate::db::{self, DbPool};
+use crate::build_info::{BuildInfo, BUILD_INFO};
+use crate::db::{self, DbHealth, DbPool};
+
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// Starts the uptime clock; call once when the server starts
+pub fn mark_started() {
+    STARTED_AT.get_or_init(Instant::now);
+}
+
+/// Seconds since the server started
+pub fn uptime_seconds() -> u64 {
+    STARTED_AT.get_or_init(Instant::now).elapsed().as_secs()
+}
 
 #[derive(Serialize)]
 pub struct LivenessResponse {
     status: &'static str,
+    #[serde(flatten)]
+    build: BuildInfo,
+    uptime_seconds: u64,
 }
 
 #[derive(Serialize)]
@@ -20,15 +39,38 @@ pub struct ReadinessChecks {
 }
 
 /// Liveness check - is the process running?
-/// Returns 200 if the server is alive.
+/// Returns 200 if the server is alive, along with its version, build and
+/// uptime.
 pub async fn liveness() -> HttpResponse {
-    HttpResponse::Ok().json(LivenessResponse { status: "ok" })
+    HttpResponse::Ok().json(LivenessResponse {
+        status: "ok",
+        build: BUILD_INFO,
+        uptime_seconds: uptime_seconds(),
+    })
 }
 
 /// Readiness check - is the service ready to handle requests?
 /// Returns 200 if all dependencies are available, 503 otherwise.
-pub async fn readiness(pool: web::Data<DbPool>) -> HttpResponse {
-    let db_healthy = db::health_check(pool.get_ref()).await;
+///
+/// During a database outage the check fails immediately, only querying the
+/// database again once the outage's backoff has passed.
+pub async fn readiness(
+    pool: web::Data<DbPool>,
+    db_health: Option<web::Data<DbHealth>>,
+) -> HttpResponse {
+    let db_healthy = match db_health {
+        Some(db_health) if db_health.retry_in().is_some() => false,
+        Some(db_health) => {
+            let healthy = db::health_check(pool.get_ref()).await;
+            if healthy {
+                db_health.record_success();
+            } else {
+                db_health.record_failure();
+            }
+            healthy
+        }
+        None => db::health_check(pool.get_ref()).await,
+    };
 
     let (status, db_status, http_status) = if db_healthy {
         ("ready", "ok", StatusCode::OK)
@@ -1,17 +1,26 @@
-use actix_web::{web, HttpRequest, HttpResponse};
+use std::path::Path;
+
+use actix_multipart::{Multipart, MultipartError};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
 use bytes::Bytes;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
 
+use crate::auth::sentry_auth::SentryCredentials;
 use crate::auth::SentryAuth;
-use crate::config::Config;
-use crate::db::DbPool;
-use crate::digest;
+use crate::config::{Config, IngestMaintenance};
+use crate::db::{self, DbHealth, DbPool};
+use crate::digest::spawn_digest;
 use crate::error::{AppError, AppResult};
+use crate::ingest::envelope::ParsedEnvelope;
+use crate::ingest::minidump::MAX_MINIDUMP_SIZE;
+use crate::ingest::parser::MAX_EVENT_SIZE;
 use crate::ingest::{
-    decompress_body, get_content_encoding, get_ingest_dir, store_event, EnvelopeParser,
-    EventMetadata,
+    build_event, decompress_body, get_content_encoding, get_ingest_dir, maintenance, origin,
+    parse_minidump, remote_addr, store_event, store_minidump, EnvelopeParser, EventMetadata,
+    IngestWriter, SpoolJob, TunnelDsn,
 };
-use crate::services::RateLimitService;
+use crate::services::{IngestedEventService, LiveRates, RateLimitService};
 
 /// Response for successful ingestion
 #[derive(serde::Serialize)]
@@ -19,7 +28,18 @@ pub struct IngestResponse {
     pub id: String,
 }
 
-/// POST /api/{project_id}/envelope/
+/// Content types accepted for envelopes
+///
+/// Browser SDKs post envelopes as `text/plain` to avoid CORS preflights, and
+/// some SDKs send raw bytes as `application/octet-stream`.
+const ENVELOPE_CONTENT_TYPES: &[&str] = &[
+    "application/x-sentry-envelope",
+    "application/json",
+    "application/octet-stream",
+    "text/plain",
+];
+
+/// POST /api/{project_id}/envelope/ (or /api/envelope/)
 /// Main ingestion endpoint compatible with Sentry SDK
 pub async fn ingest_envelope(
     pool: web::Data<DbPool>,
@@ -28,38 +48,100 @@ pub async fn ingest_envelope(
     auth: SentryAuth,
     body: Bytes,
 ) -> AppResult<HttpResponse> {
+    check_maintenance(&config)?;
+    check_content_type(&req)?;
+
+    let db_health = db_health(&req);
+
     // 0. Check rate limits (fail fast before processing)
-    if let Some(exceeded) = RateLimitService::check_quota(pool.get_ref(), &auth.project).await? {
-        log::warn!(
-            "Rate limit exceeded for project {}: retry_after={}s",
-            auth.project.id,
-            exceeded.retry_after
-        );
-        return Ok(HttpResponse::TooManyRequests()
-            .insert_header(("Retry-After", exceeded.retry_after.to_string()))
-            .json(serde_json::json!({
-                "error": "rate_limit_exceeded",
-                "retry_after": exceeded.retry_after
-            })));
+    if let Some(response) = check_rate_limit(pool.get_ref(), &db_health, &auth).await? {
+        return Ok(response);
     }
 
-    let ingested_at = Utc::now();
-    let ingest_dir = get_ingest_dir(config.ingest_dir.as_deref());
+    // 1. Decompress if needed
+    let content_encoding = get_content_encoding(&req);
+    let decompressed = decompress_body(body, content_encoding.as_deref())?;
+
+    // 2. Parse envelope
+    let mut parser = EnvelopeParser::new(&decompressed);
+    let envelope = parser.parse()?;
 
-    // 1. Get client IP
-    let remote_addr = req
-        .connection_info()
-        .realip_remote_addr()
-        .map(|s| s.to_string());
+    accept_envelope(pool.get_ref(), &config, &req, &db_health, &auth, envelope).await
+}
 
-    // 2. Decompress if needed
+/// POST /api/tunnel
+/// Envelopes forwarded by an app's backend for SDKs using the `tunnel` option
+///
+/// The project and key come from the `dsn` in the envelope header, so one
+/// URL serves every project. DSNs naming another host than this server (or
+/// one of TUNNEL_ALLOWED_HOSTS) are rejected with a 403.
+pub async fn ingest_tunnel(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    body: Bytes,
+) -> actix_web::Result<HttpResponse> {
+    check_maintenance(&config)?;
+    check_content_type(&req)?;
+
+    // The envelope is needed before anything else to know its project
     let content_encoding = get_content_encoding(&req);
     let decompressed = decompress_body(body, content_encoding.as_deref())?;
-
-    // 3. Parse envelope
     let mut parser = EnvelopeParser::new(&decompressed);
     let envelope = parser.parse()?;
 
+    let dsn = envelope
+        .headers
+        .dsn
+        .as_deref()
+        .ok_or_else(|| AppError::Validation("Missing dsn in envelope headers".to_string()))
+        .and_then(TunnelDsn::parse)?;
+    if !dsn.host_allowed(
+        req.connection_info().host(),
+        &config.ingest.tunnel_allowed_hosts,
+    ) {
+        log::warn!("Rejected tunneled envelope for host {}", dsn.host);
+        return Err(AppError::Forbidden(format!(
+            "Envelopes for {} can't be tunneled through this server",
+            dsn.host
+        ))
+        .into());
+    }
+
+    let credentials = SentryCredentials {
+        sentry_key: dsn.sentry_key,
+        sentry_version: None,
+        sentry_client: envelope
+            .headers
+            .sdk
+            .as_ref()
+            .and_then(|sdk| Some(format!("{}/{}", sdk.name.as_ref()?, sdk.version.as_ref()?))),
+    };
+    let auth = SentryAuth::resolve(&req, Some(dsn.project_id), Ok(credentials)).await?;
+
+    let db_health = db_health(&req);
+    if let Some(response) = check_rate_limit(pool.get_ref(), &db_health, &auth).await? {
+        return Ok(response);
+    }
+
+    Ok(accept_envelope(pool.get_ref(), &config, &req, &db_health, &auth, envelope).await?)
+}
+
+/// Spools the events of an authenticated envelope for digestion
+async fn accept_envelope(
+    pool: &DbPool,
+    config: &Config,
+    req: &HttpRequest,
+    db_health: &web::Data<DbHealth>,
+    auth: &SentryAuth,
+    envelope: ParsedEnvelope,
+) -> AppResult<HttpResponse> {
+    let ingested_at = Utc::now();
+    let ingest_dir = get_ingest_dir(config.ingest_dir.as_deref());
+
+    // 3. Get client IP
+    let remote_addr = remote_addr(req, config.security.trust_proxy_headers);
+
     // 4. Validate event_id
     let event_id = envelope
         .headers
@@ -70,55 +152,458 @@ pub async fn ingest_envelope(
     uuid::Uuid::parse_str(&event_id)
         .map_err(|_| AppError::Validation("event_id must be a valid UUID".to_string()))?;
 
-    // 5. Find item of type "event"
-    let event_item = envelope
+    // 5. Collect every item of type "event", validating all of them before
+    // anything is stored
+    let origin = origin::request_origin(req);
+    let mut events = Vec::new();
+    let mut filtered = 0;
+    for (index, item) in envelope
         .items
         .into_iter()
-        .find(|item| item.headers.item_type == "event");
+        .filter(|item| item.headers.item_type == "event")
+        .enumerate()
+    {
+        let event: serde_json::Value = serde_json::from_slice(&item.payload)
+            .map_err(|e| AppError::Validation(format!("Invalid event JSON: {}", e)))?;
+
+        // The envelope's event_id belongs to its first event; later ones
+        // (batched by proxies) carry their own
+        let id = if index == 0 {
+            event_id.clone()
+        } else {
+            item_event_id(&event)
+                .filter(|id| !events.iter().any(|(seen, _)| seen == id))
+                .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string())
+        };
 
-    let event_item = match event_item {
-        Some(item) => item,
-        None => {
-            // No event, just log and return OK
-            log::info!("No event item in envelope, ignoring");
+        if !check_origin(config, auth, origin.as_deref(), &id, &event)? {
+            filtered += 1;
+            continue;
+        }
+        events.push((id, item.payload));
+    }
+
+    if events.is_empty() {
+        if filtered > 0 {
             return Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }));
         }
+        // No event, just log and return OK
+        log::info!("No event item in envelope, ignoring");
+        return Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }));
+    }
+
+    if config.ingest.maintenance == IngestMaintenance::Drop {
+        maintenance::record_dropped(events.len() as u64);
+        return Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }));
+    }
+
+    // 6. Spool each event under its own event_id, once. When duplicates are
+    // merged, a repeated event_id is spooled under a file of its own so it
+    // can't overwrite the first payload before that's digested.
+    for (id, payload) in events {
+        let mut spool_id = None;
+        if !record_ingested(pool, db_health, auth.project.id, &id, ingested_at).await? {
+            if !config.digest.merge_duplicate_events {
+                log::info!("Event {} was already ingested, ignoring", id);
+                continue;
+            }
+            log::info!("Event {} was already ingested, merging the update", id);
+            spool_id = Some(uuid::Uuid::new_v4().simple().to_string());
+        }
+
+        let recorded = spool_id.is_none();
+        let metadata = EventMetadata {
+            event_id: id.clone(),
+            project_id: auth.project.id,
+            ingested_at,
+            remote_addr: remote_addr.clone(),
+            spool_id,
+        };
+        if let Err(e) = spool_event(
+            pool,
+            config,
+            req.app_data::<web::Data<IngestWriter>>(),
+            db_health,
+            &ingest_dir,
+            SpoolJob { metadata, payload },
+        )
+        .await
+        {
+            if recorded {
+                forget_ingested(pool, auth.project.id, &id).await;
+            }
+            return Err(e);
+        }
+        LiveRates::global().record_accepted(auth.project.id);
+    }
+
+    // 7. Return immediately with the first event's id (CORS handled by middleware)
+    Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }))
+}
+
+/// The event_id inside an event payload, if it's a valid UUID
+fn item_event_id(event: &serde_json::Value) -> Option<String> {
+    event
+        .get("event_id")
+        .and_then(|id| id.as_str())
+        .filter(|id| uuid::Uuid::parse_str(id).is_ok())
+        .map(str::to_string)
+}
+
+/// Stores an event and schedules its digest
+///
+/// In fast_ack mode the write and digest are left to the background writer,
+/// unless its queue is full.
+async fn spool_event(
+    pool: &DbPool,
+    config: &Config,
+    writer: Option<&web::Data<IngestWriter>>,
+    db_health: &web::Data<DbHealth>,
+    ingest_dir: &Path,
+    job: SpoolJob,
+) -> AppResult<()> {
+    let job = match writer {
+        Some(writer) => match writer.try_enqueue(job) {
+            Ok(()) => return Ok(()),
+            // Queue full: write it ourselves so backpressure reaches the SDK
+            Err(job) => job,
+        },
+        None => job,
     };
 
-    // 6. Validate that payload is valid JSON
-    let _: serde_json::Value = serde_json::from_slice(&event_item.payload)
-        .map_err(|e| AppError::Validation(format!("Invalid event JSON: {}", e)))?;
+    store_event(
+        ingest_dir,
+        job.metadata.spool_id(),
+        &job.payload,
+        config.compress_stored_events,
+    )
+    .await?;
 
-    // 7. Store event in filesystem
-    store_event(&ingest_dir, &event_id, &event_item.payload).await?;
+    spawn_digest(
+        pool,
+        config,
+        db_health.clone().into_inner(),
+        ingest_dir.to_path_buf(),
+        job.metadata,
+    );
+
+    Ok(())
+}
+
+/// POST /api/{project_id}/minidump/ (or /api/minidump/)
+/// Native crash upload (multipart form with an `upload_file_minidump` part)
+///
+/// An optional `sentry` part holds event JSON (release, tags, ...) that is
+/// merged into the event built from the minidump header.
+pub async fn ingest_minidump(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    req: HttpRequest,
+    auth: SentryAuth,
+    mut payload: Multipart,
+) -> AppResult<HttpResponse> {
+    check_maintenance(&config)?;
+    let db_health = db_health(&req);
+    if let Some(response) = check_rate_limit(pool.get_ref(), &db_health, &auth).await? {
+        return Ok(response);
+    }
+
+    let ingested_at = Utc::now();
+    let ingest_dir = get_ingest_dir(config.ingest_dir.as_deref());
+    let remote_addr = remote_addr(&req, config.security.trust_proxy_headers);
+
+    // Collect the minidump and the optional event JSON, skip anything else
+    let mut minidump = None;
+    let mut sentry = None;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(multipart_error)?;
+        let limit = match field.name() {
+            Some("upload_file_minidump") => MAX_MINIDUMP_SIZE,
+            Some("sentry") => MAX_EVENT_SIZE,
+            _ => continue,
+        };
+        let name = field.name().unwrap_or_default().to_string();
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(multipart_error)?;
+            if data.len() + chunk.len() > limit {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "{} exceeds {} bytes",
+                    name, limit
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        if name == "sentry" {
+            sentry = Some(data);
+        } else {
+            minidump = Some(data);
+        }
+    }
+
+    let minidump = minidump
+        .ok_or_else(|| AppError::Validation("Missing upload_file_minidump part".to_string()))?;
+    let info = parse_minidump(&minidump)?;
+
+    let sentry: Option<serde_json::Value> = sentry
+        .map(|data| serde_json::from_slice(&data))
+        .transpose()
+        .map_err(|e| AppError::Validation(format!("Invalid sentry JSON: {}", e)))?;
+
+    // Keep the SDK's event_id when it sent a valid one
+    let event_id = sentry
+        .as_ref()
+        .and_then(|s| s.get("event_id"))
+        .and_then(|id| id.as_str())
+        .and_then(|id| uuid::Uuid::parse_str(id).ok())
+        .unwrap_or_else(uuid::Uuid::new_v4)
+        .as_simple()
+        .to_string();
+
+    let event = build_event(&info, &event_id, sentry);
+    let origin = origin::request_origin(&req);
+    if !check_origin(&config, &auth, origin.as_deref(), &event_id, &event)? {
+        return Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }));
+    }
+    if config.ingest.maintenance == IngestMaintenance::Drop {
+        maintenance::record_dropped(1);
+        return Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }));
+    }
+    let event_bytes = serde_json::to_vec(&event)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize event: {}", e)))?;
+
+    if !record_ingested(
+        pool.get_ref(),
+        &db_health,
+        auth.project.id,
+        &event_id,
+        ingested_at,
+    )
+    .await?
+    {
+        log::info!("Event {} was already ingested, ignoring", event_id);
+        return Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }));
+    }
+
+    let stored = async {
+        store_minidump(&ingest_dir, auth.project.id, &event_id, &minidump).await?;
+        store_event(
+            &ingest_dir,
+            &event_id,
+            &event_bytes,
+            config.compress_stored_events,
+        )
+        .await
+    };
+    if let Err(e) = stored.await {
+        forget_ingested(pool.get_ref(), auth.project.id, &event_id).await;
+        return Err(e);
+    }
+
+    LiveRates::global().record_accepted(auth.project.id);
 
-    // 8. Create metadata
     let metadata = EventMetadata {
         event_id: event_id.clone(),
         project_id: auth.project.id,
         ingested_at,
         remote_addr,
+        spool_id: None,
     };
+    spawn_digest(
+        pool.get_ref(),
+        &config,
+        db_health.into_inner(),
+        ingest_dir,
+        metadata,
+    );
 
-    // 9. Spawn digest task
-    let pool_clone = pool.get_ref().clone();
-    let ingest_dir_clone = ingest_dir.clone();
-    let rate_limit_config = config.rate_limit.clone();
-    tokio::spawn(async move {
-        if let Err(e) = digest::process_event(
-            &pool_clone,
-            &metadata,
-            &ingest_dir_clone,
-            &rate_limit_config,
-        )
-        .await
-        {
-            log::error!("Failed to digest event {}: {:?}", metadata.event_id, e);
+    Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }))
+}
+
+/// Checks an event against the project's allowed domains, returning false
+/// if it's filtered out
+///
+/// A filtered event is dropped and counted, or rejected with a 403 when
+/// `ALLOWED_DOMAINS_STRICT` is set.
+fn check_origin(
+    config: &Config,
+    auth: &SentryAuth,
+    origin: Option<&str>,
+    event_id: &str,
+    event: &serde_json::Value,
+) -> AppResult<bool> {
+    let allowed = &auth.project.allowed_domains;
+    if origin::is_event_allowed(allowed, origin, event) {
+        return Ok(true);
+    }
+
+    let host = origin
+        .map(str::to_string)
+        .or_else(|| origin::event_origin(event))
+        .unwrap_or_default();
+    if config.ingest.allowed_domains_strict {
+        return Err(AppError::Forbidden(format!(
+            "Origin '{}' is not allowed for this project",
+            host
+        )));
+    }
+    log::info!(
+        "Event {} for project {} filtered: origin '{}' is not in its allowed domains",
+        event_id,
+        auth.project.id,
+        host
+    );
+    origin::record_filtered();
+    Ok(false)
+}
+
+/// Records an event as ingested, returning false if its event_id was
+/// ingested before
+///
+/// While the database is down the event is accepted unrecorded; the digest
+/// worker still skips it if it was already digested.
+async fn record_ingested(
+    pool: &DbPool,
+    db_health: &DbHealth,
+    project_id: i32,
+    event_id: &str,
+    ingested_at: DateTime<Utc>,
+) -> AppResult<bool> {
+    if db_health.retry_in().is_some() {
+        return Ok(true);
+    }
+
+    let event_id = uuid::Uuid::parse_str(event_id)
+        .map_err(|_| AppError::Validation("event_id must be a valid UUID".to_string()))?;
+    match IngestedEventService::record(pool, project_id, event_id, ingested_at).await {
+        Ok(recorded) => Ok(recorded),
+        Err(AppError::Database(e)) if db::is_unavailable(&e) => {
+            db_health.record_failure();
+            log::warn!(
+                "Database unavailable, accepting event {} without duplicate check",
+                event_id
+            );
+            Ok(true)
         }
-    });
+        Err(e) => Err(e),
+    }
+}
 
-    // 10. Return immediately (CORS handled by middleware)
-    Ok(HttpResponse::Ok().json(IngestResponse { id: event_id }))
+/// Forgets an event recorded by [`record_ingested`] that couldn't be stored,
+/// so the SDK's retry isn't dropped as a duplicate
+async fn forget_ingested(pool: &DbPool, project_id: i32, event_id: &str) {
+    let Ok(id) = uuid::Uuid::parse_str(event_id) else {
+        return;
+    };
+    if let Err(e) = IngestedEventService::forget(pool, project_id, id).await {
+        log::warn!(
+            "Failed to forget event {} after its write failed: {}",
+            event_id,
+            e
+        );
+    }
+}
+
+/// Rejects envelopes whose `Content-Type` isn't one of [`ENVELOPE_CONTENT_TYPES`]
+///
+/// Requests without a `Content-Type` are accepted. Parameters such as
+/// `; charset=utf-8` are ignored.
+fn check_content_type(req: &HttpRequest) -> AppResult<()> {
+    let Some(content_type) = req.headers().get(header::CONTENT_TYPE) else {
+        return Ok(());
+    };
+
+    let mime = content_type
+        .to_str()
+        .unwrap_or_default()
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if ENVELOPE_CONTENT_TYPES.contains(&mime.as_str()) {
+        return Ok(());
+    }
+
+    Err(AppError::UnsupportedMediaType(format!(
+        "Content-Type '{}' is not accepted for envelopes, expected one of: {}",
+        content_type.to_str().unwrap_or_default(),
+        ENVELOPE_CONTENT_TYPES.join(", ")
+    )))
+}
+
+/// The shared database health tracker, or a fresh one if none is registered
+fn db_health(req: &HttpRequest) -> web::Data<DbHealth> {
+    req.app_data::<web::Data<DbHealth>>()
+        .cloned()
+        .unwrap_or_else(|| web::Data::new(DbHealth::new()))
+}
+
+/// Builds a 429 response if the project is over quota
+///
+/// While the database is down the event is accepted unchecked; the digest
+/// worker checks the quota again before storing it.
+async fn check_rate_limit(
+    pool: &DbPool,
+    db_health: &DbHealth,
+    auth: &SentryAuth,
+) -> AppResult<Option<HttpResponse>> {
+    if db_health.retry_in().is_some() {
+        return Ok(None);
+    }
+
+    let exceeded = match RateLimitService::check_quota(pool, auth.project.id).await {
+        Ok(exceeded) => exceeded,
+        Err(AppError::Database(e)) if db::is_unavailable(&e) => {
+            db_health.record_failure();
+            log::warn!(
+                "Database unavailable, accepting event for project {} without quota check",
+                auth.project.id
+            );
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    let Some(exceeded) = exceeded else {
+        return Ok(None);
+    };
+
+    log::warn!(
+        "Rate limit exceeded for project {}: retry_after={}s",
+        auth.project.id,
+        exceeded.retry_after
+    );
+    Ok(Some(
+        HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", exceeded.retry_after.to_string()))
+            .json(serde_json::json!({
+                "error": "rate_limit_exceeded",
+                "retry_after": exceeded.retry_after
+            })),
+    ))
+}
+
+/// Seconds SDKs are told to wait while ingestion is paused
+const MAINTENANCE_RETRY_AFTER_SECS: u64 = 60;
+
+/// A 503 when ingestion is paused with INGEST_MAINTENANCE=reject
+///
+/// Drop mode is handled once events are validated, so SDKs still get their
+/// event_id back.
+fn check_maintenance(config: &Config) -> AppResult<()> {
+    if config.ingest.maintenance != IngestMaintenance::Reject {
+        return Ok(());
+    }
+    Err(AppError::Unavailable {
+        message: "Ingestion is paused for maintenance".to_string(),
+        retry_after: MAINTENANCE_RETRY_AFTER_SECS,
+    })
+}
+
+fn multipart_error(e: MultipartError) -> AppError {
+    AppError::Validation(format!("Invalid multipart body: {}", e))
 }
 
 /// POST /api/{project_id}/store/
@@ -140,19 +625,64 @@ pub async fn options() -> HttpResponse {
     HttpResponse::Ok().finish()
 }
 
-/// Configures the ingest routes
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api/{project_id}")
-            .route("/envelope/", web::post().to(ingest_envelope))
-            .route(
-                "/envelope/",
-                web::method(actix_web::http::Method::OPTIONS).to(options),
-            )
-            .route("/store/", web::post().to(ingest_store))
-            .route(
-                "/store/",
-                web::method(actix_web::http::Method::OPTIONS).to(options),
-            ),
-    );
+/// Whether `path` is one of the ingest routes registered by [`configure`]
+///
+/// Used before routing, by middleware that treats SDK traffic differently.
+pub fn is_ingest_path(path: &str) -> bool {
+    match path.strip_prefix("/api/") {
+        Some("tunnel" | "envelope/" | "minidump/") => true,
+        Some(rest) => rest
+            .split_once('/')
+            .is_some_and(|(_, endpoint)| matches!(endpoint, "envelope/" | "minidump/" | "store/")),
+        None => false,
+    }
+}
+
+/// Configures the ingest routes, reading raw bodies of up to `body_limit` bytes
+///
+/// `/api/envelope/` and `/api/minidump/` serve DSNs without a project id;
+/// the project comes from the `sentry_key`. `/api/tunnel` is registered
+/// before the `/api/{project_id}` scope, which would match it too. The body
+/// limit is set on these routes only; the rest of `/api` keeps the API limit.
+pub fn configure(body_limit: usize) -> impl FnOnce(&mut web::ServiceConfig) {
+    let payload = web::PayloadConfig::new(body_limit);
+    move |cfg| {
+        cfg.service(
+            web::resource("/api/tunnel")
+                .app_data(payload.clone())
+                .route(web::post().to(ingest_tunnel))
+                .route(web::method(actix_web::http::Method::OPTIONS).to(options)),
+        )
+        .service(
+            web::resource("/api/envelope/")
+                .app_data(payload.clone())
+                .route(web::post().to(ingest_envelope))
+                .route(web::method(actix_web::http::Method::OPTIONS).to(options)),
+        )
+        .service(
+            web::resource("/api/minidump/")
+                .app_data(payload.clone())
+                .route(web::post().to(ingest_minidump))
+                .route(web::method(actix_web::http::Method::OPTIONS).to(options)),
+        )
+        .service(
+            web::scope("/api/{project_id}")
+                .app_data(payload)
+                .route("/envelope/", web::post().to(ingest_envelope))
+                .route(
+                    "/envelope/",
+                    web::method(actix_web::http::Method::OPTIONS).to(options),
+                )
+                .route("/minidump/", web::post().to(ingest_minidump))
+                .route(
+                    "/minidump/",
+                    web::method(actix_web::http::Method::OPTIONS).to(options),
+                )
+                .route("/store/", web::post().to(ingest_store))
+                .route(
+                    "/store/",
+                    web::method(actix_web::http::Method::OPTIONS).to(options),
+                ),
+        );
+    }
 }
@@ -1,60 +1,243 @@
-use actix_web::{web, HttpResponse};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::time::Duration;
+
+use actix_web::http::header;
+use actix_web::{web, HttpRequest, HttpResponse};
+use bytes::Bytes;
+use chrono::Utc;
+use futures_util::StreamExt;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tokio::time::Interval;
 use uuid::Uuid;
 
-use crate::auth::AuthenticatedUser;
+use crate::auth::{AuthenticatedUser, BearerAuth};
+use crate::config::Config;
 use crate::db::DbPool;
-use crate::error::{AppError, AppResult};
-use crate::models::UpdateIssueState;
-use crate::pagination::{ListIssuesQuery, OffsetPaginatedResponse};
-use crate::services::{IssueService, ProjectService};
+use crate::error::{codes, AppError, AppResult};
+use crate::models::{
+    Actor, AuditAction, IssueAggregateResponse, IssueBundleQuery, IssueDetailQuery,
+    IssueUsersExportQuery, ResolveIssue, UpdateIssueState,
+};
+use crate::pagination::{
+    AggregateIssuesQuery, ListIssuesQuery, ListTrashQuery, OffsetPaginatedResponse,
+    DEFAULT_AGGREGATE_PERIOD, MAX_SPARKLINE_PAGE_SIZE,
+};
+use crate::services::issue_user::{csv_rows, EXPORT_BATCH_SIZE, EXPORT_CSV_HEADER};
+use crate::services::{
+    AuditLogService, EventService, IssueContextService, IssueService, IssueStream,
+    IssueStreamEvent, IssueUserService, ProjectMemberService, ProjectService, SavedSearchService,
+};
+
+/// Interval between keep-alive comments on idle issue streams
+pub const STREAM_HEARTBEAT: Duration = Duration::from_secs(15);
 
 /// GET /api/projects/{project_id}/issues
 /// Lists issues for a project with offset-based pagination
+///
+/// `?saved_search={id}` expands the stored parameters of a saved search;
+/// parameters given explicitly in the request override them.
+/// `?include=sparkline` attaches the events per hour of the last 24 hours,
+/// for pages of at most [`MAX_SPARKLINE_PAGE_SIZE`] issues.
 pub async fn list_issues(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
+    config: web::Data<Config>,
     path: web::Path<i32>,
     query: web::Query<ListIssuesQuery>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify project exists and get slug for response
     let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
 
+    let query = match query.saved_search {
+        Some(search_id) => {
+            let search =
+                SavedSearchService::get_visible(pool.get_ref(), search_id, user.0.id).await?;
+            if search.project_id.is_some_and(|id| id != project_id) {
+                return Err(AppError::Validation(format!(
+                    "Saved search {} belongs to another project",
+                    search_id
+                )));
+            }
+            SavedSearchService::expand(&search, req.query_string())?
+        }
+        None => query.into_inner(),
+    };
+    let per_page = query.per_page(&config.pagination)?;
+    let with_sparkline = query.includes("sparkline");
+    if with_sparkline && per_page > MAX_SPARKLINE_PAGE_SIZE {
+        return Err(AppError::invalid_field(
+            "per_page",
+            codes::VALIDATION,
+            format!(
+                "include=sparkline requires per_page of at most {}",
+                MAX_SPARKLINE_PAGE_SIZE
+            ),
+        ));
+    }
+
     // Execute paginated query with offset
     let (issues, total_count) = IssueService::list_offset(
         pool.get_ref(),
         project_id,
-        query.sort,
+        query.sort(project.default_issue_sort),
         query.order,
         query.filter,
+        query.dist.as_deref(),
+        query.handled,
+        query.priority,
         query.page,
-        query.per_page,
+        per_page,
     )
     .await?;
 
-    // Build responses
+    // Build responses, flagging the issues the user has opened
+    let issue_ids: Vec<Uuid> = issues.iter().map(|i| i.id).collect();
+    let seen = IssueService::seen_issue_ids(pool.get_ref(), user.0.id, &issue_ids).await?;
+    let mut sparklines = if with_sparkline {
+        IssueService::sparklines(pool.get_ref(), &issue_ids).await?
+    } else {
+        HashMap::new()
+    };
     let responses: Vec<_> = issues
         .iter()
-        .map(|i| i.to_response(&project.slug))
+        .map(|i| {
+            let mut response = i.to_response(&project.slug, config.digest.max_issue_title_length);
+            response.is_seen = seen.contains(&i.id);
+            if with_sparkline {
+                response.sparkline = Some(sparklines.remove(&i.id).unwrap_or_default());
+            }
+            response
+        })
         .collect();
 
     Ok(HttpResponse::Ok().json(OffsetPaginatedResponse::new(
         responses,
         total_count,
         query.page,
-        query.per_page,
+        per_page,
     )))
 }
 
+/// GET /api/projects/{project_id}/issues/aggregate
+/// Counts issues and their events in a period per error type or transaction
+///
+/// `?group_by=calculated_type|transaction` picks the grouping and
+/// `?period=7d` the window; `filter` and `dist` select issues as in the
+/// list. Returns the groups with the most events first.
+pub async fn aggregate_issues(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    query: web::Query<AggregateIssuesQuery>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let since = Utc::now() - query.period()?;
+    let groups = IssueService::aggregate(
+        pool.get_ref(),
+        project_id,
+        query.group_by,
+        query.filter,
+        query.dist.as_deref(),
+        since,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(IssueAggregateResponse {
+        group_by: query.group_by.as_str(),
+        period: query
+            .period
+            .clone()
+            .unwrap_or_else(|| DEFAULT_AGGREGATE_PERIOD.to_string()),
+        groups,
+    }))
+}
+
+/// GET /api/projects/{project_id}/issues/stream
+/// Server-Sent Events stream of the project's created and updated issues
+///
+/// Authenticated with an API token. Each change is sent as an `issue` event
+/// with a JSON payload; a comment line every [`STREAM_HEARTBEAT`] keeps idle
+/// connections open through proxies.
+pub async fn stream_issues(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    _auth: BearerAuth,
+) -> AppResult<HttpResponse> {
+    let project_id = path.into_inner();
+
+    // Verify project exists
+    ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let receiver = IssueStream::global().subscribe();
+    let mut heartbeat = tokio::time::interval(STREAM_HEARTBEAT);
+    heartbeat.tick().await;
+
+    let stream = futures_util::stream::unfold(
+        (receiver, heartbeat),
+        move |(mut receiver, mut heartbeat)| async move {
+            let chunk = next_stream_chunk(&mut receiver, &mut heartbeat, project_id).await?;
+            Some((Ok::<_, Infallible>(chunk), (receiver, heartbeat)))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(stream))
+}
+
+/// Waits for the project's next issue update, or a heartbeat while idle
+async fn next_stream_chunk(
+    receiver: &mut Receiver<IssueStreamEvent>,
+    heartbeat: &mut Interval,
+    project_id: i32,
+) -> Option<Bytes> {
+    loop {
+        let update = tokio::select! {
+            update = receiver.recv() => update,
+            _ = heartbeat.tick() => return Some(Bytes::from_static(b": heartbeat\n\n")),
+        };
+
+        match update {
+            Ok(update) if update.project_id == project_id => match serde_json::to_string(&update) {
+                Ok(data) => return Some(Bytes::from(format!("event: issue\ndata: {}\n\n", data))),
+                Err(e) => log::error!("Failed to serialize issue update: {}", e),
+            },
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => log::warn!(
+                "Issue stream for project {} skipped {} updates",
+                project_id,
+                skipped
+            ),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
 /// GET /api/projects/{project_id}/issues/{issue_id}
-/// Gets a single issue by ID
+/// Gets a single issue by ID and marks it as seen by the user
+///
+/// `seen_by` lists the users who opened the issue before this request,
+/// `regressed_at` when it was reopened, `priority_history` how its
+/// priority changed and `activity` what else happened to it.
+/// `?include=grouping` adds the issue's grouping keys.
 pub async fn get_issue(
     pool: web::Data<DbPool>,
+    config: web::Data<Config>,
     path: web::Path<(i32, Uuid)>,
-    _user: AuthenticatedUser,
+    query: web::Query<IssueDetailQuery>,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify project exists and get slug
     let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
@@ -66,18 +249,203 @@ pub async fn get_issue(
         return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
     }
 
-    Ok(HttpResponse::Ok().json(issue.to_response(&project.slug)))
+    let seen_by = IssueService::seen_by(pool.get_ref(), issue_id).await?;
+    let regressed_at = IssueService::regressed_at(pool.get_ref(), issue_id).await?;
+    let priority_history = IssueService::priority_history(pool.get_ref(), issue_id).await?;
+    let activity = IssueService::activity(pool.get_ref(), issue_id).await?;
+    IssueService::mark_seen_in_background(pool.get_ref(), issue_id, user.0.id);
+
+    let mut response = issue.to_response(&project.slug, config.digest.max_issue_title_length);
+    response.is_seen = true;
+    response.seen_by = Some(seen_by);
+    response.regressed_at = Some(regressed_at);
+    response.priority_history = Some(priority_history);
+    response.activity = Some(activity);
+    if query.includes("grouping") {
+        let groupings = IssueService::groupings(pool.get_ref(), issue_id).await?;
+        response.groupings = Some(groupings.iter().map(|g| g.to_response()).collect());
+    }
+    if query.includes("latest_event") {
+        let latest = EventService::latest(pool.get_ref(), &issue).await?;
+        response.latest_event = latest.map(|e| e.to_detail_response());
+    }
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// GET /api/projects/{project_id}/issues/{issue_id}/bundle
+/// Downloads the issue and its latest events as a single JSON document
+pub async fn get_issue_bundle(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(i32, Uuid)>,
+    query: web::Query<IssueBundleQuery>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+
+    let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+    let bundle = IssueService::bundle(
+        pool.get_ref(),
+        &project,
+        issue_id,
+        query.event_count(),
+        config.digest.max_issue_title_length,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.json\"", bundle.issue.short_id),
+        ))
+        .json(bundle))
+}
+
+/// GET /api/projects/{project_id}/issues/{issue_id}/users/export
+/// Downloads the end users the issue affected as CSV, for support outreach
+///
+/// The users are personal data, so only admins and project members can
+/// download them and each download is recorded in the audit log.
+/// `?since=` (RFC 3339) only exports users first affected from then on.
+/// Users are streamed in batches, oldest first.
+pub async fn export_issue_users(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, Uuid)>,
+    query: web::Query<IssueUsersExportQuery>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+
+    let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
+    if issue.project_id != project_id {
+        return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
+    }
+    let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let since = query.since;
+    AuditLogService::record(
+        pool.get_ref(),
+        user.0.id,
+        Some(project_id),
+        AuditAction::IssueUsersExport,
+        serde_json::json!({ "issue_id": issue_id, "since": since }),
+    )
+    .await?;
+
+    // Each batch resumes after the last user of the previous one; `None`
+    // once the last batch is sent
+    let pool = pool.get_ref().clone();
+    let header = Bytes::from_static(EXPORT_CSV_HEADER.as_bytes());
+    let rows = futures_util::stream::try_unfold(Some(None), move |after| {
+        let pool = pool.clone();
+        async move {
+            let Some(after) = after else {
+                return Ok(None);
+            };
+            let users = IssueUserService::export_batch(&pool, issue_id, since, after).await?;
+            if users.is_empty() {
+                return Ok(None);
+            }
+            let next = match users.last() {
+                Some(last) if users.len() as i64 == EXPORT_BATCH_SIZE => {
+                    Some(Some((last.first_seen, last.user_key.clone())))
+                }
+                _ => None,
+            };
+            Ok::<_, AppError>(Some((Bytes::from(csv_rows(&users)), next)))
+        }
+    });
+    let body = futures_util::stream::once(async { Ok(header) }).chain(rows);
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}-users.csv\"",
+                issue.short_id(&project.slug)
+            ),
+        ))
+        .streaming(body))
+}
+
+/// GET /api/projects/{project_id}/issues/{issue_id}/contexts
+/// Gets the OS, browser and runtime breakdowns of an issue's events
+pub async fn get_issue_contexts(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, Uuid)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+
+    // Verify issue belongs to the project
+    let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
+    if issue.project_id != project_id {
+        return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
+    }
+
+    let contexts = IssueContextService::breakdown(pool.get_ref(), issue_id).await?;
+
+    Ok(HttpResponse::Ok().json(contexts))
+}
+
+/// GET /api/projects/{project_id}/issues/{issue_id}/level-breakdown
+/// Counts the issue's events per level (error, warning, fatal, ...)
+pub async fn get_issue_level_breakdown(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, Uuid)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+
+    // Verify issue belongs to the project
+    let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
+    if issue.project_id != project_id {
+        return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
+    }
+
+    let breakdown = EventService::level_breakdown(pool.get_ref(), issue_id).await?;
+
+    Ok(HttpResponse::Ok().json(breakdown))
+}
+
+/// GET /api/projects/{project_id}/issues/{issue_id}/transactions
+/// Counts the issue's events per transaction, to show which routes it affects
+pub async fn get_issue_transactions(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, Uuid)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+
+    // Verify issue belongs to the project
+    let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
+    if issue.project_id != project_id {
+        return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
+    }
+
+    let breakdown = EventService::transaction_breakdown(pool.get_ref(), issue_id).await?;
+
+    Ok(HttpResponse::Ok().json(breakdown))
 }
 
 /// PATCH /api/projects/{project_id}/issues/{issue_id}
-/// Updates issue state (resolve, mute, etc.)
+/// Updates issue state (resolve, mute, etc.) and its sample rate
 pub async fn update_issue(
     pool: web::Data<DbPool>,
+    config: web::Data<Config>,
     path: web::Path<(i32, Uuid)>,
     body: web::Json<UpdateIssueState>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify project exists and get slug
     let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
@@ -88,27 +456,49 @@ pub async fn update_issue(
         return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
     }
 
+    // Sampling first, so an invalid rate leaves the state alone
+    let issue = match body.sample_rate {
+        Some(sample_rate) => {
+            IssueService::set_sample_rate(pool.get_ref(), issue_id, sample_rate).await?
+        }
+        None => issue,
+    };
+    let issue = match body.priority {
+        Some(priority) => {
+            IssueService::set_priority(pool.get_ref(), issue_id, priority, Actor::User(user.0.id))
+                .await?
+        }
+        None => issue,
+    };
+
     // Apply state changes
     // Priority: is_resolved takes precedence over is_muted
     let updated = match (body.is_resolved, body.is_muted) {
-        (Some(true), _) => IssueService::resolve(pool.get_ref(), issue_id).await?,
+        (Some(true), _) => {
+            IssueService::resolve(pool.get_ref(), issue_id, Actor::User(user.0.id)).await?
+        }
         (Some(false), _) => IssueService::unresolve(pool.get_ref(), issue_id).await?,
         (None, Some(true)) => IssueService::mute(pool.get_ref(), issue_id).await?,
         (None, Some(false)) => IssueService::unmute(pool.get_ref(), issue_id).await?,
         (None, None) => issue, // No changes requested
     };
 
-    Ok(HttpResponse::Ok().json(updated.to_response(&project.slug)))
+    let seen = IssueService::seen_issue_ids(pool.get_ref(), user.0.id, &[issue_id]).await?;
+    let mut response = updated.to_response(&project.slug, config.digest.max_issue_title_length);
+    response.is_seen = seen.contains(&issue_id);
+
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// DELETE /api/projects/{project_id}/issues/{issue_id}
-/// Soft-deletes an issue
+/// Moves an issue to the trash
 pub async fn delete_issue(
     pool: web::Data<DbPool>,
     path: web::Path<(i32, Uuid)>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
 
     // Verify issue belongs to the project before deleting
     let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
@@ -121,13 +511,135 @@ pub async fn delete_issue(
     Ok(HttpResponse::NoContent().finish())
 }
 
+/// GET /api/projects/{project_id}/issues/trash
+/// Lists deleted issues that can still be restored, most recently deleted first
+pub async fn list_trash(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<i32>,
+    query: web::Query<ListTrashQuery>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let project_id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let per_page = query.per_page(&config.pagination)?;
+    let (issues, total_count) = IssueService::list_trash(
+        pool.get_ref(),
+        project_id,
+        config.retention.issue_trash_days,
+        query.page,
+        per_page,
+    )
+    .await?;
+
+    let items = issues
+        .iter()
+        .map(|issue| issue.to_response(&project.slug, config.digest.max_issue_title_length))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(OffsetPaginatedResponse::new(
+        items,
+        total_count,
+        query.page,
+        per_page,
+    )))
+}
+
+/// POST /api/projects/{project_id}/issues/{issue_id}/restore
+/// Takes an issue out of the trash
+pub async fn restore_issue(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(i32, Uuid)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let issue =
+        IssueService::get_deleted(pool.get_ref(), issue_id, config.retention.issue_trash_days)
+            .await?;
+    if issue.project_id != project_id {
+        return Err(AppError::NotFound(format!(
+            "Deleted issue {} not found",
+            issue_id
+        )));
+    }
+
+    let restored =
+        IssueService::restore(pool.get_ref(), issue_id, config.retention.issue_trash_days).await?;
+
+    Ok(HttpResponse::Ok()
+        .json(restored.to_response(&project.slug, config.digest.max_issue_title_length)))
+}
+
+/// POST /api/projects/{project_id}/issues/{issue_id}/resolve
+/// Resolves an issue, optionally with the `commit` or `pr_url` that fixed it
+///
+/// The body can be left out. Resolving again replaces both references.
+pub async fn resolve_issue(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(i32, Uuid)>,
+    body: Option<web::Json<ResolveIssue>>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (project_id, issue_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, project_id).await?;
+    let project = ProjectService::get_by_id(pool.get_ref(), project_id).await?;
+
+    let resolution = body.map(web::Json::into_inner).unwrap_or_default();
+
+    let issue = IssueService::get_by_id(pool.get_ref(), issue_id).await?;
+    if issue.project_id != project_id {
+        return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
+    }
+
+    let resolved = IssueService::resolve_with(
+        pool.get_ref(),
+        issue_id,
+        Actor::User(user.0.id),
+        &resolution,
+    )
+    .await?;
+
+    let seen = IssueService::seen_issue_ids(pool.get_ref(), user.0.id, &[issue_id]).await?;
+    let mut response = resolved.to_response(&project.slug, config.digest.max_issue_title_length);
+    response.is_seen = seen.contains(&issue_id);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 /// Configure issue routes
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/projects/{project_id}/issues")
             .route("", web::get().to(list_issues))
+            // Before /{issue_id}, which would match these paths too
+            .route("/aggregate", web::get().to(aggregate_issues))
+            .route("/stream", web::get().to(stream_issues))
+            .route("/trash", web::get().to(list_trash))
             .route("/{issue_id}", web::get().to(get_issue))
+            .route("/{issue_id}/bundle", web::get().to(get_issue_bundle))
+            .route("/{issue_id}/contexts", web::get().to(get_issue_contexts))
+            .route(
+                "/{issue_id}/users/export",
+                web::get().to(export_issue_users),
+            )
+            .route(
+                "/{issue_id}/level-breakdown",
+                web::get().to(get_issue_level_breakdown),
+            )
+            .route(
+                "/{issue_id}/transactions",
+                web::get().to(get_issue_transactions),
+            )
             .route("/{issue_id}", web::patch().to(update_issue))
-            .route("/{issue_id}", web::delete().to(delete_issue)),
+            .route("/{issue_id}", web::delete().to(delete_issue))
+            .route("/{issue_id}/resolve", web::post().to(resolve_issue))
+            .route("/{issue_id}/restore", web::post().to(restore_issue)),
     );
 }
@@ -0,0 +1,42 @@
+//! Routes about the caller.
+//!
+//! - GET /api/me/overview - Dashboard home page: recent open issues and projects
+
+use actix_web::{web, HttpResponse};
+
+use crate::auth::{AuthenticatedUser, BearerAuth};
+use crate::config::Config;
+use crate::db::DbPool;
+use crate::error::{AppError, AppResult};
+use crate::services::UsersService;
+
+/// GET /api/me/overview
+///
+/// Works with a dashboard session or an API token. Tokens don't belong to a
+/// user, so they get the issues of every project and no project list.
+pub async fn get_overview(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    user: Option<AuthenticatedUser>,
+    token: Option<BearerAuth>,
+) -> AppResult<HttpResponse> {
+    let user = match (user, token) {
+        (Some(user), _) => Some(user.0),
+        (None, Some(_)) => None,
+        (None, None) => return Err(AppError::Unauthorized("Not authenticated".to_string())),
+    };
+
+    let overview = UsersService::overview(
+        pool.get_ref(),
+        user.as_ref(),
+        config.digest.max_issue_title_length,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(overview))
+}
+
+/// Configure routes about the caller
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/api/me").route("/overview", web::get().to(get_overview)));
+}
@@ -0,0 +1,182 @@
+use std::fmt::Write;
+
+use actix_web::{web, HttpResponse};
+
+use crate::auth::ProjectCache;
+use crate::digest::{sampling, DigestLatency, DigestRunner, LatencySnapshot};
+use crate::ingest::{fsync, maintenance, origin, IngestWriter};
+use crate::services::{LiveRates, RateWindows};
+
+/// Metrics in the Prometheus text format
+/// GET /metrics
+///
+/// Like the health checks this needs no auth and never touches the database.
+pub async fn metrics(
+    writer: Option<web::Data<IngestWriter>>,
+    project_cache: Option<web::Data<ProjectCache>>,
+) -> HttpResponse {
+    let (depth, capacity) = writer
+        .map(|writer| (writer.queue_depth(), writer.queue_capacity()))
+        .unwrap_or_default();
+
+    let mut body = String::new();
+    gauge(
+        &mut body,
+        "rustrak_ingest_queue_depth",
+        "Events waiting for the background ingest writer",
+        depth,
+    );
+    gauge(
+        &mut body,
+        "rustrak_ingest_queue_capacity",
+        "Events the background ingest writer can hold (0 when INGEST_MODE=sync)",
+        capacity,
+    );
+
+    let fsync_writer = fsync::writer();
+    let _ = writeln!(
+        body,
+        "# HELP rustrak_ingest_fsync_policy When event files are synced to disk (INGEST_FSYNC)"
+    );
+    let _ = writeln!(body, "# TYPE rustrak_ingest_fsync_policy gauge");
+    let _ = writeln!(
+        body,
+        "rustrak_ingest_fsync_policy{{policy=\"{}\"}} 1",
+        fsync_writer.policy().as_str()
+    );
+    gauge(
+        &mut body,
+        "rustrak_ingest_fsync_pending",
+        "Event files waiting for the batch sync (INGEST_FSYNC=batch)",
+        fsync_writer.pending(),
+    );
+
+    let runner = DigestRunner::global();
+    gauge(
+        &mut body,
+        "rustrak_digest_backlog",
+        "Stored events waiting to be digested or being digested",
+        runner.backlog(),
+    );
+    gauge(
+        &mut body,
+        "rustrak_digest_concurrency",
+        "Max events digested at once, raised while an admin drain runs",
+        runner.concurrency(),
+    );
+
+    let (hits, misses) = project_cache
+        .map(|cache| (cache.hits(), cache.misses()))
+        .unwrap_or_default();
+    counter(
+        &mut body,
+        "rustrak_ingest_auth_cache_hits_total",
+        "Ingest authentications answered from the project/key cache",
+        hits,
+    );
+    counter(
+        &mut body,
+        "rustrak_ingest_auth_cache_misses_total",
+        "Ingest authentications that looked up the project/key in the database",
+        misses,
+    );
+    counter(
+        &mut body,
+        "rustrak_ingest_filtered_events_total",
+        "Browser events dropped because their origin isn't in the project's allowed domains",
+        origin::filtered_count(),
+    );
+    counter(
+        &mut body,
+        "rustrak_ingest_dropped_events_total",
+        "Events discarded because ingestion is in maintenance drop mode",
+        maintenance::dropped_count(),
+    );
+    counter(
+        &mut body,
+        "rustrak_digest_sampled_events_total",
+        "Events counted on their issue but not stored because the issue is sampled",
+        sampling::sampled_count(),
+    );
+
+    histogram(
+        &mut body,
+        "rustrak_digest_latency_seconds",
+        "Time from ingest to stored of the events digested since startup",
+        &DigestLatency::global().snapshot(),
+    );
+
+    live_rates(&mut body);
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(body)
+}
+
+/// Per-project events per second of this instance, by window
+fn live_rates(body: &mut String) {
+    let rates = LiveRates::global().all();
+    rate_gauge(
+        body,
+        "rustrak_live_accepted_events_per_second",
+        "Events per second accepted by this instance, per project",
+        rates.iter().map(|r| (r.project_id, r.accepted)),
+    );
+    rate_gauge(
+        body,
+        "rustrak_live_digested_events_per_second",
+        "Events per second digested by this instance, per project",
+        rates.iter().map(|r| (r.project_id, r.digested)),
+    );
+}
+
+fn rate_gauge(
+    body: &mut String,
+    name: &str,
+    help: &str,
+    rates: impl Iterator<Item = (i32, RateWindows)>,
+) {
+    let _ = writeln!(body, "# HELP {} {}", name, help);
+    let _ = writeln!(body, "# TYPE {} gauge", name);
+    for (project_id, windows) in rates {
+        for (window, value) in [("10s", windows.last_10s), ("60s", windows.last_60s)] {
+            let _ = writeln!(
+                body,
+                "{}{{project_id=\"{}\",window=\"{}\"}} {}",
+                name, project_id, window, value
+            );
+        }
+    }
+}
+
+fn histogram(body: &mut String, name: &str, help: &str, snapshot: &LatencySnapshot) {
+    let _ = writeln!(body, "# HELP {} {}", name, help);
+    let _ = writeln!(body, "# TYPE {} histogram", name);
+    for (bound, count) in &snapshot.buckets {
+        let le = if bound.is_infinite() {
+            "+Inf".to_string()
+        } else {
+            bound.to_string()
+        };
+        let _ = writeln!(body, "{}_bucket{{le=\"{}\"}} {}", name, le, count);
+    }
+    let _ = writeln!(body, "{}_sum {}", name, snapshot.sum);
+    let _ = writeln!(body, "{}_count {}", name, snapshot.count);
+}
+
+fn gauge(body: &mut String, name: &str, help: &str, value: usize) {
+    let _ = writeln!(body, "# HELP {} {}", name, help);
+    let _ = writeln!(body, "# TYPE {} gauge", name);
+    let _ = writeln!(body, "{} {}", name, value);
+}
+
+fn counter(body: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(body, "# HELP {} {}", name, help);
+    let _ = writeln!(body, "# TYPE {} counter", name);
+    let _ = writeln!(body, "{} {}", name, value);
+}
+
+/// Configures the metrics route
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics));
+}
@@ -1,8 +1,14 @@
+pub mod admin;
 pub mod alerts;
 pub mod auth;
+pub mod debug_files;
 pub mod events;
 pub mod health;
 pub mod ingest;
 pub mod issues;
+pub mod me;
+pub mod metrics;
 pub mod projects;
+pub mod saved_searches;
+pub mod teams;
 pub mod tokens;
@@ -1,47 +1,171 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use actix_web::{web, HttpResponse};
 
-use crate::auth::AuthenticatedUser;
+use crate::auth::{AuthenticatedUser, ProjectCache};
 use crate::config::Config;
 use crate::db::DbPool;
-use crate::error::AppResult;
-use crate::models::{CreateProject, UpdateProject};
+use crate::error::{codes, AppError, AppResult};
+use crate::models::{
+    AddProjectMember, CreateProject, CreateProjectKey, DailyStatsQuery, DailyStatsResponse,
+    OnboardingQuery, Project, ProjectRole, TeamSummary, UpdateProject, UpdateProjectKey,
+};
 use crate::pagination::{ListProjectsQuery, OffsetPaginatedResponse};
-use crate::services::ProjectService;
+use crate::services::project::{DEFAULT_DAILY_STATS_DAYS, MAX_DAILY_STATS_DAYS};
+use crate::services::{
+    LiveRates, ProjectKeyService, ProjectMemberService, ProjectService, TeamService,
+};
 
 /// GET /api/projects - List projects with pagination
+///
+/// `?include=stats` adds each project's open issues and events of the last
+/// 24 hours, counted for the whole page at once.
 pub async fn list_projects(
     pool: web::Data<DbPool>,
     config: web::Data<Config>,
     query: web::Query<ListProjectsQuery>,
-    _user: AuthenticatedUser, // Requires authentication
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
-    let (projects, total_count) =
-        ProjectService::list_offset(pool.get_ref(), query.order, query.page, query.per_page)
-            .await?;
+    let team_id = match query.team.as_deref() {
+        Some(slug) => Some(TeamService::get_by_slug(pool.get_ref(), slug).await?.id),
+        None => None,
+    };
+    let per_page = query.per_page(&config.pagination)?;
+
+    let (projects, total_count) = ProjectService::list_offset(
+        pool.get_ref(),
+        &user.0,
+        team_id,
+        query.order,
+        query.page,
+        per_page,
+    )
+    .await?;
 
-    let base_url = build_base_url(&config);
-    let responses: Vec<_> = projects.iter().map(|p| p.to_response(&base_url)).collect();
+    let teams: HashMap<i32, TeamSummary> = TeamService::list(pool.get_ref())
+        .await?
+        .iter()
+        .map(|t| (t.id, t.to_summary()))
+        .collect();
+
+    let project_ids: Vec<i32> = projects.iter().map(|p| p.id).collect();
+    let keys = ProjectKeyService::list_for_projects(pool.get_ref(), &project_ids).await?;
+    let stats = if query.includes("stats") {
+        Some(ProjectService::stats_for_projects(pool.get_ref(), &project_ids).await?)
+    } else {
+        None
+    };
+
+    let responses: Vec<_> = projects
+        .iter()
+        .map(|p| {
+            let team = p.team_id.and_then(|id| teams.get(&id).cloned());
+            let keys = keys.get(&p.id).map(Vec::as_slice).unwrap_or_default();
+            let mut response = p.to_response(keys, team);
+            response.stats = stats
+                .as_ref()
+                .map(|stats| stats.get(&p.id).copied().unwrap_or_default());
+            response
+        })
+        .collect();
 
     Ok(HttpResponse::Ok().json(OffsetPaginatedResponse::new(
         responses,
         total_count,
         query.page,
-        query.per_page,
+        per_page,
     )))
 }
 
 /// GET /api/projects/{id} - Get a project by ID
 pub async fn get_project(
     pool: web::Data<DbPool>,
-    config: web::Data<Config>,
     path: web::Path<i32>,
-    _user: AuthenticatedUser, // Requires authentication
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    let project = ProjectService::get_by_id(pool.get_ref(), id).await?;
+    let keys = ProjectKeyService::list(pool.get_ref(), id).await?;
+    let team = team_summary(pool.get_ref(), &project).await?;
+
+    Ok(HttpResponse::Ok().json(project.to_response(&keys, team)))
+}
+
+/// Longest `wait` accepted by the onboarding endpoint, in seconds
+const MAX_ONBOARDING_WAIT_SECS: u64 = 60;
+
+/// GET /api/projects/{id}/onboarding - Whether the project received its first event
+///
+/// With `?wait=N`, answers as soon as the first event is digested, or after
+/// N seconds (at most 60).
+pub async fn get_onboarding(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    query: web::Query<OnboardingQuery>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    let onboarding = match query.wait.filter(|&secs| secs > 0) {
+        Some(secs) => {
+            let timeout = Duration::from_secs(secs.min(MAX_ONBOARDING_WAIT_SECS));
+            ProjectService::wait_for_first_event(pool.get_ref(), id, timeout).await?
+        }
+        None => ProjectService::onboarding(pool.get_ref(), id).await?,
+    };
+
+    Ok(HttpResponse::Ok().json(onboarding))
+}
+
+/// GET /api/projects/{id}/stats/daily - Events digested per day
+///
+/// Days start at midnight in `?tz=`, else the project's timezone, else UTC.
+/// `?days=` picks how many days, ending today, are returned.
+pub async fn get_daily_stats(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    query: web::Query<DailyStatsQuery>,
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
     let project = ProjectService::get_by_id(pool.get_ref(), id).await?;
-    let base_url = build_base_url(&config);
 
-    Ok(HttpResponse::Ok().json(project.to_response(&base_url)))
+    let days = query.days.unwrap_or(DEFAULT_DAILY_STATS_DAYS);
+    if !(1..=MAX_DAILY_STATS_DAYS).contains(&days) {
+        return Err(AppError::invalid_field(
+            "days",
+            codes::VALIDATION,
+            format!("days must be between 1 and {}", MAX_DAILY_STATS_DAYS),
+        ));
+    }
+    let timezone = match query.tz.as_deref().or(project.timezone.as_deref()) {
+        Some(name) => ProjectService::validate_timezone(pool.get_ref(), name).await?,
+        None => "UTC".to_string(),
+    };
+
+    let counts = ProjectService::daily_event_counts(pool.get_ref(), id, &timezone, days).await?;
+    Ok(HttpResponse::Ok().json(DailyStatsResponse {
+        timezone,
+        days: counts,
+    }))
+}
+
+/// GET /api/projects/{id}/stats/live - Events per second accepted and digested
+///
+/// Counted in memory by this instance over the last 10 and 60 seconds; no
+/// database query is involved beyond the access check.
+pub async fn get_live_stats(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+
+    Ok(HttpResponse::Ok().json(LiveRates::global().project(id)))
 }
 
 /// POST /api/projects - Create a new project
@@ -49,38 +173,176 @@ pub async fn create_project(
     pool: web::Data<DbPool>,
     config: web::Data<Config>,
     body: web::Json<CreateProject>,
-    _user: AuthenticatedUser, // Requires authentication
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let project = ProjectService::create(pool.get_ref(), body.into_inner()).await?;
-    let base_url = build_base_url(&config);
+    // Admins see every project; anyone else needs to be a member of theirs
+    if !user.0.is_admin {
+        let creator = AddProjectMember {
+            user_id: user.0.id,
+            role: ProjectRole::Admin,
+        };
+        ProjectMemberService::add(pool.get_ref(), project.id, creator).await?;
+    }
+    let keys = ProjectKeyService::list(pool.get_ref(), project.id).await?;
+    let team = team_summary(pool.get_ref(), &project).await?;
 
-    Ok(HttpResponse::Created().json(project.to_response(&base_url)))
+    // Reveal the default key's DSN (only time it's visible!)
+    let mut response = project.to_response(&keys, team);
+    response.dsn = keys.first().map(|k| k.dsn(&build_base_url(&config)));
+
+    Ok(HttpResponse::Created().json(response))
 }
 
 /// PATCH /api/projects/{id} - Update a project
 pub async fn update_project(
     pool: web::Data<DbPool>,
-    config: web::Data<Config>,
+    project_cache: Option<web::Data<ProjectCache>>,
     path: web::Path<i32>,
     body: web::Json<UpdateProject>,
-    _user: AuthenticatedUser, // Requires authentication
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
     let project = ProjectService::update(pool.get_ref(), id, body.into_inner()).await?;
-    let base_url = build_base_url(&config);
 
-    Ok(HttpResponse::Ok().json(project.to_response(&base_url)))
+    if let Some(project_cache) = project_cache {
+        project_cache.remove(id);
+    }
+    let keys = ProjectKeyService::list(pool.get_ref(), id).await?;
+    let team = team_summary(pool.get_ref(), &project).await?;
+
+    Ok(HttpResponse::Ok().json(project.to_response(&keys, team)))
 }
 
 /// DELETE /api/projects/{id} - Delete a project
 pub async fn delete_project(
     pool: web::Data<DbPool>,
+    project_cache: Option<web::Data<ProjectCache>>,
     path: web::Path<i32>,
-    _user: AuthenticatedUser, // Requires authentication
+    user: AuthenticatedUser,
 ) -> AppResult<HttpResponse> {
     let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
     ProjectService::delete(pool.get_ref(), id).await?;
 
+    // Stop accepting the project's keys right away
+    if let Some(project_cache) = project_cache {
+        project_cache.remove(id);
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// GET /api/projects/{id}/keys - List a project's keys (masked)
+pub async fn list_keys(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    ProjectService::get_by_id(pool.get_ref(), id).await?;
+    let keys = ProjectKeyService::list(pool.get_ref(), id).await?;
+    let responses: Vec<_> = keys.iter().map(|k| k.to_response()).collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// POST /api/projects/{id}/keys - Create a new key
+pub async fn create_key(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<i32>,
+    body: web::Json<CreateProjectKey>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    let key = ProjectKeyService::create(pool.get_ref(), id, body.into_inner()).await?;
+
+    // Return full key and DSN (only time they're visible!)
+    Ok(HttpResponse::Created().json(key.to_created_response(&build_base_url(&config))))
+}
+
+/// PATCH /api/projects/{id}/keys/{key_id} - Activate or deactivate a key
+pub async fn update_key(
+    pool: web::Data<DbPool>,
+    project_cache: Option<web::Data<ProjectCache>>,
+    path: web::Path<(i32, i32)>,
+    body: web::Json<UpdateProjectKey>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (id, key_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    let key = ProjectKeyService::set_active(pool.get_ref(), id, key_id, body.is_active).await?;
+
+    // A deactivated key stops being accepted right away, and a reactivated
+    // one isn't rejected from the cache
+    if let Some(project_cache) = project_cache {
+        project_cache.remove_key(id, &key.key);
+    }
+
+    Ok(HttpResponse::Ok().json(key.to_response()))
+}
+
+/// DELETE /api/projects/{id}/keys/{key_id} - Delete a key
+pub async fn delete_key(
+    pool: web::Data<DbPool>,
+    project_cache: Option<web::Data<ProjectCache>>,
+    path: web::Path<(i32, i32)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (id, key_id) = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    let key = ProjectKeyService::delete(pool.get_ref(), id, key_id).await?;
+
+    if let Some(project_cache) = project_cache {
+        project_cache.remove_key(id, &key.key);
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// GET /api/projects/{id}/members - List a project's members
+pub async fn list_members(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_access(pool.get_ref(), &user.0, id).await?;
+    let members = ProjectMemberService::list(pool.get_ref(), id).await?;
+
+    Ok(HttpResponse::Ok().json(members))
+}
+
+/// POST /api/projects/{id}/members - Add a user to a project
+///
+/// Only admins and the project's admins can manage its members.
+pub async fn add_member(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    body: web::Json<AddProjectMember>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    ProjectMemberService::require_admin(pool.get_ref(), &user.0, id).await?;
+    let member = ProjectMemberService::add(pool.get_ref(), id, body.into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(member))
+}
+
+/// DELETE /api/projects/{id}/members/{user_id} - Remove a user from a project
+pub async fn remove_member(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, i32)>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let (id, user_id) = path.into_inner();
+    ProjectMemberService::require_admin(pool.get_ref(), &user.0, id).await?;
+    ProjectMemberService::remove(pool.get_ref(), id, user_id).await?;
+
     Ok(HttpResponse::NoContent().finish())
 }
 
@@ -92,7 +354,17 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("", web::post().to(create_project))
             .route("/{id}", web::get().to(get_project))
             .route("/{id}", web::patch().to(update_project))
-            .route("/{id}", web::delete().to(delete_project)),
+            .route("/{id}", web::delete().to(delete_project))
+            .route("/{id}/onboarding", web::get().to(get_onboarding))
+            .route("/{id}/stats/daily", web::get().to(get_daily_stats))
+            .route("/{id}/stats/live", web::get().to(get_live_stats))
+            .route("/{id}/keys", web::get().to(list_keys))
+            .route("/{id}/keys", web::post().to(create_key))
+            .route("/{id}/keys/{key_id}", web::patch().to(update_key))
+            .route("/{id}/keys/{key_id}", web::delete().to(delete_key))
+            .route("/{id}/members", web::get().to(list_members))
+            .route("/{id}/members", web::post().to(add_member))
+            .route("/{id}/members/{user_id}", web::delete().to(remove_member)),
     );
 }
 
@@ -100,3 +372,11 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 fn build_base_url(config: &Config) -> String {
     format!("{}:{}", config.host, config.port)
 }
+
+/// Load the summary of the project's team, if any
+async fn team_summary(pool: &DbPool, project: &Project) -> AppResult<Option<TeamSummary>> {
+    match project.team_id {
+        Some(id) => Ok(Some(TeamService::get_by_id(pool, id).await?.to_summary())),
+        None => Ok(None),
+    }
+}
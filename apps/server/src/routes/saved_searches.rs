@@ -0,0 +1,56 @@
+//! Saved search routes.
+//!
+//! - GET /api/saved-searches?project_id={id} - List searches visible to the user
+//! - POST /api/saved-searches - Create search
+//! - DELETE /api/saved-searches/{id} - Delete search
+//!
+//! Saved searches are applied with `?saved_search={id}` on the issues list endpoint.
+
+use actix_web::{web, HttpResponse};
+
+use crate::auth::AuthenticatedUser;
+use crate::db::DbPool;
+use crate::error::AppResult;
+use crate::models::{CreateSavedSearch, ListSavedSearchesQuery};
+use crate::services::SavedSearchService;
+
+/// GET /api/saved-searches
+pub async fn list_saved_searches(
+    pool: web::Data<DbPool>,
+    query: web::Query<ListSavedSearchesQuery>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let searches = SavedSearchService::list(pool.get_ref(), user.0.id, query.project_id).await?;
+    let responses: Vec<_> = searches.iter().map(|s| s.to_response()).collect();
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// POST /api/saved-searches
+pub async fn create_saved_search(
+    pool: web::Data<DbPool>,
+    body: web::Json<CreateSavedSearch>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let search = SavedSearchService::create(pool.get_ref(), &user.0, body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(search.to_response()))
+}
+
+/// DELETE /api/saved-searches/{id}
+pub async fn delete_saved_search(
+    pool: web::Data<DbPool>,
+    path: web::Path<i32>,
+    user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    SavedSearchService::delete(pool.get_ref(), path.into_inner(), &user.0).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Configure saved search routes
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/saved-searches")
+            .route("", web::get().to(list_saved_searches))
+            .route("", web::post().to(create_saved_search))
+            .route("/{id}", web::delete().to(delete_saved_search)),
+    );
+}
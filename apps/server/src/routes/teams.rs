@@ -0,0 +1,93 @@
+//! Team routes for grouping projects.
+//!
+//! - GET /api/teams - List teams
+//! - POST /api/teams - Create team
+//! - GET /api/teams/{id} - Get team
+//! - PATCH /api/teams/{id} - Update team
+//! - DELETE /api/teams/{id}?reassign_to={team_id} - Delete team
+
+use actix_web::{web, HttpResponse};
+
+use crate::auth::AuthenticatedUser;
+use crate::db::DbPool;
+use crate::error::AppResult;
+use crate::models::{CreateTeam, DeleteTeamQuery, UpdateTeam};
+use crate::services::TeamService;
+
+/// GET /api/teams
+pub async fn list_teams(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+) -> AppResult<HttpResponse> {
+    let teams = TeamService::list(pool.get_ref()).await?;
+
+    let team_ids: Vec<i32> = teams.iter().map(|t| t.id).collect();
+    let mut channels = TeamService::default_channels_for_teams(pool.get_ref(), &team_ids).await?;
+
+    let responses: Vec<_> = teams
+        .into_iter()
+        .map(|team| {
+            let channel_ids = channels.remove(&team.id).unwrap_or_default();
+            team.to_response(channel_ids)
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(responses))
+}
+
+/// POST /api/teams
+pub async fn create_team(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+    body: web::Json<CreateTeam>,
+) -> AppResult<HttpResponse> {
+    let team = TeamService::create(pool.get_ref(), body.into_inner()).await?;
+    let channel_ids = TeamService::get_default_channels(pool.get_ref(), team.id).await?;
+    Ok(HttpResponse::Created().json(team.to_response(channel_ids)))
+}
+
+/// GET /api/teams/{id}
+pub async fn get_team(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+    path: web::Path<i32>,
+) -> AppResult<HttpResponse> {
+    let team = TeamService::get_by_id(pool.get_ref(), path.into_inner()).await?;
+    let channel_ids = TeamService::get_default_channels(pool.get_ref(), team.id).await?;
+    Ok(HttpResponse::Ok().json(team.to_response(channel_ids)))
+}
+
+/// PATCH /api/teams/{id}
+pub async fn update_team(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<UpdateTeam>,
+) -> AppResult<HttpResponse> {
+    let team = TeamService::update(pool.get_ref(), path.into_inner(), body.into_inner()).await?;
+    let channel_ids = TeamService::get_default_channels(pool.get_ref(), team.id).await?;
+    Ok(HttpResponse::Ok().json(team.to_response(channel_ids)))
+}
+
+/// DELETE /api/teams/{id}
+pub async fn delete_team(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser,
+    path: web::Path<i32>,
+    query: web::Query<DeleteTeamQuery>,
+) -> AppResult<HttpResponse> {
+    TeamService::delete(pool.get_ref(), path.into_inner(), query.reassign_to).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Configure team routes
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/teams")
+            .route("", web::get().to(list_teams))
+            .route("", web::post().to(create_team))
+            .route("/{id}", web::get().to(get_team))
+            .route("/{id}", web::patch().to(update_team))
+            .route("/{id}", web::delete().to(delete_team)),
+    );
+}
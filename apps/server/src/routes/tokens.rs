@@ -1,20 +1,40 @@
 use actix_web::{web, HttpResponse};
 
 use crate::auth::AuthenticatedUser;
+use crate::config::Config;
 use crate::db::DbPool;
 use crate::error::AppResult;
-use crate::models::CreateAuthToken;
+use crate::middleware::rate_limit::{RateLimit, RateLimitScope};
+use crate::models::{CreateAuthToken, UpdateAuthToken};
+use crate::pagination::{ListFormat, ListTokensQuery, OffsetPaginatedResponse};
 use crate::services::AuthTokenService;
 
-/// GET /api/tokens - List all tokens (masked)
+/// GET /api/tokens - List tokens with pagination (masked)
+///
+/// `?format=flat` returns every token as a bare array, as before pagination.
 pub async fn list_tokens(
     pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<ListTokensQuery>,
     _user: AuthenticatedUser, // Requires authentication
 ) -> AppResult<HttpResponse> {
-    let tokens = AuthTokenService::list(pool.get_ref()).await?;
+    if query.format == ListFormat::Flat {
+        let tokens = AuthTokenService::list(pool.get_ref()).await?;
+        let responses: Vec<_> = tokens.iter().map(|t| t.to_response()).collect();
+        return Ok(HttpResponse::Ok().json(responses));
+    }
+
+    let per_page = query.per_page(&config.pagination)?;
+    let (tokens, total_count) =
+        AuthTokenService::list_paginated(pool.get_ref(), query.page, per_page).await?;
     let responses: Vec<_> = tokens.iter().map(|t| t.to_response()).collect();
 
-    Ok(HttpResponse::Ok().json(responses))
+    Ok(HttpResponse::Ok().json(OffsetPaginatedResponse::new(
+        responses,
+        total_count,
+        query.page,
+        per_page,
+    )))
 }
 
 /// POST /api/tokens - Create a new token
@@ -29,6 +49,19 @@ pub async fn create_token(
     Ok(HttpResponse::Created().json(token.to_created_response()))
 }
 
+/// PATCH /api/tokens/{id} - Update a token's description
+pub async fn update_token(
+    pool: web::Data<DbPool>,
+    _user: AuthenticatedUser, // Requires authentication
+    path: web::Path<i32>,
+    body: web::Json<UpdateAuthToken>,
+) -> AppResult<HttpResponse> {
+    let id = path.into_inner();
+    let token = AuthTokenService::update(pool.get_ref(), id, body.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(token.to_response()))
+}
+
 /// DELETE /api/tokens/{id} - Revoke a token
 pub async fn delete_token(
     pool: web::Data<DbPool>,
@@ -45,8 +78,10 @@ pub async fn delete_token(
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/tokens")
+            .wrap(RateLimit::new(RateLimitScope::Tokens))
             .route("", web::get().to(list_tokens))
             .route("", web::post().to(create_token))
+            .route("/{id}", web::patch().to(update_token))
             .route("/{id}", web::delete().to(delete_token)),
     );
 }
@@ -5,21 +5,48 @@
 //! - CRUD operations for alert rules (per-project)
 //! - Alert triggering and dispatching
 
-use chrono::{Duration, Utc};
+use std::sync::{Arc, OnceLock};
+
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
+use crate::config::AlertConfig;
+use crate::error::{codes, AppError, AppResult};
+use crate::models::alert::ALERT_LEVELS;
 use crate::models::{
-    AlertHistory, AlertPayload, AlertRule, AlertType, CreateAlertRule, CreateNotificationChannel,
-    Issue, IssueInfo, NotificationChannel, Project, ProjectInfo, UpdateAlertRule,
+    AlertConditions, AlertHistory, AlertPayload, AlertRule, AlertStatus, AlertType, ChannelHealth,
+    ChannelStats, CreateAlertRule, CreateNotificationChannel, DigestAlert, DigestIssue, Issue,
+    IssueAlert, IssueInfo, NotificationChannel, Project, ProjectInfo, UpdateAlertRule,
     UpdateNotificationChannel,
 };
-use crate::services::notification::create_dispatcher;
+use crate::services::alert_digest::{AlertDigestBuffer, PendingDigest, Pushed};
+use crate::services::alert_dispatch::AlertDispatchLimiter;
+use crate::services::notification::{create_dispatcher, EmailNotifier};
+
+/// Longest digest window a rule can have (one day)
+const MAX_DIGEST_MINUTES: i32 = 24 * 60;
+
+/// Consecutive failures after which a channel is disabled, set by `AlertService::init`
+static AUTO_DISABLE_AFTER: OnceLock<u32> = OnceLock::new();
 
 pub struct AlertService;
 
 impl AlertService {
+    /// Sets the channel auto-disable threshold from config
+    ///
+    /// Must run before the first alert is dispatched; later calls are ignored.
+    pub fn init(config: &AlertConfig) {
+        if AUTO_DISABLE_AFTER.set(config.auto_disable_after).is_err() {
+            log::warn!("Alert service already initialized");
+        }
+    }
+
+    /// Consecutive failures after which a channel is disabled; 0 never disables
+    pub fn auto_disable_after() -> u32 {
+        *AUTO_DISABLE_AFTER.get_or_init(|| AlertConfig::default().auto_disable_after)
+    }
+
     // =========================================================================
     // Notification Channel CRUD
     // =========================================================================
@@ -65,7 +92,13 @@ impl AlertService {
     ) -> AppResult<NotificationChannel> {
         // Validate config based on channel type
         let dispatcher = create_dispatcher(input.channel_type);
-        dispatcher.validate_config(&input.config)?;
+        dispatcher
+            .validate_config(&input.config)
+            .map_err(|e| e.for_field("config"))?;
+        dispatcher
+            .verify_config(&input.config)
+            .await
+            .map_err(|e| e.for_field("config"))?;
 
         let channel = sqlx::query_as::<_, NotificationChannel>(
             r#"
@@ -85,7 +118,11 @@ impl AlertService {
         .map_err(|e| {
             if let sqlx::Error::Database(ref db_err) = e {
                 if db_err.constraint() == Some("notification_channels_name_key") {
-                    return AppError::Conflict(format!("Channel '{}' already exists", input.name));
+                    return AppError::field_conflict(
+                        "name",
+                        codes::CONFLICT_NAME_TAKEN,
+                        format!("Channel '{}' already exists", input.name),
+                    );
                 }
             }
             AppError::Database(e)
@@ -98,14 +135,25 @@ impl AlertService {
     pub async fn update_channel(
         pool: &PgPool,
         id: i32,
-        input: UpdateNotificationChannel,
+        mut input: UpdateNotificationChannel,
     ) -> AppResult<NotificationChannel> {
         let existing = Self::get_channel(pool, id).await?;
 
+        // A secret or header values sent back masked keep what's stored
+        if let Some(ref mut config) = input.config {
+            existing.unmask(config);
+        }
+
         // If config is being updated, validate it
         if let Some(ref config) = input.config {
             let dispatcher = create_dispatcher(existing.channel_type);
-            dispatcher.validate_config(config)?;
+            dispatcher
+                .validate_config(config)
+                .map_err(|e| e.for_field("config"))?;
+            dispatcher
+                .verify_config(config)
+                .await
+                .map_err(|e| e.for_field("config"))?;
         }
 
         let channel = sqlx::query_as::<_, NotificationChannel>(
@@ -130,7 +178,11 @@ impl AlertService {
         .map_err(|e| {
             if let sqlx::Error::Database(ref db_err) = e {
                 if db_err.constraint() == Some("notification_channels_name_key") {
-                    return AppError::Conflict("Channel name already exists".to_string());
+                    return AppError::field_conflict(
+                        "name",
+                        codes::CONFLICT_NAME_TAKEN,
+                        "Channel name already exists",
+                    );
                 }
             }
             AppError::Database(e)
@@ -140,17 +192,165 @@ impl AlertService {
     }
 
     /// Deletes a notification channel
-    pub async fn delete_channel(pool: &PgPool, id: i32) -> AppResult<()> {
-        let result = sqlx::query("DELETE FROM notification_channels WHERE id = $1")
+    ///
+    /// A channel alert rules still send to is only deleted with `force`,
+    /// which detaches it from those rules in the same transaction; otherwise
+    /// the rules are named in an [`AppError::InUse`]. Alert history keeps the
+    /// channel's name and type. Returns the number of rules detached.
+    pub async fn delete_channel(pool: &PgPool, id: i32, force: bool) -> AppResult<usize> {
+        let mut tx = pool.begin().await?;
+
+        // Locked so no rule starts using the channel until it's gone
+        let name: Option<String> =
+            sqlx::query_scalar("SELECT name FROM notification_channels WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let name = name.ok_or_else(|| AppError::NotFound(format!("Channel {} not found", id)))?;
+
+        let rules: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT r.name
+            FROM alert_rule_channels rc
+            JOIN alert_rules r ON r.id = rc.alert_rule_id
+            WHERE rc.channel_id = $1
+            ORDER BY r.name, r.id
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if !rules.is_empty() && !force {
+            return Err(AppError::InUse {
+                message: format!(
+                    "Channel '{}' is used by {} alert rule(s); pass force=true to detach and delete it",
+                    name,
+                    rules.len()
+                ),
+                dependents: rules,
+            });
+        }
+
+        sqlx::query("DELETE FROM alert_rule_channels WHERE channel_id = $1")
             .bind(id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
+        sqlx::query("DELETE FROM notification_channels WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound(format!("Channel {} not found", id)));
+        if !rules.is_empty() {
+            log::info!(
+                "Deleted channel '{}', detaching it from {} alert rule(s)",
+                name,
+                rules.len()
+            );
         }
+        Ok(rules.len())
+    }
 
-        Ok(())
+    /// Delivery health of every channel, by name
+    pub async fn channel_health(pool: &PgPool) -> AppResult<Vec<ChannelHealth>> {
+        let health = sqlx::query_as::<_, ChannelHealth>(
+            r#"
+            SELECT nc.id, nc.name, nc.channel_type, nc.is_enabled,
+                   nc.failure_count AS failure_streak,
+                   nc.last_success_at, nc.last_failure_at, nc.last_failure_message,
+                   CASE WHEN NOT nc.is_enabled AND activity.action = 'auto_disabled'
+                        THEN activity.created_at END AS auto_disabled_at
+            FROM notification_channels nc
+            LEFT JOIN LATERAL (
+                SELECT action, created_at
+                FROM notification_channel_activity
+                WHERE channel_id = nc.id
+                ORDER BY created_at DESC, id DESC
+                LIMIT 1
+            ) activity ON TRUE
+            ORDER BY nc.name
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(health)
+    }
+
+    /// Delivery stats of a channel over the alerts created since `since`
+    pub async fn channel_stats(
+        pool: &PgPool,
+        id: i32,
+        since: DateTime<Utc>,
+    ) -> AppResult<ChannelStats> {
+        let stats = sqlx::query_as::<_, ChannelStats>(
+            r#"
+            SELECT nc.id AS channel_id, nc.failure_count, nc.last_success_at,
+                   h.attempts, h.successes, h.failures,
+                   h.successes::float8 / NULLIF(h.successes + h.failures, 0) AS success_rate
+            FROM notification_channels nc
+            CROSS JOIN LATERAL (
+                SELECT COUNT(*) FILTER (WHERE status <> 'skipped') AS attempts,
+                       COUNT(*) FILTER (WHERE status = 'sent') AS successes,
+                       COUNT(*) FILTER (
+                           WHERE status = 'failed' OR (status = 'pending' AND attempt_count > 0)
+                       ) AS failures
+                FROM alert_history
+                WHERE channel_id = nc.id AND created_at >= $2
+            ) h
+            WHERE nc.id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(since)
+        .fetch_optional(pool)
+        .await?;
+
+        stats.ok_or_else(|| AppError::NotFound(format!("Channel {} not found", id)))
+    }
+
+    /// Clears a channel's failure streak and enables it again
+    pub async fn reset_channel_failures(pool: &PgPool, id: i32) -> AppResult<NotificationChannel> {
+        let mut tx = pool.begin().await?;
+
+        let streak: Option<i32> = sqlx::query_scalar(
+            "SELECT failure_count FROM notification_channels WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let streak =
+            streak.ok_or_else(|| AppError::NotFound(format!("Channel {} not found", id)))?;
+
+        let channel = sqlx::query_as::<_, NotificationChannel>(
+            r#"
+            UPDATE notification_channels
+            SET failure_count = 0, is_enabled = TRUE, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, channel_type, config, is_enabled, failure_count,
+                      last_failure_at, last_failure_message, last_success_at,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_channel_activity (channel_id, channel_name, action, message)
+            VALUES ($1, $2, 'failures_reset', $3)
+            "#,
+        )
+        .bind(channel.id)
+        .bind(&channel.name)
+        .bind(format!("Cleared a streak of {} failed deliveries", streak))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(channel)
     }
 
     // =========================================================================
@@ -162,7 +362,7 @@ impl AlertService {
         let rules = sqlx::query_as::<_, AlertRule>(
             r#"
             SELECT id, project_id, name, alert_type, is_enabled, conditions,
-                   cooldown_minutes, last_triggered_at, created_at, updated_at
+                   cooldown_minutes, digest_minutes, last_triggered_at, created_at, updated_at
             FROM alert_rules
             WHERE project_id = $1
             ORDER BY created_at DESC
@@ -180,7 +380,7 @@ impl AlertService {
         sqlx::query_as::<_, AlertRule>(
             r#"
             SELECT id, project_id, name, alert_type, is_enabled, conditions,
-                   cooldown_minutes, last_triggered_at, created_at, updated_at
+                   cooldown_minutes, digest_minutes, last_triggered_at, created_at, updated_at
             FROM alert_rules
             WHERE id = $1
             "#,
@@ -208,14 +408,19 @@ impl AlertService {
         project_id: i32,
         input: CreateAlertRule,
     ) -> AppResult<AlertRule> {
+        validate_digest_minutes(input.digest_minutes)?;
+        validate_conditions(&input.conditions)?;
+
         let mut tx = pool.begin().await?;
 
         let rule = sqlx::query_as::<_, AlertRule>(
             r#"
-            INSERT INTO alert_rules (project_id, name, alert_type, conditions, cooldown_minutes)
-            VALUES ($1, $2, $3::text::varchar, $4, $5)
+            INSERT INTO alert_rules (
+                project_id, name, alert_type, conditions, cooldown_minutes, digest_minutes
+            )
+            VALUES ($1, $2, $3::text::varchar, $4, $5, $6)
             RETURNING id, project_id, name, alert_type, is_enabled, conditions,
-                      cooldown_minutes, last_triggered_at, created_at, updated_at
+                      cooldown_minutes, digest_minutes, last_triggered_at, created_at, updated_at
             "#,
         )
         .bind(project_id)
@@ -223,15 +428,13 @@ impl AlertService {
         .bind(input.alert_type.to_string())
         .bind(&input.conditions)
         .bind(input.cooldown_minutes)
+        .bind(input.digest_minutes)
         .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(ref db_err) = e {
-                if db_err.constraint() == Some("alert_rules_project_id_alert_type_key") {
-                    return AppError::Conflict(format!(
-                        "Alert rule for type '{}' already exists in this project",
-                        input.alert_type
-                    ));
+                if db_err.is_foreign_key_violation() {
+                    return AppError::NotFound(format!("Project {} not found", project_id));
                 }
             }
             AppError::Database(e)
@@ -267,6 +470,13 @@ impl AlertService {
         id: i32,
         input: UpdateAlertRule,
     ) -> AppResult<AlertRule> {
+        if let Some(digest_minutes) = input.digest_minutes {
+            validate_digest_minutes(digest_minutes)?;
+        }
+        if let Some(ref conditions) = input.conditions {
+            validate_conditions(conditions)?;
+        }
+
         let mut tx = pool.begin().await?;
 
         let rule = sqlx::query_as::<_, AlertRule>(
@@ -276,10 +486,11 @@ impl AlertService {
                 is_enabled = COALESCE($3, is_enabled),
                 conditions = COALESCE($4, conditions),
                 cooldown_minutes = COALESCE($5, cooldown_minutes),
+                digest_minutes = COALESCE($6, digest_minutes),
                 updated_at = NOW()
             WHERE id = $1
             RETURNING id, project_id, name, alert_type, is_enabled, conditions,
-                      cooldown_minutes, last_triggered_at, created_at, updated_at
+                      cooldown_minutes, digest_minutes, last_triggered_at, created_at, updated_at
             "#,
         )
         .bind(id)
@@ -287,6 +498,7 @@ impl AlertService {
         .bind(input.is_enabled)
         .bind(&input.conditions)
         .bind(input.cooldown_minutes)
+        .bind(input.digest_minutes)
         .fetch_optional(&mut *tx)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Alert rule {} not found", id)))?;
@@ -347,20 +559,41 @@ impl AlertService {
         pool: &PgPool,
         project: &Project,
         issue: &Issue,
+        environment: Option<&str>,
         dashboard_url: &str,
+        max_title_length: usize,
     ) -> AppResult<()> {
-        Self::trigger_alert(pool, project, issue, AlertType::NewIssue, dashboard_url).await
+        Self::trigger_alert(
+            pool,
+            project,
+            issue,
+            environment,
+            AlertType::NewIssue,
+            dashboard_url,
+            max_title_length,
+        )
+        .await
     }
 
     /// Triggers an alert for a regression
-    #[allow(dead_code)]
     pub async fn trigger_regression_alert(
         pool: &PgPool,
         project: &Project,
         issue: &Issue,
+        environment: Option<&str>,
         dashboard_url: &str,
+        max_title_length: usize,
     ) -> AppResult<()> {
-        Self::trigger_alert(pool, project, issue, AlertType::Regression, dashboard_url).await
+        Self::trigger_alert(
+            pool,
+            project,
+            issue,
+            environment,
+            AlertType::Regression,
+            dashboard_url,
+            max_title_length,
+        )
+        .await
     }
 
     /// Triggers an alert for an unmute
@@ -369,44 +602,102 @@ impl AlertService {
         pool: &PgPool,
         project: &Project,
         issue: &Issue,
+        environment: Option<&str>,
         dashboard_url: &str,
+        max_title_length: usize,
     ) -> AppResult<()> {
-        Self::trigger_alert(pool, project, issue, AlertType::Unmute, dashboard_url).await
+        Self::trigger_alert(
+            pool,
+            project,
+            issue,
+            environment,
+            AlertType::Unmute,
+            dashboard_url,
+            max_title_length,
+        )
+        .await
     }
 
     /// Core alert triggering logic
+    ///
+    /// Every enabled rule of the type whose filters match the issue fires
+    /// to its own channels, with its own cooldown or digest window.
     async fn trigger_alert(
         pool: &PgPool,
         project: &Project,
         issue: &Issue,
+        environment: Option<&str>,
         alert_type: AlertType,
         dashboard_url: &str,
+        max_title_length: usize,
     ) -> AppResult<()> {
-        // 1. Find enabled rule for this project and alert type
-        let rule: Option<AlertRule> = sqlx::query_as(
+        // 1. Find enabled rules for this project and alert type
+        let rules: Vec<AlertRule> = sqlx::query_as(
             r#"
             SELECT id, project_id, name, alert_type, is_enabled, conditions,
-                   cooldown_minutes, last_triggered_at, created_at, updated_at
+                   cooldown_minutes, digest_minutes, last_triggered_at, created_at, updated_at
             FROM alert_rules
             WHERE project_id = $1 AND alert_type = $2::text::varchar AND is_enabled = TRUE
+            ORDER BY id
             "#,
         )
         .bind(project.id)
         .bind(alert_type.to_string())
-        .fetch_optional(pool)
+        .fetch_all(pool)
         .await?;
 
-        let rule = match rule {
-            Some(r) => r,
-            None => {
+        if rules.is_empty() {
+            log::debug!(
+                "No enabled alert rule for {:?} in project {}",
+                alert_type,
+                project.id
+            );
+            return Ok(());
+        }
+
+        for rule in rules {
+            if !rule_matches(&rule, issue, environment) {
                 log::debug!(
-                    "No enabled alert rule for {:?} in project {}",
-                    alert_type,
-                    project.id
+                    "Issue {} doesn't match the filters of alert rule {}",
+                    issue.id,
+                    rule.id
                 );
-                return Ok(());
+                continue;
             }
-        };
+
+            if let Err(e) =
+                Self::fire_rule(pool, &rule, project, issue, dashboard_url, max_title_length).await
+            {
+                log::error!("Failed to trigger alert rule {}: {}", rule.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends an issue to the channels of one rule
+    async fn fire_rule(
+        pool: &PgPool,
+        rule: &AlertRule,
+        project: &Project,
+        issue: &Issue,
+        dashboard_url: &str,
+        max_title_length: usize,
+    ) -> AppResult<()> {
+        // Digest rules batch their alerts; cooldown does not apply to them
+        if rule.digest_minutes > 0 {
+            Self::queue_digest(
+                pool,
+                &AlertDigestBuffer::global(),
+                rule,
+                project,
+                issue,
+                dashboard_url,
+                max_title_length,
+            )
+            .await;
+            return Ok(());
+        }
 
         // 2. Atomically check cooldown and update last_triggered_at
         // This prevents race conditions where concurrent triggers bypass cooldown
@@ -432,19 +723,7 @@ impl AlertService {
         }
 
         // 3. Get associated channels
-        let channels: Vec<NotificationChannel> = sqlx::query_as(
-            r#"
-            SELECT nc.id, nc.name, nc.channel_type, nc.config, nc.is_enabled,
-                   nc.failure_count, nc.last_failure_at, nc.last_failure_message,
-                   nc.last_success_at, nc.created_at, nc.updated_at
-            FROM notification_channels nc
-            INNER JOIN alert_rule_channels arc ON nc.id = arc.channel_id
-            WHERE arc.alert_rule_id = $1 AND nc.is_enabled = TRUE
-            "#,
-        )
-        .bind(rule.id)
-        .fetch_all(pool)
-        .await?;
+        let channels = Self::enabled_rule_channels(pool, rule.id).await?;
 
         if channels.is_empty() {
             log::debug!("No enabled channels for alert rule {}", rule.id);
@@ -452,52 +731,243 @@ impl AlertService {
         }
 
         // 4. Build payload
-        let payload = AlertPayload {
+        let payload = AlertPayload::Issue(IssueAlert {
             alert_id: format!(
-                "{}-{}-{}",
+                "{}-{}-{}-{}",
                 project.id,
                 issue.id,
+                rule.id,
                 Utc::now().timestamp_millis()
             ),
-            alert_type: alert_type.to_string(),
+            alert_type: rule.alert_type.to_string(),
             triggered_at: Utc::now(),
-            project: ProjectInfo {
-                id: project.id,
-                name: project.name.clone(),
-                slug: project.slug.clone(),
-            },
-            issue: IssueInfo {
-                id: issue.id.to_string(),
-                short_id: issue.short_id(&project.slug),
-                title: issue.title(),
-                level: issue.level.clone(),
-                first_seen: issue.first_seen,
-                last_seen: issue.last_seen,
-                event_count: issue.digested_event_count,
-            },
-            issue_url: format!(
-                "{}/projects/{}/issues/{}",
-                dashboard_url, project.slug, issue.id
-            ),
+            project: project_info(project),
+            issue: issue_info(project, issue, max_title_length),
+            issue_url: issue_url(dashboard_url, project, issue),
             actor: "Rustrak".to_string(),
-        };
+        });
 
         // Note: last_triggered_at was already updated atomically in step 2
 
         log::info!(
-            "Triggering {} alert for issue {} in project {}",
-            alert_type,
+            "Triggering {} alert rule {} for issue {} in project {}",
+            rule.alert_type,
+            rule.id,
             issue.id,
             project.name
         );
 
-        // 6. Dispatch to all channels (spawn tasks for parallel execution)
+        // 5. Dispatch to all channels
+        Self::dispatch_to_channels(pool, channels, payload, rule.id).await;
+
+        Ok(())
+    }
+
+    /// Queues an issue into the current window of a digest rule
+    ///
+    /// The issue that opens a window schedules its flush once
+    /// `digest_minutes` have passed. When the buffer is full the window is
+    /// sent right away instead, before returning.
+    pub async fn queue_digest(
+        pool: &PgPool,
+        buffer: &Arc<AlertDigestBuffer>,
+        rule: &AlertRule,
+        project: &Project,
+        issue: &Issue,
+        dashboard_url: &str,
+        max_title_length: usize,
+    ) {
+        let digest_issue = DigestIssue {
+            issue: issue_info(project, issue, max_title_length),
+            issue_url: issue_url(dashboard_url, project, issue),
+        };
+
+        let pool = pool.clone();
+        let rule_id = rule.id;
+        let dashboard_url = dashboard_url.to_string();
+
+        match buffer.push(rule.id, project_info(project), digest_issue) {
+            Pushed::Queued => {
+                log::debug!("Queued issue {} into digest of rule {}", issue.id, rule.id);
+            }
+            Pushed::Opened(window) => {
+                log::debug!(
+                    "Opened {} minute digest window for rule {}",
+                    rule.digest_minutes,
+                    rule.id
+                );
+
+                let buffer = buffer.clone();
+                let length = std::time::Duration::from_secs(rule.digest_minutes as u64 * 60);
+                tokio::spawn(async move {
+                    tokio::time::sleep(length).await;
+                    // Nothing left if the window was sent early
+                    let Some(digest) = buffer.take_window(rule_id, window) else {
+                        return;
+                    };
+                    if let Err(e) = Self::send_digest(&pool, rule_id, digest, &dashboard_url).await
+                    {
+                        log::error!("Failed to send digest for alert rule {}: {}", rule_id, e);
+                    }
+                });
+            }
+            Pushed::Full(digest) => {
+                log::warn!(
+                    "Alert digest buffer is full, sending the digest of rule {} early",
+                    rule.id
+                );
+                if let Err(e) = Self::send_digest(&pool, rule_id, digest, &dashboard_url).await {
+                    log::error!("Failed to send digest for alert rule {}: {}", rule_id, e);
+                }
+            }
+        }
+    }
+
+    /// Sends the pending digest of a rule and closes its window
+    pub async fn flush_digest(
+        pool: &PgPool,
+        buffer: &AlertDigestBuffer,
+        rule_id: i32,
+        dashboard_url: &str,
+    ) -> AppResult<()> {
+        match buffer.take(rule_id) {
+            Some(digest) => Self::send_digest(pool, rule_id, digest, dashboard_url).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Sends a digest taken out of the buffer
+    ///
+    /// A window that caught a single issue is sent as a regular alert.
+    /// Nothing is sent if the rule was disabled or deleted meanwhile.
+    async fn send_digest(
+        pool: &PgPool,
+        rule_id: i32,
+        digest: PendingDigest,
+        dashboard_url: &str,
+    ) -> AppResult<()> {
+        let rule: Option<AlertRule> = sqlx::query_as(
+            r#"
+            UPDATE alert_rules
+            SET last_triggered_at = NOW()
+            WHERE id = $1 AND is_enabled = TRUE
+            RETURNING id, project_id, name, alert_type, is_enabled, conditions,
+                      cooldown_minutes, digest_minutes, last_triggered_at, created_at, updated_at
+            "#,
+        )
+        .bind(rule_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let rule = match rule {
+            Some(r) => r,
+            None => {
+                log::debug!("Dropping digest of disabled alert rule {}", rule_id);
+                return Ok(());
+            }
+        };
+
+        let channels = Self::enabled_rule_channels(pool, rule.id).await?;
+
+        if channels.is_empty() {
+            log::debug!("No enabled channels for alert rule {}", rule.id);
+            return Ok(());
+        }
+
+        let triggered_at = Utc::now();
+        let issue_count = digest.issues.len();
+        let mut issues = digest.issues;
+
+        let payload = if issue_count == 1 {
+            let DigestIssue { issue, issue_url } = issues.remove(0);
+            AlertPayload::Issue(IssueAlert {
+                alert_id: format!(
+                    "{}-{}-{}-{}",
+                    digest.project.id,
+                    issue.id,
+                    rule.id,
+                    triggered_at.timestamp_millis()
+                ),
+                alert_type: rule.alert_type.to_string(),
+                triggered_at,
+                project: digest.project,
+                issue,
+                issue_url,
+                actor: "Rustrak".to_string(),
+            })
+        } else {
+            AlertPayload::Digest(DigestAlert {
+                alert_id: format!(
+                    "{}-digest-{}-{}",
+                    digest.project.id,
+                    rule.id,
+                    triggered_at.timestamp_millis()
+                ),
+                alert_type: rule.alert_type.to_string(),
+                triggered_at,
+                window_start: digest.window_start,
+                project_url: format!("{}/projects/{}/issues", dashboard_url, digest.project.slug),
+                project: digest.project,
+                issues,
+                actor: "Rustrak".to_string(),
+            })
+        };
+
+        log::info!(
+            "Sending {} alert digest of {} issue(s) for rule {}",
+            rule.alert_type,
+            issue_count,
+            rule.id
+        );
+
+        Self::dispatch_to_channels(pool, channels, payload, rule.id).await;
+
+        Ok(())
+    }
+
+    /// Gets the enabled channels linked to a rule
+    async fn enabled_rule_channels(
+        pool: &PgPool,
+        rule_id: i32,
+    ) -> AppResult<Vec<NotificationChannel>> {
+        let channels = sqlx::query_as(
+            r#"
+            SELECT nc.id, nc.name, nc.channel_type, nc.config, nc.is_enabled,
+                   nc.failure_count, nc.last_failure_at, nc.last_failure_message,
+                   nc.last_success_at, nc.created_at, nc.updated_at
+            FROM notification_channels nc
+            INNER JOIN alert_rule_channels arc ON nc.id = arc.channel_id
+            WHERE arc.alert_rule_id = $1 AND nc.is_enabled = TRUE
+            "#,
+        )
+        .bind(rule_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(channels)
+    }
+
+    /// Dispatches an alert to every channel
+    ///
+    /// Each channel is sent from its own task, but no more than
+    /// `ALERT_DISPATCH_CONCURRENCY` deliveries run at once. A slot is taken
+    /// before the task is spawned, so the caller waits for one instead of
+    /// piling up waiting tasks.
+    async fn dispatch_to_channels(
+        pool: &PgPool,
+        channels: Vec<NotificationChannel>,
+        payload: AlertPayload,
+        rule_id: i32,
+    ) {
+        let limiter = AlertDispatchLimiter::global();
+
         for channel in channels {
             let pool = pool.clone();
             let payload = payload.clone();
-            let rule_id = rule.id;
+            let permit = limiter.acquire().await;
 
             tokio::spawn(async move {
+                let _permit = permit;
                 if let Err(e) = Self::dispatch_to_channel(&pool, &channel, &payload, rule_id).await
                 {
                     log::error!(
@@ -509,8 +979,6 @@ impl AlertService {
                 }
             });
         }
-
-        Ok(())
     }
 
     /// Dispatches an alert to a single channel
@@ -520,10 +988,13 @@ impl AlertService {
         payload: &AlertPayload,
         rule_id: i32,
     ) -> AppResult<()> {
-        let idempotency_key = format!("{}-{}", payload.alert_id, channel.id);
+        let idempotency_key = format!("{}-{}", payload.alert_id(), channel.id);
 
-        // Parse issue_id as UUID
-        let issue_uuid = Uuid::parse_str(&payload.issue.id).ok();
+        // Parse issue_id as UUID; digests cover several issues and have none
+        let issue_uuid = match payload {
+            AlertPayload::Issue(alert) => Uuid::parse_str(&alert.issue.id).ok(),
+            AlertPayload::Digest(_) => None,
+        };
 
         // Create history record with idempotent insert (ON CONFLICT DO NOTHING)
         // This avoids TOCTOU race conditions from separate SELECT + INSERT
@@ -542,8 +1013,8 @@ impl AlertService {
         .bind(rule_id)
         .bind(channel.id)
         .bind(issue_uuid)
-        .bind(payload.project.id)
-        .bind(&payload.alert_type)
+        .bind(payload.project().id)
+        .bind(payload.alert_type())
         .bind(channel.channel_type.to_string())
         .bind(&channel.name)
         .bind(&idempotency_key)
@@ -622,19 +1093,7 @@ impl AlertService {
             .execute(pool)
             .await?;
 
-            sqlx::query(
-                r#"
-                UPDATE notification_channels
-                SET last_failure_at = NOW(),
-                    last_failure_message = $2,
-                    failure_count = failure_count + 1
-                WHERE id = $1
-                "#,
-            )
-            .bind(channel.id)
-            .bind(&result.error_message)
-            .execute(pool)
-            .await?;
+            Self::record_channel_failure(pool, channel, result.error_message.as_deref()).await?;
 
             log::warn!(
                 "Alert to channel {} ({}) failed: {:?}",
@@ -647,6 +1106,132 @@ impl AlertService {
         Ok(())
     }
 
+    /// Counts a failed delivery against a channel, disabling it once its
+    /// streak reaches `ALERT_CHANNEL_AUTO_DISABLE_AFTER`
+    async fn record_channel_failure(
+        pool: &PgPool,
+        channel: &NotificationChannel,
+        error_message: Option<&str>,
+    ) -> AppResult<()> {
+        let threshold = Self::auto_disable_after() as i32;
+
+        // Row locked first, so only the failure that crosses the threshold
+        // sees the channel go from enabled to disabled
+        let updated: Option<(i32, bool)> = sqlx::query_as(
+            r#"
+            WITH current AS (
+                SELECT is_enabled FROM notification_channels WHERE id = $1 FOR UPDATE
+            )
+            UPDATE notification_channels nc
+            SET last_failure_at = NOW(),
+                last_failure_message = $2,
+                failure_count = nc.failure_count + 1,
+                is_enabled = nc.is_enabled AND ($3 = 0 OR nc.failure_count + 1 < $3)
+            FROM current
+            WHERE nc.id = $1
+            RETURNING nc.failure_count, current.is_enabled AND NOT nc.is_enabled
+            "#,
+        )
+        .bind(channel.id)
+        .bind(error_message)
+        .bind(threshold)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some((streak, disabled)) = updated else {
+            return Ok(());
+        };
+        if !disabled {
+            return Ok(());
+        }
+
+        let message = format!(
+            "Disabled after {} consecutive failed deliveries, the last one: {}",
+            streak,
+            error_message.unwrap_or("unknown error")
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO notification_channel_activity (channel_id, channel_name, action, message)
+            VALUES ($1, $2, 'auto_disabled', $3)
+            "#,
+        )
+        .bind(channel.id)
+        .bind(&channel.name)
+        .bind(&message)
+        .execute(pool)
+        .await?;
+
+        log::warn!("Channel {} ({}): {}", channel.id, channel.name, message);
+
+        let pool = pool.clone();
+        let channel_name = channel.name.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::notify_channel_disabled(&pool, &channel_name, &message).await {
+                log::error!(
+                    "Failed to notify admins that channel {} was disabled: {}",
+                    channel_name,
+                    e
+                );
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Emails the admins that a channel was disabled, through the oldest
+    /// enabled email channel; does nothing without one
+    async fn notify_channel_disabled(
+        pool: &PgPool,
+        channel_name: &str,
+        reason: &str,
+    ) -> AppResult<()> {
+        let email_channel: Option<NotificationChannel> = sqlx::query_as(
+            r#"
+            SELECT id, name, channel_type, config, is_enabled, failure_count,
+                   last_failure_at, last_failure_message, last_success_at,
+                   created_at, updated_at
+            FROM notification_channels
+            WHERE channel_type = 'email' AND is_enabled = TRUE
+            ORDER BY id
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(email_channel) = email_channel else {
+            return Ok(());
+        };
+
+        let admins: Vec<String> = sqlx::query_scalar(
+            "SELECT email FROM users WHERE is_admin = TRUE AND is_active = TRUE ORDER BY id",
+        )
+        .fetch_all(pool)
+        .await?;
+        if admins.is_empty() {
+            return Ok(());
+        }
+
+        let subject = format!(
+            "Rustrak: notification channel {} was disabled",
+            channel_name
+        );
+        let body = format!(
+            "The notification channel {} was disabled.\n\n{}\n\n\
+             Alerts are no longer sent to it. Fix its configuration, then \
+             reset its failures to enable it again.",
+            channel_name, reason
+        );
+        let result = EmailNotifier::new()
+            .send_notice(&email_channel, &admins, &subject, &body)
+            .await;
+        if let Some(error) = result.error_message {
+            return Err(AppError::Internal(error));
+        }
+
+        Ok(())
+    }
+
     // =========================================================================
     // Alert History
     // =========================================================================
@@ -677,6 +1262,60 @@ impl AlertService {
         Ok(history)
     }
 
+    /// Lists alert history for a project with offset-based pagination
+    ///
+    /// Optionally filtered by delivery status and channel. Returns the page and
+    /// the total count of matching entries.
+    pub async fn list_history_paginated(
+        pool: &PgPool,
+        project_id: i32,
+        status: Option<AlertStatus>,
+        channel_id: Option<i32>,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<AlertHistory>, i64)> {
+        let offset = (page - 1) * per_page;
+
+        let total_count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM alert_history
+            WHERE project_id = $1
+              AND ($2::varchar IS NULL OR status = $2)
+              AND ($3::int IS NULL OR channel_id = $3)
+            "#,
+        )
+        .bind(project_id)
+        .bind(status)
+        .bind(channel_id)
+        .fetch_one(pool)
+        .await?;
+
+        let history = sqlx::query_as::<_, AlertHistory>(
+            r#"
+            SELECT id, alert_rule_id, channel_id, issue_id, project_id,
+                   alert_type, channel_type, channel_name, status,
+                   attempt_count, next_retry_at, error_message,
+                   http_status_code, idempotency_key, created_at, sent_at
+            FROM alert_history
+            WHERE project_id = $1
+              AND ($2::varchar IS NULL OR status = $2)
+              AND ($3::int IS NULL OR channel_id = $3)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(project_id)
+        .bind(status)
+        .bind(channel_id)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok((history, total_count))
+    }
+
     /// Processes pending retries (for background worker)
     #[allow(dead_code)]
     pub async fn process_retry_queue(pool: &PgPool, max_retries: i32) -> AppResult<u32> {
@@ -725,3 +1364,101 @@ impl AlertService {
         Ok(processed)
     }
 }
+
+fn validate_digest_minutes(digest_minutes: i32) -> AppResult<()> {
+    if !(0..=MAX_DIGEST_MINUTES).contains(&digest_minutes) {
+        return Err(AppError::Validation(format!(
+            "digest_minutes must be between 0 and {}",
+            MAX_DIGEST_MINUTES
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the shape of a rule's conditions, in particular its filters
+fn validate_conditions(conditions: &serde_json::Value) -> AppResult<()> {
+    let invalid = |message: String| {
+        AppError::invalid_field("conditions", codes::VALIDATION_INVALID_FORMAT, message)
+    };
+
+    if !conditions.is_object() {
+        return Err(invalid("conditions must be an object".to_string()));
+    }
+    let conditions: AlertConditions = serde_json::from_value(conditions.clone())
+        .map_err(|e| invalid(format!("Invalid filters: {}", e)))?;
+    let Some(filters) = conditions.filters else {
+        return Ok(());
+    };
+
+    if let Some(min_level) = &filters.min_level {
+        if !ALERT_LEVELS.contains(&min_level.as_str()) {
+            return Err(invalid(format!(
+                "filters.min_level must be one of {}",
+                ALERT_LEVELS.join(", ")
+            )));
+        }
+    }
+    if filters.environments.iter().any(|env| env.trim().is_empty()) {
+        return Err(invalid(
+            "filters.environments can't contain empty names".to_string(),
+        ));
+    }
+    if filters
+        .title_contains
+        .as_deref()
+        .is_some_and(|text| text.trim().is_empty())
+    {
+        return Err(invalid("filters.title_contains can't be empty".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Whether an issue passes a rule's filters
+///
+/// Conditions stored before filters were validated may not parse; such
+/// rules fire for every issue rather than going silent.
+fn rule_matches(rule: &AlertRule, issue: &Issue, environment: Option<&str>) -> bool {
+    match serde_json::from_value::<AlertConditions>(rule.conditions.clone()) {
+        Ok(conditions) => conditions
+            .filters
+            .is_none_or(|filters| filters.matches(issue, environment)),
+        Err(e) => {
+            log::warn!(
+                "Ignoring invalid conditions of alert rule {}: {}",
+                rule.id,
+                e
+            );
+            true
+        }
+    }
+}
+
+fn project_info(project: &Project) -> ProjectInfo {
+    ProjectInfo {
+        id: project.id,
+        name: project.name.clone(),
+        slug: project.slug.clone(),
+    }
+}
+
+fn issue_info(project: &Project, issue: &Issue, max_title_length: usize) -> IssueInfo {
+    IssueInfo {
+        id: issue.id.to_string(),
+        short_id: issue.short_id(&project.slug),
+        title: issue.title(max_title_length),
+        level: issue.level.clone(),
+        first_seen: issue.first_seen,
+        last_seen: issue.last_seen,
+        event_count: issue.digested_event_count,
+        is_unhandled: issue.is_unhandled,
+        priority: issue.priority,
+    }
+}
+
+fn issue_url(dashboard_url: &str, project: &Project, issue: &Issue) -> String {
+    format!(
+        "{}/projects/{}/issues/{}",
+        dashboard_url, project.slug, issue.id
+    )
+}
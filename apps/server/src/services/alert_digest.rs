@@ -0,0 +1,169 @@
+//! In-memory buffer for digest alert rules.
+//!
+//! Rules with a digest window queue their issues here instead of alerting
+//! right away. The first issue of a window opens it; when the window ends the
+//! queued issues are taken out and sent as a single summary.
+//!
+//! The buffer holds at most [`MAX_PENDING_ISSUES`] issues across all rules.
+//! Once it's full, a rule queuing another issue has its window closed early
+//! and sent right away, so a burst slows down to one summary per issue
+//! instead of growing the buffer.
+//!
+//! Pending digests live in memory only and are lost on restart.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{DigestIssue, ProjectInfo};
+
+/// Issues the global buffer holds across all rules
+pub const MAX_PENDING_ISSUES: usize = 10_000;
+
+static GLOBAL: LazyLock<Arc<AlertDigestBuffer>> =
+    LazyLock::new(|| Arc::new(AlertDigestBuffer::new()));
+
+/// Issues queued for a rule during its current window
+#[derive(Debug, Clone)]
+pub struct PendingDigest {
+    /// Tells this window apart from later ones of the same rule
+    pub window: u64,
+    pub project: ProjectInfo,
+    pub window_start: DateTime<Utc>,
+    pub issues: Vec<DigestIssue>,
+}
+
+/// What became of a queued issue
+#[derive(Debug)]
+pub enum Pushed {
+    /// The issue opened this window, which the caller flushes once it ends
+    Opened(u64),
+    /// The issue joined the open window
+    Queued,
+    /// The buffer is full: the rule's window was closed early and must be
+    /// sent now, the issue included
+    Full(PendingDigest),
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    digests: HashMap<i32, PendingDigest>,
+    /// Issues across all digests
+    issues: usize,
+    next_window: u64,
+}
+
+/// Pending digests keyed by alert rule ID
+#[derive(Debug)]
+pub struct AlertDigestBuffer {
+    capacity: usize,
+    pending: Mutex<Pending>,
+}
+
+impl Default for AlertDigestBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlertDigestBuffer {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_PENDING_ISSUES)
+    }
+
+    /// A buffer holding at most `capacity` issues
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    /// The buffer used by the digest worker
+    pub fn global() -> Arc<AlertDigestBuffer> {
+        GLOBAL.clone()
+    }
+
+    /// Queues an issue for a rule
+    pub fn push(&self, rule_id: i32, project: ProjectInfo, issue: DigestIssue) -> Pushed {
+        let mut pending = self.pending.lock().unwrap();
+
+        if pending.issues >= self.capacity {
+            let digest = match pending.digests.remove(&rule_id) {
+                Some(mut digest) => {
+                    pending.issues -= digest.issues.len();
+                    digest.issues.push(issue);
+                    digest
+                }
+                None => PendingDigest {
+                    window: pending.next_window(),
+                    project,
+                    window_start: Utc::now(),
+                    issues: vec![issue],
+                },
+            };
+            return Pushed::Full(digest);
+        }
+
+        pending.issues += 1;
+        if let Some(digest) = pending.digests.get_mut(&rule_id) {
+            digest.issues.push(issue);
+            return Pushed::Queued;
+        }
+
+        let window = pending.next_window();
+        pending.digests.insert(
+            rule_id,
+            PendingDigest {
+                window,
+                project,
+                window_start: Utc::now(),
+                issues: vec![issue],
+            },
+        );
+        Pushed::Opened(window)
+    }
+
+    /// Takes the pending digest of a rule, closing its window
+    pub fn take(&self, rule_id: i32) -> Option<PendingDigest> {
+        let mut pending = self.pending.lock().unwrap();
+        let digest = pending.digests.remove(&rule_id)?;
+        pending.issues -= digest.issues.len();
+        Some(digest)
+    }
+
+    /// Takes the pending digest of a rule if it's still `window`, which
+    /// may have been sent early since
+    pub fn take_window(&self, rule_id: i32, window: u64) -> Option<PendingDigest> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.digests.get(&rule_id)?.window != window {
+            return None;
+        }
+        let digest = pending.digests.remove(&rule_id)?;
+        pending.issues -= digest.issues.len();
+        Some(digest)
+    }
+
+    /// Number of issues queued for a rule
+    pub fn len(&self, rule_id: i32) -> usize {
+        self.pending
+            .lock()
+            .unwrap()
+            .digests
+            .get(&rule_id)
+            .map_or(0, |digest| digest.issues.len())
+    }
+
+    /// Number of issues queued across all rules
+    pub fn total(&self) -> usize {
+        self.pending.lock().unwrap().issues
+    }
+}
+
+impl Pending {
+    fn next_window(&mut self) -> u64 {
+        self.next_window += 1;
+        self.next_window
+    }
+}
@@ -0,0 +1,72 @@
+//! Concurrency limit for alert delivery.
+//!
+//! Every channel an alert goes to is sent from its own task, but only a
+//! fixed number of them may exist at once. A permit is taken before the task
+//! is spawned, so during an issue storm the digest waits for a free slot
+//! instead of spawning thousands of tasks, webhook/SMTP connections and
+//! database transactions.
+
+use std::sync::{Arc, OnceLock};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::AlertConfig;
+
+static GLOBAL: OnceLock<Arc<AlertDispatchLimiter>> = OnceLock::new();
+
+/// Bounds how many alert deliveries run at the same time
+#[derive(Debug)]
+pub struct AlertDispatchLimiter {
+    permits: Arc<Semaphore>,
+    concurrency: usize,
+}
+
+impl AlertDispatchLimiter {
+    pub fn new(concurrency: usize) -> Self {
+        let concurrency = concurrency.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency)),
+            concurrency,
+        }
+    }
+
+    /// Sets up the global limiter from config
+    ///
+    /// Must run before the first alert is dispatched; later calls are ignored.
+    pub fn init(config: &AlertConfig) {
+        if GLOBAL
+            .set(Arc::new(Self::new(config.dispatch_concurrency)))
+            .is_err()
+        {
+            log::warn!("Alert dispatch limiter already initialized");
+        }
+    }
+
+    /// The limiter used for alert delivery, with the default limit if
+    /// `init` was never called
+    pub fn global() -> Arc<AlertDispatchLimiter> {
+        GLOBAL
+            .get_or_init(|| Arc::new(Self::new(AlertConfig::default().dispatch_concurrency)))
+            .clone()
+    }
+
+    /// Waits until a delivery slot is free; the slot is released when the
+    /// permit is dropped
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("alert dispatch semaphore is never closed")
+    }
+
+    /// Max deliveries running at once
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Delivery slots currently free
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+}
@@ -0,0 +1,49 @@
+use sqlx::PgPool;
+
+use crate::error::AppResult;
+use crate::models::{AuditAction, AuditLogEntry};
+
+pub struct AuditLogService;
+
+impl AuditLogService {
+    /// Records that a user did `action`, with what it applied to in `details`
+    pub async fn record(
+        pool: &PgPool,
+        user_id: i32,
+        project_id: Option<i32>,
+        action: AuditAction,
+        details: serde_json::Value,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (user_id, project_id, action, details)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(user_id)
+        .bind(project_id)
+        .bind(action.as_str())
+        .bind(details)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Entries of a project, most recent first
+    pub async fn list_for_project(pool: &PgPool, project_id: i32) -> AppResult<Vec<AuditLogEntry>> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            SELECT id, user_id, project_id, action, details, created_at
+            FROM audit_log
+            WHERE project_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
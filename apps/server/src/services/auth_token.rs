@@ -2,7 +2,7 @@ use sqlx::PgPool;
 
 use crate::auth::generate_token;
 use crate::error::{AppError, AppResult};
-use crate::models::{AuthToken, CreateAuthToken};
+use crate::models::{AuthToken, CreateAuthToken, UpdateAuthToken};
 
 pub struct AuthTokenService;
 
@@ -22,8 +22,37 @@ impl AuthTokenService {
         Ok(tokens)
     }
 
+    /// Lists tokens with offset-based pagination, newest first
+    ///
+    /// Returns the page and the total number of tokens.
+    pub async fn list_paginated(
+        pool: &PgPool,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<AuthToken>, i64)> {
+        let offset = (page - 1) * per_page;
+
+        let total_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM auth_tokens")
+            .fetch_one(pool)
+            .await?;
+
+        let tokens = sqlx::query_as::<_, AuthToken>(
+            r#"
+            SELECT id, token, description, created_at, last_used_at
+            FROM auth_tokens
+            ORDER BY created_at DESC, id DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok((tokens, total_count.0))
+    }
+
     /// Gets a token by ID
-    #[allow(dead_code)]
     pub async fn get_by_id(pool: &PgPool, id: i32) -> AppResult<AuthToken> {
         let token = sqlx::query_as::<_, AuthToken>(
             r#"
@@ -75,6 +104,36 @@ impl AuthTokenService {
         Ok(token)
     }
 
+    /// Updates a token's description
+    pub async fn update(pool: &PgPool, id: i32, input: UpdateAuthToken) -> AppResult<AuthToken> {
+        let Some(description) = input.description else {
+            return Self::get_by_id(pool, id).await;
+        };
+
+        let description = description.trim();
+        if description.len() > 255 {
+            return Err(AppError::Validation(
+                "Description cannot exceed 255 characters".to_string(),
+            ));
+        }
+        let description = (!description.is_empty()).then_some(description);
+
+        let token = sqlx::query_as::<_, AuthToken>(
+            r#"
+            UPDATE auth_tokens SET description = $1
+            WHERE id = $2
+            RETURNING id, token, description, created_at, last_used_at
+            "#,
+        )
+        .bind(description)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Token with id {} not found", id)))?;
+
+        Ok(token)
+    }
+
     /// Deletes a token (revoke)
     pub async fn delete(pool: &PgPool, id: i32) -> AppResult<()> {
         let result = sqlx::query("DELETE FROM auth_tokens WHERE id = $1")
@@ -0,0 +1,49 @@
+//! Background job that resolves issues that went quiet.
+//!
+//! Runs once at startup and then every [`AUTO_RESOLVE_INTERVAL`]. Open issues
+//! of projects with `auto_resolve_days` that haven't had an event for that
+//! many days are resolved and flagged `auto_resolved`; a new event reopens
+//! them.
+
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::error::AppResult;
+use crate::services::IssueService;
+
+/// Time between auto-resolve runs
+pub const AUTO_RESOLVE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Issues resolved per statement
+pub const AUTO_RESOLVE_BATCH_SIZE: i64 = 500;
+
+pub struct AutoResolveService;
+
+impl AutoResolveService {
+    /// Resolves every issue past its project's threshold once
+    ///
+    /// Returns the number of issues resolved.
+    pub async fn run(pool: &PgPool) -> AppResult<u64> {
+        let resolved = IssueService::auto_resolve(pool, AUTO_RESOLVE_BATCH_SIZE).await?;
+        if resolved > 0 {
+            log::info!("Auto-resolved {} quiet issues", resolved);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Starts the periodic job; failed runs are logged and retried on the next tick
+    pub fn spawn(pool: PgPool) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(AUTO_RESOLVE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::run(&pool).await {
+                    log::error!("Auto-resolve job failed: {}", e);
+                }
+            }
+        })
+    }
+}
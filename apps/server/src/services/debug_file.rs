@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::digest::breakpad::{SymbolFile, MAX_SYMBOL_FILE_SIZE};
+use crate::digest::symbol_cache::SymbolCache;
+use crate::error::{AppError, AppResult};
+use crate::models::DebugFile;
+
+pub struct DebugFileService;
+
+impl DebugFileService {
+    /// Lists the debug files of a project
+    pub async fn list(pool: &PgPool, project_id: i32) -> AppResult<Vec<DebugFile>> {
+        let files = sqlx::query_as::<_, DebugFile>(
+            r#"
+            SELECT id, project_id, debug_id, code_file, os, arch, file_size, created_at
+            FROM debug_files
+            WHERE project_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(files)
+    }
+
+    /// Stores a Breakpad symbol file
+    ///
+    /// The whole file is validated first. Uploading symbols for a debug ID
+    /// that already has some replaces them.
+    pub async fn upload(pool: &PgPool, project_id: i32, data: &[u8]) -> AppResult<DebugFile> {
+        if data.len() > MAX_SYMBOL_FILE_SIZE {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Symbol file exceeds {} bytes",
+                MAX_SYMBOL_FILE_SIZE
+            )));
+        }
+
+        let text = std::str::from_utf8(data).map_err(|_| {
+            AppError::Validation("Invalid symbol file: not a text file".to_string())
+        })?;
+        let module = SymbolFile::parse(text)?.module;
+
+        let file = sqlx::query_as::<_, DebugFile>(
+            r#"
+            INSERT INTO debug_files (project_id, debug_id, code_file, os, arch, file_size, data)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (project_id, debug_id) DO UPDATE
+            SET code_file = EXCLUDED.code_file,
+                os = EXCLUDED.os,
+                arch = EXCLUDED.arch,
+                file_size = EXCLUDED.file_size,
+                data = EXCLUDED.data,
+                created_at = NOW()
+            RETURNING id, project_id, debug_id, code_file, os, arch, file_size, created_at
+            "#,
+        )
+        .bind(project_id)
+        .bind(&module.debug_id)
+        .bind(truncate(&module.name, 255))
+        .bind(truncate(&module.os, 32))
+        .bind(truncate(&module.arch, 32))
+        .bind(data.len() as i32)
+        .bind(data)
+        .fetch_one(pool)
+        .await?;
+        SymbolCache::global().remove(project_id, &file.debug_id);
+
+        Ok(file)
+    }
+
+    /// Deletes a debug file of a project
+    pub async fn delete(pool: &PgPool, project_id: i32, id: i32) -> AppResult<()> {
+        let debug_id: Option<String> = sqlx::query_scalar(
+            "DELETE FROM debug_files WHERE id = $1 AND project_id = $2 RETURNING debug_id",
+        )
+        .bind(id)
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let debug_id =
+            debug_id.ok_or_else(|| AppError::NotFound(format!("Debug file {} not found", id)))?;
+        SymbolCache::global().remove(project_id, &debug_id);
+
+        Ok(())
+    }
+
+    /// Loads and parses the symbol files for the given debug IDs
+    ///
+    /// Debug IDs without a symbol file are left out. Parsed files come from
+    /// the [`SymbolCache`] while their upload is unchanged. A stored file
+    /// that no longer parses is logged and skipped, so it never blocks
+    /// digestion.
+    pub async fn load_symbols(
+        pool: &PgPool,
+        project_id: i32,
+        debug_ids: &[String],
+    ) -> AppResult<HashMap<String, Arc<SymbolFile>>> {
+        if debug_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let uploads: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT debug_id, created_at FROM debug_files WHERE project_id = $1 AND debug_id = ANY($2)",
+        )
+        .bind(project_id)
+        .bind(debug_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let cache = SymbolCache::global();
+        let mut symbols = HashMap::new();
+        let mut missing = Vec::new();
+        for (debug_id, uploaded_at) in uploads {
+            match cache.get(project_id, &debug_id, uploaded_at) {
+                Some(file) => {
+                    symbols.insert(debug_id, file);
+                }
+                None => missing.push(debug_id),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(symbols);
+        }
+
+        let rows: Vec<(String, DateTime<Utc>, Vec<u8>)> = sqlx::query_as(
+            r#"
+            SELECT debug_id, created_at, data
+            FROM debug_files
+            WHERE project_id = $1 AND debug_id = ANY($2)
+            "#,
+        )
+        .bind(project_id)
+        .bind(&missing)
+        .fetch_all(pool)
+        .await?;
+
+        for (debug_id, uploaded_at, data) in rows {
+            let parsed = std::str::from_utf8(&data)
+                .map_err(|e| AppError::Internal(e.to_string()))
+                .and_then(SymbolFile::parse);
+            match parsed {
+                Ok(file) => {
+                    let file = Arc::new(file);
+                    cache.insert(project_id, &debug_id, uploaded_at, file.clone());
+                    symbols.insert(debug_id, file);
+                }
+                Err(e) => log::warn!("Skipping symbol file {}: {}", debug_id, e),
+            }
+        }
+
+        Ok(symbols)
+    }
+}
+
+/// Truncates a string to max_len characters
+fn truncate(s: &str, max_len: usize) -> String {
+    s.chars().take(max_len).collect()
+}
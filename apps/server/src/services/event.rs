@@ -1,12 +1,25 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use ipnetwork::IpNetwork;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::digest::contexts::MAX_DIST_LENGTH;
+use crate::digest::{extract_dist, merge_event_data};
 use crate::error::{AppError, AppResult};
-use crate::models::Event;
-use crate::pagination::{EventCursor, SortOrder};
-use crate::services::grouping::DenormalizedFields;
+use crate::models::{
+    Event, EventLevelCount, EventTransactionCount, Issue, LevelBreakdownResponse,
+    TransactionBreakdownResponse,
+};
+use crate::pagination::{CursorError, EventCursor, EventSort, SortOrder};
+use crate::services::grouping::{is_unhandled, DenormalizedFields};
+
+/// Max transactions returned by `transaction_breakdown`
+pub const MAX_ISSUE_TRANSACTIONS: i64 = 50;
+
+/// Events whose payload `prune_payloads` drops per statement
+const PRUNE_BATCH_SIZE: i64 = 1000;
 
 pub struct EventService;
 
@@ -15,83 +28,73 @@ impl EventService {
     ///
     /// Uses KEYSET pagination for efficient large dataset handling.
     /// Returns (events, has_more) where has_more indicates if there are more results.
+    ///
+    /// Events are ordered by digest_order, or by timestamp with digest_order
+    /// breaking ties. The cursor must be one issued for the same ordering.
+    /// With `dist`, only events of that distribution are listed.
+    ///
+    /// The newest-first page is read backwards off the unique
+    /// `(issue_id, digest_order)` index, so it takes no sort; the issue's
+    /// `last_event_id` only saves a lookup when a single event is needed.
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_paginated(
         pool: &PgPool,
         issue_id: Uuid,
+        by: EventSort,
         order: SortOrder,
         cursor: Option<&EventCursor>,
+        dist: Option<&str>,
         limit: i64,
     ) -> AppResult<(Vec<Event>, bool)> {
         // Fetch limit+1 to determine if there are more results
         let fetch_limit = limit + 1;
 
-        let events = match (order, cursor) {
-            // DESC (newest first) - no cursor
-            (SortOrder::Desc, None) => {
-                sqlx::query_as::<_, Event>(
-                    r#"
-                    SELECT * FROM events
-                    WHERE issue_id = $1
-                    ORDER BY digest_order DESC
-                    LIMIT $2
-                    "#,
-                )
-                .bind(issue_id)
-                .bind(fetch_limit)
-                .fetch_all(pool)
-                .await?
-            }
+        let (direction, past) = match order {
+            SortOrder::Desc => ("DESC", "<"),
+            SortOrder::Asc => ("ASC", ">"),
+        };
 
-            // DESC - with cursor
-            (SortOrder::Desc, Some(c)) => {
-                sqlx::query_as::<_, Event>(
-                    r#"
-                    SELECT * FROM events
-                    WHERE issue_id = $1 AND digest_order < $3
-                    ORDER BY digest_order DESC
-                    LIMIT $2
-                    "#,
-                )
-                .bind(issue_id)
-                .bind(fetch_limit)
-                .bind(c.last_digest_order)
-                .fetch_all(pool)
-                .await?
-            }
+        let (order_by, after_cursor) = match by {
+            EventSort::Received => (
+                format!("digest_order {}", direction),
+                format!("digest_order {} $4", past),
+            ),
+            EventSort::Timestamp => (
+                format!("timestamp {0}, digest_order {0}", direction),
+                format!("(timestamp, digest_order) {} ($5, $4)", past),
+            ),
+        };
 
-            // ASC (oldest first) - no cursor
-            (SortOrder::Asc, None) => {
-                sqlx::query_as::<_, Event>(
-                    r#"
-                    SELECT * FROM events
-                    WHERE issue_id = $1
-                    ORDER BY digest_order ASC
-                    LIMIT $2
-                    "#,
-                )
-                .bind(issue_id)
-                .bind(fetch_limit)
-                .fetch_all(pool)
-                .await?
-            }
+        if cursor.is_some_and(|c| c.by != by.as_str()) {
+            return Err(CursorError::Invalid("cursor is for another ordering").into());
+        }
 
-            // ASC - with cursor
-            (SortOrder::Asc, Some(c)) => {
-                sqlx::query_as::<_, Event>(
-                    r#"
-                    SELECT * FROM events
-                    WHERE issue_id = $1 AND digest_order > $3
-                    ORDER BY digest_order ASC
-                    LIMIT $2
-                    "#,
-                )
-                .bind(issue_id)
-                .bind(fetch_limit)
-                .bind(c.last_digest_order)
-                .fetch_all(pool)
-                .await?
+        let sql = format!(
+            r#"
+            SELECT * FROM events
+            WHERE issue_id = $1 AND ($3::text IS NULL OR dist = $3){}
+            ORDER BY {}
+            LIMIT $2
+            "#,
+            if cursor.is_some() {
+                format!(" AND {}", after_cursor)
+            } else {
+                String::new()
+            },
+            order_by
+        );
+
+        let mut query = sqlx::query_as::<_, Event>(&sql)
+            .bind(issue_id)
+            .bind(fetch_limit)
+            .bind(dist);
+        if let Some(c) = cursor {
+            query = query.bind(c.last_digest_order);
+            if by == EventSort::Timestamp {
+                query = query.bind(c.last_timestamp);
             }
-        };
+        }
+        let events = query.fetch_all(pool).await?;
 
         let has_more = events.len() > limit as usize;
         let events: Vec<Event> = events.into_iter().take(limit as usize).collect();
@@ -110,10 +113,57 @@ impl EventService {
         Ok(event)
     }
 
-    /// Creates a new event
+    /// Gets an event by its SDK-assigned event_id within a project
+    pub async fn get_by_event_id(
+        pool: &PgPool,
+        project_id: i32,
+        event_id: Uuid,
+    ) -> AppResult<Event> {
+        let event = sqlx::query_as::<_, Event>(
+            "SELECT * FROM events WHERE project_id = $1 AND event_id = $2",
+        )
+        .bind(project_id)
+        .bind(event_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Event {} not found", event_id)))?;
+
+        Ok(event)
+    }
+
+    /// Gets the latest stored event of an issue
+    ///
+    /// Follows the issue's `last_event_id`; if that event is gone, e.g.
+    /// deleted by hand before a recount, falls back to the highest digest
+    /// order. Returns None when the issue has no stored events.
+    pub async fn latest(pool: &PgPool, issue: &Issue) -> AppResult<Option<Event>> {
+        if let Some(id) = issue.last_event_id {
+            let event =
+                sqlx::query_as::<_, Event>("SELECT * FROM events WHERE id = $1 AND issue_id = $2")
+                    .bind(id)
+                    .bind(issue.id)
+                    .fetch_optional(pool)
+                    .await?;
+            if event.is_some() {
+                return Ok(event);
+            }
+        }
+
+        let event = sqlx::query_as::<_, Event>(
+            "SELECT * FROM events WHERE issue_id = $1 ORDER BY digest_order DESC LIMIT 1",
+        )
+        .bind(issue.id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Creates a new event with the row id `id`
     #[allow(clippy::too_many_arguments)]
     pub async fn create(
         pool: &PgPool,
+        id: Uuid,
         event_id: Uuid,
         project_id: i32,
         issue_id: Uuid,
@@ -125,20 +175,7 @@ impl EventService {
         remote_addr: Option<&str>,
     ) -> AppResult<Event> {
         // Extract fields from event_data
-        let timestamp = event_data
-            .get("timestamp")
-            .and_then(|t| {
-                if let Some(ts) = t.as_f64() {
-                    DateTime::from_timestamp(ts as i64, ((ts.fract()) * 1_000_000_000.0) as u32)
-                } else if let Some(ts_str) = t.as_str() {
-                    DateTime::parse_from_rfc3339(ts_str)
-                        .ok()
-                        .map(|dt| dt.to_utc())
-                } else {
-                    None
-                }
-            })
-            .unwrap_or(ingested_at);
+        let timestamp = event_timestamp(event_data, ingested_at);
 
         let level = event_data
             .get("level")
@@ -155,6 +192,8 @@ impl EventService {
             .and_then(|r| r.as_str())
             .unwrap_or("");
 
+        let dist = extract_dist(event_data).unwrap_or_default();
+
         let environment = event_data
             .get("environment")
             .and_then(|e| e.as_str())
@@ -184,17 +223,18 @@ impl EventService {
         let event = sqlx::query_as::<_, Event>(
             r#"
             INSERT INTO events (
-                event_id, project_id, issue_id, grouping_id, data,
+                id, event_id, project_id, issue_id, grouping_id, data,
                 timestamp, ingested_at,
                 calculated_type, calculated_value, transaction,
                 last_frame_filename, last_frame_module, last_frame_function,
                 level, platform, release, environment, server_name,
-                sdk_name, sdk_version, digest_order, remote_addr
+                sdk_name, sdk_version, digest_order, remote_addr, dist, is_unhandled
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
             RETURNING *
             "#,
         )
+        .bind(id)
         .bind(event_id)
         .bind(project_id)
         .bind(issue_id)
@@ -217,12 +257,225 @@ impl EventService {
         .bind(sdk_version)
         .bind(digest_order)
         .bind(remote_addr_inet)
+        .bind(dist)
+        .bind(denormalized.is_unhandled)
         .fetch_one(pool)
         .await?;
 
         Ok(event)
     }
 
+    /// Counts an issue's events per level, most frequent first
+    pub async fn level_breakdown(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> AppResult<LevelBreakdownResponse> {
+        let levels = sqlx::query_as::<_, EventLevelCount>(
+            r#"
+            SELECT level, COUNT(*) AS count
+            FROM events
+            WHERE issue_id = $1
+            GROUP BY level
+            ORDER BY count DESC, level
+            "#,
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(LevelBreakdownResponse {
+            total: levels.iter().map(|l| l.count).sum(),
+            levels,
+        })
+    }
+
+    /// Counts an issue's events per transaction, most affected first
+    ///
+    /// Events without a transaction are left out. Only the
+    /// `MAX_ISSUE_TRANSACTIONS` most frequent are listed, but `total`
+    /// covers them all.
+    pub async fn transaction_breakdown(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> AppResult<TransactionBreakdownResponse> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM events WHERE issue_id = $1 AND transaction <> ''",
+        )
+        .bind(issue_id)
+        .fetch_one(pool)
+        .await?;
+
+        let transactions = sqlx::query_as::<_, EventTransactionCount>(
+            r#"
+            SELECT transaction, COUNT(*) AS count
+            FROM events
+            WHERE issue_id = $1 AND transaction <> ''
+            GROUP BY transaction
+            ORDER BY count DESC, transaction
+            LIMIT $2
+            "#,
+        )
+        .bind(issue_id)
+        .bind(MAX_ISSUE_TRANSACTIONS)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(TransactionBreakdownResponse {
+            total,
+            transactions,
+        })
+    }
+
+    /// Merges an update sent under the event_id of a stored event
+    ///
+    /// Whichever of the two was ingested later wins where both set a field.
+    /// The event keeps its issue and digest order; the issue's `last_seen`
+    /// moves forward to the update's timestamp but nothing is counted again.
+    /// Returns None if the event is no longer stored, or only its metadata is.
+    pub async fn merge_update(
+        pool: &PgPool,
+        project_id: i32,
+        event_id: Uuid,
+        update: &serde_json::Value,
+        ingested_at: DateTime<Utc>,
+    ) -> AppResult<Option<Event>> {
+        let mut tx = pool.begin().await?;
+
+        let stored = sqlx::query_as::<_, Event>(
+            "SELECT * FROM events WHERE project_id = $1 AND event_id = $2 FOR UPDATE",
+        )
+        .bind(project_id)
+        .bind(event_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(stored) = stored.filter(|event| !event.payload_pruned) else {
+            return Ok(None);
+        };
+
+        // An update can be digested before the payload it completes
+        let data = if ingested_at >= stored.ingested_at {
+            let mut data = stored.data;
+            merge_event_data(&mut data, update);
+            data
+        } else {
+            let mut data = update.clone();
+            merge_event_data(&mut data, &stored.data);
+            data
+        };
+
+        let event = sqlx::query_as::<_, Event>(
+            r#"
+            UPDATE events
+            SET data = $2,
+                ingested_at = LEAST(ingested_at, $3),
+                level = COALESCE($2->>'level', 'error'),
+                release = COALESCE($2->>'release', ''),
+                dist = LEFT(TRIM(COALESCE($2->>'dist', '')), $4),
+                environment = COALESCE($2->>'environment', ''),
+                server_name = COALESCE($2->>'server_name', ''),
+                sdk_name = COALESCE($2->'sdk'->>'name', ''),
+                sdk_version = COALESCE($2->'sdk'->>'version', ''),
+                is_unhandled = $5
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(stored.id)
+        .bind(&data)
+        .bind(ingested_at)
+        .bind(MAX_DIST_LENGTH as i32)
+        .bind(is_unhandled(&data))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE issues
+            SET last_seen = GREATEST(last_seen, $2),
+                is_unhandled = is_unhandled OR $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(event.issue_id)
+        .bind(event_timestamp(update, ingested_at))
+        .bind(event.is_unhandled)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(event))
+    }
+
+    /// Drops the raw payload of events digested more than `days` ago
+    ///
+    /// The rows stay, with their metadata, flagged `payload_pruned`. Works in
+    /// batches so a large backlog doesn't hold one long transaction. Returns
+    /// the number of events pruned.
+    pub async fn prune_payloads(pool: &PgPool, days: i32) -> AppResult<u64> {
+        let mut pruned = 0;
+        loop {
+            let result = sqlx::query(
+                r#"
+                UPDATE events
+                SET data = '{}'::jsonb, payload_pruned = TRUE
+                WHERE id IN (
+                    SELECT id FROM events
+                    WHERE NOT payload_pruned
+                      AND digested_at < NOW() - make_interval(days => $1)
+                    LIMIT $2
+                )
+                "#,
+            )
+            .bind(days)
+            .bind(PRUNE_BATCH_SIZE)
+            .execute(pool)
+            .await?;
+
+            pruned += result.rows_affected();
+            if result.rows_affected() < PRUNE_BATCH_SIZE as u64 {
+                return Ok(pruned);
+            }
+        }
+    }
+
+    /// Whether each (project_id, event_id) has a stored payload, or is
+    /// still to get one
+    ///
+    /// Events that were deleted, pruned or sampled out map to false, events
+    /// still waiting to be digested to true. Events the database has no
+    /// trace of, e.g. ones whose ingested id was already forgotten, are left
+    /// out.
+    pub async fn payloads_kept(
+        pool: &PgPool,
+        events: &[(i32, Uuid)],
+    ) -> AppResult<HashMap<(i32, Uuid), bool>> {
+        let (project_ids, event_ids): (Vec<i32>, Vec<Uuid>) = events.iter().copied().unzip();
+        let rows: Vec<(i32, Uuid, bool)> = sqlx::query_as(
+            r#"
+            SELECT k.project_id, k.event_id,
+                CASE
+                    WHEN e.id IS NOT NULL THEN NOT e.payload_pruned
+                    ELSE i.digested_at IS NULL
+                END
+            FROM UNNEST($1::int[], $2::uuid[]) AS k(project_id, event_id)
+            LEFT JOIN events e
+                ON e.project_id = k.project_id AND e.event_id = k.event_id
+            LEFT JOIN ingested_events i
+                ON i.project_id = k.project_id AND i.event_id = k.event_id
+            WHERE e.id IS NOT NULL OR i.event_id IS NOT NULL
+            "#,
+        )
+        .bind(&project_ids)
+        .bind(&event_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(project_id, event_id, kept)| ((project_id, event_id), kept))
+            .collect())
+    }
+
     /// Checks if an event with this event_id already exists in the project
     pub async fn exists(pool: &PgPool, project_id: i32, event_id: Uuid) -> AppResult<bool> {
         let exists: bool = sqlx::query_scalar(
@@ -236,3 +489,21 @@ impl EventService {
         Ok(exists)
     }
 }
+
+/// The `timestamp` an SDK set on an event, or `fallback` if it set none
+fn event_timestamp(event_data: &serde_json::Value, fallback: DateTime<Utc>) -> DateTime<Utc> {
+    event_data
+        .get("timestamp")
+        .and_then(|t| {
+            if let Some(ts) = t.as_f64() {
+                DateTime::from_timestamp(ts as i64, ((ts.fract()) * 1_000_000_000.0) as u32)
+            } else if let Some(ts_str) = t.as_str() {
+                DateTime::parse_from_rfc3339(ts_str)
+                    .ok()
+                    .map(|dt| dt.to_utc())
+            } else {
+                None
+            }
+        })
+        .unwrap_or(fallback)
+}
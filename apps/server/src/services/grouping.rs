@@ -1,13 +1,45 @@
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 
-/// Separator used in grouping keys (diamond character)
-const GROUPING_SEPARATOR: &str = " ⋄ ";
+use crate::models::grouping::GROUPING_SEPARATOR;
+use crate::models::issue::NO_TRANSACTION;
+
+/// Separator between exception types of a chain in grouping keys
+const CHAIN_SEPARATOR: &str = " > ";
+
+/// Max characters of an exception value or log message that grouping uses
+///
+/// Independent of MAX_ISSUE_VALUE_LENGTH, so changing how much of the value
+/// is stored never moves new events to other issues.
+pub const GROUPING_VALUE_LENGTH: usize = 1024;
 
 /// Calculates the grouping key for an event
+///
+/// Native events also group by their crashing frame, preferring its
+/// symbolicated function name (see [`calculate_fallback_grouping_key`]).
 pub fn calculate_grouping_key(event_data: &Value) -> String {
+    grouping_key(
+        event_data,
+        get_native_frame_key(event_data, true).as_deref(),
+    )
+}
+
+/// Calculates the grouping key a native event had before symbolication
+///
+/// The crashing frame is identified by "module+offset" instead of its
+/// function name. Returns None when this is the same as the main key, i.e.
+/// when the crashing frame has no function name.
+pub fn calculate_fallback_grouping_key(event_data: &Value) -> Option<String> {
+    let frame_key = get_native_frame_key(event_data, false)?;
+    let fallback = grouping_key(event_data, Some(&frame_key));
+
+    (fallback != calculate_grouping_key(event_data)).then_some(fallback)
+}
+
+fn grouping_key(event_data: &Value, native_frame: Option<&str>) -> String {
     let (calculated_type, calculated_value) = get_type_and_value(event_data);
     let transaction = get_transaction(event_data);
+    let chain_types = get_exception_chain_types(event_data);
 
     // Check for custom fingerprint
     if let Some(fingerprint) = event_data.get("fingerprint").and_then(|f| f.as_array()) {
@@ -16,7 +48,13 @@ pub fn calculate_grouping_key(event_data: &Value) -> String {
             .map(|part| {
                 let part_str = part.as_str().unwrap_or("");
                 if part_str == "{{ default }}" {
-                    default_grouping_key(&calculated_type, &calculated_value, &transaction)
+                    default_grouping_key(
+                        &calculated_type,
+                        &calculated_value,
+                        &transaction,
+                        &chain_types,
+                        native_frame,
+                    )
                 } else {
                     part_str.to_string()
                 }
@@ -26,17 +64,44 @@ pub fn calculate_grouping_key(event_data: &Value) -> String {
     }
 
     // Default grouping
-    default_grouping_key(&calculated_type, &calculated_value, &transaction)
+    default_grouping_key(
+        &calculated_type,
+        &calculated_value,
+        &transaction,
+        &chain_types,
+        native_frame,
+    )
 }
 
 /// Default grouping key: "Type: value ⋄ transaction"
+///
+/// Chained exceptions append all types of the chain in event order, which
+/// SDKs send root cause first ("⋄ Cause > Wrapper"), so the same root cause
+/// raised through different wrappers groups apart.
+/// Native events append their crashing frame.
 fn default_grouping_key(
     calculated_type: &str,
     calculated_value: &str,
     transaction: &str,
+    chain_types: &[String],
+    native_frame: Option<&str>,
 ) -> String {
     let title = get_title(calculated_type, calculated_value);
-    format!("{}{}{}", title, GROUPING_SEPARATOR, transaction)
+    let mut key = format!("{}{}{}", title, GROUPING_SEPARATOR, transaction);
+
+    if chain_types.len() > 1 {
+        key = format!(
+            "{}{}{}",
+            key,
+            GROUPING_SEPARATOR,
+            chain_types.join(CHAIN_SEPARATOR)
+        );
+    }
+
+    match native_frame {
+        Some(frame) => format!("{}{}{}", key, GROUPING_SEPARATOR, frame),
+        None => key,
+    }
 }
 
 /// Calculates the SHA256 hash of the grouping key
@@ -62,31 +127,133 @@ pub fn get_type_and_value(event_data: &Value) -> (String, String) {
             .unwrap_or("")
             .to_string();
 
-        return (truncate(&exc_type, 128), truncate(&exc_value, 1024));
+        return (
+            truncate(&exc_type, 128),
+            truncate(&exc_value, GROUPING_VALUE_LENGTH),
+        );
     }
 
     // Try to extract from logentry/message
     if let Some(message) = get_log_message(event_data) {
-        return ("Log Message".to_string(), truncate(&message, 1024));
+        return (
+            "Log Message".to_string(),
+            truncate(&message, GROUPING_VALUE_LENGTH),
+        );
     }
 
     // Fallback
     ("Unknown".to_string(), String::new())
 }
 
-/// Gets the main exception (the last one in the chain)
-fn get_main_exception(event_data: &Value) -> Option<&Value> {
-    let exception = event_data.get("exception")?;
+/// Gets the exception chain of the event (empty if there is none)
+fn get_exception_chain(event_data: &Value) -> &[Value] {
+    let Some(exception) = event_data.get("exception") else {
+        return &[];
+    };
 
     // Can be a direct array or an object with "values"
     let values = if exception.is_array() {
-        exception.as_array()?
+        exception.as_array()
     } else {
-        exception.get("values")?.as_array()?
+        exception.get("values").and_then(|v| v.as_array())
     };
 
-    // Return the last exception (most important)
-    values.last()
+    values.map(|v| v.as_slice()).unwrap_or_default()
+}
+
+/// Gets the main exception: the root cause of the chain
+///
+/// SDKs send the chain root cause first and the exception actually raised
+/// last. When the exceptions are linked by `mechanism.exception_id` and
+/// `parent_id`, the links are followed instead of relying on the order.
+fn get_main_exception(event_data: &Value) -> Option<&Value> {
+    let chain = get_exception_chain(event_data);
+    linked_root_cause(chain).or_else(|| chain.first())
+}
+
+/// Follows `mechanism.parent_id` links from the raised exception down to its
+/// innermost cause; None if the chain isn't linked
+fn linked_root_cause(chain: &[Value]) -> Option<&Value> {
+    let id = |exception: &Value, key: &str| {
+        exception
+            .get("mechanism")
+            .and_then(|m| m.get(key))
+            .and_then(|id| id.as_u64())
+    };
+
+    // The raised exception is the one without a parent
+    let mut current = chain
+        .iter()
+        .find(|e| id(e, "exception_id").is_some() && id(e, "parent_id").is_none())?;
+
+    // At most one step per exception, in case the links form a cycle
+    for _ in 0..chain.len() {
+        let current_id = id(current, "exception_id");
+        match chain.iter().find(|e| id(e, "parent_id") == current_id) {
+            Some(cause) => current = cause,
+            None => break,
+        }
+    }
+    Some(current)
+}
+
+/// Whether any exception in the chain reached the SDK uncaught
+///
+/// SDKs flag those with `mechanism.handled: false`; exceptions without a
+/// mechanism, or without `handled`, count as handled.
+pub fn is_unhandled(event_data: &Value) -> bool {
+    get_exception_chain(event_data).iter().any(|exception| {
+        exception
+            .get("mechanism")
+            .and_then(|m| m.get("handled"))
+            .and_then(|h| h.as_bool())
+            == Some(false)
+    })
+}
+
+/// Gets the types of all exceptions in the chain, in event order
+pub fn get_exception_chain_types(event_data: &Value) -> Vec<String> {
+    get_exception_chain(event_data)
+        .iter()
+        .map(|exception| {
+            let exc_type = exception
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("Error");
+            truncate(exc_type, 128)
+        })
+        .collect()
+}
+
+/// Identifies the crashing frame of a native event
+///
+/// That is the last frame of the main exception, if it has an
+/// `instruction_addr`. With `prefer_function` its function name is used when
+/// known; otherwise "module+0xoffset" as set by symbolication.
+fn get_native_frame_key(event_data: &Value, prefer_function: bool) -> Option<String> {
+    let frame = get_main_exception(event_data)?
+        .get("stacktrace")
+        .and_then(|st| st.get("frames"))
+        .and_then(|f| f.as_array())?
+        .last()
+        .filter(|f| f.get("instruction_addr").is_some())?;
+
+    let function = frame
+        .get("function")
+        .and_then(|f| f.as_str())
+        .filter(|f| prefer_function && !f.is_empty());
+    if let Some(function) = function {
+        return Some(truncate(function, 255));
+    }
+
+    let package = frame.get("package").and_then(|p| p.as_str())?;
+    let module = package.rsplit(['/', '\\']).next().unwrap_or(package);
+    let offset = frame
+        .get("data")
+        .and_then(|d| d.get("module_offset"))
+        .and_then(|o| o.as_str())?;
+
+    Some(format!("{}+{}", truncate(module, 255), offset))
 }
 
 /// Gets the log message
@@ -120,7 +287,7 @@ fn get_transaction(event_data: &Value) -> String {
         .get("transaction")
         .and_then(|t| t.as_str())
         .map(|s| truncate(s, 200))
-        .unwrap_or_else(|| "<no transaction>".to_string())
+        .unwrap_or_else(|| NO_TRANSACTION.to_string())
 }
 
 /// Generates the error title
@@ -142,6 +309,15 @@ fn truncate(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Cuts a string longer than `max_len` characters, ending it in `…`
+pub fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        return s.to_string();
+    }
+    let kept: String = s.chars().take(max_len.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
 /// Extracts denormalized fields from the event
 pub fn get_denormalized_fields(event_data: &Value) -> DenormalizedFields {
     let (calculated_type, calculated_value) = get_type_and_value(event_data);
@@ -156,6 +332,8 @@ pub fn get_denormalized_fields(event_data: &Value) -> DenormalizedFields {
         last_frame_filename: filename,
         last_frame_module: module,
         last_frame_function: function,
+        exception_chain_length: get_exception_chain(event_data).len() as i32,
+        is_unhandled: is_unhandled(event_data),
     }
 }
 
@@ -168,6 +346,30 @@ pub struct DenormalizedFields {
     pub last_frame_filename: String,
     pub last_frame_module: String,
     pub last_frame_function: String,
+    /// Number of exceptions in the chain (0 for log messages)
+    pub exception_chain_length: i32,
+    /// Whether an exception of the chain wasn't caught
+    pub is_unhandled: bool,
+}
+
+impl DenormalizedFields {
+    /// Cuts the value, transaction and frame fields to `max_length`
+    /// characters for storage (MAX_ISSUE_VALUE_LENGTH)
+    ///
+    /// The full value stays in the event JSON; grouping keys are computed
+    /// from the event and don't depend on this.
+    pub fn capped(mut self, max_length: usize) -> Self {
+        for field in [
+            &mut self.calculated_value,
+            &mut self.transaction,
+            &mut self.last_frame_filename,
+            &mut self.last_frame_module,
+            &mut self.last_frame_function,
+        ] {
+            *field = truncate_with_ellipsis(field, max_length);
+        }
+        self
+    }
 }
 
 /// Extracts information from the last stacktrace frame
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db;
+use crate::error::AppResult;
+
+/// Days an ingested event_id is remembered
+pub const INGESTED_EVENT_RETENTION_DAYS: i32 = 7;
+
+/// Event ids accepted by ingest, used to store each event only once
+pub struct IngestedEventService;
+
+impl IngestedEventService {
+    /// Records that an event was accepted
+    ///
+    /// Returns false when the event_id was already ingested for the project,
+    /// in which case the event must not be stored again.
+    pub async fn record(
+        pool: &PgPool,
+        project_id: i32,
+        event_id: Uuid,
+        ingested_at: DateTime<Utc>,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO ingested_events (project_id, event_id, ingested_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (project_id, event_id) DO NOTHING
+            "#,
+        )
+        .bind(project_id)
+        .bind(event_id)
+        .bind(ingested_at)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Whether an event was already digested
+    ///
+    /// Events stored before ingested events were tracked are found in the
+    /// events table.
+    pub async fn is_digested(pool: &PgPool, project_id: i32, event_id: Uuid) -> AppResult<bool> {
+        let digested: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM ingested_events
+                WHERE project_id = $1 AND event_id = $2 AND digested_at IS NOT NULL
+            ) OR EXISTS(
+                SELECT 1 FROM events WHERE project_id = $1 AND event_id = $2
+            )
+            "#,
+        )
+        .bind(project_id)
+        .bind(event_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(digested)
+    }
+
+    /// Marks an event as digested
+    ///
+    /// Events accepted while the database was down have no row yet, so one
+    /// is created.
+    pub async fn mark_digested(
+        pool: &PgPool,
+        project_id: i32,
+        event_id: Uuid,
+        ingested_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ingested_events (project_id, event_id, ingested_at, digested_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (project_id, event_id) DO UPDATE SET digested_at = NOW()
+            "#,
+        )
+        .bind(project_id)
+        .bind(event_id)
+        .bind(ingested_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks an event as digested without being stored, its issue sampling
+    /// it out
+    pub async fn mark_sampled(
+        pool: &PgPool,
+        project_id: i32,
+        event_id: Uuid,
+        ingested_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ingested_events (project_id, event_id, ingested_at, digested_at, sampled)
+            VALUES ($1, $2, $3, NOW(), TRUE)
+            ON CONFLICT (project_id, event_id) DO UPDATE SET digested_at = NOW(), sampled = TRUE
+            "#,
+        )
+        .bind(project_id)
+        .bind(event_id)
+        .bind(ingested_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forgets an event that was discarded before being digested, so the
+    /// SDK can send it again
+    pub async fn forget(pool: &PgPool, project_id: i32, event_id: Uuid) -> AppResult<()> {
+        sqlx::query("DELETE FROM ingested_events WHERE project_id = $1 AND event_id = $2")
+            .bind(project_id)
+            .bind(event_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes event ids ingested more than `days` ago
+    ///
+    /// Returns the number of rows removed.
+    pub async fn purge(pool: &PgPool, days: i32) -> AppResult<u64> {
+        let mut tx = pool.begin().await?;
+        db::disable_statement_timeout(&mut tx).await?;
+        let result = sqlx::query(
+            "DELETE FROM ingested_events WHERE ingested_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(days)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,14 +1,27 @@
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
-use crate::models::Issue;
-use crate::pagination::{IssueCursor, IssueFilter, IssueSort, SortOrder};
-use crate::services::grouping::DenormalizedFields;
+use crate::db;
+use crate::error::{codes, AppError, AppResult};
+use crate::models::{
+    Actor, EventCounts, Grouping, HourlyEventCount, Issue, IssueActivity, IssueAggregate,
+    IssueBundle, IssuePriority, IssuePriorityChange, IssueRecount, IssueSeenBy, Project,
+    ResolveIssue,
+};
+use crate::pagination::{EventSort, IssueCursor, IssueFilter, IssueGroupBy, IssueSort, SortOrder};
+use crate::services::grouping::{truncate_with_ellipsis, DenormalizedFields};
+use crate::services::{EventService, ProjectSequenceService, RateWindow};
 
 pub struct IssueService;
 
+/// Max groups returned by [`IssueService::aggregate`]
+pub const MAX_AGGREGATE_GROUPS: i64 = 50;
+
+/// Hours covered by [`IssueService::sparklines`], ending with the current one
+pub const SPARKLINE_HOURS: i32 = 24;
+
 impl IssueService {
     /// Lists issues with cursor-based pagination
     ///
@@ -33,7 +46,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                         ORDER BY digest_order DESC
                         LIMIT $2
                         "#,
@@ -46,7 +59,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                         ORDER BY digest_order DESC
                         LIMIT $2
                         "#,
@@ -65,7 +78,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                           AND digest_order < $3
                         ORDER BY digest_order DESC
                         LIMIT $2
@@ -80,7 +93,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                           AND digest_order < $3
                         ORDER BY digest_order DESC
                         LIMIT $2
@@ -100,7 +113,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                         ORDER BY digest_order ASC
                         LIMIT $2
                         "#,
@@ -113,7 +126,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                         ORDER BY digest_order ASC
                         LIMIT $2
                         "#,
@@ -132,7 +145,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                           AND digest_order > $3
                         ORDER BY digest_order ASC
                         LIMIT $2
@@ -147,7 +160,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                           AND digest_order > $3
                         ORDER BY digest_order ASC
                         LIMIT $2
@@ -167,7 +180,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                         ORDER BY last_seen DESC, id DESC
                         LIMIT $2
                         "#,
@@ -180,7 +193,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                         ORDER BY last_seen DESC, id DESC
                         LIMIT $2
                         "#,
@@ -200,7 +213,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                           AND (last_seen, id) < ($3, $4)
                         ORDER BY last_seen DESC, id DESC
                         LIMIT $2
@@ -216,7 +229,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                           AND (last_seen, id) < ($3, $4)
                         ORDER BY last_seen DESC, id DESC
                         LIMIT $2
@@ -237,7 +250,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                         ORDER BY last_seen ASC, id ASC
                         LIMIT $2
                         "#,
@@ -250,7 +263,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                         ORDER BY last_seen ASC, id ASC
                         LIMIT $2
                         "#,
@@ -264,13 +277,13 @@ impl IssueService {
 
             // last_seen ASC - with cursor
             (IssueSort::LastSeen, SortOrder::Asc, Some(c)) => {
-                let last_seen = c.last_seen.unwrap_or_else(|| DateTime::UNIX_EPOCH);
+                let last_seen = c.last_seen.unwrap_or(DateTime::UNIX_EPOCH);
                 let last_id = c.last_id.unwrap_or(Uuid::nil());
                 if include_resolved {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted
+                        WHERE project_id = $1 AND deleted_at IS NULL
                           AND (last_seen, id) > ($3, $4)
                         ORDER BY last_seen ASC, id ASC
                         LIMIT $2
@@ -286,7 +299,7 @@ impl IssueService {
                     sqlx::query_as::<_, Issue>(
                         r#"
                         SELECT * FROM issues
-                        WHERE project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted
+                        WHERE project_id = $1 AND deleted_at IS NULL AND NOT is_resolved AND NOT is_muted
                           AND (last_seen, id) > ($3, $4)
                         ORDER BY last_seen ASC, id ASC
                         LIMIT $2
@@ -300,6 +313,13 @@ impl IssueService {
                     .await?
                 }
             }
+
+            // Priorities repeat too much for a keyset
+            (IssueSort::Priority, _, _) => {
+                return Err(AppError::Validation(
+                    "Sorting by priority needs offset pagination".to_string(),
+                ))
+            }
         };
 
         let has_more = issues.len() > limit as usize;
@@ -311,28 +331,24 @@ impl IssueService {
     /// Lists issues with offset-based pagination
     ///
     /// Returns (issues, total_count) where total_count is the total matching issues.
+    /// With `dist`, only issues with events of that distribution are listed,
+    /// with `priority` only those of that priority.
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_offset(
         pool: &PgPool,
         project_id: i32,
         sort: IssueSort,
         order: SortOrder,
         filter: IssueFilter,
+        dist: Option<&str>,
+        handled: Option<bool>,
+        priority: Option<IssuePriority>,
         page: i64,
         per_page: i64,
     ) -> AppResult<(Vec<Issue>, i64)> {
         let offset = (page - 1) * per_page;
 
-        // Build WHERE clause based on filter
-        let where_clause = match filter {
-            IssueFilter::Open => {
-                "project_id = $1 AND NOT is_deleted AND NOT is_resolved AND NOT is_muted"
-            }
-            IssueFilter::Resolved => "project_id = $1 AND NOT is_deleted AND is_resolved",
-            IssueFilter::Muted => {
-                "project_id = $1 AND NOT is_deleted AND is_muted AND NOT is_resolved"
-            }
-            IssueFilter::All => "project_id = $1 AND NOT is_deleted",
-        };
+        let where_clause = format!("project_id = $1 AND {}", filter_clause(filter));
 
         // Build ORDER BY clause
         let order_clause = match (sort, order) {
@@ -340,22 +356,38 @@ impl IssueService {
             (IssueSort::DigestOrder, SortOrder::Asc) => "digest_order ASC",
             (IssueSort::LastSeen, SortOrder::Desc) => "last_seen DESC, id DESC",
             (IssueSort::LastSeen, SortOrder::Asc) => "last_seen ASC, id ASC",
+            // Most recently seen first among issues of the same priority
+            (IssueSort::Priority, SortOrder::Desc) => {
+                "CASE priority WHEN 'high' THEN 3 WHEN 'medium' THEN 2 ELSE 1 END DESC, last_seen DESC, id DESC"
+            }
+            (IssueSort::Priority, SortOrder::Asc) => {
+                "CASE priority WHEN 'high' THEN 3 WHEN 'medium' THEN 2 ELSE 1 END ASC, last_seen DESC, id DESC"
+            }
         };
 
         // Get total count
-        let count_query = format!("SELECT COUNT(*) FROM issues WHERE {}", where_clause);
+        let count_query = format!(
+            "SELECT COUNT(*) FROM issues WHERE {} AND {} AND {} AND {}",
+            where_clause, DIST_CLAUSE, HANDLED_CLAUSE, PRIORITY_CLAUSE
+        );
         let total_count: (i64,) = sqlx::query_as(&count_query)
             .bind(project_id)
+            .bind(dist)
+            .bind(handled)
+            .bind(priority)
             .fetch_one(pool)
             .await?;
 
         // Get paginated results
         let select_query = format!(
-            "SELECT * FROM issues WHERE {} ORDER BY {} LIMIT $2 OFFSET $3",
-            where_clause, order_clause
+            "SELECT * FROM issues WHERE {} AND {} AND {} AND {} ORDER BY {} LIMIT $5 OFFSET $6",
+            where_clause, DIST_CLAUSE, HANDLED_CLAUSE, PRIORITY_CLAUSE, order_clause
         );
         let issues = sqlx::query_as::<_, Issue>(&select_query)
             .bind(project_id)
+            .bind(dist)
+            .bind(handled)
+            .bind(priority)
             .bind(per_page)
             .bind(offset)
             .fetch_all(pool)
@@ -364,10 +396,85 @@ impl IssueService {
         Ok((issues, total_count.0))
     }
 
+    /// Lists the most recently seen open issues across projects
+    ///
+    /// With `member_id`, only issues of the projects that user is a member
+    /// of are listed.
+    pub async fn recent_open(
+        pool: &PgPool,
+        member_id: Option<i32>,
+        limit: i64,
+    ) -> AppResult<Vec<Issue>> {
+        let query = format!(
+            r#"
+            SELECT * FROM issues
+            WHERE {}
+              AND ($1::int IS NULL OR EXISTS (
+                  SELECT 1 FROM project_members m
+                  WHERE m.project_id = issues.project_id AND m.user_id = $1
+              ))
+            ORDER BY last_seen DESC, id DESC
+            LIMIT $2
+            "#,
+            filter_clause(IssueFilter::Open)
+        );
+        let issues = sqlx::query_as::<_, Issue>(&query)
+            .bind(member_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(issues)
+    }
+
+    /// Counts issues and their events since `since`, grouped by type or
+    /// transaction
+    ///
+    /// Returns the [`MAX_AGGREGATE_GROUPS`] groups with the most events.
+    /// `filter` and `dist` select issues as in [`Self::list_offset`]; only
+    /// issues with events in the period are counted.
+    pub async fn aggregate(
+        pool: &PgPool,
+        project_id: i32,
+        group_by: IssueGroupBy,
+        filter: IssueFilter,
+        dist: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> AppResult<Vec<IssueAggregate>> {
+        let query = format!(
+            r#"
+            SELECT issues.{group_by} AS value,
+                   COUNT(DISTINCT issues.id) AS issue_count,
+                   COUNT(*) AS event_count,
+                   (ARRAY_AGG(issues.id ORDER BY issues.last_seen DESC, issues.id))[1]
+                       AS latest_issue_id
+            FROM events e
+            JOIN issues ON issues.id = e.issue_id
+            WHERE e.project_id = $1 AND e.digested_at >= $3
+              AND {filter} AND {dist}
+            GROUP BY issues.{group_by}
+            ORDER BY event_count DESC, value
+            LIMIT $4
+            "#,
+            group_by = group_by.as_str(),
+            filter = filter_clause(filter),
+            dist = DIST_CLAUSE,
+        );
+        let groups = sqlx::query_as::<_, IssueAggregate>(&query)
+            .bind(project_id)
+            .bind(dist)
+            .bind(since)
+            .bind(MAX_AGGREGATE_GROUPS)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(groups)
+    }
+
     /// Gets an issue by ID
     pub async fn get_by_id(pool: &PgPool, id: Uuid) -> AppResult<Issue> {
         let issue =
-            sqlx::query_as::<_, Issue>("SELECT * FROM issues WHERE id = $1 AND NOT is_deleted")
+            sqlx::query_as::<_, Issue>("SELECT * FROM issues WHERE id = $1 AND deleted_at IS NULL")
                 .bind(id)
                 .fetch_optional(pool)
                 .await?
@@ -385,25 +492,22 @@ impl IssueService {
         level: Option<&str>,
         platform: Option<&str>,
     ) -> AppResult<Issue> {
-        // Get the next digest_order for this project
-        let max_order: Option<i32> =
-            sqlx::query_scalar("SELECT MAX(digest_order) FROM issues WHERE project_id = $1")
-                .bind(project_id)
-                .fetch_one(pool)
-                .await?;
+        let mut tx = pool.begin().await?;
 
-        let digest_order = max_order.unwrap_or(0) + 1;
+        let digest_order = ProjectSequenceService::reserve_issue_orders(&mut tx, project_id, 1)
+            .await?
+            .start;
 
         let issue = sqlx::query_as::<_, Issue>(
             r#"
             INSERT INTO issues (
                 project_id, digest_order, first_seen, last_seen,
-                digested_event_count, stored_event_count,
+                digested_event_count, stored_event_count, last_event_order,
                 calculated_type, calculated_value, transaction,
                 last_frame_filename, last_frame_module, last_frame_function,
-                level, platform
+                level, platform, exception_chain_length, is_unhandled, priority
             )
-            VALUES ($1, $2, $3, $3, 1, 1, $4, $5, $6, $7, $8, $9, $10, $11)
+            VALUES ($1, $2, $3, $3, 1, 1, 1, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING *
             "#,
         )
@@ -418,9 +522,13 @@ impl IssueService {
         .bind(&denormalized.last_frame_function)
         .bind(level)
         .bind(platform)
-        .fetch_one(pool)
+        .bind(denormalized.exception_chain_length)
+        .bind(denormalized.is_unhandled)
+        .bind(IssuePriority::from_event(level, denormalized.is_unhandled))
+        .fetch_one(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         Ok(issue)
     }
 
@@ -435,7 +543,8 @@ impl IssueService {
             UPDATE issues
             SET last_seen = $2,
                 digested_event_count = digested_event_count + 1,
-                stored_event_count = stored_event_count + 1
+                stored_event_count = stored_event_count + 1,
+                last_event_order = last_event_order + 1
             WHERE id = $1
             RETURNING *
             "#,
@@ -448,17 +557,146 @@ impl IssueService {
         Ok(issue)
     }
 
-    /// Marks an issue as resolved
-    pub async fn resolve(pool: &PgPool, id: Uuid) -> AppResult<Issue> {
+    /// Copies the culprit fields of each issue's latest event onto the issue
+    ///
+    /// Issues digested before the fields followed new events keep those of
+    /// their first event; this brings them up to date. Returns the number of
+    /// issues changed.
+    pub async fn backfill_culprits(pool: &PgPool) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE issues i
+            SET transaction = e.transaction,
+                last_frame_filename = e.last_frame_filename,
+                last_frame_module = e.last_frame_module,
+                last_frame_function = e.last_frame_function
+            FROM (
+                SELECT DISTINCT ON (issue_id)
+                    issue_id, transaction,
+                    last_frame_filename, last_frame_module, last_frame_function
+                FROM events
+                ORDER BY issue_id, ingested_at DESC, digest_order DESC
+            ) e
+            WHERE e.issue_id = i.id
+              AND (i.transaction, i.last_frame_filename, i.last_frame_module, i.last_frame_function)
+                  IS DISTINCT FROM
+                  (e.transaction, e.last_frame_filename, e.last_frame_module, e.last_frame_function)
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Cuts the stored value, transaction and frame fields of issues and
+    /// events longer than `max_length` characters, as the digest does for
+    /// new events
+    ///
+    /// Values are cut here rather than in SQL so character boundaries are
+    /// the same as the digest's whatever the database encoding. Returns the
+    /// number of issues and events changed.
+    pub async fn truncate_values(pool: &PgPool, max_length: usize) -> AppResult<(u64, u64)> {
+        const BATCH_SIZE: i64 = 1000;
+
+        let mut changed = [0; 2];
+        for (table, count) in ["issues", "events"].into_iter().zip(&mut changed) {
+            let mut after = Uuid::nil();
+            loop {
+                // Bytes are never fewer than characters, so this finds every
+                // row that may be too long
+                let rows: Vec<(Uuid, String, String, String, String, String)> =
+                    sqlx::query_as(&format!(
+                        r#"
+                        SELECT id, calculated_value, transaction,
+                            last_frame_filename, last_frame_module, last_frame_function
+                        FROM {table}
+                        WHERE id > $1
+                          AND GREATEST(
+                              octet_length(calculated_value), octet_length(transaction),
+                              octet_length(last_frame_filename), octet_length(last_frame_module),
+                              octet_length(last_frame_function)
+                          ) > $2
+                        ORDER BY id
+                        LIMIT $3
+                        "#
+                    ))
+                    .bind(after)
+                    .bind(max_length as i32)
+                    .bind(BATCH_SIZE)
+                    .fetch_all(pool)
+                    .await?;
+
+                for (id, value, transaction, filename, module, function) in &rows {
+                    let fields = [value, transaction, filename, module, function]
+                        .map(|field| truncate_with_ellipsis(field, max_length));
+                    if fields
+                        .iter()
+                        .zip([value, transaction, filename, module, function])
+                        .all(|(capped, field)| capped == field)
+                    {
+                        continue;
+                    }
+
+                    sqlx::query(&format!(
+                        r#"
+                        UPDATE {table}
+                        SET calculated_value = $2, transaction = $3,
+                            last_frame_filename = $4, last_frame_module = $5,
+                            last_frame_function = $6
+                        WHERE id = $1
+                        "#
+                    ))
+                    .bind(id)
+                    .bind(&fields[0])
+                    .bind(&fields[1])
+                    .bind(&fields[2])
+                    .bind(&fields[3])
+                    .bind(&fields[4])
+                    .execute(pool)
+                    .await?;
+                    *count += 1;
+                }
+
+                match rows.last() {
+                    Some(row) if rows.len() as i64 == BATCH_SIZE => after = row.0,
+                    _ => break,
+                }
+            }
+        }
+
+        Ok((changed[0], changed[1]))
+    }
+
+    /// Marks an issue as resolved, recording who resolved it and when
+    pub async fn resolve(pool: &PgPool, id: Uuid, actor: Actor) -> AppResult<Issue> {
+        Self::resolve_with(pool, id, actor, &ResolveIssue::default()).await
+    }
+
+    /// Marks an issue as resolved, also recording the commit or pull
+    /// request that fixed it, if given
+    pub async fn resolve_with(
+        pool: &PgPool,
+        id: Uuid,
+        actor: Actor,
+        resolution: &ResolveIssue,
+    ) -> AppResult<Issue> {
+        let resolution = resolution.validate()?;
+
         let issue = sqlx::query_as::<_, Issue>(
             r#"
             UPDATE issues
-            SET is_resolved = TRUE, is_muted = FALSE
-            WHERE id = $1 AND NOT is_deleted
+            SET is_resolved = TRUE, is_muted = FALSE,
+                resolved_at = NOW(), resolved_by = $2, auto_resolved = FALSE,
+                resolved_commit = $3, resolved_pr_url = $4
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
         )
         .bind(id)
+        .bind(actor.user_id())
+        .bind(resolution.commit)
+        .bind(resolution.pr_url)
         .fetch_one(pool)
         .await
         .map_err(|_| AppError::NotFound(format!("Issue {} not found", id)))?;
@@ -471,8 +709,9 @@ impl IssueService {
         let issue = sqlx::query_as::<_, Issue>(
             r#"
             UPDATE issues
-            SET is_resolved = FALSE
-            WHERE id = $1 AND NOT is_deleted
+            SET is_resolved = FALSE, resolved_at = NULL, resolved_by = NULL,
+                auto_resolved = FALSE, resolved_commit = NULL, resolved_pr_url = NULL
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
         )
@@ -490,7 +729,7 @@ impl IssueService {
             r#"
             UPDATE issues
             SET is_muted = TRUE
-            WHERE id = $1 AND NOT is_deleted AND NOT is_resolved
+            WHERE id = $1 AND deleted_at IS NULL AND NOT is_resolved
             RETURNING *
             "#,
         )
@@ -508,7 +747,7 @@ impl IssueService {
             r#"
             UPDATE issues
             SET is_muted = FALSE
-            WHERE id = $1 AND NOT is_deleted
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
         )
@@ -520,17 +759,720 @@ impl IssueService {
         Ok(issue)
     }
 
-    /// Deletes an issue (soft delete)
+    /// Sets the share of the issue's new events that are stored
+    ///
+    /// The rate must be above 0 and at most 1; 1 stores every event again.
+    pub async fn set_sample_rate(pool: &PgPool, id: Uuid, sample_rate: f64) -> AppResult<Issue> {
+        if !(sample_rate > 0.0 && sample_rate <= 1.0) {
+            return Err(AppError::invalid_field(
+                "sample_rate",
+                codes::VALIDATION,
+                "sample_rate must be above 0 and at most 1",
+            ));
+        }
+        let sample_rate = (sample_rate < 1.0).then_some(sample_rate);
+
+        let issue = sqlx::query_as::<_, Issue>(
+            r#"
+            UPDATE issues
+            SET sample_rate = $2
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(sample_rate)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Issue {} not found", id)))?;
+
+        Ok(issue)
+    }
+
+    /// Sets an issue's priority by hand, recording the change
+    ///
+    /// The priority then stays as set: spikes no longer raise it.
+    pub async fn set_priority(
+        pool: &PgPool,
+        id: Uuid,
+        priority: IssuePriority,
+        actor: Actor,
+    ) -> AppResult<Issue> {
+        let mut tx = pool.begin().await?;
+
+        let old: IssuePriority = sqlx::query_scalar(
+            "SELECT priority FROM issues WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Issue {} not found", id)))?;
+
+        let issue = sqlx::query_as::<_, Issue>(
+            r#"
+            UPDATE issues
+            SET priority = $2, priority_is_manual = TRUE
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(priority)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if old != priority {
+            record_priority_change(&mut tx, id, old, priority, true, actor).await?;
+        }
+
+        tx.commit().await?;
+        Ok(issue)
+    }
+
+    /// Window events are counted in towards a priority spike: the clock hour,
+    /// as for the hourly rate limits, with `events` as its limit
+    pub fn priority_spike_window(events: i64) -> RateWindow {
+        RateWindow::new(chrono::Duration::hours(1), events)
+    }
+
+    /// Raises an issue's priority one level if it reached the limit of the
+    /// spike window its events are counted in
+    ///
+    /// The events are those the issue's `digested_event_count` gained since
+    /// the window started or the priority last changed, so each level takes
+    /// a spike of its own. Priorities set by a user are left alone. Returns
+    /// the issue if its priority was raised.
+    pub async fn raise_priority_on_spike(
+        pool: &PgPool,
+        issue: &Issue,
+        window: &RateWindow,
+    ) -> AppResult<Option<Issue>> {
+        if window.limit == 0 || issue.priority_is_manual {
+            return Ok(None);
+        }
+        let Some(raised) = issue.priority.raised() else {
+            return Ok(None);
+        };
+
+        let recent = (issue.digested_event_count - issue.spike_base_event_count) as i64;
+        if recent < window.limit {
+            return Ok(None);
+        }
+
+        let mut tx = pool.begin().await?;
+
+        // Unless a user or another worker changed it meanwhile
+        let raised_issue = sqlx::query_as::<_, Issue>(
+            r#"
+            UPDATE issues
+            SET priority = $3,
+                spike_base_event_count = digested_event_count
+            WHERE id = $1 AND priority = $2 AND NOT priority_is_manual
+            RETURNING *
+            "#,
+        )
+        .bind(issue.id)
+        .bind(issue.priority)
+        .bind(raised)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if raised_issue.is_some() {
+            record_priority_change(
+                &mut tx,
+                issue.id,
+                issue.priority,
+                raised,
+                false,
+                Actor::System,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(raised_issue)
+    }
+
+    /// Moves an issue to the trash
+    ///
+    /// Its groupings are detached, so new events of the same kind open a
+    /// fresh issue. The events stay until the retention job purges the issue.
     pub async fn delete(pool: &PgPool, id: Uuid) -> AppResult<()> {
-        let result = sqlx::query("UPDATE issues SET is_deleted = TRUE WHERE id = $1")
+        let mut tx = pool.begin().await?;
+
+        // Same lock as the digest worker, which updates the issue of the
+        // grouping it matches; taken before touching the issue row
+        let project_id: i32 = sqlx::query_scalar(
+            "SELECT project_id FROM issues WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Issue {} not found", id)))?;
+        lock_project(&mut tx, project_id).await?;
+
+        let result = sqlx::query(
+            "UPDATE issues SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Issue {} not found", id)));
+        }
+
+        sqlx::query("UPDATE groupings SET detached_at = NOW() WHERE issue_id = $1")
             .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Lists a project's deleted issues that can still be restored, most
+    /// recently deleted first
+    ///
+    /// Returns (issues, total_count).
+    pub async fn list_trash(
+        pool: &PgPool,
+        project_id: i32,
+        trash_days: i32,
+        page: i64,
+        per_page: i64,
+    ) -> AppResult<(Vec<Issue>, i64)> {
+        let offset = (page - 1) * per_page;
+
+        let total_count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM issues
+            WHERE project_id = $1 AND deleted_at > NOW() - make_interval(days => $2)
+            "#,
+        )
+        .bind(project_id)
+        .bind(trash_days)
+        .fetch_one(pool)
+        .await?;
+
+        let issues = sqlx::query_as::<_, Issue>(
+            r#"
+            SELECT * FROM issues
+            WHERE project_id = $1 AND deleted_at > NOW() - make_interval(days => $2)
+            ORDER BY deleted_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(project_id)
+        .bind(trash_days)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok((issues, total_count))
+    }
+
+    /// Gets an issue from the trash, if it was deleted less than `trash_days` ago
+    pub async fn get_deleted(pool: &PgPool, id: Uuid, trash_days: i32) -> AppResult<Issue> {
+        let issue = sqlx::query_as::<_, Issue>(
+            r#"
+            SELECT * FROM issues
+            WHERE id = $1 AND deleted_at > NOW() - make_interval(days => $2)
+            "#,
+        )
+        .bind(id)
+        .bind(trash_days)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Deleted issue {} not found", id)))?;
+
+        Ok(issue)
+    }
+
+    /// Takes an issue out of the trash if it was deleted less than
+    /// `trash_days` ago
+    ///
+    /// Its groupings are attached again, except for keys a newer issue has
+    /// taken over since; events of those keep going to the newer issue.
+    pub async fn restore(pool: &PgPool, id: Uuid, trash_days: i32) -> AppResult<Issue> {
+        let mut tx = pool.begin().await?;
+
+        let project_id: i32 = sqlx::query_scalar(
+            r#"
+            SELECT project_id FROM issues
+            WHERE id = $1 AND deleted_at > NOW() - make_interval(days => $2)
+            "#,
+        )
+        .bind(id)
+        .bind(trash_days)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Deleted issue {} not found", id)))?;
+        lock_project(&mut tx, project_id).await?;
+
+        let issue = sqlx::query_as::<_, Issue>(
+            "UPDATE issues SET deleted_at = NULL WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE groupings g
+            SET detached_at = NULL
+            WHERE g.issue_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM groupings a
+                  WHERE a.project_id = g.project_id
+                    AND a.grouping_key_hash = g.grouping_key_hash
+                    AND a.detached_at IS NULL
+              )
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(issue)
+    }
+
+    /// Recomputes the event counters of a project's issues from their events
+    ///
+    /// `stored_event_count` becomes the number of events; `digested_event_count`
+    /// the highest digest order among them, as events are numbered one by
+    /// one as they're digested. The digested count is only ever raised:
+    /// events sampled out or deleted after the last stored one were digested
+    /// too, and leave no trace to count. `last_event_id` is pointed back at the
+    /// latest event left. Takes the digest lock of the project until `tx`
+    /// ends. Returns the issues whose counters changed, so a second run
+    /// returns none.
+    pub async fn recount(
+        tx: &mut Transaction<'_, Postgres>,
+        project_id: i32,
+    ) -> AppResult<Vec<IssueRecount>> {
+        lock_project(tx, project_id).await?;
+
+        let rows = sqlx::query_as::<_, (Uuid, i32, i32, i32, i32)>(
+            r#"
+            WITH counts AS (
+                SELECT i.id,
+                       i.stored_event_count AS old_stored,
+                       i.digested_event_count AS old_digested,
+                       COUNT(e.id)::int AS stored,
+                       GREATEST(
+                           i.digested_event_count,
+                           COUNT(e.id),
+                           COALESCE(MAX(e.digest_order), 0)
+                       )::int AS digested
+                FROM issues i
+                LEFT JOIN events e ON e.issue_id = i.id
+                WHERE i.project_id = $1
+                GROUP BY i.id
+            )
+            UPDATE issues i
+            SET stored_event_count = c.stored,
+                digested_event_count = c.digested
+            FROM counts c
+            WHERE i.id = c.id
+              AND (c.old_stored, c.old_digested) IS DISTINCT FROM (c.stored, c.digested)
+            RETURNING i.id, c.old_stored, c.old_digested, c.stored, c.digested
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            WITH latest AS (
+                SELECT i.id,
+                       (SELECT e.id FROM events e
+                        WHERE e.issue_id = i.id
+                        ORDER BY e.digest_order DESC
+                        LIMIT 1) AS last_event_id
+                FROM issues i
+                WHERE i.project_id = $1
+            )
+            UPDATE issues i
+            SET last_event_id = l.last_event_id
+            FROM latest l
+            WHERE i.id = l.id AND i.last_event_id IS DISTINCT FROM l.last_event_id
+            "#,
+        )
+        .bind(project_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(issue_id, old_stored, old_digested, stored, digested)| IssueRecount {
+                    issue_id,
+                    before: EventCounts {
+                        stored_event_count: old_stored,
+                        digested_event_count: old_digested,
+                    },
+                    after: EventCounts {
+                        stored_event_count: stored,
+                        digested_event_count: digested,
+                    },
+                },
+            )
+            .collect())
+    }
+
+    /// Permanently deletes issues that have been in the trash for more than
+    /// `trash_days`, along with their events
+    ///
+    /// Returns the number of issues removed.
+    pub async fn purge_deleted(pool: &PgPool, trash_days: i32) -> AppResult<u64> {
+        // Deleting the events of large issues can outlast the statement timeout
+        let mut tx = pool.begin().await?;
+        db::disable_statement_timeout(&mut tx).await?;
+        let result =
+            sqlx::query("DELETE FROM issues WHERE deleted_at <= NOW() - make_interval(days => $1)")
+                .bind(trash_days)
+                .execute(&mut *tx)
+                .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Resolves open issues of projects with `auto_resolve_days` that have
+    /// had no events for that many days
+    ///
+    /// Muted issues are left alone, their silence is expected. Issues are
+    /// updated `batch_size` at a time, so locks are held briefly. Returns the
+    /// number of issues resolved.
+    pub async fn auto_resolve(pool: &PgPool, batch_size: i64) -> AppResult<u64> {
+        let mut resolved = 0;
+        loop {
+            // last_seen is checked again by the UPDATE, in case an event came
+            // in while the batch was being picked
+            // Each resolved issue gets an `auto_resolved` activity entry, so
+            // users can tell why it closed
+            let result = sqlx::query(
+                r#"
+                WITH stale AS (
+                    SELECT i.id
+                    FROM issues i
+                    JOIN projects p ON p.id = i.project_id
+                    WHERE p.auto_resolve_days IS NOT NULL
+                      AND i.deleted_at IS NULL AND NOT i.is_resolved AND NOT i.is_muted
+                      AND i.last_seen < NOW() - make_interval(days => p.auto_resolve_days)
+                    LIMIT $1
+                    FOR UPDATE OF i SKIP LOCKED
+                ), resolved AS (
+                    UPDATE issues i
+                    SET is_resolved = TRUE, resolved_at = NOW(), resolved_by = NULL,
+                        auto_resolved = TRUE
+                    FROM stale, projects p
+                    WHERE i.id = stale.id AND p.id = i.project_id
+                      AND NOT i.is_resolved AND NOT i.is_muted
+                      AND i.last_seen < NOW() - make_interval(days => p.auto_resolve_days)
+                    RETURNING i.id
+                )
+                INSERT INTO issue_activity (issue_id, kind)
+                SELECT id, 'auto_resolved' FROM resolved
+                "#,
+            )
+            .bind(batch_size)
             .execute(pool)
             .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound(format!("Issue {} not found", id)));
+            resolved += result.rows_affected();
+            if result.rows_affected() < batch_size as u64 {
+                return Ok(resolved);
+            }
         }
+    }
+
+    /// Records that a user opened the issue, updating `seen_at` on later views
+    pub async fn mark_seen(pool: &PgPool, issue_id: Uuid, user_id: i32) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO issue_seen (user_id, issue_id, seen_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (user_id, issue_id) DO UPDATE SET seen_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(issue_id)
+        .execute(pool)
+        .await?;
 
         Ok(())
     }
+
+    /// Records a view without delaying the caller; failures are only logged
+    pub fn mark_seen_in_background(pool: &PgPool, issue_id: Uuid, user_id: i32) {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::mark_seen(&pool, issue_id, user_id).await {
+                log::warn!("Failed to mark issue {} as seen: {}", issue_id, e);
+            }
+        });
+    }
+
+    /// Of the given issues, the ones the user has opened
+    pub async fn seen_issue_ids(
+        pool: &PgPool,
+        user_id: i32,
+        issue_ids: &[Uuid],
+    ) -> AppResult<HashSet<Uuid>> {
+        let seen: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT issue_id FROM issue_seen WHERE user_id = $1 AND issue_id = ANY($2)",
+        )
+        .bind(user_id)
+        .bind(issue_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(seen.into_iter().collect())
+    }
+
+    /// Events per hour of each given issue over the last [`SPARKLINE_HOURS`]
+    ///
+    /// Hours are UTC, oldest first, and every issue gets all of them, with
+    /// zero for hours without events. One query covers all the issues.
+    pub async fn sparklines(
+        pool: &PgPool,
+        issue_ids: &[Uuid],
+    ) -> AppResult<HashMap<Uuid, Vec<HourlyEventCount>>> {
+        let rows = sqlx::query_as::<_, (Uuid, DateTime<Utc>, i64)>(
+            r#"
+            WITH bounds AS (
+                SELECT date_trunc('hour', NOW() AT TIME ZONE 'UTC') AS last_hour
+            ),
+            hours AS (
+                SELECT generate_series(
+                    bounds.last_hour - ($2::int - 1) * INTERVAL '1 hour',
+                    bounds.last_hour,
+                    INTERVAL '1 hour'
+                ) AS hour
+                FROM bounds
+            ),
+            counts AS (
+                SELECT e.issue_id, date_trunc('hour', e.digested_at AT TIME ZONE 'UTC') AS hour,
+                       COUNT(*) AS events
+                FROM events e, bounds
+                WHERE e.issue_id = ANY($1)
+                  AND e.digested_at >= (bounds.last_hour - ($2::int - 1) * INTERVAL '1 hour')
+                                       AT TIME ZONE 'UTC'
+                GROUP BY e.issue_id, 2
+            )
+            SELECT ids.id, hours.hour AT TIME ZONE 'UTC', COALESCE(counts.events, 0)
+            FROM UNNEST($1::uuid[]) AS ids(id)
+            CROSS JOIN hours
+            LEFT JOIN counts ON counts.issue_id = ids.id AND counts.hour = hours.hour
+            ORDER BY ids.id, hours.hour
+            "#,
+        )
+        .bind(issue_ids)
+        .bind(SPARKLINE_HOURS)
+        .fetch_all(pool)
+        .await?;
+
+        let mut sparklines: HashMap<Uuid, Vec<HourlyEventCount>> = HashMap::new();
+        for (issue_id, hour, events) in rows {
+            sparklines
+                .entry(issue_id)
+                .or_default()
+                .push(HourlyEventCount { hour, events });
+        }
+        Ok(sparklines)
+    }
+
+    /// Users who have opened the issue, most recent first
+    pub async fn seen_by(pool: &PgPool, issue_id: Uuid) -> AppResult<Vec<IssueSeenBy>> {
+        let seen_by = sqlx::query_as::<_, IssueSeenBy>(
+            r#"
+            SELECT s.user_id, u.email, s.seen_at
+            FROM issue_seen s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.issue_id = $1
+            ORDER BY s.seen_at DESC
+            "#,
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(seen_by)
+    }
+
+    /// When a new event reopened the resolved issue, most recent first
+    pub async fn regressed_at(pool: &PgPool, issue_id: Uuid) -> AppResult<Vec<DateTime<Utc>>> {
+        let regressed_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"
+            SELECT regressed_at
+            FROM issue_regressions
+            WHERE issue_id = $1
+            ORDER BY regressed_at DESC
+            "#,
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(regressed_at)
+    }
+
+    /// Priority changes of the issue, most recent first
+    pub async fn priority_history(
+        pool: &PgPool,
+        issue_id: Uuid,
+    ) -> AppResult<Vec<IssuePriorityChange>> {
+        let changes = sqlx::query_as::<_, IssuePriorityChange>(
+            r#"
+            SELECT old_priority, new_priority, is_manual, changed_by, changed_at
+            FROM issue_priority_changes
+            WHERE issue_id = $1
+            ORDER BY changed_at DESC, id DESC
+            "#,
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(changes)
+    }
+
+    /// Activity of the issue, most recent first
+    pub async fn activity(pool: &PgPool, issue_id: Uuid) -> AppResult<Vec<IssueActivity>> {
+        let activity = sqlx::query_as::<_, IssueActivity>(
+            r#"
+            SELECT kind, created_at
+            FROM issue_activity
+            WHERE issue_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(activity)
+    }
+
+    /// Grouping keys of the issue, oldest first
+    ///
+    /// Includes keys detached while the issue was in the trash.
+    pub async fn groupings(pool: &PgPool, issue_id: Uuid) -> AppResult<Vec<Grouping>> {
+        let groupings = sqlx::query_as::<_, Grouping>(
+            r#"
+            SELECT id, project_id, issue_id, grouping_key, grouping_key_hash, created_at
+            FROM groupings
+            WHERE issue_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(groupings)
+    }
+
+    /// Builds a self-contained bundle of the issue and its `events` most
+    /// recently received events, for download
+    pub async fn bundle(
+        pool: &PgPool,
+        project: &Project,
+        issue_id: Uuid,
+        events: i64,
+        max_title_length: usize,
+    ) -> AppResult<IssueBundle> {
+        let issue = Self::get_by_id(pool, issue_id).await?;
+        if issue.project_id != project.id {
+            return Err(AppError::NotFound(format!("Issue {} not found", issue_id)));
+        }
+
+        let groupings = Self::groupings(pool, issue_id).await?;
+        let regressed_at = Self::regressed_at(pool, issue_id).await?;
+        let (events, _) = EventService::list_paginated(
+            pool,
+            issue_id,
+            EventSort::Received,
+            SortOrder::Desc,
+            None,
+            None,
+            events,
+        )
+        .await?;
+
+        let mut response = issue.to_response(&project.slug, max_title_length);
+        response.regressed_at = Some(regressed_at);
+        response.groupings = Some(groupings.iter().map(|g| g.to_response()).collect());
+
+        Ok(IssueBundle {
+            exported_at: Utc::now(),
+            issue: response,
+            events: events.iter().map(|e| e.to_detail_response()).collect(),
+        })
+    }
+}
+
+/// Conditions on the `issues` table selecting the issues of a filter
+fn filter_clause(filter: IssueFilter) -> &'static str {
+    match filter {
+        IssueFilter::Open => "deleted_at IS NULL AND NOT is_resolved AND NOT is_muted",
+        IssueFilter::Resolved => "deleted_at IS NULL AND is_resolved",
+        IssueFilter::Muted => "deleted_at IS NULL AND is_muted AND NOT is_resolved",
+        IssueFilter::All => "deleted_at IS NULL",
+    }
+}
+
+/// Issues seen with the dist bound as `$2`, as recorded with their contexts
+const DIST_CLAUSE: &str = "($2::text IS NULL OR EXISTS (
+    SELECT 1 FROM issue_contexts c
+    WHERE c.issue_id = issues.id AND c.dimension = 'dist' AND c.value = $2
+))";
+
+/// Issues with `handled` bound as `$3`: with only handled events if true,
+/// with an unhandled one if false
+const HANDLED_CLAUSE: &str = "($3::bool IS NULL OR is_unhandled <> $3)";
+
+/// Issues of the priority bound as `$4`
+const PRIORITY_CLAUSE: &str = "($4::varchar IS NULL OR priority = $4)";
+
+/// Records a change of an issue's priority
+async fn record_priority_change(
+    tx: &mut Transaction<'_, Postgres>,
+    issue_id: Uuid,
+    old: IssuePriority,
+    new: IssuePriority,
+    is_manual: bool,
+    actor: Actor,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO issue_priority_changes
+            (issue_id, old_priority, new_priority, is_manual, changed_by)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(issue_id)
+    .bind(old)
+    .bind(new)
+    .bind(is_manual)
+    .bind(actor.user_id())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Takes the per-project lock the digest worker holds while it matches
+/// groupings, until the transaction ends
+async fn lock_project(tx: &mut Transaction<'_, Postgres>, project_id: i32) -> AppResult<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(project_id as i64)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
 }
@@ -0,0 +1,91 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::digest::ContextDimension;
+use crate::error::AppResult;
+use crate::models::{IssueContextValue, IssueContextsResponse};
+
+/// Max values returned per dimension
+pub const MAX_CONTEXT_VALUES: i64 = 10;
+
+pub struct IssueContextService;
+
+#[derive(sqlx::FromRow)]
+struct ContextRow {
+    dimension: String,
+    value: String,
+    count: i32,
+    total: i64,
+    percentage: f64,
+}
+
+impl IssueContextService {
+    /// Counts an event of the issue towards each of its context values
+    pub async fn record(
+        pool: &PgPool,
+        issue_id: Uuid,
+        contexts: &[(ContextDimension, String)],
+    ) -> AppResult<()> {
+        for (dimension, value) in contexts {
+            sqlx::query(
+                r#"
+                INSERT INTO issue_contexts (issue_id, dimension, value, count)
+                VALUES ($1, $2, $3, 1)
+                ON CONFLICT (issue_id, dimension, value)
+                DO UPDATE SET count = issue_contexts.count + 1
+                "#,
+            )
+            .bind(issue_id)
+            .bind(dimension.as_str())
+            .bind(value)
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Top values per dimension, with their share of the dimension's events
+    pub async fn breakdown(pool: &PgPool, issue_id: Uuid) -> AppResult<IssueContextsResponse> {
+        let rows: Vec<ContextRow> = sqlx::query_as(
+            r#"
+            SELECT dimension, value, count, total, percentage
+            FROM (
+                SELECT dimension, value, count,
+                       SUM(count) OVER (PARTITION BY dimension)::BIGINT AS total,
+                       ROUND(count * 100.0 / SUM(count) OVER (PARTITION BY dimension), 1)::FLOAT8
+                           AS percentage,
+                       ROW_NUMBER() OVER (PARTITION BY dimension ORDER BY count DESC, value)
+                           AS rank
+                FROM issue_contexts
+                WHERE issue_id = $1
+            ) ranked
+            WHERE rank <= $2
+            ORDER BY dimension, rank
+            "#,
+        )
+        .bind(issue_id)
+        .bind(MAX_CONTEXT_VALUES)
+        .fetch_all(pool)
+        .await?;
+
+        let mut response = IssueContextsResponse::default();
+        for row in rows {
+            let breakdown = match row.dimension.as_str() {
+                "os" => &mut response.os,
+                "browser" => &mut response.browser,
+                "runtime" => &mut response.runtime,
+                "dist" => &mut response.dist,
+                _ => continue,
+            };
+            breakdown.total = row.total;
+            breakdown.values.push(IssueContextValue {
+                value: row.value,
+                count: row.count,
+                percentage: row.percentage,
+            });
+        }
+
+        Ok(response)
+    }
+}
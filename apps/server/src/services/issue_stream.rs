@@ -0,0 +1,94 @@
+//! Live feed of issue changes for the SSE endpoint.
+//!
+//! The digest worker publishes every issue it creates or updates to a
+//! broadcast channel; each open stream subscribes and forwards the updates of
+//! its project. Nothing is buffered for clients that aren't connected, and
+//! subscribers that fall too far behind skip the updates they missed.
+
+use std::sync::{Arc, LazyLock};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::models::Issue;
+
+/// Updates a slow subscriber can fall behind before it skips some
+const CHANNEL_CAPACITY: usize = 256;
+
+static GLOBAL: LazyLock<Arc<IssueStream>> = LazyLock::new(|| Arc::new(IssueStream::new()));
+
+/// What happened to the issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueChange {
+    Created,
+    Updated,
+}
+
+/// Compact description of an issue change, sent as the SSE `data`
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueStreamEvent {
+    #[serde(rename = "type")]
+    pub change: IssueChange,
+    pub project_id: i32,
+    pub issue_id: Uuid,
+    pub title: String,
+    pub level: Option<String>,
+    pub event_count: i32,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl IssueStreamEvent {
+    pub fn new(change: IssueChange, issue: &Issue, max_title_length: usize) -> Self {
+        Self {
+            change,
+            project_id: issue.project_id,
+            issue_id: issue.id,
+            title: issue.title(max_title_length),
+            level: issue.level.clone(),
+            event_count: issue.digested_event_count,
+            last_seen: issue.last_seen,
+        }
+    }
+}
+
+/// Broadcast channel of issue changes across all projects
+#[derive(Debug)]
+pub struct IssueStream {
+    sender: broadcast::Sender<IssueStreamEvent>,
+}
+
+impl Default for IssueStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IssueStream {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// The stream fed by the digest worker
+    pub fn global() -> Arc<IssueStream> {
+        GLOBAL.clone()
+    }
+
+    /// Sends a change to every subscriber; a no-op when nobody listens
+    pub fn publish(&self, event: IssueStreamEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Receives the changes published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<IssueStreamEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Number of open subscriptions
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::digest::EventUser;
+use crate::error::AppResult;
+use crate::models::IssueUser;
+
+/// Users read per query while exporting
+pub const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Header row of the affected users CSV
+pub const EXPORT_CSV_HEADER: &str =
+    "user_id,email,username,first_affected,last_affected,event_count\n";
+
+pub struct IssueUserService;
+
+impl IssueUserService {
+    /// Counts an event of the issue towards the user it was sent for
+    ///
+    /// The email and username follow the user's latest event, so a user who
+    /// changed them is exported with the current ones.
+    pub async fn record(
+        pool: &PgPool,
+        issue_id: Uuid,
+        user: &EventUser,
+        timestamp: DateTime<Utc>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO issue_users (
+                issue_id, user_key, user_id, email, username, first_seen, last_seen
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            ON CONFLICT (issue_id, user_key) DO UPDATE SET
+                user_id = COALESCE(EXCLUDED.user_id, issue_users.user_id),
+                email = CASE WHEN EXCLUDED.last_seen >= issue_users.last_seen
+                             THEN COALESCE(EXCLUDED.email, issue_users.email)
+                             ELSE COALESCE(issue_users.email, EXCLUDED.email) END,
+                username = CASE WHEN EXCLUDED.last_seen >= issue_users.last_seen
+                                THEN COALESCE(EXCLUDED.username, issue_users.username)
+                                ELSE COALESCE(issue_users.username, EXCLUDED.username) END,
+                first_seen = LEAST(issue_users.first_seen, EXCLUDED.first_seen),
+                last_seen = GREATEST(issue_users.last_seen, EXCLUDED.last_seen),
+                event_count = issue_users.event_count + 1
+            "#,
+        )
+        .bind(issue_id)
+        .bind(user.key())
+        .bind(&user.id)
+        .bind(&user.email)
+        .bind(&user.username)
+        .bind(timestamp)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Next batch of users of the issue, by when they were first affected
+    ///
+    /// `after` is the `(first_seen, user_key)` of the last user of the
+    /// previous batch; `since` leaves out users first affected before it.
+    pub async fn export_batch(
+        pool: &PgPool,
+        issue_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        after: Option<(DateTime<Utc>, String)>,
+    ) -> AppResult<Vec<IssueUser>> {
+        let (after_seen, after_key) = after.unzip();
+        let users = sqlx::query_as::<_, IssueUser>(
+            r#"
+            SELECT user_key, user_id, email, username, first_seen, last_seen, event_count
+            FROM issue_users
+            WHERE issue_id = $1
+              AND ($2::timestamptz IS NULL OR first_seen >= $2)
+              AND ($3::timestamptz IS NULL OR (first_seen, user_key) > ($3, $4))
+            ORDER BY first_seen, user_key
+            LIMIT $5
+            "#,
+        )
+        .bind(issue_id)
+        .bind(since)
+        .bind(after_seen)
+        .bind(after_key)
+        .bind(EXPORT_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+}
+
+/// Formats users as rows of the affected users CSV
+pub fn csv_rows(users: &[IssueUser]) -> String {
+    let mut rows = String::new();
+    for user in users {
+        let fields = [
+            csv_field(user.user_id.as_deref().unwrap_or_default()),
+            csv_field(user.email.as_deref().unwrap_or_default()),
+            csv_field(user.username.as_deref().unwrap_or_default()),
+            user.first_seen.to_rfc3339(),
+            user.last_seen.to_rfc3339(),
+            user.event_count.to_string(),
+        ];
+        rows.push_str(&fields.join(","));
+        rows.push('\n');
+    }
+    rows
+}
+
+/// Quotes a CSV field when it has a comma, quote or line break (RFC 4180)
+///
+/// Fields starting with `=`, `+`, `-` or `@` get a leading `'`, so
+/// spreadsheets don't run SDK-sent values as formulas.
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
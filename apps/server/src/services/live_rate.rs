@@ -0,0 +1,223 @@
+//! In-memory events-per-second rates of each project.
+//!
+//! The ingest handler counts every event it accepts and the digest worker
+//! every event it digests, in a sliding window of one-second buckets per
+//! project. Nothing is stored: the rates are those of this instance since it
+//! started, and each replica of a multi-instance deployment has its own.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+
+/// Seconds of history kept per project, one bucket each
+pub const LIVE_RATE_WINDOW_SECS: u64 = 60;
+
+/// Shorter window reported next to the full one
+pub const LIVE_RATE_SHORT_WINDOW_SECS: u64 = 10;
+
+static GLOBAL: LazyLock<Arc<LiveRates>> = LazyLock::new(|| Arc::new(LiveRates::new()));
+
+/// Name of this instance: the HOSTNAME (the container or pod name), or a
+/// random id when it isn't set
+static INSTANCE_ID: LazyLock<String> = LazyLock::new(|| {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..12].to_string())
+});
+
+/// Identifies the instance whose rates are reported
+pub fn instance_id() -> &'static str {
+    &INSTANCE_ID
+}
+
+/// Events per second over the short and the full window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RateWindows {
+    pub last_10s: f64,
+    pub last_60s: f64,
+}
+
+/// Live rates of a project, as returned by `GET /api/projects/{id}/stats/live`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectLiveRate {
+    pub project_id: i32,
+    /// Instance the rates were measured on; other replicas aren't included
+    pub instance_id: &'static str,
+    /// Events accepted by the ingest endpoints
+    pub accepted: RateWindows,
+    /// Events digested into issues
+    pub digested: RateWindows,
+}
+
+/// Event counts of the last [`LIVE_RATE_WINDOW_SECS`] seconds
+#[derive(Debug, Clone)]
+struct Window {
+    counts: [u64; LIVE_RATE_WINDOW_SECS as usize],
+    /// Second each bucket was last counted in; older buckets are stale
+    seconds: [u64; LIVE_RATE_WINDOW_SECS as usize],
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            counts: [0; LIVE_RATE_WINDOW_SECS as usize],
+            seconds: [0; LIVE_RATE_WINDOW_SECS as usize],
+        }
+    }
+}
+
+impl Window {
+    fn record(&mut self, second: u64) {
+        let bucket = (second % LIVE_RATE_WINDOW_SECS) as usize;
+        if self.seconds[bucket] != second {
+            self.seconds[bucket] = second;
+            self.counts[bucket] = 0;
+        }
+        self.counts[bucket] += 1;
+    }
+
+    /// Events per second over the `secs` seconds up to and including `now`
+    fn rate(&self, now: u64, secs: u64) -> f64 {
+        let total: u64 = self
+            .seconds
+            .iter()
+            .zip(&self.counts)
+            .filter(|(&second, _)| second <= now && now - second < secs)
+            .map(|(_, &count)| count)
+            .sum();
+        total as f64 / secs as f64
+    }
+
+    fn windows(&self, now: u64) -> RateWindows {
+        RateWindows {
+            last_10s: self.rate(now, LIVE_RATE_SHORT_WINDOW_SECS),
+            last_60s: self.rate(now, LIVE_RATE_WINDOW_SECS),
+        }
+    }
+
+    fn last_second(&self) -> u64 {
+        self.seconds.iter().copied().max().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ProjectWindows {
+    accepted: Window,
+    digested: Window,
+}
+
+/// Sliding-window event rates of every project with recent events
+#[derive(Debug)]
+pub struct LiveRates {
+    started: Instant,
+    projects: Mutex<HashMap<i32, ProjectWindows>>,
+}
+
+impl Default for LiveRates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveRates {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            projects: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The rates fed by the ingest handler and the digest worker
+    pub fn global() -> Arc<LiveRates> {
+        GLOBAL.clone()
+    }
+
+    /// Current second, counted from when the rates were created; starts at 1
+    /// so no bucket looks recent before it's used
+    fn now(&self) -> u64 {
+        self.started.elapsed().as_secs() + 1
+    }
+
+    /// Counts an event accepted by an ingest endpoint
+    pub fn record_accepted(&self, project_id: i32) {
+        self.record_accepted_at(project_id, self.now());
+    }
+
+    /// Counts an event digested into an issue
+    pub fn record_digested(&self, project_id: i32) {
+        self.record_digested_at(project_id, self.now());
+    }
+
+    /// Rates of a project; zero for projects without recent events
+    pub fn project(&self, project_id: i32) -> ProjectLiveRate {
+        self.project_at(project_id, self.now())
+    }
+
+    /// Rates of every project with events in the last minute
+    pub fn all(&self) -> Vec<ProjectLiveRate> {
+        self.all_at(self.now())
+    }
+
+    /// [`Self::record_accepted`] at a given second
+    pub fn record_accepted_at(&self, project_id: i32, second: u64) {
+        self.with_project(project_id, second, |windows| {
+            windows.accepted.record(second)
+        });
+    }
+
+    /// [`Self::record_digested`] at a given second
+    pub fn record_digested_at(&self, project_id: i32, second: u64) {
+        self.with_project(project_id, second, |windows| {
+            windows.digested.record(second)
+        });
+    }
+
+    /// [`Self::project`] at a given second
+    pub fn project_at(&self, project_id: i32, now: u64) -> ProjectLiveRate {
+        let projects = self.projects.lock().unwrap();
+        let windows = projects.get(&project_id).cloned().unwrap_or_default();
+        live_rate(project_id, &windows, now)
+    }
+
+    /// [`Self::all`] at a given second, dropping projects that went quiet
+    pub fn all_at(&self, now: u64) -> Vec<ProjectLiveRate> {
+        let mut projects = self.projects.lock().unwrap();
+        projects.retain(|_, windows| !is_stale(windows, now));
+
+        let mut rates: Vec<_> = projects
+            .iter()
+            .map(|(&project_id, windows)| live_rate(project_id, windows, now))
+            .collect();
+        rates.sort_by_key(|rate| rate.project_id);
+        rates
+    }
+
+    fn with_project(&self, project_id: i32, now: u64, f: impl FnOnce(&mut ProjectWindows)) {
+        let mut projects = self.projects.lock().unwrap();
+        if !projects.contains_key(&project_id) {
+            // Forget projects that went quiet before tracking another one
+            projects.retain(|_, windows| !is_stale(windows, now));
+        }
+        f(projects.entry(project_id).or_default());
+    }
+}
+
+fn is_stale(windows: &ProjectWindows, now: u64) -> bool {
+    let last = windows
+        .accepted
+        .last_second()
+        .max(windows.digested.last_second());
+    last + LIVE_RATE_WINDOW_SECS <= now
+}
+
+fn live_rate(project_id: i32, windows: &ProjectWindows, now: u64) -> ProjectLiveRate {
+    ProjectLiveRate {
+        project_id,
+        instance_id: instance_id(),
+        accepted: windows.accepted.windows(now),
+        digested: windows.digested.windows(now),
+    }
+}
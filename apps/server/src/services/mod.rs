@@ -1,21 +1,60 @@
 pub mod alert;
+pub mod alert_digest;
+pub mod alert_dispatch;
+pub mod audit_log;
 pub mod auth_token;
+pub mod auto_resolve;
+pub mod debug_file;
 pub mod event;
 pub mod grouping;
+pub mod ingested_event;
 pub mod issue;
+pub mod issue_context;
+pub mod issue_stream;
+pub mod issue_user;
+pub mod live_rate;
 pub mod notification;
 pub mod project;
+pub mod project_key;
+pub mod project_member;
+pub mod project_sequence;
 pub mod rate_limit;
+pub mod regroup;
+pub mod retention;
+pub mod saved_search;
+pub mod self_monitoring;
+pub mod team;
 pub mod users;
 
 pub use alert::AlertService;
+pub use alert_digest::AlertDigestBuffer;
+pub use alert_dispatch::AlertDispatchLimiter;
+pub use audit_log::AuditLogService;
 pub use auth_token::AuthTokenService;
+pub use auto_resolve::AutoResolveService;
+pub use debug_file::DebugFileService;
 pub use event::EventService;
 pub use grouping::{
-    calculate_grouping_key, get_denormalized_fields, hash_grouping_key, DenormalizedFields,
+    calculate_fallback_grouping_key, calculate_grouping_key, get_denormalized_fields,
+    hash_grouping_key, DenormalizedFields,
 };
+pub use ingested_event::IngestedEventService;
 pub use issue::IssueService;
+pub use issue_context::IssueContextService;
+pub use issue_stream::{IssueChange, IssueStream, IssueStreamEvent};
+pub use issue_user::IssueUserService;
+pub use live_rate::{LiveRates, ProjectLiveRate, RateWindows};
 pub use notification::{create_dispatcher, NotificationDispatcher, NotificationResult};
 pub use project::ProjectService;
-pub use rate_limit::RateLimitService;
+pub use project_key::ProjectKeyService;
+pub use project_member::ProjectMemberService;
+pub use project_sequence::ProjectSequenceService;
+pub use rate_limit::{RateLimitService, RateWindow, WindowCounts};
+pub use regroup::{
+    IssueRegroup, RegroupMove, RegroupOutcome, RegroupPlan, RegroupService, RegroupTarget,
+};
+pub use retention::RetentionService;
+pub use saved_search::SavedSearchService;
+pub use self_monitoring::SelfMonitor;
+pub use team::TeamService;
 pub use users::UsersService;
@@ -8,9 +8,9 @@ use lettre::message::header::ContentType;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
 
-use super::{NotificationDispatcher, NotificationResult};
+use super::{format_alert_type, NotificationDispatcher, NotificationResult, MAX_DIGEST_ISSUES};
 use crate::error::{AppError, AppResult};
-use crate::models::{AlertPayload, EmailConfig, NotificationChannel};
+use crate::models::{AlertPayload, DigestAlert, EmailConfig, IssueAlert, NotificationChannel};
 
 /// Email notification dispatcher
 pub struct EmailNotifier {
@@ -40,27 +40,42 @@ impl EmailNotifier {
 
     /// Formats an alert as HTML email body
     fn format_html(payload: &AlertPayload) -> String {
-        let level_color = match payload.issue.level.as_deref() {
-            Some("fatal") => "#dc2626",
-            Some("error") => "#ef4444",
-            Some("warning") => "#f59e0b",
-            Some("info") => "#3b82f6",
-            _ => "#6b7280",
-        };
+        match payload {
+            AlertPayload::Issue(alert) => Self::format_issue_html(alert),
+            AlertPayload::Digest(digest) => Self::format_digest_html(digest),
+        }
+    }
 
-        let alert_type_display = payload
-            .alert_type
-            .replace('_', " ")
-            .split_whitespace()
-            .map(|word| {
-                let mut chars = word.chars();
-                match chars.next() {
-                    Some(first) => first.to_uppercase().chain(chars).collect(),
-                    None => String::new(),
-                }
-            })
-            .collect::<Vec<String>>()
-            .join(" ");
+    /// Formats an alert as plain text email body
+    fn format_text(payload: &AlertPayload) -> String {
+        match payload {
+            AlertPayload::Issue(alert) => Self::format_issue_text(alert),
+            AlertPayload::Digest(digest) => Self::format_digest_text(digest),
+        }
+    }
+
+    /// Formats the email subject
+    fn format_subject(payload: &AlertPayload) -> String {
+        match payload {
+            AlertPayload::Issue(alert) => format!(
+                "[{}] {} - {}",
+                alert.project.name,
+                format_alert_type(&alert.alert_type),
+                alert.issue.short_id
+            ),
+            AlertPayload::Digest(digest) => format!(
+                "[{}] {} - {} issues",
+                digest.project.name,
+                format_alert_type(&digest.alert_type),
+                digest.issues.len()
+            ),
+        }
+    }
+
+    /// HTML body for a single-issue alert
+    fn format_issue_html(payload: &IssueAlert) -> String {
+        let level_color = level_color(payload.issue.level.as_deref());
+        let alert_type_display = format_alert_type(&payload.alert_type);
 
         format!(
             r#"<!DOCTYPE html>
@@ -125,21 +140,9 @@ impl EmailNotifier {
         )
     }
 
-    /// Formats an alert as plain text email body
-    fn format_text(payload: &AlertPayload) -> String {
-        let alert_type_display = payload
-            .alert_type
-            .replace('_', " ")
-            .split_whitespace()
-            .map(|word| {
-                let mut chars = word.chars();
-                match chars.next() {
-                    Some(first) => first.to_uppercase().chain(chars).collect(),
-                    None => String::new(),
-                }
-            })
-            .collect::<Vec<String>>()
-            .join(" ");
+    /// Plain text body for a single-issue alert
+    fn format_issue_text(payload: &IssueAlert) -> String {
+        let alert_type_display = format_alert_type(&payload.alert_type);
 
         format!(
             r#"{alert_type_display} in {project_name}
@@ -164,30 +167,138 @@ This alert was sent by Rustrak for project {project_name}."#,
             issue_url = &payload.issue_url,
         )
     }
-}
 
-impl Default for EmailNotifier {
-    fn default() -> Self {
-        Self::new()
+    /// HTML body for a digest, listing the first issues and counting the rest
+    fn format_digest_html(digest: &DigestAlert) -> String {
+        let mut rows: String = digest
+            .issues
+            .iter()
+            .take(MAX_DIGEST_ISSUES)
+            .map(|entry| {
+                format!(
+                    r#"
+                <tr>
+                    <td style="padding: 8px 0; border-top: 1px solid #e5e7eb; width: 12px;"><span style="display: inline-block; width: 8px; height: 8px; border-radius: 4px; background-color: {level_color};"></span></td>
+                    <td style="padding: 8px 0; border-top: 1px solid #e5e7eb;"><a href="{issue_url}" style="color: #2563eb; text-decoration: none;">{short_id}</a> <span style="color: #374151;">{title}</span></td>
+                    <td style="padding: 8px 0; color: #6b7280; border-top: 1px solid #e5e7eb; text-align: right;">{event_count}</td>
+                </tr>"#,
+                    level_color = level_color(entry.issue.level.as_deref()),
+                    issue_url = &entry.issue_url,
+                    short_id = html_escape(&entry.issue.short_id),
+                    title = html_escape(&entry.issue.title),
+                    event_count = entry.issue.event_count,
+                )
+            })
+            .collect();
+        if digest.issues.len() > MAX_DIGEST_ISSUES {
+            rows.push_str(&format!(
+                r#"
+                <tr>
+                    <td colspan="3" style="padding: 8px 0; color: #6b7280; border-top: 1px solid #e5e7eb;">and {} more</td>
+                </tr>"#,
+                digest.issues.len() - MAX_DIGEST_ISSUES
+            ));
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+</head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; margin: 0; padding: 20px; background-color: #f3f4f6;">
+    <div style="max-width: 600px; margin: 0 auto; background-color: #ffffff; border-radius: 8px; overflow: hidden; box-shadow: 0 1px 3px rgba(0,0,0,0.1);">
+        <div style="background-color: #2563eb; padding: 16px 24px;">
+            <h1 style="color: #ffffff; margin: 0; font-size: 18px; font-weight: 600;">
+                {issue_count} {alert_type_display} alerts in {project_name}
+            </h1>
+        </div>
+        <div style="padding: 24px;">
+            <p style="margin: 0 0 16px 0; font-size: 13px; color: #6b7280;">
+                Since {window_start}
+            </p>
+            <table style="width: 100%; border-collapse: collapse; font-size: 13px;">
+                <tr>
+                    <td></td>
+                    <td style="padding: 8px 0; color: #6b7280;">Issue</td>
+                    <td style="padding: 8px 0; color: #6b7280; text-align: right;">Events</td>
+                </tr>{rows}
+            </table>
+            <div style="margin-top: 24px;">
+                <a href="{project_url}" style="display: inline-block; background-color: #2563eb; color: #ffffff; padding: 10px 20px; border-radius: 6px; text-decoration: none; font-size: 14px; font-weight: 500;">
+                    View Issues
+                </a>
+            </div>
+        </div>
+        <div style="padding: 16px 24px; background-color: #f9fafb; border-top: 1px solid #e5e7eb;">
+            <p style="margin: 0; font-size: 12px; color: #6b7280;">
+                This digest was sent by Rustrak for project {project_name}.
+            </p>
+        </div>
+    </div>
+</body>
+</html>"#,
+            issue_count = digest.issues.len(),
+            alert_type_display = format_alert_type(&digest.alert_type),
+            project_name = html_escape(&digest.project.name),
+            window_start = digest.window_start.format("%Y-%m-%d %H:%M UTC"),
+            rows = rows,
+            project_url = &digest.project_url,
+        )
     }
-}
 
-/// Simple HTML escaping for email content
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-}
+    /// Plain text body for a digest, listing the first issues and counting the rest
+    fn format_digest_text(digest: &DigestAlert) -> String {
+        let mut lines: Vec<String> = digest
+            .issues
+            .iter()
+            .take(MAX_DIGEST_ISSUES)
+            .map(|entry| {
+                format!(
+                    "{}: {} ({} events)\n  {}",
+                    entry.issue.short_id,
+                    entry.issue.title,
+                    entry.issue.event_count,
+                    entry.issue_url
+                )
+            })
+            .collect();
+        if digest.issues.len() > MAX_DIGEST_ISSUES {
+            lines.push(format!(
+                "and {} more",
+                digest.issues.len() - MAX_DIGEST_ISSUES
+            ));
+        }
 
-#[async_trait]
-impl NotificationDispatcher for EmailNotifier {
-    async fn send(
+        format!(
+            r#"{issue_count} {alert_type_display} alerts in {project_name}
+Since {window_start}
+
+{issues}
+
+View issues: {project_url}
+
+--
+This digest was sent by Rustrak for project {project_name}."#,
+            issue_count = digest.issues.len(),
+            alert_type_display = format_alert_type(&digest.alert_type),
+            project_name = &digest.project.name,
+            window_start = digest.window_start.format("%Y-%m-%d %H:%M UTC"),
+            issues = lines.join("\n"),
+            project_url = &digest.project_url,
+        )
+    }
+
+    /// Sends a plain text notice about Rustrak itself, e.g. a channel being
+    /// disabled, through the SMTP server of an email channel
+    pub async fn send_notice(
         &self,
         channel: &NotificationChannel,
-        payload: &AlertPayload,
+        recipients: &[String],
+        subject: &str,
+        body: &str,
     ) -> NotificationResult {
-        // Parse config
         let config: EmailConfig = match serde_json::from_value(channel.config.clone()) {
             Ok(c) => c,
             Err(e) => {
@@ -195,6 +306,19 @@ impl NotificationDispatcher for EmailNotifier {
             }
         };
 
+        self.deliver(&config, recipients, subject, body, None).await
+    }
+
+    /// Sends an email to each recipient with the SMTP settings of `config`,
+    /// falling back to the global ones
+    async fn deliver(
+        &self,
+        config: &EmailConfig,
+        recipients: &[String],
+        subject: &str,
+        text_body: &str,
+        html_body: Option<&str>,
+    ) -> NotificationResult {
         // Determine SMTP settings (channel config overrides global)
         let smtp_host = config.smtp_host.as_ref().or(self.global_smtp_host.as_ref());
         let smtp_host = match smtp_host {
@@ -210,35 +334,11 @@ impl NotificationDispatcher for EmailNotifier {
             .as_ref()
             .unwrap_or(&self.global_from_address);
 
-        // Build email subject
-        let alert_type_display = payload
-            .alert_type
-            .replace('_', " ")
-            .split_whitespace()
-            .map(|word| {
-                let mut chars = word.chars();
-                match chars.next() {
-                    Some(first) => first.to_uppercase().chain(chars).collect(),
-                    None => String::new(),
-                }
-            })
-            .collect::<Vec<String>>()
-            .join(" ");
-
-        let subject = format!(
-            "[{}] {} - {}",
-            payload.project.name, alert_type_display, payload.issue.short_id
-        );
-
-        // Build HTML and text bodies
-        let html_body = Self::format_html(payload);
-        let text_body = Self::format_text(payload);
-
         // Send to each recipient
         let mut sent_any = false;
-        for recipient in &config.recipients {
+        for recipient in recipients {
             // Build email message
-            let email = match Message::builder()
+            let builder = Message::builder()
                 .from(
                     from_address
                         .parse()
@@ -251,20 +351,26 @@ impl NotificationDispatcher for EmailNotifier {
                         continue;
                     }
                 })
-                .subject(&subject)
-                .multipart(
+                .subject(subject);
+            let email = match html_body {
+                Some(html_body) => builder.multipart(
                     lettre::message::MultiPart::alternative()
                         .singlepart(
                             lettre::message::SinglePart::builder()
                                 .header(ContentType::TEXT_PLAIN)
-                                .body(text_body.clone()),
+                                .body(text_body.to_string()),
                         )
                         .singlepart(
                             lettre::message::SinglePart::builder()
                                 .header(ContentType::TEXT_HTML)
-                                .body(html_body.clone()),
+                                .body(html_body.to_string()),
                         ),
-                ) {
+                ),
+                None => builder
+                    .header(ContentType::TEXT_PLAIN)
+                    .body(text_body.to_string()),
+            };
+            let email = match email {
                 Ok(email) => email,
                 Err(e) => {
                     return NotificationResult::failure(
@@ -352,6 +458,61 @@ impl NotificationDispatcher for EmailNotifier {
 
         NotificationResult::success(None)
     }
+}
+
+impl Default for EmailNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Header color for an issue level
+fn level_color(level: Option<&str>) -> &'static str {
+    match level {
+        Some("fatal") => "#dc2626",
+        Some("error") => "#ef4444",
+        Some("warning") => "#f59e0b",
+        Some("info") => "#3b82f6",
+        _ => "#6b7280",
+    }
+}
+
+/// Simple HTML escaping for email content
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[async_trait]
+impl NotificationDispatcher for EmailNotifier {
+    async fn send(
+        &self,
+        channel: &NotificationChannel,
+        payload: &AlertPayload,
+    ) -> NotificationResult {
+        // Parse config
+        let config: EmailConfig = match serde_json::from_value(channel.config.clone()) {
+            Ok(c) => c,
+            Err(e) => {
+                return NotificationResult::failure(format!("Invalid email config: {}", e), None)
+            }
+        };
+
+        let subject = Self::format_subject(payload);
+        let html_body = Self::format_html(payload);
+        let text_body = Self::format_text(payload);
+
+        self.deliver(
+            &config,
+            &config.recipients,
+            &subject,
+            &text_body,
+            Some(&html_body),
+        )
+        .await
+    }
 
     fn validate_config(&self, config: &serde_json::Value) -> AppResult<()> {
         let email_config: EmailConfig = serde_json::from_value(config.clone())
@@ -390,7 +551,7 @@ mod tests {
     use chrono::Utc;
 
     fn create_test_payload() -> AlertPayload {
-        AlertPayload {
+        AlertPayload::Issue(IssueAlert {
             alert_id: "test-123".to_string(),
             alert_type: "new_issue".to_string(),
             triggered_at: Utc::now(),
@@ -407,10 +568,44 @@ mod tests {
                 first_seen: Utc::now(),
                 last_seen: Utc::now(),
                 event_count: 5,
+                is_unhandled: false,
+                priority: crate::models::IssuePriority::Medium,
             },
             issue_url: "https://example.com/issues/abc-123".to_string(),
             actor: "Rustrak".to_string(),
-        }
+        })
+    }
+
+    fn create_digest_payload(issue_count: usize) -> AlertPayload {
+        AlertPayload::Digest(DigestAlert {
+            alert_id: "1-digest-1-123".to_string(),
+            alert_type: "new_issue".to_string(),
+            triggered_at: Utc::now(),
+            window_start: Utc::now(),
+            project: crate::models::ProjectInfo {
+                id: 1,
+                name: "Test Project".to_string(),
+                slug: "test-project".to_string(),
+            },
+            issues: (1..=issue_count)
+                .map(|i| crate::models::DigestIssue {
+                    issue: crate::models::IssueInfo {
+                        id: format!("issue-{}", i),
+                        short_id: format!("TEST-{}", i),
+                        title: format!("Error {}", i),
+                        level: Some("error".to_string()),
+                        first_seen: Utc::now(),
+                        last_seen: Utc::now(),
+                        event_count: 1,
+                        is_unhandled: false,
+                        priority: crate::models::IssuePriority::Medium,
+                    },
+                    issue_url: format!("https://example.com/issues/issue-{}", i),
+                })
+                .collect(),
+            project_url: "https://example.com/projects/test-project/issues".to_string(),
+            actor: "Rustrak".to_string(),
+        })
     }
 
     #[test]
@@ -435,6 +630,27 @@ mod tests {
         assert!(text.contains("https://example.com/issues/abc-123"));
     }
 
+    #[test]
+    fn test_format_digest_lists_issues() {
+        let payload = create_digest_payload(12);
+
+        let text = EmailNotifier::format_text(&payload);
+        assert!(text.contains("12 New Issue alerts in Test Project"));
+        assert!(text.contains("TEST-10: Error 10"));
+        assert!(!text.contains("TEST-11"));
+        assert!(text.contains("and 2 more"));
+
+        let html = EmailNotifier::format_html(&payload);
+        assert!(html.contains("https://example.com/issues/issue-1"));
+        assert!(html.contains("and 2 more"));
+        assert!(html.contains("View Issues"));
+
+        assert_eq!(
+            EmailNotifier::format_subject(&payload),
+            "[Test Project] New Issue - 12 issues"
+        );
+    }
+
     #[test]
     fn test_html_escape() {
         assert_eq!(html_escape("<script>"), "&lt;script&gt;");
@@ -0,0 +1,82 @@
+//! HTTP client shared by the Slack and webhook notifiers.
+//!
+//! Clients are built on first use from the outbound settings in
+//! `AlertConfig` (proxy, TLS verification, timeouts) and reused for every
+//! delivery, so connections are pooled across alerts. The delivery timeout
+//! bounds a whole request, so a receiver that answers slowly can't hold a
+//! dispatch task for longer than that.
+
+use std::sync::OnceLock;
+
+use super::NotificationResult;
+use crate::config::OutboundHttpConfig;
+
+static CONFIG: OnceLock<OutboundHttpConfig> = OnceLock::new();
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+static INSECURE_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Sets the outbound settings used by the shared clients
+///
+/// Must run before the first notification is sent; later calls are ignored.
+pub fn init(config: &OutboundHttpConfig) {
+    if CONFIG.set(config.clone()).is_err() {
+        log::warn!("Outbound HTTP config already initialized");
+    }
+}
+
+/// The outbound settings, with the defaults if `init` was never called
+fn config() -> &'static OutboundHttpConfig {
+    CONFIG.get_or_init(OutboundHttpConfig::default)
+}
+
+/// Builds a client from the outbound settings
+///
+/// `verify_tls: false` accepts any certificate, as does `tls_insecure` in
+/// the config.
+pub fn build_client(
+    config: &OutboundHttpConfig,
+    verify_tls: bool,
+) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(config.connect_timeout)
+        .read_timeout(config.read_timeout)
+        .timeout(config.delivery_timeout);
+
+    if let Some(proxy_url) = &config.proxy {
+        let mut proxy = reqwest::Proxy::all(proxy_url)?;
+        if let Some(no_proxy) = &config.no_proxy {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+        }
+        builder = builder.proxy(proxy);
+    }
+
+    if config.tls_insecure || !verify_tls {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder.build()
+}
+
+/// The shared client; `verify_tls: false` returns one that accepts any certificate
+pub fn client(verify_tls: bool) -> &'static reqwest::Client {
+    let cell = if verify_tls {
+        &CLIENT
+    } else {
+        &INSECURE_CLIENT
+    };
+    cell.get_or_init(|| build_client(config(), verify_tls).expect("Failed to create HTTP client"))
+}
+
+/// Failed result for a request that got no response
+///
+/// Like every failed delivery, it's scheduled for a retry.
+pub fn request_failure(e: &reqwest::Error) -> NotificationResult {
+    let error_msg = if e.is_timeout() {
+        "Request timed out".to_string()
+    } else if e.is_connect() {
+        "Connection failed".to_string()
+    } else {
+        format!("Request failed: {}", e)
+    };
+    NotificationResult::failure(error_msg, None)
+}
@@ -4,7 +4,9 @@
 //! multiple delivery channels (Webhook, Email, Slack) through a common trait.
 
 pub mod email;
+pub mod http;
 pub mod slack;
+pub mod template;
 pub mod webhook;
 
 use async_trait::async_trait;
@@ -16,6 +18,25 @@ pub use email::EmailNotifier;
 pub use slack::SlackNotifier;
 pub use webhook::WebhookNotifier;
 
+/// Issues listed in a digest message; the rest are summarized as a count
+pub const MAX_DIGEST_ISSUES: usize = 10;
+
+/// Formats an alert type for display ("new_issue" -> "New Issue")
+fn format_alert_type(alert_type: &str) -> String {
+    alert_type
+        .replace('_', " ")
+        .split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 // =============================================================================
 // Notification Result
 // =============================================================================
@@ -73,6 +94,15 @@ pub trait NotificationDispatcher: Send + Sync {
     /// Called before creating or updating a channel to ensure
     /// the configuration is valid for this channel type.
     fn validate_config(&self, config: &serde_json::Value) -> AppResult<()>;
+
+    /// Verify channel configuration against the remote service
+    ///
+    /// Called after `validate_config` when creating or updating a channel.
+    /// Does nothing by default; dispatchers that support it contact the
+    /// service only when live validation is enabled.
+    async fn verify_config(&self, _config: &serde_json::Value) -> AppResult<()> {
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -3,119 +3,128 @@
 //! Sends alerts to Slack channels using incoming webhooks.
 //! Uses Slack Block Kit for rich message formatting.
 
+use std::sync::OnceLock;
+
 use async_trait::async_trait;
 use serde_json::json;
 
-use super::{NotificationDispatcher, NotificationResult};
+use super::template::{self, TemplateFormat};
+use super::{
+    format_alert_type, http, NotificationDispatcher, NotificationResult, MAX_DIGEST_ISSUES,
+};
+use crate::config::AlertConfig;
 use crate::error::{AppError, AppResult};
-use crate::models::{AlertPayload, NotificationChannel, SlackConfig};
+use crate::models::{
+    AlertPayload, DigestAlert, IssueAlert, IssueInfo, NotificationChannel, SlackConfig,
+};
+
+/// Timeout for the live webhook check on channel create/update
+const VERIFY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+static LIVE_VALIDATION: OnceLock<bool> = OnceLock::new();
 
 /// Slack notification dispatcher
 pub struct SlackNotifier {
-    client: reqwest::Client,
+    /// Check webhooks against Slack on channel create/update (SLACK_VALIDATE_WEBHOOKS)
+    live_validation: bool,
 }
 
 impl SlackNotifier {
+    /// Sets whether webhooks are checked against Slack from config
+    ///
+    /// Must run before the first channel is saved; later calls are ignored.
+    pub fn init(config: &AlertConfig) {
+        if LIVE_VALIDATION.set(config.slack_validate_webhooks).is_err() {
+            log::warn!("Slack notifier already initialized");
+        }
+    }
+
     /// Creates a new Slack notifier
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
+        let live_validation =
+            *LIVE_VALIDATION.get_or_init(|| AlertConfig::default().slack_validate_webhooks);
 
-        Self { client }
+        Self { live_validation }
     }
 
-    /// Formats an alert as a Slack Block Kit message
-    fn format_message(config: &SlackConfig, payload: &AlertPayload) -> serde_json::Value {
-        let level_emoji = match payload.issue.level.as_deref() {
-            Some("fatal") => ":rotating_light:",
-            Some("error") => ":x:",
-            Some("warning") => ":warning:",
-            Some("info") => ":information_source:",
-            Some("debug") => ":mag:",
-            _ => ":grey_question:",
-        };
+    /// Posts an empty message to the webhook without sending anything visible
+    ///
+    /// Slack answers a working webhook with `no_text`; revoked or broken ones
+    /// get errors like `no_service` or `invalid_token`.
+    async fn check_webhook(&self, webhook_url: &str) -> AppResult<()> {
+        let response = http::client(true)
+            .post(webhook_url)
+            .timeout(VERIFY_TIMEOUT)
+            .json(&json!({}))
+            .send()
+            .await
+            .map_err(|e| AppError::Validation(Self::describe_request_error(&e)))?;
 
-        let alert_emoji = match payload.alert_type.as_str() {
-            "new_issue" => ":new:",
-            "regression" => ":repeat:",
-            "unmute" => ":loud_sound:",
-            _ => ":bell:",
-        };
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
 
-        let alert_type_display = payload
-            .alert_type
-            .replace('_', " ")
-            .split_whitespace()
-            .map(|word| {
-                let mut chars = word.chars();
-                match chars.next() {
-                    Some(first) => first.to_uppercase().chain(chars).collect(),
-                    None => String::new(),
-                }
-            })
-            .collect::<Vec<String>>()
-            .join(" ");
+        if status < 300 || body == "no_text" {
+            Ok(())
+        } else {
+            Err(AppError::Validation(Self::describe_error(status, &body)))
+        }
+    }
+
+    /// Turns a Slack error response into a readable message
+    fn describe_error(status: u16, error_body: &str) -> String {
+        match error_body {
+            "invalid_token" => "Invalid Slack webhook URL".to_string(),
+            "no_service" => "Slack webhook is disabled or revoked (no_service)".to_string(),
+            "channel_not_found" => "Slack channel not found".to_string(),
+            "channel_is_archived" => "Slack channel is archived".to_string(),
+            "posting_to_general_channel_denied" => "Cannot post to #general channel".to_string(),
+            "" => format!("Slack API error: HTTP {}", status),
+            _ => format!("Slack API error: {}", error_body),
+        }
+    }
+
+    /// Turns a failed request into a readable message
+    fn describe_request_error(e: &reqwest::Error) -> String {
+        if e.is_timeout() {
+            "Request to Slack timed out".to_string()
+        } else if e.is_connect() {
+            "Connection to Slack failed".to_string()
+        } else {
+            format!("Slack request failed: {}", e)
+        }
+    }
+
+    /// Formats an alert as a Slack Block Kit message, or as the text of the
+    /// channel's template
+    ///
+    /// A template that fails to render, e.g. one written for single issues
+    /// receiving a digest, falls back to the built-in blocks.
+    fn format_message(config: &SlackConfig, payload: &AlertPayload) -> serde_json::Value {
+        let text = config.template.as_deref().and_then(|t| {
+            template::render(t, TemplateFormat::SlackText, payload)
+                .inspect_err(|e| {
+                    log::warn!(
+                        "Slack template failed for alert {}, sending the built-in message: {}",
+                        payload.alert_id(),
+                        e
+                    )
+                })
+                .ok()
+        });
 
         let mut message = json!({
             "username": config.username.as_deref().unwrap_or("Rustrak"),
             "icon_emoji": config.icon_emoji.as_deref().unwrap_or(":bug:"),
-            "blocks": [
-                {
-                    "type": "header",
-                    "text": {
-                        "type": "plain_text",
-                        "text": format!("{} {} in {}", alert_emoji, alert_type_display, payload.project.name),
-                        "emoji": true
-                    }
-                },
-                {
-                    "type": "section",
-                    "text": {
-                        "type": "mrkdwn",
-                        "text": format!(
-                            "{} *<{}|{}>*\n{}",
-                            level_emoji,
-                            payload.issue_url,
-                            payload.issue.short_id,
-                            Self::escape_markdown(&payload.issue.title)
-                        )
-                    }
-                },
-                {
-                    "type": "context",
-                    "elements": [
-                        {
-                            "type": "mrkdwn",
-                            "text": format!(
-                                "*Events:* {} | *First seen:* <!date^{}^{{date_short_pretty}} {{time}}|{}> | *Last seen:* <!date^{}^{{date_short_pretty}} {{time}}|{}>",
-                                payload.issue.event_count,
-                                payload.issue.first_seen.timestamp(),
-                                payload.issue.first_seen.format("%Y-%m-%d %H:%M"),
-                                payload.issue.last_seen.timestamp(),
-                                payload.issue.last_seen.format("%Y-%m-%d %H:%M")
-                            )
-                        }
-                    ]
-                },
-                {
-                    "type": "actions",
-                    "elements": [
-                        {
-                            "type": "button",
-                            "text": {
-                                "type": "plain_text",
-                                "text": "View Issue",
-                                "emoji": true
-                            },
-                            "url": payload.issue_url,
-                            "action_id": "view_issue"
-                        }
-                    ]
-                }
-            ]
         });
+        match text {
+            Some(text) => message["text"] = json!(text),
+            None => {
+                message["blocks"] = match payload {
+                    AlertPayload::Issue(alert) => Self::issue_blocks(alert),
+                    AlertPayload::Digest(digest) => Self::digest_blocks(digest),
+                }
+            }
+        }
 
         // Add channel override if specified
         if let Some(ref channel) = config.channel {
@@ -125,6 +134,176 @@ impl SlackNotifier {
         message
     }
 
+    /// Blocks for a single-issue alert
+    fn issue_blocks(alert: &IssueAlert) -> serde_json::Value {
+        json!([
+            {
+                "type": "header",
+                "text": {
+                    "type": "plain_text",
+                    "text": format!(
+                        "{} {} in {}",
+                        Self::alert_emoji(&alert.alert_type),
+                        format_alert_type(&alert.alert_type),
+                        alert.project.name
+                    ),
+                    "emoji": true
+                }
+            },
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "{} *<{}|{}>*{}\n{}",
+                        Self::level_emoji(alert.issue.level.as_deref()),
+                        alert.issue_url,
+                        alert.issue.short_id,
+                        Self::crash_tag(&alert.issue),
+                        Self::escape_markdown(&alert.issue.title)
+                    )
+                }
+            },
+            {
+                "type": "context",
+                "elements": [
+                    {
+                        "type": "mrkdwn",
+                        "text": format!(
+                            "*Events:* {} | *First seen:* <!date^{}^{{date_short_pretty}} {{time}}|{}> | *Last seen:* <!date^{}^{{date_short_pretty}} {{time}}|{}>",
+                            alert.issue.event_count,
+                            alert.issue.first_seen.timestamp(),
+                            alert.issue.first_seen.format("%Y-%m-%d %H:%M"),
+                            alert.issue.last_seen.timestamp(),
+                            alert.issue.last_seen.format("%Y-%m-%d %H:%M")
+                        )
+                    }
+                ]
+            },
+            {
+                "type": "actions",
+                "elements": [
+                    {
+                        "type": "button",
+                        "text": {
+                            "type": "plain_text",
+                            "text": "View Issue",
+                            "emoji": true
+                        },
+                        "url": alert.issue_url,
+                        "action_id": "view_issue"
+                    }
+                ]
+            }
+        ])
+    }
+
+    /// Blocks for a digest, listing the first issues and counting the rest
+    fn digest_blocks(digest: &DigestAlert) -> serde_json::Value {
+        let mut lines: Vec<String> = digest
+            .issues
+            .iter()
+            .take(MAX_DIGEST_ISSUES)
+            .map(|entry| {
+                format!(
+                    "{} *<{}|{}>*{} {}",
+                    Self::level_emoji(entry.issue.level.as_deref()),
+                    entry.issue_url,
+                    entry.issue.short_id,
+                    Self::crash_tag(&entry.issue),
+                    Self::escape_markdown(&entry.issue.title)
+                )
+            })
+            .collect();
+        if digest.issues.len() > MAX_DIGEST_ISSUES {
+            lines.push(format!(
+                "_and {} more_",
+                digest.issues.len() - MAX_DIGEST_ISSUES
+            ));
+        }
+
+        json!([
+            {
+                "type": "header",
+                "text": {
+                    "type": "plain_text",
+                    "text": format!(
+                        "{} {} {} alerts in {}",
+                        Self::alert_emoji(&digest.alert_type),
+                        digest.issues.len(),
+                        format_alert_type(&digest.alert_type),
+                        digest.project.name
+                    ),
+                    "emoji": true
+                }
+            },
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": lines.join("\n")
+                }
+            },
+            {
+                "type": "context",
+                "elements": [
+                    {
+                        "type": "mrkdwn",
+                        "text": format!(
+                            "*Since:* <!date^{}^{{date_short_pretty}} {{time}}|{}>",
+                            digest.window_start.timestamp(),
+                            digest.window_start.format("%Y-%m-%d %H:%M")
+                        )
+                    }
+                ]
+            },
+            {
+                "type": "actions",
+                "elements": [
+                    {
+                        "type": "button",
+                        "text": {
+                            "type": "plain_text",
+                            "text": "View Issues",
+                            "emoji": true
+                        },
+                        "url": digest.project_url,
+                        "action_id": "view_issues"
+                    }
+                ]
+            }
+        ])
+    }
+
+    /// Calls out issues with unhandled events, which crashed the app
+    fn crash_tag(issue: &IssueInfo) -> &'static str {
+        if issue.is_unhandled {
+            " `crash`"
+        } else {
+            ""
+        }
+    }
+
+    fn level_emoji(level: Option<&str>) -> &'static str {
+        match level {
+            Some("fatal") => ":rotating_light:",
+            Some("error") => ":x:",
+            Some("warning") => ":warning:",
+            Some("info") => ":information_source:",
+            Some("debug") => ":mag:",
+            _ => ":grey_question:",
+        }
+    }
+
+    fn alert_emoji(alert_type: &str) -> &'static str {
+        match alert_type {
+            "new_issue" => ":new:",
+            "regression" => ":repeat:",
+            "unmute" => ":loud_sound:",
+            _ => ":bell:",
+        }
+    }
+
     /// Escapes special Slack markdown characters
     fn escape_markdown(text: &str) -> String {
         text.replace('&', "&amp;")
@@ -157,8 +336,7 @@ impl NotificationDispatcher for SlackNotifier {
         let message = Self::format_message(&config, payload);
 
         // Send to Slack webhook
-        match self
-            .client
+        match http::client(true)
             .post(&config.webhook_url)
             .header("Content-Type", "application/json")
             .json(&message)
@@ -171,29 +349,13 @@ impl NotificationDispatcher for SlackNotifier {
                     NotificationResult::success(Some(status))
                 } else {
                     let error_body = response.text().await.unwrap_or_default();
-                    let error_msg = match error_body.as_str() {
-                        "invalid_token" => "Invalid Slack webhook URL".to_string(),
-                        "channel_not_found" => "Slack channel not found".to_string(),
-                        "channel_is_archived" => "Slack channel is archived".to_string(),
-                        "posting_to_general_channel_denied" => {
-                            "Cannot post to #general channel".to_string()
-                        }
-                        _ if error_body.is_empty() => format!("Slack API error: HTTP {}", status),
-                        _ => format!("Slack API error: {}", error_body),
-                    };
-                    NotificationResult::failure(error_msg, Some(status))
+                    NotificationResult::failure(
+                        Self::describe_error(status, &error_body),
+                        Some(status),
+                    )
                 }
             }
-            Err(e) => {
-                let error_msg = if e.is_timeout() {
-                    "Request to Slack timed out".to_string()
-                } else if e.is_connect() {
-                    "Connection to Slack failed".to_string()
-                } else {
-                    format!("Slack request failed: {}", e)
-                };
-                NotificationResult::failure(error_msg, None)
-            }
+            Err(e) => NotificationResult::failure(Self::describe_request_error(&e), None),
         }
     }
 
@@ -226,8 +388,23 @@ impl NotificationDispatcher for SlackNotifier {
             ));
         }
 
+        if let Some(ref template) = slack_config.template {
+            template::validate(template, TemplateFormat::SlackText)?;
+        }
+
         Ok(())
     }
+
+    async fn verify_config(&self, config: &serde_json::Value) -> AppResult<()> {
+        if !self.live_validation {
+            return Ok(());
+        }
+
+        let slack_config: SlackConfig = serde_json::from_value(config.clone())
+            .map_err(|e| AppError::Validation(format!("Invalid Slack config: {}", e)))?;
+
+        self.check_webhook(&slack_config.webhook_url).await
+    }
 }
 
 #[cfg(test)]
@@ -236,7 +413,7 @@ mod tests {
     use chrono::Utc;
 
     fn create_test_payload() -> AlertPayload {
-        AlertPayload {
+        AlertPayload::Issue(IssueAlert {
             alert_id: "test-123".to_string(),
             alert_type: "new_issue".to_string(),
             triggered_at: Utc::now(),
@@ -253,10 +430,44 @@ mod tests {
                 first_seen: Utc::now(),
                 last_seen: Utc::now(),
                 event_count: 5,
+                is_unhandled: false,
+                priority: crate::models::IssuePriority::Medium,
             },
             issue_url: "https://example.com/issues/abc-123".to_string(),
             actor: "Rustrak".to_string(),
-        }
+        })
+    }
+
+    fn create_digest_payload(issue_count: usize) -> AlertPayload {
+        AlertPayload::Digest(DigestAlert {
+            alert_id: "1-digest-1-123".to_string(),
+            alert_type: "new_issue".to_string(),
+            triggered_at: Utc::now(),
+            window_start: Utc::now(),
+            project: crate::models::ProjectInfo {
+                id: 1,
+                name: "Test Project".to_string(),
+                slug: "test-project".to_string(),
+            },
+            issues: (1..=issue_count)
+                .map(|i| crate::models::DigestIssue {
+                    issue: crate::models::IssueInfo {
+                        id: format!("issue-{}", i),
+                        short_id: format!("TEST-{}", i),
+                        title: format!("Error {}", i),
+                        level: Some("error".to_string()),
+                        first_seen: Utc::now(),
+                        last_seen: Utc::now(),
+                        event_count: 1,
+                        is_unhandled: false,
+                        priority: crate::models::IssuePriority::Medium,
+                    },
+                    issue_url: format!("https://example.com/issues/issue-{}", i),
+                })
+                .collect(),
+            project_url: "https://example.com/projects/test-project/issues".to_string(),
+            actor: "Rustrak".to_string(),
+        })
     }
 
     #[test]
@@ -266,6 +477,7 @@ mod tests {
             channel: Some("#alerts".to_string()),
             username: Some("TestBot".to_string()),
             icon_emoji: Some(":robot:".to_string()),
+            template: None,
         };
         let payload = create_test_payload();
 
@@ -277,6 +489,151 @@ mod tests {
         assert_eq!(message["channel"], "#alerts");
     }
 
+    #[test]
+    fn test_format_digest_message_lists_issues() {
+        let config = SlackConfig {
+            webhook_url: "https://hooks.slack.com/test".to_string(),
+            channel: None,
+            username: None,
+            icon_emoji: None,
+            template: None,
+        };
+
+        let message = SlackNotifier::format_message(&config, &create_digest_payload(12));
+
+        let header = message["blocks"][0]["text"]["text"].as_str().unwrap();
+        assert!(header.contains("12 New Issue alerts in Test Project"));
+        let list = message["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert!(list.contains("TEST-1"));
+        assert!(list.contains("TEST-10"));
+        assert!(!list.contains("TEST-11"));
+        assert!(list.contains("and 2 more"));
+        assert_eq!(
+            message["blocks"][3]["elements"][0]["url"],
+            "https://example.com/projects/test-project/issues"
+        );
+    }
+
+    #[test]
+    fn test_format_message_tags_crashes() {
+        let config = SlackConfig {
+            webhook_url: "https://hooks.slack.com/test".to_string(),
+            channel: None,
+            username: None,
+            icon_emoji: None,
+            template: None,
+        };
+        let mut payload = create_test_payload();
+
+        let message = SlackNotifier::format_message(&config, &payload);
+        let text = message["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert!(!text.contains("crash"));
+
+        if let AlertPayload::Issue(ref mut alert) = payload {
+            alert.issue.is_unhandled = true;
+        }
+        let message = SlackNotifier::format_message(&config, &payload);
+        let text = message["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert!(text.contains("*<https://example.com/issues/abc-123|TEST-1>* `crash`"));
+    }
+
+    #[test]
+    fn test_format_message_with_template() {
+        let config = SlackConfig {
+            webhook_url: "https://hooks.slack.com/test".to_string(),
+            channel: None,
+            username: None,
+            icon_emoji: None,
+            template: Some("{{issue.short_id}} en {{project.name}}".to_string()),
+        };
+
+        let message = SlackNotifier::format_message(&config, &create_test_payload());
+        assert_eq!(message["text"], "TEST-1 en Test Project");
+        assert!(message.get("blocks").is_none());
+        assert_eq!(message["username"], "Rustrak");
+    }
+
+    #[test]
+    fn test_format_message_template_falls_back_to_blocks() {
+        // Written for single issues, so it can't render a digest
+        let config = SlackConfig {
+            webhook_url: "https://hooks.slack.com/test".to_string(),
+            channel: None,
+            username: None,
+            icon_emoji: None,
+            template: Some("{{issue.short_id}}".to_string()),
+        };
+
+        let message = SlackNotifier::format_message(&config, &create_digest_payload(2));
+        assert!(message.get("text").is_none());
+        assert!(message["blocks"].is_array());
+    }
+
+    /// Serves `status` and `body` for every POST, like a Slack webhook would
+    fn mock_webhook(status: u16, body: &'static str) -> actix_test::TestServer {
+        actix_test::start(move || {
+            actix_web::App::new().default_service(actix_web::web::to(move || async move {
+                actix_web::HttpResponse::build(
+                    actix_web::http::StatusCode::from_u16(status).unwrap(),
+                )
+                .body(body)
+            }))
+        })
+    }
+
+    fn live_notifier() -> SlackNotifier {
+        SlackNotifier {
+            live_validation: true,
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_check_webhook_accepts_working_webhook() {
+        // Working webhooks reject the empty test message with `no_text`
+        let server = mock_webhook(400, "no_text");
+
+        let result = live_notifier().check_webhook(&server.url("/hook")).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_check_webhook_rejects_revoked_webhook() {
+        let server = mock_webhook(404, "no_service");
+
+        let result = live_notifier().check_webhook(&server.url("/hook")).await;
+
+        match result {
+            Err(AppError::Validation(msg)) => assert!(msg.contains("no_service")),
+            other => panic!("Expected validation error, got {:?}", other),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_check_webhook_surfaces_unknown_errors() {
+        let server = mock_webhook(403, "action_prohibited");
+
+        let result = live_notifier().check_webhook(&server.url("/hook")).await;
+
+        match result {
+            Err(AppError::Validation(msg)) => {
+                assert_eq!(msg, "Slack API error: action_prohibited")
+            }
+            other => panic!("Expected validation error, got {:?}", other),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_verify_config_skipped_without_live_validation() {
+        let notifier = SlackNotifier {
+            live_validation: false,
+        };
+        // Would fail to connect if it were contacted
+        let config = json!({ "webhook_url": "https://hooks.slack.com/services/T0/B0/revoked" });
+
+        assert!(notifier.verify_config(&config).await.is_ok());
+    }
+
     #[test]
     fn test_escape_markdown() {
         assert_eq!(SlackNotifier::escape_markdown("a & b"), "a &amp; b");
@@ -0,0 +1,96 @@
+//! Custom alert message templates.
+//!
+//! Slack and webhook channels can replace the built-in message with a
+//! Handlebars template, rendered with the alert payload as context: the
+//! same JSON webhooks receive, so `{{issue.title}}`, `{{project.name}}` or
+//! `{{#each issues}}` for digests. Rendering is strict, a field that doesn't
+//! exist is an error rather than an empty string, so typos are caught when
+//! the channel is saved by rendering a sample alert.
+
+use handlebars::Handlebars;
+
+use crate::error::{AppError, AppResult};
+use crate::models::AlertPayload;
+
+/// Longest template accepted, in bytes
+pub const MAX_TEMPLATE_LEN: usize = 10 * 1024;
+
+/// What a template renders, which decides how values are escaped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateFormat {
+    /// Slack `mrkdwn` text; `&`, `<` and `>` are escaped
+    SlackText,
+    /// A JSON request body; values are escaped for JSON strings
+    Json,
+}
+
+impl TemplateFormat {
+    fn escape(self, value: &str) -> String {
+        match self {
+            TemplateFormat::SlackText => value
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+            TemplateFormat::Json => {
+                let quoted = serde_json::Value::from(value).to_string();
+                quoted[1..quoted.len() - 1].to_string()
+            }
+        }
+    }
+}
+
+fn registry(format: TemplateFormat, strict: bool) -> Handlebars<'static> {
+    let mut registry = Handlebars::new();
+    registry.set_strict_mode(strict);
+    registry.register_escape_fn(move |value| format.escape(value));
+    registry
+}
+
+/// Renders `template` for `payload`
+pub fn render(
+    template: &str,
+    format: TemplateFormat,
+    payload: &AlertPayload,
+) -> Result<String, String> {
+    registry(format, true)
+        .render_template(template, payload)
+        .map_err(|e| e.to_string())
+}
+
+/// Checks a channel's template by rendering the sample alert
+///
+/// The error carries what the template renders to with missing fields left
+/// empty, to show where it went wrong. JSON templates must render valid JSON.
+pub fn validate(template: &str, format: TemplateFormat) -> AppResult<()> {
+    if template.trim().is_empty() {
+        return Err(AppError::Validation("Template cannot be empty".to_string()));
+    }
+    if template.len() > MAX_TEMPLATE_LEN {
+        return Err(AppError::Validation(format!(
+            "Template cannot exceed {} bytes",
+            MAX_TEMPLATE_LEN
+        )));
+    }
+
+    let sample = AlertPayload::sample();
+    let rendered = render(template, format, &sample).map_err(|e| {
+        let lenient = registry(format, false)
+            .render_template(template, &sample)
+            .unwrap_or_default();
+        AppError::Validation(format!(
+            "Invalid template: {}. Sample output: {}",
+            e, lenient
+        ))
+    })?;
+
+    if format == TemplateFormat::Json {
+        if let Err(e) = serde_json::from_str::<serde_json::Value>(&rendered) {
+            return Err(AppError::Validation(format!(
+                "Template must render JSON: {}. Sample output: {}",
+                e, rendered
+            )));
+        }
+    }
+
+    Ok(())
+}
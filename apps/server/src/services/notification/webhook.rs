@@ -1,33 +1,53 @@
 //! Webhook notification dispatcher.
 //!
-//! Sends alerts as HTTP POST requests with JSON payloads.
+//! Sends alerts as HTTP POST (or PUT) requests with JSON payloads.
 //! Supports HMAC-SHA256 signature verification for security.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use chrono::Utc;
 use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderName, HeaderValue};
+use serde_json::{Map, Value};
 use sha2::Sha256;
 
-use super::{NotificationDispatcher, NotificationResult};
+use super::template::{self, TemplateFormat};
+use super::{http, NotificationDispatcher, NotificationResult};
 use crate::error::{AppError, AppResult};
-use crate::models::{AlertPayload, NotificationChannel, WebhookConfig};
+use crate::models::{
+    AlertPayload, NotificationChannel, WebhookConfig, WebhookMethod, WebhookPayloadStyle,
+};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Headers that only concern a single connection, or that the client or the
+/// notifier sets itself; custom headers can't override them
+const RESERVED_HEADERS: &[&str] = &[
+    "connection",
+    "content-length",
+    "content-type",
+    "host",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "x-rustrak-request-id",
+    "x-rustrak-signature",
+    "x-rustrak-timestamp",
+];
+
 /// Webhook notification dispatcher
-pub struct WebhookNotifier {
-    client: reqwest::Client,
-}
+pub struct WebhookNotifier;
 
 impl WebhookNotifier {
     /// Creates a new webhook notifier
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
-        Self { client }
+        Self
     }
 
     /// Generates HMAC-SHA256 signature for webhook payload
@@ -39,6 +59,47 @@ impl WebhookNotifier {
         let result = mac.finalize();
         hex::encode(result.into_bytes())
     }
+
+    /// Flattens the payload to a single object of strings
+    ///
+    /// Nested keys are joined with `_` and list items numbered, e.g.
+    /// `issue_title` or `issues_0_short_id`; nulls become empty strings.
+    pub fn flatten_payload(payload: &AlertPayload) -> serde_json::Result<Map<String, Value>> {
+        let mut flat = Map::new();
+        flatten_into(&mut flat, None, serde_json::to_value(payload)?);
+        Ok(flat)
+    }
+}
+
+fn flatten_into(flat: &mut Map<String, Value>, prefix: Option<&str>, value: Value) {
+    let key = |k: &str| match prefix {
+        Some(prefix) => format!("{}_{}", prefix, k),
+        None => k.to_string(),
+    };
+    match value {
+        Value::Object(object) => {
+            for (k, v) in object {
+                flatten_into(flat, Some(&key(&k)), v);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.into_iter().enumerate() {
+                flatten_into(flat, Some(&key(&i.to_string())), v);
+            }
+        }
+        Value::String(s) => {
+            flat.insert(prefix.unwrap_or_default().to_string(), Value::String(s));
+        }
+        Value::Null => {
+            flat.insert(prefix.unwrap_or_default().to_string(), Value::from(""));
+        }
+        other => {
+            flat.insert(
+                prefix.unwrap_or_default().to_string(),
+                Value::String(other.to_string()),
+            );
+        }
+    }
 }
 
 impl Default for WebhookNotifier {
@@ -58,30 +119,48 @@ impl NotificationDispatcher for WebhookNotifier {
         let config: WebhookConfig = match serde_json::from_value(channel.config.clone()) {
             Ok(c) => c,
             Err(e) => {
-                return NotificationResult::failure(
-                    format!("Invalid webhook config: {}", e),
-                    None,
-                )
+                return NotificationResult::failure(format!("Invalid webhook config: {}", e), None)
             }
         };
 
-        // Serialize payload
-        let body = match serde_json::to_vec(payload) {
-            Ok(b) => b,
-            Err(e) => {
-                return NotificationResult::failure(format!("Failed to serialize payload: {}", e), None)
-            }
+        // Serialize payload, or render the channel's template; a template
+        // that fails to render falls back to the payload
+        let rendered = config.template.as_deref().and_then(|t| {
+            template::render(t, TemplateFormat::Json, payload)
+                .inspect_err(|e| {
+                    log::warn!(
+                        "Webhook template failed for alert {}, sending the payload: {}",
+                        payload.alert_id(),
+                        e
+                    )
+                })
+                .ok()
+        });
+        let body = match rendered {
+            Some(rendered) => rendered.into_bytes(),
+            None => match serialize_payload(payload, config.payload_style) {
+                Ok(b) => b,
+                Err(e) => {
+                    return NotificationResult::failure(
+                        format!("Failed to serialize payload: {}", e),
+                        None,
+                    )
+                }
+            },
         };
 
         let timestamp = Utc::now().timestamp().to_string();
 
         // Build request
-        let mut request = self
-            .client
-            .post(&config.url)
+        let client = http::client(config.verify_tls);
+        let request = match config.method {
+            WebhookMethod::Post => client.post(&config.url),
+            WebhookMethod::Put => client.put(&config.url),
+        };
+        let mut request = request
             .header("Content-Type", "application/json")
             .header("X-Rustrak-Timestamp", &timestamp)
-            .header("X-Rustrak-Request-ID", &payload.alert_id);
+            .header("X-Rustrak-Request-ID", payload.alert_id());
 
         // Add HMAC signature if secret is configured
         if let Some(ref secret) = config.secret {
@@ -89,6 +168,11 @@ impl NotificationDispatcher for WebhookNotifier {
             request = request.header("X-Rustrak-Signature", format!("sha256={}", signature));
         }
 
+        // Per-channel override of the delivery timeout
+        if let Some(secs) = config.timeout_secs {
+            request = request.timeout(Duration::from_secs(secs));
+        }
+
         // Add custom headers
         if let Some(ref headers) = config.headers {
             for (key, value) in headers {
@@ -112,16 +196,7 @@ impl NotificationDispatcher for WebhookNotifier {
                     NotificationResult::failure(error_msg, Some(status))
                 }
             }
-            Err(e) => {
-                let error_msg = if e.is_timeout() {
-                    "Request timed out".to_string()
-                } else if e.is_connect() {
-                    "Connection failed".to_string()
-                } else {
-                    format!("Request failed: {}", e)
-                };
-                NotificationResult::failure(error_msg, None)
-            }
+            Err(e) => http::request_failure(&e),
         }
     }
 
@@ -130,9 +205,7 @@ impl NotificationDispatcher for WebhookNotifier {
             .map_err(|e| AppError::Validation(format!("Invalid webhook config: {}", e)))?;
 
         if webhook_config.url.is_empty() {
-            return Err(AppError::Validation(
-                "Webhook URL is required".to_string(),
-            ));
+            return Err(AppError::Validation("Webhook URL is required".to_string()));
         }
 
         // Validate URL format
@@ -146,10 +219,48 @@ impl NotificationDispatcher for WebhookNotifier {
             ));
         }
 
+        if webhook_config.timeout_secs == Some(0) {
+            return Err(AppError::Validation(
+                "Webhook timeout_secs must be at least 1".to_string(),
+            ));
+        }
+
+        for (name, value) in webhook_config.headers.iter().flatten() {
+            let header = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                AppError::Validation(format!("Invalid webhook header name '{}'", name))
+            })?;
+            if RESERVED_HEADERS.contains(&header.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "Webhook header '{}' can't be set",
+                    name
+                )));
+            }
+            HeaderValue::from_str(value).map_err(|_| {
+                AppError::Validation(format!("Invalid value for webhook header '{}'", name))
+            })?;
+        }
+
+        if let Some(ref template) = webhook_config.template {
+            template::validate(template, TemplateFormat::Json)?;
+        }
+
         Ok(())
     }
 }
 
+/// Serializes the payload in the channel's style
+fn serialize_payload(
+    payload: &AlertPayload,
+    style: WebhookPayloadStyle,
+) -> serde_json::Result<Vec<u8>> {
+    match style {
+        WebhookPayloadStyle::Full => serde_json::to_vec(payload),
+        WebhookPayloadStyle::Flat => {
+            serde_json::to_vec(&WebhookNotifier::flatten_payload(payload)?)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
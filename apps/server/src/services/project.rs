@@ -1,41 +1,84 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use slug::slugify;
 use sqlx::PgPool;
+use tokio::time::Instant;
+use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
-use crate::models::{CreateProject, Project, UpdateProject};
+use crate::db;
+use crate::digest::in_app::MAX_IN_APP_PREFIXES;
+use crate::error::{codes, AppError, AppResult};
+use crate::ingest::origin::validate_allowed_domains;
+use crate::models::{
+    CreateProject, DailyEventCount, EventCounts, Project, ProjectOnboarding, ProjectRecount,
+    ProjectStats, SdkSummary, UpdateProject, User,
+};
 use crate::pagination::SortOrder;
+use crate::services::{IssueService, ProjectKeyService, ProjectMemberService, TeamService};
+
+/// First delay between checks while waiting for a project's first event
+const ONBOARDING_POLL_INITIAL: Duration = Duration::from_millis(250);
+
+/// Longest delay between checks while waiting for a project's first event
+const ONBOARDING_POLL_MAX: Duration = Duration::from_secs(2);
+
+/// Default number of days returned by the daily stats
+pub const DEFAULT_DAILY_STATS_DAYS: i64 = 14;
+
+/// Most days the daily stats can cover
+pub const MAX_DAILY_STATS_DAYS: i64 = 90;
 
 pub struct ProjectService;
 
 impl ProjectService {
-    /// Lists all projects
-    pub async fn list(pool: &PgPool) -> AppResult<Vec<Project>> {
-        let projects = sqlx::query_as::<_, Project>(
+    /// Lists the projects `user` can see: all of them for admins, those
+    /// they're a member of for other users
+    pub async fn list(pool: &PgPool, user: &User) -> AppResult<Vec<Project>> {
+        let query = format!(
             r#"
-            SELECT id, name, slug, sentry_key, stored_event_count,
+            SELECT id, name, slug, stored_event_count,
                    digested_event_count, created_at, updated_at,
-                   quota_exceeded_until, quota_exceeded_reason, next_quota_check
+                   quota_exceeded_until, quota_exceeded_reason, next_quota_check, team_id,
+                   allowed_domains, auto_resolve_days, in_app_include, in_app_exclude,
+                   default_issue_sort, timezone
             FROM projects
+            WHERE {}
             ORDER BY created_at DESC
             "#,
-        )
-        .fetch_all(pool)
-        .await?;
+            member_clause("$1")
+        );
+        let projects = sqlx::query_as::<_, Project>(&query)
+            .bind(ProjectMemberService::member_filter(user))
+            .fetch_all(pool)
+            .await?;
 
         Ok(projects)
     }
 
-    /// Lists projects with offset-based pagination
+    /// Lists the projects `user` can see with offset-based pagination
+    ///
+    /// When `team_id` is set, only projects of that team are returned.
     pub async fn list_offset(
         pool: &PgPool,
+        user: &User,
+        team_id: Option<i32>,
         order: SortOrder,
         page: i64,
         per_page: i64,
     ) -> AppResult<(Vec<Project>, i64)> {
         let offset = (page - 1) * per_page;
+        let member_id = ProjectMemberService::member_filter(user);
 
         // Get total count
-        let total_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM projects")
+        let count_query = format!(
+            "SELECT COUNT(*) FROM projects WHERE ($1::int IS NULL OR team_id = $1) AND {}",
+            member_clause("$2")
+        );
+        let total_count: (i64,) = sqlx::query_as(&count_query)
+            .bind(team_id)
+            .bind(member_id)
             .fetch_one(pool)
             .await?;
 
@@ -47,32 +90,150 @@ impl ProjectService {
 
         let query = format!(
             r#"
-            SELECT id, name, slug, sentry_key, stored_event_count,
+            SELECT id, name, slug, stored_event_count,
                    digested_event_count, created_at, updated_at,
-                   quota_exceeded_until, quota_exceeded_reason, next_quota_check
+                   quota_exceeded_until, quota_exceeded_reason, next_quota_check, team_id,
+                   allowed_domains, auto_resolve_days, in_app_include, in_app_exclude,
+                   default_issue_sort, timezone
             FROM projects
+            WHERE ($3::int IS NULL OR team_id = $3) AND {}
             {}
             LIMIT $1 OFFSET $2
             "#,
+            member_clause("$4"),
             order_clause
         );
 
         let projects = sqlx::query_as::<_, Project>(&query)
             .bind(per_page)
             .bind(offset)
+            .bind(team_id)
+            .bind(member_id)
             .fetch_all(pool)
             .await?;
 
         Ok((projects, total_count.0))
     }
 
+    /// Recomputes a project's event counters, and those of its issues, from
+    /// the events actually stored
+    ///
+    /// Fixes counters that drifted after manual database edits or crashes.
+    /// The project's `digested_event_count` becomes the sum of its issues',
+    /// so events of issues purged from the trash are no longer counted.
+    /// Running it again without new events changes nothing.
+    pub async fn recount(pool: &PgPool, id: i32) -> AppResult<ProjectRecount> {
+        let mut tx = pool.begin().await?;
+        // Counts every event of the project, which takes long on large ones
+        db::disable_statement_timeout(&mut tx).await?;
+
+        // Issues first: that takes the digest lock before the project row
+        let issues = IssueService::recount(&mut tx, id).await?;
+
+        let before = sqlx::query_as::<_, EventCounts>(
+            "SELECT stored_event_count, digested_event_count FROM projects WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Project with id {} not found", id)))?;
+
+        let after = sqlx::query_as::<_, EventCounts>(
+            r#"
+            UPDATE projects
+            SET stored_event_count = (SELECT COUNT(*) FROM events WHERE project_id = $1),
+                digested_event_count = (
+                    SELECT COALESCE(SUM(digested_event_count), 0)
+                    FROM issues WHERE project_id = $1
+                )
+            WHERE id = $1
+            RETURNING stored_event_count, digested_event_count
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(ProjectRecount {
+            project_id: id,
+            before,
+            after,
+            issues,
+        })
+    }
+
+    /// Gets the stats of several projects in one query, keyed by project ID
+    ///
+    /// Every requested project has an entry, with zeroes when it has no
+    /// open issues or recent events.
+    pub async fn stats_for_projects(
+        pool: &PgPool,
+        project_ids: &[i32],
+    ) -> AppResult<HashMap<i32, ProjectStats>> {
+        let rows = sqlx::query_as::<_, (i32, i64, i64)>(
+            r#"
+            WITH open_issues AS (
+                SELECT project_id, COUNT(*) AS count
+                FROM issues
+                WHERE project_id = ANY($1) AND deleted_at IS NULL
+                  AND is_resolved = false AND is_muted = false
+                GROUP BY project_id
+            ),
+            recent_events AS (
+                SELECT project_id, COUNT(*) AS count
+                FROM events
+                WHERE project_id = ANY($1) AND digested_at >= NOW() - INTERVAL '24 hours'
+                GROUP BY project_id
+            )
+            SELECT ids.id, COALESCE(open_issues.count, 0), COALESCE(recent_events.count, 0)
+            FROM UNNEST($1::int[]) AS ids(id)
+            LEFT JOIN open_issues ON open_issues.project_id = ids.id
+            LEFT JOIN recent_events ON recent_events.project_id = ids.id
+            "#,
+        )
+        .bind(project_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, open_issue_count, events_24h)| {
+                (
+                    id,
+                    ProjectStats {
+                        open_issue_count,
+                        events_24h,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Gets the slugs of several projects, keyed by project ID
+    pub async fn slugs_for_projects(
+        pool: &PgPool,
+        project_ids: &[i32],
+    ) -> AppResult<HashMap<i32, String>> {
+        let rows =
+            sqlx::query_as::<_, (i32, String)>("SELECT id, slug FROM projects WHERE id = ANY($1)")
+                .bind(project_ids)
+                .fetch_all(pool)
+                .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
     /// Gets a project by ID
     pub async fn get_by_id(pool: &PgPool, id: i32) -> AppResult<Project> {
         let project = sqlx::query_as::<_, Project>(
             r#"
-            SELECT id, name, slug, sentry_key, stored_event_count,
+            SELECT id, name, slug, stored_event_count,
                    digested_event_count, created_at, updated_at,
-                   quota_exceeded_until, quota_exceeded_reason, next_quota_check
+                   quota_exceeded_until, quota_exceeded_reason, next_quota_check, team_id,
+                   allowed_domains, auto_resolve_days, in_app_include, in_app_exclude,
+                   default_issue_sort, timezone
             FROM projects
             WHERE id = $1
             "#,
@@ -85,77 +246,162 @@ impl ProjectService {
         Ok(project)
     }
 
-    /// Gets a project by sentry_key (for authentication)
+    /// Gets whether a project has digested its first event, and which SDK
+    /// sent it
     ///
-    /// NOTE: Currently unused but kept for future API token scoping feature
-    /// where tokens can be restricted to specific projects via sentry_key lookup.
-    #[allow(dead_code)]
-    pub async fn get_by_sentry_key(pool: &PgPool, sentry_key: &uuid::Uuid) -> AppResult<Project> {
+    /// The counter still says so once retention has deleted the first event,
+    /// but its time and SDK are then unknown.
+    pub async fn onboarding(pool: &PgPool, id: i32) -> AppResult<ProjectOnboarding> {
+        let project = Self::get_by_id(pool, id).await?;
+
+        let first_event: Option<(DateTime<Utc>, String, String)> = sqlx::query_as(
+            r#"
+            SELECT digested_at, sdk_name, sdk_version
+            FROM events
+            WHERE project_id = $1
+            ORDER BY digested_at ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(ProjectOnboarding {
+            received_first_event: project.digested_event_count > 0 || first_event.is_some(),
+            first_event_at: first_event.as_ref().map(|(at, _, _)| *at),
+            sdk: first_event
+                .filter(|(_, name, _)| !name.is_empty())
+                .map(|(_, name, version)| SdkSummary { name, version }),
+        })
+    }
+
+    /// Waits up to `timeout` for a project's first event
+    ///
+    /// Checks again with a growing delay, so each check only holds a
+    /// connection for one query. Returns the state at the deadline if no
+    /// event arrived.
+    pub async fn wait_for_first_event(
+        pool: &PgPool,
+        id: i32,
+        timeout: Duration,
+    ) -> AppResult<ProjectOnboarding> {
+        let deadline = Instant::now() + timeout;
+        let mut delay = ONBOARDING_POLL_INITIAL;
+
+        loop {
+            let onboarding = Self::onboarding(pool, id).await?;
+            let now = Instant::now();
+            if onboarding.received_first_event || now >= deadline {
+                return Ok(onboarding);
+            }
+
+            tokio::time::sleep(delay.min(deadline - now)).await;
+            delay = (delay * 2).min(ONBOARDING_POLL_MAX);
+        }
+    }
+
+    /// Finds the project an active `sentry_key` belongs to
+    ///
+    /// Keys are unique across projects, so the key alone identifies one.
+    pub async fn get_by_key(pool: &PgPool, key: &Uuid) -> AppResult<Option<Project>> {
         let project = sqlx::query_as::<_, Project>(
             r#"
-            SELECT id, name, slug, sentry_key, stored_event_count,
-                   digested_event_count, created_at, updated_at,
-                   quota_exceeded_until, quota_exceeded_reason, next_quota_check
-            FROM projects
-            WHERE sentry_key = $1
+            SELECT p.id, p.name, p.slug, p.stored_event_count,
+                   p.digested_event_count, p.created_at, p.updated_at,
+                   p.quota_exceeded_until, p.quota_exceeded_reason, p.next_quota_check, p.team_id,
+                   p.allowed_domains, p.auto_resolve_days, p.in_app_include, p.in_app_exclude,
+                   p.default_issue_sort, p.timezone
+            FROM projects p
+            JOIN project_keys k ON k.project_id = p.id
+            WHERE k.key = $1 AND k.is_active
             "#,
         )
-        .bind(sentry_key)
+        .bind(key)
         .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+        .await?;
 
         Ok(project)
     }
 
-    /// Creates a new project
+    /// Creates a new project with a default key
+    ///
+    /// Projects created in a team inherit the team's default notification
+    /// channels: they are copied into a new-issue alert rule for the project,
+    /// so later changes to the team defaults don't affect existing projects.
     pub async fn create(pool: &PgPool, input: CreateProject) -> AppResult<Project> {
         // Validate name
         let name = input.name.trim();
         if name.is_empty() {
-            return Err(AppError::Validation("Name cannot be empty".to_string()));
+            return Err(AppError::invalid_field(
+                "name",
+                codes::VALIDATION_REQUIRED,
+                "Name cannot be empty",
+            ));
         }
         if name.len() > 255 {
-            return Err(AppError::Validation(
-                "Name cannot exceed 255 characters".to_string(),
+            return Err(AppError::invalid_field(
+                "name",
+                codes::VALIDATION_TOO_LONG,
+                "Name cannot exceed 255 characters",
             ));
         }
 
         // Generate or validate slug
         let slug = Self::generate_unique_slug(pool, name, input.slug.as_deref()).await?;
 
-        // Insert project (sentry_key is auto-generated in DB)
+        let mut tx = pool.begin().await?;
+
         let project = sqlx::query_as::<_, Project>(
             r#"
-            INSERT INTO projects (name, slug)
-            VALUES ($1, $2)
-            RETURNING id, name, slug, sentry_key, stored_event_count,
+            INSERT INTO projects (name, slug, team_id)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, slug, stored_event_count,
                       digested_event_count, created_at, updated_at,
-                      quota_exceeded_until, quota_exceeded_reason, next_quota_check
+                      quota_exceeded_until, quota_exceeded_reason, next_quota_check, team_id,
+                      allowed_domains, auto_resolve_days, in_app_include, in_app_exclude,
+                      default_issue_sort, timezone
             "#,
         )
         .bind(name)
         .bind(&slug)
-        .fetch_one(pool)
+        .bind(input.team_id)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(ref db_err) = e {
                 if db_err.constraint() == Some("projects_name_key") {
-                    return AppError::Conflict(format!(
-                        "Project with name '{}' already exists",
-                        name
-                    ));
+                    return AppError::field_conflict(
+                        "name",
+                        codes::CONFLICT_NAME_TAKEN,
+                        format!("Project with name '{}' already exists", name),
+                    );
                 }
                 if db_err.constraint() == Some("projects_slug_key") {
-                    return AppError::Conflict(format!(
-                        "Project with slug '{}' already exists",
-                        slug
+                    return AppError::field_conflict(
+                        "slug",
+                        codes::CONFLICT_SLUG_TAKEN,
+                        format!("Project with slug '{}' already exists", slug),
+                    );
+                }
+                if db_err.is_foreign_key_violation() {
+                    return AppError::NotFound(format!(
+                        "Team with id {} not found",
+                        input.team_id.unwrap_or_default()
                     ));
                 }
             }
             AppError::Database(e)
         })?;
 
+        ProjectKeyService::create_default(&mut tx, project.id).await?;
+
+        if let Some(team_id) = project.team_id {
+            TeamService::copy_default_channels(&mut tx, team_id, project.id).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(project)
     }
 
@@ -164,15 +410,127 @@ impl ProjectService {
         // Verify it exists
         Self::get_by_id(pool, id).await?;
 
+        let allowed_domains = input
+            .allowed_domains
+            .as_deref()
+            .map(|domains| validate_allowed_domains(domains, input.confirm_wildcard))
+            .transpose()?;
+
+        let in_app_include = input
+            .in_app_include
+            .as_deref()
+            .map(|prefixes| validate_in_app_prefixes("in_app_include", prefixes))
+            .transpose()?;
+        let in_app_exclude = input
+            .in_app_exclude
+            .as_deref()
+            .map(|prefixes| validate_in_app_prefixes("in_app_exclude", prefixes))
+            .transpose()?;
+
+        // An empty name resets the timezone to UTC
+        let timezone = match input.timezone.as_deref().map(str::trim) {
+            Some("") => Some(None),
+            Some(name) => Some(Some(Self::validate_timezone(pool, name).await?)),
+            None => None,
+        };
+
+        if input.auto_resolve_days.is_some_and(|days| days < 0) {
+            return Err(AppError::invalid_field(
+                "auto_resolve_days",
+                codes::VALIDATION,
+                "auto_resolve_days cannot be negative",
+            ));
+        }
+
+        if let Some(team_id) = input.team_id {
+            sqlx::query("UPDATE projects SET team_id = $1, updated_at = NOW() WHERE id = $2")
+                .bind(team_id)
+                .bind(id)
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    if let sqlx::Error::Database(ref db_err) = e {
+                        if db_err.is_foreign_key_violation() {
+                            return AppError::NotFound(format!(
+                                "Team with id {} not found",
+                                team_id.unwrap_or_default()
+                            ));
+                        }
+                    }
+                    AppError::Database(e)
+                })?;
+        }
+
+        if let Some(allowed_domains) = allowed_domains {
+            sqlx::query(
+                "UPDATE projects SET allowed_domains = $1, updated_at = NOW() WHERE id = $2",
+            )
+            .bind(allowed_domains)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        }
+
+        if in_app_include.is_some() || in_app_exclude.is_some() {
+            sqlx::query(
+                r#"
+                UPDATE projects
+                SET in_app_include = COALESCE($1, in_app_include),
+                    in_app_exclude = COALESCE($2, in_app_exclude),
+                    updated_at = NOW()
+                WHERE id = $3
+                "#,
+            )
+            .bind(in_app_include)
+            .bind(in_app_exclude)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        }
+
+        if let Some(sort) = input.default_issue_sort {
+            sqlx::query(
+                "UPDATE projects SET default_issue_sort = $1, updated_at = NOW() WHERE id = $2",
+            )
+            .bind(sort)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        }
+
+        if let Some(timezone) = timezone {
+            sqlx::query("UPDATE projects SET timezone = $1, updated_at = NOW() WHERE id = $2")
+                .bind(timezone)
+                .bind(id)
+                .execute(pool)
+                .await?;
+        }
+
+        if let Some(days) = input.auto_resolve_days {
+            sqlx::query(
+                "UPDATE projects SET auto_resolve_days = $1, updated_at = NOW() WHERE id = $2",
+            )
+            .bind((days > 0).then_some(days))
+            .bind(id)
+            .execute(pool)
+            .await?;
+        }
+
         // Build query dynamically based on present fields
         if let Some(ref name) = input.name {
             let name = name.trim();
             if name.is_empty() {
-                return Err(AppError::Validation("Name cannot be empty".to_string()));
+                return Err(AppError::invalid_field(
+                    "name",
+                    codes::VALIDATION_REQUIRED,
+                    "Name cannot be empty",
+                ));
             }
             if name.len() > 255 {
-                return Err(AppError::Validation(
-                    "Name cannot exceed 255 characters".to_string(),
+                return Err(AppError::invalid_field(
+                    "name",
+                    codes::VALIDATION_TOO_LONG,
+                    "Name cannot exceed 255 characters",
                 ));
             }
 
@@ -180,9 +538,11 @@ impl ProjectService {
                 r#"
                 UPDATE projects SET name = $1, updated_at = NOW()
                 WHERE id = $2
-                RETURNING id, name, slug, sentry_key, stored_event_count,
+                RETURNING id, name, slug, stored_event_count,
                           digested_event_count, created_at, updated_at,
-                          quota_exceeded_until, quota_exceeded_reason, next_quota_check
+                          quota_exceeded_until, quota_exceeded_reason, next_quota_check, team_id,
+                      allowed_domains, auto_resolve_days, in_app_include, in_app_exclude,
+                      default_issue_sort, timezone
                 "#,
             )
             .bind(name)
@@ -192,10 +552,11 @@ impl ProjectService {
             .map_err(|e| {
                 if let sqlx::Error::Database(ref db_err) = e {
                     if db_err.constraint() == Some("projects_name_key") {
-                        return AppError::Conflict(format!(
-                            "Project with name '{}' already exists",
-                            name
-                        ));
+                        return AppError::field_conflict(
+                            "name",
+                            codes::CONFLICT_NAME_TAKEN,
+                            format!("Project with name '{}' already exists", name),
+                        );
                     }
                 }
                 AppError::Database(e)
@@ -208,6 +569,70 @@ impl ProjectService {
         Self::get_by_id(pool, id).await
     }
 
+    /// Checks that `name` is an IANA timezone known to the database
+    ///
+    /// PostgreSQL's zone list is the one `AT TIME ZONE` buckets with, so a
+    /// name accepted here can always be used in queries.
+    pub async fn validate_timezone(pool: &PgPool, name: &str) -> AppResult<String> {
+        let name = name.trim();
+        let known: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1)")
+                .bind(name)
+                .fetch_one(pool)
+                .await?;
+        // Longer names don't fit the column
+        if !known || name.len() > 64 {
+            return Err(AppError::invalid_field(
+                "timezone",
+                codes::VALIDATION_INVALID_FORMAT,
+                format!(
+                    "Unknown timezone '{}', expected an IANA name like 'Europe/Madrid'",
+                    name
+                ),
+            ));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Events digested per day over the last `days` days in `timezone`,
+    /// oldest first, including days without events
+    ///
+    /// `timezone` must have been checked with [`Self::validate_timezone`].
+    pub async fn daily_event_counts(
+        pool: &PgPool,
+        project_id: i32,
+        timezone: &str,
+        days: i64,
+    ) -> AppResult<Vec<DailyEventCount>> {
+        let counts = sqlx::query_as::<_, DailyEventCount>(
+            r#"
+            WITH bounds AS (
+                SELECT (NOW() AT TIME ZONE $2)::date - ($3::int - 1) AS first_day,
+                       (NOW() AT TIME ZONE $2)::date AS last_day
+            ),
+            counts AS (
+                SELECT (e.digested_at AT TIME ZONE $2)::date AS date, COUNT(*) AS events
+                FROM events e, bounds
+                WHERE e.project_id = $1
+                  AND e.digested_at >= (bounds.first_day::timestamp AT TIME ZONE $2)
+                GROUP BY 1
+            )
+            SELECT days.date::date AS date, COALESCE(counts.events, 0) AS events
+            FROM bounds,
+                 generate_series(bounds.first_day, bounds.last_day, INTERVAL '1 day') AS days(date)
+            LEFT JOIN counts ON counts.date = days.date::date
+            ORDER BY days.date
+            "#,
+        )
+        .bind(project_id)
+        .bind(timezone)
+        .bind(days as i32)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(counts)
+    }
+
     /// Deletes a project (hard delete)
     pub async fn delete(pool: &PgPool, id: i32) -> AppResult<()> {
         let result = sqlx::query("DELETE FROM projects WHERE id = $1")
@@ -237,8 +662,10 @@ impl ProjectService {
         };
 
         if base_slug.is_empty() {
-            return Err(AppError::Validation(
-                "Cannot generate valid slug from name".to_string(),
+            return Err(AppError::invalid_field(
+                "slug",
+                codes::VALIDATION_INVALID_FORMAT,
+                "Cannot generate valid slug from name",
             ));
         }
 
@@ -269,3 +696,43 @@ impl ProjectService {
         }
     }
 }
+
+/// Projects the user bound as `param` is a member of, all of them if it's NULL
+fn member_clause(param: &str) -> String {
+    format!(
+        "({param}::int IS NULL OR EXISTS (
+            SELECT 1 FROM project_members m
+            WHERE m.project_id = projects.id AND m.user_id = {param}
+        ))"
+    )
+}
+
+/// Trims a project's in-app prefixes, dropping empty ones
+fn validate_in_app_prefixes(field: &'static str, prefixes: &[String]) -> AppResult<Vec<String>> {
+    let prefixes: Vec<String> = prefixes
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if prefixes.len() > MAX_IN_APP_PREFIXES {
+        return Err(AppError::invalid_field(
+            field,
+            codes::VALIDATION_TOO_LONG,
+            format!(
+                "{} cannot have more than {} prefixes",
+                field, MAX_IN_APP_PREFIXES
+            ),
+        ));
+    }
+    if prefixes.iter().any(|p| p.len() > 255) {
+        return Err(AppError::invalid_field(
+            field,
+            codes::VALIDATION_TOO_LONG,
+            "Prefixes cannot exceed 255 characters",
+        ));
+    }
+
+    Ok(prefixes)
+}
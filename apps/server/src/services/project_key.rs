@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateProjectKey, ProjectKey};
+
+/// Label of the key every project is created with
+pub const DEFAULT_KEY_LABEL: &str = "Default";
+
+pub struct ProjectKeyService;
+
+impl ProjectKeyService {
+    /// Lists a project's keys, oldest first
+    pub async fn list(pool: &PgPool, project_id: i32) -> AppResult<Vec<ProjectKey>> {
+        let keys = sqlx::query_as::<_, ProjectKey>(
+            r#"
+            SELECT id, project_id, key, label, is_active, created_at, last_used_at
+            FROM project_keys
+            WHERE project_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Lists the keys of several projects, by project id
+    pub async fn list_for_projects(
+        pool: &PgPool,
+        project_ids: &[i32],
+    ) -> AppResult<HashMap<i32, Vec<ProjectKey>>> {
+        let keys = sqlx::query_as::<_, ProjectKey>(
+            r#"
+            SELECT id, project_id, key, label, is_active, created_at, last_used_at
+            FROM project_keys
+            WHERE project_id = ANY($1)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(project_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let mut by_project: HashMap<i32, Vec<ProjectKey>> = HashMap::new();
+        for key in keys {
+            by_project.entry(key.project_id).or_default().push(key);
+        }
+
+        Ok(by_project)
+    }
+
+    /// Gets an active key of a project (for authentication)
+    pub async fn get_active(
+        pool: &PgPool,
+        project_id: i32,
+        key: &Uuid,
+    ) -> AppResult<Option<ProjectKey>> {
+        let result = sqlx::query_as::<_, ProjectKey>(
+            r#"
+            SELECT id, project_id, key, label, is_active, created_at, last_used_at
+            FROM project_keys
+            WHERE project_id = $1 AND key = $2 AND is_active
+            "#,
+        )
+        .bind(project_id)
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Creates a new key for a project
+    pub async fn create(
+        pool: &PgPool,
+        project_id: i32,
+        input: CreateProjectKey,
+    ) -> AppResult<ProjectKey> {
+        let label = match input.label.as_deref().map(str::trim) {
+            Some(label) if !label.is_empty() => label,
+            _ => DEFAULT_KEY_LABEL,
+        };
+        if label.len() > 255 {
+            return Err(AppError::Validation(
+                "Label cannot exceed 255 characters".to_string(),
+            ));
+        }
+
+        let key = sqlx::query_as::<_, ProjectKey>(
+            r#"
+            INSERT INTO project_keys (project_id, label)
+            VALUES ($1, $2)
+            RETURNING id, project_id, key, label, is_active, created_at, last_used_at
+            "#,
+        )
+        .bind(project_id)
+        .bind(label)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e {
+                if db_err.is_foreign_key_violation() {
+                    return AppError::NotFound(format!("Project with id {} not found", project_id));
+                }
+            }
+            AppError::Database(e)
+        })?;
+
+        Ok(key)
+    }
+
+    /// Creates the default key of a newly created project
+    pub async fn create_default(
+        tx: &mut Transaction<'_, Postgres>,
+        project_id: i32,
+    ) -> AppResult<ProjectKey> {
+        let key = sqlx::query_as::<_, ProjectKey>(
+            r#"
+            INSERT INTO project_keys (project_id, label)
+            VALUES ($1, $2)
+            RETURNING id, project_id, key, label, is_active, created_at, last_used_at
+            "#,
+        )
+        .bind(project_id)
+        .bind(DEFAULT_KEY_LABEL)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(key)
+    }
+
+    /// Activates or deactivates a key
+    ///
+    /// Deactivated keys are rejected by ingest from the next request on.
+    pub async fn set_active(
+        pool: &PgPool,
+        project_id: i32,
+        id: i32,
+        is_active: bool,
+    ) -> AppResult<ProjectKey> {
+        sqlx::query_as::<_, ProjectKey>(
+            r#"
+            UPDATE project_keys SET is_active = $1
+            WHERE id = $2 AND project_id = $3
+            RETURNING id, project_id, key, label, is_active, created_at, last_used_at
+            "#,
+        )
+        .bind(is_active)
+        .bind(id)
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Key with id {} not found", id)))
+    }
+
+    /// Deletes a key, returning it
+    pub async fn delete(pool: &PgPool, project_id: i32, id: i32) -> AppResult<ProjectKey> {
+        sqlx::query_as::<_, ProjectKey>(
+            r#"
+            DELETE FROM project_keys
+            WHERE id = $1 AND project_id = $2
+            RETURNING id, project_id, key, label, is_active, created_at, last_used_at
+            "#,
+        )
+        .bind(id)
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Key with id {} not found", id)))
+    }
+
+    /// Updates last_used_at, unless another request already did recently
+    pub async fn update_last_used(pool: &PgPool, id: i32) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE project_keys SET last_used_at = NOW()
+            WHERE id = $1
+              AND (last_used_at IS NULL OR last_used_at < NOW() - make_interval(secs => $2))
+            "#,
+        )
+        .bind(id)
+        .bind(ProjectKey::LAST_USED_INTERVAL.num_seconds() as f64)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
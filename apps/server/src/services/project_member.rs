@@ -0,0 +1,151 @@
+use sqlx::PgPool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{AddProjectMember, ProjectMember, ProjectRole, User};
+use crate::services::UsersService;
+
+pub struct ProjectMemberService;
+
+impl ProjectMemberService {
+    /// Lists a project's members, oldest first
+    pub async fn list(pool: &PgPool, project_id: i32) -> AppResult<Vec<ProjectMember>> {
+        let members = sqlx::query_as::<_, ProjectMember>(
+            r#"
+            SELECT m.project_id, m.user_id, u.email, m.role, m.created_at
+            FROM project_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.project_id = $1
+            ORDER BY m.created_at, m.user_id
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Adds a user to a project
+    pub async fn add(
+        pool: &PgPool,
+        project_id: i32,
+        input: AddProjectMember,
+    ) -> AppResult<ProjectMember> {
+        if UsersService::get_by_id(pool, input.user_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::Validation(format!(
+                "User {} does not exist",
+                input.user_id
+            )));
+        }
+
+        let member = sqlx::query_as::<_, ProjectMember>(
+            r#"
+            WITH inserted AS (
+                INSERT INTO project_members (project_id, user_id, role)
+                VALUES ($1, $2, $3)
+                RETURNING project_id, user_id, role, created_at
+            )
+            SELECT i.project_id, i.user_id, u.email, i.role, i.created_at
+            FROM inserted i
+            JOIN users u ON u.id = i.user_id
+            "#,
+        )
+        .bind(project_id)
+        .bind(input.user_id)
+        .bind(input.role)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e {
+                if db_err.is_unique_violation() {
+                    return AppError::Conflict(format!(
+                        "User {} is already a member of project {}",
+                        input.user_id, project_id
+                    ));
+                }
+                if db_err.is_foreign_key_violation() {
+                    return AppError::NotFound(format!("Project with id {} not found", project_id));
+                }
+            }
+            AppError::Database(e)
+        })?;
+
+        Ok(member)
+    }
+
+    /// Removes a user from a project
+    pub async fn remove(pool: &PgPool, project_id: i32, user_id: i32) -> AppResult<()> {
+        let result =
+            sqlx::query("DELETE FROM project_members WHERE project_id = $1 AND user_id = $2")
+                .bind(project_id)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "User {} is not a member of project {}",
+                user_id, project_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Role of a user in a project, `None` if they aren't a member
+    pub async fn role(
+        pool: &PgPool,
+        project_id: i32,
+        user_id: i32,
+    ) -> AppResult<Option<ProjectRole>> {
+        let role = sqlx::query_scalar::<_, ProjectRole>(
+            "SELECT role FROM project_members WHERE project_id = $1 AND user_id = $2",
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    /// User whose memberships scope project queries, `None` for admins,
+    /// who see every project
+    pub fn member_filter(user: &User) -> Option<i32> {
+        (!user.is_admin).then_some(user.id)
+    }
+
+    /// Fails unless the user can see the project
+    ///
+    /// Projects the user isn't a member of are reported as not found, the
+    /// same as projects that don't exist.
+    pub async fn require_access(pool: &PgPool, user: &User, project_id: i32) -> AppResult<()> {
+        if user.is_admin || Self::role(pool, project_id, user.id).await?.is_some() {
+            return Ok(());
+        }
+        Err(AppError::NotFound(format!(
+            "Project with id {} not found",
+            project_id
+        )))
+    }
+
+    /// Fails unless the user can add and remove the project's members
+    pub async fn require_admin(pool: &PgPool, user: &User, project_id: i32) -> AppResult<()> {
+        if user.is_admin {
+            return Ok(());
+        }
+        match Self::role(pool, project_id, user.id).await? {
+            Some(ProjectRole::Admin) => Ok(()),
+            Some(ProjectRole::Member) => Err(AppError::Forbidden(
+                "Only project admins can manage members".to_string(),
+            )),
+            None => Err(AppError::NotFound(format!(
+                "Project with id {} not found",
+                project_id
+            ))),
+        }
+    }
+}
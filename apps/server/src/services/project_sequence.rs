@@ -0,0 +1,39 @@
+use std::ops::Range;
+
+use sqlx::{Postgres, Transaction};
+
+use crate::error::AppResult;
+
+pub struct ProjectSequenceService;
+
+impl ProjectSequenceService {
+    /// Reserves `count` consecutive issue digest orders of a project
+    ///
+    /// The counter row stays locked until `tx` ends, so concurrent callers
+    /// get disjoint ranges and a rolled back reservation is handed out again.
+    /// A project without a row starts after its highest existing order.
+    pub async fn reserve_issue_orders(
+        tx: &mut Transaction<'_, Postgres>,
+        project_id: i32,
+        count: i32,
+    ) -> AppResult<Range<i32>> {
+        let last: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO project_sequences (project_id, last_issue_order)
+            VALUES (
+                $1,
+                (SELECT COALESCE(MAX(digest_order), 0) FROM issues WHERE project_id = $1) + $2
+            )
+            ON CONFLICT (project_id) DO UPDATE
+            SET last_issue_order = project_sequences.last_issue_order + $2
+            RETURNING last_issue_order
+            "#,
+        )
+        .bind(project_id)
+        .bind(count)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(last - count + 1..last + 1)
+    }
+}
@@ -1,7 +1,7 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
 
-use crate::config::RateLimitConfig;
+use crate::config::{RateLimitAlgorithm, RateLimitConfig};
 use crate::error::AppResult;
 use crate::models::{Installation, Project};
 
@@ -26,6 +26,122 @@ pub enum QuotaScope {
     Project,
 }
 
+/// Events digested in a rate limit window and the one before it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowCounts {
+    pub previous: i64,
+    pub current: i64,
+}
+
+/// A single limit: at most `limit` events per `length`
+///
+/// The trailing algorithm counts the `length` up to now. The fixed and
+/// sliding ones use windows aligned to the Unix epoch, so a minute window
+/// always starts at `:00` and an hour window at the top of the hour.
+#[derive(Debug, Clone, Copy)]
+pub struct RateWindow {
+    pub length: Duration,
+    pub limit: i64,
+}
+
+impl RateWindow {
+    pub fn new(length: Duration, limit: i64) -> Self {
+        Self { length, limit }
+    }
+
+    /// Start of the clock-aligned window containing `now`
+    pub fn start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let length_ms = self.length.num_milliseconds().max(1);
+        let now_ms = now.timestamp_millis();
+        DateTime::from_timestamp_millis(now_ms - now_ms.rem_euclid(length_ms)).unwrap_or(now)
+    }
+
+    /// Start of the window the algorithm counts as current at `now`
+    pub fn current_start(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        now: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        match algorithm {
+            RateLimitAlgorithm::Trailing => now - self.length,
+            RateLimitAlgorithm::Fixed | RateLimitAlgorithm::Sliding => self.start(now),
+        }
+    }
+
+    /// Fraction of the current window that has elapsed at `now`, in `[0, 1)`
+    fn elapsed_fraction(&self, now: DateTime<Utc>) -> f64 {
+        let elapsed = (now - self.start(now)).num_milliseconds() as f64;
+        elapsed / self.length.num_milliseconds().max(1) as f64
+    }
+
+    /// Number of events the algorithm charges against the limit at `now`
+    pub fn estimate(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        counts: WindowCounts,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        match algorithm {
+            RateLimitAlgorithm::Trailing | RateLimitAlgorithm::Fixed => counts.current as f64,
+            RateLimitAlgorithm::Sliding => {
+                counts.previous as f64 * (1.0 - self.elapsed_fraction(now)) + counts.current as f64
+            }
+        }
+    }
+
+    /// Returns when ingest may accept events again if the limit is reached
+    ///
+    /// Like the original per-window check, the limit counts as reached once
+    /// the next event would bring the estimate to `limit`.
+    pub fn exceeded_until(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        counts: WindowCounts,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        // Ingest reopens once the estimate drops below this
+        let threshold = (self.limit - 1) as f64;
+        if self.estimate(algorithm, counts, now) < threshold {
+            return None;
+        }
+
+        let window_end = self.start(now) + self.length;
+        let at_fraction = |start: DateTime<Utc>, fraction: f64| {
+            start
+                + Duration::milliseconds((self.length.num_milliseconds() as f64 * fraction) as i64)
+        };
+
+        let until = match algorithm {
+            RateLimitAlgorithm::Trailing => now + self.length,
+            RateLimitAlgorithm::Fixed => window_end,
+            RateLimitAlgorithm::Sliding => {
+                let current = counts.current as f64;
+                if current < threshold {
+                    // The previous window's share decays enough within this window
+                    let fraction = 1.0 - (threshold - current) / counts.previous as f64;
+                    at_fraction(self.start(now), fraction)
+                } else if threshold > 0.0 {
+                    // This window's events carry over; wait until their share decays
+                    at_fraction(window_end, 1.0 - threshold / current)
+                } else {
+                    window_end + self.length
+                }
+            }
+        };
+        Some(until.max(now))
+    }
+
+    /// Events that can be digested before the limit could possibly be reached
+    pub fn headroom(
+        &self,
+        algorithm: RateLimitAlgorithm,
+        counts: WindowCounts,
+        now: DateTime<Utc>,
+    ) -> i64 {
+        self.limit - self.estimate(algorithm, counts, now).ceil() as i64 - 1
+    }
+}
+
 impl RateLimitService {
     /// Gets the installation singleton
     pub async fn get_installation(pool: &PgPool) -> AppResult<Installation> {
@@ -38,12 +154,27 @@ impl RateLimitService {
 
     /// Checks if quota is exceeded for installation or project (call during ingest)
     /// Returns Some(QuotaExceeded) if rate limited, None if allowed
-    pub async fn check_quota(pool: &PgPool, project: &Project) -> AppResult<Option<QuotaExceeded>> {
+    ///
+    /// The project's quota state is read along with the installation's rather
+    /// than taken from a loaded `Project`, which ingest may have cached.
+    pub async fn check_quota(pool: &PgPool, project_id: i32) -> AppResult<Option<QuotaExceeded>> {
         let now = Utc::now();
 
+        let (installation_until, project_until): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+            sqlx::query_as(
+                r#"
+                SELECT i.quota_exceeded_until, p.quota_exceeded_until
+                FROM installation i
+                LEFT JOIN projects p ON p.id = $1
+                WHERE i.id = 1
+                "#,
+            )
+            .bind(project_id)
+            .fetch_one(pool)
+            .await?;
+
         // 1. Check installation (global) quota
-        let installation = Self::get_installation(pool).await?;
-        if let Some(until) = installation.quota_exceeded_until {
+        if let Some(until) = installation_until {
             if now < until {
                 let retry_after = (until - now).num_seconds().max(1) as u64;
                 return Ok(Some(QuotaExceeded {
@@ -54,7 +185,7 @@ impl RateLimitService {
         }
 
         // 2. Check project quota
-        if let Some(until) = project.quota_exceeded_until {
+        if let Some(until) = project_until {
             if now < until {
                 let retry_after = (until - now).num_seconds().max(1) as u64;
                 return Ok(Some(QuotaExceeded {
@@ -85,39 +216,110 @@ impl RateLimitService {
         Ok(())
     }
 
-    /// Counts events in a time window for the whole installation
-    async fn count_global_events_since(
+    /// Counts events in `window` and the one before it for the whole installation
+    ///
+    /// Only the sliding algorithm uses the previous window, the others don't
+    /// count it.
+    async fn count_global_events(
         pool: &PgPool,
-        since: chrono::DateTime<Utc>,
-    ) -> AppResult<i64> {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE digested_at >= $1")
-            .bind(since)
-            .fetch_one(pool)
-            .await?;
-        Ok(count)
+        window: &RateWindow,
+        algorithm: RateLimitAlgorithm,
+        now: DateTime<Utc>,
+    ) -> AppResult<WindowCounts> {
+        let (current_start, since) = Self::count_range(window, algorithm, now);
+        let (previous, current): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE digested_at < $2),
+                   COUNT(*) FILTER (WHERE digested_at >= $2)
+            FROM events
+            WHERE digested_at >= $1
+            "#,
+        )
+        .bind(since)
+        .bind(current_start)
+        .fetch_one(pool)
+        .await?;
+        Ok(WindowCounts { previous, current })
     }
 
-    /// Counts events in a time window for a specific project
-    async fn count_project_events_since(
+    /// Counts events in `window` and the one before it for a specific project
+    async fn count_project_events(
         pool: &PgPool,
         project_id: i32,
-        since: chrono::DateTime<Utc>,
-    ) -> AppResult<i64> {
-        let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM events WHERE project_id = $1 AND digested_at >= $2",
+        window: &RateWindow,
+        algorithm: RateLimitAlgorithm,
+        now: DateTime<Utc>,
+    ) -> AppResult<WindowCounts> {
+        let (current_start, since) = Self::count_range(window, algorithm, now);
+        let (previous, current): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE digested_at < $3),
+                   COUNT(*) FILTER (WHERE digested_at >= $3)
+            FROM events
+            WHERE project_id = $1 AND digested_at >= $2
+            "#,
         )
         .bind(project_id)
         .bind(since)
+        .bind(current_start)
         .fetch_one(pool)
         .await?;
-        Ok(count)
+        Ok(WindowCounts { previous, current })
+    }
+
+    /// Start of the current window and of the range that needs counting
+    fn count_range(
+        window: &RateWindow,
+        algorithm: RateLimitAlgorithm,
+        now: DateTime<Utc>,
+    ) -> (DateTime<Utc>, DateTime<Utc>) {
+        let current_start = window.current_start(algorithm, now);
+        let since = match algorithm {
+            RateLimitAlgorithm::Trailing | RateLimitAlgorithm::Fixed => current_start,
+            RateLimitAlgorithm::Sliding => current_start - window.length,
+        };
+        (current_start, since)
+    }
+
+    /// Compares the minute and hour counts against their limits
+    ///
+    /// Returns the exceeded state to store and how many events may be digested
+    /// before counting again.
+    fn evaluate(
+        algorithm: RateLimitAlgorithm,
+        minute: (&RateWindow, WindowCounts),
+        hour: (&RateWindow, WindowCounts),
+        now: DateTime<Utc>,
+    ) -> (Option<DateTime<Utc>>, Option<String>, i64) {
+        let (exceeded_until, exceeded_reason) =
+            if let Some(until) = minute.0.exceeded_until(algorithm, minute.1, now) {
+                let reason = serde_json::to_string(&("minute", 1, minute.0.limit))
+                    .expect("tuple serialization should not fail");
+                (Some(until), Some(reason))
+            } else if let Some(until) = hour.0.exceeded_until(algorithm, hour.1, now) {
+                let reason = serde_json::to_string(&("hour", 1, hour.0.limit))
+                    .expect("tuple serialization should not fail");
+                (Some(until), Some(reason))
+            } else {
+                (None, None)
+            };
+
+        // Each digested event raises either estimate by at most one, so the
+        // limit can't be reached before this many more events
+        let check_again_after = minute
+            .0
+            .headroom(algorithm, minute.1, now)
+            .min(hour.0.headroom(algorithm, hour.1, now))
+            .max(1);
+
+        (exceeded_until, exceeded_reason, check_again_after)
     }
 
     /// Updates installation quota state
     async fn update_installation_quota(
         pool: &PgPool,
         config: &RateLimitConfig,
-        now: chrono::DateTime<Utc>,
+        now: DateTime<Utc>,
     ) -> AppResult<()> {
         let installation = Self::get_installation(pool).await?;
 
@@ -132,35 +334,18 @@ impl RateLimitService {
             || (installation.next_quota_check - new_count) > min_threshold;
 
         if should_check {
+            let algorithm = config.algorithm;
+            let minute = RateWindow::new(Duration::minutes(1), config.max_events_per_minute);
+            let hour = RateWindow::new(Duration::hours(1), config.max_events_per_hour);
+
             // Count events in each window (parallel queries)
             let (count_minute, count_hour) = tokio::try_join!(
-                Self::count_global_events_since(pool, now - Duration::minutes(1)),
-                Self::count_global_events_since(pool, now - Duration::hours(1))
+                Self::count_global_events(pool, &minute, algorithm, now),
+                Self::count_global_events(pool, &hour, algorithm, now)
             )?;
 
-            // Check which thresholds are exceeded
-            let (exceeded_until, exceeded_reason) = if count_minute + 1
-                >= config.max_events_per_minute
-            {
-                // Exceeded per-minute limit
-                let until = now + Duration::minutes(1);
-                let reason = serde_json::to_string(&("minute", 1, config.max_events_per_minute))
-                    .expect("tuple serialization should not fail");
-                (Some(until), Some(reason))
-            } else if count_hour + 1 >= config.max_events_per_hour {
-                // Exceeded per-hour limit
-                let until = now + Duration::hours(1);
-                let reason = serde_json::to_string(&("hour", 1, config.max_events_per_hour))
-                    .expect("tuple serialization should not fail");
-                (Some(until), Some(reason))
-            } else {
-                (None, None)
-            };
-
-            // Calculate when to check again
-            let check_again_after = (config.max_events_per_minute - count_minute - 1)
-                .min(config.max_events_per_hour - count_hour - 1)
-                .max(1);
+            let (exceeded_until, exceeded_reason, check_again_after) =
+                Self::evaluate(algorithm, (&minute, count_minute), (&hour, count_hour), now);
 
             sqlx::query(
                 r#"
@@ -194,7 +379,7 @@ impl RateLimitService {
         pool: &PgPool,
         project_id: i32,
         config: &RateLimitConfig,
-        now: chrono::DateTime<Utc>,
+        now: DateTime<Utc>,
     ) -> AppResult<()> {
         // Get current project state
         let project: Project = sqlx::query_as("SELECT * FROM projects WHERE id = $1")
@@ -215,35 +400,21 @@ impl RateLimitService {
             || (project.next_quota_check - new_count) > min_threshold;
 
         if should_check {
+            let algorithm = config.algorithm;
+            let minute = RateWindow::new(
+                Duration::minutes(1),
+                config.max_events_per_project_per_minute,
+            );
+            let hour = RateWindow::new(Duration::hours(1), config.max_events_per_project_per_hour);
+
             // Count events in each window (parallel queries)
             let (count_minute, count_hour) = tokio::try_join!(
-                Self::count_project_events_since(pool, project_id, now - Duration::minutes(1)),
-                Self::count_project_events_since(pool, project_id, now - Duration::hours(1))
+                Self::count_project_events(pool, project_id, &minute, algorithm, now),
+                Self::count_project_events(pool, project_id, &hour, algorithm, now)
             )?;
 
-            // Check which thresholds are exceeded
-            let (exceeded_until, exceeded_reason) = if count_minute + 1
-                >= config.max_events_per_project_per_minute
-            {
-                let until = now + Duration::minutes(1);
-                let reason =
-                    serde_json::to_string(&("minute", 1, config.max_events_per_project_per_minute))
-                        .expect("tuple serialization should not fail");
-                (Some(until), Some(reason))
-            } else if count_hour + 1 >= config.max_events_per_project_per_hour {
-                let until = now + Duration::hours(1);
-                let reason =
-                    serde_json::to_string(&("hour", 1, config.max_events_per_project_per_hour))
-                        .expect("tuple serialization should not fail");
-                (Some(until), Some(reason))
-            } else {
-                (None, None)
-            };
-
-            // Calculate when to check again
-            let check_again_after = (config.max_events_per_project_per_minute - count_minute - 1)
-                .min(config.max_events_per_project_per_hour - count_hour - 1)
-                .max(1);
+            let (exceeded_until, exceeded_reason, check_again_after) =
+                Self::evaluate(algorithm, (&minute, count_minute), (&hour, count_hour), now);
 
             sqlx::query(
                 r#"
@@ -0,0 +1,867 @@
+//! Regrouping a project's events after the grouping algorithm changed.
+//!
+//! Events digested before a change to [`calculate_grouping_key`] keep the
+//! issue their old key picked, so old and new events of the same error can
+//! end up in separate issues. A regroup recomputes the key of every stored
+//! event and moves it to the issue of its new key: issues whose events now
+//! share a key merge, and an issue whose events no longer do splits.
+//!
+//! Applying it moves events in batches, each in a transaction that also
+//! advances the project's row in `regroup_checkpoints`, so an interrupted run
+//! continues where it stopped.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::digest::extract_contexts;
+use crate::error::AppResult;
+use crate::models::IssuePriority;
+use crate::services::grouping::{get_title, get_type_and_value};
+use crate::services::{
+    calculate_grouping_key, get_denormalized_fields, hash_grouping_key, ProjectSequenceService,
+};
+
+/// Events read per query, and moved per transaction
+const BATCH_SIZE: i64 = 1000;
+
+/// Issue the events of a grouping key end up in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegroupTarget {
+    Issue {
+        id: Uuid,
+        digest_order: i32,
+    },
+    /// Created for the key, titled like its latest event
+    New {
+        title: String,
+    },
+}
+
+/// Events of an issue that move to `target`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegroupMove {
+    pub target: RegroupTarget,
+    pub events: i64,
+}
+
+/// How the events of one issue are regrouped
+#[derive(Debug, Clone)]
+pub struct IssueRegroup {
+    pub issue_id: Uuid,
+    pub digest_order: i32,
+    pub title: String,
+    /// Events with a payload, which are regrouped
+    pub events: i64,
+    /// Events that stay in the issue
+    pub staying: i64,
+    /// Largest first
+    pub moves: Vec<RegroupMove>,
+    /// Issue receiving all of its events; the issue is deleted afterwards
+    pub merged_into: Option<RegroupTarget>,
+}
+
+/// What regrouping a project would change
+#[derive(Debug, Clone, Default)]
+pub struct RegroupPlan {
+    /// Events whose key was recomputed
+    pub events: i64,
+    /// Events whose payload was pruned; they stay where they are
+    pub pruned_events: i64,
+    /// Issues with events moving elsewhere, by digest order
+    pub issues: Vec<IssueRegroup>,
+    pub new_issues: usize,
+}
+
+impl RegroupPlan {
+    pub fn moved_events(&self) -> i64 {
+        self.issues
+            .iter()
+            .flat_map(|issue| &issue.moves)
+            .map(|m| m.events)
+            .sum()
+    }
+
+    pub fn merged_issues(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.merged_into.is_some())
+            .count()
+    }
+
+    /// Issues whose events end up in more than one issue
+    pub fn split_issues(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.moves.len() + (issue.staying > 0) as usize > 1)
+            .count()
+    }
+}
+
+/// Result of applying a regroup
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegroupOutcome {
+    pub moved_events: i64,
+    pub new_issues: usize,
+    pub deleted_issues: u64,
+    /// Whether an interrupted run was continued
+    pub resumed: bool,
+}
+
+/// Grouping key of the stored events, and where they go
+struct KeyPlan {
+    key: String,
+    events: i64,
+    /// Issues with events of the key, and how many
+    sources: Vec<(Uuid, i64)>,
+    /// Latest event of the key, which titles a new issue
+    latest: (DateTime<Utc>, Uuid),
+    title: String,
+    target: Option<Uuid>,
+}
+
+/// Plan with the state applying it needs
+struct PlanState {
+    plan: RegroupPlan,
+    keys: HashMap<String, KeyPlan>,
+    /// Keys that already have a grouping
+    grouped: HashSet<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredEvent {
+    id: Uuid,
+    issue_id: Uuid,
+    data: Value,
+    timestamp: DateTime<Utc>,
+}
+
+pub struct RegroupService;
+
+impl RegroupService {
+    /// Recomputes the grouping key of the project's events and tells how
+    /// their issues would merge and split, without changing anything
+    pub async fn plan(pool: &PgPool, project_id: i32) -> AppResult<RegroupPlan> {
+        Ok(Self::build_plan(pool, project_id).await?.plan)
+    }
+
+    /// Whether an interrupted regroup of the project is waiting to be
+    /// continued, and how many events it had moved
+    pub async fn checkpoint(pool: &PgPool, project_id: i32) -> AppResult<Option<i64>> {
+        let moved: Option<i32> = sqlx::query_scalar(
+            "SELECT moved_events FROM regroup_checkpoints WHERE project_id = $1",
+        )
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(moved.map(i64::from))
+    }
+
+    /// Regroups the project's events, continuing an interrupted run if any
+    ///
+    /// New issues get their fields from their latest event, with values cut
+    /// to `max_value_length`.
+    pub async fn apply(
+        pool: &PgPool,
+        project_id: i32,
+        max_value_length: usize,
+    ) -> AppResult<RegroupOutcome> {
+        let mut outcome = RegroupOutcome::default();
+
+        if Self::checkpoint(pool, project_id).await?.is_some() {
+            outcome.resumed = true;
+            log::info!(
+                "Continuing the interrupted regroup of project {}",
+                project_id
+            );
+        } else {
+            let state = Self::build_plan(pool, project_id).await?;
+            log::info!(
+                "Regrouping project {}: {} of {} events move, {} new issues",
+                project_id,
+                state.plan.moved_events(),
+                state.plan.events,
+                state.plan.new_issues
+            );
+            outcome.new_issues = Self::prepare(pool, project_id, state, max_value_length).await?;
+        }
+
+        outcome.moved_events = Self::move_events(pool, project_id).await?;
+        outcome.deleted_issues = Self::finish(pool, project_id).await?;
+
+        Ok(outcome)
+    }
+
+    /// Reads every stored event of the project and picks the issue of each
+    /// recomputed key
+    ///
+    /// A key that has a grouping goes to its issue. Otherwise the issue with
+    /// most of the key's events keeps them, unless it already keeps another
+    /// key; then the key gets a new issue.
+    async fn build_plan(pool: &PgPool, project_id: i32) -> AppResult<PlanState> {
+        let issues: HashMap<Uuid, (i32, String)> =
+            sqlx::query_as::<_, (Uuid, i32, String, String)>(
+                r#"
+            SELECT id, digest_order, calculated_type, calculated_value
+            FROM issues
+            WHERE project_id = $1 AND deleted_at IS NULL
+            "#,
+            )
+            .bind(project_id)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|(id, order, kind, value)| (id, (order, get_title(&kind, &value))))
+            .collect();
+
+        // Issues keeping events that can't be regrouped can't be merged away
+        let pruned: Vec<(Uuid, i64)> = sqlx::query_as(
+            r#"
+            SELECT e.issue_id, COUNT(*)
+            FROM events e
+            JOIN issues i ON i.id = e.issue_id
+            WHERE e.project_id = $1 AND e.payload_pruned AND i.deleted_at IS NULL
+            GROUP BY e.issue_id
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut plan = RegroupPlan {
+            pruned_events: pruned.iter().map(|(_, count)| count).sum(),
+            ..Default::default()
+        };
+        let pinned: HashSet<Uuid> = pruned.into_iter().map(|(id, _)| id).collect();
+
+        let mut keys: HashMap<String, KeyPlan> = HashMap::new();
+        let mut after = Uuid::nil();
+        loop {
+            let events = stored_events(pool, project_id, after).await?;
+            for event in &events {
+                let key = calculate_grouping_key(&event.data);
+                let hash = hash_grouping_key(&key);
+                let entry = keys.entry(hash).or_insert_with(|| KeyPlan {
+                    key,
+                    events: 0,
+                    sources: Vec::new(),
+                    latest: (DateTime::<Utc>::MIN_UTC, Uuid::nil()),
+                    title: String::new(),
+                    target: None,
+                });
+
+                entry.events += 1;
+                match entry
+                    .sources
+                    .iter_mut()
+                    .find(|(id, _)| *id == event.issue_id)
+                {
+                    Some((_, count)) => *count += 1,
+                    None => entry.sources.push((event.issue_id, 1)),
+                }
+                if (event.timestamp, event.id) > entry.latest {
+                    entry.latest = (event.timestamp, event.id);
+                    let (kind, value) = get_type_and_value(&event.data);
+                    entry.title = get_title(&kind, &value);
+                }
+            }
+            plan.events += events.len() as i64;
+
+            match events.last() {
+                Some(event) if events.len() as i64 == BATCH_SIZE => after = event.id,
+                _ => break,
+            }
+        }
+
+        let groupings: HashMap<String, Uuid> = sqlx::query_as(
+            r#"
+            SELECT grouping_key_hash, issue_id FROM groupings
+            WHERE project_id = $1 AND detached_at IS NULL
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut claimed = HashSet::new();
+        let mut grouped = HashSet::new();
+        for (hash, key) in keys.iter_mut() {
+            if let Some(issue_id) = groupings.get(hash) {
+                key.target = Some(*issue_id);
+                claimed.insert(*issue_id);
+                grouped.insert(hash.clone());
+            }
+        }
+
+        // Largest keys pick first; ties go to the oldest issue
+        let mut ungrouped: Vec<&mut KeyPlan> = keys
+            .values_mut()
+            .filter(|key| key.target.is_none())
+            .collect();
+        ungrouped.sort_by(|a, b| b.events.cmp(&a.events).then_with(|| a.key.cmp(&b.key)));
+        for key in ungrouped {
+            key.sources.sort_by_key(|(id, count)| {
+                (-count, issues.get(id).map_or(i32::MAX, |(order, _)| *order))
+            });
+            key.target = key
+                .sources
+                .iter()
+                .map(|(id, _)| *id)
+                .find(|id| !claimed.contains(id));
+            match key.target {
+                Some(id) => {
+                    claimed.insert(id);
+                }
+                None => plan.new_issues += 1,
+            }
+        }
+
+        // Moves of each issue, merged by target
+        let mut moves: HashMap<Uuid, Vec<RegroupMove>> = HashMap::new();
+        let mut staying: HashMap<Uuid, i64> = HashMap::new();
+        let mut totals: HashMap<Uuid, i64> = HashMap::new();
+        for key in keys.values() {
+            let target = match key.target {
+                Some(id) => RegroupTarget::Issue {
+                    id,
+                    digest_order: issues.get(&id).map_or(0, |(order, _)| *order),
+                },
+                None => RegroupTarget::New {
+                    title: key.title.clone(),
+                },
+            };
+            for (issue_id, count) in &key.sources {
+                *totals.entry(*issue_id).or_default() += count;
+                if key.target == Some(*issue_id) {
+                    *staying.entry(*issue_id).or_default() += count;
+                    continue;
+                }
+                let issue_moves = moves.entry(*issue_id).or_default();
+                match issue_moves.iter_mut().find(|m| m.target == target) {
+                    Some(m) => m.events += count,
+                    None => issue_moves.push(RegroupMove {
+                        target: target.clone(),
+                        events: *count,
+                    }),
+                }
+            }
+        }
+
+        for (issue_id, mut issue_moves) in moves {
+            let Some((digest_order, title)) = issues.get(&issue_id).cloned() else {
+                continue;
+            };
+            issue_moves.sort_by_key(|m| std::cmp::Reverse(m.events));
+            let staying = staying.get(&issue_id).copied().unwrap_or(0);
+            let merged_into =
+                (staying == 0 && !pinned.contains(&issue_id) && !claimed.contains(&issue_id))
+                    .then(|| issue_moves[0].target.clone());
+
+            plan.issues.push(IssueRegroup {
+                issue_id,
+                digest_order,
+                title,
+                events: totals[&issue_id],
+                staying,
+                moves: issue_moves,
+                merged_into,
+            });
+        }
+        plan.issues.sort_by_key(|issue| issue.digest_order);
+
+        Ok(PlanState {
+            plan,
+            keys,
+            grouped,
+        })
+    }
+
+    /// Creates the new issues and the groupings of the recomputed keys, and
+    /// starts the checkpoint
+    ///
+    /// Returns the number of issues created.
+    async fn prepare(
+        pool: &PgPool,
+        project_id: i32,
+        state: PlanState,
+        max_value_length: usize,
+    ) -> AppResult<usize> {
+        let mut tx = lock_project(pool, project_id).await?;
+
+        // Events digested while planning may have grouped some keys already
+        let hashes: Vec<String> = state.keys.keys().cloned().collect();
+        let grouped: HashSet<String> = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT grouping_key_hash FROM groupings
+            WHERE project_id = $1 AND detached_at IS NULL AND grouping_key_hash = ANY($2)
+            "#,
+        )
+        .bind(project_id)
+        .bind(&hashes)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .chain(state.grouped)
+        .collect();
+
+        let mut created = 0;
+        for (hash, key) in &state.keys {
+            if grouped.contains(hash) {
+                continue;
+            }
+            let issue_id = match key.target {
+                Some(id) => id,
+                None => {
+                    created += 1;
+                    create_issue(&mut tx, project_id, key.latest.1, max_value_length).await?
+                }
+            };
+            sqlx::query(
+                r#"
+                INSERT INTO groupings (project_id, issue_id, grouping_key, grouping_key_hash)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(project_id)
+            .bind(issue_id)
+            .bind(&key.key)
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let merges: serde_json::Map<String, Value> = state
+            .plan
+            .issues
+            .iter()
+            .filter_map(|issue| match issue.merged_into {
+                Some(RegroupTarget::Issue { id, .. }) => {
+                    Some((issue.issue_id.to_string(), Value::String(id.to_string())))
+                }
+                _ => None,
+            })
+            .collect();
+        sqlx::query("INSERT INTO regroup_checkpoints (project_id, merges) VALUES ($1, $2)")
+            .bind(project_id)
+            .bind(Value::Object(merges))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// Moves each event to the issue of its recomputed key, one batch per
+    /// transaction, from the checkpoint on
+    ///
+    /// Returns the number of events moved in total, including by earlier runs.
+    async fn move_events(pool: &PgPool, project_id: i32) -> AppResult<i64> {
+        let mut groupings: HashMap<String, (i32, Uuid)> = HashMap::new();
+        let mut checked = 0;
+
+        loop {
+            let mut tx = lock_project(pool, project_id).await?;
+            let (after, moved): (Option<Uuid>, i32) = sqlx::query_as(
+                r#"
+                SELECT last_event_id, moved_events FROM regroup_checkpoints
+                WHERE project_id = $1
+                FOR UPDATE
+                "#,
+            )
+            .bind(project_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let events = stored_events(&mut *tx, project_id, after.unwrap_or(Uuid::nil())).await?;
+            let Some(last) = events.last() else {
+                return Ok(moved.into());
+            };
+            let last_id = last.id;
+
+            let mut moves = Vec::new();
+            for event in &events {
+                let hash = hash_grouping_key(&calculate_grouping_key(&event.data));
+                if !groupings.contains_key(&hash) {
+                    let grouping: Option<(i32, Uuid)> = sqlx::query_as(
+                        r#"
+                        SELECT id, issue_id FROM groupings
+                        WHERE project_id = $1 AND grouping_key_hash = $2 AND detached_at IS NULL
+                        "#,
+                    )
+                    .bind(project_id)
+                    .bind(&hash)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+                    // Left in place if the key has no issue to go to
+                    let Some(grouping) = grouping else {
+                        continue;
+                    };
+                    groupings.insert(hash.clone(), grouping);
+                }
+                let (grouping_id, target) = groupings[&hash];
+                if target != event.issue_id {
+                    moves.push((event, grouping_id, target));
+                }
+            }
+
+            move_batch(&mut tx, &moves).await?;
+
+            sqlx::query(
+                r#"
+                UPDATE regroup_checkpoints
+                SET last_event_id = $2, moved_events = moved_events + $3, updated_at = NOW()
+                WHERE project_id = $1
+                "#,
+            )
+            .bind(project_id)
+            .bind(last_id)
+            .bind(moves.len() as i32)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            checked += events.len();
+            log::info!(
+                "Regrouping project {}: checked {} events, moved {}",
+                project_id,
+                checked,
+                i64::from(moved) + moves.len() as i64
+            );
+        }
+    }
+
+    /// Deletes the issues all of whose events moved, handing their groupings
+    /// and sampled-out event counts to the issue they merged into, and ends
+    /// the checkpoint
+    ///
+    /// Returns the number of issues deleted.
+    async fn finish(pool: &PgPool, project_id: i32) -> AppResult<u64> {
+        let mut tx = lock_project(pool, project_id).await?;
+        let merges: Value = sqlx::query_scalar(
+            "DELETE FROM regroup_checkpoints WHERE project_id = $1 RETURNING merges",
+        )
+        .bind(project_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut deleted = 0;
+        for (source, target) in merges.as_object().into_iter().flatten() {
+            let (Ok(source), Some(Ok(target))) = (
+                source.parse::<Uuid>(),
+                target.as_str().map(str::parse::<Uuid>),
+            ) else {
+                continue;
+            };
+
+            // Events digested since planning keep the issue alive
+            let emptied: bool =
+                sqlx::query_scalar("SELECT NOT EXISTS (SELECT 1 FROM events WHERE issue_id = $1)")
+                    .bind(source)
+                    .fetch_one(&mut *tx)
+                    .await?;
+            if !emptied {
+                continue;
+            }
+
+            // Its groupings would go with it, and events still sent with
+            // their keys would open a new issue
+            sqlx::query("UPDATE groupings SET issue_id = $2 WHERE issue_id = $1")
+                .bind(source)
+                .bind(target)
+                .execute(&mut *tx)
+                .await?;
+
+            let removed: Option<(i32, bool)> = sqlx::query_as(
+                r#"
+                DELETE FROM issues
+                WHERE id = $1
+                RETURNING digested_event_count, is_unhandled
+                "#,
+            )
+            .bind(source)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some((unstored, is_unhandled)) = removed else {
+                continue;
+            };
+            deleted += 1;
+
+            sqlx::query(
+                r#"
+                UPDATE issues
+                SET digested_event_count = digested_event_count + $2,
+                    is_unhandled = is_unhandled OR $3
+                WHERE id = $1
+                "#,
+            )
+            .bind(target)
+            .bind(unstored)
+            .bind(is_unhandled)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(deleted)
+    }
+}
+
+/// Starts a transaction holding the project's advisory lock, which digesting
+/// takes to create issues and groupings
+async fn lock_project(pool: &PgPool, project_id: i32) -> AppResult<Transaction<'static, Postgres>> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(project_id as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(tx)
+}
+
+/// Next batch of the project's events with a payload, in id order, skipping
+/// those of issues in the trash
+async fn stored_events<'e, E>(
+    executor: E,
+    project_id: i32,
+    after: Uuid,
+) -> AppResult<Vec<StoredEvent>>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let events = sqlx::query_as(
+        r#"
+        SELECT e.id, e.issue_id, e.data, e.timestamp
+        FROM events e
+        JOIN issues i ON i.id = e.issue_id
+        WHERE e.project_id = $1 AND e.id > $2
+          AND NOT e.payload_pruned AND i.deleted_at IS NULL
+        ORDER BY e.id
+        LIMIT $3
+        "#,
+    )
+    .bind(project_id)
+    .bind(after)
+    .bind(BATCH_SIZE)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(events)
+}
+
+/// Creates an empty issue titled like `event_id`; the moved events fill in
+/// its counters
+async fn create_issue(
+    tx: &mut Transaction<'_, Postgres>,
+    project_id: i32,
+    event_id: Uuid,
+    max_value_length: usize,
+) -> AppResult<Uuid> {
+    let (data, timestamp, level, platform): (Value, DateTime<Utc>, String, String) =
+        sqlx::query_as("SELECT data, timestamp, level, platform FROM events WHERE id = $1")
+            .bind(event_id)
+            .fetch_one(&mut **tx)
+            .await?;
+    let denormalized = get_denormalized_fields(&data).capped(max_value_length);
+    let digest_order = ProjectSequenceService::reserve_issue_orders(tx, project_id, 1)
+        .await?
+        .start;
+
+    let issue_id = sqlx::query_scalar(
+        r#"
+        INSERT INTO issues (
+            project_id, digest_order, first_seen, last_seen,
+            digested_event_count, stored_event_count, last_event_order,
+            calculated_type, calculated_value, transaction,
+            last_frame_filename, last_frame_module, last_frame_function,
+            level, platform, exception_chain_length, is_unhandled, priority
+        )
+        VALUES ($1, $2, $3, $3, 0, 0, 0, $4, $5, $6, $7, $8, $9, $10, NULLIF($11, ''), $12, $13, $14)
+        RETURNING id
+        "#,
+    )
+    .bind(project_id)
+    .bind(digest_order)
+    .bind(timestamp)
+    .bind(&denormalized.calculated_type)
+    .bind(&denormalized.calculated_value)
+    .bind(&denormalized.transaction)
+    .bind(&denormalized.last_frame_filename)
+    .bind(&denormalized.last_frame_module)
+    .bind(&denormalized.last_frame_function)
+    .bind(&level)
+    .bind(&platform)
+    .bind(denormalized.exception_chain_length)
+    .bind(denormalized.is_unhandled)
+    .bind(IssuePriority::from_event(Some(&level), denormalized.is_unhandled))
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(issue_id)
+}
+
+/// Moves events to their new issue and grouping, carrying their counts and
+/// context values along
+///
+/// Moved events are numbered after the target's latest event.
+async fn move_batch(
+    tx: &mut Transaction<'_, Postgres>,
+    moves: &[(&StoredEvent, i32, Uuid)],
+) -> AppResult<()> {
+    if moves.is_empty() {
+        return Ok(());
+    }
+
+    let mut by_target: BTreeMap<Uuid, Vec<&(&StoredEvent, i32, Uuid)>> = BTreeMap::new();
+    let mut by_source: BTreeMap<Uuid, i32> = BTreeMap::new();
+    let mut contexts: BTreeMap<(Uuid, &'static str, String), i32> = BTreeMap::new();
+    for m in moves {
+        let (event, _, target) = m;
+        by_target.entry(*target).or_default().push(m);
+        *by_source.entry(event.issue_id).or_default() += 1;
+        for (dimension, value) in extract_contexts(&event.data) {
+            *contexts
+                .entry((event.issue_id, dimension.as_str(), value.clone()))
+                .or_default() -= 1;
+            *contexts
+                .entry((*target, dimension.as_str(), value))
+                .or_default() += 1;
+        }
+    }
+
+    let mut ids = Vec::with_capacity(moves.len());
+    let mut issue_ids = Vec::with_capacity(moves.len());
+    let mut grouping_ids = Vec::with_capacity(moves.len());
+    let mut orders = Vec::with_capacity(moves.len());
+    for (target, mut events) in by_target {
+        events.sort_by_key(|(event, _, _)| (event.timestamp, event.id));
+        let count = events.len() as i32;
+        let first = events[0].0.timestamp;
+        let last = events[events.len() - 1].0.timestamp;
+
+        let last_order: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE issues
+            SET last_event_order = last_event_order + $2,
+                digested_event_count = digested_event_count + $2,
+                stored_event_count = stored_event_count + $2,
+                first_seen = LEAST(first_seen, $3),
+                last_seen = GREATEST(last_seen, $4)
+            WHERE id = $1
+            RETURNING last_event_order
+            "#,
+        )
+        .bind(target)
+        .bind(count)
+        .bind(first)
+        .bind(last)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        for (order, (event, grouping_id, _)) in (last_order - count + 1..).zip(events) {
+            ids.push(event.id);
+            issue_ids.push(target);
+            grouping_ids.push(*grouping_id);
+            orders.push(order);
+        }
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE events e
+        SET issue_id = m.issue_id, grouping_id = m.grouping_id, digest_order = m.digest_order
+        FROM UNNEST($1::uuid[], $2::uuid[], $3::int[], $4::int[])
+            AS m(id, issue_id, grouping_id, digest_order)
+        WHERE e.id = m.id
+        "#,
+    )
+    .bind(&ids)
+    .bind(&issue_ids)
+    .bind(&grouping_ids)
+    .bind(&orders)
+    .execute(&mut **tx)
+    .await?;
+
+    // Issues that lost events; first and last seen are only recomputed when
+    // every digested event is still stored
+    let sources: Vec<Uuid> = by_source.keys().copied().collect();
+    let counts: Vec<i32> = by_source.values().copied().collect();
+    sqlx::query(
+        r#"
+        UPDATE issues i
+        SET digested_event_count = i.digested_event_count - m.count,
+            stored_event_count = i.stored_event_count - m.count
+        FROM UNNEST($1::uuid[], $2::int[]) AS m(id, count)
+        WHERE i.id = m.id
+        "#,
+    )
+    .bind(&sources)
+    .bind(&counts)
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE issues i
+        SET first_seen = s.first_seen, last_seen = s.last_seen
+        FROM (
+            SELECT issue_id, MIN(timestamp) AS first_seen, MAX(timestamp) AS last_seen
+            FROM events
+            WHERE issue_id = ANY($1)
+            GROUP BY issue_id
+        ) s
+        WHERE i.id = s.issue_id AND i.stored_event_count = i.digested_event_count
+        "#,
+    )
+    .bind(&sources)
+    .execute(&mut **tx)
+    .await?;
+
+    // Both sides may have a new latest event
+    let touched: Vec<Uuid> = sources.iter().chain(&issue_ids).copied().collect();
+    sqlx::query(
+        r#"
+        UPDATE issues i
+        SET last_event_id = (
+            SELECT e.id FROM events e
+            WHERE e.issue_id = i.id
+            ORDER BY e.digest_order DESC
+            LIMIT 1
+        )
+        WHERE i.id = ANY($1)
+        "#,
+    )
+    .bind(&touched)
+    .execute(&mut **tx)
+    .await?;
+
+    for ((issue_id, dimension, value), delta) in contexts {
+        if delta == 0 {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO issue_contexts (issue_id, dimension, value, count)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (issue_id, dimension, value)
+            DO UPDATE SET count = issue_contexts.count + $4
+            "#,
+        )
+        .bind(issue_id)
+        .bind(dimension)
+        .bind(&value)
+        .bind(delta)
+        .execute(&mut **tx)
+        .await?;
+    }
+    sqlx::query("DELETE FROM issue_contexts WHERE issue_id = ANY($1) AND count <= 0")
+        .bind(&sources)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Background job that purges data past its retention window.
+//!
+//! Runs once at startup and then every [`RETENTION_INTERVAL`]. Issues that
+//! have been in the trash longer than `ISSUE_TRASH_DAYS` are deleted for good,
+//! their events and groupings with them. Ingested event ids are forgotten
+//! after [`INGESTED_EVENT_RETENTION_DAYS`]. With `PAYLOAD_RETENTION_DAYS`,
+//! older events lose their raw payload but keep their metadata.
+//!
+//! Minidumps are part of their event's payload: they are deleted once the
+//! event is gone or its payload was pruned, or when the event was sampled
+//! out.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::config::RetentionConfig;
+use crate::error::AppResult;
+use crate::ingest::{delete_minidump, list_minidumps};
+use crate::services::ingested_event::INGESTED_EVENT_RETENTION_DAYS;
+use crate::services::{EventService, IngestedEventService, IssueService};
+
+/// Time between retention runs
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Minidumps whose events are looked up per query
+const MINIDUMP_BATCH_SIZE: usize = 1000;
+
+pub struct RetentionService;
+
+impl RetentionService {
+    /// Purges everything past its retention window once
+    ///
+    /// Returns the number of issues removed.
+    pub async fn run(pool: &PgPool, config: &RetentionConfig, ingest_dir: &Path) -> AppResult<u64> {
+        let purged = IssueService::purge_deleted(pool, config.issue_trash_days).await?;
+        if purged > 0 {
+            log::info!("Purged {} issues from the trash", purged);
+        }
+
+        if let Some(days) = config.payload_retention_days {
+            let pruned = EventService::prune_payloads(pool, days).await?;
+            if pruned > 0 {
+                log::info!("Pruned the payloads of {} events", pruned);
+            }
+        }
+
+        let forgotten = IngestedEventService::purge(pool, INGESTED_EVENT_RETENTION_DAYS).await?;
+        if forgotten > 0 {
+            log::debug!("Forgot {} ingested event ids", forgotten);
+        }
+
+        let deleted = Self::purge_minidumps(pool, ingest_dir).await?;
+        if deleted > 0 {
+            log::info!("Deleted {} minidumps", deleted);
+        }
+
+        Ok(purged)
+    }
+
+    /// Deletes the minidumps in `ingest_dir` whose payload is no longer kept
+    ///
+    /// Returns the number of files deleted.
+    pub async fn purge_minidumps(pool: &PgPool, ingest_dir: &Path) -> AppResult<u64> {
+        // A minidump the database has no trace of is kept as long as an
+        // ingested event id is, in case its event is still waiting
+        let forgotten_before = SystemTime::now()
+            - Duration::from_secs(INGESTED_EVENT_RETENTION_DAYS as u64 * 24 * 60 * 60);
+
+        let mut deleted = 0;
+        for batch in list_minidumps(ingest_dir)
+            .await?
+            .chunks(MINIDUMP_BATCH_SIZE)
+        {
+            let keys: Vec<(i32, Uuid)> = batch
+                .iter()
+                .map(|minidump| (minidump.project_id, minidump.event_id))
+                .collect();
+            let kept = EventService::payloads_kept(pool, &keys).await?;
+
+            for minidump in batch {
+                let keep = match kept.get(&(minidump.project_id, minidump.event_id)) {
+                    Some(&kept) => kept,
+                    None => minidump.modified >= forgotten_before,
+                };
+                if !keep {
+                    delete_minidump(
+                        ingest_dir,
+                        minidump.project_id,
+                        &minidump.event_id.to_string(),
+                    )
+                    .await?;
+                    deleted += 1;
+                }
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Starts the periodic job; failed runs are logged and retried on the next tick
+    pub fn spawn(pool: PgPool, config: RetentionConfig, ingest_dir: PathBuf) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::run(&pool, &config, &ingest_dir).await {
+                    log::error!("Retention job failed: {}", e);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,187 @@
+use actix_web::web;
+use sqlx::PgPool;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateSavedSearch, SavedSearch, User};
+use crate::pagination::ListIssuesQuery;
+
+/// Query parameter that selects a saved search (never stored itself)
+const SAVED_SEARCH_PARAM: &str = "saved_search";
+
+pub struct SavedSearchService;
+
+impl SavedSearchService {
+    /// Lists searches visible to the user: shared ones and their own
+    ///
+    /// With `project_id`, only global searches and those of that project are returned.
+    pub async fn list(
+        pool: &PgPool,
+        user_id: i32,
+        project_id: Option<i32>,
+    ) -> AppResult<Vec<SavedSearch>> {
+        let searches = sqlx::query_as::<_, SavedSearch>(
+            r#"
+            SELECT id, user_id, project_id, name, query, created_at, updated_at
+            FROM saved_searches
+            WHERE (user_id IS NULL OR user_id = $1)
+              AND ($2::int IS NULL OR project_id IS NULL OR project_id = $2)
+            ORDER BY name ASC, id ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(searches)
+    }
+
+    /// Gets a search by ID if it is visible to the user
+    pub async fn get_visible(pool: &PgPool, id: i32, user_id: i32) -> AppResult<SavedSearch> {
+        sqlx::query_as::<_, SavedSearch>(
+            r#"
+            SELECT id, user_id, project_id, name, query, created_at, updated_at
+            FROM saved_searches
+            WHERE id = $1 AND (user_id IS NULL OR user_id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Saved search {} not found", id)))
+    }
+
+    /// Creates a saved search owned by the user, or a shared one (admins only)
+    pub async fn create(
+        pool: &PgPool,
+        user: &User,
+        input: CreateSavedSearch,
+    ) -> AppResult<SavedSearch> {
+        let name = input.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("Name cannot be empty".to_string()));
+        }
+        if name.len() > 255 {
+            return Err(AppError::Validation(
+                "Name cannot exceed 255 characters".to_string(),
+            ));
+        }
+
+        if input.shared && !user.is_admin {
+            return Err(AppError::Forbidden(
+                "Only admins can create shared searches".to_string(),
+            ));
+        }
+
+        // Stored parameters must be accepted by the issues list endpoint
+        Self::parse_issues_query(Self::stored_params(&input.query)?)?;
+
+        let owner = if input.shared { None } else { Some(user.id) };
+
+        let search = sqlx::query_as::<_, SavedSearch>(
+            r#"
+            INSERT INTO saved_searches (user_id, project_id, name, query)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, project_id, name, query, created_at, updated_at
+            "#,
+        )
+        .bind(owner)
+        .bind(input.project_id)
+        .bind(name)
+        .bind(&input.query)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e {
+                if db_err.is_foreign_key_violation() {
+                    return AppError::NotFound(format!(
+                        "Project with id {} not found",
+                        input.project_id.unwrap_or_default()
+                    ));
+                }
+            }
+            AppError::Database(e)
+        })?;
+
+        Ok(search)
+    }
+
+    /// Deletes a saved search; shared searches can only be deleted by admins
+    pub async fn delete(pool: &PgPool, id: i32, user: &User) -> AppResult<()> {
+        let search = Self::get_visible(pool, id, user.id).await?;
+
+        if search.is_shared() && !user.is_admin {
+            return Err(AppError::Forbidden(
+                "Only admins can delete shared searches".to_string(),
+            ));
+        }
+
+        sqlx::query("DELETE FROM saved_searches WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Expands a saved search into issues list parameters
+    ///
+    /// `explicit_query` is the raw request query string; its parameters override
+    /// the stored ones.
+    pub fn expand(search: &SavedSearch, explicit_query: &str) -> AppResult<ListIssuesQuery> {
+        let mut params = Self::stored_params(&search.query)?;
+
+        for (key, value) in url::form_urlencoded::parse(explicit_query.as_bytes()) {
+            if key == SAVED_SEARCH_PARAM {
+                continue;
+            }
+            params.retain(|(k, _)| *k != key);
+            params.push((key.into_owned(), value.into_owned()));
+        }
+
+        Self::parse_issues_query(params)
+    }
+
+    /// Converts a stored JSON object into query parameter pairs
+    fn stored_params(query: &serde_json::Value) -> AppResult<Vec<(String, String)>> {
+        let object = query.as_object().ok_or_else(|| {
+            AppError::Validation("Search query must be a JSON object".to_string())
+        })?;
+
+        let mut params = Vec::with_capacity(object.len());
+        for (key, value) in object {
+            if key == SAVED_SEARCH_PARAM {
+                return Err(AppError::Validation(
+                    "Saved searches cannot reference other saved searches".to_string(),
+                ));
+            }
+
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => {
+                    return Err(AppError::Validation(format!(
+                        "Invalid value for search parameter '{}'",
+                        key
+                    )))
+                }
+            };
+            params.push((key.clone(), value));
+        }
+
+        Ok(params)
+    }
+
+    /// Parses parameter pairs with the same deserializer as the issues endpoint
+    fn parse_issues_query(params: Vec<(String, String)>) -> AppResult<ListIssuesQuery> {
+        let encoded = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+
+        web::Query::<ListIssuesQuery>::from_query(&encoded)
+            .map(|q| q.into_inner())
+            .map_err(|e| AppError::Validation(format!("Invalid search parameters: {}", e)))
+    }
+}
@@ -0,0 +1,238 @@
+//! Self-monitoring: Rustrak's own errors reported into one of its projects.
+//!
+//! With `SELF_MONITORING_PROJECT_ID` set, panics and server errors become
+//! synthetic events that are stored and digested like SDK events, without
+//! going through the ingest endpoint. Everything captured is tagged
+//! `component: server`.
+//!
+//! Errors raised while a captured event is being digested are only logged,
+//! so a broken database can't feed itself an endless stream of events.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::build_info;
+use crate::config::{Config, DigestConfig, RateLimitConfig};
+use crate::digest::process_event;
+use crate::error::{AppError, AppResult};
+use crate::ingest::{get_ingest_dir, store_event, EventMetadata};
+
+/// Panic events waiting to be captured on the main runtime
+static PANIC_EVENTS: OnceLock<mpsc::UnboundedSender<Value>> = OnceLock::new();
+
+tokio::task_local! {
+    /// Set while a captured event is digested
+    static CAPTURING: ();
+}
+
+/// Whether the current task is digesting a self-monitoring event
+pub fn is_capturing() -> bool {
+    CAPTURING.try_with(|_| ()).is_ok()
+}
+
+/// The request a server error was returned for
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    pub method: String,
+    pub path: String,
+    /// Route pattern, e.g. `/api/projects/{id}`; used as the transaction
+    pub route: Option<String>,
+}
+
+/// Reports errors into the self-monitoring project
+#[derive(Clone)]
+pub struct SelfMonitor {
+    pool: PgPool,
+    project_id: i32,
+    ingest_dir: PathBuf,
+    compress: bool,
+    rate_limit: RateLimitConfig,
+    digest: DigestConfig,
+}
+
+impl SelfMonitor {
+    /// The monitor for `SELF_MONITORING_PROJECT_ID`, if set
+    pub fn from_config(pool: &PgPool, config: &Config) -> Option<Self> {
+        let project_id = config.self_monitoring_project_id?;
+        Some(Self {
+            pool: pool.clone(),
+            project_id,
+            ingest_dir: get_ingest_dir(config.ingest_dir.as_deref()),
+            compress: config.compress_stored_events,
+            rate_limit: config.rate_limit.clone(),
+            digest: config.digest.clone(),
+        })
+    }
+
+    pub fn project_id(&self) -> i32 {
+        self.project_id
+    }
+
+    /// Stores and digests an event in the background
+    ///
+    /// Returns None, after logging the event, when called while a captured
+    /// event is being digested.
+    pub fn capture(&self, mut event: Value) -> Option<JoinHandle<()>> {
+        let summary = event_summary(&event);
+        if is_capturing() {
+            log::error!("Not reporting error raised by self-monitoring: {}", summary);
+            return None;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            log::error!("Not reporting error outside the runtime: {}", summary);
+            return None;
+        };
+
+        let event_id = Uuid::new_v4().simple().to_string();
+        event["event_id"] = json!(event_id);
+        let monitor = self.clone();
+
+        Some(runtime.spawn(CAPTURING.scope((), async move {
+            if let Err(e) = monitor.digest(&event_id, &event).await {
+                log::error!("Failed to report error '{}': {}", summary, e);
+            }
+        })))
+    }
+
+    /// Reports a server error returned for a request
+    pub fn capture_error(
+        &self,
+        error_type: &str,
+        message: &str,
+        request: Option<&CapturedRequest>,
+    ) -> Option<JoinHandle<()>> {
+        self.capture(error_event(error_type, message, request))
+    }
+
+    /// Reports every panic of the process from now on
+    ///
+    /// Must be called from the main runtime: panic events are sent there,
+    /// since the panicking thread may be an HTTP worker about to be replaced.
+    /// The previous hook still runs, so panics are printed as before. Only
+    /// the first monitor installed is used.
+    pub fn install_panic_hook(self) {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        if PANIC_EVENTS.set(sender).is_err() {
+            return;
+        }
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                self.capture(event);
+            }
+        });
+
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            if is_capturing() {
+                log::error!("Not reporting panic raised by self-monitoring");
+                return;
+            }
+            if let Some(sender) = PANIC_EVENTS.get() {
+                let _ = sender.send(panic_event(info));
+            }
+        }));
+    }
+
+    async fn digest(&self, event_id: &str, event: &Value) -> AppResult<()> {
+        let payload = serde_json::to_vec(event).map_err(|e| AppError::Internal(e.to_string()))?;
+        store_event(&self.ingest_dir, event_id, &payload, self.compress).await?;
+
+        let metadata = EventMetadata {
+            event_id: event_id.to_string(),
+            project_id: self.project_id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &self.pool,
+            &metadata,
+            &self.ingest_dir,
+            &self.rate_limit,
+            &self.digest,
+        )
+        .await
+    }
+}
+
+/// Event for a server error, e.g. a `DatabaseError` returned by a handler
+pub fn error_event(error_type: &str, message: &str, request: Option<&CapturedRequest>) -> Value {
+    let mut event = base_event("error");
+    event["exception"] = json!({
+        "values": [{
+            "type": error_type,
+            "value": message,
+            "mechanism": {"type": "generic", "handled": true}
+        }]
+    });
+    if let Some(request) = request {
+        event["request"] = json!({"method": request.method, "url": request.path});
+        event["transaction"] = json!(request.route.as_deref().unwrap_or(&request.path));
+    }
+    event
+}
+
+/// Event for a panic, with the panic location as its only frame
+pub fn panic_event(info: &std::panic::PanicHookInfo<'_>) -> Value {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    let frames: Vec<Value> = info
+        .location()
+        .map(|location| {
+            json!({
+                "filename": location.file(),
+                "lineno": location.line(),
+                "colno": location.column(),
+                "in_app": true
+            })
+        })
+        .into_iter()
+        .collect();
+
+    let mut event = base_event("fatal");
+    event["exception"] = json!({
+        "values": [{
+            "type": "panic",
+            "value": message,
+            "mechanism": {"type": "panic", "handled": false},
+            "stacktrace": {"frames": frames}
+        }]
+    });
+    if let Some(thread) = std::thread::current().name() {
+        event["extra"] = json!({"thread": thread});
+    }
+    event
+}
+
+fn base_event(level: &str) -> Value {
+    json!({
+        "timestamp": Utc::now().timestamp_millis() as f64 / 1000.0,
+        "platform": "rust",
+        "level": level,
+        "logger": "rustrak",
+        "release": format!("rustrak@{}", build_info::VERSION),
+        "tags": {"component": "server"},
+        "sdk": {"name": "rustrak.self_monitoring", "version": build_info::VERSION}
+    })
+}
+
+fn event_summary(event: &Value) -> String {
+    let exception = &event["exception"]["values"][0];
+    format!(
+        "{}: {}",
+        exception["type"].as_str().unwrap_or("error"),
+        exception["value"].as_str().unwrap_or("")
+    )
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use slug::slugify;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{CreateTeam, Team, UpdateTeam};
+
+pub struct TeamService;
+
+impl TeamService {
+    /// Lists all teams
+    pub async fn list(pool: &PgPool) -> AppResult<Vec<Team>> {
+        let teams = sqlx::query_as::<_, Team>(
+            r#"
+            SELECT id, name, slug, created_at, updated_at
+            FROM teams
+            ORDER BY name ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(teams)
+    }
+
+    /// Gets a team by ID
+    pub async fn get_by_id(pool: &PgPool, id: i32) -> AppResult<Team> {
+        sqlx::query_as::<_, Team>(
+            r#"
+            SELECT id, name, slug, created_at, updated_at
+            FROM teams
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Team with id {} not found", id)))
+    }
+
+    /// Gets a team by slug
+    pub async fn get_by_slug(pool: &PgPool, slug: &str) -> AppResult<Team> {
+        sqlx::query_as::<_, Team>(
+            r#"
+            SELECT id, name, slug, created_at, updated_at
+            FROM teams
+            WHERE slug = $1
+            "#,
+        )
+        .bind(slug)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Team with slug '{}' not found", slug)))
+    }
+
+    /// Gets the default notification channel IDs of a team
+    pub async fn get_default_channels(pool: &PgPool, team_id: i32) -> AppResult<Vec<i32>> {
+        let ids: Vec<i32> = sqlx::query_scalar(
+            "SELECT channel_id FROM team_default_channels WHERE team_id = $1 ORDER BY channel_id",
+        )
+        .bind(team_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Gets the default notification channel IDs of several teams in one
+    /// query, keyed by team ID
+    ///
+    /// Teams without default channels have no entry.
+    pub async fn default_channels_for_teams(
+        pool: &PgPool,
+        team_ids: &[i32],
+    ) -> AppResult<HashMap<i32, Vec<i32>>> {
+        let rows = sqlx::query_as::<_, (i32, i32)>(
+            r#"
+            SELECT team_id, channel_id
+            FROM team_default_channels
+            WHERE team_id = ANY($1)
+            ORDER BY team_id, channel_id
+            "#,
+        )
+        .bind(team_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let mut channels: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (team_id, channel_id) in rows {
+            channels.entry(team_id).or_default().push(channel_id);
+        }
+        Ok(channels)
+    }
+
+    /// Creates a new team
+    pub async fn create(pool: &PgPool, input: CreateTeam) -> AppResult<Team> {
+        let name = input.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("Name cannot be empty".to_string()));
+        }
+        if name.len() > 255 {
+            return Err(AppError::Validation(
+                "Name cannot exceed 255 characters".to_string(),
+            ));
+        }
+
+        let slug = match input.slug.as_deref() {
+            Some(s) if !s.trim().is_empty() => slugify(s.trim()),
+            _ => slugify(name),
+        };
+        if slug.is_empty() {
+            return Err(AppError::Validation(
+                "Cannot generate valid slug from name".to_string(),
+            ));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let team = sqlx::query_as::<_, Team>(
+            r#"
+            INSERT INTO teams (name, slug)
+            VALUES ($1, $2)
+            RETURNING id, name, slug, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(&slug)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e {
+                if db_err.constraint() == Some("teams_name_key") {
+                    return AppError::Conflict(format!("Team with name '{}' already exists", name));
+                }
+                if db_err.constraint() == Some("teams_slug_key") {
+                    return AppError::Conflict(format!("Team with slug '{}' already exists", slug));
+                }
+            }
+            AppError::Database(e)
+        })?;
+
+        Self::set_default_channels(&mut tx, team.id, &input.default_channel_ids).await?;
+
+        tx.commit().await?;
+
+        Ok(team)
+    }
+
+    /// Updates an existing team
+    pub async fn update(pool: &PgPool, id: i32, input: UpdateTeam) -> AppResult<Team> {
+        // Verify it exists
+        Self::get_by_id(pool, id).await?;
+
+        let mut tx = pool.begin().await?;
+
+        if let Some(ref name) = input.name {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(AppError::Validation("Name cannot be empty".to_string()));
+            }
+            if name.len() > 255 {
+                return Err(AppError::Validation(
+                    "Name cannot exceed 255 characters".to_string(),
+                ));
+            }
+
+            sqlx::query("UPDATE teams SET name = $1 WHERE id = $2")
+                .bind(name)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    if let sqlx::Error::Database(ref db_err) = e {
+                        if db_err.constraint() == Some("teams_name_key") {
+                            return AppError::Conflict(format!(
+                                "Team with name '{}' already exists",
+                                name
+                            ));
+                        }
+                    }
+                    AppError::Database(e)
+                })?;
+        }
+
+        if let Some(ref channel_ids) = input.default_channel_ids {
+            sqlx::query("DELETE FROM team_default_channels WHERE team_id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+            Self::set_default_channels(&mut tx, id, channel_ids).await?;
+        }
+
+        tx.commit().await?;
+
+        Self::get_by_id(pool, id).await
+    }
+
+    /// Deletes a team
+    ///
+    /// A team that still has projects can only be deleted when `reassign_to`
+    /// names another team; its projects are moved there first.
+    pub async fn delete(pool: &PgPool, id: i32, reassign_to: Option<i32>) -> AppResult<()> {
+        let mut tx = pool.begin().await?;
+
+        // Locking the team blocks projects from being assigned to it (their
+        // foreign key check needs a share lock) until the delete commits
+        sqlx::query_scalar::<_, i32>("SELECT id FROM teams WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Team with id {} not found", id)))?;
+
+        let project_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM projects WHERE team_id = $1")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        if project_count > 0 {
+            let target = reassign_to.ok_or_else(|| {
+                AppError::Conflict(format!(
+                    "Team has {} project(s); move them or pass reassign_to",
+                    project_count
+                ))
+            })?;
+
+            if target == id {
+                return Err(AppError::Validation(
+                    "Cannot reassign projects to the team being deleted".to_string(),
+                ));
+            }
+            Self::get_by_id(pool, target).await?;
+
+            sqlx::query("UPDATE projects SET team_id = $1, updated_at = NOW() WHERE team_id = $2")
+                .bind(target)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM teams WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Copies the team's default channels into a new-issue alert rule for a project
+    ///
+    /// Does nothing when the team has no default channels.
+    pub async fn copy_default_channels(
+        tx: &mut Transaction<'_, Postgres>,
+        team_id: i32,
+        project_id: i32,
+    ) -> AppResult<()> {
+        let channel_ids: Vec<i32> =
+            sqlx::query_scalar("SELECT channel_id FROM team_default_channels WHERE team_id = $1")
+                .bind(team_id)
+                .fetch_all(&mut **tx)
+                .await?;
+
+        if channel_ids.is_empty() {
+            return Ok(());
+        }
+
+        let rule_id: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO alert_rules (project_id, name, alert_type)
+            VALUES ($1, 'New issues', 'new_issue')
+            RETURNING id
+            "#,
+        )
+        .bind(project_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO alert_rule_channels (alert_rule_id, channel_id)
+            SELECT $1, UNNEST($2::int[])
+            "#,
+        )
+        .bind(rule_id)
+        .bind(&channel_ids)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Links default channels to a team
+    async fn set_default_channels(
+        tx: &mut Transaction<'_, Postgres>,
+        team_id: i32,
+        channel_ids: &[i32],
+    ) -> AppResult<()> {
+        for channel_id in channel_ids {
+            sqlx::query(
+                "INSERT INTO team_default_channels (team_id, channel_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(team_id)
+            .bind(channel_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(ref db_err) = e {
+                    if db_err.is_foreign_key_violation() {
+                        return AppError::NotFound(format!("Channel {} not found", channel_id));
+                    }
+                }
+                AppError::Database(e)
+            })?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 use sqlx::PgPool;
 
-use crate::error::{AppError, AppResult};
-use crate::models::{CreateUserRequest, User};
+use crate::auth::generate_token;
+use crate::error::{codes, AppError, AppResult};
+use crate::models::{CreateUserRequest, ProjectOverview, User, UserOverview};
+use crate::services::{IssueService, ProjectMemberService, ProjectService};
+
+/// Issues listed in the dashboard overview
+pub const OVERVIEW_ISSUE_LIMIT: i64 = 10;
 
 pub struct UsersService;
 
@@ -28,7 +33,11 @@ impl UsersService {
         .await
         .map_err(|e| match e {
             sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
-                AppError::Validation("Email already exists".to_string())
+                AppError::invalid_field(
+                    "email",
+                    codes::VALIDATION_EMAIL_TAKEN,
+                    "Email already exists",
+                )
             }
             _ => AppError::Internal(format!("Failed to create user: {}", e)),
         })?;
@@ -36,6 +45,52 @@ impl UsersService {
         Ok(user)
     }
 
+    /// Gets the user with `email`, creating it on first login through SSO
+    ///
+    /// Users created this way get a random password, so they can only log in
+    /// through the provider until an admin sets one. `grant_admin` makes the
+    /// user an admin but never revokes it, admin rights given by hand survive
+    /// the user leaving the provider's admin groups.
+    pub async fn find_or_create_sso_user(
+        pool: &PgPool,
+        email: &str,
+        grant_admin: bool,
+    ) -> AppResult<User> {
+        let user = match Self::get_by_email(pool, email).await? {
+            Some(user) => user,
+            None => {
+                let req = CreateUserRequest {
+                    email: email.to_string(),
+                    password: generate_token(),
+                };
+                match Self::create_user(pool, &req, grant_admin).await {
+                    Ok(user) => return Ok(user),
+                    // Created by a concurrent login in the meantime
+                    Err(e) if e.field() == Some("email") => Self::get_by_email(pool, email)
+                        .await?
+                        .ok_or_else(|| AppError::Internal("SSO user disappeared".into()))?,
+                    Err(e) => return Err(e),
+                }
+            }
+        };
+
+        if grant_admin && !user.is_admin {
+            return Ok(sqlx::query_as::<_, User>(
+                r#"
+                UPDATE users
+                SET is_admin = TRUE
+                WHERE id = $1
+                RETURNING id, email, password_hash, is_active, is_admin, created_at, last_login
+                "#,
+            )
+            .bind(user.id)
+            .fetch_one(pool)
+            .await?);
+        }
+
+        Ok(user)
+    }
+
     /// Gets a user by email
     pub async fn get_by_email(pool: &PgPool, email: &str) -> AppResult<Option<User>> {
         let user = sqlx::query_as::<_, User>(
@@ -97,4 +152,62 @@ impl UsersService {
 
         Ok(count.0)
     }
+
+    /// Dashboard home page of `user`, or of an API token when `None`
+    ///
+    /// Users get the open issues and projects of the projects they're a
+    /// member of (all of them for admins). Tokens aren't tied to a user, so
+    /// they get the open issues of every project and no project list.
+    pub async fn overview(
+        pool: &PgPool,
+        user: Option<&User>,
+        max_title_length: usize,
+    ) -> AppResult<UserOverview> {
+        let member_id = user.and_then(ProjectMemberService::member_filter);
+        let issues = IssueService::recent_open(pool, member_id, OVERVIEW_ISSUE_LIMIT).await?;
+
+        let mut project_ids: Vec<i32> = issues.iter().map(|i| i.project_id).collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        let slugs = ProjectService::slugs_for_projects(pool, &project_ids).await?;
+
+        let seen = match user {
+            Some(user) => {
+                let issue_ids: Vec<_> = issues.iter().map(|i| i.id).collect();
+                IssueService::seen_issue_ids(pool, user.id, &issue_ids).await?
+            }
+            None => Default::default(),
+        };
+        let issues = issues
+            .iter()
+            .map(|i| {
+                let slug = slugs.get(&i.project_id).map(String::as_str).unwrap_or("");
+                let mut response = i.to_response(slug, max_title_length);
+                response.is_seen = seen.contains(&i.id);
+                response
+            })
+            .collect();
+
+        let projects = match user {
+            Some(user) => {
+                let projects = ProjectService::list(pool, user).await?;
+                let ids: Vec<i32> = projects.iter().map(|p| p.id).collect();
+                let stats = ProjectService::stats_for_projects(pool, &ids).await?;
+                Some(
+                    projects
+                        .into_iter()
+                        .map(|p| ProjectOverview {
+                            stats: stats.get(&p.id).copied().unwrap_or_default(),
+                            id: p.id,
+                            name: p.name,
+                            slug: p.slug,
+                        })
+                        .collect(),
+                )
+            }
+            None => None,
+        };
+
+        Ok(UserOverview { issues, projects })
+    }
 }
@@ -77,7 +77,7 @@ impl EventBuilder {
         exc_value: &str,
         frames: Vec<StackFrame>,
     ) -> Self {
-        let frames_json: Vec<Value> = frames.into_iter().map(|f| f.to_json()).collect();
+        let frames_json: Vec<Value> = frames.into_iter().map(|f| f.into_json()).collect();
         self.exception = Some(json!({
             "values": [{
                 "type": exc_type,
@@ -176,7 +176,7 @@ impl StackFrame {
         self
     }
 
-    fn to_json(self) -> Value {
+    fn into_json(self) -> Value {
         let mut frame = json!({
             "filename": self.filename,
             "function": self.function,
@@ -228,6 +228,218 @@ pub fn create_envelope_no_length(project_id: u32, sentry_key: &str, event: &Valu
     envelope.into_bytes()
 }
 
+/// Malformed envelopes the parser must reject, each with a short description
+pub const MALFORMED_ENVELOPES: &[(&str, &[u8])] = &[
+    ("empty body", b""),
+    ("byte order mark only", b"\xEF\xBB\xBF"),
+    ("blank lines only", b"\n\n\n"),
+    ("NUL bytes", b"\0\0\0\n"),
+    ("headers not JSON", b"not json\n{\"type\":\"event\"}\n{}"),
+    ("truncated headers", b"{\"event_id\":\n"),
+    ("invalid UTF-8 in headers", b"{\"event_id\":\"\xFF\xFE\"}\n"),
+    ("item headers not JSON", b"{}\n{type:event}\n{}"),
+    ("item headers without type", b"{}\n{\"length\":2}\n{}"),
+    ("negative length", b"{}\n{\"type\":\"event\",\"length\":-1}\n{}"),
+    (
+        "length beyond usize",
+        b"{}\n{\"type\":\"event\",\"length\":18446744073709551616}\n{}",
+    ),
+    ("truncated payload", b"{}\n{\"type\":\"event\",\"length\":100}\n{}"),
+    ("length too short", b"{}\n{\"type\":\"event\",\"length\":1}\n{}\n"),
+    ("bare CR after payload", b"{}\n{\"type\":\"event\",\"length\":2}\n{}\r"),
+    (
+        "invalid MessagePack",
+        b"{}\n{\"type\":\"event\",\"content_type\":\"application/x-msgpack\",\"length\":3}\n\xC1\xC1\xC1",
+    ),
+    (
+        "unknown event content type",
+        b"{}\n{\"type\":\"event\",\"content_type\":\"text/html\"}\n<p>",
+    ),
+];
+
+/// An envelope whose item headers line is longer than the parser accepts
+pub fn envelope_with_oversized_header() -> Vec<u8> {
+    let padding = "x".repeat(10 * 1024);
+    format!(
+        "{{}}\n{{\"type\":\"event\",\"padding\":\"{}\"}}\n{{}}\n",
+        padding
+    )
+    .into_bytes()
+}
+
+/// Builds small minidump files with a system info, exception and module list stream
+pub struct MinidumpBuilder {
+    platform_id: u32,
+    exception: Option<(u32, u64)>,
+    modules: Vec<(String, u64, u32, Option<[u8; 16]>)>,
+}
+
+impl Default for MinidumpBuilder {
+    fn default() -> Self {
+        Self {
+            platform_id: 0x8201, // Linux
+            exception: None,
+            modules: Vec::new(),
+        }
+    }
+}
+
+impl MinidumpBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn windows(mut self) -> Self {
+        self.platform_id = 2;
+        self
+    }
+
+    pub fn with_exception(mut self, code: u32, address: u64) -> Self {
+        self.exception = Some((code, address));
+        self
+    }
+
+    pub fn with_module(mut self, name: &str, base: u64, size: u32) -> Self {
+        self.modules.push((name.to_string(), base, size, None));
+        self
+    }
+
+    /// Adds a module with a PDB 7.0 CodeView record (GUID bytes, age 1)
+    pub fn with_pdb_module(mut self, name: &str, base: u64, size: u32, guid: [u8; 16]) -> Self {
+        self.modules
+            .push((name.to_string(), base, size, Some(guid)));
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        const HEADER_SIZE: usize = 32;
+        const MODULE_SIZE: usize = 108;
+
+        let stream_count = 2 + self.exception.is_some() as usize;
+        let mut streams: Vec<(u32, Vec<u8>)> = Vec::new();
+
+        // MINIDUMP_SYSTEM_INFO, PlatformId at offset 20
+        let mut system_info = vec![0u8; 56];
+        system_info[20..24].copy_from_slice(&self.platform_id.to_le_bytes());
+        streams.push((7, system_info));
+
+        // ThreadId, alignment, MINIDUMP_EXCEPTION, thread context location
+        if let Some((code, address)) = self.exception {
+            let mut exception = vec![0u8; 168];
+            exception[0..4].copy_from_slice(&1u32.to_le_bytes());
+            exception[8..12].copy_from_slice(&code.to_le_bytes());
+            exception[24..32].copy_from_slice(&address.to_le_bytes());
+            streams.push((6, exception));
+        }
+
+        // Module names and CodeView records go after the module list
+        let directory_end = HEADER_SIZE + stream_count * 12;
+        let stream_data_len: usize = streams.iter().map(|(_, d)| d.len()).sum();
+        let module_list_rva = directory_end + stream_data_len;
+        let mut extra_rva = module_list_rva + 4 + self.modules.len() * MODULE_SIZE;
+
+        let mut module_list = (self.modules.len() as u32).to_le_bytes().to_vec();
+        let mut extra = Vec::new();
+        for (name, base, size, guid) in &self.modules {
+            let mut module = vec![0u8; MODULE_SIZE];
+            module[0..8].copy_from_slice(&base.to_le_bytes());
+            module[8..12].copy_from_slice(&size.to_le_bytes());
+
+            let name_utf16: Vec<u8> = name.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+            module[20..24].copy_from_slice(&(extra_rva as u32).to_le_bytes());
+            extra.extend_from_slice(&(name_utf16.len() as u32).to_le_bytes());
+            extra.extend_from_slice(&name_utf16);
+            extra_rva += 4 + name_utf16.len();
+
+            if let Some(guid) = guid {
+                let mut cv = b"RSDS".to_vec();
+                cv.extend_from_slice(guid);
+                cv.extend_from_slice(&1u32.to_le_bytes());
+                cv.extend_from_slice(format!("{}.pdb\0", name).as_bytes());
+                module[76..80].copy_from_slice(&(cv.len() as u32).to_le_bytes());
+                module[80..84].copy_from_slice(&(extra_rva as u32).to_le_bytes());
+                extra_rva += cv.len();
+                extra.extend_from_slice(&cv);
+            }
+
+            module_list.extend_from_slice(&module);
+        }
+        module_list.extend_from_slice(&extra);
+        streams.push((4, module_list));
+
+        // Header: signature, version, stream count, directory RVA
+        let mut data = b"MDMP".to_vec();
+        data.extend_from_slice(&0xa793u32.to_le_bytes());
+        data.extend_from_slice(&(stream_count as u32).to_le_bytes());
+        data.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        data.resize(HEADER_SIZE, 0);
+
+        let mut rva = directory_end;
+        for (stream_type, stream) in &streams {
+            data.extend_from_slice(&stream_type.to_le_bytes());
+            data.extend_from_slice(&(stream.len() as u32).to_le_bytes());
+            data.extend_from_slice(&(rva as u32).to_le_bytes());
+            rva += stream.len();
+        }
+        for (_, stream) in streams {
+            data.extend_from_slice(&stream);
+        }
+
+        data
+    }
+}
+
+/// Debug ID of `libcrash.so`, the module described by [`SYMBOL_FILE`]
+pub const SYMBOL_DEBUG_ID: &str = "3f2504e0-4f89-11d3-9a0c-0305e82c3301-1";
+
+/// Hand-written Breakpad symbol file for `libcrash.so`
+///
+/// `crash_handler` covers 0x1000..0x1040 and `do_work` 0x1100..0x1130, both
+/// with line records; `exported_helper` is only a PUBLIC symbol at 0x1200.
+pub const SYMBOL_FILE: &str = "\
+MODULE Linux x86_64 3F2504E04F8911D39A0C0305E82C33011 libcrash.so
+INFO CODE_ID E004253F894FD3119A0C0305E82C3301
+FILE 0 /src/crash.c
+FILE 1 /src/work.c
+FUNC 1000 40 0 crash_handler
+1000 20 10 0
+1020 20 12 0
+FUNC m 1100 30 0 do_work
+1100 30 25 1
+PUBLIC 1200 0 exported_helper
+STACK CFI INIT 1000 40 .cfa: $rsp 8 + .ra: .cfa -8 + ^
+";
+
+/// Load address of `libcrash.so` in [`events::native_crash`]
+pub const LIBCRASH_BASE: u64 = 0x7f00_0000_0000;
+
+/// RSA key (PKCS#8, base64 DER) the mocked OIDC provider signs ID tokens with
+///
+/// Generated for the tests only, it protects nothing.
+pub const OIDC_SIGNING_KEY: &str = "\
+MIIEvAIBADANBgkqhkiG9w0BAQEFAASCBKYwggSiAgEAAoIBAQDTrkoImcI3QE3fotlEAXMGhtEk\
+/NbhgQj57ZlCcJF4xMtJ3vTCQDWwlS24rMjBPZyv09EQjCuvGIgaiPbxaFxRaGcSy33vgOjcXIsL\
+oz/SeWGnb7X2XO77aEhCpeitgT7PTafe+Dd4W8wOBveCMQrznWlBqCgJ3fiZI4alieh44Vbz0ccU\
+8DldRM0AAeoBCy6CfwWmkWD8NzVb+wTt6Y9pT3PDJnRmOiPfWwCMErRW1SU08T03hGlJuRDSPQwy\
+dXf0/R0k+2j0smw6/1poqXHAwfwVrAxfpYyqZLjm5GGeG41vuUql5my4EZgoXvOjhH4WNfpYtUZU\
+chwWUcVNw6xDAgMBAAECggEAI8Fm4/kE40xLWyEozeHoygtvvG2w5jpjh8PohV56VAn5xKqScVVj\
+PEaWiIE3D90WYGTJBaCHqHXeV40XGUPlhCK8dmJFDXWa384J9pdGqoiyRBQMima8P18oBYn8uYIB\
+WAbn+jO3Ou9Wkm/+0tDx5qCKLPr4eGI0Ibnsfn3C9NfcrRDFjaupXRPthD6LHMxo3SqTNnB/X5//\
+e2U7pTC3QxjJB3rEhHnXrkpr/c+cRYaRIqTMoLNPaPgh5iCcS72hI3s231mRfDcApPNoDUapApAs\
+IHuknDd00DecGSqzOEevdUPZWdDYDSP0F5DDLO/9vbDyKw7Zv2oaiv7t+c1h8QKBgQDtytPNuO2G\
+Y14rrPthYi0+RRe6sPBy315W84vR6pv3UAEYR9b6KbFzcTpwEO3hgpOTj2ptqKyRvMgecTGWVxJO\
+uoTzyRzhkVxMKNIdTFhwdXNbvIW0qJSr/VXmqxvy66aEvTU1sF3q1BHeOstGKcN9RWf4+BG/Fgql\
+Zy3zGpqBvwKBgQDj46DPWmjvu6nL0zt1uBW5oIhahczTp3nvlMXNtpBqo2kuGmtGRIbtkjNhzunx\
+pwDNVCwhEGTb8B08PHLGjpy5Lrajfrt/WGjt+2uEC0eQLo7OlY8fVpWkDF8m8pheWQhlaKs+CbG4\
+X/OsKAS+mHAMSxOpr21ZfsoGl5w8j34ufQKBgAhFziiSDnFmc5hBZF129Ov9q12rWjIk4ztTLcIX\
+z2VDO0aE+U2qtam2frn3XM9Do662hpVaEObhEnU8quT4EoL6qjVxAbg3PmbH5MdwKIOBEopWfLBU\
+TRfOsHZd7ZL978UecAN2J05MInMAyKGgQlTRXVSkDLoBIk2u0UJr4oQxAoGAYu9bzSmPDSUH3jli\
+QwUcSW5sRAB6oBjBU0XfaiOsJt+ythU6JVNy0Ij8TaYq4oKCUqIMVn5riyB/ULtdiN45JBwnpmtK\
+s0qKo2bXkofjFbFgxkcfz5cjeAj29k2z4KH+hygdz90djtH5U/A7vxMm8e9WJgDHFGd7BcmkRpZ1\
+TXECgYAYc7XyuO+K11uUCM5HdCOEcsbpbeW7XZ4Ij4pDTpesaOGp8+T5OHw5dZA0CUsK6e7sowaZ\
+0j4kpn8OKXB9sls3TC2tru4HdSPj34MVroAGh59RYgzJic+gcCRG8YKE/e51812A8n8HA8K02qFu\
+6+uTQtV1bqAppnwTl8Nc01OyzQ==";
+
 /// Common test exception events
 pub mod events {
     use super::*;
@@ -273,4 +485,34 @@ pub mod events {
             .with_level("warning")
             .build()
     }
+
+    /// Native SIGSEGV with raw frame addresses (oldest first) in `libcrash.so`
+    pub fn native_crash(instruction_addrs: &[u64]) -> Value {
+        let frames: Vec<Value> = instruction_addrs
+            .iter()
+            .map(|addr| json!({ "instruction_addr": format!("0x{:x}", addr) }))
+            .collect();
+
+        let mut event = EventBuilder::new()
+            .with_platform("native")
+            .with_level("fatal")
+            .build();
+        event["exception"] = json!({
+            "values": [{
+                "type": "SIGSEGV",
+                "value": "Segfault",
+                "stacktrace": { "frames": frames },
+            }]
+        });
+        event["debug_meta"] = json!({
+            "images": [{
+                "type": "elf",
+                "code_file": "/usr/lib/libcrash.so",
+                "debug_id": SYMBOL_DEBUG_ID,
+                "image_addr": format!("0x{:x}", LIBCRASH_BASE),
+                "image_size": 0x2000,
+            }]
+        });
+        event
+    }
 }
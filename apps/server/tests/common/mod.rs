@@ -4,6 +4,10 @@
 
 pub mod db;
 pub mod fixtures;
+pub mod session;
+pub mod statements;
 
-pub use db::TestDb;
-pub use fixtures::{create_envelope, create_envelope_no_length, EventBuilder, StackFrame};
+pub use fixtures::{
+    envelope_with_oversized_header, MinidumpBuilder, LIBCRASH_BASE, MALFORMED_ENVELOPES,
+    SYMBOL_DEBUG_ID, SYMBOL_FILE,
+};
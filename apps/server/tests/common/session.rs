@@ -0,0 +1,50 @@
+//! Session helpers for tests of endpoints behind `AuthenticatedUser`
+//!
+//! Apps under test add [`session_middleware`] and [`configure_login`]; a
+//! [`login_request`] then returns the session cookie to send with the
+//! requests of that user.
+
+use actix_session::{storage::CookieSessionStore, Session, SessionMiddleware};
+use actix_web::cookie::{Cookie, Key};
+use actix_web::dev::ServiceResponse;
+use actix_web::{test, web, HttpResponse};
+use rustrak::auth::set_user_session;
+use rustrak::error::AppResult;
+
+/// Path of the test-only login route
+const LOGIN_PATH: &str = "/test/login";
+
+/// Cookie session middleware with a fixed key
+pub fn session_middleware() -> SessionMiddleware<CookieSessionStore> {
+    SessionMiddleware::builder(CookieSessionStore::default(), Key::from(&[0u8; 64]))
+        .cookie_secure(false)
+        .build()
+}
+
+/// Logs in as the user with the id in the path, without a password
+async fn login(session: Session, path: web::Path<i32>) -> AppResult<HttpResponse> {
+    set_user_session(&session, path.into_inner())?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Adds the test-only login route
+pub fn configure_login(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        &format!("{}/{{user_id}}", LOGIN_PATH),
+        web::post().to(login),
+    );
+}
+
+/// Request logging in as `user_id`
+pub fn login_request(user_id: i32) -> test::TestRequest {
+    test::TestRequest::post().uri(&format!("{}/{}", LOGIN_PATH, user_id))
+}
+
+/// Session cookie set by a login response
+pub fn session_cookie<B>(resp: &ServiceResponse<B>) -> Cookie<'static> {
+    resp.response()
+        .cookies()
+        .next()
+        .expect("Login response has no session cookie")
+        .into_owned()
+}
@@ -5,12 +5,15 @@
 
 use actix_web::{middleware, web, App, HttpServer};
 use chrono::Utc;
-use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
+use rustrak::config::{
+    AlertConfig, BodyLimitConfig, Config, DatabaseConfig, DigestConfig, IngestConfig,
+    PaginationConfig, RateLimitConfig, RetentionConfig,
+};
 use rustrak::digest::worker::process_event;
 use rustrak::ingest::EventMetadata;
 use rustrak::models::CreateProject;
 use rustrak::routes;
-use rustrak::services::{IssueService, ProjectService};
+use rustrak::services::{IssueService, ProjectKeyService, ProjectService};
 use sentry::protocol::{Event, Exception, Frame, Level, Stacktrace};
 use sqlx::PgPool;
 use std::net::TcpListener;
@@ -72,18 +75,38 @@ fn create_test_config(ingest_dir: &str) -> Config {
             acquire_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             max_lifetime: Duration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: Duration::from_millis(500),
+            statement_timeout: Duration::from_secs(30),
         },
         rate_limit: RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: Some(ingest_dir.to_string()),
+        compress_stored_events: false,
+        ingest: IngestConfig::default(),
+        digest: DigestConfig::default(),
+        pagination: PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: RetentionConfig::default(),
+        alerts: AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -93,12 +116,21 @@ async fn create_test_project(pool: &PgPool, name: &str) -> rustrak::models::Proj
         CreateProject {
             name: name.to_string(),
             slug: None,
+            team_id: None,
         },
     )
     .await
     .expect("Failed to create test project")
 }
 
+/// Returns the sentry_key of a project's default key
+async fn default_sentry_key(pool: &PgPool, project_id: i32) -> String {
+    let keys = ProjectKeyService::list(pool, project_id)
+        .await
+        .expect("Failed to list project keys");
+    keys[0].key.to_string()
+}
+
 /// Finds an available port for the test server
 fn get_available_port() -> u16 {
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind to port");
@@ -136,7 +168,7 @@ impl TestServer {
                             .route("", web::get().to(routes::health::liveness))
                             .route("/ready", web::get().to(routes::health::readiness)),
                     )
-                    .configure(routes::ingest::configure)
+                    .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST))
             })
             .bind(("127.0.0.1", port))
             .expect("Failed to bind server")
@@ -180,10 +212,17 @@ impl TestServer {
                         project_id,
                         ingested_at: chrono::Utc::now(),
                         remote_addr: None,
+                        spool_id: None,
                     };
 
-                    let _ =
-                        process_event(&self.pool, &metadata, ingest_path, rate_limit_config).await;
+                    let _ = process_event(
+                        &self.pool,
+                        &metadata,
+                        ingest_path,
+                        rate_limit_config,
+                        &DigestConfig::default(),
+                    )
+                    .await;
                 }
             }
         }
@@ -204,7 +243,7 @@ async fn test_sentry_sdk_capture_message() {
     let project = create_test_project(&db.pool, "SDK Message Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     // Configure Sentry SDK with test transport
     let _guard = sentry::init(sentry::ClientOptions {
@@ -231,6 +270,7 @@ async fn test_sentry_sdk_capture_message() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -260,7 +300,7 @@ async fn test_sentry_sdk_capture_error() {
     let project = create_test_project(&db.pool, "SDK Error Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -293,6 +333,7 @@ async fn test_sentry_sdk_capture_error() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -327,7 +368,7 @@ async fn test_sentry_sdk_with_custom_fingerprint() {
     let project = create_test_project(&db.pool, &project_name).await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     // Initialize Sentry client
     let _guard = sentry::init(sentry::ClientOptions {
@@ -363,6 +404,7 @@ async fn test_sentry_sdk_with_custom_fingerprint() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
 
     // Process events multiple times to ensure all events are digested
@@ -407,7 +449,7 @@ async fn test_sentry_sdk_with_stacktrace() {
     let project = create_test_project(&db.pool, "SDK Stacktrace Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -461,6 +503,7 @@ async fn test_sentry_sdk_with_stacktrace() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -490,7 +533,7 @@ async fn test_sentry_sdk_different_levels() {
     let project = create_test_project(&db.pool, "SDK Levels Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -520,6 +563,7 @@ async fn test_sentry_sdk_different_levels() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -549,7 +593,7 @@ async fn test_sentry_sdk_with_tags() {
     let project = create_test_project(&db.pool, "SDK Tags Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -574,6 +618,7 @@ async fn test_sentry_sdk_with_tags() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -602,7 +647,7 @@ async fn test_sentry_sdk_with_user_context() {
     let project = create_test_project(&db.pool, "SDK User Context Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -630,6 +675,7 @@ async fn test_sentry_sdk_with_user_context() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -658,7 +704,7 @@ async fn test_sentry_sdk_with_breadcrumbs() {
     let project = create_test_project(&db.pool, "SDK Breadcrumbs Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -694,6 +740,7 @@ async fn test_sentry_sdk_with_breadcrumbs() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -731,7 +778,7 @@ async fn test_sentry_sdk_groups_similar_errors() {
     let project = create_test_project(&db.pool, &project_name).await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     // Initialize Sentry client
     let _guard = sentry::init(sentry::ClientOptions {
@@ -768,6 +815,7 @@ async fn test_sentry_sdk_groups_similar_errors() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
 
     // Process events multiple times to ensure all events are digested
@@ -812,7 +860,7 @@ async fn test_sentry_sdk_separates_different_errors() {
     let project = create_test_project(&db.pool, "SDK Separation Test").await;
     let server = TestServer::new(&db).await;
 
-    let dsn = server.dsn(&project.sentry_key.to_string(), project.id);
+    let dsn = server.dsn(&default_sentry_key(&db.pool, project.id).await, project.id);
 
     let _guard = sentry::init(sentry::ClientOptions {
         dsn: dsn.parse().ok(),
@@ -851,6 +899,7 @@ async fn test_sentry_sdk_separates_different_errors() {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     };
     server
         .process_pending_events(project.id, &rate_limit_config)
@@ -869,7 +918,7 @@ async fn test_sentry_sdk_separates_different_errors() {
     .expect("Failed to list issues");
 
     // Should have separate issues for different error types
-    assert!(issues.len() >= 1);
+    assert!(!issues.is_empty());
 
     server.shutdown();
 }
@@ -4,20 +4,30 @@
 //! with a real PostgreSQL database.
 
 use actix_session::{storage::CookieSessionStore, SessionMiddleware};
-use actix_web::{cookie::Key, test, web, App};
+use actix_web::{cookie::Key, test, web, App, HttpResponse};
+use chrono::Utc;
 use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
+use rustrak::error::AppError;
 use rustrak::models::{
-    AlertType, ChannelType, CreateAlertRule, CreateNotificationChannel, UpdateAlertRule,
-    UpdateNotificationChannel,
+    AddProjectMember, AlertHistory, AlertStatus, AlertType, ChannelType, CreateAlertRule,
+    CreateNotificationChannel, CreateUserRequest, ProjectRole, UpdateAlertRule,
+    UpdateNotificationChannel, MASKED_HEADER_VALUE,
 };
 use rustrak::routes;
-use rustrak::services::{AlertService, ProjectService};
-use serde_json::json;
+use rustrak::services::grouping::DenormalizedFields;
+use rustrak::services::{
+    AlertDigestBuffer, AlertService, IssueService, ProjectMemberService, ProjectService,
+    UsersService,
+};
+use serde_json::{json, Value};
 use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use testcontainers::{runners::AsyncRunner, ContainerAsync};
 use testcontainers_modules::postgres::Postgres;
 
+use crate::common::session::{configure_login, login_request, session_cookie, session_middleware};
+
 /// Test database container with connection pool
 struct TestDb {
     #[allow(dead_code)]
@@ -71,18 +81,38 @@ fn create_test_config() -> Config {
             acquire_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             max_lifetime: Duration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: Duration::from_millis(500),
+            statement_timeout: Duration::from_secs(30),
         },
         rate_limit: RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: None,
+        compress_stored_events: false,
+        ingest: rustrak::config::IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -98,6 +128,7 @@ async fn create_test_project(pool: &PgPool) -> i32 {
         rustrak::models::CreateProject {
             name: format!("Test Project {}", chrono::Utc::now().timestamp_millis()),
             slug: None,
+            team_id: None,
         },
     )
     .await
@@ -160,7 +191,7 @@ async fn test_channel_crud_service_level() {
     assert!(!updated.is_enabled);
 
     // Delete channel
-    AlertService::delete_channel(&db.pool, channel.id)
+    AlertService::delete_channel(&db.pool, channel.id, false)
         .await
         .expect("Failed to delete channel");
 
@@ -194,8 +225,30 @@ async fn test_channel_duplicate_name_fails() {
 
     let result = AlertService::create_channel(&db.pool, create_input2).await;
     assert!(result.is_err());
-    let err_msg = format!("{:?}", result.unwrap_err());
-    assert!(err_msg.contains("already exists"));
+    let err = result.unwrap_err();
+    assert!(format!("{:?}", err).contains("already exists"));
+    assert_eq!(err.code(), "conflict.name_taken");
+    assert_eq!(err.field(), Some("name"));
+}
+
+#[tokio::test]
+async fn test_channel_invalid_config_reports_field() {
+    let db = TestDb::new().await;
+
+    let result = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "Bad Webhook".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": "not a url" }),
+            is_enabled: true,
+        },
+    )
+    .await;
+
+    let err = result.unwrap_err();
+    assert_eq!(err.code(), "validation.invalid");
+    assert_eq!(err.field(), Some("config"));
 }
 
 #[tokio::test]
@@ -214,6 +267,68 @@ async fn test_channel_invalid_config_fails() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_webhook_headers_masked_and_kept_on_update() {
+    let db = TestDb::new().await;
+
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "n8n".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({
+                "url": "https://example.com/webhook",
+                "method": "PUT",
+                "headers": { "X-Api-Key": "n8n-key" }
+            }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Responses show the header, not its value
+    let response = serde_json::to_value(channel.clone().masked()).unwrap();
+    assert_eq!(
+        response["config"]["headers"]["X-Api-Key"],
+        MASKED_HEADER_VALUE
+    );
+    assert_eq!(response["config"]["method"], "PUT");
+
+    // Sending the masked config back keeps the key
+    let mut config = response["config"].clone();
+    config["payload_style"] = json!("flat");
+    let updated = AlertService::update_channel(
+        &db.pool,
+        channel.id,
+        UpdateNotificationChannel {
+            name: None,
+            config: Some(config),
+            is_enabled: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.config["headers"]["X-Api-Key"], "n8n-key");
+    assert_eq!(updated.config["payload_style"], "flat");
+
+    let err = AlertService::update_channel(
+        &db.pool,
+        channel.id,
+        UpdateNotificationChannel {
+            name: None,
+            config: Some(json!({
+                "url": "https://example.com/webhook",
+                "headers": { "Transfer-Encoding": "chunked" }
+            })),
+            is_enabled: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.field(), Some("config"));
+}
+
 #[tokio::test]
 async fn test_slack_channel_config_validation() {
     let db = TestDb::new().await;
@@ -273,6 +388,7 @@ async fn test_rule_crud_service_level() {
         channel_ids: vec![channel.id],
         conditions: json!({}),
         cooldown_minutes: 5,
+        digest_minutes: 0,
     };
 
     let rule = AlertService::create_rule(&db.pool, project_id, create_input)
@@ -308,6 +424,7 @@ async fn test_rule_crud_service_level() {
         is_enabled: Some(false),
         conditions: None,
         cooldown_minutes: Some(10),
+        digest_minutes: None,
         channel_ids: None,
     };
 
@@ -329,49 +446,105 @@ async fn test_rule_crud_service_level() {
 }
 
 #[tokio::test]
-async fn test_rule_duplicate_alert_type_fails() {
+async fn test_rule_multiple_per_alert_type() {
     let db = TestDb::new().await;
 
     let project_id = create_test_project(&db.pool).await;
 
-    let channel = AlertService::create_channel(
-        &db.pool,
-        CreateNotificationChannel {
-            name: "Channel for Rules".to_string(),
-            channel_type: ChannelType::Webhook,
-            config: json!({ "url": "https://example.com/webhook" }),
-            is_enabled: true,
-        },
-    )
-    .await
-    .expect("Failed to create channel");
-
-    // Create first rule
-    let create_input = CreateAlertRule {
-        name: "First Rule".to_string(),
+    let rule = |name: &str, conditions: Value| CreateAlertRule {
+        name: name.to_string(),
         alert_type: AlertType::NewIssue,
-        channel_ids: vec![channel.id],
-        conditions: json!({}),
+        channel_ids: vec![],
+        conditions,
         cooldown_minutes: 0,
+        digest_minutes: 0,
     };
 
-    AlertService::create_rule(&db.pool, project_id, create_input)
+    AlertService::create_rule(&db.pool, project_id, rule("Everything", json!({})))
         .await
         .expect("First rule should succeed");
+    AlertService::create_rule(
+        &db.pool,
+        project_id,
+        rule("Fatals", json!({ "filters": { "min_level": "fatal" } })),
+    )
+    .await
+    .expect("Second rule of the same type should succeed");
 
-    // Try to create another with same alert type
-    let duplicate_input = CreateAlertRule {
-        name: "Duplicate Rule".to_string(),
-        alert_type: AlertType::NewIssue, // Same type
-        channel_ids: vec![channel.id],
-        conditions: json!({}),
-        cooldown_minutes: 0,
-    };
+    let rules = AlertService::list_rules(&db.pool, project_id)
+        .await
+        .unwrap();
+    assert_eq!(rules.len(), 2);
+}
 
-    let result = AlertService::create_rule(&db.pool, project_id, duplicate_input).await;
-    assert!(result.is_err());
-    let err_msg = format!("{:?}", result.unwrap_err());
-    assert!(err_msg.contains("already exists"));
+#[tokio::test]
+async fn test_rule_filters_validation() {
+    let db = TestDb::new().await;
+
+    let project_id = create_test_project(&db.pool).await;
+
+    for conditions in [
+        json!([]),
+        json!({ "filters": { "min_level": "critical" } }),
+        json!({ "filters": { "environments": "production" } }),
+        json!({ "filters": { "environments": [""] } }),
+        json!({ "filters": { "title_contains": " " } }),
+        json!({ "filters": { "level": "fatal" } }),
+    ] {
+        let result = AlertService::create_rule(
+            &db.pool,
+            project_id,
+            CreateAlertRule {
+                name: "Filtered".to_string(),
+                alert_type: AlertType::NewIssue,
+                channel_ids: vec![],
+                conditions: conditions.clone(),
+                cooldown_minutes: 0,
+                digest_minutes: 0,
+            },
+        )
+        .await;
+        match result {
+            Err(err) => assert_eq!(err.field(), Some("conditions"), "{}", conditions),
+            Ok(_) => panic!("{} was accepted", conditions),
+        }
+    }
+
+    let rule = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Filtered".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![],
+            conditions: json!({
+                "filters": {
+                    "min_level": "error",
+                    "environments": ["production", "staging"],
+                    "title_contains": "Timeout"
+                }
+            }),
+            cooldown_minutes: 0,
+            digest_minutes: 0,
+        },
+    )
+    .await
+    .expect("Valid filters should be accepted");
+
+    let result = AlertService::update_rule(
+        &db.pool,
+        rule.id,
+        UpdateAlertRule {
+            name: None,
+            is_enabled: None,
+            conditions: Some(json!({ "filters": { "min_level": "loud" } })),
+            cooldown_minutes: None,
+            digest_minutes: None,
+            channel_ids: None,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::InvalidField { .. })));
 }
 
 #[tokio::test]
@@ -387,6 +560,7 @@ async fn test_rule_with_invalid_channel_fails() {
         channel_ids: vec![99999], // Non-existent
         conditions: json!({}),
         cooldown_minutes: 0,
+        digest_minutes: 0,
     };
 
     let result = AlertService::create_rule(&db.pool, project_id, create_input).await;
@@ -434,6 +608,7 @@ async fn test_update_rule_channels() {
             channel_ids: vec![channel1.id],
             conditions: json!({}),
             cooldown_minutes: 0,
+            digest_minutes: 0,
         },
     )
     .await
@@ -454,6 +629,7 @@ async fn test_update_rule_channels() {
             is_enabled: None,
             conditions: None,
             cooldown_minutes: None,
+            digest_minutes: None,
             channel_ids: Some(vec![channel1.id, channel2.id]),
         },
     )
@@ -477,6 +653,7 @@ async fn test_update_rule_channels() {
             is_enabled: None,
             conditions: None,
             cooldown_minutes: None,
+            digest_minutes: None,
             channel_ids: Some(vec![channel2.id]),
         },
     )
@@ -518,107 +695,1014 @@ async fn test_deleting_channel_removes_from_rules() {
             channel_ids: vec![channel.id],
             conditions: json!({}),
             cooldown_minutes: 0,
+            digest_minutes: 0,
         },
     )
     .await
     .unwrap();
 
-    // Delete channel
-    AlertService::delete_channel(&db.pool, channel.id)
+    insert_history(&db.pool, project_id, channel.id, "sent").await;
+
+    // Refused without force, naming the rules using the channel
+    let result = AlertService::delete_channel(&db.pool, channel.id, false).await;
+    match result {
+        Err(AppError::InUse { dependents, .. }) => {
+            assert_eq!(dependents, vec!["Rule with deletable channel".to_string()]);
+        }
+        other => panic!("Expected InUse error, got {:?}", other),
+    }
+    assert!(AlertService::get_channel(&db.pool, channel.id)
+        .await
+        .is_ok());
+    let channels = AlertService::get_rule_channels(&db.pool, rule.id)
         .await
         .unwrap();
+    assert_eq!(channels, vec![channel.id]);
 
-    // Rule should still exist but have no channels
+    // Forced: detached from the rule, which still exists
+    let detached = AlertService::delete_channel(&db.pool, channel.id, true)
+        .await
+        .unwrap();
+    assert_eq!(detached, 1);
+    assert!(AlertService::get_channel(&db.pool, channel.id)
+        .await
+        .is_err());
     let channels = AlertService::get_rule_channels(&db.pool, rule.id)
         .await
         .unwrap();
     assert!(channels.is_empty());
+    AlertService::get_rule(&db.pool, rule.id).await.unwrap();
+
+    // History keeps the channel's name for auditing
+    let (channel_id, channel_name): (Option<i32>, String) =
+        sqlx::query_as("SELECT channel_id, channel_name FROM alert_history WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(channel_id, None);
+    assert_eq!(channel_name, "Test");
 }
 
 #[tokio::test]
-async fn test_alert_history_empty() {
+async fn test_rule_digest_minutes_validation() {
     let db = TestDb::new().await;
 
     let project_id = create_test_project(&db.pool).await;
 
-    let history = AlertService::list_history(&db.pool, project_id, 50)
-        .await
-        .expect("Failed to list history");
+    let result = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Too long digest".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 24 * 60 + 1,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
 
-    assert!(history.is_empty());
+    let rule = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Digest".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 15,
+        },
+    )
+    .await
+    .expect("Failed to create digest rule");
+    assert_eq!(rule.digest_minutes, 15);
+
+    let update = |digest_minutes| UpdateAlertRule {
+        name: None,
+        is_enabled: None,
+        conditions: None,
+        cooldown_minutes: None,
+        digest_minutes: Some(digest_minutes),
+        channel_ids: None,
+    };
+
+    let result = AlertService::update_rule(&db.pool, rule.id, update(-1)).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let updated = AlertService::update_rule(&db.pool, rule.id, update(0))
+        .await
+        .expect("Failed to switch rule back to immediate");
+    assert_eq!(updated.digest_minutes, 0);
 }
 
-// =============================================================================
-// HTTP Route Tests
-// =============================================================================
+/// Records the JSON body of every request, like a webhook receiver would
+fn recording_webhook() -> (actix_test::TestServer, Arc<Mutex<Vec<Value>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let server = actix_test::start(move || {
+        let sink = sink.clone();
+        App::new().default_service(web::to(move |body: web::Json<Value>| {
+            let sink = sink.clone();
+            async move {
+                sink.lock().unwrap().push(body.into_inner());
+                HttpResponse::Ok().finish()
+            }
+        }))
+    });
+    (server, received)
+}
 
-// Note: These tests are marked as ignored because actix-web's test framework
-// doesn't properly preserve session cookies. Full testing should be done via
-// E2E tests with a real HTTP client.
+/// Waits for the spawned channel dispatches to record their history
+async fn wait_for_sent_history(
+    pool: &PgPool,
+    project_id: i32,
+    expected: usize,
+) -> Vec<AlertHistory> {
+    for _ in 0..50 {
+        let history = AlertService::list_history(pool, project_id, 50)
+            .await
+            .unwrap();
+        if history
+            .iter()
+            .filter(|h| h.status == AlertStatus::Sent)
+            .count()
+            >= expected
+        {
+            return history;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Alerts were not sent in time");
+}
 
 #[actix_web::test]
-async fn test_list_channels_unauthorized() {
+async fn test_digest_rule_sends_single_summary() {
     let db = TestDb::new().await;
-    let config = create_test_config();
+    let (server, received) = recording_webhook();
 
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(db.pool.clone()))
-            .app_data(web::Data::new(config))
-            .wrap(
-                SessionMiddleware::builder(CookieSessionStore::default(), test_session_key())
-                    .cookie_secure(false)
-                    .build(),
-            )
-            .configure(routes::alerts::configure),
+    let project_id = create_test_project(&db.pool).await;
+    let project = ProjectService::get_by_id(&db.pool, project_id)
+        .await
+        .unwrap();
+
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "Digest Webhook".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": server.url("/hook") }),
+            is_enabled: true,
+        },
     )
-    .await;
+    .await
+    .unwrap();
 
-    // No session cookie
-    let req = test::TestRequest::get()
-        .uri("/api/alert-channels")
-        .to_request();
+    let rule = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Hourly digest".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![channel.id],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 60,
+        },
+    )
+    .await
+    .unwrap();
 
-    let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 401);
+    // Queue three new issues into the same window
+    let buffer = Arc::new(AlertDigestBuffer::new());
+    for value in ["first", "second", "third"] {
+        let issue = IssueService::create(
+            &db.pool,
+            project_id,
+            Utc::now(),
+            &DenormalizedFields {
+                calculated_type: "Error".to_string(),
+                calculated_value: value.to_string(),
+                transaction: "/digest".to_string(),
+                last_frame_filename: "test.rs".to_string(),
+                last_frame_module: "test_module".to_string(),
+                last_frame_function: "test_function".to_string(),
+                exception_chain_length: 1,
+                is_unhandled: false,
+            },
+            Some("error"),
+            Some("rust"),
+        )
+        .await
+        .unwrap();
+        AlertService::queue_digest(
+            &db.pool,
+            &buffer,
+            &rule,
+            &project,
+            &issue,
+            "https://rustrak.example.com",
+            120,
+        )
+        .await;
+    }
+    assert_eq!(buffer.len(rule.id), 3);
+
+    // Nothing is sent until the window is flushed
+    assert!(received.lock().unwrap().is_empty());
+
+    AlertService::flush_digest(&db.pool, &buffer, rule.id, "https://rustrak.example.com")
+        .await
+        .expect("Failed to flush digest");
+    assert_eq!(buffer.len(rule.id), 0);
+
+    let history = wait_for_sent_history(&db.pool, project_id, 1).await;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].issue_id, None);
+    assert_eq!(history[0].alert_type, "new_issue");
+
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 1);
+    let issues = received[0]["issues"].as_array().expect("issues list");
+    assert_eq!(issues.len(), 3);
+    assert_eq!(issues[0]["title"], "Error: first");
+    assert!(issues[2]["issue_url"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://rustrak.example.com/projects/"));
+    assert!(received[0].get("issue").is_none());
+
+    // Flushing a closed window sends nothing more
+    AlertService::flush_digest(&db.pool, &buffer, rule.id, "https://rustrak.example.com")
+        .await
+        .unwrap();
+    assert_eq!(
+        AlertService::list_history(&db.pool, project_id, 50)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
 }
 
 #[actix_web::test]
-async fn test_list_rules_unauthorized() {
+async fn test_digest_with_single_issue_sends_regular_alert() {
     let db = TestDb::new().await;
-    let config = create_test_config();
+    let (server, received) = recording_webhook();
 
-    // Create a test project first
     let project_id = create_test_project(&db.pool).await;
+    let project = ProjectService::get_by_id(&db.pool, project_id)
+        .await
+        .unwrap();
 
-    let app = test::init_service(
-        App::new()
-            .app_data(web::Data::new(db.pool.clone()))
-            .app_data(web::Data::new(config))
-            .wrap(
-                SessionMiddleware::builder(CookieSessionStore::default(), test_session_key())
-                    .cookie_secure(false)
-                    .build(),
-            )
-            .configure(routes::alerts::configure),
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "Digest Webhook".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": server.url("/hook") }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .unwrap();
+
+    let rule = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Hourly digest".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![channel.id],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 60,
+        },
+    )
+    .await
+    .unwrap();
+
+    let issue = IssueService::create(
+        &db.pool,
+        project_id,
+        Utc::now(),
+        &DenormalizedFields {
+            calculated_type: "Error".to_string(),
+            calculated_value: "alone".to_string(),
+            transaction: "/digest".to_string(),
+            last_frame_filename: "test.rs".to_string(),
+            last_frame_module: "test_module".to_string(),
+            last_frame_function: "test_function".to_string(),
+            exception_chain_length: 1,
+            is_unhandled: false,
+        },
+        Some("error"),
+        Some("rust"),
+    )
+    .await
+    .unwrap();
+
+    let buffer = Arc::new(AlertDigestBuffer::new());
+    AlertService::queue_digest(
+        &db.pool,
+        &buffer,
+        &rule,
+        &project,
+        &issue,
+        "http://localhost:3000",
+        120,
     )
     .await;
+    AlertService::flush_digest(&db.pool, &buffer, rule.id, "http://localhost:3000")
+        .await
+        .unwrap();
 
-    let req = test::TestRequest::get()
-        .uri(&format!("/api/projects/{}/alert-rules", project_id))
-        .to_request();
+    let history = wait_for_sent_history(&db.pool, project_id, 1).await;
+    assert_eq!(history[0].issue_id, Some(issue.id));
 
-    let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 401);
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received[0]["issue"]["id"], issue.id.to_string());
+    assert!(received[0].get("issues").is_none());
 }
 
 #[actix_web::test]
-async fn test_list_history_unauthorized() {
+async fn test_full_digest_buffer_sends_window_early() {
     let db = TestDb::new().await;
-    let config = create_test_config();
+    let (server, received) = recording_webhook();
 
     let project_id = create_test_project(&db.pool).await;
-
+    let project = ProjectService::get_by_id(&db.pool, project_id)
+        .await
+        .unwrap();
+
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "Digest Webhook".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": server.url("/hook") }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .unwrap();
+
+    let rule = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Hourly digest".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![channel.id],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 60,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Room for two issues; the third sends the window without waiting an hour
+    let buffer = Arc::new(AlertDigestBuffer::with_capacity(2));
+    for value in ["first", "second", "third"] {
+        let issue = create_issue_with_level(&db.pool, project_id, value, "error").await;
+        AlertService::queue_digest(
+            &db.pool,
+            &buffer,
+            &rule,
+            &project,
+            &issue,
+            "http://localhost:3000",
+            120,
+        )
+        .await;
+    }
+    assert_eq!(buffer.total(), 0);
+
+    let history = wait_for_sent_history(&db.pool, project_id, 1).await;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].issue_id, None);
+
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0]["issues"].as_array().unwrap().len(), 3);
+}
+
+async fn create_issue_with_level(
+    pool: &PgPool,
+    project_id: i32,
+    value: &str,
+    level: &str,
+) -> rustrak::models::Issue {
+    IssueService::create(
+        pool,
+        project_id,
+        Utc::now(),
+        &DenormalizedFields {
+            calculated_type: "Error".to_string(),
+            calculated_value: value.to_string(),
+            transaction: "/filters".to_string(),
+            last_frame_filename: "test.rs".to_string(),
+            last_frame_module: "test_module".to_string(),
+            last_frame_function: "test_function".to_string(),
+            exception_chain_length: 1,
+            is_unhandled: false,
+        },
+        Some(level),
+        Some("rust"),
+    )
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn test_filtered_rules_notify_their_own_channels() {
+    let db = TestDb::new().await;
+    let (pager_server, pager_received) = recording_webhook();
+    let (chat_server, chat_received) = recording_webhook();
+
+    let project_id = create_test_project(&db.pool).await;
+    let project = ProjectService::get_by_id(&db.pool, project_id)
+        .await
+        .unwrap();
+
+    let mut channel_ids = Vec::new();
+    for (name, server) in [("Pager", &pager_server), ("Chat", &chat_server)] {
+        let channel = AlertService::create_channel(
+            &db.pool,
+            CreateNotificationChannel {
+                name: format!("{} {}", name, project_id),
+                channel_type: ChannelType::Webhook,
+                config: json!({ "url": server.url("/hook") }),
+                is_enabled: true,
+            },
+        )
+        .await
+        .unwrap();
+        channel_ids.push(channel.id);
+    }
+
+    for (name, channel_id, conditions) in [
+        (
+            "Fatals to pager",
+            channel_ids[0],
+            json!({ "filters": { "min_level": "fatal" } }),
+        ),
+        ("Everything to chat", channel_ids[1], json!({})),
+    ] {
+        AlertService::create_rule(
+            &db.pool,
+            project_id,
+            CreateAlertRule {
+                name: name.to_string(),
+                alert_type: AlertType::NewIssue,
+                channel_ids: vec![channel_id],
+                conditions,
+                cooldown_minutes: 0,
+                digest_minutes: 0,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    // A fatal issue notifies both rules
+    let fatal = create_issue_with_level(&db.pool, project_id, "crash", "fatal").await;
+    AlertService::trigger_new_issue_alert(
+        &db.pool,
+        &project,
+        &fatal,
+        Some("production"),
+        "http://localhost:3000",
+        120,
+    )
+    .await
+    .unwrap();
+    wait_for_sent_history(&db.pool, project_id, 2).await;
+
+    // A warning only notifies the unfiltered rule
+    let warning = create_issue_with_level(&db.pool, project_id, "slow", "warning").await;
+    AlertService::trigger_new_issue_alert(
+        &db.pool,
+        &project,
+        &warning,
+        Some("production"),
+        "http://localhost:3000",
+        120,
+    )
+    .await
+    .unwrap();
+    wait_for_sent_history(&db.pool, project_id, 3).await;
+
+    // Give a stray delivery time to show up
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let pager = pager_received.lock().unwrap().clone();
+    assert_eq!(pager.len(), 1);
+    assert_eq!(pager[0]["issue"]["id"], fatal.id.to_string());
+
+    let mut chat: Vec<String> = chat_received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|body| body["issue"]["id"].as_str().unwrap().to_string())
+        .collect();
+    chat.sort();
+    let mut expected = vec![fatal.id.to_string(), warning.id.to_string()];
+    expected.sort();
+    assert_eq!(chat, expected);
+}
+
+#[actix_web::test]
+async fn test_rule_environment_and_title_filters() {
+    let db = TestDb::new().await;
+    let (server, received) = recording_webhook();
+
+    let project_id = create_test_project(&db.pool).await;
+    let project = ProjectService::get_by_id(&db.pool, project_id)
+        .await
+        .unwrap();
+
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: format!("Filters {}", project_id),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": server.url("/hook") }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .unwrap();
+
+    AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Production timeouts".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![channel.id],
+            conditions: json!({
+                "filters": { "environments": ["production"], "title_contains": "TIMEOUT" }
+            }),
+            cooldown_minutes: 0,
+            digest_minutes: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let cases = [
+        ("Request timeout", Some("staging")),
+        ("Request timeout", None),
+        ("Connection refused", Some("production")),
+        ("Request timeout", Some("production")),
+    ];
+    for (value, environment) in cases {
+        let issue = create_issue_with_level(&db.pool, project_id, value, "error").await;
+        AlertService::trigger_new_issue_alert(
+            &db.pool,
+            &project,
+            &issue,
+            environment,
+            "http://localhost:3000",
+            120,
+        )
+        .await
+        .unwrap();
+    }
+
+    wait_for_sent_history(&db.pool, project_id, 1).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0]["issue"]["title"], "Error: Request timeout");
+}
+
+/// Answers every delivery with a 500, like a dead webhook
+fn failing_webhook() -> (actix_test::TestServer, Arc<Mutex<usize>>) {
+    let hits = Arc::new(Mutex::new(0));
+    let counter = hits.clone();
+    let server = actix_test::start(move || {
+        let counter = counter.clone();
+        App::new().default_service(web::to(move || {
+            let counter = counter.clone();
+            async move {
+                *counter.lock().unwrap() += 1;
+                HttpResponse::InternalServerError().finish()
+            }
+        }))
+    });
+    (server, hits)
+}
+
+#[actix_web::test]
+async fn test_channel_disabled_after_repeated_failures() {
+    let db = TestDb::new().await;
+    let (server, hits) = failing_webhook();
+    let threshold = AlertService::auto_disable_after() as usize;
+    assert_eq!(threshold, 20);
+
+    let project_id = create_test_project(&db.pool).await;
+    let project = ProjectService::get_by_id(&db.pool, project_id)
+        .await
+        .unwrap();
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "Dead Webhook".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": server.url("/hook") }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .unwrap();
+    AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "Everything".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![channel.id],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    for i in 0..threshold {
+        let issue =
+            create_issue_with_level(&db.pool, project_id, &format!("e{}", i), "error").await;
+        AlertService::trigger_new_issue_alert(
+            &db.pool,
+            &project,
+            &issue,
+            None,
+            "http://localhost:3000",
+            120,
+        )
+        .await
+        .unwrap();
+    }
+
+    let mut disabled = None;
+    for _ in 0..100 {
+        let channel = AlertService::get_channel(&db.pool, channel.id)
+            .await
+            .unwrap();
+        if !channel.is_enabled {
+            disabled = Some(channel);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let disabled = disabled.expect("Channel was not disabled");
+    assert_eq!(disabled.failure_count, threshold as i32);
+    assert_eq!(*hits.lock().unwrap(), threshold);
+
+    let activity: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT action, message FROM notification_channel_activity WHERE channel_id = $1",
+    )
+    .bind(channel.id)
+    .fetch_all(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!(activity.len(), 1);
+    assert_eq!(activity[0].0, "auto_disabled");
+    assert!(activity[0]
+        .1
+        .as_deref()
+        .unwrap()
+        .starts_with("Disabled after 20 consecutive failed deliveries"));
+
+    let health = AlertService::channel_health(&db.pool).await.unwrap();
+    let entry = health.iter().find(|h| h.id == channel.id).unwrap();
+    assert!(!entry.is_enabled);
+    assert_eq!(entry.failure_streak, threshold as i32);
+    assert!(entry.auto_disabled_at.is_some());
+    assert!(entry.last_failure_message.is_some());
+
+    // A disabled channel isn't sent to anymore
+    let issue = create_issue_with_level(&db.pool, project_id, "later", "error").await;
+    AlertService::trigger_new_issue_alert(
+        &db.pool,
+        &project,
+        &issue,
+        None,
+        "http://localhost:3000",
+        120,
+    )
+    .await
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(*hits.lock().unwrap(), threshold);
+
+    // Resetting clears the streak and enables it again
+    let reset = AlertService::reset_channel_failures(&db.pool, channel.id)
+        .await
+        .unwrap();
+    assert!(reset.is_enabled);
+    assert_eq!(reset.failure_count, 0);
+
+    let health = AlertService::channel_health(&db.pool).await.unwrap();
+    let entry = health.iter().find(|h| h.id == channel.id).unwrap();
+    assert!(entry.is_enabled);
+    assert_eq!(entry.failure_streak, 0);
+    assert!(entry.auto_disabled_at.is_none());
+
+    let action: String = sqlx::query_scalar(
+        "SELECT action FROM notification_channel_activity WHERE channel_id = $1 ORDER BY id DESC LIMIT 1",
+    )
+    .bind(channel.id)
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!(action, "failures_reset");
+
+    let result = AlertService::reset_channel_failures(&db.pool, 999_999).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_alert_history_empty() {
+    let db = TestDb::new().await;
+
+    let project_id = create_test_project(&db.pool).await;
+
+    let history = AlertService::list_history(&db.pool, project_id, 50)
+        .await
+        .expect("Failed to list history");
+
+    assert!(history.is_empty());
+}
+
+/// Inserts an alert history entry for a channel
+async fn insert_history(pool: &PgPool, project_id: i32, channel_id: i32, status: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO alert_history (
+            channel_id, project_id, alert_type, channel_type, channel_name,
+            status, idempotency_key
+        )
+        VALUES ($1, $2, 'new_issue', 'webhook', 'Test', $3, gen_random_uuid()::text)
+        "#,
+    )
+    .bind(channel_id)
+    .bind(project_id)
+    .bind(status)
+    .execute(pool)
+    .await
+    .expect("Failed to insert alert history");
+}
+
+#[tokio::test]
+async fn test_channel_stats_success_rate() {
+    let db = TestDb::new().await;
+    let project_id = create_test_project(&db.pool).await;
+    let channel = AlertService::create_channel(
+        &db.pool,
+        CreateNotificationChannel {
+            name: "Flaky Hook".to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": "https://example.com/webhook" }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .expect("Failed to create channel");
+
+    // No deliveries yet: no rate to compute
+    let since = Utc::now() - chrono::Duration::hours(24);
+    let stats = AlertService::channel_stats(&db.pool, channel.id, since)
+        .await
+        .unwrap();
+    assert_eq!(stats.attempts, 0);
+    assert_eq!(stats.success_rate, None);
+
+    for status in ["sent", "sent", "sent", "failed", "skipped", "pending"] {
+        insert_history(&db.pool, project_id, channel.id, status).await;
+    }
+    // A delivery that failed once and waits for a retry counts as a failure
+    sqlx::query(
+        "UPDATE alert_history SET attempt_count = 1 WHERE id = (SELECT MAX(id) FROM alert_history)",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    // Deliveries before the window aren't counted
+    insert_history(&db.pool, project_id, channel.id, "failed").await;
+    sqlx::query(
+        "UPDATE alert_history SET created_at = NOW() - INTERVAL '2 days' WHERE id = (SELECT MAX(id) FROM alert_history)",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "UPDATE notification_channels SET failure_count = 2, last_success_at = NOW() WHERE id = $1",
+    )
+    .bind(channel.id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let stats = AlertService::channel_stats(&db.pool, channel.id, since)
+        .await
+        .unwrap();
+    assert_eq!(stats.channel_id, channel.id);
+    assert_eq!(stats.attempts, 5);
+    assert_eq!(stats.successes, 3);
+    assert_eq!(stats.failures, 2);
+    assert_eq!(stats.success_rate, Some(0.6));
+    assert_eq!(stats.failure_count, 2);
+    assert!(stats.last_success_at.is_some());
+
+    let week = AlertService::channel_stats(&db.pool, channel.id, since - chrono::Duration::days(6))
+        .await
+        .unwrap();
+    assert_eq!(week.failures, 3);
+    assert_eq!(week.success_rate, Some(0.5));
+
+    let result = AlertService::channel_stats(&db.pool, 999_999, since).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_alert_history_paginated_and_filtered() {
+    let db = TestDb::new().await;
+    let project_id = create_test_project(&db.pool).await;
+
+    let mut channel_ids = Vec::new();
+    for name in ["Hook A", "Hook B"] {
+        let channel = AlertService::create_channel(
+            &db.pool,
+            CreateNotificationChannel {
+                name: name.to_string(),
+                channel_type: ChannelType::Webhook,
+                config: json!({ "url": "https://example.com/webhook" }),
+                is_enabled: true,
+            },
+        )
+        .await
+        .expect("Failed to create channel");
+        channel_ids.push(channel.id);
+    }
+
+    // Hook A: 3 sent, 2 failed; Hook B: 1 sent, 1 pending
+    for status in ["sent", "sent", "sent", "failed", "failed"] {
+        insert_history(&db.pool, project_id, channel_ids[0], status).await;
+    }
+    for status in ["sent", "pending"] {
+        insert_history(&db.pool, project_id, channel_ids[1], status).await;
+    }
+
+    // Pages of 3 over all 7 entries
+    let (page1, total) =
+        AlertService::list_history_paginated(&db.pool, project_id, None, None, 1, 3)
+            .await
+            .unwrap();
+    assert_eq!(total, 7);
+    assert_eq!(page1.len(), 3);
+
+    let (page3, _) = AlertService::list_history_paginated(&db.pool, project_id, None, None, 3, 3)
+        .await
+        .unwrap();
+    assert_eq!(page3.len(), 1);
+
+    // No entry shows up on two pages
+    let (page2, _) = AlertService::list_history_paginated(&db.pool, project_id, None, None, 2, 3)
+        .await
+        .unwrap();
+    let mut ids: Vec<i64> = page1
+        .iter()
+        .chain(&page2)
+        .chain(&page3)
+        .map(|h| h.id)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 7);
+
+    // By status
+    let (failed, total) = AlertService::list_history_paginated(
+        &db.pool,
+        project_id,
+        Some(AlertStatus::Failed),
+        None,
+        1,
+        20,
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 2);
+    assert!(failed.iter().all(|h| h.status == AlertStatus::Failed));
+
+    // By channel
+    let (hook_b, total) = AlertService::list_history_paginated(
+        &db.pool,
+        project_id,
+        None,
+        Some(channel_ids[1]),
+        1,
+        20,
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 2);
+    assert!(hook_b.iter().all(|h| h.channel_id == Some(channel_ids[1])));
+
+    // Both filters
+    let (sent_a, total) = AlertService::list_history_paginated(
+        &db.pool,
+        project_id,
+        Some(AlertStatus::Sent),
+        Some(channel_ids[0]),
+        1,
+        20,
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(sent_a.len(), 3);
+}
+
+// =============================================================================
+// HTTP Route Tests
+// =============================================================================
+
+// Note: These tests are marked as ignored because actix-web's test framework
+// doesn't properly preserve session cookies. Full testing should be done via
+// E2E tests with a real HTTP client.
+
+#[actix_web::test]
+async fn test_list_channels_unauthorized() {
+    let db = TestDb::new().await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .wrap(
+                SessionMiddleware::builder(CookieSessionStore::default(), test_session_key())
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .configure(routes::alerts::configure),
+    )
+    .await;
+
+    // No session cookie
+    let req = test::TestRequest::get()
+        .uri("/api/alert-channels")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_list_rules_unauthorized() {
+    let db = TestDb::new().await;
+    let config = create_test_config();
+
+    // Create a test project first
+    let project_id = create_test_project(&db.pool).await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .wrap(
+                SessionMiddleware::builder(CookieSessionStore::default(), test_session_key())
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .configure(routes::alerts::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/alert-rules", project_id))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_list_history_unauthorized() {
+    let db = TestDb::new().await;
+    let config = create_test_config();
+
+    let project_id = create_test_project(&db.pool).await;
+
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
@@ -640,6 +1724,95 @@ async fn test_list_history_unauthorized() {
     assert_eq!(resp.status(), 401);
 }
 
+#[actix_web::test]
+async fn test_alert_rules_require_membership() {
+    let db = TestDb::new().await;
+    let config = create_test_config();
+    let project_id = create_test_project(&db.pool).await;
+    let rule = AlertService::create_rule(
+        &db.pool,
+        project_id,
+        CreateAlertRule {
+            name: "New Issue Alert".to_string(),
+            alert_type: AlertType::NewIssue,
+            channel_ids: vec![],
+            conditions: json!({}),
+            cooldown_minutes: 0,
+            digest_minutes: 0,
+        },
+    )
+    .await
+    .unwrap();
+    let outsider = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "outsider@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        false,
+    )
+    .await
+    .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::alerts::configure),
+    )
+    .await;
+    let resp = test::call_service(&app, login_request(outsider.id).to_request()).await;
+    let cookie = session_cookie(&resp);
+
+    let rules_uri = format!("/api/projects/{}/alert-rules", project_id);
+    let rule_uri = format!("{}/{}", rules_uri, rule.id);
+    let requests = [
+        test::TestRequest::get().uri(&rules_uri),
+        test::TestRequest::post().uri(&rules_uri).set_json(json!({
+            "name": "Sneaky Rule",
+            "alert_type": "new_issue",
+            "channel_ids": [],
+        })),
+        test::TestRequest::get().uri(&rule_uri),
+        test::TestRequest::patch()
+            .uri(&rule_uri)
+            .set_json(json!({ "is_enabled": false })),
+        test::TestRequest::delete().uri(&rule_uri),
+        test::TestRequest::get().uri(&format!("/api/projects/{}/alert-history", project_id)),
+    ];
+    for req in requests {
+        let resp = test::call_service(&app, req.cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    // Nothing was changed by the outsider
+    let rules = AlertService::list_rules(&db.pool, project_id)
+        .await
+        .unwrap();
+    assert_eq!(rules.len(), 1);
+    assert!(rules[0].is_enabled);
+
+    // Members can read the rules
+    ProjectMemberService::add(
+        &db.pool,
+        project_id,
+        AddProjectMember {
+            user_id: outsider.id,
+            role: ProjectRole::Member,
+        },
+    )
+    .await
+    .unwrap();
+    let req = test::TestRequest::get()
+        .uri(&rules_uri)
+        .cookie(cookie)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+}
+
 #[actix_web::test]
 #[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
 async fn test_create_channel_success() {
@@ -5,8 +5,10 @@
 
 use actix_session::{storage::CookieSessionStore, SessionMiddleware};
 use actix_web::{cookie::Key, test, web, App};
-use rustrak::config::{Config, DatabaseConfig};
+use rustrak::config::{BodyLimitConfig, Config, DatabaseConfig};
 use rustrak::middleware::auth::RequireAuth;
+use rustrak::middleware::cors::CorsPolicy;
+use rustrak::middleware::rate_limit::ApiRateLimiter;
 use rustrak::models::User;
 use rustrak::routes;
 use rustrak::services::UsersService;
@@ -69,18 +71,38 @@ fn create_test_config() -> Config {
             acquire_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             max_lifetime: Duration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: Duration::from_millis(500),
+            statement_timeout: Duration::from_secs(30),
         },
         rate_limit: rustrak::config::RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: None,
+        compress_stored_events: false,
+        ingest: rustrak::config::IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -184,6 +206,9 @@ async fn test_register_invalid_email() {
 
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "validation.invalid_format");
+    assert_eq!(body["error"]["field"], "email");
 }
 
 #[actix_web::test]
@@ -217,6 +242,9 @@ async fn test_register_empty_password_rejected() {
 
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "validation.required");
+    assert_eq!(body["error"]["field"], "password");
 }
 
 #[actix_web::test]
@@ -252,6 +280,9 @@ async fn test_register_duplicate_email() {
 
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "validation.email_taken");
+    assert_eq!(body["error"]["field"], "email");
 }
 
 #[actix_web::test]
@@ -558,11 +589,7 @@ async fn test_get_current_user_authenticated() {
     assert_eq!(login_resp.status(), 200);
 
     // Extract session cookie
-    let cookies: Vec<_> = login_resp
-        .headers()
-        .get_all("set-cookie")
-        .into_iter()
-        .collect();
+    let cookies: Vec<_> = login_resp.headers().get_all("set-cookie").collect();
     assert!(!cookies.is_empty());
 
     let cookie_value = cookies[0].to_str().unwrap();
@@ -732,11 +759,7 @@ async fn test_middleware_allows_authenticated_access() {
         .to_request();
 
     let login_resp = test::call_service(&app, login_req).await;
-    let cookies: Vec<_> = login_resp
-        .headers()
-        .get_all("set-cookie")
-        .into_iter()
-        .collect();
+    let cookies: Vec<_> = login_resp.headers().get_all("set-cookie").collect();
     let cookie_value = cookies[0].to_str().unwrap();
 
     // Now access protected route with session
@@ -814,6 +837,142 @@ async fn test_middleware_exempts_health_routes() {
     assert_eq!(resp.status(), 200);
 }
 
+// =============================================================================
+// CORS Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_cors_preflight_allows_credentials_for_allowed_origin() {
+    let config = create_test_config();
+    let session_key = Key::from(&[0u8; 64]);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config))
+            .wrap(CorsPolicy::new(&["https://dash.example.com".to_string()]))
+            .wrap(
+                SessionMiddleware::builder(CookieSessionStore::default(), session_key.clone())
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .wrap(RequireAuth)
+            .configure(routes::auth::configure)
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let preflight = |uri: &str, origin: &str| {
+        test::TestRequest::default()
+            .method(actix_web::http::Method::OPTIONS)
+            .uri(uri)
+            .insert_header(("Origin", origin.to_string()))
+            .insert_header(("Access-Control-Request-Method", "GET"))
+            .to_request()
+    };
+
+    // The dashboard origin may send the session cookie
+    let resp = test::call_service(&app, preflight("/auth/me", "https://dash.example.com")).await;
+    assert_eq!(resp.status(), 200);
+    let headers = resp.headers();
+    assert_eq!(
+        headers.get("access-control-allow-origin").unwrap(),
+        "https://dash.example.com"
+    );
+    assert_eq!(
+        headers.get("access-control-allow-credentials").unwrap(),
+        "true"
+    );
+
+    // Other origins can't call the API
+    let resp = test::call_service(&app, preflight("/auth/me", "https://evil.example.com")).await;
+    assert_eq!(resp.status(), 400);
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+
+    // Ingest stays open to any origin, without credentials
+    let resp = test::call_service(
+        &app,
+        preflight("/api/1/envelope/", "https://shop.example.org"),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("access-control-allow-origin").unwrap(),
+        "https://shop.example.org"
+    );
+    assert!(resp
+        .headers()
+        .get("access-control-allow-credentials")
+        .is_none());
+}
+
+// =============================================================================
+// Rate Limit Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_register_rate_limited_per_ip() {
+    let db = TestDb::new().await;
+    let config = create_test_config();
+    let limiter = web::Data::new(ApiRateLimiter::new(config.api_rate_limit.clone()));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .app_data(limiter)
+            .wrap(
+                SessionMiddleware::builder(CookieSessionStore::default(), Key::from(&[0u8; 64]))
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .configure(routes::auth::configure),
+    )
+    .await;
+
+    let register = |ip: &str, n: usize| {
+        test::TestRequest::post()
+            .uri("/auth/register")
+            .peer_addr(format!("{}:40000", ip).parse().unwrap())
+            .set_json(json!({
+                "email": format!("user{}@example.com", n),
+                "password": "password123"
+            }))
+            .to_request()
+    };
+
+    for n in 0..30 {
+        let resp = test::call_service(&app, register("10.0.0.1", n)).await;
+        assert_ne!(resp.status(), 429, "attempt {} was rate limited", n + 1);
+    }
+
+    let resp = test::call_service(&app, register("10.0.0.1", 30)).await;
+    assert_eq!(resp.status(), 429);
+    let retry_after: u64 = resp
+        .headers()
+        .get("Retry-After")
+        .expect("Retry-After header")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "rate_limited");
+    assert_eq!(body["error"]["retry_after"], retry_after);
+
+    // Another client is unaffected
+    let resp = test::call_service(&app, register("10.0.0.2", 31)).await;
+    assert_eq!(resp.status(), 201);
+
+    // Reads are not counted
+    let req = test::TestRequest::get()
+        .uri("/auth/me")
+        .peer_addr("10.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
 // =============================================================================
 // Edge Cases and Corner Cases
 // =============================================================================
@@ -972,11 +1131,7 @@ async fn test_session_persists_across_requests() {
         .to_request();
 
     let login_resp = test::call_service(&app, login_req).await;
-    let cookies: Vec<_> = login_resp
-        .headers()
-        .get_all("set-cookie")
-        .into_iter()
-        .collect();
+    let cookies: Vec<_> = login_resp.headers().get_all("set-cookie").collect();
     let cookie_value = cookies[0].to_str().unwrap();
 
     // Make multiple requests with same session
@@ -1025,11 +1180,7 @@ async fn test_logout_invalidates_session() {
         .to_request();
 
     let login_resp = test::call_service(&app, login_req).await;
-    let cookies: Vec<_> = login_resp
-        .headers()
-        .get_all("set-cookie")
-        .into_iter()
-        .collect();
+    let cookies: Vec<_> = login_resp.headers().get_all("set-cookie").collect();
     let cookie_value = cookies[0].to_str().unwrap();
 
     // Verify session works
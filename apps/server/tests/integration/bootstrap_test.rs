@@ -1,9 +1,10 @@
 //! Integration tests for Bootstrap functionality
 //!
-//! Tests the CREATE_SUPERUSER bootstrap mechanism
+//! Tests the CREATE_SUPERUSER and RUSTRAK_BOOTSTRAP_TOKEN bootstrap mechanisms
 
 use rustrak::bootstrap;
-use rustrak::services::UsersService;
+use rustrak::config::Environment;
+use rustrak::services::{AuthTokenService, UsersService};
 use sqlx::PgPool;
 use std::env;
 use testcontainers::{runners::AsyncRunner, ContainerAsync};
@@ -58,7 +59,7 @@ async fn test_bootstrap_creates_superuser_when_empty() {
     env::set_var("CREATE_SUPERUSER", "admin@example.com:password123");
 
     // Run bootstrap
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     // Verify user was created
@@ -92,7 +93,7 @@ async fn test_bootstrap_skips_when_users_exist() {
     env::set_var("CREATE_SUPERUSER", "admin@example.com:password123");
 
     // Run bootstrap - should skip
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     // Verify admin was NOT created
@@ -113,7 +114,7 @@ async fn test_bootstrap_skips_when_env_not_set() {
     env::remove_var("CREATE_SUPERUSER");
 
     // Run bootstrap
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     // Verify no users were created
@@ -128,7 +129,7 @@ async fn test_bootstrap_fails_with_invalid_format() {
     // Invalid format (missing colon)
     env::set_var("CREATE_SUPERUSER", "admin@example.com");
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_err());
 
     // Clean up
@@ -142,7 +143,7 @@ async fn test_bootstrap_fails_with_empty_password() {
     // Empty password should be rejected
     env::set_var("CREATE_SUPERUSER", "admin@example.com:");
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_err());
 
     // Verify no user was created
@@ -160,7 +161,7 @@ async fn test_bootstrap_with_empty_string() {
     // Empty string
     env::set_var("CREATE_SUPERUSER", "");
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     // Verify no users were created
@@ -180,7 +181,7 @@ async fn test_bootstrap_creates_admin_user() {
         "superadmin@example.com:superpassword123",
     );
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     let user = UsersService::get_by_email(&db.pool, "superadmin@example.com")
@@ -201,7 +202,7 @@ async fn test_bootstrap_password_is_hashed() {
 
     env::set_var("CREATE_SUPERUSER", "hashcheck@example.com:testpassword123");
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     let user = UsersService::get_by_email(&db.pool, "hashcheck@example.com")
@@ -228,7 +229,7 @@ async fn test_bootstrap_with_email_containing_colon() {
     // This tests splitn behavior - splits on first colon
     env::set_var("CREATE_SUPERUSER", "test:email@example.com:password123");
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
 
     // Should fail because email doesn't match regex constraint
     assert!(result.is_err());
@@ -244,7 +245,7 @@ async fn test_bootstrap_idempotent_across_restarts() {
     env::set_var("CREATE_SUPERUSER", "restart@example.com:password123");
 
     // First "startup"
-    let result1 = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result1 = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result1.is_ok());
 
     let user1 = UsersService::get_by_email(&db.pool, "restart@example.com")
@@ -253,7 +254,7 @@ async fn test_bootstrap_idempotent_across_restarts() {
         .unwrap();
 
     // Second "startup" - should skip
-    let result2 = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result2 = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result2.is_ok());
 
     // Verify no duplicate was created
@@ -279,7 +280,7 @@ async fn test_bootstrap_with_whitespace_in_email() {
     // Email with surrounding whitespace
     env::set_var("CREATE_SUPERUSER", "  whitespace@example.com  :password123");
 
-    let result = bootstrap::create_superuser_if_needed(&db.pool).await;
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Development).await;
     assert!(result.is_ok());
 
     // Email should be trimmed
@@ -293,3 +294,50 @@ async fn test_bootstrap_with_whitespace_in_email() {
     // Clean up
     env::remove_var("CREATE_SUPERUSER");
 }
+
+#[tokio::test]
+async fn test_bootstrap_superuser_refused_in_production() {
+    let db = TestDb::new().await;
+
+    env::set_var("CREATE_SUPERUSER", "production@example.com:password123");
+
+    let result = bootstrap::create_superuser_if_needed(&db.pool, Environment::Production).await;
+    assert!(result.is_ok());
+
+    // Nothing is created, the operator has to go through the API
+    let user = UsersService::get_by_email(&db.pool, "production@example.com")
+        .await
+        .unwrap();
+    assert!(user.is_none());
+
+    // Clean up
+    env::remove_var("CREATE_SUPERUSER");
+}
+
+#[tokio::test]
+async fn test_bootstrap_token() {
+    let db = TestDb::new().await;
+
+    env::set_var("RUSTRAK_BOOTSTRAP_TOKEN", "1");
+
+    // Production never creates a token
+    let token = bootstrap::create_token_if_needed(&db.pool, Environment::Production)
+        .await
+        .unwrap();
+    assert!(token.is_none());
+    assert!(!AuthTokenService::has_any_token(&db.pool).await.unwrap());
+
+    // Development creates one, and only while no tokens exist
+    let token = bootstrap::create_token_if_needed(&db.pool, Environment::Development)
+        .await
+        .unwrap();
+    assert_eq!(token.map(|t| t.len()), Some(40));
+
+    let token = bootstrap::create_token_if_needed(&db.pool, Environment::Development)
+        .await
+        .unwrap();
+    assert!(token.is_none());
+
+    // Clean up
+    env::remove_var("RUSTRAK_BOOTSTRAP_TOKEN");
+}
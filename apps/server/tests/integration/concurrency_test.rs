@@ -4,7 +4,7 @@
 //! event processing without race conditions.
 
 use chrono::Utc;
-use rustrak::config::RateLimitConfig;
+use rustrak::config::{DigestConfig, RateLimitConfig};
 use rustrak::digest::worker::process_event;
 use rustrak::ingest::{store_event, EventMetadata};
 use rustrak::models::CreateProject;
@@ -65,6 +65,7 @@ fn create_rate_limit_config() -> RateLimitConfig {
         max_events_per_hour: 100000,
         max_events_per_project_per_minute: 5000,
         max_events_per_project_per_hour: 50000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     }
 }
 
@@ -74,6 +75,7 @@ async fn create_test_project(pool: &PgPool, name: &str) -> rustrak::models::Proj
         CreateProject {
             name: name.to_string(),
             slug: None,
+            team_id: None,
         },
     )
     .await
@@ -127,7 +129,7 @@ async fn test_concurrent_different_errors_same_project_creates_sequential_issues
                 create_unique_event_json(&format!("Error{}", i), &format!("Message {}", i));
             let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-            store_event(&ingest_dir_clone, &event_id, &event_bytes)
+            store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
                 .await
                 .expect("Failed to store event");
 
@@ -136,6 +138,7 @@ async fn test_concurrent_different_errors_same_project_creates_sequential_issues
                 project_id,
                 ingested_at: Utc::now(),
                 remote_addr: None,
+                spool_id: None,
             };
 
             process_event(
@@ -143,6 +146,7 @@ async fn test_concurrent_different_errors_same_project_creates_sequential_issues
                 &metadata,
                 &ingest_dir_clone,
                 &rate_limit_config_clone,
+                &DigestConfig::default(),
             )
             .await
             .expect("Failed to process event");
@@ -217,7 +221,7 @@ async fn test_concurrent_same_errors_same_project_groups_into_one_issue() {
                 create_unique_event_json("SameError", "Same message for grouping");
             let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-            store_event(&ingest_dir_clone, &event_id, &event_bytes)
+            store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
                 .await
                 .expect("Failed to store event");
 
@@ -226,6 +230,7 @@ async fn test_concurrent_same_errors_same_project_groups_into_one_issue() {
                 project_id,
                 ingested_at: Utc::now(),
                 remote_addr: None,
+                spool_id: None,
             };
 
             process_event(
@@ -233,6 +238,7 @@ async fn test_concurrent_same_errors_same_project_groups_into_one_issue() {
                 &metadata,
                 &ingest_dir_clone,
                 &rate_limit_config_clone,
+                &DigestConfig::default(),
             )
             .await
             .expect("Failed to process event");
@@ -261,12 +267,240 @@ async fn test_concurrent_same_errors_same_project_groups_into_one_issue() {
 
     assert_eq!(issues.len(), 1, "Expected 1 issue, got {}", issues.len());
     assert_eq!(
-        issues[0].digested_event_count, num_events as i32,
+        issues[0].digested_event_count, num_events,
         "Expected {} events in issue, got {}",
         num_events, issues[0].digested_event_count
     );
 }
 
+/// Test that parallel digests into one issue number its events uniquely, even
+/// while its counters are being recounted
+#[actix_web::test]
+async fn test_concurrent_digests_one_issue_number_events_uniquely() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Concurrent Event Orders").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path().to_path_buf();
+    let rate_limit_config = Arc::new(create_rate_limit_config());
+    let pool = Arc::new(db.pool.clone());
+
+    let num_tasks = 8;
+    let events_per_task = 5;
+    let mut handles = Vec::new();
+
+    for _ in 0..num_tasks {
+        let pool_clone = Arc::clone(&pool);
+        let ingest_dir_clone = ingest_dir.clone();
+        let rate_limit_config_clone = Arc::clone(&rate_limit_config);
+        let project_id = project.id;
+
+        handles.push(tokio::spawn(async move {
+            for _ in 0..events_per_task {
+                let (event_id, event_json) =
+                    create_unique_event_json("OrderedError", "Same message for grouping");
+                let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+                store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
+                    .await
+                    .expect("Failed to store event");
+
+                let metadata = EventMetadata {
+                    event_id: event_id.clone(),
+                    project_id,
+                    ingested_at: Utc::now(),
+                    remote_addr: None,
+                    spool_id: None,
+                };
+
+                process_event(
+                    &pool_clone,
+                    &metadata,
+                    &ingest_dir_clone,
+                    &rate_limit_config_clone,
+                    &DigestConfig::default(),
+                )
+                .await
+                .expect("Failed to process event");
+            }
+        }));
+    }
+
+    // Recounts rewrite the counters while events are being numbered
+    let recount_pool = Arc::clone(&pool);
+    let project_id = project.id;
+    let recounts = tokio::spawn(async move {
+        for _ in 0..10 {
+            let mut tx = recount_pool.begin().await.unwrap();
+            IssueService::recount(&mut tx, project_id).await.unwrap();
+            tx.commit().await.unwrap();
+            tokio::task::yield_now().await;
+        }
+    });
+
+    for handle in handles {
+        handle.await.expect("Task panicked");
+    }
+    recounts.await.expect("Recount task panicked");
+
+    let (issues, _) = IssueService::list_paginated(
+        &db.pool,
+        project.id,
+        IssueSort::DigestOrder,
+        SortOrder::Asc,
+        true,
+        None,
+        100,
+    )
+    .await
+    .expect("Failed to list issues");
+    assert_eq!(issues.len(), 1, "Expected 1 issue, got {}", issues.len());
+    let issue = &issues[0];
+
+    let orders: Vec<i32> = sqlx::query_scalar(
+        "SELECT digest_order FROM events WHERE issue_id = $1 ORDER BY digest_order",
+    )
+    .bind(issue.id)
+    .fetch_all(&db.pool)
+    .await
+    .unwrap();
+
+    // Every event is stored, each with its own order
+    let total = (num_tasks * events_per_task) as usize;
+    assert_eq!(orders.len(), total, "Got orders {:?}", orders);
+    assert!(
+        orders.windows(2).all(|pair| pair[0] < pair[1]),
+        "digest_order values should be unique. Got: {:?}",
+        orders
+    );
+    assert_eq!(issue.last_event_order, *orders.last().unwrap());
+
+    // The next event continues after the highest order
+    let (event_id, event_json) =
+        create_unique_event_json("OrderedError", "Same message for grouping");
+    store_event(
+        &ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&event_json).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        &db.pool,
+        &metadata,
+        &ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let next: i32 = sqlx::query_scalar("SELECT digest_order FROM events WHERE event_id = $1::uuid")
+        .bind(&event_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert!(next > *orders.last().unwrap());
+}
+
+/// Test that issues created outside the digest take their order from the same
+/// project sequence as digested ones
+#[actix_web::test]
+async fn test_issue_orders_shared_between_digest_and_direct_creation() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Shared Issue Orders").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path().to_path_buf();
+    let rate_limit_config = Arc::new(create_rate_limit_config());
+    let pool = Arc::new(db.pool.clone());
+
+    let num_each = 8;
+    let mut handles = Vec::new();
+
+    for i in 0..num_each {
+        let pool_clone = Arc::clone(&pool);
+        let ingest_dir_clone = ingest_dir.clone();
+        let rate_limit_config_clone = Arc::clone(&rate_limit_config);
+        let project_id = project.id;
+
+        handles.push(tokio::spawn(async move {
+            let (event_id, event_json) =
+                create_unique_event_json(&format!("DigestError{}", i), "digested");
+            let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+            store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
+                .await
+                .expect("Failed to store event");
+
+            let metadata = EventMetadata {
+                event_id: event_id.clone(),
+                project_id,
+                ingested_at: Utc::now(),
+                remote_addr: None,
+                spool_id: None,
+            };
+
+            process_event(
+                &pool_clone,
+                &metadata,
+                &ingest_dir_clone,
+                &rate_limit_config_clone,
+                &DigestConfig::default(),
+            )
+            .await
+            .expect("Failed to process event");
+        }));
+
+        let pool_clone = Arc::clone(&pool);
+        let project_id = project.id;
+        handles.push(tokio::spawn(async move {
+            let denormalized = rustrak::services::DenormalizedFields {
+                calculated_type: format!("DirectError{}", i),
+                calculated_value: "created directly".to_string(),
+                transaction: String::new(),
+                last_frame_filename: String::new(),
+                last_frame_module: String::new(),
+                last_frame_function: String::new(),
+                exception_chain_length: 1,
+                is_unhandled: false,
+            };
+            IssueService::create(
+                &pool_clone,
+                project_id,
+                Utc::now(),
+                &denormalized,
+                Some("error"),
+                None,
+            )
+            .await
+            .expect("Failed to create issue");
+        }));
+    }
+
+    for handle in handles {
+        handle.await.expect("Task panicked");
+    }
+
+    let orders: HashSet<i32> =
+        sqlx::query_scalar("SELECT digest_order FROM issues WHERE project_id = $1")
+            .bind(project.id)
+            .fetch_all(&db.pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect();
+
+    let expected: HashSet<i32> = (1..=2 * num_each).collect();
+    assert_eq!(orders, expected);
+}
+
 // =============================================================================
 // Concurrent Issue Creation Tests - Different Projects
 // =============================================================================
@@ -307,7 +541,7 @@ async fn test_concurrent_different_projects_process_in_parallel() {
                 );
                 let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-                store_event(&ingest_dir_clone, &event_id, &event_bytes)
+                store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
                     .await
                     .expect("Failed to store event");
 
@@ -316,6 +550,7 @@ async fn test_concurrent_different_projects_process_in_parallel() {
                     project_id,
                     ingested_at: Utc::now(),
                     remote_addr: None,
+                    spool_id: None,
                 };
 
                 process_event(
@@ -323,6 +558,7 @@ async fn test_concurrent_different_projects_process_in_parallel() {
                     &metadata,
                     &ingest_dir_clone,
                     &rate_limit_config_clone,
+                    &DigestConfig::default(),
                 )
                 .await
                 .expect("Failed to process event");
@@ -403,7 +639,7 @@ async fn test_high_concurrency_stress_test() {
             );
             let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-            store_event(&ingest_dir_clone, &event_id, &event_bytes)
+            store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
                 .await
                 .expect("Failed to store event");
 
@@ -412,6 +648,7 @@ async fn test_high_concurrency_stress_test() {
                 project_id,
                 ingested_at: Utc::now(),
                 remote_addr: None,
+                spool_id: None,
             };
 
             process_event(
@@ -419,6 +656,7 @@ async fn test_high_concurrency_stress_test() {
                 &metadata,
                 &ingest_dir_clone,
                 &rate_limit_config_clone,
+                &DigestConfig::default(),
             )
             .await
             .expect("Failed to process event");
@@ -507,7 +745,7 @@ async fn test_concurrent_mixed_create_and_update() {
                     create_unique_event_json(&error_type, "Same message for grouping");
                 let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-                store_event(&ingest_dir_clone, &event_id, &event_bytes)
+                store_event(&ingest_dir_clone, &event_id, &event_bytes, false)
                     .await
                     .expect("Failed to store event");
 
@@ -516,6 +754,7 @@ async fn test_concurrent_mixed_create_and_update() {
                     project_id,
                     ingested_at: Utc::now(),
                     remote_addr: None,
+                    spool_id: None,
                 };
 
                 process_event(
@@ -523,6 +762,7 @@ async fn test_concurrent_mixed_create_and_update() {
                     &metadata,
                     &ingest_dir_clone,
                     &rate_limit_config_clone,
+                    &DigestConfig::default(),
                 )
                 .await
                 .expect("Failed to process event");
@@ -555,7 +795,7 @@ async fn test_concurrent_mixed_create_and_update() {
     // Verify each issue has 4 events
     for issue in &issues {
         assert_eq!(
-            issue.digested_event_count, copies_per_error as i32,
+            issue.digested_event_count, copies_per_error,
             "Each issue should have {} events, issue {} has {}",
             copies_per_error, issue.id, issue.digested_event_count
         );
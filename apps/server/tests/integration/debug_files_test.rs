@@ -0,0 +1,344 @@
+//! Integration tests for debug files and native symbolication
+//!
+//! Tests storing Breakpad symbol files and symbolicating native events
+//! with them during digestion, including grouping before and after upload.
+
+use actix_session::{storage::CookieSessionStore, SessionMiddleware};
+use actix_web::{cookie::Key, test, web, App};
+use chrono::Utc;
+use rustrak::config::{DigestConfig, RateLimitConfig};
+use rustrak::digest::worker::process_event;
+use rustrak::error::AppError;
+use rustrak::ingest::{store_event, EventMetadata};
+use rustrak::models::{CreateProject, CreateUserRequest, Event, Project};
+use rustrak::routes;
+use rustrak::services::{DebugFileService, ProjectService, UsersService};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::path::Path;
+use tempfile::TempDir;
+use testcontainers::{runners::AsyncRunner, ContainerAsync};
+use testcontainers_modules::postgres::Postgres;
+use uuid::Uuid;
+
+use crate::common::fixtures::events;
+use crate::common::session::{configure_login, login_request, session_cookie, session_middleware};
+use crate::common::{LIBCRASH_BASE, SYMBOL_DEBUG_ID, SYMBOL_FILE};
+
+/// Test database container with connection pool
+struct TestDb {
+    #[allow(dead_code)]
+    container: ContainerAsync<Postgres>,
+    pool: PgPool,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let container = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start PostgreSQL container");
+
+        let host = container.get_host().await.expect("Failed to get host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get port");
+
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+
+        let pool = PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pgcrypto")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable pgcrypto extension");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        TestDb { container, pool }
+    }
+}
+
+async fn create_test_project(pool: &PgPool) -> Project {
+    ProjectService::create(
+        pool,
+        CreateProject {
+            name: "Native Project".to_string(),
+            slug: None,
+            team_id: None,
+        },
+    )
+    .await
+    .expect("Failed to create test project")
+}
+
+/// Stores and digests an event, returning the stored event
+async fn digest(pool: &PgPool, project_id: i32, ingest_dir: &Path, mut event: Value) -> Event {
+    let event_id = Uuid::new_v4().simple().to_string();
+    event["event_id"] = serde_json::json!(event_id);
+
+    store_event(
+        ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&event).unwrap(),
+        false,
+    )
+    .await
+    .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    let rate_limit_config = RateLimitConfig {
+        max_events_per_minute: 1000,
+        max_events_per_hour: 10000,
+        max_events_per_project_per_minute: 500,
+        max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
+    };
+
+    process_event(
+        pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    sqlx::query_as::<_, Event>("SELECT * FROM events WHERE event_id = $1")
+        .bind(Uuid::parse_str(&event_id).unwrap())
+        .fetch_one(pool)
+        .await
+        .expect("Event not stored")
+}
+
+fn crash_frame(event: &Event) -> &Value {
+    let frames = event.data["exception"]["values"][0]["stacktrace"]["frames"]
+        .as_array()
+        .expect("frames");
+    frames.last().expect("crash frame")
+}
+
+// =============================================================================
+// Debug File Storage
+// =============================================================================
+
+#[tokio::test]
+async fn test_upload_and_list_debug_files() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool).await;
+
+    let file = DebugFileService::upload(&db.pool, project.id, SYMBOL_FILE.as_bytes())
+        .await
+        .expect("Failed to upload symbol file");
+
+    assert_eq!(file.debug_id, SYMBOL_DEBUG_ID);
+    assert_eq!(file.code_file, "libcrash.so");
+    assert_eq!(file.os, "Linux");
+    assert_eq!(file.arch, "x86_64");
+    assert_eq!(file.file_size as usize, SYMBOL_FILE.len());
+
+    // Re-uploading the same module replaces the file
+    let replaced = DebugFileService::upload(&db.pool, project.id, SYMBOL_FILE.as_bytes())
+        .await
+        .expect("Failed to re-upload symbol file");
+    assert_eq!(replaced.id, file.id);
+
+    let files = DebugFileService::list(&db.pool, project.id).await.unwrap();
+    assert_eq!(files.len(), 1);
+
+    DebugFileService::delete(&db.pool, project.id, file.id)
+        .await
+        .expect("Failed to delete debug file");
+    assert!(DebugFileService::list(&db.pool, project.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let result = DebugFileService::delete(&db.pool, project.id, file.id).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_upload_invalid_symbol_file() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool).await;
+
+    let result = DebugFileService::upload(&db.pool, project.id, b"MODULE Linux").await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let result = DebugFileService::upload(&db.pool, project.id, &[0xff, 0xfe, 0x00]).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[actix_web::test]
+async fn test_upload_debug_file_unauthorized() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool).await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .wrap(
+                SessionMiddleware::builder(CookieSessionStore::default(), Key::from(&[0u8; 64]))
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .configure(routes::debug_files::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/projects/{}/files/dsyms", project.id))
+        .insert_header(("Content-Type", "multipart/form-data; boundary=x"))
+        .set_payload("--x--\r\n")
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_debug_files_require_membership() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool).await;
+    let file = DebugFileService::upload(&db.pool, project.id, SYMBOL_FILE.as_bytes())
+        .await
+        .unwrap();
+    let outsider = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "outsider@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        false,
+    )
+    .await
+    .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::debug_files::configure),
+    )
+    .await;
+    let resp = test::call_service(&app, login_request(outsider.id).to_request()).await;
+    let cookie = session_cookie(&resp);
+
+    let files_uri = format!("/api/projects/{}/files/dsyms", project.id);
+    let upload = format!(
+        "--x\r\nContent-Disposition: form-data; name=\"file\"; filename=\"crash.sym\"\r\n\r\n{}\r\n--x--\r\n",
+        SYMBOL_FILE
+    );
+    let requests = [
+        test::TestRequest::get().uri(&files_uri),
+        test::TestRequest::post()
+            .uri(&files_uri)
+            .insert_header(("Content-Type", "multipart/form-data; boundary=x"))
+            .set_payload(upload),
+        test::TestRequest::delete().uri(&format!("{}/{}", files_uri, file.id)),
+    ];
+    for req in requests {
+        let resp = test::call_service(&app, req.cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    // The outsider's delete didn't go through
+    let files = DebugFileService::list(&db.pool, project.id).await.unwrap();
+    assert_eq!(files.len(), 1);
+}
+
+// =============================================================================
+// Symbolication During Digest
+// =============================================================================
+
+#[actix_web::test]
+async fn test_digest_symbolicates_native_frames() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool).await;
+    let temp_dir = TempDir::new().unwrap();
+
+    DebugFileService::upload(&db.pool, project.id, SYMBOL_FILE.as_bytes())
+        .await
+        .unwrap();
+
+    let event = digest(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        events::native_crash(&[LIBCRASH_BASE + 0x1111, LIBCRASH_BASE + 0x1024]),
+    )
+    .await;
+
+    let frame = crash_frame(&event);
+    assert_eq!(frame["function"], "crash_handler");
+    assert_eq!(frame["filename"], "/src/crash.c");
+    assert_eq!(frame["lineno"], 12);
+    assert_eq!(frame["data"]["symbolicator_status"], "symbolicated");
+    assert_eq!(event.last_frame_function, "crash_handler");
+}
+
+#[actix_web::test]
+async fn test_native_grouping_stable_across_symbol_upload() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    let crash = || events::native_crash(&[LIBCRASH_BASE + 0x1111, LIBCRASH_BASE + 0x1024]);
+
+    // Before symbols: grouped by module+offset, frames keep their addresses
+    let before = digest(&db.pool, project.id, temp_dir.path(), crash()).await;
+    let frame = crash_frame(&before);
+    assert_eq!(frame["data"]["symbolicator_status"], "missing");
+    assert_eq!(frame["instruction_addr"], "0x7f0000001024");
+    assert!(frame.get("function").is_none());
+
+    DebugFileService::upload(&db.pool, project.id, SYMBOL_FILE.as_bytes())
+        .await
+        .unwrap();
+
+    // After symbols: same crash still lands in the same issue
+    let after = digest(&db.pool, project.id, temp_dir.path(), crash()).await;
+    assert_eq!(crash_frame(&after)["function"], "crash_handler");
+    assert_eq!(after.issue_id, before.issue_id);
+
+    // Another offset in the same function now groups by function name
+    let other_offset = digest(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        events::native_crash(&[LIBCRASH_BASE + 0x1111, LIBCRASH_BASE + 0x1004]),
+    )
+    .await;
+    assert_eq!(other_offset.issue_id, before.issue_id);
+
+    // A crash in a different function is a different issue
+    let other_function = digest(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        events::native_crash(&[LIBCRASH_BASE + 0x1024, LIBCRASH_BASE + 0x1110]),
+    )
+    .await;
+    assert_ne!(other_function.issue_id, before.issue_id);
+
+    let issue_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM issues WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(issue_count, 2);
+}
@@ -2,12 +2,22 @@
 //!
 //! Tests the complete event digest workflow: ingest -> grouping -> issue creation.
 
-use chrono::Utc;
-use rustrak::config::RateLimitConfig;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, DurationRound, Utc};
+use rustrak::config::{DigestConfig, RateLimitConfig, RetentionConfig};
+use rustrak::db::DbHealth;
+use rustrak::digest::latency::{bucket_index, LATENCY_BUCKETS};
 use rustrak::digest::worker::process_event;
-use rustrak::ingest::{store_event, EventMetadata};
-use rustrak::models::CreateProject;
-use rustrak::services::{EventService, IssueService, ProjectService};
+use rustrak::digest::{DigestLatency, DigestRunner, DrainState};
+use rustrak::ingest::{store_event, store_minidump, EventMetadata};
+use rustrak::models::{Actor, CreateProject, IssuePriority, ResolveIssue, UpdateProject};
+use rustrak::services::ingested_event::INGESTED_EVENT_RETENTION_DAYS;
+use rustrak::services::{
+    AutoResolveService, EventService, IssueContextService, IssueService, IssueUserService,
+    ProjectService, RegroupService, RegroupTarget, RetentionService,
+};
 use serde_json::json;
 use sqlx::PgPool;
 use tempfile::TempDir;
@@ -61,6 +71,7 @@ fn create_rate_limit_config() -> RateLimitConfig {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     }
 }
 
@@ -70,6 +81,7 @@ async fn create_test_project(pool: &PgPool, name: &str) -> rustrak::models::Proj
         CreateProject {
             name: name.to_string(),
             slug: None,
+            team_id: None,
         },
     )
     .await
@@ -117,7 +129,7 @@ async fn test_digest_creates_issue_and_event() {
     let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
     // Store event in temp storage
-    store_event(ingest_dir, &event_id, &event_bytes)
+    store_event(ingest_dir, &event_id, &event_bytes, false)
         .await
         .expect("Failed to store event");
 
@@ -126,12 +138,19 @@ async fn test_digest_creates_issue_and_event() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
     // Process the event
-    process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     // Verify issue was created
     let (issues, _) = IssueService::list_paginated(
@@ -168,7 +187,7 @@ async fn test_digest_groups_similar_events() {
     let rate_limit_config = create_rate_limit_config();
 
     // Create two events with the same error type and message
-    for i in 0..2 {
+    for _ in 0..2 {
         let event_id = Uuid::new_v4().to_string().replace("-", "");
         let event_json = json!({
             "event_id": &event_id,
@@ -185,7 +204,7 @@ async fn test_digest_groups_similar_events() {
         });
         let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-        store_event(ingest_dir, &event_id, &event_bytes)
+        store_event(ingest_dir, &event_id, &event_bytes, false)
             .await
             .expect("Failed to store event");
 
@@ -194,11 +213,18 @@ async fn test_digest_groups_similar_events() {
             project_id: project.id,
             ingested_at: Utc::now(),
             remote_addr: None,
+            spool_id: None,
         };
 
-        process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-            .await
-            .expect("Failed to process event");
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
     }
 
     // Should have only 1 issue with 2 events
@@ -249,7 +275,7 @@ async fn test_digest_creates_separate_issues_for_different_errors() {
         });
         let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-        store_event(ingest_dir, &event_id, &event_bytes)
+        store_event(ingest_dir, &event_id, &event_bytes, false)
             .await
             .expect("Failed to store event");
 
@@ -258,11 +284,18 @@ async fn test_digest_creates_separate_issues_for_different_errors() {
             project_id: project.id,
             ingested_at: Utc::now(),
             remote_addr: None,
+            spool_id: None,
         };
 
-        process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-            .await
-            .expect("Failed to process event");
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
     }
 
     // Should have 3 separate issues
@@ -307,7 +340,7 @@ async fn test_digest_handles_custom_fingerprint() {
         });
         let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-        store_event(ingest_dir, &event_id, &event_bytes)
+        store_event(ingest_dir, &event_id, &event_bytes, false)
             .await
             .expect("Failed to store event");
 
@@ -316,11 +349,18 @@ async fn test_digest_handles_custom_fingerprint() {
             project_id: project.id,
             ingested_at: Utc::now(),
             remote_addr: None,
+            spool_id: None,
         };
 
-        process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-            .await
-            .expect("Failed to process event");
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
     }
 
     // Should have 1 issue because of custom fingerprint
@@ -365,7 +405,7 @@ async fn test_digest_handles_default_fingerprint_placeholder() {
     });
     let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-    store_event(ingest_dir, &event_id, &event_bytes)
+    store_event(ingest_dir, &event_id, &event_bytes, false)
         .await
         .expect("Failed to store event");
 
@@ -374,11 +414,18 @@ async fn test_digest_handles_default_fingerprint_placeholder() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
-    process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     // Verify issue was created with expanded fingerprint
     let (issues, _) = IssueService::list_paginated(
@@ -396,6 +443,57 @@ async fn test_digest_handles_default_fingerprint_placeholder() {
     assert_eq!(issues.len(), 1);
 }
 
+#[actix_web::test]
+async fn test_digest_records_latency_from_ingest() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Latency Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+
+    let event_id = Uuid::new_v4().simple().to_string();
+    store_event(
+        ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&create_event_json(&event_id)).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    // The event waited in the spool for 45 seconds
+    let metadata = EventMetadata {
+        event_id,
+        project_id: project.id,
+        ingested_at: Utc::now() - chrono::Duration::seconds(45),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    let bucket = bucket_index(45.0);
+    assert_eq!(
+        (LATENCY_BUCKETS[bucket - 1], LATENCY_BUCKETS[bucket]),
+        (30.0, 60.0)
+    );
+    // Events of other tests digested meanwhile land in other buckets
+    let events_in_bucket = || {
+        let buckets = DigestLatency::global().snapshot().buckets;
+        buckets[bucket].1 - buckets[bucket - 1].1
+    };
+    let before = events_in_bucket();
+
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &create_rate_limit_config(),
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    assert!(events_in_bucket() > before);
+    assert!(DigestRunner::global().status().latency.samples >= 1);
+}
+
 // =============================================================================
 // Duplicate Handling Tests
 // =============================================================================
@@ -414,7 +512,7 @@ async fn test_digest_ignores_duplicate_event_id() {
 
     // Process same event twice
     for _ in 0..2 {
-        store_event(ingest_dir, &event_id, &event_bytes)
+        store_event(ingest_dir, &event_id, &event_bytes, false)
             .await
             .expect("Failed to store event");
 
@@ -423,10 +521,18 @@ async fn test_digest_ignores_duplicate_event_id() {
             project_id: project.id,
             ingested_at: Utc::now(),
             remote_addr: None,
+            spool_id: None,
         };
 
         // Second processing should silently ignore the duplicate
-        let _ = process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config).await;
+        let _ = process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await;
     }
 
     // Should only have 1 issue with 1 event
@@ -446,10 +552,419 @@ async fn test_digest_ignores_duplicate_event_id() {
     assert_eq!(issues[0].digested_event_count, 1);
 }
 
+#[actix_web::test]
+async fn test_digest_skips_event_recorded_as_digested() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Digested Once Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    let event_id = Uuid::new_v4().simple().to_string();
+    let event_bytes = serde_json::to_vec(&create_event_json(&event_id)).unwrap();
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .unwrap();
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    // The stored event is gone (e.g. purged with its issue), but the event id
+    // is still known as digested
+    sqlx::query("DELETE FROM issues WHERE project_id = $1")
+        .bind(project.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .unwrap();
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let issues: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM issues WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(issues, 0);
+    assert!(!ingest_dir.join(format!("{}.json", event_id)).exists());
+}
+
+#[actix_web::test]
+async fn test_digest_merges_update_for_same_event_id() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Merge Event Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+    let digest_config = DigestConfig {
+        merge_duplicate_events: true,
+        ..DigestConfig::default()
+    };
+
+    let event_id = Uuid::new_v4().simple().to_string();
+    let first_seen = Utc::now() - chrono::Duration::minutes(5);
+    store_event(
+        ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&create_event_json(&event_id)).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: first_seen,
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &digest_config,
+    )
+    .await
+    .unwrap();
+
+    // The follow-up only carries what the SDK learned since
+    let updated_at = DateTime::from_timestamp(first_seen.timestamp() + 120, 0).unwrap();
+    let update = json!({
+        "event_id": &event_id,
+        "timestamp": updated_at.to_rfc3339(),
+        "release": "2.1.0",
+        "level": null,
+        "user": {"id": "42"}
+    });
+    let spool_id = Uuid::new_v4().simple().to_string();
+    store_event(
+        ingest_dir,
+        &spool_id,
+        &serde_json::to_vec(&update).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    let update_metadata = EventMetadata {
+        ingested_at: Utc::now(),
+        spool_id: Some(spool_id.clone()),
+        ..metadata.clone()
+    };
+    process_event(
+        &db.pool,
+        &update_metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &digest_config,
+    )
+    .await
+    .unwrap();
+
+    let event =
+        EventService::get_by_event_id(&db.pool, project.id, Uuid::parse_str(&event_id).unwrap())
+            .await
+            .unwrap();
+    assert_eq!(event.release, "2.1.0");
+    assert_eq!(event.level, "error");
+    assert_eq!(event.data["user"]["id"], "42");
+    assert_eq!(event.data["exception"]["values"][0]["type"], "TypeError");
+
+    // Seen again, but not counted again
+    let issue = IssueService::get_by_id(&db.pool, event.issue_id)
+        .await
+        .unwrap();
+    assert_eq!(issue.digested_event_count, 1);
+    assert_eq!(issue.stored_event_count, 1);
+    // Seen when the update says, not when it arrived
+    assert_eq!(issue.last_seen, updated_at);
+    assert!(!ingest_dir.join(format!("{}.json", spool_id)).exists());
+}
+
+#[actix_web::test]
+async fn test_digest_drops_update_when_merging_disabled() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Drop Update Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    let event_id = Uuid::new_v4().simple().to_string();
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    store_event(
+        ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&create_event_json(&event_id)).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let update = json!({"event_id": &event_id, "release": "2.1.0"});
+    store_event(
+        ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&update).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let event =
+        EventService::get_by_event_id(&db.pool, project.id, Uuid::parse_str(&event_id).unwrap())
+            .await
+            .unwrap();
+    assert_eq!(event.release, "");
+    assert!(event.data.get("user").is_none());
+}
+
 // =============================================================================
 // Log Message Grouping Tests
 // =============================================================================
 
+#[actix_web::test]
+async fn test_digest_records_dist() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Dist Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    for dist in [Some("123"), Some("124"), Some("124"), None] {
+        let event_id = Uuid::new_v4().simple().to_string();
+        let mut event_json = create_event_json(&event_id);
+        event_json["release"] = json!("app@2.0.0");
+        if let Some(dist) = dist {
+            event_json["dist"] = json!(dist);
+        }
+        store_event(
+            ingest_dir,
+            &event_id,
+            &serde_json::to_vec(&event_json).unwrap(),
+            false,
+        )
+        .await
+        .expect("Failed to store event");
+
+        let metadata = EventMetadata {
+            event_id,
+            project_id: project.id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
+    }
+
+    let (issues, total) = IssueService::list_offset(
+        &db.pool,
+        project.id,
+        rustrak::pagination::IssueSort::LastSeen,
+        rustrak::pagination::SortOrder::Desc,
+        rustrak::pagination::IssueFilter::All,
+        None,
+        None,
+        None,
+        1,
+        10,
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 1);
+    let issue_id = issues[0].id;
+
+    // The issue tracks which builds it was seen on
+    let breakdown = IssueContextService::breakdown(&db.pool, issue_id)
+        .await
+        .unwrap();
+    assert_eq!(breakdown.dist.total, 3);
+    assert_eq!(breakdown.dist.values[0].value, "124");
+    assert_eq!(breakdown.dist.values[0].count, 2);
+    assert_eq!(breakdown.dist.values[1].value, "123");
+
+    let (events, _) = EventService::list_paginated(
+        &db.pool,
+        issue_id,
+        rustrak::pagination::EventSort::Received,
+        rustrak::pagination::SortOrder::Desc,
+        None,
+        Some("124"),
+        10,
+    )
+    .await
+    .unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events
+        .iter()
+        .all(|e| e.dist == "124" && e.release == "app@2.0.0"));
+
+    for (dist, expected) in [("123", 1), ("999", 0)] {
+        let (_, total) = IssueService::list_offset(
+            &db.pool,
+            project.id,
+            rustrak::pagination::IssueSort::LastSeen,
+            rustrak::pagination::SortOrder::Desc,
+            rustrak::pagination::IssueFilter::All,
+            Some(dist),
+            None,
+            None,
+            1,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(total, expected, "dist {}", dist);
+    }
+}
+
+#[actix_web::test]
+async fn test_digest_flags_unhandled_issues() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Unhandled Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    // A crash between handled events of one issue, and an issue that was
+    // only ever handled
+    let events = [
+        ("TypeError", Some(true)),
+        ("TypeError", Some(false)),
+        ("TypeError", None),
+        ("ValueError", Some(true)),
+    ];
+    let mut event_ids = Vec::new();
+    for (error_type, handled) in events {
+        let event_id = Uuid::new_v4().simple().to_string();
+        let mut event_json = create_event_json(&event_id);
+        event_json["exception"]["values"][0]["type"] = json!(error_type);
+        if let Some(handled) = handled {
+            event_json["exception"]["values"][0]["mechanism"] =
+                json!({ "type": "generic", "handled": handled });
+        }
+        store_event(
+            ingest_dir,
+            &event_id,
+            &serde_json::to_vec(&event_json).unwrap(),
+            false,
+        )
+        .await
+        .expect("Failed to store event");
+
+        let metadata = EventMetadata {
+            event_id: event_id.clone(),
+            project_id: project.id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
+        event_ids.push(event_id);
+    }
+
+    let mut flags = Vec::new();
+    for event_id in &event_ids {
+        let event =
+            EventService::get_by_event_id(&db.pool, project.id, Uuid::parse_str(event_id).unwrap())
+                .await
+                .unwrap();
+        flags.push(event.is_unhandled);
+    }
+    assert_eq!(flags, [false, true, false, false]);
+
+    // Once an issue crashed it stays flagged, whatever the later events
+    let list = |handled| {
+        IssueService::list_offset(
+            &db.pool,
+            project.id,
+            rustrak::pagination::IssueSort::LastSeen,
+            rustrak::pagination::SortOrder::Desc,
+            rustrak::pagination::IssueFilter::All,
+            None,
+            handled,
+            None,
+            1,
+            10,
+        )
+    };
+    let (crashes, total) = list(Some(false)).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(crashes[0].calculated_type, "TypeError");
+    assert!(crashes[0].is_unhandled);
+
+    let (handled, total) = list(Some(true)).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(handled[0].calculated_type, "ValueError");
+    assert!(!handled[0].is_unhandled);
+
+    let (_, total) = list(None).await.unwrap();
+    assert_eq!(total, 2);
+}
+
 #[actix_web::test]
 async fn test_digest_groups_log_messages() {
     let db = TestDb::new().await;
@@ -473,7 +988,7 @@ async fn test_digest_groups_log_messages() {
         });
         let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-        store_event(ingest_dir, &event_id, &event_bytes)
+        store_event(ingest_dir, &event_id, &event_bytes, false)
             .await
             .expect("Failed to store event");
 
@@ -482,11 +997,18 @@ async fn test_digest_groups_log_messages() {
             project_id: project.id,
             ingested_at: Utc::now(),
             remote_addr: None,
+            spool_id: None,
         };
 
-        process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-            .await
-            .expect("Failed to process event");
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
     }
 
     // Should have 1 issue grouped by log message
@@ -535,7 +1057,7 @@ async fn test_digest_updates_issue_last_seen() {
     });
     let event_bytes1 = serde_json::to_vec(&event_json1).unwrap();
 
-    store_event(ingest_dir, &event_id1, &event_bytes1)
+    store_event(ingest_dir, &event_id1, &event_bytes1, false)
         .await
         .expect("Failed to store event");
 
@@ -544,11 +1066,18 @@ async fn test_digest_updates_issue_last_seen() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
-    process_event(&db.pool, &metadata1, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata1,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     let (issues_before, _) = IssueService::list_paginated(
         &db.pool,
@@ -584,7 +1113,7 @@ async fn test_digest_updates_issue_last_seen() {
     });
     let event_bytes2 = serde_json::to_vec(&event_json2).unwrap();
 
-    store_event(ingest_dir, &event_id2, &event_bytes2)
+    store_event(ingest_dir, &event_id2, &event_bytes2, false)
         .await
         .expect("Failed to store event");
 
@@ -593,11 +1122,18 @@ async fn test_digest_updates_issue_last_seen() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
-    process_event(&db.pool, &metadata2, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata2,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     let (issues_after, _) = IssueService::list_paginated(
         &db.pool,
@@ -637,7 +1173,7 @@ async fn test_digest_updates_project_counters() {
         let event_json = create_event_json(&event_id);
         let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-        store_event(ingest_dir, &event_id, &event_bytes)
+        store_event(ingest_dir, &event_id, &event_bytes, false)
             .await
             .expect("Failed to store event");
 
@@ -646,11 +1182,18 @@ async fn test_digest_updates_project_counters() {
             project_id: project.id,
             ingested_at: Utc::now(),
             remote_addr: None,
+            spool_id: None,
         };
 
-        process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-            .await
-            .expect("Failed to process event");
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
     }
 
     // Check project counters
@@ -683,7 +1226,7 @@ async fn test_digest_handles_missing_exception() {
     });
     let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-    store_event(ingest_dir, &event_id, &event_bytes)
+    store_event(ingest_dir, &event_id, &event_bytes, false)
         .await
         .expect("Failed to store event");
 
@@ -692,12 +1235,19 @@ async fn test_digest_handles_missing_exception() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
     // Should still process successfully with fallback grouping
-    process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     let (issues, _) = IssueService::list_paginated(
         &db.pool,
@@ -737,7 +1287,7 @@ async fn test_digest_handles_multiline_error_value() {
     });
     let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-    store_event(ingest_dir, &event_id, &event_bytes)
+    store_event(ingest_dir, &event_id, &event_bytes, false)
         .await
         .expect("Failed to store event");
 
@@ -746,11 +1296,18 @@ async fn test_digest_handles_multiline_error_value() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
-    process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     let (issues, _) = IssueService::list_paginated(
         &db.pool,
@@ -765,8 +1322,15 @@ async fn test_digest_handles_multiline_error_value() {
     .expect("Failed to list issues");
 
     assert_eq!(issues.len(), 1);
+    let max_length = DigestConfig::default().max_issue_title_length;
     // Should only use first line in title
-    assert!(!issues[0].title().contains('\n'));
+    assert!(!issues[0].title(max_length).contains('\n'));
+    assert!(issues[0].title(max_length).chars().count() <= max_length);
+
+    // Longer titles are cut to the max length, the value is kept whole
+    assert_eq!(issues[0].title(20), "AssertionError: Exp…");
+    assert_eq!(issues[0].title(20).chars().count(), 20);
+    assert!(issues[0].calculated_value.ends_with("at main.rs:10"));
 }
 
 #[actix_web::test]
@@ -781,7 +1345,7 @@ async fn test_digest_cleans_up_temp_file() {
     let event_json = create_event_json(&event_id);
     let event_bytes = serde_json::to_vec(&event_json).unwrap();
 
-    store_event(ingest_dir, &event_id, &event_bytes)
+    store_event(ingest_dir, &event_id, &event_bytes, false)
         .await
         .expect("Failed to store event");
 
@@ -794,12 +1358,2123 @@ async fn test_digest_cleans_up_temp_file() {
         project_id: project.id,
         ingested_at: Utc::now(),
         remote_addr: None,
+        spool_id: None,
     };
 
-    process_event(&db.pool, &metadata, ingest_dir, &rate_limit_config)
-        .await
-        .expect("Failed to process event");
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
 
     // Verify file is deleted after processing
     assert!(!file_path.exists());
 }
+
+#[actix_web::test]
+async fn test_digest_reads_compressed_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Compressed Event Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = create_event_json(&event_id);
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+    let path = store_event(ingest_dir, &event_id, &event_bytes, true)
+        .await
+        .expect("Failed to store event");
+    assert_ne!(std::fs::read(&path).unwrap(), event_bytes);
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    let event = EventService::get_by_event_id(&db.pool, project.id, event_id.parse().unwrap())
+        .await
+        .expect("Failed to find event");
+    assert_eq!(event.data["exception"], event_json["exception"]);
+}
+
+#[actix_web::test]
+async fn test_digest_truncates_long_stacktrace() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Long Stacktrace Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+    let digest_config = DigestConfig {
+        max_stacktrace_frames: 10,
+        ..DigestConfig::default()
+    };
+
+    let frames: Vec<serde_json::Value> = (0..300)
+        .map(|i| {
+            json!({
+                "filename": "app.rs",
+                "function": format!("frame_{}", i),
+                "lineno": i,
+                "in_app": true
+            })
+        })
+        .collect();
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let mut event_json = create_event_json(&event_id);
+    event_json["exception"]["values"][0]["stacktrace"]["frames"] = json!(frames);
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &digest_config,
+    )
+    .await
+    .expect("Failed to process event");
+
+    let (issues, _) = IssueService::list_paginated(
+        &db.pool,
+        project.id,
+        rustrak::pagination::IssueSort::DigestOrder,
+        rustrak::pagination::SortOrder::Desc,
+        true,
+        None,
+        100,
+    )
+    .await
+    .expect("Failed to list issues");
+    assert_eq!(issues.len(), 1);
+
+    let (events, _) = EventService::list_paginated(
+        &db.pool,
+        issues[0].id,
+        rustrak::pagination::EventSort::Received,
+        rustrak::pagination::SortOrder::Desc,
+        None,
+        None,
+        10,
+    )
+    .await
+    .expect("Failed to list events");
+    assert_eq!(events.len(), 1);
+
+    // Stored payload keeps the top and bottom 5 frames plus a marker
+    let stacktrace = &events[0].data["exception"]["values"][0]["stacktrace"];
+    let stored = stacktrace["frames"].as_array().unwrap();
+    assert_eq!(stored.len(), 11);
+    assert_eq!(stored[0]["function"], "frame_0");
+    assert_eq!(stored[5]["function"], "<290 frames omitted>");
+    assert_eq!(stored[10]["function"], "frame_299");
+    assert_eq!(stacktrace["frames_omitted"], json!([5, 295]));
+
+    // Denormalized fields still come from the crash site
+    assert_eq!(events[0].last_frame_function, "frame_299");
+}
+
+#[actix_web::test]
+async fn test_digest_titles_chained_exception_by_root_cause() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Chained Exception Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let mut event_json = create_event_json(&event_id);
+    event_json["exception"] = json!({
+        "values": [
+            { "type": "KeyError", "value": "'user_id'" },
+            { "type": "RuntimeError", "value": "handler failed" }
+        ]
+    });
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    let (issues, _) = IssueService::list_paginated(
+        &db.pool,
+        project.id,
+        rustrak::pagination::IssueSort::DigestOrder,
+        rustrak::pagination::SortOrder::Desc,
+        true,
+        None,
+        100,
+    )
+    .await
+    .expect("Failed to list issues");
+
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].title(120), "KeyError: 'user_id'");
+    assert_eq!(issues[0].exception_chain_length, 2);
+}
+
+#[actix_web::test]
+async fn test_digest_trims_breadcrumbs() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Breadcrumbs Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    let breadcrumbs: Vec<serde_json::Value> = (0..1000)
+        .map(|i| json!({ "category": "http", "message": format!("request {}", i) }))
+        .collect();
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let mut event_json = create_event_json(&event_id);
+    event_json["breadcrumbs"] = json!({ "values": breadcrumbs });
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    let (issues, _) = IssueService::list_paginated(
+        &db.pool,
+        project.id,
+        rustrak::pagination::IssueSort::DigestOrder,
+        rustrak::pagination::SortOrder::Desc,
+        true,
+        None,
+        100,
+    )
+    .await
+    .expect("Failed to list issues");
+    assert_eq!(issues.len(), 1);
+
+    let (events, _) = EventService::list_paginated(
+        &db.pool,
+        issues[0].id,
+        rustrak::pagination::EventSort::Received,
+        rustrak::pagination::SortOrder::Desc,
+        None,
+        None,
+        10,
+    )
+    .await
+    .expect("Failed to list events");
+
+    // Only the 100 most recent breadcrumbs are stored, plus the annotation
+    let data = &events[0].data;
+    let stored = data["breadcrumbs"]["values"].as_array().unwrap();
+    assert_eq!(stored.len(), 100);
+    assert_eq!(stored[0]["message"], "request 900");
+    assert_eq!(stored[99]["message"], "request 999");
+    assert_eq!(data["_meta"]["breadcrumbs"]["values"][""]["len"], 1000);
+
+    // Grouping is unaffected
+    assert_eq!(
+        issues[0].title(120),
+        "TypeError: Cannot read property 'x' of null"
+    );
+}
+
+#[actix_web::test]
+async fn test_digest_keeps_configured_number_of_breadcrumbs() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Breadcrumbs Limit Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+    let digest_config = DigestConfig {
+        max_breadcrumbs: 25,
+        ..DigestConfig::default()
+    };
+
+    let breadcrumbs: Vec<serde_json::Value> = (0..1000)
+        .map(|i| json!({ "category": "http", "message": format!("request {}", i) }))
+        .collect();
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let mut event_json = create_event_json(&event_id);
+    event_json["breadcrumbs"] = json!({ "values": breadcrumbs });
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &rate_limit_config,
+        &digest_config,
+    )
+    .await
+    .expect("Failed to process event");
+
+    let data: serde_json::Value =
+        sqlx::query_scalar("SELECT data FROM events WHERE project_id = $1")
+            .bind(project.id)
+            .fetch_one(&db.pool)
+            .await
+            .expect("Failed to fetch event");
+
+    // The 25 newest are kept, in their original order
+    let stored = data["breadcrumbs"]["values"].as_array().unwrap();
+    assert_eq!(stored.len(), 25);
+    assert_eq!(stored[0]["message"], "request 975");
+    assert_eq!(stored[24]["message"], "request 999");
+    assert_eq!(data["_meta"]["breadcrumbs"]["values"][""]["len"], 1000);
+}
+
+// =============================================================================
+// Issue Context Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_digest_aggregates_issue_contexts() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Contexts Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    // 10 events: 6 on Chrome/macOS, 3 on Firefox/Ubuntu, 1 without browser
+    let contexts = [
+        (Some(("Chrome", "120.0.6099.109")), ("macOS", "14.2.1")),
+        (Some(("Chrome", "120.0.6099.71")), ("macOS", "14.1")),
+        (Some(("Chrome", "120.0.1")), ("macOS", "14.2")),
+        (Some(("Chrome", "120.0")), ("macOS", "14.0")),
+        (Some(("Chrome", "120.0.6099.109")), ("macOS", "14.3")),
+        (Some(("Chrome", "120.0.6099.109")), ("macOS", "14.2")),
+        (Some(("Firefox", "118.0.2")), ("Ubuntu", "22.04.3 LTS")),
+        (Some(("Firefox", "118.0")), ("Ubuntu", "22.04")),
+        (Some(("Firefox", "118.0.1")), ("Ubuntu", "22.04")),
+        (None, ("Ubuntu", "22.04")),
+    ];
+
+    for (browser, (os_name, os_version)) in contexts {
+        let event_id = Uuid::new_v4().to_string().replace("-", "");
+        let mut event_json = create_event_json(&event_id);
+        event_json["contexts"] = json!({
+            "os": { "name": os_name, "version": os_version },
+            "runtime": { "name": "node", "version": "20.10.0" }
+        });
+        if let Some((name, version)) = browser {
+            event_json["contexts"]["browser"] = json!({ "name": name, "version": version });
+        }
+
+        let event_bytes = serde_json::to_vec(&event_json).unwrap();
+        store_event(ingest_dir, &event_id, &event_bytes, false)
+            .await
+            .expect("Failed to store event");
+
+        let metadata = EventMetadata {
+            event_id,
+            project_id: project.id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
+    }
+
+    let (issues, _) = IssueService::list_paginated(
+        &db.pool,
+        project.id,
+        rustrak::pagination::IssueSort::DigestOrder,
+        rustrak::pagination::SortOrder::Desc,
+        true,
+        None,
+        100,
+    )
+    .await
+    .expect("Failed to list issues");
+    assert_eq!(issues.len(), 1);
+
+    let contexts = IssueContextService::breakdown(&db.pool, issues[0].id)
+        .await
+        .expect("Failed to get contexts");
+
+    // Percentages are relative to events that reported the context
+    assert_eq!(contexts.browser.total, 9);
+    let browsers: Vec<_> = contexts
+        .browser
+        .values
+        .iter()
+        .map(|v| (v.value.as_str(), v.count, v.percentage))
+        .collect();
+    assert_eq!(
+        browsers,
+        vec![("Chrome 120.0", 6, 66.7), ("Firefox 118.0", 3, 33.3)]
+    );
+
+    // OS versions are normalized to major.minor
+    assert_eq!(contexts.os.total, 10);
+    let os: Vec<_> = contexts
+        .os
+        .values
+        .iter()
+        .map(|v| (v.value.as_str(), v.count, v.percentage))
+        .collect();
+    assert_eq!(
+        os,
+        vec![
+            ("Ubuntu 22.04", 4, 40.0),
+            ("macOS 14.2", 3, 30.0),
+            ("macOS 14.0", 1, 10.0),
+            ("macOS 14.1", 1, 10.0),
+            ("macOS 14.3", 1, 10.0),
+        ]
+    );
+
+    assert_eq!(contexts.runtime.values.len(), 1);
+    assert_eq!(contexts.runtime.values[0].value, "node 20.10");
+    assert_eq!(contexts.runtime.values[0].percentage, 100.0);
+}
+
+#[actix_web::test]
+async fn test_digest_records_issue_users() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Users Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+
+    // Ada twice, a device id once, and one event without a user
+    let users = [
+        Some(json!({ "id": "42", "email": "ada@example.com" })),
+        Some(json!({ "id": "device-7f3a" })),
+        Some(json!({ "id": "42", "email": "ada@example.com", "username": "ada" })),
+        None,
+    ];
+    for user in users {
+        let event_id = Uuid::new_v4().to_string().replace("-", "");
+        let mut event_json = create_event_json(&event_id);
+        if let Some(user) = user {
+            event_json["user"] = user;
+        }
+        let event_bytes = serde_json::to_vec(&event_json).unwrap();
+        store_event(ingest_dir, &event_id, &event_bytes, false)
+            .await
+            .expect("Failed to store event");
+
+        let metadata = EventMetadata {
+            event_id,
+            project_id: project.id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &create_rate_limit_config(),
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
+    }
+
+    let issue_id: Uuid = sqlx::query_scalar("SELECT id FROM issues WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    let users = IssueUserService::export_batch(&db.pool, issue_id, None, None)
+        .await
+        .unwrap();
+    let users: Vec<_> = users
+        .iter()
+        .map(|u| {
+            (
+                u.user_id.as_deref(),
+                u.email.as_deref(),
+                u.username.as_deref(),
+                u.event_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        users,
+        vec![
+            (Some("42"), Some("ada@example.com"), Some("ada"), 2),
+            (Some("device-7f3a"), None, None, 1),
+        ]
+    );
+}
+
+#[actix_web::test]
+async fn test_issue_contexts_limited_to_top_values() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Many Contexts Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+
+    // 12 distinct runtimes; runtime 0 is seen twice
+    for i in std::iter::once(0).chain(0..12) {
+        let event_id = Uuid::new_v4().to_string().replace("-", "");
+        let mut event_json = create_event_json(&event_id);
+        event_json["contexts"] = json!({
+            "runtime": { "name": "python", "version": format!("3.{}.0", i) }
+        });
+
+        let event_bytes = serde_json::to_vec(&event_json).unwrap();
+        store_event(ingest_dir, &event_id, &event_bytes, false)
+            .await
+            .expect("Failed to store event");
+
+        let metadata = EventMetadata {
+            event_id,
+            project_id: project.id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &rate_limit_config,
+            &DigestConfig::default(),
+        )
+        .await
+        .expect("Failed to process event");
+    }
+
+    let issue_id: Uuid = sqlx::query_scalar("SELECT id FROM issues WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    let contexts = IssueContextService::breakdown(&db.pool, issue_id)
+        .await
+        .expect("Failed to get contexts");
+
+    assert_eq!(contexts.runtime.total, 13);
+    assert_eq!(contexts.runtime.values.len(), 10);
+    assert_eq!(contexts.runtime.values[0].value, "python 3.0");
+    assert_eq!(contexts.runtime.values[0].count, 2);
+    assert!(contexts.os.values.is_empty());
+    assert!(contexts.browser.values.is_empty());
+}
+
+// =============================================================================
+// Culprit Tests
+// =============================================================================
+
+/// Digests a `checkout` fingerprinted event ingested at `ingested_at`
+async fn digest_checkout_event(
+    pool: &PgPool,
+    project_id: i32,
+    ingest_dir: &std::path::Path,
+    transaction: Option<&str>,
+    module: &str,
+    function: &str,
+    ingested_at: chrono::DateTime<Utc>,
+) -> Uuid {
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let mut event_json = json!({
+        "event_id": &event_id,
+        "timestamp": Utc::now().timestamp() as f64,
+        "platform": "python",
+        "level": "error",
+        "fingerprint": ["checkout"],
+        "exception": {
+            "values": [{
+                "type": "ValueError",
+                "value": "bad amount",
+                "stacktrace": {
+                    "frames": [{
+                        "filename": "app/views/checkout.py",
+                        "module": module,
+                        "function": function,
+                        "in_app": true
+                    }]
+                }
+            }]
+        }
+    });
+    if let Some(transaction) = transaction {
+        event_json["transaction"] = json!(transaction);
+    }
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id,
+        ingested_at,
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        pool,
+        &metadata,
+        ingest_dir,
+        &create_rate_limit_config(),
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    sqlx::query_scalar("SELECT issue_id FROM events WHERE event_id = $1")
+        .bind(Uuid::parse_str(&event_id).unwrap())
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn test_digest_refreshes_culprit_from_latest_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Culprit Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let now = Utc::now();
+
+    let issue_id = digest_checkout_event(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        None,
+        "app.views.checkout",
+        "process_payment",
+        now - chrono::Duration::minutes(10),
+    )
+    .await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(
+        issue.culprit().as_deref(),
+        Some("app.views.checkout in process_payment")
+    );
+
+    // A newer event moves the culprit along
+    digest_checkout_event(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        Some("/checkout/confirm"),
+        "app.views.checkout",
+        "confirm_order",
+        now,
+    )
+    .await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.culprit().as_deref(), Some("/checkout/confirm"));
+    assert_eq!(issue.last_frame_function, "confirm_order");
+
+    // An older event digested late leaves it alone
+    digest_checkout_event(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        Some("/checkout/legacy"),
+        "app.views.legacy",
+        "pay",
+        now - chrono::Duration::minutes(5),
+    )
+    .await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.culprit().as_deref(), Some("/checkout/confirm"));
+    assert_eq!(issue.digested_event_count, 3);
+}
+
+#[actix_web::test]
+async fn test_backfill_culprits_uses_latest_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Culprit Backfill Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let now = Utc::now();
+
+    let issue_id = digest_checkout_event(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        None,
+        "app.views.checkout",
+        "process_payment",
+        now - chrono::Duration::minutes(1),
+    )
+    .await;
+    digest_checkout_event(
+        &db.pool,
+        project.id,
+        temp_dir.path(),
+        Some("/checkout"),
+        "app.views.checkout",
+        "confirm_order",
+        now,
+    )
+    .await;
+
+    // Issues digested before culprits were refreshed kept the first event's fields
+    sqlx::query(
+        r#"
+        UPDATE issues
+        SET transaction = '<no transaction>', last_frame_function = 'process_payment'
+        WHERE id = $1
+        "#,
+    )
+    .bind(issue_id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let updated = IssueService::backfill_culprits(&db.pool).await.unwrap();
+    assert_eq!(updated, 1);
+
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.culprit().as_deref(), Some("/checkout"));
+    assert_eq!(issue.last_frame_function, "confirm_order");
+
+    // Nothing left to change on a second run
+    let updated = IssueService::backfill_culprits(&db.pool).await.unwrap();
+    assert_eq!(updated, 0);
+}
+
+#[actix_web::test]
+async fn test_digest_caps_stored_value_length() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Huge Value Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let digest_config = DigestConfig::default();
+
+    // A single-line 1MB value, e.g. a serialized request body
+    let huge_value = format!("Bad request: {}", "x".repeat(1024 * 1024));
+    let mut issue_ids = Vec::new();
+    for _ in 0..2 {
+        let event_id = Uuid::new_v4().simple().to_string();
+        let event_json = json!({
+            "event_id": &event_id,
+            "timestamp": Utc::now().timestamp() as f64,
+            "platform": "python",
+            "level": "error",
+            "exception": { "values": [{ "type": "RequestError", "value": &huge_value }] }
+        });
+        store_event(
+            ingest_dir,
+            &event_id,
+            &serde_json::to_vec(&event_json).unwrap(),
+            false,
+        )
+        .await
+        .expect("Failed to store event");
+
+        let metadata = EventMetadata {
+            event_id: event_id.clone(),
+            project_id: project.id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        };
+        process_event(
+            &db.pool,
+            &metadata,
+            ingest_dir,
+            &create_rate_limit_config(),
+            &digest_config,
+        )
+        .await
+        .expect("Failed to process event");
+
+        let (issue_id, value, data): (Uuid, String, serde_json::Value) = sqlx::query_as(
+            "SELECT issue_id, calculated_value, data FROM events WHERE event_id = $1",
+        )
+        .bind(Uuid::parse_str(&event_id).unwrap())
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(value.chars().count(), digest_config.max_issue_value_length);
+        assert!(value.ends_with('…'));
+        // The full value is only kept in the event JSON
+        assert_eq!(data["exception"]["values"][0]["value"], huge_value);
+        issue_ids.push(issue_id);
+    }
+    assert_eq!(issue_ids[0], issue_ids[1]);
+
+    let issue = IssueService::get_by_id(&db.pool, issue_ids[0])
+        .await
+        .unwrap();
+    assert_eq!(
+        issue.calculated_value.chars().count(),
+        digest_config.max_issue_value_length
+    );
+}
+
+#[actix_web::test]
+async fn test_truncate_values_cuts_existing_rows() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Truncate Values Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+
+    // Rows stored before values were capped
+    for table in ["issues", "events"] {
+        sqlx::query(&format!(
+            "UPDATE {} SET calculated_value = repeat('v', 1024) WHERE project_id = $1",
+            table
+        ))
+        .bind(project.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    }
+
+    let changed = IssueService::truncate_values(&db.pool, 500).await.unwrap();
+    assert_eq!(changed, (1, 1));
+
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.calculated_value.chars().count(), 500);
+    assert!(issue.calculated_value.ends_with("v…"));
+
+    // Nothing left to cut on a second run
+    let changed = IssueService::truncate_values(&db.pool, 500).await.unwrap();
+    assert_eq!(changed, (0, 0));
+}
+
+// =============================================================================
+// Daily Stats Tests
+// =============================================================================
+
+fn timezone_update(timezone: &str) -> UpdateProject {
+    UpdateProject {
+        name: None,
+        team_id: None,
+        allowed_domains: None,
+        confirm_wildcard: false,
+        auto_resolve_days: None,
+        in_app_include: None,
+        in_app_exclude: None,
+        default_issue_sort: None,
+        timezone: Some(timezone.to_string()),
+    }
+}
+
+#[actix_web::test]
+async fn test_daily_event_counts_use_project_timezone() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Tokyo Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    // Yesterday's midnight in Tokyo (UTC+9, no DST) is 15:00 UTC, so events
+    // an hour either side of it fall on two Tokyo days but one UTC day
+    let tokyo_today = (Utc::now() + chrono::Duration::hours(9)).date_naive();
+    let midnight = (tokyo_today - chrono::Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        - chrono::Duration::hours(9);
+    for (offset, error_type) in [(-1, "LateError"), (1, "EarlyError")] {
+        let issue_id = digest_error(&db.pool, project.id, temp_dir.path(), error_type).await;
+        sqlx::query("UPDATE events SET digested_at = $2 WHERE issue_id = $1")
+            .bind(issue_id)
+            .bind(midnight + chrono::Duration::hours(offset))
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+    let before_midnight = (midnight - chrono::Duration::hours(1)).date_naive();
+    let tokyo_day = |days_ago: i64| tokyo_today - chrono::Duration::days(days_ago);
+
+    let utc = ProjectService::daily_event_counts(&db.pool, project.id, "UTC", 7)
+        .await
+        .unwrap();
+    assert_eq!(utc.len(), 7);
+    let nonzero: Vec<_> = utc.iter().filter(|d| d.events > 0).collect();
+    assert_eq!(nonzero.len(), 1);
+    assert_eq!((nonzero[0].date, nonzero[0].events), (before_midnight, 2));
+
+    let tokyo = ProjectService::daily_event_counts(&db.pool, project.id, "Asia/Tokyo", 7)
+        .await
+        .unwrap();
+    assert_eq!(tokyo.last().unwrap().date, tokyo_today);
+    let nonzero: Vec<_> = tokyo
+        .iter()
+        .filter(|d| d.events > 0)
+        .map(|d| (d.date, d.events))
+        .collect();
+    assert_eq!(nonzero, [(tokyo_day(2), 1), (tokyo_day(1), 1)]);
+
+    // The timezone is validated when set and can be reset to UTC
+    let updated = ProjectService::update(&db.pool, project.id, timezone_update(" Asia/Tokyo "))
+        .await
+        .unwrap();
+    assert_eq!(updated.timezone.as_deref(), Some("Asia/Tokyo"));
+    let result =
+        ProjectService::update(&db.pool, project.id, timezone_update("Mars/Olympus_Mons")).await;
+    assert!(matches!(
+        result,
+        Err(rustrak::error::AppError::InvalidField { .. })
+    ));
+    let updated = ProjectService::update(&db.pool, project.id, timezone_update(""))
+        .await
+        .unwrap();
+    assert_eq!(updated.timezone, None);
+}
+
+#[actix_web::test]
+async fn test_issue_sparklines_count_events_per_hour() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Sparkline Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    // Events a minute into known hours before the current one; the one 24
+    // hours back falls just outside the sparkline
+    let last_hour = Utc::now()
+        .duration_trunc(chrono::Duration::hours(1))
+        .unwrap();
+    let mut issue_ids = Vec::new();
+    for (error_type, hours_ago) in [
+        ("HourlyError", 0),
+        ("HourlyError", 2),
+        ("HourlyError", 2),
+        ("HourlyError", 23),
+        ("StaleError", 24),
+    ] {
+        let issue_id = digest_error(&db.pool, project.id, temp_dir.path(), error_type).await;
+        sqlx::query(
+            "UPDATE events SET digested_at = $2 WHERE id = \
+             (SELECT id FROM events WHERE issue_id = $1 ORDER BY digest_order DESC LIMIT 1)",
+        )
+        .bind(issue_id)
+        .bind(last_hour - chrono::Duration::hours(hours_ago) + chrono::Duration::minutes(1))
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        if !issue_ids.contains(&issue_id) {
+            issue_ids.push(issue_id);
+        }
+    }
+    let (hourly, stale) = (issue_ids[0], issue_ids[1]);
+
+    // One call covers every issue of the page
+    let sparklines = IssueService::sparklines(&db.pool, &issue_ids)
+        .await
+        .unwrap();
+    assert_eq!(sparklines.len(), 2);
+
+    let sparkline = &sparklines[&hourly];
+    assert_eq!(sparkline.len(), 24);
+    assert_eq!(sparkline[0].hour, last_hour - chrono::Duration::hours(23));
+    assert_eq!(sparkline[23].hour, last_hour);
+    let events: Vec<i64> = sparkline.iter().map(|h| h.events).collect();
+    let mut expected = vec![0; 24];
+    expected[0] = 1;
+    expected[21] = 2;
+    expected[23] = 1;
+    assert_eq!(events, expected);
+
+    let sparkline = &sparklines[&stale];
+    assert_eq!(sparkline.len(), 24);
+    assert!(sparkline.iter().all(|h| h.events == 0));
+
+    assert!(IssueService::sparklines(&db.pool, &[])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+// =============================================================================
+// Issue Trash Tests
+// =============================================================================
+
+/// Digests a new `TypeError` event and returns the id of its issue
+async fn digest_type_error(pool: &PgPool, project_id: i32, ingest_dir: &std::path::Path) -> Uuid {
+    digest_error(pool, project_id, ingest_dir, "TypeError").await
+}
+
+/// Digests a new event of `error_type` and returns the id of its issue
+async fn digest_error(
+    pool: &PgPool,
+    project_id: i32,
+    ingest_dir: &std::path::Path,
+    error_type: &str,
+) -> Uuid {
+    digest_error_with(
+        pool,
+        project_id,
+        ingest_dir,
+        error_type,
+        &DigestConfig::default(),
+    )
+    .await
+}
+
+async fn digest_error_with(
+    pool: &PgPool,
+    project_id: i32,
+    ingest_dir: &std::path::Path,
+    error_type: &str,
+    digest_config: &DigestConfig,
+) -> Uuid {
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let mut event_json = create_event_json(&event_id);
+    event_json["exception"]["values"][0]["type"] = json!(error_type);
+    let event_bytes = serde_json::to_vec(&event_json).unwrap();
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        pool,
+        &metadata,
+        ingest_dir,
+        &create_rate_limit_config(),
+        digest_config,
+    )
+    .await
+    .expect("Failed to process event");
+
+    sqlx::query_scalar("SELECT issue_id FROM events WHERE event_id = $1")
+        .bind(Uuid::parse_str(&event_id).unwrap())
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn event_count(pool: &PgPool, issue_id: Uuid) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE issue_id = $1")
+        .bind(issue_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn test_deleted_issue_restores_with_events() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Trash Restore Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let trash_days = RetentionConfig::DEFAULT_ISSUE_TRASH_DAYS;
+
+    let issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+
+    IssueService::delete(&db.pool, issue_id)
+        .await
+        .expect("Failed to delete issue");
+    assert!(IssueService::get_by_id(&db.pool, issue_id).await.is_err());
+    assert!(IssueService::delete(&db.pool, issue_id).await.is_err());
+
+    let (trash, total) = IssueService::list_trash(&db.pool, project.id, trash_days, 1, 20)
+        .await
+        .expect("Failed to list trash");
+    assert_eq!(total, 1);
+    assert_eq!(trash[0].id, issue_id);
+    assert!(trash[0].deleted_at.is_some());
+
+    let restored = IssueService::restore(&db.pool, issue_id, trash_days)
+        .await
+        .expect("Failed to restore issue");
+    assert!(restored.deleted_at.is_none());
+    assert!(IssueService::get_by_id(&db.pool, issue_id).await.is_ok());
+    assert_eq!(event_count(&db.pool, issue_id).await, 2);
+
+    // The grouping is linked again, so new events join the restored issue
+    let next_issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    assert_eq!(next_issue_id, issue_id);
+
+    let (trash, _) = IssueService::list_trash(&db.pool, project.id, trash_days, 1, 20)
+        .await
+        .unwrap();
+    assert!(trash.is_empty());
+}
+
+#[actix_web::test]
+async fn test_deleted_issue_recurring_event_opens_new_issue() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Trash Recurring Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let trash_days = RetentionConfig::DEFAULT_ISSUE_TRASH_DAYS;
+
+    let issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    IssueService::delete(&db.pool, issue_id).await.unwrap();
+
+    let new_issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    assert_ne!(new_issue_id, issue_id);
+    assert_eq!(event_count(&db.pool, issue_id).await, 1);
+
+    // The newer issue keeps the grouping once the old one is restored
+    IssueService::restore(&db.pool, issue_id, trash_days)
+        .await
+        .expect("Failed to restore issue");
+    let next_issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    assert_eq!(next_issue_id, new_issue_id);
+}
+
+#[actix_web::test]
+async fn test_deleted_issue_purged_after_trash_window() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Trash Purge Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config = RetentionConfig::default();
+
+    let expired_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    IssueService::delete(&db.pool, expired_id).await.unwrap();
+    sqlx::query("UPDATE issues SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1")
+        .bind(expired_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    // Past the window it can't be restored and is no longer listed
+    let err = IssueService::restore(&db.pool, expired_id, config.issue_trash_days).await;
+    assert!(matches!(err, Err(rustrak::error::AppError::NotFound(_))));
+    let (trash, _) = IssueService::list_trash(&db.pool, project.id, config.issue_trash_days, 1, 20)
+        .await
+        .unwrap();
+    assert!(trash.is_empty());
+
+    // A recently deleted issue survives the retention run
+    let recent_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    IssueService::delete(&db.pool, recent_id).await.unwrap();
+
+    let purged = RetentionService::run(&db.pool, &config, temp_dir.path())
+        .await
+        .expect("Retention run failed");
+    assert_eq!(purged, 1);
+
+    let remaining: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM issues WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, vec![recent_id]);
+    assert_eq!(event_count(&db.pool, expired_id).await, 0);
+    assert_eq!(event_count(&db.pool, recent_id).await, 1);
+}
+
+#[actix_web::test]
+async fn test_event_payloads_pruned_after_payload_retention() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Payload Retention Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config = RetentionConfig {
+        payload_retention_days: Some(90),
+        ..RetentionConfig::default()
+    };
+
+    let old_issue_id = digest_error(&db.pool, project.id, temp_dir.path(), "OldError").await;
+    sqlx::query("UPDATE events SET digested_at = NOW() - INTERVAL '91 days' WHERE issue_id = $1")
+        .bind(old_issue_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let recent_issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+
+    RetentionService::run(&db.pool, &config, temp_dir.path())
+        .await
+        .expect("Retention run failed");
+
+    let event_of = |issue_id: Uuid| {
+        let pool = db.pool.clone();
+        async move {
+            let id: Uuid = sqlx::query_scalar("SELECT id FROM events WHERE issue_id = $1")
+                .bind(issue_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            EventService::get_by_id(&pool, id).await.unwrap()
+        }
+    };
+
+    // The old event keeps its row and metadata, but not its payload
+    let old = event_of(old_issue_id).await;
+    assert!(old.payload_pruned);
+    assert_eq!(old.data, json!({}));
+    assert_eq!(old.calculated_type, "OldError");
+    assert_eq!(old.transaction, "/api/users");
+    let detail = old.to_detail_response();
+    assert!(detail.payload_pruned);
+    assert!(detail.data.is_null());
+    assert_eq!(detail.title, old.title());
+
+    let recent = event_of(recent_issue_id).await;
+    assert!(!recent.payload_pruned);
+    assert_eq!(recent.data["exception"]["values"][0]["type"], "TypeError");
+
+    // Updates can't be merged into a payload that's gone
+    let merged = EventService::merge_update(
+        &db.pool,
+        project.id,
+        old.event_id,
+        &json!({"level": "fatal"}),
+        Utc::now(),
+    )
+    .await
+    .unwrap();
+    assert!(merged.is_none());
+
+    // Without payload retention, payloads are kept
+    sqlx::query("UPDATE events SET digested_at = NOW() - INTERVAL '91 days' WHERE issue_id = $1")
+        .bind(recent_issue_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    RetentionService::run(&db.pool, &RetentionConfig::default(), temp_dir.path())
+        .await
+        .unwrap();
+    assert!(!event_of(recent_issue_id).await.payload_pruned);
+}
+
+#[actix_web::test]
+async fn test_minidumps_deleted_with_event_payloads() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Minidump Retention Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config = RetentionConfig {
+        payload_retention_days: Some(90),
+        ..RetentionConfig::default()
+    };
+
+    let stored_minidump = |issue_id: Option<Uuid>| {
+        let pool = db.pool.clone();
+        let dir = temp_dir.path().to_path_buf();
+        async move {
+            let event_id = match issue_id {
+                Some(issue_id) => {
+                    sqlx::query_scalar("SELECT event_id FROM events WHERE issue_id = $1")
+                        .bind(issue_id)
+                        .fetch_one(&pool)
+                        .await
+                        .unwrap()
+                }
+                None => Uuid::new_v4(),
+            };
+            store_minidump(&dir, project.id, &event_id.to_string(), b"MDMP")
+                .await
+                .unwrap()
+        }
+    };
+
+    // An event with its payload keeps its minidump
+    let kept_issue = digest_error(&db.pool, project.id, temp_dir.path(), "KeptError").await;
+    let kept = stored_minidump(Some(kept_issue)).await;
+
+    // Pruned and deleted events lose theirs
+    let pruned_issue = digest_error(&db.pool, project.id, temp_dir.path(), "PrunedError").await;
+    let pruned = stored_minidump(Some(pruned_issue)).await;
+    sqlx::query("UPDATE events SET digested_at = NOW() - INTERVAL '91 days' WHERE issue_id = $1")
+        .bind(pruned_issue)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let deleted_issue = digest_error(&db.pool, project.id, temp_dir.path(), "DeletedError").await;
+    let deleted = stored_minidump(Some(deleted_issue)).await;
+    sqlx::query("DELETE FROM events WHERE issue_id = $1")
+        .bind(deleted_issue)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    // An event not digested yet keeps its minidump until its id is forgotten
+    let pending = stored_minidump(None).await;
+    let forgotten = stored_minidump(None).await;
+    std::fs::File::options()
+        .write(true)
+        .open(&forgotten)
+        .unwrap()
+        .set_modified(
+            std::time::SystemTime::now()
+                - Duration::from_secs((INGESTED_EVENT_RETENTION_DAYS as u64 + 1) * 24 * 60 * 60),
+        )
+        .unwrap();
+
+    RetentionService::run(&db.pool, &config, temp_dir.path())
+        .await
+        .expect("Retention run failed");
+
+    assert!(kept.exists());
+    assert!(!pruned.exists());
+    assert!(!deleted.exists());
+    assert!(pending.exists());
+    assert!(!forgotten.exists());
+}
+
+// =============================================================================
+// Auto-Resolve Tests
+// =============================================================================
+
+fn auto_resolve_update(days: i32) -> UpdateProject {
+    UpdateProject {
+        name: None,
+        team_id: None,
+        allowed_domains: None,
+        confirm_wildcard: false,
+        auto_resolve_days: Some(days),
+        in_app_include: None,
+        in_app_exclude: None,
+        default_issue_sort: None,
+        timezone: None,
+    }
+}
+
+async fn backdate_last_seen(pool: &PgPool, issue_id: Uuid, days: i32) {
+    sqlx::query("UPDATE issues SET last_seen = NOW() - make_interval(days => $2) WHERE id = $1")
+        .bind(issue_id)
+        .bind(days)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn test_auto_resolve_quiet_issues_and_reopen_on_new_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Auto Resolve Project").await;
+    let other = create_test_project(&db.pool, "Manual Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+
+    let result = ProjectService::update(&db.pool, project.id, auto_resolve_update(-1)).await;
+    assert!(result.is_err());
+    let updated = ProjectService::update(&db.pool, project.id, auto_resolve_update(7))
+        .await
+        .unwrap();
+    assert_eq!(updated.auto_resolve_days, Some(7));
+
+    let quiet = digest_error(&db.pool, project.id, dir, "TypeError").await;
+    let recent = digest_error(&db.pool, project.id, dir, "ValueError").await;
+    let muted = digest_error(&db.pool, project.id, dir, "KeyError").await;
+    let elsewhere = digest_error(&db.pool, other.id, dir, "TypeError").await;
+    for issue_id in [quiet, muted, elsewhere] {
+        backdate_last_seen(&db.pool, issue_id, 8).await;
+    }
+    IssueService::mute(&db.pool, muted).await.unwrap();
+
+    // Only the quiet, unmuted issue of the project with a threshold
+    let resolved = AutoResolveService::run(&db.pool).await.unwrap();
+    assert_eq!(resolved, 1);
+    let issue = IssueService::get_by_id(&db.pool, quiet).await.unwrap();
+    assert!(issue.is_resolved);
+    assert!(issue.auto_resolved);
+    assert!(issue.resolved_at.is_some());
+    assert_eq!(issue.resolved_by, None);
+    let activity = IssueService::activity(&db.pool, quiet).await.unwrap();
+    assert_eq!(activity.len(), 1);
+    assert_eq!(activity[0].kind, "auto_resolved");
+    for issue_id in [recent, muted, elsewhere] {
+        let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+        assert!(!issue.is_resolved);
+        assert!(IssueService::activity(&db.pool, issue_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+    assert_eq!(AutoResolveService::run(&db.pool).await.unwrap(), 0);
+
+    // The error is back: the issue is reopened
+    let issue_id = digest_error(&db.pool, project.id, dir, "TypeError").await;
+    assert_eq!(issue_id, quiet);
+    let issue = IssueService::get_by_id(&db.pool, quiet).await.unwrap();
+    assert!(!issue.is_resolved);
+    assert!(!issue.auto_resolved);
+    assert!(issue.resolved_at.is_none());
+
+    // Issues resolved by hand are reopened the same way
+    IssueService::resolve(&db.pool, recent, Actor::System)
+        .await
+        .unwrap();
+    digest_error(&db.pool, project.id, dir, "ValueError").await;
+    let issue = IssueService::get_by_id(&db.pool, recent).await.unwrap();
+    assert!(!issue.is_resolved);
+    assert!(!issue.auto_resolved);
+    assert_eq!(issue.regression_count, 1);
+
+    // 0 turns auto-resolving off
+    let updated = ProjectService::update(&db.pool, project.id, auto_resolve_update(0))
+        .await
+        .unwrap();
+    assert_eq!(updated.auto_resolve_days, None);
+}
+
+#[actix_web::test]
+async fn test_regressions_counted_each_time_issue_reopens() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Zombie Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    ProjectService::update(&db.pool, project.id, auto_resolve_update(7))
+        .await
+        .unwrap();
+
+    let issue_id = digest_error(&db.pool, project.id, dir, "TypeError").await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.regression_count, 0);
+
+    for round in 1..=3 {
+        backdate_last_seen(&db.pool, issue_id, 8).await;
+        assert_eq!(AutoResolveService::run(&db.pool).await.unwrap(), 1);
+        digest_error(&db.pool, project.id, dir, "TypeError").await;
+
+        let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+        assert!(!issue.is_resolved);
+        assert_eq!(issue.regression_count, round);
+    }
+
+    // Events of an open issue aren't regressions
+    digest_error(&db.pool, project.id, dir, "TypeError").await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.regression_count, 3);
+
+    // Resolving by hand counts too, and drops the fix that didn't hold
+    let resolution = ResolveIssue {
+        commit: Some("a1b2c3d".to_string()),
+        pr_url: None,
+    };
+    IssueService::resolve_with(&db.pool, issue_id, Actor::System, &resolution)
+        .await
+        .unwrap();
+    digest_error(&db.pool, project.id, dir, "TypeError").await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert!(!issue.is_resolved);
+    assert_eq!(issue.resolved_commit, None);
+    assert_eq!(issue.regression_count, 4);
+
+    let regressed_at = IssueService::regressed_at(&db.pool, issue_id)
+        .await
+        .unwrap();
+    assert_eq!(regressed_at.len(), 4);
+    assert!(regressed_at.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[actix_web::test]
+async fn test_issue_priority_raised_on_spike() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Spike Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    let digest_config = DigestConfig {
+        priority_spike_events: 3,
+        ..DigestConfig::default()
+    };
+    let digest =
+        |error_type| digest_error_with(&db.pool, project.id, dir, error_type, &digest_config);
+
+    // Handled errors start at medium
+    let issue_id = digest("TypeError").await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.priority, IssuePriority::Medium);
+    assert!(!issue.priority_is_manual);
+
+    digest("TypeError").await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.priority, IssuePriority::Medium);
+
+    // The third event within the window raises it a level
+    digest("TypeError").await;
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.priority, IssuePriority::High);
+    assert!(!issue.priority_is_manual);
+
+    let history = IssueService::priority_history(&db.pool, issue_id)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].old_priority, IssuePriority::Medium);
+    assert_eq!(history[0].new_priority, IssuePriority::High);
+    assert!(!history[0].is_manual);
+    assert_eq!(history[0].changed_by, None);
+
+    // A priority set by hand stays, whatever the events
+    let other_id = digest("ValueError").await;
+    IssueService::set_priority(&db.pool, other_id, IssuePriority::Low, Actor::System)
+        .await
+        .unwrap();
+    for _ in 0..4 {
+        digest("ValueError").await;
+    }
+    let other = IssueService::get_by_id(&db.pool, other_id).await.unwrap();
+    assert_eq!(other.priority, IssuePriority::Low);
+    assert!(other.priority_is_manual);
+
+    let history = IssueService::priority_history(&db.pool, other_id)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].new_priority, IssuePriority::Low);
+    assert!(history[0].is_manual);
+}
+
+// =============================================================================
+// Digest Runner Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_drain_digests_backlog_and_restores_concurrency() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Drain Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+    let rate_limit_config = create_rate_limit_config();
+    let db_health = Arc::new(DbHealth::new());
+
+    // Paused, so spooled events wait in the ingest directory
+    let runner = Arc::new(DigestRunner::new(0));
+    for i in 0..201 {
+        let event_id = Uuid::new_v4().simple().to_string();
+        // One event has no file, so its digest fails
+        if i > 0 {
+            store_event(
+                ingest_dir,
+                &event_id,
+                &serde_json::to_vec(&create_event_json(&event_id)).unwrap(),
+                false,
+            )
+            .await
+            .unwrap();
+        }
+        runner.spawn(
+            &db.pool,
+            &rate_limit_config,
+            &DigestConfig::default(),
+            db_health.clone(),
+            ingest_dir.to_path_buf(),
+            EventMetadata {
+                event_id,
+                project_id: project.id,
+                ingested_at: Utc::now(),
+                remote_addr: None,
+                spool_id: None,
+            },
+        );
+    }
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let status = runner.status();
+    assert_eq!(status.backlog, 201);
+    assert_eq!(status.processed_last_minute, 0);
+    assert_eq!(status.concurrency, 0);
+
+    let job = runner
+        .start_drain(8, Duration::from_secs(60))
+        .expect("Failed to start drain");
+    assert_eq!(job.backlog_at_start, 201);
+    assert_eq!(job.previous_concurrency, 0);
+    assert_eq!(runner.concurrency(), 8);
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let job = loop {
+        let job = runner.drain_job().unwrap();
+        if job.state != DrainState::Running {
+            break job;
+        }
+        assert!(Instant::now() < deadline, "Drain didn't finish");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+    assert_eq!(job.state, DrainState::Completed);
+    assert_eq!(job.processed, 201);
+
+    // Back to paused once the backlog is gone
+    let status = runner.status();
+    assert_eq!(status.concurrency, 0);
+    assert_eq!(runner.available(), 0);
+    assert_eq!(status.backlog, 0);
+    assert_eq!(status.processed_last_minute, 201);
+    assert!(status.last_error.is_some());
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE project_id = $1")
+        .bind(project.id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, 200);
+    assert_eq!(std::fs::read_dir(ingest_dir).unwrap().count(), 0);
+}
+// =============================================================================
+// Sampling Tests
+// =============================================================================
+
+/// Digests a copy of `create_event_json` under a new event_id
+async fn digest_new_event(pool: &PgPool, ingest_dir: &std::path::Path, project_id: i32) -> Uuid {
+    let event_id = Uuid::new_v4().simple().to_string();
+    let event_bytes = serde_json::to_vec(&create_event_json(&event_id)).unwrap();
+    store_event(ingest_dir, &event_id, &event_bytes, false)
+        .await
+        .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        pool,
+        &metadata,
+        ingest_dir,
+        &create_rate_limit_config(),
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    Uuid::parse_str(&event_id).unwrap()
+}
+
+#[actix_web::test]
+async fn test_digest_samples_events_of_sampled_issue() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Sampling Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+
+    let first = digest_new_event(&db.pool, ingest_dir, project.id).await;
+    let issue_id = EventService::get_by_event_id(&db.pool, project.id, first)
+        .await
+        .expect("Failed to get event")
+        .issue_id;
+
+    let issue = IssueService::set_sample_rate(&db.pool, issue_id, 0.1)
+        .await
+        .expect("Failed to set sample rate");
+    assert_eq!(issue.sample_rate, Some(0.1));
+
+    let mut kept = 0;
+    for _ in 0..100 {
+        let event_id = digest_new_event(&db.pool, ingest_dir, project.id).await;
+        let stored = EventService::exists(&db.pool, project.id, event_id)
+            .await
+            .unwrap();
+        assert_eq!(stored, rustrak::digest::keeps_event(event_id, Some(0.1)));
+        if stored {
+            kept += 1;
+        }
+    }
+
+    // Every event is counted, about 1 in 10 is stored
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.digested_event_count - 1, 100);
+    assert!((1..=25).contains(&kept), "kept {} of 100 events", kept);
+    assert_eq!(issue.stored_event_count, 1 + kept);
+    assert_eq!(issue.last_event_order, 1 + kept);
+
+    let project = ProjectService::get_by_id(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert_eq!(project.stored_event_count, 1 + kept);
+    assert_eq!(project.digested_event_count, 101);
+
+    let sampled: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM ingested_events WHERE project_id = $1 AND sampled",
+    )
+    .bind(project.id)
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!(sampled, 100 - kept as i64);
+    assert_eq!(std::fs::read_dir(ingest_dir).unwrap().count(), 0);
+
+    // A rate of 1 stores every event again
+    let issue = IssueService::set_sample_rate(&db.pool, issue_id, 1.0)
+        .await
+        .unwrap();
+    assert_eq!(issue.sample_rate, None);
+    for _ in 0..10 {
+        let event_id = digest_new_event(&db.pool, ingest_dir, project.id).await;
+        assert!(EventService::exists(&db.pool, project.id, event_id)
+            .await
+            .unwrap());
+    }
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.digested_event_count, 111);
+    assert_eq!(issue.stored_event_count, 11 + kept);
+}
+
+#[actix_web::test]
+async fn test_recount_keeps_sampled_out_events_counted() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Sampled Recount Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+
+    let first = digest_new_event(&db.pool, ingest_dir, project.id).await;
+    let issue_id = EventService::get_by_event_id(&db.pool, project.id, first)
+        .await
+        .unwrap()
+        .issue_id;
+    IssueService::set_sample_rate(&db.pool, issue_id, 0.1)
+        .await
+        .unwrap();
+    for _ in 0..50 {
+        digest_new_event(&db.pool, ingest_dir, project.id).await;
+    }
+    let before = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(before.digested_event_count, 51);
+    assert!(before.stored_event_count < 51);
+
+    // The sampled-out events aren't stored, yet they were digested
+    let recount = ProjectService::recount(&db.pool, project.id).await.unwrap();
+    assert!(recount.issues.is_empty());
+    assert_eq!(recount.after.digested_event_count, 51);
+    let after = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(after.digested_event_count, 51);
+    assert_eq!(after.stored_event_count, before.stored_event_count);
+}
+
+#[actix_web::test]
+async fn test_sample_rate_must_be_a_fraction() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Sampling Validation").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let event_id = digest_new_event(&db.pool, temp_dir.path(), project.id).await;
+    let issue_id = EventService::get_by_event_id(&db.pool, project.id, event_id)
+        .await
+        .unwrap()
+        .issue_id;
+
+    for rate in [0.0, -0.5, 1.5, f64::NAN] {
+        let err = IssueService::set_sample_rate(&db.pool, issue_id, rate)
+            .await
+            .unwrap_err();
+        assert_eq!(err.field(), Some("sample_rate"));
+    }
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.sample_rate, None);
+}
+// =============================================================================
+// In-App Rules Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_digest_applies_project_in_app_rules() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "In-App Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let ingest_dir = temp_dir.path();
+
+    ProjectService::update(
+        &db.pool,
+        project.id,
+        UpdateProject {
+            name: None,
+            team_id: None,
+            allowed_domains: None,
+            confirm_wildcard: false,
+            auto_resolve_days: None,
+            in_app_include: None,
+            in_app_exclude: Some(vec!["framework".to_string()]),
+            default_issue_sort: None,
+            timezone: None,
+        },
+    )
+    .await
+    .expect("Failed to update project");
+
+    let event_id = Uuid::new_v4().simple().to_string();
+    let mut event_json = create_event_json(&event_id);
+    event_json["exception"]["values"][0]["stacktrace"]["frames"] = json!([
+        { "filename": "app.rs", "module": "app::handlers", "function": "handle_request", "in_app": true },
+        { "filename": "router.rs", "module": "framework::router", "function": "dispatch", "in_app": true }
+    ]);
+    store_event(
+        ingest_dir,
+        &event_id,
+        &serde_json::to_vec(&event_json).unwrap(),
+        false,
+    )
+    .await
+    .expect("Failed to store event");
+
+    let metadata = EventMetadata {
+        event_id: event_id.clone(),
+        project_id: project.id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        &db.pool,
+        &metadata,
+        ingest_dir,
+        &create_rate_limit_config(),
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to process event");
+
+    // The framework frame no longer counts as the culprit, in the issue or the stored event
+    let event =
+        EventService::get_by_event_id(&db.pool, project.id, Uuid::parse_str(&event_id).unwrap())
+            .await
+            .unwrap();
+    let issue = IssueService::get_by_id(&db.pool, event.issue_id)
+        .await
+        .unwrap();
+    assert_eq!(issue.last_frame_module, "app::handlers");
+    assert_eq!(issue.last_frame_function, "handle_request");
+    assert_eq!(
+        event.data["exception"]["values"][0]["stacktrace"]["frames"][1]["in_app"],
+        false
+    );
+}
+
+// =============================================================================
+// Latest Event Tests
+// =============================================================================
+
+/// Id and digest order of the issue's events, latest first
+async fn events_latest_first(pool: &PgPool, issue_id: Uuid) -> Vec<(Uuid, i32)> {
+    sqlx::query_as(
+        "SELECT id, digest_order FROM events WHERE issue_id = $1 ORDER BY digest_order DESC",
+    )
+    .bind(issue_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn test_issue_points_at_latest_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Latest Event").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let mut issue_id = Uuid::nil();
+    for _ in 0..3 {
+        issue_id = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    }
+    let events = events_latest_first(&db.pool, issue_id).await;
+    assert_eq!(events.len(), 3);
+
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.last_event_id, Some(events[0].0));
+    let latest = EventService::latest(&db.pool, &issue)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.id, events[0].0);
+    assert_eq!(latest.digest_order, 3);
+
+    // A pointer left dangling by a manual delete falls back to sorting
+    sqlx::query("DELETE FROM events WHERE id = $1")
+        .bind(events[0].0)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let latest = EventService::latest(&db.pool, &issue)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.id, events[1].0);
+
+    // Recounting repairs it
+    ProjectService::recount(&db.pool, project.id).await.unwrap();
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.last_event_id, Some(events[1].0));
+
+    sqlx::query("DELETE FROM events WHERE issue_id = $1")
+        .bind(issue_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    ProjectService::recount(&db.pool, project.id).await.unwrap();
+    let issue = IssueService::get_by_id(&db.pool, issue_id).await.unwrap();
+    assert_eq!(issue.last_event_id, None);
+    assert!(EventService::latest(&db.pool, &issue)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+// =============================================================================
+// Regroup Tests
+// =============================================================================
+
+/// Changes the exception type stored on events, as if an older grouping
+/// algorithm had normalized it differently
+async fn retype_events(pool: &PgPool, event_ids: &[Uuid], error_type: &str) {
+    sqlx::query(
+        "UPDATE events SET data = jsonb_set(data, '{exception,values,0,type}', to_jsonb($2::text)) WHERE id = ANY($1)",
+    )
+    .bind(event_ids)
+    .bind(error_type)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+async fn event_ids(pool: &PgPool, issue_id: Uuid) -> Vec<Uuid> {
+    sqlx::query_scalar("SELECT id FROM events WHERE issue_id = $1 ORDER BY digest_order")
+        .bind(issue_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn test_regroup_merges_and_splits_issues() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Regroup Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let mut type_issue = Uuid::nil();
+    for _ in 0..3 {
+        type_issue = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    }
+    let mut key_issue = Uuid::nil();
+    let mut value_issue = Uuid::nil();
+    for _ in 0..2 {
+        key_issue = digest_error(&db.pool, project.id, temp_dir.path(), "KeyError").await;
+        value_issue = digest_error(&db.pool, project.id, temp_dir.path(), "ValueError").await;
+    }
+    let type_order = IssueService::get_by_id(&db.pool, type_issue)
+        .await
+        .unwrap()
+        .digest_order;
+
+    // The current algorithm groups the KeyErrors with the TypeErrors, and
+    // one of the ValueErrors apart
+    retype_events(&db.pool, &event_ids(&db.pool, key_issue).await, "TypeError").await;
+    let value_events = event_ids(&db.pool, value_issue).await;
+    retype_events(&db.pool, &value_events[1..], "IndexError").await;
+
+    let plan = RegroupService::plan(&db.pool, project.id).await.unwrap();
+    assert_eq!(plan.events, 7);
+    assert_eq!(plan.moved_events(), 3);
+    assert_eq!(plan.merged_issues(), 1);
+    assert_eq!(plan.split_issues(), 1);
+    assert_eq!(plan.new_issues, 1);
+
+    let merged = plan
+        .issues
+        .iter()
+        .find(|i| i.issue_id == key_issue)
+        .unwrap();
+    let into_type_issue = RegroupTarget::Issue {
+        id: type_issue,
+        digest_order: type_order,
+    };
+    assert_eq!(merged.merged_into, Some(into_type_issue.clone()));
+    assert_eq!(merged.moves.len(), 1);
+    assert_eq!(merged.moves[0].target, into_type_issue);
+    assert_eq!(merged.moves[0].events, 2);
+
+    let split = plan
+        .issues
+        .iter()
+        .find(|i| i.issue_id == value_issue)
+        .unwrap();
+    assert_eq!(split.merged_into, None);
+    assert_eq!(split.staying, 1);
+    assert_eq!(
+        split.moves[0].target,
+        RegroupTarget::New {
+            title: "IndexError: Cannot read property 'x' of null".to_string()
+        }
+    );
+
+    // A dry run changes nothing
+    assert_eq!(event_count(&db.pool, key_issue).await, 2);
+
+    let outcome = RegroupService::apply(&db.pool, project.id, 500)
+        .await
+        .unwrap();
+    assert_eq!(outcome.moved_events, 3);
+    assert_eq!(outcome.new_issues, 1);
+    assert_eq!(outcome.deleted_issues, 1);
+    assert!(!outcome.resumed);
+
+    let issue = IssueService::get_by_id(&db.pool, type_issue).await.unwrap();
+    assert_eq!(issue.digested_event_count, 5);
+    assert_eq!(issue.stored_event_count, 5);
+    assert_eq!(issue.last_event_order, 5);
+    assert_eq!(event_count(&db.pool, type_issue).await, 5);
+    assert!(IssueService::get_by_id(&db.pool, key_issue).await.is_err());
+
+    // The merged issue's grouping now leads to the issue it merged into
+    let grouping_issues: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT issue_id FROM groupings WHERE project_id = $1 AND grouping_key LIKE '%KeyError%'",
+    )
+    .bind(project.id)
+    .fetch_all(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!(grouping_issues, vec![type_issue]);
+
+    let issue = IssueService::get_by_id(&db.pool, value_issue)
+        .await
+        .unwrap();
+    assert_eq!(issue.digested_event_count, 1);
+    assert_eq!(event_count(&db.pool, value_issue).await, 1);
+
+    let new_issue: Uuid = sqlx::query_scalar("SELECT issue_id FROM events WHERE id = $1")
+        .bind(value_events[1])
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    let issue = IssueService::get_by_id(&db.pool, new_issue).await.unwrap();
+    assert_eq!(issue.calculated_type, "IndexError");
+    assert_eq!(issue.digested_event_count, 1);
+
+    // New events of the split-off key go to its issue, and nothing is left to move
+    let issue_id = digest_error(&db.pool, project.id, temp_dir.path(), "IndexError").await;
+    assert_eq!(issue_id, new_issue);
+    let plan = RegroupService::plan(&db.pool, project.id).await.unwrap();
+    assert_eq!(plan.moved_events(), 0);
+    assert_eq!(
+        RegroupService::checkpoint(&db.pool, project.id)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[actix_web::test]
+async fn test_regroup_continues_from_checkpoint() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Regroup Resume Project").await;
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    let type_issue = digest_type_error(&db.pool, project.id, temp_dir.path()).await;
+    let mut key_issue = Uuid::nil();
+    for _ in 0..2 {
+        key_issue = digest_error(&db.pool, project.id, temp_dir.path(), "KeyError").await;
+    }
+    let key_events = event_ids(&db.pool, key_issue).await;
+    retype_events(&db.pool, &key_events, "TypeError").await;
+
+    // A run interrupted after the first of the events in id order
+    let first = *key_events.iter().min().unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO regroup_checkpoints (project_id, last_event_id, moved_events, merges)
+        VALUES ($1, $2, 1, jsonb_build_object($3::text, $4::text))
+        "#,
+    )
+    .bind(project.id)
+    .bind(first)
+    .bind(key_issue.to_string())
+    .bind(type_issue.to_string())
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        RegroupService::checkpoint(&db.pool, project.id)
+            .await
+            .unwrap(),
+        Some(1)
+    );
+
+    let outcome = RegroupService::apply(&db.pool, project.id, 500)
+        .await
+        .unwrap();
+    assert!(outcome.resumed);
+    assert_eq!(outcome.moved_events, 2);
+
+    // Events up to the checkpoint are skipped, so the issue keeps one
+    assert_eq!(event_count(&db.pool, type_issue).await, 2);
+    assert_eq!(event_count(&db.pool, key_issue).await, 1);
+    assert_eq!(
+        sqlx::query_scalar::<_, Uuid>("SELECT issue_id FROM events WHERE id = $1")
+            .bind(first)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap(),
+        key_issue
+    );
+
+    // Issues that still have events aren't deleted
+    assert_eq!(outcome.deleted_issues, 0);
+    assert!(IssueService::get_by_id(&db.pool, key_issue).await.is_ok());
+    assert_eq!(
+        RegroupService::checkpoint(&db.pool, project.id)
+            .await
+            .unwrap(),
+        None
+    );
+}
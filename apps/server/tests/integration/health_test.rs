@@ -1,8 +1,12 @@
 //! Integration tests for Health endpoints
 //!
-//! Tests the liveness and readiness health check endpoints.
+//! Tests the liveness and readiness health check endpoints, and the version
+//! header.
 
 use actix_web::{test, web, App};
+use rustrak::build_info;
+use rustrak::db::DbHealth;
+use rustrak::middleware::version::{version_header, VERSION_HEADER};
 use rustrak::routes;
 use serde_json::Value;
 use sqlx::PgPool;
@@ -76,6 +80,10 @@ async fn test_liveness_returns_ok() {
 
     let body: Value = test::read_body_json(resp).await;
     assert_eq!(body["status"], "ok");
+    assert_eq!(body["version"], build_info::VERSION);
+    assert_eq!(body["commit"], build_info::COMMIT);
+    assert_eq!(body["built_at"], build_info::BUILT_AT);
+    assert!(body["uptime_seconds"].is_u64());
 }
 
 #[actix_web::test]
@@ -118,6 +126,29 @@ async fn test_liveness_does_not_require_auth() {
     assert!(resp.status().is_success());
 }
 
+#[actix_web::test]
+async fn test_responses_include_version_header() {
+    let db = TestDb::new().await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .wrap(version_header())
+            .service(web::scope("/health").route("", web::get().to(routes::health::liveness))),
+    )
+    .await;
+
+    for uri in ["/health", "/missing"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        let version = resp
+            .headers()
+            .get(VERSION_HEADER)
+            .expect("Missing version header");
+        assert_eq!(version.to_str().unwrap(), build_info::VERSION);
+    }
+}
+
 // =============================================================================
 // Readiness Endpoint Tests
 // =============================================================================
@@ -171,6 +202,57 @@ async fn test_readiness_returns_not_ready_with_closed_pool() {
     assert_eq!(body["checks"]["database"], "error");
 }
 
+#[actix_web::test]
+async fn test_readiness_records_outage_with_closed_pool() {
+    let db = TestDb::new().await;
+    db.pool.close().await;
+    let db_health = web::Data::new(DbHealth::new());
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(db_health.clone())
+            .service(
+                web::scope("/health").route("/ready", web::get().to(routes::health::readiness)),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/health/ready").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    assert!(db_health.retry_in().is_some());
+}
+
+#[actix_web::test]
+async fn test_readiness_fails_fast_during_outage() {
+    let db = TestDb::new().await;
+    let db_health = web::Data::new(DbHealth::new());
+    db_health.record_failure();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(db_health.clone())
+            .service(
+                web::scope("/health").route("/ready", web::get().to(routes::health::readiness)),
+            ),
+    )
+    .await;
+
+    // The database isn't queried again until the backoff has passed
+    let req = test::TestRequest::get().uri("/health/ready").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+
+    tokio::time::sleep(db_health.retry_in().unwrap()).await;
+
+    let req = test::TestRequest::get().uri("/health/ready").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(db_health.retry_in(), None);
+}
+
 #[actix_web::test]
 async fn test_readiness_does_not_require_auth() {
     let db = TestDb::new().await;
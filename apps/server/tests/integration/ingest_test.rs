@@ -3,16 +3,28 @@
 //! Tests event ingestion via the Sentry-compatible envelope endpoint.
 
 use actix_web::{test, web, App};
-use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
+use chrono::Utc;
+use futures_util::future::join_all;
+use rustrak::auth::ProjectCache;
+use rustrak::config::{
+    BodyLimitConfig, Config, DatabaseConfig, IngestConfig, IngestMaintenance, IngestMode,
+    RateLimitConfig,
+};
+use rustrak::db::DbHealth;
+use rustrak::ingest::{maintenance, EventMetadata, IngestWriter, SpoolJob};
+use rustrak::models::CreateProjectKey;
 use rustrak::routes;
-use rustrak::services::ProjectService;
+use rustrak::services::{LiveRates, ProjectKeyService, ProjectService};
 use serde_json::{json, Value};
 use sqlx::PgPool;
+use std::sync::Arc;
 use std::time::Duration;
 use testcontainers::{runners::AsyncRunner, ContainerAsync};
 use testcontainers_modules::postgres::Postgres;
 use uuid::Uuid;
 
+use crate::common::{envelope_with_oversized_header, MinidumpBuilder, MALFORMED_ENVELOPES};
+
 /// Test database container with connection pool
 struct TestDb {
     #[allow(dead_code)]
@@ -66,33 +78,57 @@ fn create_test_config() -> Config {
             acquire_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             max_lifetime: Duration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: Duration::from_millis(500),
+            statement_timeout: Duration::from_secs(30),
         },
         rate_limit: RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: Some("/tmp/rustrak_test_ingest".to_string()),
+        compress_stored_events: false,
+        ingest: IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
-/// Creates a test project and returns its sentry_key
+/// Creates a test project and returns its default sentry_key
 async fn create_test_project(pool: &PgPool, name: &str) -> (i32, String) {
     let project = ProjectService::create(
         pool,
         rustrak::models::CreateProject {
             name: name.to_string(),
             slug: None,
+            team_id: None,
         },
     )
     .await
     .expect("Failed to create test project");
-    (project.id, project.sentry_key.to_string())
+    let keys = ProjectKeyService::list(pool, project.id)
+        .await
+        .expect("Failed to list project keys");
+    (project.id, keys[0].key.to_string())
 }
 
 /// Creates a minimal valid Sentry envelope
@@ -122,7 +158,7 @@ async fn test_ingest_basic_event() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -161,267 +197,418 @@ async fn test_ingest_basic_event() {
 }
 
 #[actix_web::test]
-async fn test_ingest_with_query_param_auth() {
+async fn test_ingest_body_limit_is_separate_from_api_limit() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "Query Auth Project").await;
-    let config = create_test_config();
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Body Limit Project").await;
+    let mut config = create_test_config();
+    config.body_limits.api = 1024;
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
-            .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(rustrak::error::configure_extractors(
+                config.body_limits.clone(),
+            ))
+            .configure(routes::ingest::configure(config.body_limits.ingest))
+            .app_data(web::Data::new(config)),
     )
     .await;
 
+    // Larger than the API limit, well under the ingest one
     let event_id = Uuid::new_v4().to_string().replace("-", "");
     let event_json = json!({
         "event_id": event_id,
-        "level": "error"
+        "level": "error",
+        "message": "x".repeat(4096),
     })
     .to_string();
 
-    let envelope = create_envelope(&event_id, &event_json);
-
-    // Use query parameter for auth instead of header
     let req = test::TestRequest::post()
-        .uri(&format!(
-            "/api/{}/envelope/?sentry_key={}",
-            project_id, sentry_key
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
         ))
         .insert_header(("Content-Type", "application/x-sentry-envelope"))
-        .set_payload(envelope)
+        .set_payload(create_envelope(&event_id, &event_json))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
-    assert!(resp.status().is_success());
+    assert!(resp.status().is_success(), "{}", resp.status());
 }
 
-// =============================================================================
-// Authentication Error Tests
-// =============================================================================
-
 #[actix_web::test]
-async fn test_ingest_missing_auth() {
+async fn test_ingest_every_event_in_envelope() {
     let db = TestDb::new().await;
-    let (project_id, _) = create_test_project(&db.pool, "No Auth Project").await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Batched Project").await;
     let config = create_test_config();
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let event_id = Uuid::new_v4().to_string().replace("-", "");
-    let event_json = json!({"event_id": event_id}).to_string();
-    let envelope = create_envelope(&event_id, &event_json);
+    let first_id = Uuid::new_v4().simple().to_string();
+    let second_id = Uuid::new_v4().simple().to_string();
+    let event = |event_id: Option<&str>, error_type: &str| {
+        let mut event = json!({
+            "level": "error",
+            "platform": "python",
+            "exception": {"values": [{"type": error_type, "value": "Batched"}]}
+        });
+        if let Some(event_id) = event_id {
+            event["event_id"] = json!(event_id);
+        }
+        event.to_string()
+    };
+    let first = event(Some(&first_id), "FirstError");
+    let second = event(Some(&second_id), "SecondError");
+    // No event_id of its own and no length header
+    let third = event(None, "ThirdError");
+
+    let envelope = format!(
+        "{{\"event_id\":\"{}\"}}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n{{\"type\":\"event\"}}\n{}\n",
+        first_id,
+        first.len(),
+        first,
+        second.len(),
+        second,
+        third
+    );
 
     let req = test::TestRequest::post()
         .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
         .insert_header(("Content-Type", "application/x-sentry-envelope"))
         .set_payload(envelope)
         .to_request();
 
     let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 401);
+    assert!(resp.status().is_success());
+
+    // The response carries the first event's id
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["id"], first_id);
+
+    wait_for_event_count(&db.pool, project_id, 3).await;
+
+    let event_ids: Vec<Uuid> =
+        sqlx::query_scalar("SELECT event_id FROM events WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+    assert!(event_ids.contains(&Uuid::parse_str(&first_id).unwrap()));
+    assert!(event_ids.contains(&Uuid::parse_str(&second_id).unwrap()));
+
+    let mut types: Vec<String> = sqlx::query_scalar(
+        "SELECT calculated_type FROM issues WHERE project_id = $1 AND deleted_at IS NULL",
+    )
+    .bind(project_id)
+    .fetch_all(&db.pool)
+    .await
+    .unwrap();
+    types.sort();
+    assert_eq!(types, ["FirstError", "SecondError", "ThirdError"]);
 }
 
 #[actix_web::test]
-async fn test_ingest_invalid_sentry_key() {
+async fn test_ingest_same_event_twice_creates_one_issue() {
     let db = TestDb::new().await;
-    let (project_id, _) = create_test_project(&db.pool, "Invalid Key Project").await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Resent Project").await;
     let config = create_test_config();
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let event_id = Uuid::new_v4().to_string().replace("-", "");
-    let event_json = json!({"event_id": event_id}).to_string();
-    let envelope = create_envelope(&event_id, &event_json);
+    let event_id = Uuid::new_v4().simple().to_string();
+    let event_json = json!({
+        "event_id": event_id,
+        "level": "error",
+        "exception": {"values": [{"type": "ResentError", "value": "Sent twice"}]}
+    })
+    .to_string();
 
-    let req = test::TestRequest::post()
-        .uri(&format!("/api/{}/envelope/", project_id))
-        .insert_header((
-            "X-Sentry-Auth",
-            "Sentry sentry_key=00000000-0000-0000-0000-000000000000, sentry_version=7",
-        ))
-        .set_payload(envelope)
-        .to_request();
+    for _ in 0..2 {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/{}/envelope/", project_id))
+            .insert_header((
+                "X-Sentry-Auth",
+                format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+            ))
+            .insert_header(("Content-Type", "application/x-sentry-envelope"))
+            .set_payload(create_envelope(&event_id, &event_json))
+            .to_request();
+
+        // Duplicates get the usual response with the existing id
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["id"], event_id);
+    }
 
-    let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 401);
+    wait_for_event_count(&db.pool, project_id, 1).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    wait_for_event_count(&db.pool, project_id, 1).await;
+
+    let counts: Vec<i32> =
+        sqlx::query_scalar("SELECT digested_event_count FROM issues WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(counts, [1]);
+
+    let digested: bool = sqlx::query_scalar(
+        "SELECT digested_at IS NOT NULL FROM ingested_events WHERE project_id = $1 AND event_id = $2",
+    )
+    .bind(project_id)
+    .bind(Uuid::parse_str(&event_id).unwrap())
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    assert!(digested);
 }
 
 #[actix_web::test]
-async fn test_ingest_wrong_project_id() {
+async fn test_ingest_retry_after_failed_write_is_stored() {
     let db = TestDb::new().await;
-    let (_, sentry_key) = create_test_project(&db.pool, "Wrong ID Project").await;
-    let config = create_test_config();
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Failed Write Project").await;
+    // The ingest dir can't be created while a file sits in its place
+    let root = tempfile::TempDir::new().unwrap();
+    let ingest_dir = root.path().join("ingest");
+    std::fs::write(&ingest_dir, b"not a directory").unwrap();
+    let mut config = create_test_config();
+    config.ingest_dir = Some(ingest_dir.to_string_lossy().into_owned());
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let event_id = Uuid::new_v4().to_string().replace("-", "");
-    let event_json = json!({"event_id": event_id}).to_string();
-    let envelope = create_envelope(&event_id, &event_json);
-
-    // Use wrong project_id (99999) - project doesn't exist
-    let req = test::TestRequest::post()
-        .uri("/api/99999/envelope/")
-        .insert_header((
-            "X-Sentry-Auth",
-            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
-        ))
-        .set_payload(envelope)
-        .to_request();
+    let event_id = Uuid::new_v4().simple().to_string();
+    let event_json = json!({
+        "event_id": event_id,
+        "level": "error",
+        "exception": {"values": [{"type": "RetriedError", "value": "Sent after a failure"}]}
+    })
+    .to_string();
+    let request = || {
+        test::TestRequest::post()
+            .uri(&format!("/api/{}/envelope/", project_id))
+            .insert_header((
+                "X-Sentry-Auth",
+                format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+            ))
+            .insert_header(("Content-Type", "application/x-sentry-envelope"))
+            .set_payload(create_envelope(&event_id, &event_json))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, request()).await;
+    assert_eq!(resp.status(), 500);
+    let remembered: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM ingested_events WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(remembered, 0);
 
-    let resp = test::call_service(&app, req).await;
-    // Project doesn't exist, so auth fails - could be 401 or 404 depending on implementation
-    // Our auth checks project_id + sentry_key together, so it returns 404 when project not found
-    assert!(resp.status() == 401 || resp.status() == 404);
+    // The SDK retries once the disk is writable again
+    std::fs::remove_file(&ingest_dir).unwrap();
+    let resp = test::call_service(&app, request()).await;
+    assert!(resp.status().is_success());
+    wait_for_event_count(&db.pool, project_id, 1).await;
 }
 
-// =============================================================================
-// Envelope Validation Tests
-// =============================================================================
-
 #[actix_web::test]
-async fn test_ingest_missing_event_id() {
+async fn test_ingest_merges_resent_event_when_enabled() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "Missing Event ID").await;
-    let config = create_test_config();
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Merged Resend Project").await;
+    let mut config = create_test_config();
+    config.digest.merge_duplicate_events = true;
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    // Envelope without event_id in headers
-    let envelope = br#"{}
-{"type":"event","length":2}
-{}"#;
+    let event_id = Uuid::new_v4().simple().to_string();
+    let first = json!({
+        "event_id": event_id,
+        "level": "error",
+        "exception": {"values": [{"type": "ResentError", "value": "Sent twice"}]}
+    })
+    .to_string();
+    let update = json!({"event_id": event_id, "user": {"email": "jane@example.com"}}).to_string();
+
+    for event_json in [&first, &update] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/{}/envelope/", project_id))
+            .insert_header((
+                "X-Sentry-Auth",
+                format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+            ))
+            .insert_header(("Content-Type", "application/x-sentry-envelope"))
+            .set_payload(create_envelope(&event_id, event_json))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        wait_for_event_count(&db.pool, project_id, 1).await;
+    }
 
-    let req = test::TestRequest::post()
-        .uri(&format!("/api/{}/envelope/", project_id))
-        .insert_header((
-            "X-Sentry-Auth",
-            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
-        ))
-        .set_payload(envelope.to_vec())
-        .to_request();
+    let event_uuid = Uuid::parse_str(&event_id).unwrap();
+    let mut email = None;
+    for _ in 0..50 {
+        let data: Value = sqlx::query_scalar("SELECT data FROM events WHERE event_id = $1")
+            .bind(event_uuid)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        if let Some(value) = data["user"]["email"].as_str() {
+            assert_eq!(data["exception"]["values"][0]["type"], "ResentError");
+            email = Some(value.to_string());
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(email.as_deref(), Some("jane@example.com"));
 
-    let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 400);
+    let counts: Vec<i32> =
+        sqlx::query_scalar("SELECT digested_event_count FROM issues WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(counts, [1]);
 }
 
 #[actix_web::test]
-async fn test_ingest_invalid_event_id_format() {
+async fn test_ingest_drop_mode_discards_events() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "Invalid Event ID").await;
-    let config = create_test_config();
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Drop Mode Project").await;
+    let mut config = create_test_config();
+    config.ingest.maintenance = IngestMaintenance::Drop;
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    // Envelope with invalid event_id (not a UUID)
-    let envelope = br#"{"event_id":"not-a-uuid"}
-{"type":"event","length":2}
-{}"#;
-
+    let event_id = Uuid::new_v4().simple().to_string();
+    let dropped_before = maintenance::dropped_count();
     let req = test::TestRequest::post()
         .uri(&format!("/api/{}/envelope/", project_id))
         .insert_header((
             "X-Sentry-Auth",
             format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
         ))
-        .set_payload(envelope.to_vec())
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(create_envelope(
+            &event_id,
+            r#"{"message":"Dropped during maintenance"}"#,
+        ))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.status(), 200);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["id"], event_id);
+    assert!(maintenance::dropped_count() > dropped_before);
+
+    let ingested: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM ingested_events WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(ingested, 0);
 }
 
 #[actix_web::test]
-async fn test_ingest_invalid_json_payload() {
+async fn test_ingest_reject_mode_returns_503() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "Invalid JSON").await;
-    let config = create_test_config();
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Reject Mode Project").await;
+    let mut config = create_test_config();
+    config.ingest.maintenance = IngestMaintenance::Reject;
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let event_id = Uuid::new_v4().to_string().replace("-", "");
-    // Invalid JSON payload
-    let envelope = format!(
-        r#"{{"event_id":"{}"}}
-{{"type":"event","length":12}}
-not valid json"#,
-        event_id
-    );
-
+    let event_id = Uuid::new_v4().simple().to_string();
     let req = test::TestRequest::post()
         .uri(&format!("/api/{}/envelope/", project_id))
         .insert_header((
             "X-Sentry-Auth",
             format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
         ))
-        .set_payload(envelope.into_bytes())
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(create_envelope(
+            &event_id,
+            r#"{"message":"Rejected during maintenance"}"#,
+        ))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
-    assert_eq!(resp.status(), 400);
-}
+    assert_eq!(resp.status(), 503);
+    assert!(resp.headers().contains_key("retry-after"));
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "unavailable");
+    assert_eq!(body["error"]["retry_after"], 60);
 
-// =============================================================================
-// Special Cases Tests
-// =============================================================================
+    let ingested: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM ingested_events WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(ingested, 0);
+}
 
 #[actix_web::test]
-async fn test_ingest_envelope_without_event_item() {
+async fn test_ingest_rejects_envelope_with_invalid_later_event() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "No Event Item").await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Bad Batch Project").await;
     let config = create_test_config();
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let event_id = Uuid::new_v4().to_string().replace("-", "");
-    // Envelope with only session item, no event
+    let event_id = Uuid::new_v4().simple().to_string();
     let envelope = format!(
-        r#"{{"event_id":"{}"}}
-{{"type":"session","length":2}}
-{{}}"#,
+        "{{\"event_id\":\"{}\"}}\n{{\"type\":\"event\"}}\n{{}}\n{{\"type\":\"event\"}}\nnot json\n",
         event_id
     );
 
@@ -431,99 +618,1482 @@ async fn test_ingest_envelope_without_event_item() {
             "X-Sentry-Auth",
             format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
         ))
-        .set_payload(envelope.into_bytes())
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(envelope)
         .to_request();
 
     let resp = test::call_service(&app, req).await;
-    // Should still return 200, but with no event stored
-    assert!(resp.status().is_success());
+    assert_eq!(resp.status(), 400);
 
-    let body: Value = test::read_body_json(resp).await;
-    assert_eq!(body["id"], event_id);
+    // Nothing from the envelope was kept
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    wait_for_event_count(&db.pool, project_id, 0).await;
 }
 
 #[actix_web::test]
-async fn test_ingest_empty_body() {
+async fn test_ingest_reports_malformed_envelope_position() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "Empty Body").await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Malformed Project").await;
     let config = create_test_config();
 
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let req = test::TestRequest::post()
-        .uri(&format!("/api/{}/envelope/", project_id))
-        .insert_header((
-            "X-Sentry-Auth",
-            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
-        ))
-        .set_payload(Vec::new())
+    let (_, truncated) = MALFORMED_ENVELOPES
+        .iter()
+        .find(|(name, _)| *name == "truncated payload")
+        .unwrap();
+    let req = envelope_request(&format!("/api/{}/envelope/", project_id), &sentry_key)
+        .set_payload(truncated.to_vec())
         .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Unexpected EOF while reading item payload at byte 33"));
 
+    let req = envelope_request(&format!("/api/{}/envelope/", project_id), &sentry_key)
+        .set_payload(envelope_with_oversized_header())
+        .to_request();
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), 400);
-}
 
-// =============================================================================
-// CORS Tests
-// =============================================================================
+    // A BOM and CRLF line endings are accepted
+    let event_id = Uuid::new_v4().simple().to_string();
+    let envelope = format!(
+        "\u{feff}{{\"event_id\":\"{}\"}}\r\n{{\"type\":\"event\"}}\r\n{{\"level\":\"error\"}}\r\n",
+        event_id
+    );
+    let req = envelope_request(&format!("/api/{}/envelope/", project_id), &sentry_key)
+        .set_payload(envelope)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    wait_for_event_count(&db.pool, project_id, 1).await;
+}
 
 #[actix_web::test]
-async fn test_ingest_cors_preflight() {
+async fn test_ingest_with_query_param_auth() {
     let db = TestDb::new().await;
-    let (project_id, _) = create_test_project(&db.pool, "CORS Project").await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Query Auth Project").await;
     let config = create_test_config();
 
-    // CORS is handled by middleware, so we need to include it in the test
-    let cors = actix_cors::Cors::default()
-        .allow_any_origin()
-        .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
-        .allowed_headers(vec![
-            actix_web::http::header::AUTHORIZATION,
-            actix_web::http::header::ACCEPT,
-            actix_web::http::header::CONTENT_TYPE,
-            actix_web::http::header::CONTENT_ENCODING,
-            actix_web::http::header::HeaderName::from_static("x-sentry-auth"),
-        ])
-        .max_age(3600);
-
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .wrap(cors)
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
-    let req = test::TestRequest::default()
-        .method(actix_web::http::Method::OPTIONS)
-        .uri(&format!("/api/{}/envelope/", project_id))
-        .insert_header(("Origin", "https://example.com"))
-        .insert_header(("Access-Control-Request-Method", "POST"))
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = json!({
+        "event_id": event_id,
+        "level": "error"
+    })
+    .to_string();
+
+    let envelope = create_envelope(&event_id, &event_json);
+
+    // Use query parameter for auth instead of header
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/{}/envelope/?sentry_key={}",
+            project_id, sentry_key
+        ))
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(envelope)
         .to_request();
 
     let resp = test::call_service(&app, req).await;
     assert!(resp.status().is_success());
-
-    // Check CORS headers
-    let headers = resp.headers();
-    assert!(headers.contains_key("access-control-allow-origin"));
-    assert!(headers.contains_key("access-control-allow-methods"));
 }
 
 #[actix_web::test]
-async fn test_ingest_response_has_cors_headers() {
+async fn test_ingest_with_legacy_secret_and_authorization_header() {
     let db = TestDb::new().await;
-    let (project_id, sentry_key) = create_test_project(&db.pool, "CORS Response Project").await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Legacy Auth Project").await;
     let config = create_test_config();
 
-    // CORS is handled by middleware, so we need to include it in the test
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let send = |header: (&'static str, String)| {
+        let event_id = Uuid::new_v4().simple().to_string();
+        let event_json = json!({"event_id": event_id, "level": "error"}).to_string();
+        test::TestRequest::post()
+            .uri(&format!("/api/{}/envelope/", project_id))
+            .insert_header(header)
+            .insert_header(("Content-Type", "application/x-sentry-envelope"))
+            .set_payload(create_envelope(&event_id, &event_json))
+            .to_request()
+    };
+
+    // Old SDKs still send the deprecated secret; it's ignored
+    let resp = test::call_service(
+        &app,
+        send((
+            "X-Sentry-Auth",
+            format!(
+                "Sentry sentry_version=7, sentry_key={}, sentry_secret=0123456789abcdef",
+                sentry_key
+            ),
+        )),
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    // Proxies may forward the credentials as Authorization
+    let resp = test::call_service(
+        &app,
+        send((
+            "Authorization",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        )),
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    // A header and the query naming different keys is ambiguous
+    let event_id = Uuid::new_v4().simple().to_string();
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/{}/envelope/?sentry_key={}",
+            project_id,
+            Uuid::new_v4().simple()
+        ))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(create_envelope(
+            &event_id,
+            &json!({"event_id": event_id}).to_string(),
+        ))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+// =============================================================================
+// Authentication Error Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_ingest_missing_auth() {
+    let db = TestDb::new().await;
+    let (project_id, _) = create_test_project(&db.pool, "No Auth Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = json!({"event_id": event_id}).to_string();
+    let envelope = create_envelope(&event_id, &event_json);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(envelope)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+    assert_eq!(resp.headers().get("WWW-Authenticate").unwrap(), "Sentry");
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "unauthorized");
+}
+
+#[actix_web::test]
+async fn test_ingest_invalid_sentry_key() {
+    let db = TestDb::new().await;
+    let (project_id, _) = create_test_project(&db.pool, "Invalid Key Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = json!({"event_id": event_id}).to_string();
+    let envelope = create_envelope(&event_id, &event_json);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            "Sentry sentry_key=00000000-0000-0000-0000-000000000000, sentry_version=7",
+        ))
+        .set_payload(envelope)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_ingest_wrong_project_id() {
+    let db = TestDb::new().await;
+    let (_, sentry_key) = create_test_project(&db.pool, "Wrong ID Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = json!({"event_id": event_id}).to_string();
+    let envelope = create_envelope(&event_id, &event_json);
+
+    // Use wrong project_id (99999) - project doesn't exist
+    let req = test::TestRequest::post()
+        .uri("/api/99999/envelope/")
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    // Project doesn't exist, so auth fails - could be 401 or 404 depending on implementation
+    // Our auth checks project_id + sentry_key together, so it returns 404 when project not found
+    assert!(resp.status() == 401 || resp.status() == 404);
+}
+
+// =============================================================================
+// Project Key Tests
+// =============================================================================
+
+/// Builds an envelope request authenticated with `sentry_key`
+fn keyed_request(project_id: i32, sentry_key: &str) -> test::TestRequest {
+    envelope_request(&format!("/api/{}/envelope/", project_id), sentry_key)
+}
+
+/// Builds an envelope request to `uri` authenticated with `sentry_key`
+fn envelope_request(uri: &str, sentry_key: &str) -> test::TestRequest {
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = json!({"event_id": event_id, "level": "error"}).to_string();
+
+    test::TestRequest::post()
+        .uri(uri)
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(create_envelope(&event_id, &event_json))
+}
+
+#[actix_web::test]
+async fn test_ingest_accepts_any_active_key() {
+    let db = TestDb::new().await;
+    let (project_id, default_key) = create_test_project(&db.pool, "Multi Key Project").await;
+    let second_key = ProjectKeyService::create(
+        &db.pool,
+        project_id,
+        CreateProjectKey {
+            label: Some("Backend".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create key");
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = keyed_request(project_id, &default_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let req = keyed_request(project_id, &second_key.key.to_string()).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Keys only authenticate their own project
+    let (other_project_id, _) = create_test_project(&db.pool, "Other Key Project").await;
+    let req = keyed_request(other_project_id, &default_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_ingest_resolves_project_from_key() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Key Only Project").await;
+    let (other_project_id, _) = create_test_project(&db.pool, "Key Only Other").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    // No project id in the path
+    let req = envelope_request("/api/envelope/", &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Placeholder project id
+    let req = envelope_request("/api/0/envelope/", &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Both events land in the key's project
+    wait_for_event_count(&db.pool, project_id, 2).await;
+    wait_for_event_count(&db.pool, other_project_id, 0).await;
+}
+
+/// Envelope as an app backend forwards it for the SDK's `tunnel` option
+fn tunneled_envelope(dsn: &str) -> Vec<u8> {
+    let event_id = Uuid::new_v4().simple().to_string();
+    let event_json = json!({"event_id": event_id, "level": "error"}).to_string();
+    format!(
+        "{}\n{{\"type\":\"event\",\"length\":{}}}\n{}",
+        json!({"event_id": event_id, "dsn": dsn}),
+        event_json.len(),
+        event_json
+    )
+    .into_bytes()
+}
+
+#[actix_web::test]
+async fn test_ingest_tunneled_envelope() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Tunnel Project").await;
+    let (other_project_id, other_key) = create_test_project(&db.pool, "Tunnel Other").await;
+    let mut config = create_test_config();
+    config.ingest.tunnel_allowed_hosts = vec!["errors.example.com".to_string()];
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+    let tunnel = |dsn: String| {
+        test::TestRequest::post()
+            .uri("/api/tunnel")
+            .insert_header(("Host", "rustrak.example.com"))
+            .insert_header(("Content-Type", "text/plain;charset=UTF-8"))
+            .set_payload(tunneled_envelope(&dsn))
+            .to_request()
+    };
+
+    // One URL serves every project, picked by the DSN in the envelope
+    let dsn = format!("https://{}@rustrak.example.com/{}", sentry_key, project_id);
+    let resp = test::call_service(&app, tunnel(dsn)).await;
+    assert!(resp.status().is_success());
+    let dsn = format!(
+        "https://{}@errors.example.com:8443/{}",
+        other_key, other_project_id
+    );
+    let resp = test::call_service(&app, tunnel(dsn)).await;
+    assert!(resp.status().is_success());
+
+    wait_for_event_count(&db.pool, project_id, 1).await;
+    wait_for_event_count(&db.pool, other_project_id, 1).await;
+
+    // DSNs of other servers aren't relayed
+    let dsn = format!("https://{}@o1.ingest.sentry.io/{}", sentry_key, project_id);
+    let resp = test::call_service(&app, tunnel(dsn)).await;
+    assert_eq!(resp.status(), 403);
+
+    // The key must still belong to the DSN's project
+    let dsn = format!("https://{}@rustrak.example.com/{}", other_key, project_id);
+    let resp = test::call_service(&app, tunnel(dsn)).await;
+    assert_eq!(resp.status(), 401);
+
+    // Without a DSN there's no project to attribute the envelope to
+    let req = test::TestRequest::post()
+        .uri("/api/tunnel")
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(create_envelope(&Uuid::new_v4().simple().to_string(), "{}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    wait_for_event_count(&db.pool, project_id, 1).await;
+}
+
+#[actix_web::test]
+async fn test_ingest_key_only_rejects_unknown_key() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Key Only Unknown").await;
+    let key = ProjectKeyService::list(&db.pool, project_id).await.unwrap()[0].clone();
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let unknown = Uuid::new_v4().to_string();
+    let req = envelope_request("/api/envelope/", &unknown).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    // Deactivated keys don't resolve a project either
+    ProjectKeyService::set_active(&db.pool, project_id, key.id, false)
+        .await
+        .unwrap();
+    let req = envelope_request("/api/envelope/", &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_ingest_rejects_deactivated_key_immediately() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Deactivated Key").await;
+    let key = ProjectKeyService::list(&db.pool, project_id).await.unwrap()[0].clone();
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = keyed_request(project_id, &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    ProjectKeyService::set_active(&db.pool, project_id, key.id, false)
+        .await
+        .unwrap();
+    let req = keyed_request(project_id, &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    ProjectKeyService::set_active(&db.pool, project_id, key.id, true)
+        .await
+        .unwrap();
+    let req = keyed_request(project_id, &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    ProjectKeyService::delete(&db.pool, project_id, key.id)
+        .await
+        .unwrap();
+    let req = keyed_request(project_id, &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_ingest_auth_cache_invalidated_on_key_rotation() {
+    let db = TestDb::new().await;
+    let (project_id, old_key) = create_test_project(&db.pool, "Rotated Key").await;
+    let old = ProjectKeyService::list(&db.pool, project_id).await.unwrap()[0].clone();
+    let config = create_test_config();
+    let project_cache = web::Data::new(ProjectCache::with_ttl(Duration::from_secs(30)));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .app_data(web::Data::new(DbHealth::new()))
+            .app_data(project_cache.clone())
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST))
+            .configure(routes::metrics::configure),
+    )
+    .await;
+
+    let req = keyed_request(project_id, &old_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Within the TTL the key is accepted without asking the database
+    sqlx::query("UPDATE project_keys SET is_active = FALSE WHERE id = $1")
+        .bind(old.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let req = keyed_request(project_id, &old_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Rotate: add a new key and deactivate the old one, as the key routes do
+    let new_key = ProjectKeyService::create(
+        &db.pool,
+        project_id,
+        CreateProjectKey {
+            label: Some("Rotated".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    let old = ProjectKeyService::set_active(&db.pool, project_id, old.id, false)
+        .await
+        .unwrap();
+    project_cache.remove_key(project_id, &old.key);
+
+    let req = keyed_request(project_id, &old_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = keyed_request(project_id, &new_key.key.to_string()).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // The rejected old key is answered from the cache too
+    let req = keyed_request(project_id, &old_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let body = test::read_body(test::call_service(&app, req).await).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("# TYPE rustrak_ingest_auth_cache_hits_total counter"));
+    assert!(body.contains("rustrak_ingest_auth_cache_hits_total 2\n"));
+    assert!(body.contains("rustrak_ingest_auth_cache_misses_total 3\n"));
+}
+
+#[actix_web::test]
+async fn test_ingest_burst_shows_in_live_rate() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Live Rate").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST))
+            .configure(routes::metrics::configure),
+    )
+    .await;
+
+    let burst = 30;
+    for _ in 0..burst {
+        let req = keyed_request(project_id, &sentry_key).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    // Other tests ingest into a project with the same id of their own
+    // database, so the rates are at least those of the burst
+    let rate = LiveRates::global().project(project_id);
+    assert!(rate.accepted.last_10s >= burst as f64 / 10.0);
+    assert!(rate.accepted.last_60s >= burst as f64 / 60.0);
+    assert!(!rate.instance_id.is_empty());
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let body = test::read_body(test::call_service(&app, req).await).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("# TYPE rustrak_live_accepted_events_per_second gauge"));
+    assert!(body.contains(&format!(
+        "rustrak_live_accepted_events_per_second{{project_id=\"{}\",window=\"10s\"}}",
+        project_id
+    )));
+}
+
+#[actix_web::test]
+async fn test_ingest_updates_key_last_used() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Key Last Used").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = keyed_request(project_id, &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Updated in the background
+    let mut last_used_at = None;
+    for _ in 0..20 {
+        last_used_at = ProjectKeyService::list(&db.pool, project_id).await.unwrap()[0].last_used_at;
+        if last_used_at.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let last_used_at = last_used_at.expect("last_used_at was never set");
+
+    // Not updated again within the interval
+    let req = keyed_request(project_id, &sentry_key).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let key = ProjectKeyService::list(&db.pool, project_id).await.unwrap()[0].clone();
+    assert_eq!(key.last_used_at, Some(last_used_at));
+}
+
+// =============================================================================
+// Envelope Validation Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_ingest_missing_event_id() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Missing Event ID").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    // Envelope without event_id in headers
+    let envelope = br#"{}
+{"type":"event","length":2}
+{}"#;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope.to_vec())
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "validation.invalid");
+    assert!(body["error"].get("field").is_none());
+}
+
+#[actix_web::test]
+async fn test_ingest_invalid_event_id_format() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Invalid Event ID").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    // Envelope with invalid event_id (not a UUID)
+    let envelope = br#"{"event_id":"not-a-uuid"}
+{"type":"event","length":2}
+{}"#;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope.to_vec())
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_ingest_invalid_json_payload() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Invalid JSON").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    // Invalid JSON payload
+    let envelope = format!(
+        r#"{{"event_id":"{}"}}
+{{"type":"event","length":12}}
+not valid json"#,
+        event_id
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope.into_bytes())
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_ingest_msgpack_event() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "MessagePack Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let payload = rmp_serde::to_vec_named(&json!({
+        "event_id": event_id,
+        "level": "error",
+        "message": "sent as msgpack"
+    }))
+    .unwrap();
+    let mut envelope = format!(
+        "{{\"event_id\":\"{}\"}}\n{{\"type\":\"event\",\"length\":{},\"content_type\":\"application/x-msgpack\"}}\n",
+        event_id,
+        payload.len()
+    )
+    .into_bytes();
+    envelope.extend_from_slice(&payload);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Digested like a JSON event
+    let mut stored = None;
+    for _ in 0..40 {
+        stored = sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT data FROM events WHERE project_id = $1",
+        )
+        .bind(project_id)
+        .fetch_optional(&db.pool)
+        .await
+        .unwrap();
+        if stored.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let stored = stored.expect("Event was never digested");
+    assert_eq!(stored["message"], "sent as msgpack");
+}
+
+#[actix_web::test]
+async fn test_ingest_unsupported_content_type() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "CBOR Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let envelope = format!(
+        r#"{{"event_id":"{}"}}
+{{"type":"event","length":2,"content_type":"application/cbor"}}
+{{}}"#,
+        event_id
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope.into_bytes())
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Unsupported item content_type"));
+}
+
+#[actix_web::test]
+async fn test_ingest_rejects_unsupported_request_content_type() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "HTML Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = envelope_request(&format!("/api/{}/envelope/", project_id), &sentry_key)
+        .insert_header(("Content-Type", "text/html"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 415);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "unsupported_media_type");
+
+    // Browser SDKs post envelopes as plain text
+    let req = envelope_request(&format!("/api/{}/envelope/", project_id), &sentry_key)
+        .insert_header(("Content-Type", "text/plain;charset=UTF-8"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+}
+
+// =============================================================================
+// Special Cases Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_ingest_envelope_without_event_item() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "No Event Item").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    // Envelope with only session item, no event
+    let envelope = format!(
+        r#"{{"event_id":"{}"}}
+{{"type":"session","length":2}}
+{{}}"#,
+        event_id
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(envelope.into_bytes())
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    // Should still return 200, but with no event stored
+    assert!(resp.status().is_success());
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["id"], event_id);
+}
+
+#[actix_web::test]
+async fn test_ingest_empty_body() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Empty Body").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .set_payload(Vec::new())
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+// =============================================================================
+// Client IP Tests
+// =============================================================================
+
+/// Ingests an event from `peer` with an `X-Forwarded-For` header and returns
+/// the remote address stored with it once digested
+async fn ingest_with_forwarded_for(
+    trust_proxy_headers: bool,
+    peer: &str,
+    forwarded: &str,
+) -> String {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Client IP Project").await;
+    let mut config = create_test_config();
+    config.security.trust_proxy_headers = trust_proxy_headers;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let event_id = Uuid::new_v4().to_string().replace("-", "");
+    let event_json = json!({
+        "event_id": event_id,
+        "level": "error",
+        "message": "client ip"
+    })
+    .to_string();
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .peer_addr(peer.parse().unwrap())
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .insert_header(("X-Forwarded-For", forwarded))
+        .insert_header(("Content-Type", "application/x-sentry-envelope"))
+        .set_payload(create_envelope(&event_id, &event_json))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Digestion runs in the background
+    for _ in 0..50 {
+        let remote_addr: Option<String> =
+            sqlx::query_scalar("SELECT host(remote_addr) FROM events WHERE event_id = $1")
+                .bind(Uuid::parse_str(&event_id).unwrap())
+                .fetch_optional(&db.pool)
+                .await
+                .unwrap();
+        if let Some(remote_addr) = remote_addr {
+            return remote_addr;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Event was not digested in time");
+}
+
+#[actix_web::test]
+async fn test_ingest_ignores_forwarded_for_by_default() {
+    let remote_addr =
+        ingest_with_forwarded_for(false, "10.0.0.5:50000", "203.0.113.7, 10.0.0.1").await;
+
+    // A client could have set the header itself
+    assert_eq!(remote_addr, "10.0.0.5");
+}
+
+#[actix_web::test]
+async fn test_ingest_uses_forwarded_for_when_trusted() {
+    let remote_addr = ingest_with_forwarded_for(
+        true,
+        "10.0.0.5:50000",
+        "198.51.100.2, 203.0.113.7, 10.0.0.1",
+    )
+    .await;
+
+    // The rightmost address that isn't one of our proxies is the client;
+    // anything left of it was supplied by the client
+    assert_eq!(remote_addr, "203.0.113.7");
+}
+
+// =============================================================================
+// Allowed Domains Tests
+// =============================================================================
+
+/// Creates a project only accepting browser events from `*.example.com`
+async fn create_restricted_project(pool: &PgPool, name: &str) -> (i32, String) {
+    let (project_id, sentry_key) = create_test_project(pool, name).await;
+    sqlx::query("UPDATE projects SET allowed_domains = ARRAY['*.example.com'] WHERE id = $1")
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    (project_id, sentry_key)
+}
+
+/// Builds an envelope request for an event of `platform` sent from `origin`
+fn origin_request(
+    project_id: i32,
+    sentry_key: &str,
+    origin: Option<&str>,
+    platform: &str,
+    url: &str,
+) -> test::TestRequest {
+    let event_id = Uuid::new_v4().simple().to_string();
+    let event_json = json!({
+        "event_id": event_id,
+        "level": "error",
+        "platform": platform,
+        "request": {"url": url}
+    })
+    .to_string();
+
+    let mut req = test::TestRequest::post()
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header((
+            "X-Sentry-Auth",
+            format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+        ))
+        .insert_header(("Content-Type", "text/plain"))
+        .set_payload(create_envelope(&event_id, &event_json));
+    if let Some(origin) = origin {
+        req = req.insert_header(("Origin", origin));
+    }
+    req
+}
+
+#[actix_web::test]
+async fn test_ingest_accepts_allowed_origin() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_restricted_project(&db.pool, "Allowed Origin").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = origin_request(
+        project_id,
+        &sentry_key,
+        Some("https://app.example.com"),
+        "javascript",
+        "https://app.example.com/checkout",
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    wait_for_event_count(&db.pool, project_id, 1).await;
+}
+
+#[actix_web::test]
+async fn test_ingest_drops_event_from_other_origin() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_restricted_project(&db.pool, "Other Origin").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    // The SDK isn't told, so it doesn't retry
+    let req = origin_request(
+        project_id,
+        &sentry_key,
+        Some("https://evil.io"),
+        "javascript",
+        "https://evil.io/",
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // A browser event without an Origin header is checked by its URL
+    let req = origin_request(
+        project_id,
+        &sentry_key,
+        None,
+        "javascript",
+        "https://evil.io/",
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let ingested: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM ingested_events WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(ingested, 0);
+    wait_for_event_count(&db.pool, project_id, 0).await;
+}
+
+#[actix_web::test]
+async fn test_ingest_rejects_other_origin_when_strict() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_restricted_project(&db.pool, "Strict Origin").await;
+    let mut config = create_test_config();
+    config.ingest.allowed_domains_strict = true;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = origin_request(
+        project_id,
+        &sentry_key,
+        Some("https://evil.io"),
+        "javascript",
+        "https://evil.io/",
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+}
+
+#[actix_web::test]
+async fn test_ingest_server_side_event_bypasses_allowed_domains() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_restricted_project(&db.pool, "Server Origin").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    // A backend's request.url is its own host, not a browser origin
+    let req = origin_request(
+        project_id,
+        &sentry_key,
+        None,
+        "python",
+        "http://internal.local/api/orders",
+    )
+    .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    wait_for_event_count(&db.pool, project_id, 1).await;
+}
+
+// =============================================================================
+// Fast-Ack Ingest Tests
+// =============================================================================
+
+fn fast_ack_config(ingest_dir: &tempfile::TempDir, queue_size: usize) -> Config {
+    let mut config = create_test_config();
+    config.ingest_dir = Some(ingest_dir.path().to_string_lossy().into_owned());
+    config.ingest = IngestConfig {
+        mode: IngestMode::FastAck,
+        queue_size,
+        ..IngestConfig::default()
+    };
+    config
+}
+
+/// Waits until `expected` events of the project are digested
+async fn wait_for_event_count(pool: &PgPool, project_id: i32, expected: i64) {
+    let mut count = 0;
+    for _ in 0..100 {
+        count = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE project_id = $1")
+            .bind(project_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        if count == expected {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Expected {} digested events, found {}", expected, count);
+}
+
+#[actix_web::test]
+async fn test_fast_ack_saturated_queue_loses_no_events() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Fast Ack Project").await;
+    let ingest_dir = tempfile::TempDir::new().unwrap();
+    let config = fast_ack_config(&ingest_dir, 1);
+    let writer = web::Data::new(IngestWriter::start(
+        db.pool.clone(),
+        config.clone(),
+        Arc::new(DbHealth::new()),
+    ));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .app_data(writer.clone())
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    // Far more concurrent events than the queue holds
+    let requests = (0..20).map(|i| {
+        let event_id = Uuid::new_v4().to_string().replace("-", "");
+        let event_json = json!({
+            "event_id": event_id,
+            "level": "error",
+            "message": format!("fast ack {}", i)
+        })
+        .to_string();
+        test::TestRequest::post()
+            .uri(&format!("/api/{}/envelope/", project_id))
+            .insert_header((
+                "X-Sentry-Auth",
+                format!("Sentry sentry_key={}, sentry_version=7", sentry_key),
+            ))
+            .insert_header(("Content-Type", "application/x-sentry-envelope"))
+            .set_payload(create_envelope(&event_id, &event_json))
+            .to_request()
+    });
+    let responses = join_all(requests.map(|req| test::call_service(&app, req))).await;
+    assert!(responses.iter().all(|resp| resp.status().is_success()));
+
+    wait_for_event_count(&db.pool, project_id, 20).await;
+}
+
+#[actix_web::test]
+async fn test_fast_ack_shutdown_drains_queue() {
+    let db = TestDb::new().await;
+    let (project_id, _) = create_test_project(&db.pool, "Drain Project").await;
+    let ingest_dir = tempfile::TempDir::new().unwrap();
+    let writer = IngestWriter::start(
+        db.pool.clone(),
+        fast_ack_config(&ingest_dir, 10),
+        Arc::new(DbHealth::new()),
+    );
+
+    for i in 0..5 {
+        let event_id = Uuid::new_v4().to_string().replace("-", "");
+        let payload = json!({
+            "event_id": event_id,
+            "level": "error",
+            "message": format!("queued {}", i)
+        });
+        let job = SpoolJob {
+            metadata: EventMetadata {
+                event_id,
+                project_id,
+                ingested_at: Utc::now(),
+                remote_addr: None,
+                spool_id: None,
+            },
+            payload: serde_json::to_vec(&payload).unwrap(),
+        };
+        assert!(writer.try_enqueue(job).is_ok());
+    }
+
+    writer.shutdown().await;
+    assert_eq!(writer.queue_depth(), 0);
+
+    // Everything queued was written and handed to the digest
+    wait_for_event_count(&db.pool, project_id, 5).await;
+
+    // A stopped writer hands events back for a synchronous write
+    let job = SpoolJob {
+        metadata: EventMetadata {
+            event_id: Uuid::new_v4().simple().to_string(),
+            project_id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        },
+        payload: b"{}".to_vec(),
+    };
+    assert!(writer.try_enqueue(job).is_err());
+}
+
+#[actix_web::test]
+async fn test_fast_ack_retries_failed_writes() {
+    let db = TestDb::new().await;
+    let (project_id, _) = create_test_project(&db.pool, "Retry Project").await;
+    let ingest_dir = tempfile::TempDir::new().unwrap();
+
+    // A file where the spool directory should be fails every write
+    let spool_dir = ingest_dir.path().join("spool");
+    std::fs::write(&spool_dir, b"").unwrap();
+    let mut config = fast_ack_config(&ingest_dir, 10);
+    config.ingest_dir = Some(spool_dir.to_string_lossy().into_owned());
+
+    let writer = IngestWriter::start(db.pool.clone(), config, Arc::new(DbHealth::new()));
+
+    let event_id = Uuid::new_v4().simple().to_string();
+    let payload = json!({
+        "event_id": event_id,
+        "level": "error",
+        "message": "written on retry"
+    });
+    let job = SpoolJob {
+        metadata: EventMetadata {
+            event_id,
+            project_id,
+            ingested_at: Utc::now(),
+            remote_addr: None,
+            spool_id: None,
+        },
+        payload: serde_json::to_vec(&payload).unwrap(),
+    };
+    assert!(writer.try_enqueue(job).is_ok());
+
+    // The acknowledged event is kept until the disk recovers
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    std::fs::remove_file(&spool_dir).unwrap();
+
+    wait_for_event_count(&db.pool, project_id, 1).await;
+    writer.shutdown().await;
+}
+
+#[actix_web::test]
+async fn test_metrics_reports_ingest_queue() {
+    let db = TestDb::new().await;
+    let ingest_dir = tempfile::TempDir::new().unwrap();
+    let writer = web::Data::new(IngestWriter::start(
+        db.pool.clone(),
+        fast_ack_config(&ingest_dir, 7),
+        Arc::new(DbHealth::new()),
+    ));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(writer)
+            .configure(routes::metrics::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains("# TYPE rustrak_ingest_queue_depth gauge"));
+    assert!(body.contains("rustrak_ingest_queue_depth 0\n"));
+    assert!(body.contains("rustrak_ingest_queue_capacity 7\n"));
+    assert!(body.contains("rustrak_ingest_fsync_policy{policy=\"always\"} 1\n"));
+    assert!(body.contains("rustrak_ingest_fsync_pending 0\n"));
+    assert!(body.contains("# TYPE rustrak_digest_backlog gauge"));
+    assert!(body.contains("rustrak_digest_concurrency 10\n"));
+}
+
+// =============================================================================
+// CORS Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_ingest_cors_preflight() {
+    let db = TestDb::new().await;
+    let (project_id, _) = create_test_project(&db.pool, "CORS Project").await;
+    let config = create_test_config();
+
+    // CORS is handled by middleware, so we need to include it in the test
+    let cors = actix_cors::Cors::default()
+        .allow_any_origin()
+        .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
+        .allowed_headers(vec![
+            actix_web::http::header::AUTHORIZATION,
+            actix_web::http::header::ACCEPT,
+            actix_web::http::header::CONTENT_TYPE,
+            actix_web::http::header::CONTENT_ENCODING,
+            actix_web::http::header::HeaderName::from_static("x-sentry-auth"),
+        ])
+        .max_age(3600);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .wrap(cors)
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri(&format!("/api/{}/envelope/", project_id))
+        .insert_header(("Origin", "https://example.com"))
+        .insert_header(("Access-Control-Request-Method", "POST"))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // Check CORS headers
+    let headers = resp.headers();
+    assert!(headers.contains_key("access-control-allow-origin"));
+    assert!(headers.contains_key("access-control-allow-methods"));
+}
+
+#[actix_web::test]
+async fn test_ingest_response_has_cors_headers() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "CORS Response Project").await;
+    let config = create_test_config();
+
+    // CORS is handled by middleware, so we need to include it in the test
     let cors = actix_cors::Cors::default()
         .allow_any_origin()
         .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
@@ -541,7 +2111,7 @@ async fn test_ingest_response_has_cors_headers() {
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
             .wrap(cors)
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -570,6 +2140,165 @@ async fn test_ingest_response_has_cors_headers() {
     );
 }
 
+// =============================================================================
+// Minidump Endpoint Tests
+// =============================================================================
+
+const MULTIPART_BOUNDARY: &str = "rustrak-test-boundary";
+
+/// Builds a multipart/form-data body from (name, filename, data) parts
+fn create_multipart(parts: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, filename, data) in parts {
+        body.extend_from_slice(format!("--{}\r\n", MULTIPART_BOUNDARY).as_bytes());
+        let disposition = match filename {
+            Some(f) => format!("form-data; name=\"{}\"; filename=\"{}\"", name, f),
+            None => format!("form-data; name=\"{}\"", name),
+        };
+        body.extend_from_slice(format!("Content-Disposition: {}\r\n\r\n", disposition).as_bytes());
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    body
+}
+
+/// Waits for the background digest to create an issue
+async fn wait_for_issue(pool: &PgPool, project_id: i32) -> rustrak::models::Issue {
+    for _ in 0..50 {
+        let issue = sqlx::query_as::<_, rustrak::models::Issue>(
+            "SELECT * FROM issues WHERE project_id = $1",
+        )
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap();
+        if let Some(issue) = issue {
+            return issue;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("No issue was created for project {}", project_id);
+}
+
+#[actix_web::test]
+async fn test_ingest_minidump_creates_native_issue() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Native Project").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let minidump = MinidumpBuilder::new()
+        .with_exception(11, 0x5000_1234) // SIGSEGV
+        .with_module("/usr/bin/app", 0x4000_0000, 0x1000)
+        .with_module("/usr/lib/libcrash.so", 0x5000_0000, 0x2000)
+        .build();
+    let sentry = json!({ "release": "app@1.0.0" }).to_string();
+    let body = create_multipart(&[
+        ("upload_file_minidump", Some("crash.dmp"), &minidump),
+        ("sentry", None, sentry.as_bytes()),
+    ]);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/{}/minidump/?sentry_key={}",
+            project_id, sentry_key
+        ))
+        .insert_header((
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        ))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: Value = test::read_body_json(resp).await;
+    let event_id = body["id"].as_str().expect("id in response");
+    assert!(Uuid::parse_str(event_id).is_ok());
+
+    let issue = wait_for_issue(&db.pool, project_id).await;
+    assert_eq!(issue.platform.as_deref(), Some("native"));
+    assert_eq!(issue.level.as_deref(), Some("fatal"));
+    assert_eq!(issue.calculated_type, "SIGSEGV");
+    assert_eq!(
+        issue.calculated_value,
+        "Fatal Error: SIGSEGV in libcrash.so"
+    );
+}
+
+#[actix_web::test]
+async fn test_ingest_minidump_missing_file() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Native Missing").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let body = create_multipart(&[("sentry", None, b"{}")]);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/{}/minidump/?sentry_key={}",
+            project_id, sentry_key
+        ))
+        .insert_header((
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        ))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_web::test]
+async fn test_ingest_minidump_invalid_file() {
+    let db = TestDb::new().await;
+    let (project_id, sentry_key) = create_test_project(&db.pool, "Native Invalid").await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
+    )
+    .await;
+
+    let body = create_multipart(&[("upload_file_minidump", Some("crash.dmp"), b"not a minidump")]);
+
+    let req = test::TestRequest::post()
+        .uri(&format!(
+            "/api/{}/minidump/?sentry_key={}",
+            project_id, sentry_key
+        ))
+        .insert_header((
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+        ))
+        .set_payload(body)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+}
+
 // =============================================================================
 // Legacy Store Endpoint Tests
 // =============================================================================
@@ -584,7 +2313,7 @@ async fn test_store_endpoint_deprecated() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -3,12 +3,23 @@
 //! Tests the complete Issues API with a real PostgreSQL database.
 
 use actix_web::{test, web, App};
-use chrono::Utc;
+use chrono::{DurationRound, Utc};
 use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
-use rustrak::models::CreateProject;
+use rustrak::digest::{ContextDimension, EventUser};
+use rustrak::models::{
+    Actor, AddProjectMember, CreateProject, CreateUserRequest, IssuePriority, ProjectRole,
+    ResolveIssue, UpdateProject,
+};
+use rustrak::pagination::{
+    IssueFilter, IssueGroupBy, IssueSort, ListIssuesQuery, SortOrder, MAX_SPARKLINE_PAGE_SIZE,
+};
 use rustrak::routes;
-use rustrak::services::grouping::DenormalizedFields;
-use rustrak::services::{AuthTokenService, IssueService, ProjectService};
+use rustrak::services::grouping::{hash_grouping_key, DenormalizedFields};
+use rustrak::services::{
+    AuditLogService, AuthTokenService, EventService, IssueChange, IssueContextService,
+    IssueService, IssueStream, IssueStreamEvent, IssueUserService, ProjectMemberService,
+    ProjectService, UsersService,
+};
 use serde_json::{json, Value};
 use sqlx::PgPool;
 use std::time::Duration as StdDuration;
@@ -16,6 +27,9 @@ use testcontainers::{runners::AsyncRunner, ContainerAsync};
 use testcontainers_modules::postgres::Postgres;
 use uuid::Uuid;
 
+use crate::common::session::{configure_login, login_request, session_cookie, session_middleware};
+use crate::common::statements::StatementCounter;
+
 /// Test database container with connection pool
 struct TestDb {
     #[allow(dead_code)]
@@ -67,18 +81,38 @@ fn create_test_config() -> Config {
             acquire_timeout: StdDuration::from_secs(5),
             idle_timeout: StdDuration::from_secs(60),
             max_lifetime: StdDuration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: StdDuration::from_millis(500),
+            statement_timeout: StdDuration::from_secs(30),
         },
         rate_limit: RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: None,
+        compress_stored_events: false,
+        ingest: rustrak::config::IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -100,6 +134,7 @@ async fn create_test_project(pool: &PgPool, name: &str) -> rustrak::models::Proj
         CreateProject {
             name: name.to_string(),
             slug: None,
+            team_id: None,
         },
     )
     .await
@@ -118,6 +153,8 @@ fn create_denormalized_fields(
         last_frame_filename: "test.rs".to_string(),
         last_frame_module: "test_module".to_string(),
         last_frame_function: "test_function".to_string(),
+        exception_chain_length: 1,
+        is_unhandled: false,
     }
 }
 
@@ -271,7 +308,9 @@ async fn test_list_issues_filters_resolved_by_default() {
     create_test_issue(&db.pool, project.id, "ValueError", "Error 2").await;
 
     // Resolve one issue
-    IssueService::resolve(&db.pool, issue1.id).await.unwrap();
+    IssueService::resolve(&db.pool, issue1.id, Actor::System)
+        .await
+        .unwrap();
 
     let app = test::init_service(
         App::new()
@@ -307,7 +346,9 @@ async fn test_list_issues_include_resolved() {
     let issue1 = create_test_issue(&db.pool, project.id, "TypeError", "Error 1").await;
     create_test_issue(&db.pool, project.id, "ValueError", "Error 2").await;
 
-    IssueService::resolve(&db.pool, issue1.id).await.unwrap();
+    IssueService::resolve(&db.pool, issue1.id, Actor::System)
+        .await
+        .unwrap();
 
     let app = test::init_service(
         App::new()
@@ -374,6 +415,121 @@ async fn test_list_issues_sort_by_last_seen() {
     assert!(issues[0]["title"].as_str().unwrap().contains("Second"));
 }
 
+#[actix_web::test]
+async fn test_list_issues_include_sparkline() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Sparkline Project").await;
+    create_test_issue(&db.pool, project.id, "TypeError", "First").await;
+    create_test_issue(&db.pool, project.id, "ValueError", "Second").await;
+    let admin = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "admin@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        true,
+    )
+    .await
+    .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::issues::configure),
+    )
+    .await;
+    let resp = test::call_service(&app, login_request(admin.id).to_request()).await;
+    let cookie = session_cookie(&resp);
+    let issues_uri = format!("/api/projects/{}/issues", project.id);
+
+    // Counts the statements of one list request and returns its body
+    let list = |query: &str| {
+        let req = test::TestRequest::get()
+            .uri(&format!("{}?{}", issues_uri, query))
+            .cookie(cookie.clone())
+            .to_request();
+        let counter = StatementCounter::default();
+        let app = &app;
+        async move {
+            let resp = {
+                let _guard = tracing::subscriber::set_default(counter.clone());
+                test::call_service(app, req).await
+            };
+            assert_eq!(resp.status(), 200);
+            let body: Value = test::read_body_json(resp).await;
+            (counter.count(), body)
+        }
+    };
+
+    let (without, body) = list("").await;
+    assert!(body["items"][0].get("sparkline").is_none());
+
+    // One more query for the whole page, not one per issue
+    let (with, body) = list("include=sparkline").await;
+    assert_eq!(with, without + 1);
+    let items = body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    for item in items {
+        assert_eq!(item["sparkline"].as_array().unwrap().len(), 24);
+    }
+}
+
+#[actix_web::test]
+async fn test_list_issues_sparkline_page_size_limit() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Sparkline Limit Project").await;
+    let admin = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "admin@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        true,
+    )
+    .await
+    .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::issues::configure),
+    )
+    .await;
+    let resp = test::call_service(&app, login_request(admin.id).to_request()).await;
+    let cookie = session_cookie(&resp);
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/projects/{}/issues?include=sparkline&per_page={}",
+            project.id,
+            MAX_SPARKLINE_PAGE_SIZE + 1
+        ))
+        .cookie(cookie.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["field"], "per_page");
+
+    // The same page size is fine without sparklines
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/projects/{}/issues?per_page={}",
+            project.id,
+            MAX_SPARKLINE_PAGE_SIZE + 1
+        ))
+        .cookie(cookie)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+}
+
 // =============================================================================
 // Get Issue Tests
 // =============================================================================
@@ -474,6 +630,62 @@ async fn test_get_issue_wrong_project() {
     assert_eq!(resp.status(), 404);
 }
 
+// =============================================================================
+// Issue Contexts Tests
+// =============================================================================
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_get_issue_contexts() {
+    let db = TestDb::new().await;
+    let token = create_test_token(&db.pool).await;
+    let project = create_test_project(&db.pool, "Contexts Project").await;
+    let config = create_test_config();
+
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+    for browser in [
+        "Chrome 120.0",
+        "Chrome 120.0",
+        "Chrome 120.0",
+        "Firefox 118.0",
+    ] {
+        IssueContextService::record(
+            &db.pool,
+            issue.id,
+            &[(ContextDimension::Browser, browser.to_string())],
+        )
+        .await
+        .expect("Failed to record contexts");
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::issues::configure)
+            .configure(routes::projects::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/projects/{}/issues/{}/contexts",
+            project.id, issue.id
+        ))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["browser"]["total"], 4);
+    assert_eq!(body["browser"]["values"][0]["value"], "Chrome 120.0");
+    assert_eq!(body["browser"]["values"][0]["percentage"], 75.0);
+    assert_eq!(body["browser"]["values"][1]["percentage"], 25.0);
+    assert_eq!(body["os"]["values"], json!([]));
+}
+
 // =============================================================================
 // Update Issue Tests
 // =============================================================================
@@ -521,7 +733,9 @@ async fn test_unresolve_issue() {
     let config = create_test_config();
 
     let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
-    IssueService::resolve(&db.pool, issue.id).await.unwrap();
+    IssueService::resolve(&db.pool, issue.id, Actor::System)
+        .await
+        .unwrap();
 
     let app = test::init_service(
         App::new()
@@ -642,6 +856,37 @@ async fn test_update_issue_empty_body() {
     assert!(resp.status().is_success());
 }
 
+#[actix_web::test]
+async fn test_update_issue_rejects_oversized_json() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Oversized Project").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+    let mut config = create_test_config();
+    config.body_limits.api = 1024;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .configure(rustrak::error::configure_extractors(
+                config.body_limits.clone(),
+            ))
+            .app_data(web::Data::new(config))
+            .configure(routes::issues::configure),
+    )
+    .await;
+
+    // The body is refused before authentication is even looked at
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/projects/{}/issues/{}", project.id, issue.id))
+        .set_json(json!({"is_resolved": true, "padding": "x".repeat(2048)}))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 413);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "payload_too_large");
+}
+
 // =============================================================================
 // Delete Issue Tests
 // =============================================================================
@@ -821,6 +1066,129 @@ async fn test_list_issues_invalid_cursor() {
     assert_eq!(resp.status(), 400);
 }
 
+#[actix_web::test]
+async fn test_list_issues_default_sort_from_project() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Default Sort Project").await;
+    assert_eq!(project.default_issue_sort, IssueSort::DigestOrder);
+
+    let older = create_test_issue(&db.pool, project.id, "TypeError", "First").await;
+    let newer = create_test_issue(&db.pool, project.id, "ValueError", "Second").await;
+    // The first issue saw the latest event
+    sqlx::query("UPDATE issues SET last_seen = NOW() + INTERVAL '1 hour' WHERE id = $1")
+        .bind(older.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let project = ProjectService::update(
+        &db.pool,
+        project.id,
+        UpdateProject {
+            name: None,
+            team_id: None,
+            allowed_domains: None,
+            confirm_wildcard: false,
+            auto_resolve_days: None,
+            in_app_include: None,
+            in_app_exclude: None,
+            default_issue_sort: Some(IssueSort::LastSeen),
+            timezone: None,
+        },
+    )
+    .await
+    .unwrap();
+    let response = serde_json::to_value(project.to_response(&[], None)).unwrap();
+    assert_eq!(response["default_issue_sort"], "last_seen");
+
+    let first_issue = |query: &str| {
+        let query = web::Query::<ListIssuesQuery>::from_query(query)
+            .unwrap()
+            .into_inner();
+        let pool = db.pool.clone();
+        let sort = query.sort(project.default_issue_sort);
+        async move {
+            let (issues, _) = IssueService::list_offset(
+                &pool,
+                project.id,
+                sort,
+                SortOrder::Desc,
+                IssueFilter::Open,
+                None,
+                None,
+                None,
+                1,
+                10,
+            )
+            .await
+            .unwrap();
+            issues[0].id
+        }
+    };
+
+    // Without `sort` the project's default applies, an explicit one wins
+    assert_eq!(first_issue("").await, older.id);
+    assert_eq!(first_issue("sort=digest_order").await, newer.id);
+}
+
+#[actix_web::test]
+async fn test_list_issues_by_priority() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Priority Project").await;
+
+    let low = create_test_issue(&db.pool, project.id, "TypeError", "Low").await;
+    let medium = create_test_issue(&db.pool, project.id, "ValueError", "Medium").await;
+    let high = create_test_issue(&db.pool, project.id, "KeyError", "High").await;
+    assert_eq!(medium.priority, IssuePriority::Medium);
+    for (issue, priority) in [(&low, IssuePriority::Low), (&high, IssuePriority::High)] {
+        IssueService::set_priority(&db.pool, issue.id, priority, Actor::System)
+            .await
+            .unwrap();
+    }
+
+    let list = |priority, order| {
+        IssueService::list_offset(
+            &db.pool,
+            project.id,
+            IssueSort::Priority,
+            order,
+            IssueFilter::Open,
+            None,
+            None,
+            priority,
+            1,
+            10,
+        )
+    };
+    let ids = |issues: Vec<rustrak::models::Issue>| issues.iter().map(|i| i.id).collect::<Vec<_>>();
+
+    let (issues, total) = list(None, SortOrder::Desc).await.unwrap();
+    assert_eq!(total, 3);
+    assert_eq!(ids(issues), [high.id, medium.id, low.id]);
+    let (issues, _) = list(None, SortOrder::Asc).await.unwrap();
+    assert_eq!(ids(issues), [low.id, medium.id, high.id]);
+
+    let (issues, total) = list(Some(IssuePriority::High), SortOrder::Desc)
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(ids(issues), [high.id]);
+
+    // Setting the same priority again isn't a change
+    IssueService::set_priority(&db.pool, high.id, IssuePriority::High, Actor::System)
+        .await
+        .unwrap();
+    let history = IssueService::priority_history(&db.pool, high.id)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].old_priority, IssuePriority::Medium);
+
+    let response = serde_json::to_value(high.to_response("prio", 100)).unwrap();
+    assert_eq!(response["priority"], "medium");
+    assert!(response.get("priority_history").is_none());
+}
+
 // =============================================================================
 // Response Format Tests
 // =============================================================================
@@ -872,9 +1240,808 @@ async fn test_issue_response_format() {
     assert!(body.get("platform").is_some());
     assert!(body.get("is_resolved").is_some());
     assert!(body.get("is_muted").is_some());
+    assert!(body.get("resolved_at").is_some());
+    assert!(body.get("resolved_by").is_some());
+    assert!(body.get("culprit").is_some());
 
     // Verify short_id format (PROJECT-N)
     let short_id = body["short_id"].as_str().unwrap();
     assert!(short_id.starts_with(&project.slug.to_uppercase()));
     assert!(short_id.contains("-"));
 }
+
+// =============================================================================
+// Resolution Audit Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_resolve_records_user_and_time() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Resolve Audit Project").await;
+    let user = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "resolver@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        false,
+    )
+    .await
+    .unwrap();
+
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+    assert!(issue.resolved_at.is_none());
+    assert!(issue.resolved_by.is_none());
+
+    let before = Utc::now();
+    let resolved = IssueService::resolve(&db.pool, issue.id, Actor::User(user.id))
+        .await
+        .unwrap();
+    assert!(resolved.is_resolved);
+    assert!(resolved.resolved_at.unwrap() >= before - chrono::Duration::seconds(1));
+    assert_eq!(resolved.resolved_by, Some(user.id));
+
+    let response = resolved.to_response(&project.slug, 120);
+    assert_eq!(response.resolved_by, Some(user.id));
+    assert_eq!(response.resolved_at, resolved.resolved_at);
+
+    // Unresolving clears both fields
+    let reopened = IssueService::unresolve(&db.pool, issue.id).await.unwrap();
+    assert!(!reopened.is_resolved);
+    assert!(reopened.resolved_at.is_none());
+    assert!(reopened.resolved_by.is_none());
+}
+
+#[actix_web::test]
+async fn test_resolve_records_fixing_change() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Resolve Commit Project").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+
+    let resolution = ResolveIssue {
+        commit: Some("9fceb02".to_string()),
+        pr_url: Some("https://github.com/acme/app/pull/42".to_string()),
+    };
+    let resolved = IssueService::resolve_with(&db.pool, issue.id, Actor::System, &resolution)
+        .await
+        .unwrap();
+    assert!(resolved.is_resolved);
+    assert_eq!(resolved.resolved_commit.as_deref(), Some("9fceb02"));
+
+    let response = serde_json::to_value(resolved.to_response(&project.slug, 120)).unwrap();
+    assert_eq!(response["resolved_commit"], "9fceb02");
+    assert_eq!(
+        response["resolved_pr_url"],
+        "https://github.com/acme/app/pull/42"
+    );
+
+    // Invalid references leave the issue alone
+    let invalid = ResolveIssue {
+        commit: None,
+        pr_url: Some("not a url".to_string()),
+    };
+    assert!(
+        IssueService::resolve_with(&db.pool, issue.id, Actor::System, &invalid)
+            .await
+            .is_err()
+    );
+    let unchanged = IssueService::get_by_id(&db.pool, issue.id).await.unwrap();
+    assert_eq!(unchanged.resolved_commit.as_deref(), Some("9fceb02"));
+
+    // Resolving without references clears them, as does reopening
+    let resolved = IssueService::resolve(&db.pool, issue.id, Actor::System)
+        .await
+        .unwrap();
+    assert_eq!(resolved.resolved_commit, None);
+    assert_eq!(resolved.resolved_pr_url, None);
+
+    IssueService::resolve_with(&db.pool, issue.id, Actor::System, &resolution)
+        .await
+        .unwrap();
+    let reopened = IssueService::unresolve(&db.pool, issue.id).await.unwrap();
+    assert_eq!(reopened.resolved_commit, None);
+    assert_eq!(reopened.resolved_pr_url, None);
+}
+
+#[actix_web::test]
+async fn test_system_resolution_has_no_user() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "System Resolve Project").await;
+
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+    let resolved = IssueService::resolve(&db.pool, issue.id, Actor::System)
+        .await
+        .unwrap();
+
+    assert!(resolved.is_resolved);
+    assert!(resolved.resolved_at.is_some());
+    assert!(resolved.resolved_by.is_none());
+}
+
+#[actix_web::test]
+async fn test_resolve_route_body_is_optional() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Resolve Route Project").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+    let admin = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "admin@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        true,
+    )
+    .await
+    .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::issues::configure),
+    )
+    .await;
+    let resp = test::call_service(&app, login_request(admin.id).to_request()).await;
+    let cookie = session_cookie(&resp);
+    let resolve_uri = format!("/api/projects/{}/issues/{}/resolve", project.id, issue.id);
+
+    let req = test::TestRequest::post()
+        .uri(&resolve_uri)
+        .cookie(cookie.clone())
+        .set_json(json!({ "commit": "9fceb02" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["is_resolved"], true);
+    assert_eq!(body["resolved_commit"], "9fceb02");
+
+    // Without a body the issue is resolved again, without references
+    let req = test::TestRequest::post()
+        .uri(&resolve_uri)
+        .cookie(cookie)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["is_resolved"], true);
+    assert_eq!(body["resolved_commit"], Value::Null);
+}
+
+// =============================================================================
+// Seen Tracking Tests
+// =============================================================================
+
+async fn create_test_user(pool: &PgPool, email: &str) -> rustrak::models::User {
+    UsersService::create_user(
+        pool,
+        &CreateUserRequest {
+            email: email.to_string(),
+            password: "password123".to_string(),
+        },
+        false,
+    )
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn test_mark_seen_tracks_users_per_issue() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Seen Project").await;
+    let alice = create_test_user(&db.pool, "alice@example.com").await;
+    let bob = create_test_user(&db.pool, "bob@example.com").await;
+
+    let seen_issue = create_test_issue(&db.pool, project.id, "TypeError", "Seen").await;
+    let unseen_issue = create_test_issue(&db.pool, project.id, "ValueError", "Unseen").await;
+
+    assert!(IssueService::seen_by(&db.pool, seen_issue.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    IssueService::mark_seen(&db.pool, seen_issue.id, alice.id)
+        .await
+        .unwrap();
+    IssueService::mark_seen(&db.pool, seen_issue.id, bob.id)
+        .await
+        .unwrap();
+
+    let ids = [seen_issue.id, unseen_issue.id];
+    let seen = IssueService::seen_issue_ids(&db.pool, alice.id, &ids)
+        .await
+        .unwrap();
+    assert!(seen.contains(&seen_issue.id));
+    assert!(!seen.contains(&unseen_issue.id));
+
+    // Most recent viewer first
+    let seen_by = IssueService::seen_by(&db.pool, seen_issue.id)
+        .await
+        .unwrap();
+    let emails: Vec<_> = seen_by.iter().map(|s| s.email.as_str()).collect();
+    assert_eq!(emails, vec!["bob@example.com", "alice@example.com"]);
+}
+
+#[actix_web::test]
+async fn test_mark_seen_again_updates_seen_at() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Seen Again Project").await;
+    let user = create_test_user(&db.pool, "viewer@example.com").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+
+    IssueService::mark_seen(&db.pool, issue.id, user.id)
+        .await
+        .unwrap();
+    let first = IssueService::seen_by(&db.pool, issue.id).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    IssueService::mark_seen(&db.pool, issue.id, user.id)
+        .await
+        .unwrap();
+    let second = IssueService::seen_by(&db.pool, issue.id).await.unwrap();
+
+    assert_eq!(second.len(), 1);
+    assert!(second[0].seen_at > first[0].seen_at);
+}
+
+#[actix_web::test]
+async fn test_mark_seen_in_background_records_view() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Background Seen Project").await;
+    let user = create_test_user(&db.pool, "background@example.com").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+
+    IssueService::mark_seen_in_background(&db.pool, issue.id, user.id);
+
+    let mut seen = false;
+    for _ in 0..50 {
+        let ids = IssueService::seen_issue_ids(&db.pool, user.id, &[issue.id])
+            .await
+            .unwrap();
+        if ids.contains(&issue.id) {
+            seen = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(seen);
+
+    // Failures (here a user that doesn't exist) are only logged
+    IssueService::mark_seen_in_background(&db.pool, issue.id, 999_999);
+}
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_get_issue_marks_seen() {
+    let db = TestDb::new().await;
+    let token = create_test_token(&db.pool).await;
+    let project = create_test_project(&db.pool, "Seen API Project").await;
+    let config = create_test_config();
+
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Error").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::issues::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/issues", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body["items"][0]["is_seen"], false);
+    assert!(body["items"][0].get("seen_by").is_none());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/issues/{}", project.id, issue.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body["is_seen"], true);
+    assert!(body["seen_by"].is_array());
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/issues", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let body: Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body["items"][0]["is_seen"], true);
+}
+
+#[actix_web::test]
+async fn test_issue_groupings() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Grouping Project").await;
+    let issue = create_test_issue(&db.pool, project.id, "ValueError", "bad input").await;
+
+    let keys = ["ValueError: bad input ⋄ /checkout", "checkout-failure"];
+    for key in keys {
+        sqlx::query(
+            "INSERT INTO groupings (project_id, issue_id, grouping_key, grouping_key_hash) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(project.id)
+        .bind(issue.id)
+        .bind(key)
+        .bind(hash_grouping_key(key))
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    }
+
+    let groupings: Vec<_> = IssueService::groupings(&db.pool, issue.id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|g| g.to_response())
+        .collect();
+    assert_eq!(groupings.len(), 2);
+    assert_eq!(groupings[0].grouping_key, keys[0]);
+    assert_eq!(
+        groupings[0].components,
+        ["ValueError: bad input", "/checkout"]
+    );
+    assert_eq!(groupings[0].grouping_key_hash, hash_grouping_key(keys[0]));
+    assert_eq!(groupings[1].components, ["checkout-failure"]);
+
+    // Groupings are left out of the response unless asked for
+    let response = issue.to_response(&project.slug, 100);
+    assert!(serde_json::to_value(&response)
+        .unwrap()
+        .get("groupings")
+        .is_none());
+}
+
+#[actix_web::test]
+async fn test_issue_bundle() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Bundle Project").await;
+    let other = create_test_project(&db.pool, "Other Project").await;
+    let issue = create_aggregated_issue(&db.pool, project.id, "TypeError", "/checkout", 4, 0).await;
+
+    let bundle = IssueService::bundle(&db.pool, &project, issue.id, 3, 100)
+        .await
+        .unwrap();
+    assert_eq!(bundle.issue.id, issue.id);
+    assert_eq!(bundle.issue.groupings.as_ref().map(Vec::len), Some(1));
+    // Capped to the requested count, most recently received first
+    assert_eq!(bundle.events.len(), 3);
+    let latest = EventService::list_paginated(
+        &db.pool,
+        issue.id,
+        rustrak::pagination::EventSort::Received,
+        rustrak::pagination::SortOrder::Desc,
+        None,
+        None,
+        1,
+    )
+    .await
+    .unwrap()
+    .0;
+    assert_eq!(bundle.events[0].id, latest[0].id);
+    assert_eq!(bundle.events[0].data["transaction"], "/checkout");
+
+    let bundle = serde_json::to_value(&bundle).unwrap();
+    assert!(bundle["exported_at"].is_string());
+    assert_eq!(bundle["events"].as_array().unwrap().len(), 3);
+
+    // The issue is only reachable through its own project
+    let err = IssueService::bundle(&db.pool, &other, issue.id, 3, 100)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rustrak::error::AppError::NotFound(_)));
+}
+
+// =============================================================================
+// Aggregation Tests
+// =============================================================================
+
+/// Creates an issue with `events` events, last seen `seen_ago` minutes ago
+async fn create_aggregated_issue(
+    pool: &PgPool,
+    project_id: i32,
+    calc_type: &str,
+    transaction: &str,
+    events: usize,
+    seen_ago: i64,
+) -> rustrak::models::Issue {
+    let denormalized = create_denormalized_fields(calc_type, "failed", transaction);
+    let issue = IssueService::create(
+        pool,
+        project_id,
+        Utc::now(),
+        &denormalized,
+        Some("error"),
+        Some("rust"),
+    )
+    .await
+    .expect("Failed to create test issue");
+    sqlx::query("UPDATE issues SET last_seen = NOW() - make_interval(mins => $2) WHERE id = $1")
+        .bind(issue.id)
+        .bind(seen_ago as i32)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let key = format!("{} ⋄ {} ⋄ {}", calc_type, transaction, issue.id);
+    let grouping_id: i32 = sqlx::query_scalar(
+        "INSERT INTO groupings (project_id, issue_id, grouping_key, grouping_key_hash) \
+         VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(project_id)
+    .bind(issue.id)
+    .bind(&key)
+    .bind(hash_grouping_key(&key))
+    .fetch_one(pool)
+    .await
+    .unwrap();
+
+    for order in 0..events {
+        EventService::create(
+            pool,
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            project_id,
+            issue.id,
+            grouping_id,
+            &json!({"level": "error", "platform": "rust", "transaction": transaction}),
+            Utc::now(),
+            &denormalized,
+            order as i32 + 1,
+            None,
+        )
+        .await
+        .expect("Failed to create test event");
+    }
+    issue
+}
+
+#[actix_web::test]
+async fn test_aggregate_issues() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Aggregate Project").await;
+    let other = create_test_project(&db.pool, "Other Project").await;
+
+    create_aggregated_issue(&db.pool, project.id, "TypeError", "/checkout", 3, 30).await;
+    let checkout_value =
+        create_aggregated_issue(&db.pool, project.id, "ValueError", "/checkout", 1, 5).await;
+    let login_type =
+        create_aggregated_issue(&db.pool, project.id, "TypeError", "/login", 2, 10).await;
+    let search_key =
+        create_aggregated_issue(&db.pool, project.id, "KeyError", "/search", 2, 20).await;
+    // Only one of the /search events is in the period
+    sqlx::query(
+        "UPDATE events SET digested_at = NOW() - INTERVAL '10 days' \
+         WHERE id = (SELECT id FROM events WHERE issue_id = $1 LIMIT 1)",
+    )
+    .bind(search_key.id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    // Resolved issues and other projects are left out
+    let resolved = create_aggregated_issue(&db.pool, project.id, "TypeError", "/login", 4, 1).await;
+    IssueService::resolve(&db.pool, resolved.id, Actor::System)
+        .await
+        .unwrap();
+    create_aggregated_issue(&db.pool, other.id, "TypeError", "/checkout", 5, 1).await;
+
+    let since = Utc::now() - chrono::Duration::days(7);
+    let by_transaction = IssueService::aggregate(
+        &db.pool,
+        project.id,
+        IssueGroupBy::Transaction,
+        IssueFilter::Open,
+        None,
+        since,
+    )
+    .await
+    .unwrap();
+    let groups: Vec<_> = by_transaction
+        .iter()
+        .map(|g| (g.value.as_str(), g.issue_count, g.event_count))
+        .collect();
+    assert_eq!(
+        groups,
+        [("/checkout", 2, 4), ("/login", 1, 2), ("/search", 1, 1)]
+    );
+    assert_eq!(by_transaction[0].latest_issue_id, checkout_value.id);
+    assert_eq!(by_transaction[1].latest_issue_id, login_type.id);
+    assert_eq!(by_transaction[2].latest_issue_id, search_key.id);
+
+    let by_type = IssueService::aggregate(
+        &db.pool,
+        project.id,
+        IssueGroupBy::CalculatedType,
+        IssueFilter::Open,
+        None,
+        since,
+    )
+    .await
+    .unwrap();
+    let groups: Vec<_> = by_type
+        .iter()
+        .map(|g| (g.value.as_str(), g.issue_count, g.event_count))
+        .collect();
+    // Ties are ordered by value
+    assert_eq!(
+        groups,
+        [
+            ("TypeError", 2, 5),
+            ("KeyError", 1, 1),
+            ("ValueError", 1, 1)
+        ]
+    );
+    assert_eq!(by_type[0].latest_issue_id, login_type.id);
+
+    // The list filters apply
+    let all = IssueService::aggregate(
+        &db.pool,
+        project.id,
+        IssueGroupBy::Transaction,
+        IssueFilter::All,
+        None,
+        since,
+    )
+    .await
+    .unwrap();
+    assert_eq!(all[0].value, "/login");
+    assert_eq!((all[0].issue_count, all[0].event_count), (2, 6));
+    assert_eq!(all[0].latest_issue_id, resolved.id);
+}
+
+// =============================================================================
+// Issue Stream Tests
+// =============================================================================
+
+/// Reads SSE chunks until one mentions `needle`, or panics after a few seconds
+async fn read_stream_until<B>(body: &mut B, needle: &str) -> String
+where
+    B: actix_web::body::MessageBody + Unpin,
+    B::Error: std::fmt::Debug,
+{
+    let mut received = String::new();
+    tokio::time::timeout(StdDuration::from_secs(5), async {
+        while !received.contains(needle) {
+            let chunk = std::future::poll_fn(|cx| std::pin::Pin::new(&mut *body).poll_next(cx))
+                .await
+                .expect("Stream ended early")
+                .expect("Failed to read stream");
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    })
+    .await
+    .expect("Timed out waiting for stream data");
+    received
+}
+
+#[actix_web::test]
+async fn test_stream_issues_sends_project_updates() {
+    let db = TestDb::new().await;
+    let token = create_test_token(&db.pool).await;
+    let project = create_test_project(&db.pool, "Stream Project").await;
+    let other = create_test_project(&db.pool, "Other Stream Project").await;
+
+    let issue = create_test_issue(&db.pool, project.id, "StreamError", "Streamed").await;
+    let other_issue = create_test_issue(&db.pool, other.id, "OtherError", "Other").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .configure(routes::issues::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/issues/stream", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    let stream = IssueStream::global();
+    stream.publish(IssueStreamEvent::new(
+        IssueChange::Created,
+        &other_issue,
+        120,
+    ));
+    stream.publish(IssueStreamEvent::new(IssueChange::Updated, &issue, 120));
+
+    let mut body = resp.into_body();
+    let received = read_stream_until(&mut body, &issue.id.to_string()).await;
+
+    assert!(received.contains("event: issue\ndata: "));
+    assert!(received.contains("\"type\":\"updated\""));
+    assert!(!received.contains(&other_issue.id.to_string()));
+}
+
+#[actix_web::test]
+async fn test_stream_issues_requires_token() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Stream Auth Project").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .configure(routes::issues::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/issues/stream", project.id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/issues/stream", project.id))
+        .insert_header(("Authorization", format!("Bearer {}", "0".repeat(40))))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+// =============================================================================
+// Affected Users Export Tests
+// =============================================================================
+
+fn event_user(id: Option<&str>, email: Option<&str>, username: Option<&str>) -> EventUser {
+    EventUser {
+        id: id.map(str::to_string),
+        email: email.map(str::to_string),
+        username: username.map(str::to_string),
+    }
+}
+
+#[actix_web::test]
+async fn test_export_issue_users_csv() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Export Project").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Export").await;
+    let member = create_test_user(&db.pool, "support@example.com").await;
+    ProjectMemberService::add(
+        &db.pool,
+        project.id,
+        AddProjectMember {
+            user_id: member.id,
+            role: ProjectRole::Member,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Ada hits the issue twice, the mobile device only sends an id
+    let t0 = (Utc::now() - chrono::Duration::hours(3))
+        .duration_trunc(chrono::Duration::seconds(1))
+        .unwrap();
+    let ada = event_user(Some("42"), Some("ada@example.com"), Some("ada"));
+    let device = event_user(Some("device-7f3a"), None, None);
+    let by_email = event_user(None, Some("grace@example.com"), Some("=cmd()"));
+    for (user, hours) in [(&ada, 0), (&device, 1), (&ada, 2), (&by_email, 2)] {
+        IssueUserService::record(
+            &db.pool,
+            issue.id,
+            user,
+            t0 + chrono::Duration::hours(hours),
+        )
+        .await
+        .unwrap();
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::issues::configure),
+    )
+    .await;
+    let resp = test::call_service(&app, login_request(member.id).to_request()).await;
+    let cookie = session_cookie(&resp);
+    let export_uri = format!(
+        "/api/projects/{}/issues/{}/users/export",
+        project.id, issue.id
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&export_uri)
+        .cookie(cookie.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let body = test::read_body(resp).await;
+    let csv = std::str::from_utf8(&body).unwrap();
+    let time = |hours| (t0 + chrono::Duration::hours(hours)).to_rfc3339();
+    assert_eq!(
+        csv,
+        format!(
+            "user_id,email,username,first_affected,last_affected,event_count\n\
+             42,ada@example.com,ada,{t0},{t2},2\n\
+             device-7f3a,,,{t1},{t1},1\n\
+             ,grace@example.com,'=cmd(),{t2},{t2},1\n",
+            t0 = time(0),
+            t1 = time(1),
+            t2 = time(2),
+        )
+    );
+
+    // A re-export only covers users first affected since then
+    let since = (t0 + chrono::Duration::minutes(30)).to_rfc3339();
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "{}?since={}",
+            export_uri,
+            since.replace('+', "%2B")
+        ))
+        .cookie(cookie)
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let csv = std::str::from_utf8(&body).unwrap();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(!csv.contains("ada@example.com"));
+
+    // Both downloads are in the audit log
+    let entries = AuditLogService::list_for_project(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.action == "issue_users.export"
+        && e.user_id == Some(member.id)
+        && e.details["issue_id"] == issue.id.to_string()));
+    assert!(entries[0].details["since"].is_string());
+    assert!(entries[1].details["since"].is_null());
+}
+
+#[actix_web::test]
+async fn test_export_issue_users_requires_membership() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Private Export").await;
+    let issue = create_test_issue(&db.pool, project.id, "TypeError", "Private").await;
+    let outsider = create_test_user(&db.pool, "outsider@example.com").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .wrap(session_middleware())
+            .configure(configure_login)
+            .configure(routes::issues::configure),
+    )
+    .await;
+    let export_uri = format!(
+        "/api/projects/{}/issues/{}/users/export",
+        project.id, issue.id
+    );
+
+    let req = test::TestRequest::get().uri(&export_uri).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let resp = test::call_service(&app, login_request(outsider.id).to_request()).await;
+    let req = test::TestRequest::get()
+        .uri(&export_uri)
+        .cookie(session_cookie(&resp))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    // Refused downloads aren't audited
+    assert!(AuditLogService::list_for_project(&db.pool, project.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
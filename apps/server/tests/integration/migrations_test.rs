@@ -0,0 +1,316 @@
+//! Integration tests for startup migrations
+//!
+//! Simulates pending, newer and modified migrations by running migrators built
+//! from altered copies of the migrations directory.
+
+use rustrak::db::{
+    inspect_migrations, run_migrations, run_migrator, MigrationError, MigrationInfo, MIGRATOR,
+};
+use rustrak::services::{ProjectKeyService, ProjectMemberService};
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+use std::path::Path;
+use tempfile::TempDir;
+use testcontainers::{runners::AsyncRunner, ContainerAsync};
+use testcontainers_modules::postgres::Postgres;
+use uuid::Uuid;
+
+/// Test database container with connection pool, without migrations applied
+struct TestDb {
+    #[allow(dead_code)]
+    container: ContainerAsync<Postgres>,
+    pool: PgPool,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let container = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start PostgreSQL container");
+
+        let host = container.get_host().await.expect("Failed to get host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get port");
+
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+
+        let pool = PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pgcrypto")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable pgcrypto extension");
+
+        TestDb { container, pool }
+    }
+}
+
+/// Copies the migrations directory, keeping files accepted by `keep`
+fn copy_migrations(keep: impl Fn(&str) -> bool) -> TempDir {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    for entry in std::fs::read_dir("./migrations").expect("Failed to read migrations") {
+        let entry = entry.unwrap();
+        let name = entry.file_name().into_string().unwrap();
+        if keep(&name) {
+            std::fs::copy(entry.path(), dir.path().join(&name)).unwrap();
+        }
+    }
+    dir
+}
+
+async fn migrator_from(dir: &Path) -> Migrator {
+    Migrator::new(dir).await.expect("Failed to load migrations")
+}
+
+fn latest_migration() -> MigrationInfo {
+    let migration = MIGRATOR.iter().last().unwrap();
+    MigrationInfo {
+        version: migration.version,
+        description: migration.description.to_string(),
+    }
+}
+
+async fn applied_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+// =============================================================================
+// Pending Migrations
+// =============================================================================
+
+#[actix_web::test]
+async fn test_fresh_database_has_all_migrations_pending() {
+    let db = TestDb::new().await;
+
+    let state = inspect_migrations(&db.pool, &MIGRATOR)
+        .await
+        .expect("Failed to inspect migrations");
+    let up_migrations = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .count();
+    assert_eq!(state.pending.len(), up_migrations);
+    assert!(state.unknown.is_empty());
+    assert!(state.modified.is_empty());
+
+    run_migrations(&db.pool, false)
+        .await
+        .expect("Failed to run migrations");
+
+    let state = inspect_migrations(&db.pool, &MIGRATOR).await.unwrap();
+    assert!(state.pending.is_empty());
+    assert!(state.check(false).is_ok());
+}
+
+#[actix_web::test]
+async fn test_pending_migrations_are_applied() {
+    let db = TestDb::new().await;
+    let latest = latest_migration();
+
+    // An older server applied everything except the latest migration
+    let older = copy_migrations(|name| !name.starts_with(&latest.version.to_string()));
+    run_migrator(&db.pool, &migrator_from(older.path()).await, false)
+        .await
+        .expect("Failed to run older migrations");
+
+    // Inspecting (as --dry-run does) lists it without applying it
+    let state = inspect_migrations(&db.pool, &MIGRATOR).await.unwrap();
+    assert_eq!(state.pending, vec![latest.clone()]);
+    let before = applied_count(&db.pool).await;
+    inspect_migrations(&db.pool, &MIGRATOR).await.unwrap();
+    assert_eq!(applied_count(&db.pool).await, before);
+
+    run_migrations(&db.pool, false)
+        .await
+        .expect("Failed to apply pending migration");
+    assert_eq!(applied_count(&db.pool).await, before + 1);
+}
+
+// =============================================================================
+// Database Ahead of Binary
+// =============================================================================
+
+#[actix_web::test]
+async fn test_refuses_database_migrated_by_newer_server() {
+    let db = TestDb::new().await;
+
+    // A newer server added a migration this binary doesn't have
+    let newer = copy_migrations(|_| true);
+    std::fs::write(
+        newer.path().join("20990101000000_future_feature.up.sql"),
+        "CREATE TABLE future_feature (id SERIAL PRIMARY KEY);",
+    )
+    .unwrap();
+    std::fs::write(
+        newer.path().join("20990101000000_future_feature.down.sql"),
+        "DROP TABLE future_feature;",
+    )
+    .unwrap();
+    run_migrator(&db.pool, &migrator_from(newer.path()).await, false)
+        .await
+        .expect("Failed to run newer migrations");
+
+    let err = run_migrations(&db.pool, false)
+        .await
+        .expect_err("Older binary must refuse to start");
+    match &err {
+        MigrationError::DatabaseAhead { migrations, .. } => {
+            assert_eq!(migrations.len(), 1);
+            assert_eq!(migrations[0].version, 20990101000000);
+            assert_eq!(migrations[0].description, "future feature");
+        }
+        other => panic!("Expected DatabaseAhead, got {:?}", other),
+    }
+    let message = err.to_string();
+    assert!(message.contains("Database is ahead of binary v"));
+    assert!(message.contains("Upgrade the server"));
+
+    // The override is for modified migrations only
+    assert!(matches!(
+        run_migrations(&db.pool, true).await,
+        Err(MigrationError::DatabaseAhead { .. })
+    ));
+}
+
+// =============================================================================
+// Modified Migrations
+// =============================================================================
+
+#[actix_web::test]
+async fn test_modified_migration_is_reported() {
+    let db = TestDb::new().await;
+    let latest = latest_migration();
+
+    // The applied version of the latest migration differs from the binary's
+    let altered = copy_migrations(|_| true);
+    let up_file = std::fs::read_dir(altered.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            name.starts_with(&latest.version.to_string()) && name.ends_with(".up.sql")
+        })
+        .unwrap();
+    let sql = std::fs::read_to_string(&up_file).unwrap();
+    std::fs::write(&up_file, format!("{}\n-- hotfix\n", sql)).unwrap();
+    run_migrator(&db.pool, &migrator_from(altered.path()).await, false)
+        .await
+        .expect("Failed to run altered migrations");
+
+    let err = run_migrations(&db.pool, false)
+        .await
+        .expect_err("Modified migration must be refused");
+    match &err {
+        MigrationError::Modified { migrations } => assert_eq!(migrations, &vec![latest.clone()]),
+        other => panic!("Expected Modified, got {:?}", other),
+    }
+    let message = err.to_string();
+    assert!(message.contains(&latest.version.to_string()));
+    assert!(message.contains("ALLOW_DIRTY_MIGRATIONS=true"));
+}
+
+#[actix_web::test]
+async fn test_allow_dirty_accepts_modified_migration() {
+    let db = TestDb::new().await;
+    let latest = latest_migration();
+
+    run_migrations(&db.pool, false).await.unwrap();
+    sqlx::query("UPDATE _sqlx_migrations SET checksum = '\\x00' WHERE version = $1")
+        .bind(latest.version)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let state = inspect_migrations(&db.pool, &MIGRATOR).await.unwrap();
+    assert_eq!(state.modified, vec![latest]);
+
+    run_migrations(&db.pool, true)
+        .await
+        .expect("Override should accept the modified migration");
+
+    // The binary's checksum is now recorded, so later strict starts succeed
+    let state = inspect_migrations(&db.pool, &MIGRATOR).await.unwrap();
+    assert!(state.modified.is_empty());
+    run_migrations(&db.pool, false)
+        .await
+        .expect("Strict run should succeed after the override");
+}
+
+// =============================================================================
+// Data Migrations
+// =============================================================================
+
+#[actix_web::test]
+async fn test_project_keys_migration_keeps_existing_keys() {
+    let db = TestDb::new().await;
+
+    // A server from before project keys, with a project and its DSN key
+    let older = copy_migrations(|name| name < "20260130000000");
+    run_migrator(&db.pool, &migrator_from(older.path()).await, false)
+        .await
+        .expect("Failed to run older migrations");
+    let (project_id, sentry_key): (i32, Uuid) = sqlx::query_as(
+        "INSERT INTO projects (name, slug) VALUES ('Legacy', 'legacy') RETURNING id, sentry_key",
+    )
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+
+    run_migrations(&db.pool, false)
+        .await
+        .expect("Failed to apply pending migrations");
+
+    let keys = ProjectKeyService::list(&db.pool, project_id).await.unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].key, sentry_key);
+    assert_eq!(keys[0].label, "Default");
+    assert!(keys[0].is_active);
+
+    let active = ProjectKeyService::get_active(&db.pool, project_id, &sentry_key)
+        .await
+        .unwrap();
+    assert!(active.is_some());
+}
+
+#[actix_web::test]
+async fn test_project_members_migration_keeps_existing_access() {
+    let db = TestDb::new().await;
+
+    // A server from before project members, where every user saw every project
+    let older = copy_migrations(|name| name < "20260208000000");
+    run_migrator(&db.pool, &migrator_from(older.path()).await, false)
+        .await
+        .expect("Failed to run older migrations");
+    let (project_id,): (i32,) = sqlx::query_as(
+        "INSERT INTO projects (name, slug) VALUES ('Legacy', 'legacy') RETURNING id",
+    )
+    .fetch_one(&db.pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO users (email, password_hash, is_admin) \
+         VALUES ('dev@example.com', 'x', false), ('admin@example.com', 'x', true)",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    run_migrations(&db.pool, false)
+        .await
+        .expect("Failed to apply pending migrations");
+
+    // Admins see every project without being members
+    let members = ProjectMemberService::list(&db.pool, project_id)
+        .await
+        .unwrap();
+    let emails: Vec<&str> = members.iter().map(|m| m.email.as_str()).collect();
+    assert_eq!(emails, vec!["dev@example.com"]);
+}
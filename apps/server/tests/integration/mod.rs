@@ -6,11 +6,20 @@ mod alerts_api_test;
 mod auth_test;
 mod bootstrap_test;
 mod concurrency_test;
+mod db_outage_test;
+mod debug_files_test;
 mod digest_test;
 mod events_api_test;
 mod health_test;
 mod ingest_test;
 mod issues_api_test;
+mod migrations_test;
+mod oidc_test;
+mod overview_test;
+mod project_members_test;
 mod projects_api_test;
 mod rate_limit_test;
+mod saved_searches_test;
+mod self_monitoring_test;
+mod teams_test;
 mod tokens_api_test;
@@ -0,0 +1,177 @@
+//! Integration tests for the dashboard overview
+//!
+//! Tests the sections of `GET /api/me/overview` for users and API tokens,
+//! with a real PostgreSQL database.
+
+use chrono::{Duration, Utc};
+use rustrak::models::{
+    Actor, AddProjectMember, CreateProject, CreateUserRequest, ProjectRole, User,
+};
+use rustrak::services::grouping::DenormalizedFields;
+use rustrak::services::{IssueService, ProjectMemberService, ProjectService, UsersService};
+use sqlx::PgPool;
+use testcontainers::{runners::AsyncRunner, ContainerAsync};
+use testcontainers_modules::postgres::Postgres;
+use uuid::Uuid;
+
+const MAX_TITLE_LENGTH: usize = 255;
+
+/// Test database container with connection pool
+struct TestDb {
+    #[allow(dead_code)]
+    container: ContainerAsync<Postgres>,
+    pool: PgPool,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let container = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start PostgreSQL container");
+
+        let host = container.get_host().await.expect("Failed to get host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get port");
+
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+
+        let pool = PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        // Enable pgcrypto extension for gen_random_uuid()
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pgcrypto")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable pgcrypto extension");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        TestDb { container, pool }
+    }
+}
+
+async fn create_user(pool: &PgPool, email: &str) -> User {
+    UsersService::create_user(
+        pool,
+        &CreateUserRequest {
+            email: email.to_string(),
+            password: "password123".to_string(),
+        },
+        false,
+    )
+    .await
+    .expect("Failed to create user")
+}
+
+async fn create_project(pool: &PgPool, name: &str) -> i32 {
+    ProjectService::create(
+        pool,
+        CreateProject {
+            name: name.to_string(),
+            slug: None,
+            team_id: None,
+        },
+    )
+    .await
+    .expect("Failed to create project")
+    .id
+}
+
+/// Creates an open issue last seen `minutes_ago`
+async fn create_issue(pool: &PgPool, project_id: i32, calc_type: &str, minutes_ago: i64) -> Uuid {
+    let denormalized = DenormalizedFields {
+        calculated_type: calc_type.to_string(),
+        calculated_value: "failed".to_string(),
+        transaction: "/api/test".to_string(),
+        last_frame_filename: "test.rs".to_string(),
+        last_frame_module: "test_module".to_string(),
+        last_frame_function: "test_function".to_string(),
+        exception_chain_length: 1,
+        is_unhandled: false,
+    };
+    let seen = Utc::now() - Duration::minutes(minutes_ago);
+    IssueService::create(pool, project_id, seen, &denormalized, Some("error"), None)
+        .await
+        .expect("Failed to create issue")
+        .id
+}
+
+#[tokio::test]
+async fn test_overview_sections() {
+    let db = TestDb::new().await;
+    let api = create_project(&db.pool, "API").await;
+    let web = create_project(&db.pool, "Web").await;
+    let dev = create_user(&db.pool, "dev@example.com").await;
+    ProjectMemberService::add(
+        &db.pool,
+        api,
+        AddProjectMember {
+            user_id: dev.id,
+            role: ProjectRole::Member,
+        },
+    )
+    .await
+    .unwrap();
+
+    let older = create_issue(&db.pool, api, "TimeoutError", 30).await;
+    let newer = create_issue(&db.pool, api, "KeyError", 5).await;
+    let resolved = create_issue(&db.pool, api, "ValueError", 1).await;
+    IssueService::resolve(&db.pool, resolved, Actor::System)
+        .await
+        .unwrap();
+    let other = create_issue(&db.pool, web, "TypeError", 10).await;
+    IssueService::mark_seen(&db.pool, older, dev.id)
+        .await
+        .unwrap();
+
+    // Users see their projects' open issues, most recently seen first
+    let overview = UsersService::overview(&db.pool, Some(&dev), MAX_TITLE_LENGTH)
+        .await
+        .unwrap();
+    let issues: Vec<(Uuid, bool)> = overview.issues.iter().map(|i| (i.id, i.is_seen)).collect();
+    assert_eq!(issues, vec![(newer, false), (older, true)]);
+    assert_eq!(overview.issues[0].short_id, "API-2");
+
+    let projects = overview.projects.expect("Users get their projects");
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].id, api);
+    assert_eq!(projects[0].slug, "api");
+    assert_eq!(projects[0].stats.open_issue_count, 2);
+
+    // Tokens see every project's issues, without the project list
+    let overview = UsersService::overview(&db.pool, None, MAX_TITLE_LENGTH)
+        .await
+        .unwrap();
+    let issues: Vec<Uuid> = overview.issues.iter().map(|i| i.id).collect();
+    assert_eq!(issues, vec![newer, other, older]);
+    assert!(overview.projects.is_none());
+
+    let json = serde_json::to_value(&overview).unwrap();
+    assert!(json.get("projects").is_none());
+}
+
+#[tokio::test]
+async fn test_overview_empty_for_new_user() {
+    let db = TestDb::new().await;
+    let project = create_project(&db.pool, "API").await;
+    create_issue(&db.pool, project, "TimeoutError", 5).await;
+    let user = create_user(&db.pool, "new@example.com").await;
+
+    let overview = UsersService::overview(&db.pool, Some(&user), MAX_TITLE_LENGTH)
+        .await
+        .unwrap();
+    assert!(overview.issues.is_empty());
+    assert_eq!(overview.projects.as_ref().map(Vec::len), Some(0));
+
+    // Empty sections are still included
+    let json = serde_json::to_value(&overview).unwrap();
+    assert_eq!(json["issues"], serde_json::json!([]));
+    assert_eq!(json["projects"], serde_json::json!([]));
+}
@@ -0,0 +1,220 @@
+//! Integration tests for project members
+//!
+//! Tests adding and removing members, and how membership scopes the
+//! projects a user can see, with a real PostgreSQL database.
+
+use rustrak::error::AppError;
+use rustrak::models::{AddProjectMember, CreateProject, CreateUserRequest, ProjectRole, User};
+use rustrak::pagination::SortOrder;
+use rustrak::services::{ProjectMemberService, ProjectService, UsersService};
+use sqlx::PgPool;
+use testcontainers::{runners::AsyncRunner, ContainerAsync};
+use testcontainers_modules::postgres::Postgres;
+
+/// Test database container with connection pool
+struct TestDb {
+    #[allow(dead_code)]
+    container: ContainerAsync<Postgres>,
+    pool: PgPool,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let container = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start PostgreSQL container");
+
+        let host = container.get_host().await.expect("Failed to get host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get port");
+
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+
+        let pool = PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        // Enable pgcrypto extension for gen_random_uuid()
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pgcrypto")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable pgcrypto extension");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        TestDb { container, pool }
+    }
+}
+
+async fn create_user(pool: &PgPool, email: &str, is_admin: bool) -> User {
+    UsersService::create_user(
+        pool,
+        &CreateUserRequest {
+            email: email.to_string(),
+            password: "password123".to_string(),
+        },
+        is_admin,
+    )
+    .await
+    .expect("Failed to create user")
+}
+
+async fn create_project(pool: &PgPool, name: &str) -> i32 {
+    ProjectService::create(
+        pool,
+        CreateProject {
+            name: name.to_string(),
+            slug: None,
+            team_id: None,
+        },
+    )
+    .await
+    .expect("Failed to create project")
+    .id
+}
+
+async fn add_member(pool: &PgPool, project_id: i32, user: &User, role: ProjectRole) {
+    ProjectMemberService::add(
+        pool,
+        project_id,
+        AddProjectMember {
+            user_id: user.id,
+            role,
+        },
+    )
+    .await
+    .expect("Failed to add member");
+}
+
+async fn visible_projects(pool: &PgPool, user: &User) -> Vec<i32> {
+    let (projects, total) = ProjectService::list_offset(pool, user, None, SortOrder::Asc, 1, 20)
+        .await
+        .unwrap();
+    assert_eq!(total, projects.len() as i64);
+    projects.iter().map(|p| p.id).collect()
+}
+
+#[tokio::test]
+async fn test_users_see_only_their_projects() {
+    let db = TestDb::new().await;
+    let api = create_project(&db.pool, "API").await;
+    let web = create_project(&db.pool, "Web").await;
+    let admin = create_user(&db.pool, "admin@example.com", true).await;
+    let dev = create_user(&db.pool, "dev@example.com", false).await;
+    let outsider = create_user(&db.pool, "outsider@example.com", false).await;
+    add_member(&db.pool, web, &dev, ProjectRole::Member).await;
+
+    assert_eq!(visible_projects(&db.pool, &admin).await, vec![api, web]);
+    assert_eq!(visible_projects(&db.pool, &dev).await, vec![web]);
+    assert!(visible_projects(&db.pool, &outsider).await.is_empty());
+
+    let all: Vec<i32> = ProjectService::list(&db.pool, &dev)
+        .await
+        .unwrap()
+        .iter()
+        .map(|p| p.id)
+        .collect();
+    assert_eq!(all, vec![web]);
+}
+
+#[tokio::test]
+async fn test_require_access_hides_other_projects() {
+    let db = TestDb::new().await;
+    let project = create_project(&db.pool, "API").await;
+    let admin = create_user(&db.pool, "admin@example.com", true).await;
+    let dev = create_user(&db.pool, "dev@example.com", false).await;
+    let outsider = create_user(&db.pool, "outsider@example.com", false).await;
+    add_member(&db.pool, project, &dev, ProjectRole::Member).await;
+
+    for user in [&admin, &dev] {
+        ProjectMemberService::require_access(&db.pool, user, project)
+            .await
+            .expect("Members and admins can see the project");
+    }
+
+    // Indistinguishable from a project that doesn't exist
+    let result = ProjectMemberService::require_access(&db.pool, &outsider, project).await;
+    match result {
+        Err(AppError::NotFound(msg)) => {
+            assert_eq!(msg, format!("Project with id {} not found", project))
+        }
+        other => panic!("Expected not found, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_add_and_remove_members() {
+    let db = TestDb::new().await;
+    let project = create_project(&db.pool, "API").await;
+    let dev = create_user(&db.pool, "dev@example.com", false).await;
+    let lead = create_user(&db.pool, "lead@example.com", false).await;
+    add_member(&db.pool, project, &lead, ProjectRole::Admin).await;
+    add_member(&db.pool, project, &dev, ProjectRole::Member).await;
+
+    let members = ProjectMemberService::list(&db.pool, project).await.unwrap();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].email, "lead@example.com");
+    assert_eq!(members[0].role, ProjectRole::Admin);
+    assert_eq!(members[1].email, "dev@example.com");
+    assert_eq!(members[1].role, ProjectRole::Member);
+
+    let result = ProjectMemberService::add(
+        &db.pool,
+        project,
+        AddProjectMember {
+            user_id: dev.id,
+            role: ProjectRole::Admin,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+
+    let result = ProjectMemberService::add(
+        &db.pool,
+        project,
+        AddProjectMember {
+            user_id: 9999,
+            role: ProjectRole::Member,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    ProjectMemberService::remove(&db.pool, project, dev.id)
+        .await
+        .unwrap();
+    assert!(visible_projects(&db.pool, &dev).await.is_empty());
+
+    let result = ProjectMemberService::remove(&db.pool, project, dev.id).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_only_project_admins_manage_members() {
+    let db = TestDb::new().await;
+    let project = create_project(&db.pool, "API").await;
+    let admin = create_user(&db.pool, "admin@example.com", true).await;
+    let lead = create_user(&db.pool, "lead@example.com", false).await;
+    let dev = create_user(&db.pool, "dev@example.com", false).await;
+    let outsider = create_user(&db.pool, "outsider@example.com", false).await;
+    add_member(&db.pool, project, &lead, ProjectRole::Admin).await;
+    add_member(&db.pool, project, &dev, ProjectRole::Member).await;
+
+    for user in [&admin, &lead] {
+        ProjectMemberService::require_admin(&db.pool, user, project)
+            .await
+            .expect("Admins and project admins manage members");
+    }
+
+    let result = ProjectMemberService::require_admin(&db.pool, &dev, project).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    let result = ProjectMemberService::require_admin(&db.pool, &outsider, project).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
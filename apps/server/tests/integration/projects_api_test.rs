@@ -5,13 +5,23 @@
 
 use actix_session::{storage::CookieSessionStore, SessionMiddleware};
 use actix_web::{cookie::Key, test, web, App};
-use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
+use chrono::Utc;
+use rustrak::config::{Config, DatabaseConfig, DigestConfig, RateLimitConfig};
+use rustrak::digest::worker::process_event;
+use rustrak::error::AppError;
+use rustrak::ingest::{store_event, EventMetadata};
+use rustrak::models::{CreateProject, Project, UpdateProject};
 use rustrak::routes;
+use rustrak::services::ProjectService;
+use serde_json::json;
 use sqlx::PgPool;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
 use testcontainers::{runners::AsyncRunner, ContainerAsync};
 use testcontainers_modules::postgres::Postgres;
 
+use crate::common::statements::StatementCounter;
+
 /// Test database container with connection pool
 struct TestDb {
     #[allow(dead_code)]
@@ -64,18 +74,38 @@ fn create_test_config() -> Config {
             acquire_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             max_lifetime: Duration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: Duration::from_millis(500),
+            statement_timeout: Duration::from_secs(30),
         },
         rate_limit: RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: None,
+        compress_stored_events: false,
+        ingest: rustrak::config::IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -193,3 +223,475 @@ async fn test_delete_project_not_found() {
 async fn test_list_projects_with_data() {
     // This test requires proper session cookie handling
 }
+
+// =============================================================================
+// Project Keys Tests
+// =============================================================================
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_list_project_keys_masked() {
+    // This test requires proper session cookie handling
+}
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_create_project_key_reveals_dsn() {
+    // This test requires proper session cookie handling
+}
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_deactivate_project_key() {
+    // This test requires proper session cookie handling
+}
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_delete_project_key_not_found() {
+    // This test requires proper session cookie handling
+}
+
+// =============================================================================
+// Allowed Domains Tests
+// =============================================================================
+
+fn allowed_domains_update(domains: &[&str], confirm_wildcard: bool) -> UpdateProject {
+    UpdateProject {
+        name: None,
+        team_id: None,
+        allowed_domains: Some(domains.iter().map(|d| d.to_string()).collect()),
+        confirm_wildcard,
+        auto_resolve_days: None,
+        in_app_include: None,
+        in_app_exclude: None,
+        default_issue_sort: None,
+        timezone: None,
+    }
+}
+
+#[actix_web::test]
+async fn test_update_project_allowed_domains() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Browser App").await;
+    assert!(project.allowed_domains.is_empty());
+
+    let updated = ProjectService::update(
+        &db.pool,
+        project.id,
+        allowed_domains_update(&["App.Example.com", "*.example.org"], false),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        updated.allowed_domains,
+        ["app.example.com", "*.example.org"]
+    );
+
+    let response = updated.to_response(&[], None);
+    assert_eq!(
+        serde_json::to_value(&response).unwrap()["allowed_domains"],
+        json!(["app.example.com", "*.example.org"])
+    );
+
+    // Updating other fields keeps the list, an empty list clears it
+    let renamed = ProjectService::update(
+        &db.pool,
+        project.id,
+        UpdateProject {
+            name: Some("Browser App 2".to_string()),
+            team_id: None,
+            allowed_domains: None,
+            confirm_wildcard: false,
+            auto_resolve_days: None,
+            in_app_include: None,
+            in_app_exclude: None,
+            default_issue_sort: None,
+            timezone: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(renamed.allowed_domains.len(), 2);
+
+    let cleared = ProjectService::update(&db.pool, project.id, allowed_domains_update(&[], false))
+        .await
+        .unwrap();
+    assert!(cleared.allowed_domains.is_empty());
+}
+
+#[actix_web::test]
+async fn test_update_project_allowed_domains_validation() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Validated App").await;
+
+    let err = ProjectService::update(
+        &db.pool,
+        project.id,
+        allowed_domains_update(&["https://example.com/"], false),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        AppError::InvalidField {
+            field: "allowed_domains",
+            ..
+        }
+    ));
+
+    // A bare wildcard needs confirming
+    let err = ProjectService::update(&db.pool, project.id, allowed_domains_update(&["*"], false))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AppError::InvalidField { .. }));
+    let unchanged = ProjectService::get_by_id(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert!(unchanged.allowed_domains.is_empty());
+
+    let updated =
+        ProjectService::update(&db.pool, project.id, allowed_domains_update(&["*"], true))
+            .await
+            .unwrap();
+    assert_eq!(updated.allowed_domains, ["*"]);
+}
+
+// =============================================================================
+// Onboarding Tests
+// =============================================================================
+
+async fn create_test_project(pool: &PgPool, name: &str) -> Project {
+    ProjectService::create(
+        pool,
+        CreateProject {
+            name: name.to_string(),
+            slug: None,
+            team_id: None,
+        },
+    )
+    .await
+    .expect("Failed to create test project")
+}
+
+/// Stores and digests an event sent by the Python SDK
+async fn digest_event(pool: &PgPool, project_id: i32) {
+    let temp_dir = TempDir::new().unwrap();
+    let event_id = uuid::Uuid::new_v4().simple().to_string();
+    let event = json!({
+        "event_id": event_id,
+        "level": "error",
+        "message": "First!",
+        "sdk": {"name": "sentry.python", "version": "2.19.0"}
+    });
+    store_event(
+        temp_dir.path(),
+        &event_id,
+        &serde_json::to_vec(&event).unwrap(),
+        false,
+    )
+    .await
+    .unwrap();
+
+    let metadata = EventMetadata {
+        event_id,
+        project_id,
+        ingested_at: Utc::now(),
+        remote_addr: None,
+        spool_id: None,
+    };
+    process_event(
+        pool,
+        &metadata,
+        temp_dir.path(),
+        &create_test_config().rate_limit,
+        &DigestConfig::default(),
+    )
+    .await
+    .expect("Failed to digest event");
+}
+
+#[actix_web::test]
+async fn test_onboarding_unauthorized() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Onboarding Auth").await;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(create_test_config()))
+            .wrap(
+                SessionMiddleware::builder(CookieSessionStore::default(), test_session_key())
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .configure(routes::projects::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/projects/{}/onboarding", project.id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_onboarding_before_first_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Onboarding Empty").await;
+
+    let onboarding = ProjectService::onboarding(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert!(!onboarding.received_first_event);
+    assert!(onboarding.first_event_at.is_none());
+    assert!(onboarding.sdk.is_none());
+
+    let json = serde_json::to_value(&onboarding).unwrap();
+    assert_eq!(json["sdk"], serde_json::Value::Null);
+
+    // Times out without an event
+    let started = Instant::now();
+    let onboarding =
+        ProjectService::wait_for_first_event(&db.pool, project.id, Duration::from_millis(600))
+            .await
+            .unwrap();
+    assert!(!onboarding.received_first_event);
+    assert!(started.elapsed() >= Duration::from_millis(600));
+
+    let missing = ProjectService::onboarding(&db.pool, project.id + 1000).await;
+    assert!(matches!(
+        missing,
+        Err(rustrak::error::AppError::NotFound(_))
+    ));
+}
+
+#[actix_web::test]
+async fn test_onboarding_after_first_event() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Onboarding Done").await;
+    digest_event(&db.pool, project.id).await;
+
+    let onboarding = ProjectService::onboarding(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert!(onboarding.received_first_event);
+    assert!(onboarding.first_event_at.is_some());
+    let sdk = onboarding.sdk.unwrap();
+    assert_eq!(sdk.name, "sentry.python");
+    assert_eq!(sdk.version, "2.19.0");
+}
+
+#[actix_web::test]
+async fn test_onboarding_wait_resolves_when_event_arrives() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Onboarding Wait").await;
+
+    let pool = db.pool.clone();
+    let project_id = project.id;
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        digest_event(&pool, project_id).await;
+    });
+
+    let started = Instant::now();
+    let onboarding =
+        ProjectService::wait_for_first_event(&db.pool, project.id, Duration::from_secs(30))
+            .await
+            .unwrap();
+    assert!(onboarding.received_first_event);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+// =============================================================================
+// Project Stats Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_project_stats_in_one_query() {
+    let db = TestDb::new().await;
+    let busy = create_test_project(&db.pool, "Stats Busy").await;
+    let resolved = create_test_project(&db.pool, "Stats Resolved").await;
+    let empty = create_test_project(&db.pool, "Stats Empty").await;
+
+    // Two events of one open issue
+    digest_event(&db.pool, busy.id).await;
+    digest_event(&db.pool, busy.id).await;
+    // A recent event whose issue is resolved
+    digest_event(&db.pool, resolved.id).await;
+    sqlx::query("UPDATE issues SET is_resolved = true WHERE project_id = $1")
+        .bind(resolved.id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    // Events older than a day are not counted
+    digest_event(&db.pool, busy.id).await;
+    sqlx::query(
+        "UPDATE events SET digested_at = NOW() - INTERVAL '2 days' \
+         WHERE id = (SELECT id FROM events WHERE project_id = $1 \
+                     ORDER BY digest_order DESC LIMIT 1)",
+    )
+    .bind(busy.id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+
+    let counter = StatementCounter::default();
+    let stats = {
+        let _guard = tracing::subscriber::set_default(counter.clone());
+        ProjectService::stats_for_projects(&db.pool, &[busy.id, resolved.id, empty.id])
+            .await
+            .unwrap()
+    };
+
+    // One query for the whole page, not one per project
+    assert_eq!(counter.count(), 1);
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[&busy.id].open_issue_count, 1);
+    assert_eq!(stats[&busy.id].events_24h, 2);
+    assert_eq!(stats[&resolved.id].open_issue_count, 0);
+    assert_eq!(stats[&resolved.id].events_24h, 1);
+    assert_eq!(stats[&empty.id].open_issue_count, 0);
+    assert_eq!(stats[&empty.id].events_24h, 0);
+}
+
+#[actix_web::test]
+async fn test_project_stats_omitted_unless_included() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Stats Omitted").await;
+
+    let json = serde_json::to_value(project.to_response(&[], None)).unwrap();
+    assert!(json.get("stats").is_none());
+
+    let stats = ProjectService::stats_for_projects(&db.pool, &[project.id])
+        .await
+        .unwrap();
+    let mut response = project.to_response(&[], None);
+    response.stats = stats.get(&project.id).copied();
+    let json = serde_json::to_value(response).unwrap();
+    assert_eq!(
+        json["stats"],
+        json!({ "open_issue_count": 0, "events_24h": 0 })
+    );
+}
+
+// =============================================================================
+// Recount Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_recount_repairs_drifted_counters() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Recount").await;
+    for _ in 0..3 {
+        digest_event(&db.pool, project.id).await;
+    }
+
+    // Counters off after a manual edit
+    sqlx::query("UPDATE issues SET stored_event_count = 10, digested_event_count = 1")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE projects SET stored_event_count = 0, digested_event_count = 99")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    let recount = ProjectService::recount(&db.pool, project.id).await.unwrap();
+    assert_eq!(recount.before.stored_event_count, 0);
+    assert_eq!(recount.before.digested_event_count, 99);
+    assert_eq!(recount.after.stored_event_count, 3);
+    assert_eq!(recount.after.digested_event_count, 3);
+    assert_eq!(recount.issues.len(), 1);
+    assert_eq!(recount.issues[0].before.stored_event_count, 10);
+    assert_eq!(recount.issues[0].before.digested_event_count, 1);
+    assert_eq!(recount.issues[0].after.stored_event_count, 3);
+    assert_eq!(recount.issues[0].after.digested_event_count, 3);
+
+    let stored = ProjectService::get_by_id(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert_eq!(stored.stored_event_count, 3);
+    assert_eq!(stored.digested_event_count, 3);
+
+    // Nothing left to fix
+    let again = ProjectService::recount(&db.pool, project.id).await.unwrap();
+    assert_eq!(again.before, again.after);
+    assert!(again.issues.is_empty());
+
+    let missing = ProjectService::recount(&db.pool, project.id + 1000).await;
+    assert!(matches!(missing, Err(AppError::NotFound(_))));
+}
+
+// =============================================================================
+// In-App Rules Tests
+// =============================================================================
+
+fn in_app_update(include: Option<&[&str]>, exclude: Option<&[&str]>) -> UpdateProject {
+    let prefixes = |p: &[&str]| p.iter().map(|p| p.to_string()).collect();
+    UpdateProject {
+        name: None,
+        team_id: None,
+        allowed_domains: None,
+        confirm_wildcard: false,
+        auto_resolve_days: None,
+        in_app_include: include.map(prefixes),
+        in_app_exclude: exclude.map(prefixes),
+        default_issue_sort: None,
+        timezone: None,
+    }
+}
+
+#[actix_web::test]
+async fn test_update_project_in_app_rules() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Django App").await;
+    assert!(project.in_app_include.is_empty());
+    assert!(project.in_app_exclude.is_empty());
+
+    let updated = ProjectService::update(
+        &db.pool,
+        project.id,
+        in_app_update(Some(&[" shop.", ""]), Some(&["django.", "celery."])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.in_app_include, ["shop."]);
+    assert_eq!(updated.in_app_exclude, ["django.", "celery."]);
+
+    let response = serde_json::to_value(updated.to_response(&[], None)).unwrap();
+    assert_eq!(response["in_app_exclude"], json!(["django.", "celery."]));
+
+    // Each list is replaced on its own
+    let updated = ProjectService::update(&db.pool, project.id, in_app_update(None, Some(&[])))
+        .await
+        .unwrap();
+    assert_eq!(updated.in_app_include, ["shop."]);
+    assert!(updated.in_app_exclude.is_empty());
+}
+
+#[actix_web::test]
+async fn test_update_project_in_app_rules_validation() {
+    let db = TestDb::new().await;
+    let project = create_test_project(&db.pool, "Too Many Prefixes").await;
+
+    let many: Vec<String> = (0..51).map(|i| format!("vendor{}.", i)).collect();
+    let many: Vec<&str> = many.iter().map(String::as_str).collect();
+    let err = ProjectService::update(&db.pool, project.id, in_app_update(None, Some(&many)))
+        .await
+        .unwrap_err();
+    assert_eq!(err.field(), Some("in_app_exclude"));
+
+    let long = "a".repeat(256);
+    let err = ProjectService::update(&db.pool, project.id, in_app_update(Some(&[&long]), None))
+        .await
+        .unwrap_err();
+    assert_eq!(err.field(), Some("in_app_include"));
+
+    let project = ProjectService::get_by_id(&db.pool, project.id)
+        .await
+        .unwrap();
+    assert!(project.in_app_include.is_empty());
+    assert!(project.in_app_exclude.is_empty());
+}
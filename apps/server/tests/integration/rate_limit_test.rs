@@ -4,9 +4,9 @@
 
 use actix_web::{test, web, App};
 use chrono::{Duration, Utc};
-use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
+use rustrak::config::{BodyLimitConfig, Config, DatabaseConfig, RateLimitConfig};
 use rustrak::routes;
-use rustrak::services::ProjectService;
+use rustrak::services::{ProjectKeyService, ProjectService};
 use serde_json::json;
 use sqlx::PgPool;
 use std::time::Duration as StdDuration;
@@ -67,13 +67,32 @@ fn create_test_config(rate_limit: RateLimitConfig) -> Config {
             acquire_timeout: StdDuration::from_secs(5),
             idle_timeout: StdDuration::from_secs(60),
             max_lifetime: StdDuration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: StdDuration::from_millis(500),
+            statement_timeout: StdDuration::from_secs(30),
         },
         rate_limit,
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: Some("/tmp/rustrak_test_ratelimit".to_string()),
+        compress_stored_events: false,
+        ingest: rustrak::config::IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -84,21 +103,26 @@ fn default_rate_limit_config() -> RateLimitConfig {
         max_events_per_hour: 10000,
         max_events_per_project_per_minute: 500,
         max_events_per_project_per_hour: 5000,
+        algorithm: rustrak::config::RateLimitAlgorithm::default(),
     }
 }
 
-/// Creates a test project and returns its sentry_key
+/// Creates a test project and returns its default sentry_key
 async fn create_test_project(pool: &PgPool, name: &str) -> (i32, String) {
     let project = ProjectService::create(
         pool,
         rustrak::models::CreateProject {
             name: name.to_string(),
             slug: None,
+            team_id: None,
         },
     )
     .await
     .expect("Failed to create test project");
-    (project.id, project.sentry_key.to_string())
+    let keys = ProjectKeyService::list(pool, project.id)
+        .await
+        .expect("Failed to list project keys");
+    (project.id, keys[0].key.to_string())
 }
 
 /// Sets project quota exceeded until the given time
@@ -151,7 +175,7 @@ async fn test_rate_limit_project_exceeded_returns_429() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -198,7 +222,7 @@ async fn test_rate_limit_project_expired_allows_request() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -238,7 +262,7 @@ async fn test_rate_limit_installation_exceeded_returns_429() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -273,7 +297,7 @@ async fn test_rate_limit_response_body() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -331,7 +355,7 @@ async fn test_rate_limit_429_has_cors_headers() {
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
             .wrap(cors)
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -377,7 +401,7 @@ async fn test_no_rate_limit_allows_request() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -418,7 +442,7 @@ async fn test_rate_limit_affects_only_specific_project() {
         App::new()
             .app_data(web::Data::new(db.pool.clone()))
             .app_data(web::Data::new(config))
-            .configure(routes::ingest::configure),
+            .configure(routes::ingest::configure(BodyLimitConfig::DEFAULT_INGEST)),
     )
     .await;
 
@@ -0,0 +1,261 @@
+//! Integration tests for saved searches
+//!
+//! Tests storing, listing and expanding saved issue searches with a real
+//! PostgreSQL database.
+
+use rustrak::error::AppError;
+use rustrak::models::{CreateProject, CreateSavedSearch, CreateUserRequest, User};
+use rustrak::pagination::{IssueFilter, SortOrder};
+use rustrak::services::{ProjectService, SavedSearchService, UsersService};
+use serde_json::json;
+use sqlx::PgPool;
+use testcontainers::{runners::AsyncRunner, ContainerAsync};
+use testcontainers_modules::postgres::Postgres;
+
+/// Test database container with connection pool
+struct TestDb {
+    #[allow(dead_code)]
+    container: ContainerAsync<Postgres>,
+    pool: PgPool,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let container = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start PostgreSQL container");
+
+        let host = container.get_host().await.expect("Failed to get host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get port");
+
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+
+        let pool = PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        // Enable pgcrypto extension for gen_random_uuid()
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pgcrypto")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable pgcrypto extension");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        TestDb { container, pool }
+    }
+}
+
+async fn create_user(pool: &PgPool, email: &str, is_admin: bool) -> User {
+    UsersService::create_user(
+        pool,
+        &CreateUserRequest {
+            email: email.to_string(),
+            password: "password123".to_string(),
+        },
+        is_admin,
+    )
+    .await
+    .expect("Failed to create user")
+}
+
+async fn create_project(pool: &PgPool, name: &str) -> i32 {
+    ProjectService::create(
+        pool,
+        CreateProject {
+            name: name.to_string(),
+            slug: None,
+            team_id: None,
+        },
+    )
+    .await
+    .expect("Failed to create project")
+    .id
+}
+
+fn search_input(name: &str, shared: bool, query: serde_json::Value) -> CreateSavedSearch {
+    CreateSavedSearch {
+        name: name.to_string(),
+        project_id: None,
+        shared,
+        query,
+    }
+}
+
+#[tokio::test]
+async fn test_saved_search_round_trip() {
+    let db = TestDb::new().await;
+    let user = create_user(&db.pool, "user@example.com", false).await;
+
+    let created = SavedSearchService::create(
+        &db.pool,
+        &user,
+        search_input(
+            "Resolved by activity",
+            false,
+            json!({ "filter": "resolved", "sort": "last_seen", "per_page": 50 }),
+        ),
+    )
+    .await
+    .expect("Failed to create saved search");
+    assert!(!created.is_shared());
+
+    let searches = SavedSearchService::list(&db.pool, user.id, None)
+        .await
+        .unwrap();
+    assert_eq!(searches.len(), 1);
+    assert_eq!(searches[0].name, "Resolved by activity");
+    assert_eq!(searches[0].query["per_page"], 50);
+
+    // Private searches are invisible to other users
+    let other = create_user(&db.pool, "other@example.com", false).await;
+    assert!(SavedSearchService::list(&db.pool, other.id, None)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        SavedSearchService::get_visible(&db.pool, created.id, other.id).await,
+        Err(AppError::NotFound(_))
+    ));
+
+    SavedSearchService::delete(&db.pool, created.id, &user)
+        .await
+        .unwrap();
+    assert!(SavedSearchService::list(&db.pool, user.id, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_saved_search_expansion_and_override() {
+    let db = TestDb::new().await;
+    let user = create_user(&db.pool, "user@example.com", false).await;
+
+    let search = SavedSearchService::create(
+        &db.pool,
+        &user,
+        search_input(
+            "Muted, oldest first",
+            false,
+            json!({ "filter": "muted", "order": "asc", "per_page": 50 }),
+        ),
+    )
+    .await
+    .unwrap();
+
+    // Only the shortcut given: stored parameters apply
+    let query =
+        SavedSearchService::expand(&search, &format!("saved_search={}", search.id)).unwrap();
+    assert_eq!(query.filter, IssueFilter::Muted);
+    assert_eq!(query.order, SortOrder::Asc);
+    assert_eq!(query.per_page, Some(50));
+    assert_eq!(query.sort, None);
+    assert_eq!(query.page, 1);
+
+    // Explicit parameters override stored ones
+    let query = SavedSearchService::expand(
+        &search,
+        &format!("saved_search={}&filter=all&page=3", search.id),
+    )
+    .unwrap();
+    assert_eq!(query.filter, IssueFilter::All);
+    assert_eq!(query.page, 3);
+    assert_eq!(query.order, SortOrder::Asc);
+    assert_eq!(query.per_page, Some(50));
+}
+
+#[tokio::test]
+async fn test_saved_search_rejects_invalid_parameters() {
+    let db = TestDb::new().await;
+    let user = create_user(&db.pool, "user@example.com", false).await;
+
+    for query in [
+        json!({ "filter": "bogus" }),
+        json!({ "page": "first" }),
+        json!({ "sort": ["last_seen"] }),
+        json!({ "saved_search": 1 }),
+        json!(["filter", "all"]),
+    ] {
+        let result =
+            SavedSearchService::create(&db.pool, &user, search_input("Bad", false, query)).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
+
+#[tokio::test]
+async fn test_shared_searches_writable_only_by_admins() {
+    let db = TestDb::new().await;
+    let admin = create_user(&db.pool, "admin@example.com", true).await;
+    let user = create_user(&db.pool, "user@example.com", false).await;
+
+    let result = SavedSearchService::create(
+        &db.pool,
+        &user,
+        search_input("Team view", true, json!({ "filter": "all" })),
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    let shared = SavedSearchService::create(
+        &db.pool,
+        &admin,
+        search_input("Team view", true, json!({ "filter": "all" })),
+    )
+    .await
+    .unwrap();
+    assert!(shared.is_shared());
+
+    // Visible to everyone, deletable only by admins
+    let visible = SavedSearchService::list(&db.pool, user.id, None)
+        .await
+        .unwrap();
+    assert_eq!(visible.len(), 1);
+
+    let result = SavedSearchService::delete(&db.pool, shared.id, &user).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    SavedSearchService::delete(&db.pool, shared.id, &admin)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_list_saved_searches_by_project() {
+    let db = TestDb::new().await;
+    let user = create_user(&db.pool, "user@example.com", false).await;
+    let api = create_project(&db.pool, "API").await;
+    let web = create_project(&db.pool, "Web").await;
+
+    for (name, project_id) in [
+        ("Global", None),
+        ("API only", Some(api)),
+        ("Web only", Some(web)),
+    ] {
+        SavedSearchService::create(
+            &db.pool,
+            &user,
+            CreateSavedSearch {
+                name: name.to_string(),
+                project_id,
+                shared: false,
+                query: json!({}),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let searches = SavedSearchService::list(&db.pool, user.id, Some(api))
+        .await
+        .unwrap();
+    let names: Vec<&str> = searches.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["API only", "Global"]);
+}
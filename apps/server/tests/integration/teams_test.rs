@@ -0,0 +1,335 @@
+//! Integration tests for teams
+//!
+//! Tests team CRUD, project filtering by team, default channel inheritance
+//! and delete constraints with a real PostgreSQL database.
+
+use rustrak::error::AppError;
+use rustrak::models::{
+    ChannelType, CreateNotificationChannel, CreateProject, CreateTeam, CreateUserRequest,
+    UpdateProject, UpdateTeam,
+};
+use rustrak::pagination::SortOrder;
+use rustrak::services::{AlertService, ProjectService, TeamService, UsersService};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use testcontainers::{runners::AsyncRunner, ContainerAsync};
+use testcontainers_modules::postgres::Postgres;
+
+/// Test database container with connection pool
+struct TestDb {
+    #[allow(dead_code)]
+    container: ContainerAsync<Postgres>,
+    pool: PgPool,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let container = Postgres::default()
+            .start()
+            .await
+            .expect("Failed to start PostgreSQL container");
+
+        let host = container.get_host().await.expect("Failed to get host");
+        let port = container
+            .get_host_port_ipv4(5432)
+            .await
+            .expect("Failed to get port");
+
+        let database_url = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+
+        let pool = PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        // Enable pgcrypto extension for gen_random_uuid()
+        sqlx::query("CREATE EXTENSION IF NOT EXISTS pgcrypto")
+            .execute(&pool)
+            .await
+            .expect("Failed to enable pgcrypto extension");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        TestDb { container, pool }
+    }
+}
+
+async fn create_team(pool: &PgPool, name: &str, default_channel_ids: Vec<i32>) -> i32 {
+    TeamService::create(
+        pool,
+        CreateTeam {
+            name: name.to_string(),
+            slug: None,
+            default_channel_ids,
+        },
+    )
+    .await
+    .expect("Failed to create team")
+    .id
+}
+
+async fn create_project(pool: &PgPool, name: &str, team_id: Option<i32>) -> i32 {
+    ProjectService::create(
+        pool,
+        CreateProject {
+            name: name.to_string(),
+            slug: None,
+            team_id,
+        },
+    )
+    .await
+    .expect("Failed to create project")
+    .id
+}
+
+async fn create_channel(pool: &PgPool, name: &str) -> i32 {
+    AlertService::create_channel(
+        pool,
+        CreateNotificationChannel {
+            name: name.to_string(),
+            channel_type: ChannelType::Webhook,
+            config: json!({ "url": "https://example.com/webhook" }),
+            is_enabled: true,
+        },
+    )
+    .await
+    .expect("Failed to create channel")
+    .id
+}
+
+#[tokio::test]
+async fn test_team_crud() {
+    let db = TestDb::new().await;
+
+    let team = TeamService::create(
+        &db.pool,
+        CreateTeam {
+            name: "Backend Team".to_string(),
+            slug: None,
+            default_channel_ids: vec![],
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(team.slug, "backend-team");
+
+    let fetched = TeamService::get_by_slug(&db.pool, "backend-team")
+        .await
+        .unwrap();
+    assert_eq!(fetched.id, team.id);
+
+    let updated = TeamService::update(
+        &db.pool,
+        team.id,
+        UpdateTeam {
+            name: Some("Platform".to_string()),
+            default_channel_ids: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.name, "Platform");
+    assert_eq!(updated.slug, "backend-team");
+
+    let duplicate = TeamService::create(
+        &db.pool,
+        CreateTeam {
+            name: "Platform".to_string(),
+            slug: None,
+            default_channel_ids: vec![],
+        },
+    )
+    .await;
+    assert!(matches!(duplicate, Err(AppError::Conflict(_))));
+
+    TeamService::delete(&db.pool, team.id, None).await.unwrap();
+    assert!(matches!(
+        TeamService::get_by_id(&db.pool, team.id).await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_default_channels_for_teams() {
+    let db = TestDb::new().await;
+    let slack = create_channel(&db.pool, "Slack").await;
+    let pager = create_channel(&db.pool, "Pager").await;
+
+    let backend = create_team(&db.pool, "Backend", vec![pager, slack]).await;
+    let frontend = create_team(&db.pool, "Frontend", vec![slack]).await;
+    let mobile = create_team(&db.pool, "Mobile", vec![]).await;
+
+    let channels = TeamService::default_channels_for_teams(&db.pool, &[backend, frontend, mobile])
+        .await
+        .unwrap();
+    let mut backend_channels = vec![slack, pager];
+    backend_channels.sort();
+    assert_eq!(channels[&backend], backend_channels);
+    assert_eq!(channels[&frontend], vec![slack]);
+    assert!(!channels.contains_key(&mobile));
+
+    // Only the requested teams are loaded
+    let channels = TeamService::default_channels_for_teams(&db.pool, &[frontend])
+        .await
+        .unwrap();
+    assert_eq!(channels.len(), 1);
+}
+
+#[tokio::test]
+async fn test_list_projects_filtered_by_team() {
+    let db = TestDb::new().await;
+
+    let backend = create_team(&db.pool, "Backend", vec![]).await;
+    let frontend = create_team(&db.pool, "Frontend", vec![]).await;
+
+    let api = create_project(&db.pool, "API", Some(backend)).await;
+    let worker = create_project(&db.pool, "Worker", Some(backend)).await;
+    create_project(&db.pool, "Web", Some(frontend)).await;
+    create_project(&db.pool, "Unassigned", None).await;
+    let admin = UsersService::create_user(
+        &db.pool,
+        &CreateUserRequest {
+            email: "admin@example.com".to_string(),
+            password: "password123".to_string(),
+        },
+        true,
+    )
+    .await
+    .unwrap();
+
+    let (projects, total) =
+        ProjectService::list_offset(&db.pool, &admin, Some(backend), SortOrder::Asc, 1, 20)
+            .await
+            .unwrap();
+    assert_eq!(total, 2);
+    let ids: Vec<i32> = projects.iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![api, worker]);
+
+    let (_, total) = ProjectService::list_offset(&db.pool, &admin, None, SortOrder::Desc, 1, 20)
+        .await
+        .unwrap();
+    assert_eq!(total, 4);
+}
+
+#[tokio::test]
+async fn test_project_team_assignment() {
+    let db = TestDb::new().await;
+
+    let team = create_team(&db.pool, "Backend", vec![]).await;
+    let project = create_project(&db.pool, "API", None).await;
+
+    let updated = ProjectService::update(
+        &db.pool,
+        project,
+        UpdateProject {
+            name: None,
+            team_id: Some(Some(team)),
+            allowed_domains: None,
+            confirm_wildcard: false,
+            auto_resolve_days: None,
+            in_app_include: None,
+            in_app_exclude: None,
+            default_issue_sort: None,
+            timezone: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.team_id, Some(team));
+
+    // `null` takes the project out of its team; a missing field leaves it
+    let update = |team_id: Value| -> UpdateProject {
+        serde_json::from_value(json!({ "team_id": team_id })).unwrap()
+    };
+    let kept = ProjectService::update(
+        &db.pool,
+        project,
+        serde_json::from_value(json!({})).unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(kept.team_id, Some(team));
+    let cleared = ProjectService::update(&db.pool, project, update(Value::Null))
+        .await
+        .unwrap();
+    assert_eq!(cleared.team_id, None);
+    assert_eq!(update(json!(team)).team_id, Some(Some(team)));
+
+    let missing = ProjectService::create(
+        &db.pool,
+        CreateProject {
+            name: "Orphan".to_string(),
+            slug: None,
+            team_id: Some(9999),
+        },
+    )
+    .await;
+    assert!(matches!(missing, Err(AppError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_new_project_inherits_team_default_channels() {
+    let db = TestDb::new().await;
+
+    let slack = create_channel(&db.pool, "Team Slack").await;
+    let pager = create_channel(&db.pool, "Team Pager").await;
+    let team = create_team(&db.pool, "Backend", vec![slack, pager]).await;
+
+    let project = create_project(&db.pool, "API", Some(team)).await;
+
+    let rules = AlertService::list_rules(&db.pool, project).await.unwrap();
+    assert_eq!(rules.len(), 1);
+    let mut channels = AlertService::get_rule_channels(&db.pool, rules[0].id)
+        .await
+        .unwrap();
+    channels.sort();
+    assert_eq!(channels, vec![slack, pager]);
+
+    // Channels are copied: changing team defaults leaves the project alone
+    TeamService::update(
+        &db.pool,
+        team,
+        UpdateTeam {
+            name: None,
+            default_channel_ids: Some(vec![slack]),
+        },
+    )
+    .await
+    .unwrap();
+    let channels = AlertService::get_rule_channels(&db.pool, rules[0].id)
+        .await
+        .unwrap();
+    assert_eq!(channels.len(), 2);
+
+    // Projects without a team, or in a team without defaults, get no rules
+    let plain = create_project(&db.pool, "Plain", None).await;
+    assert!(AlertService::list_rules(&db.pool, plain)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_delete_team_with_projects_requires_reassign() {
+    let db = TestDb::new().await;
+
+    let old = create_team(&db.pool, "Old", vec![]).await;
+    let new = create_team(&db.pool, "New", vec![]).await;
+    let project = create_project(&db.pool, "API", Some(old)).await;
+
+    let result = TeamService::delete(&db.pool, old, None).await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+
+    let result = TeamService::delete(&db.pool, old, Some(old)).await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+
+    let result = TeamService::delete(&db.pool, old, Some(9999)).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+
+    TeamService::delete(&db.pool, old, Some(new)).await.unwrap();
+
+    let moved = ProjectService::get_by_id(&db.pool, project).await.unwrap();
+    assert_eq!(moved.team_id, Some(new));
+}
@@ -4,6 +4,8 @@
 
 use actix_web::{test, web, App};
 use rustrak::config::{Config, DatabaseConfig, RateLimitConfig};
+use rustrak::error::AppError;
+use rustrak::models::UpdateAuthToken;
 use rustrak::routes;
 use rustrak::services::AuthTokenService;
 use serde_json::{json, Value};
@@ -65,18 +67,38 @@ fn create_test_config() -> Config {
             acquire_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             max_lifetime: Duration::from_secs(300),
+            allow_dirty_migrations: false,
+            connect_retries: 0,
+            connect_backoff: Duration::from_millis(500),
+            statement_timeout: Duration::from_secs(30),
         },
         rate_limit: RateLimitConfig {
             max_events_per_minute: 1000,
             max_events_per_hour: 10000,
             max_events_per_project_per_minute: 500,
             max_events_per_project_per_hour: 5000,
+            algorithm: rustrak::config::RateLimitAlgorithm::default(),
         },
+        api_rate_limit: rustrak::config::ApiRateLimitConfig::default(),
         security: rustrak::config::SecurityConfig {
             ssl_proxy: false,
             session_secret_key: None,
+            trust_proxy_headers: false,
+            session_store: rustrak::config::SessionBackend::Cookie,
+            environment: rustrak::config::Environment::Development,
+            oidc: None,
+            password_login_enabled: true,
+            cors_allowed_origins: Vec::new(),
         },
         ingest_dir: None,
+        compress_stored_events: false,
+        ingest: rustrak::config::IngestConfig::default(),
+        digest: rustrak::config::DigestConfig::default(),
+        pagination: rustrak::config::PaginationConfig::default(),
+        body_limits: rustrak::config::BodyLimitConfig::default(),
+        retention: rustrak::config::RetentionConfig::default(),
+        alerts: rustrak::config::AlertConfig::default(),
+        self_monitoring_project_id: None,
     }
 }
 
@@ -120,9 +142,10 @@ async fn test_list_tokens_empty() {
     let resp = test::call_service(&app, req).await;
     assert!(resp.status().is_success());
 
-    let body: Vec<Value> = test::read_body_json(resp).await;
+    let body: Value = test::read_body_json(resp).await;
     // Should have at least the token we used for auth
-    assert!(!body.is_empty());
+    assert!(!body["items"].as_array().unwrap().is_empty());
+    assert_eq!(body["page"], 1);
 }
 
 #[actix_web::test]
@@ -191,10 +214,10 @@ async fn test_list_tokens_masks_token_values() {
     let resp = test::call_service(&app, req).await;
     assert!(resp.status().is_success());
 
-    let body: Vec<Value> = test::read_body_json(resp).await;
+    let body: Value = test::read_body_json(resp).await;
 
     // The token value should be masked (show token_prefix with first 8 chars + "...")
-    for token_obj in &body {
+    for token_obj in body["items"].as_array().unwrap() {
         let token_prefix = token_obj["token_prefix"].as_str().unwrap();
         // Masked tokens should be "xxxxxxxx..." format (first 8 chars + "...")
         assert!(token_prefix.ends_with("..."));
@@ -202,6 +225,62 @@ async fn test_list_tokens_masks_token_values() {
     }
 }
 
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_list_tokens_flat_format() {
+    let db = TestDb::new().await;
+    let token = create_test_token(&db.pool).await;
+    let config = create_test_config();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::tokens::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/tokens?format=flat")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: Vec<Value> = test::read_body_json(resp).await;
+    assert!(!body.is_empty());
+}
+
+#[actix_web::test]
+async fn test_list_tokens_paginated_newest_first() {
+    let db = TestDb::new().await;
+    for i in 0..5 {
+        AuthTokenService::create(
+            &db.pool,
+            rustrak::models::CreateAuthToken {
+                description: Some(format!("Token {}", i)),
+            },
+        )
+        .await
+        .expect("Failed to create token");
+    }
+
+    let (first, total) = AuthTokenService::list_paginated(&db.pool, 1, 2)
+        .await
+        .unwrap();
+    assert_eq!(total, 5);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0].description.as_deref(), Some("Token 4"));
+    assert_eq!(first[1].description.as_deref(), Some("Token 3"));
+
+    let (last, _) = AuthTokenService::list_paginated(&db.pool, 3, 2)
+        .await
+        .unwrap();
+    assert_eq!(last.len(), 1);
+    assert_eq!(last[0].description.as_deref(), Some("Token 0"));
+}
+
 // =============================================================================
 // Create Token Tests
 // =============================================================================
@@ -368,6 +447,124 @@ async fn test_create_token_is_valid_hex() {
     assert!(new_token.chars().all(|c| !c.is_uppercase()));
 }
 
+// =============================================================================
+// Update Token Tests
+// =============================================================================
+
+#[actix_web::test]
+async fn test_update_token_description() {
+    let db = TestDb::new().await;
+    let token = AuthTokenService::create(
+        &db.pool,
+        rustrak::models::CreateAuthToken {
+            description: Some("Old name".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create token");
+
+    let updated = AuthTokenService::update(
+        &db.pool,
+        token.id,
+        UpdateAuthToken {
+            description: Some("  New name ".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.description.as_deref(), Some("New name"));
+    assert_eq!(updated.token, token.token);
+
+    // Leaving the description out keeps it
+    let unchanged =
+        AuthTokenService::update(&db.pool, token.id, UpdateAuthToken { description: None })
+            .await
+            .unwrap();
+    assert_eq!(unchanged.description.as_deref(), Some("New name"));
+
+    // An empty description clears it
+    let cleared = AuthTokenService::update(
+        &db.pool,
+        token.id,
+        UpdateAuthToken {
+            description: Some(String::new()),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(cleared.description, None);
+
+    let too_long = AuthTokenService::update(
+        &db.pool,
+        token.id,
+        UpdateAuthToken {
+            description: Some("x".repeat(256)),
+        },
+    )
+    .await;
+    assert!(matches!(too_long, Err(AppError::Validation(_))));
+}
+
+#[actix_web::test]
+async fn test_update_token_not_found() {
+    let db = TestDb::new().await;
+
+    let result = AuthTokenService::update(
+        &db.pool,
+        99999,
+        UpdateAuthToken {
+            description: Some("Missing".to_string()),
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[actix_web::test]
+#[ignore = "Session cookies not preserved in actix test framework - use E2E tests"]
+async fn test_update_token_endpoint() {
+    let db = TestDb::new().await;
+    let auth_token = create_test_token(&db.pool).await;
+    let config = create_test_config();
+
+    let token = AuthTokenService::create(
+        &db.pool,
+        rustrak::models::CreateAuthToken {
+            description: Some("Before".to_string()),
+        },
+    )
+    .await
+    .expect("Failed to create token");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db.pool.clone()))
+            .app_data(web::Data::new(config))
+            .configure(routes::tokens::configure),
+    )
+    .await;
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/api/tokens/{}", token.id))
+        .insert_header(("Authorization", format!("Bearer {}", auth_token)))
+        .set_json(json!({ "description": "After" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body["description"], "After");
+    assert!(body.get("token").is_none());
+
+    let req = test::TestRequest::patch()
+        .uri("/api/tokens/99999")
+        .insert_header(("Authorization", format!("Bearer {}", auth_token)))
+        .set_json(json!({ "description": "After" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
 // =============================================================================
 // Delete Token Tests
 // =============================================================================
@@ -2,5 +2,7 @@
 //!
 //! This file serves as the entry point for all integration tests.
 
+// Each test binary uses a different subset of the shared helpers
+#[allow(dead_code)]
 mod common;
 mod integration;
@@ -0,0 +1,98 @@
+//! Unit tests for the alert digest buffer
+
+use chrono::Utc;
+use rustrak::models::{DigestIssue, IssueInfo, IssuePriority, ProjectInfo};
+use rustrak::services::alert_digest::{AlertDigestBuffer, Pushed};
+
+fn project() -> ProjectInfo {
+    ProjectInfo {
+        id: 1,
+        name: "Digest".to_string(),
+        slug: "digest".to_string(),
+    }
+}
+
+fn issue(title: &str) -> DigestIssue {
+    DigestIssue {
+        issue: IssueInfo {
+            id: title.to_string(),
+            short_id: "DIGEST-1".to_string(),
+            title: title.to_string(),
+            level: Some("error".to_string()),
+            first_seen: Utc::now(),
+            last_seen: Utc::now(),
+            event_count: 1,
+            is_unhandled: false,
+            priority: IssuePriority::Medium,
+        },
+        issue_url: format!("http://localhost:3000/issues/{}", title),
+    }
+}
+
+#[test]
+fn test_push_opens_then_joins_window() {
+    let buffer = AlertDigestBuffer::new();
+
+    assert!(matches!(
+        buffer.push(1, project(), issue("first")),
+        Pushed::Opened(_)
+    ));
+    assert!(matches!(
+        buffer.push(1, project(), issue("second")),
+        Pushed::Queued
+    ));
+    assert!(matches!(
+        buffer.push(2, project(), issue("other")),
+        Pushed::Opened(_)
+    ));
+    assert_eq!(buffer.len(1), 2);
+    assert_eq!(buffer.total(), 3);
+
+    assert_eq!(buffer.take(1).unwrap().issues.len(), 2);
+    assert_eq!(buffer.total(), 1);
+    assert!(buffer.take(1).is_none());
+}
+
+#[test]
+fn test_full_buffer_sends_window_early() {
+    let buffer = AlertDigestBuffer::with_capacity(2);
+    let Pushed::Opened(window) = buffer.push(1, project(), issue("first")) else {
+        panic!("Expected a new window");
+    };
+    buffer.push(2, project(), issue("other"));
+
+    // The rule's window comes back with the new issue, and frees its room
+    let Pushed::Full(digest) = buffer.push(1, project(), issue("second")) else {
+        panic!("Expected the window back");
+    };
+    assert_eq!(digest.window, window);
+    let titles: Vec<_> = digest
+        .issues
+        .iter()
+        .map(|i| i.issue.title.as_str())
+        .collect();
+    assert_eq!(titles, vec!["first", "second"]);
+    assert_eq!(buffer.len(1), 0);
+    assert_eq!(buffer.total(), 1);
+
+    // The next issue opens a new window, which the old timer leaves alone
+    let Pushed::Opened(next) = buffer.push(1, project(), issue("third")) else {
+        panic!("Expected a new window");
+    };
+    assert_ne!(next, window);
+    assert!(buffer.take_window(1, window).is_none());
+    assert_eq!(buffer.take_window(1, next).unwrap().issues.len(), 1);
+}
+
+#[test]
+fn test_full_buffer_sends_issue_of_rule_without_window() {
+    let buffer = AlertDigestBuffer::with_capacity(1);
+    buffer.push(1, project(), issue("first"));
+
+    let Pushed::Full(digest) = buffer.push(2, project(), issue("other")) else {
+        panic!("Expected the issue back");
+    };
+    assert_eq!(digest.issues.len(), 1);
+    assert_eq!(buffer.len(2), 0);
+    assert_eq!(buffer.total(), 1);
+}
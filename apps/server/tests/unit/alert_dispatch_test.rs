@@ -0,0 +1,61 @@
+//! Unit tests for the alert dispatch concurrency limit
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustrak::services::AlertDispatchLimiter;
+
+#[tokio::test]
+async fn test_limiter_waits_for_free_slot() {
+    let limiter = AlertDispatchLimiter::new(2);
+    assert_eq!(limiter.concurrency(), 2);
+
+    let first = limiter.acquire().await;
+    let _second = limiter.acquire().await;
+    assert_eq!(limiter.available(), 0);
+
+    // A third delivery has to wait
+    let third = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+    assert!(third.is_err());
+
+    drop(first);
+    let third = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+    assert!(third.is_ok());
+}
+
+#[test]
+fn test_limiter_allows_at_least_one() {
+    let limiter = AlertDispatchLimiter::new(0);
+    assert_eq!(limiter.concurrency(), 1);
+    assert_eq!(limiter.available(), 1);
+}
+
+#[tokio::test]
+async fn test_limiter_bounds_concurrent_tasks() {
+    let limiter = Arc::new(AlertDispatchLimiter::new(3));
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..20)
+        .map(|_| {
+            let limiter = limiter.clone();
+            let running = running.clone();
+            let peak = peak.clone();
+            tokio::spawn(async move {
+                let _permit = limiter.acquire().await;
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+    assert_eq!(limiter.available(), 3);
+}
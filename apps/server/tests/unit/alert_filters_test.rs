@@ -0,0 +1,95 @@
+//! Unit tests for alert rule filters
+//!
+//! Tests which issues pass a rule's `min_level`.
+
+use chrono::Utc;
+use rustrak::models::{AlertFilters, Issue, IssuePriority};
+use uuid::Uuid;
+
+fn issue(level: Option<&str>) -> Issue {
+    Issue {
+        id: Uuid::new_v4(),
+        project_id: 1,
+        digest_order: 1,
+        first_seen: Utc::now(),
+        last_seen: Utc::now(),
+        digested_event_count: 1,
+        stored_event_count: 1,
+        last_event_order: 1,
+        last_event_id: None,
+        calculated_type: "TimeoutError".to_string(),
+        calculated_value: "upstream timed out".to_string(),
+        transaction: "/checkout".to_string(),
+        last_frame_filename: String::new(),
+        last_frame_module: String::new(),
+        last_frame_function: String::new(),
+        level: level.map(str::to_string),
+        platform: Some("python".to_string()),
+        is_resolved: false,
+        is_muted: false,
+        deleted_at: None,
+        resolved_at: None,
+        resolved_by: None,
+        auto_resolved: false,
+        resolved_commit: None,
+        resolved_pr_url: None,
+        exception_chain_length: 1,
+        is_unhandled: false,
+        sample_rate: None,
+        regression_count: 0,
+        priority: IssuePriority::Medium,
+        priority_is_manual: false,
+        spike_window_start: None,
+        spike_base_event_count: 0,
+    }
+}
+
+fn min_level(level: &str) -> AlertFilters {
+    AlertFilters {
+        min_level: Some(level.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_min_level_filters_out_less_severe_issues() {
+    let filters = min_level("error");
+    assert!(!filters.matches(&issue(Some("warning")), None));
+    assert!(!filters.matches(&issue(Some("info")), None));
+    assert!(filters.matches(&issue(Some("error")), None));
+    assert!(filters.matches(&issue(Some("fatal")), None));
+}
+
+#[test]
+fn test_min_level_orders_every_level() {
+    let levels = ["debug", "info", "warning", "error", "fatal"];
+    for (i, min) in levels.iter().enumerate() {
+        for (j, level) in levels.iter().enumerate() {
+            assert_eq!(
+                min_level(min).matches(&issue(Some(level)), None),
+                j >= i,
+                "{} with min_level {}",
+                level,
+                min
+            );
+        }
+    }
+}
+
+#[test]
+fn test_issue_without_level_counts_as_error() {
+    assert!(min_level("error").matches(&issue(None), None));
+    assert!(!min_level("fatal").matches(&issue(None), None));
+}
+
+#[test]
+fn test_unknown_issue_level_never_matches() {
+    assert!(!min_level("debug").matches(&issue(Some("critical")), None));
+}
+
+#[test]
+fn test_no_min_level_matches_any_level() {
+    let filters = AlertFilters::default();
+    assert!(filters.matches(&issue(Some("debug")), None));
+    assert!(filters.matches(&issue(Some("warning")), None));
+}
@@ -0,0 +1,58 @@
+//! Unit tests for the auth/token rate limiter
+//!
+//! Tests fixed-window counting per scope and client IP.
+
+use rustrak::config::ApiRateLimitConfig;
+use rustrak::middleware::rate_limit::{ApiRateLimiter, RateLimitScope};
+
+fn limiter(auth: u32, tokens: u32) -> ApiRateLimiter {
+    ApiRateLimiter::new(ApiRateLimitConfig {
+        auth_requests_per_minute: auth,
+        token_requests_per_minute: tokens,
+    })
+}
+
+#[test]
+fn test_requests_over_limit_rejected() {
+    let limiter = limiter(3, 3);
+
+    for _ in 0..3 {
+        assert!(limiter.check(RateLimitScope::Auth, "10.0.0.1").is_ok());
+    }
+
+    let retry_after = limiter
+        .check(RateLimitScope::Auth, "10.0.0.1")
+        .expect_err("fourth request should be limited");
+    assert!((1..=60).contains(&retry_after));
+}
+
+#[test]
+fn test_ips_counted_separately() {
+    let limiter = limiter(1, 1);
+
+    assert!(limiter.check(RateLimitScope::Auth, "10.0.0.1").is_ok());
+    assert!(limiter.check(RateLimitScope::Auth, "10.0.0.1").is_err());
+    assert!(limiter.check(RateLimitScope::Auth, "10.0.0.2").is_ok());
+}
+
+#[test]
+fn test_scopes_counted_separately() {
+    let limiter = limiter(1, 2);
+
+    assert!(limiter.check(RateLimitScope::Auth, "10.0.0.1").is_ok());
+    assert!(limiter.check(RateLimitScope::Auth, "10.0.0.1").is_err());
+
+    // Exhausting auth leaves the token limit untouched
+    assert!(limiter.check(RateLimitScope::Tokens, "10.0.0.1").is_ok());
+    assert!(limiter.check(RateLimitScope::Tokens, "10.0.0.1").is_ok());
+    assert!(limiter.check(RateLimitScope::Tokens, "10.0.0.1").is_err());
+}
+
+#[test]
+fn test_zero_limit_disables() {
+    let limiter = limiter(0, 0);
+
+    for _ in 0..100 {
+        assert!(limiter.check(RateLimitScope::Auth, "10.0.0.1").is_ok());
+    }
+}
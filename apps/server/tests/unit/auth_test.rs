@@ -1,8 +1,14 @@
 //! Unit tests for authentication parsing
 //!
-//! Tests X-Sentry-Auth header parsing.
+//! Tests X-Sentry-Auth header parsing and the ingest credentials found in
+//! requests.
 
-use rustrak::auth::sentry_auth::parse_sentry_auth_header;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use actix_web::ResponseError;
+use rustrak::auth::sentry_auth::{ingest_auth_error, parse_sentry_auth_header, SentryCredentials};
+use rustrak::error::AppError;
+use uuid::Uuid;
 
 // =============================================================================
 // Basic Parsing Tests (moved from inline tests)
@@ -99,7 +105,7 @@ fn test_parse_sentry_auth_header_missing_value() {
     let result = parse_sentry_auth_header(header);
 
     // No "=" means no value, should be skipped
-    assert!(result.get("sentry_key").is_none());
+    assert!(!result.contains_key("sentry_key"));
 }
 
 #[test]
@@ -166,3 +172,145 @@ fn test_parse_real_world_javascript_sdk() {
         Some(&"sentry.javascript.browser/7.0.0".to_string())
     );
 }
+
+// =============================================================================
+// Ingest Credentials Tests
+// =============================================================================
+
+const KEY: &str = "9ec79c33ec9942ab8353589fcb2e04dc";
+const OTHER_KEY: &str = "0f1e2d3c4b5a69788796a5b4c3d2e1f0";
+
+fn credentials(req: TestRequest) -> Result<SentryCredentials, AppError> {
+    SentryCredentials::from_request(&req.to_http_request())
+}
+
+#[test]
+fn test_credentials_from_query() {
+    let creds = credentials(TestRequest::default().uri(&format!(
+        "/api/1/envelope/?sentry_version=7&sentry_key={}&sentry_client=raven-js/3.0",
+        KEY
+    )))
+    .unwrap();
+
+    assert_eq!(creds.sentry_key, Uuid::parse_str(KEY).unwrap());
+    assert_eq!(creds.sentry_version.as_deref(), Some("7"));
+    assert_eq!(creds.sentry_client.as_deref(), Some("raven-js/3.0"));
+}
+
+#[test]
+fn test_credentials_from_sentry_auth_header_ignores_secret() {
+    let creds = credentials(TestRequest::default().insert_header((
+        "X-Sentry-Auth",
+        format!(
+            "Sentry sentry_version=5, sentry_client=raven-python/6.0, sentry_key={}, sentry_secret=abc123",
+            KEY
+        ),
+    )))
+    .unwrap();
+
+    assert_eq!(
+        creds,
+        SentryCredentials {
+            sentry_key: Uuid::parse_str(KEY).unwrap(),
+            sentry_version: Some("5".to_string()),
+            sentry_client: Some("raven-python/6.0".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_credentials_from_authorization_header() {
+    let creds = credentials(TestRequest::default().insert_header((
+        "Authorization",
+        format!("Sentry sentry_key={},sentry_version=7", KEY),
+    )))
+    .unwrap();
+    assert_eq!(creds.sentry_key, Uuid::parse_str(KEY).unwrap());
+
+    // Other schemes belong to a proxy, the query still counts
+    let creds = credentials(
+        TestRequest::default()
+            .uri(&format!("/api/1/envelope/?sentry_key={}", KEY))
+            .insert_header(("Authorization", "Basic dXNlcjpwYXNz")),
+    )
+    .unwrap();
+    assert_eq!(creds.sentry_key, Uuid::parse_str(KEY).unwrap());
+}
+
+#[test]
+fn test_credentials_header_wins_when_placements_agree() {
+    let creds = credentials(
+        TestRequest::default()
+            // Hyphens don't make a different key
+            .uri(&format!(
+                "/api/1/envelope/?sentry_key={}&sentry_version=6",
+                Uuid::parse_str(KEY).unwrap().hyphenated()
+            ))
+            .insert_header((
+                "X-Sentry-Auth",
+                format!("Sentry sentry_key={}, sentry_version=7", KEY),
+            ))
+            .insert_header(("Authorization", format!("Sentry sentry_key={}", KEY))),
+    )
+    .unwrap();
+
+    assert_eq!(creds.sentry_key, Uuid::parse_str(KEY).unwrap());
+    assert_eq!(creds.sentry_version.as_deref(), Some("7"));
+}
+
+#[test]
+fn test_credentials_conflicting_placements_rejected() {
+    let err = credentials(
+        TestRequest::default()
+            .uri(&format!("/api/1/envelope/?sentry_key={}", OTHER_KEY))
+            .insert_header(("X-Sentry-Auth", format!("Sentry sentry_key={}", KEY))),
+    )
+    .unwrap_err();
+    assert!(matches!(err, AppError::Validation(_)));
+    assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+
+    let err = credentials(
+        TestRequest::default()
+            .insert_header(("X-Sentry-Auth", format!("Sentry sentry_key={}", KEY)))
+            .insert_header(("Authorization", format!("Sentry sentry_key={}", OTHER_KEY))),
+    )
+    .unwrap_err();
+    assert!(matches!(err, AppError::Validation(_)));
+}
+
+#[test]
+fn test_credentials_missing_or_malformed() {
+    let unauthorized = |req| matches!(credentials(req), Err(AppError::Unauthorized(_)));
+
+    assert!(unauthorized(TestRequest::default()));
+    assert!(unauthorized(
+        TestRequest::default().uri("/api/1/envelope/?sentry_key=not-a-uuid")
+    ));
+    assert!(unauthorized(
+        TestRequest::default().insert_header(("X-Sentry-Auth", format!("Bearer {}", KEY)))
+    ));
+    assert!(unauthorized(TestRequest::default().insert_header((
+        "X-Sentry-Auth",
+        "Sentry sentry_version=7, sentry_secret=abc"
+    ))));
+    // A malformed header isn't rescued by a valid query key
+    assert!(unauthorized(
+        TestRequest::default()
+            .uri(&format!("/api/1/envelope/?sentry_key={}", KEY))
+            .insert_header(("X-Sentry-Auth", "Sentry sentry_key=abc"))
+    ));
+}
+
+#[test]
+fn test_ingest_auth_error_adds_challenge_to_401() {
+    let response = ingest_auth_error(AppError::Unauthorized("nope".into())).error_response();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response.headers().get("WWW-Authenticate").unwrap(),
+        "Sentry"
+    );
+
+    let response = ingest_auth_error(AppError::Validation("conflict".into())).error_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(response.headers().get("WWW-Authenticate").is_none());
+}
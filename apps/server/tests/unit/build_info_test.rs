@@ -0,0 +1,29 @@
+//! Unit tests for the embedded build information
+
+use rustrak::build_info::{BUILD_INFO, BUILT_AT, COMMIT, VERSION};
+
+#[test]
+fn test_build_info_fields_are_set() {
+    assert!(!VERSION.is_empty());
+    assert!(!COMMIT.is_empty());
+    assert!(!BUILT_AT.is_empty());
+}
+
+#[test]
+fn test_version_is_semver() {
+    let version = semver::Version::parse(VERSION).expect("Version should be semver");
+    assert_eq!(version.to_string(), VERSION);
+}
+
+#[test]
+fn test_built_at_is_rfc3339() {
+    assert!(chrono::DateTime::parse_from_rfc3339(BUILT_AT).is_ok());
+}
+
+#[test]
+fn test_build_info_serializes_all_fields() {
+    let json = serde_json::to_value(BUILD_INFO).unwrap();
+    assert_eq!(json["version"], VERSION);
+    assert_eq!(json["commit"], COMMIT);
+    assert_eq!(json["built_at"], BUILT_AT);
+}
@@ -0,0 +1,75 @@
+//! Unit tests for keeping compression off streaming responses
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use actix_web::body::MessageBody;
+use actix_web::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use actix_web::middleware::{from_fn, Compress};
+use actix_web::test::{call_service, init_service, TestRequest};
+use actix_web::{web, App, HttpResponse};
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use rustrak::middleware::compress::{is_streaming, skip_streaming_compression};
+
+/// An event stream that stays open after its first event, like the issue stream
+async fn events() -> HttpResponse {
+    let first = stream::once(async {
+        Ok::<_, Infallible>(Bytes::from_static(b"event: issue\ndata: {}\n\n"))
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .streaming(first.chain(stream::pending()))
+}
+
+async fn json() -> HttpResponse {
+    HttpResponse::Ok().json(vec!["compressible"; 100])
+}
+
+#[test]
+fn test_is_streaming() {
+    assert!(is_streaming("text/event-stream"));
+    assert!(is_streaming("application/x-ndjson; charset=utf-8"));
+    assert!(is_streaming("Text/Event-Stream"));
+    assert!(!is_streaming("application/json"));
+    assert!(!is_streaming("text/plain"));
+}
+
+#[actix_web::test]
+async fn test_event_stream_is_not_buffered_by_compression() {
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(skip_streaming_compression))
+            .wrap(Compress::default())
+            .route("/events", web::get().to(events))
+            .route("/json", web::get().to(json)),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/events")
+        .insert_header((ACCEPT_ENCODING, "gzip"))
+        .to_request();
+    let resp = call_service(&app, req).await;
+    assert_eq!(resp.headers().get(CONTENT_ENCODING).unwrap(), "identity");
+
+    // The first event arrives while the stream is still open
+    let mut body = Box::pin(resp.into_body());
+    let chunk = tokio::time::timeout(
+        Duration::from_secs(1),
+        std::future::poll_fn(|cx| body.as_mut().poll_next(cx)),
+    )
+    .await
+    .expect("Event held back by compression")
+    .expect("Stream ended early")
+    .unwrap();
+    assert_eq!(chunk, Bytes::from_static(b"event: issue\ndata: {}\n\n"));
+
+    // Other responses are still compressed
+    let req = TestRequest::get()
+        .uri("/json")
+        .insert_header((ACCEPT_ENCODING, "gzip"))
+        .to_request();
+    let resp = call_service(&app, req).await;
+    assert_eq!(resp.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+}
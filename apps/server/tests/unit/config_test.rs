@@ -4,8 +4,14 @@
 //!
 //! Note: These tests modify global environment variables and must run serially.
 
-use rustrak::config::RateLimitConfig;
+use rustrak::config::{
+    AlertConfig, ApiRateLimitConfig, BodyLimitConfig, DatabaseConfig, DigestConfig, Environment,
+    FsyncPolicy, IngestConfig, IngestMaintenance, IngestMode, OidcConfig, OutboundHttpConfig,
+    PaginationConfig, RateLimitAlgorithm, RateLimitConfig, RetentionConfig, SecurityConfig,
+    SessionBackend,
+};
 use serial_test::serial;
+use std::time::Duration;
 
 // =============================================================================
 // Rate Limit Config Tests
@@ -19,6 +25,7 @@ fn test_rate_limit_config_defaults() {
     std::env::remove_var("MAX_EVENTS_PER_HOUR");
     std::env::remove_var("MAX_EVENTS_PER_PROJECT_PER_MINUTE");
     std::env::remove_var("MAX_EVENTS_PER_PROJECT_PER_HOUR");
+    std::env::remove_var("RATE_LIMIT_ALGORITHM");
 
     let config = RateLimitConfig::from_env();
 
@@ -26,6 +33,38 @@ fn test_rate_limit_config_defaults() {
     assert_eq!(config.max_events_per_hour, 10000);
     assert_eq!(config.max_events_per_project_per_minute, 500);
     assert_eq!(config.max_events_per_project_per_hour, 5000);
+    assert_eq!(config.algorithm, RateLimitAlgorithm::Trailing);
+}
+
+#[test]
+#[serial]
+fn test_rate_limit_config_algorithm() {
+    std::env::set_var("RATE_LIMIT_ALGORITHM", "Sliding");
+    assert_eq!(
+        RateLimitConfig::from_env().algorithm,
+        RateLimitAlgorithm::Sliding
+    );
+
+    std::env::set_var("RATE_LIMIT_ALGORITHM", "fixed");
+    assert_eq!(
+        RateLimitConfig::from_env().algorithm,
+        RateLimitAlgorithm::Fixed
+    );
+
+    std::env::set_var("RATE_LIMIT_ALGORITHM", "trailing");
+    assert_eq!(
+        RateLimitConfig::from_env().algorithm,
+        RateLimitAlgorithm::Trailing
+    );
+
+    // Unknown algorithms fall back to trailing
+    std::env::set_var("RATE_LIMIT_ALGORITHM", "token_bucket");
+    assert_eq!(
+        RateLimitConfig::from_env().algorithm,
+        RateLimitAlgorithm::Trailing
+    );
+
+    std::env::remove_var("RATE_LIMIT_ALGORITHM");
 }
 
 #[test]
@@ -99,3 +138,820 @@ fn test_rate_limit_config_negative_values() {
     // Clean up
     std::env::remove_var("MAX_EVENTS_PER_MINUTE");
 }
+
+// =============================================================================
+// Ingest Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_ingest_config_defaults() {
+    std::env::remove_var("INGEST_MODE");
+    std::env::remove_var("INGEST_QUEUE_SIZE");
+    std::env::remove_var("INGEST_AUTH_CACHE_TTL_SECS");
+    std::env::remove_var("ALLOWED_DOMAINS_STRICT");
+    std::env::remove_var("INGEST_FSYNC");
+    std::env::remove_var("INGEST_FSYNC_INTERVAL_MS");
+    std::env::remove_var("TUNNEL_ALLOWED_HOSTS");
+    std::env::remove_var("INGEST_MAINTENANCE");
+
+    let config = IngestConfig::from_env();
+
+    assert_eq!(config.mode, IngestMode::Sync);
+    assert_eq!(config.queue_size, IngestConfig::DEFAULT_QUEUE_SIZE);
+    assert_eq!(config.auth_cache_ttl, Duration::from_secs(30));
+    assert!(!config.allowed_domains_strict);
+    assert_eq!(config.fsync, FsyncPolicy::Always);
+    assert_eq!(config.fsync_interval, Duration::from_millis(1000));
+    assert!(config.tunnel_allowed_hosts.is_empty());
+    assert_eq!(config.maintenance, IngestMaintenance::Accept);
+}
+
+#[test]
+#[serial]
+fn test_ingest_config_maintenance() {
+    std::env::set_var("INGEST_MAINTENANCE", "Drop");
+    assert_eq!(
+        IngestConfig::from_env().maintenance,
+        IngestMaintenance::Drop
+    );
+
+    std::env::set_var("INGEST_MAINTENANCE", " reject ");
+    assert_eq!(
+        IngestConfig::from_env().maintenance,
+        IngestMaintenance::Reject
+    );
+
+    // Unknown modes keep accepting events
+    std::env::set_var("INGEST_MAINTENANCE", "pause");
+    assert_eq!(
+        IngestConfig::from_env().maintenance,
+        IngestMaintenance::Accept
+    );
+
+    std::env::remove_var("INGEST_MAINTENANCE");
+}
+
+#[test]
+#[serial]
+fn test_ingest_config_fsync() {
+    std::env::set_var("INGEST_FSYNC", "Batch");
+    std::env::set_var("INGEST_FSYNC_INTERVAL_MS", "250");
+    let config = IngestConfig::from_env();
+    assert_eq!(config.fsync, FsyncPolicy::Batch);
+    assert_eq!(config.fsync_interval, Duration::from_millis(250));
+
+    std::env::set_var("INGEST_FSYNC", "never");
+    assert_eq!(IngestConfig::from_env().fsync, FsyncPolicy::Never);
+
+    // Unknown policies fall back to always, a zero interval to 1ms
+    std::env::set_var("INGEST_FSYNC", "sometimes");
+    std::env::set_var("INGEST_FSYNC_INTERVAL_MS", "0");
+    let config = IngestConfig::from_env();
+    assert_eq!(config.fsync, FsyncPolicy::Always);
+    assert_eq!(config.fsync_interval, Duration::from_millis(1));
+
+    std::env::remove_var("INGEST_FSYNC");
+    std::env::remove_var("INGEST_FSYNC_INTERVAL_MS");
+}
+
+#[test]
+#[serial]
+fn test_ingest_config_allowed_domains_strict() {
+    std::env::set_var("ALLOWED_DOMAINS_STRICT", "true");
+    assert!(IngestConfig::from_env().allowed_domains_strict);
+
+    std::env::set_var("ALLOWED_DOMAINS_STRICT", "1");
+    assert!(IngestConfig::from_env().allowed_domains_strict);
+
+    std::env::set_var("ALLOWED_DOMAINS_STRICT", "yes");
+    assert!(!IngestConfig::from_env().allowed_domains_strict);
+
+    std::env::remove_var("ALLOWED_DOMAINS_STRICT");
+}
+
+#[test]
+#[serial]
+fn test_ingest_config_tunnel_allowed_hosts() {
+    std::env::set_var(
+        "TUNNEL_ALLOWED_HOSTS",
+        " Errors.Example.com, ,rustrak.internal",
+    );
+    assert_eq!(
+        IngestConfig::from_env().tunnel_allowed_hosts,
+        ["errors.example.com", "rustrak.internal"]
+    );
+
+    std::env::remove_var("TUNNEL_ALLOWED_HOSTS");
+}
+
+#[test]
+#[serial]
+fn test_ingest_config_auth_cache_ttl() {
+    std::env::set_var("INGEST_AUTH_CACHE_TTL_SECS", "5");
+    assert_eq!(
+        IngestConfig::from_env().auth_cache_ttl,
+        Duration::from_secs(5)
+    );
+
+    // 0 turns the cache off outside of database outages
+    std::env::set_var("INGEST_AUTH_CACHE_TTL_SECS", "0");
+    assert_eq!(IngestConfig::from_env().auth_cache_ttl, Duration::ZERO);
+
+    std::env::set_var("INGEST_AUTH_CACHE_TTL_SECS", "soon");
+    assert_eq!(
+        IngestConfig::from_env().auth_cache_ttl,
+        Duration::from_secs(30)
+    );
+
+    std::env::remove_var("INGEST_AUTH_CACHE_TTL_SECS");
+}
+
+#[test]
+#[serial]
+fn test_ingest_config_fast_ack() {
+    std::env::set_var("INGEST_MODE", "FAST_ACK");
+    std::env::set_var("INGEST_QUEUE_SIZE", "50");
+
+    let config = IngestConfig::from_env();
+    assert_eq!(config.mode, IngestMode::FastAck);
+    assert_eq!(config.queue_size, 50);
+
+    // Unknown modes fall back to sync, an empty queue to a single slot
+    std::env::set_var("INGEST_MODE", "eventually");
+    std::env::set_var("INGEST_QUEUE_SIZE", "0");
+
+    let config = IngestConfig::from_env();
+    assert_eq!(config.mode, IngestMode::Sync);
+    assert_eq!(config.queue_size, 1);
+
+    std::env::remove_var("INGEST_MODE");
+    std::env::remove_var("INGEST_QUEUE_SIZE");
+}
+
+// =============================================================================
+// Digest Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_digest_config_defaults() {
+    std::env::remove_var("MAX_STACKTRACE_FRAMES");
+    std::env::remove_var("MAX_BREADCRUMBS");
+    std::env::remove_var("MAX_ISSUE_TITLE_LENGTH");
+    std::env::remove_var("MAX_ISSUE_VALUE_LENGTH");
+
+    let config = DigestConfig::from_env();
+
+    assert_eq!(config.max_stacktrace_frames, 250);
+    assert_eq!(config.max_breadcrumbs, 100);
+    assert_eq!(config.max_issue_title_length, 120);
+    assert_eq!(config.max_issue_value_length, 500);
+}
+
+#[test]
+#[serial]
+fn test_digest_config_custom_and_invalid_values() {
+    std::env::set_var("MAX_STACKTRACE_FRAMES", "50");
+    assert_eq!(DigestConfig::from_env().max_stacktrace_frames, 50);
+
+    // Negative values are not valid usize, fall back to default
+    std::env::set_var("MAX_STACKTRACE_FRAMES", "-1");
+    assert_eq!(DigestConfig::from_env().max_stacktrace_frames, 250);
+
+    // Clean up
+    std::env::remove_var("MAX_STACKTRACE_FRAMES");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_max_breadcrumbs() {
+    std::env::set_var("MAX_BREADCRUMBS", "20");
+    assert_eq!(DigestConfig::from_env().max_breadcrumbs, 20);
+
+    std::env::set_var("MAX_BREADCRUMBS", "lots");
+    assert_eq!(DigestConfig::from_env().max_breadcrumbs, 100);
+
+    // Clean up
+    std::env::remove_var("MAX_BREADCRUMBS");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_merge_duplicate_events() {
+    std::env::remove_var("MERGE_DUPLICATE_EVENTS");
+    assert!(!DigestConfig::from_env().merge_duplicate_events);
+
+    std::env::set_var("MERGE_DUPLICATE_EVENTS", "true");
+    assert!(DigestConfig::from_env().merge_duplicate_events);
+
+    std::env::set_var("MERGE_DUPLICATE_EVENTS", "yes");
+    assert!(!DigestConfig::from_env().merge_duplicate_events);
+
+    // Clean up
+    std::env::remove_var("MERGE_DUPLICATE_EVENTS");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_max_issue_title_length() {
+    std::env::set_var("MAX_ISSUE_TITLE_LENGTH", "80");
+    assert_eq!(DigestConfig::from_env().max_issue_title_length, 80);
+
+    // A zero length would blank every title
+    std::env::set_var("MAX_ISSUE_TITLE_LENGTH", "0");
+    assert_eq!(DigestConfig::from_env().max_issue_title_length, 1);
+
+    std::env::set_var("MAX_ISSUE_TITLE_LENGTH", "wide");
+    assert_eq!(DigestConfig::from_env().max_issue_title_length, 120);
+
+    // Clean up
+    std::env::remove_var("MAX_ISSUE_TITLE_LENGTH");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_max_issue_value_length() {
+    std::env::set_var("MAX_ISSUE_VALUE_LENGTH", "2000");
+    assert_eq!(DigestConfig::from_env().max_issue_value_length, 2000);
+
+    std::env::set_var("MAX_ISSUE_VALUE_LENGTH", "0");
+    assert_eq!(DigestConfig::from_env().max_issue_value_length, 1);
+
+    std::env::set_var("MAX_ISSUE_VALUE_LENGTH", "long");
+    assert_eq!(DigestConfig::from_env().max_issue_value_length, 500);
+
+    std::env::remove_var("MAX_ISSUE_VALUE_LENGTH");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_concurrency() {
+    std::env::remove_var("DIGEST_CONCURRENCY");
+    assert_eq!(DigestConfig::from_env().concurrency, 10);
+
+    std::env::set_var("DIGEST_CONCURRENCY", "32");
+    assert_eq!(DigestConfig::from_env().concurrency, 32);
+
+    // At least one event can always be digested
+    std::env::set_var("DIGEST_CONCURRENCY", "0");
+    assert_eq!(DigestConfig::from_env().concurrency, 1);
+
+    std::env::set_var("DIGEST_CONCURRENCY", "many");
+    assert_eq!(DigestConfig::from_env().concurrency, 10);
+
+    // Clean up
+    std::env::remove_var("DIGEST_CONCURRENCY");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_latency_warn() {
+    std::env::remove_var("DIGEST_LATENCY_WARN_SECS");
+    assert_eq!(DigestConfig::from_env().latency_warn_secs, 60);
+
+    std::env::set_var("DIGEST_LATENCY_WARN_SECS", "5");
+    assert_eq!(DigestConfig::from_env().latency_warn_secs, 5);
+
+    // 0 never warns
+    std::env::set_var("DIGEST_LATENCY_WARN_SECS", "0");
+    assert_eq!(DigestConfig::from_env().latency_warn_secs, 0);
+
+    std::env::set_var("DIGEST_LATENCY_WARN_SECS", "soon");
+    assert_eq!(DigestConfig::from_env().latency_warn_secs, 60);
+
+    // Clean up
+    std::env::remove_var("DIGEST_LATENCY_WARN_SECS");
+}
+
+#[test]
+#[serial]
+fn test_digest_config_priority_spike() {
+    std::env::remove_var("PRIORITY_SPIKE_EVENTS");
+    let config = DigestConfig::from_env();
+    assert_eq!(config.priority_spike_events, 100);
+
+    std::env::set_var("PRIORITY_SPIKE_EVENTS", "500");
+    let config = DigestConfig::from_env();
+    assert_eq!(config.priority_spike_events, 500);
+
+    // 0 never raises; negative counts aren't meaningful
+    std::env::set_var("PRIORITY_SPIKE_EVENTS", "-3");
+    let config = DigestConfig::from_env();
+    assert_eq!(config.priority_spike_events, 0);
+
+    // Clean up
+    std::env::remove_var("PRIORITY_SPIKE_EVENTS");
+}
+
+// =============================================================================
+// Pagination Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_pagination_config_defaults() {
+    std::env::remove_var("DEFAULT_PAGE_SIZE");
+    std::env::remove_var("MAX_PAGE_SIZE");
+    std::env::remove_var("EVENTS_PAGE_SIZE");
+    std::env::remove_var("CURSOR_SIGNING_KEY");
+    std::env::remove_var("ALLOW_UNSIGNED_CURSORS");
+
+    let config = PaginationConfig::from_env();
+
+    assert_eq!(config.default_page_size, 20);
+    assert_eq!(config.max_page_size, 100);
+    assert_eq!(config.events_page_size, 20);
+    assert_eq!(config.cursor_signing_key, None);
+    assert!(config.allow_unsigned_cursors);
+}
+
+#[test]
+#[serial]
+fn test_body_limit_config() {
+    std::env::remove_var("API_MAX_BODY_SIZE");
+    std::env::remove_var("INGEST_MAX_BODY_SIZE");
+
+    let config = BodyLimitConfig::from_env();
+    assert_eq!(config.api, 1024 * 1024);
+    assert_eq!(config.ingest, 20 * 1024 * 1024);
+
+    std::env::set_var("API_MAX_BODY_SIZE", "65536");
+    std::env::set_var("INGEST_MAX_BODY_SIZE", "0");
+    let config = BodyLimitConfig::from_env();
+    assert_eq!(config.api, 65536);
+    // 0 would refuse every event; it falls back to the default
+    assert_eq!(config.ingest, BodyLimitConfig::DEFAULT_INGEST);
+
+    std::env::remove_var("API_MAX_BODY_SIZE");
+    std::env::remove_var("INGEST_MAX_BODY_SIZE");
+}
+
+#[test]
+#[serial]
+fn test_pagination_config_cursor_signing() {
+    std::env::set_var("CURSOR_SIGNING_KEY", "cursor-key");
+    std::env::set_var("ALLOW_UNSIGNED_CURSORS", "false");
+
+    let config = PaginationConfig::from_env();
+    assert_eq!(config.cursor_signing_key.as_deref(), Some("cursor-key"));
+    assert!(!config.allow_unsigned_cursors);
+
+    // An empty key falls back like an unset one
+    std::env::set_var("CURSOR_SIGNING_KEY", "");
+    assert_eq!(PaginationConfig::from_env().cursor_signing_key, None);
+
+    // Clean up
+    std::env::remove_var("CURSOR_SIGNING_KEY");
+    std::env::remove_var("ALLOW_UNSIGNED_CURSORS");
+}
+
+#[test]
+#[serial]
+fn test_pagination_config_default_never_exceeds_max() {
+    std::env::set_var("DEFAULT_PAGE_SIZE", "500");
+    std::env::set_var("MAX_PAGE_SIZE", "50");
+
+    let config = PaginationConfig::from_env();
+    assert_eq!(config.default_page_size, 50);
+    assert_eq!(config.max_page_size, 50);
+
+    // Zero would make every page empty; both are at least 1
+    std::env::set_var("DEFAULT_PAGE_SIZE", "0");
+    std::env::set_var("MAX_PAGE_SIZE", "0");
+
+    let config = PaginationConfig::from_env();
+    assert_eq!(config.default_page_size, 1);
+    assert_eq!(config.max_page_size, 1);
+
+    // Clean up
+    std::env::remove_var("DEFAULT_PAGE_SIZE");
+    std::env::remove_var("MAX_PAGE_SIZE");
+}
+
+#[test]
+#[serial]
+fn test_pagination_config_events_page_size() {
+    std::env::remove_var("MAX_PAGE_SIZE");
+
+    // Follows DEFAULT_PAGE_SIZE unless set
+    std::env::set_var("DEFAULT_PAGE_SIZE", "30");
+    std::env::remove_var("EVENTS_PAGE_SIZE");
+    assert_eq!(PaginationConfig::from_env().events_page_size, 30);
+
+    std::env::set_var("EVENTS_PAGE_SIZE", "50");
+    assert_eq!(PaginationConfig::from_env().events_page_size, 50);
+
+    // Bounded by MAX_PAGE_SIZE like the default
+    std::env::set_var("EVENTS_PAGE_SIZE", "250");
+    assert_eq!(PaginationConfig::from_env().events_page_size, 100);
+
+    // Clean up
+    std::env::remove_var("DEFAULT_PAGE_SIZE");
+    std::env::remove_var("EVENTS_PAGE_SIZE");
+}
+
+// =============================================================================
+// Retention Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_retention_config_issue_trash_days() {
+    std::env::remove_var("ISSUE_TRASH_DAYS");
+    assert_eq!(RetentionConfig::from_env().issue_trash_days, 30);
+
+    std::env::set_var("ISSUE_TRASH_DAYS", "7");
+    assert_eq!(RetentionConfig::from_env().issue_trash_days, 7);
+
+    // Deleted issues always get at least a day in the trash
+    std::env::set_var("ISSUE_TRASH_DAYS", "0");
+    assert_eq!(RetentionConfig::from_env().issue_trash_days, 1);
+
+    std::env::set_var("ISSUE_TRASH_DAYS", "forever");
+    assert_eq!(RetentionConfig::from_env().issue_trash_days, 30);
+
+    // Clean up
+    std::env::remove_var("ISSUE_TRASH_DAYS");
+}
+
+#[test]
+#[serial]
+fn test_retention_config_payload_retention_days() {
+    std::env::remove_var("PAYLOAD_RETENTION_DAYS");
+    assert_eq!(RetentionConfig::from_env().payload_retention_days, None);
+
+    std::env::set_var("PAYLOAD_RETENTION_DAYS", "14");
+    assert_eq!(RetentionConfig::from_env().payload_retention_days, Some(14));
+
+    // Zero and junk keep payloads as long as their events
+    std::env::set_var("PAYLOAD_RETENTION_DAYS", "0");
+    assert_eq!(RetentionConfig::from_env().payload_retention_days, None);
+    std::env::set_var("PAYLOAD_RETENTION_DAYS", "soon");
+    assert_eq!(RetentionConfig::from_env().payload_retention_days, None);
+
+    std::env::remove_var("PAYLOAD_RETENTION_DAYS");
+}
+
+#[test]
+#[serial]
+fn test_alert_config_dispatch_concurrency() {
+    std::env::remove_var("ALERT_DISPATCH_CONCURRENCY");
+    assert_eq!(AlertConfig::from_env().dispatch_concurrency, 10);
+
+    std::env::set_var("ALERT_DISPATCH_CONCURRENCY", "3");
+    assert_eq!(AlertConfig::from_env().dispatch_concurrency, 3);
+
+    // At least one delivery can always run
+    std::env::set_var("ALERT_DISPATCH_CONCURRENCY", "0");
+    assert_eq!(AlertConfig::from_env().dispatch_concurrency, 1);
+
+    std::env::set_var("ALERT_DISPATCH_CONCURRENCY", "lots");
+    assert_eq!(AlertConfig::from_env().dispatch_concurrency, 10);
+
+    // Clean up
+    std::env::remove_var("ALERT_DISPATCH_CONCURRENCY");
+}
+
+#[test]
+#[serial]
+fn test_alert_config_auto_disable_after() {
+    std::env::remove_var("ALERT_CHANNEL_AUTO_DISABLE_AFTER");
+    assert_eq!(AlertConfig::from_env().auto_disable_after, 20);
+
+    std::env::set_var("ALERT_CHANNEL_AUTO_DISABLE_AFTER", "5");
+    assert_eq!(AlertConfig::from_env().auto_disable_after, 5);
+
+    // Zero keeps failing channels enabled
+    std::env::set_var("ALERT_CHANNEL_AUTO_DISABLE_AFTER", "0");
+    assert_eq!(AlertConfig::from_env().auto_disable_after, 0);
+
+    std::env::set_var("ALERT_CHANNEL_AUTO_DISABLE_AFTER", "-1");
+    assert_eq!(AlertConfig::from_env().auto_disable_after, 20);
+
+    // Clean up
+    std::env::remove_var("ALERT_CHANNEL_AUTO_DISABLE_AFTER");
+}
+
+#[test]
+#[serial]
+fn test_alert_config_slack_validate_webhooks() {
+    std::env::remove_var("SLACK_VALIDATE_WEBHOOKS");
+    assert!(!AlertConfig::from_env().slack_validate_webhooks);
+
+    std::env::set_var("SLACK_VALIDATE_WEBHOOKS", "true");
+    assert!(AlertConfig::from_env().slack_validate_webhooks);
+
+    std::env::set_var("SLACK_VALIDATE_WEBHOOKS", "1");
+    assert!(AlertConfig::from_env().slack_validate_webhooks);
+
+    std::env::set_var("SLACK_VALIDATE_WEBHOOKS", "yes");
+    assert!(!AlertConfig::from_env().slack_validate_webhooks);
+
+    // Clean up
+    std::env::remove_var("SLACK_VALIDATE_WEBHOOKS");
+}
+
+#[test]
+#[serial]
+fn test_outbound_http_config() {
+    for name in [
+        "OUTBOUND_HTTP_PROXY",
+        "OUTBOUND_NO_PROXY",
+        "OUTBOUND_TLS_INSECURE",
+        "OUTBOUND_CONNECT_TIMEOUT_SECS",
+        "OUTBOUND_READ_TIMEOUT_SECS",
+        "OUTBOUND_DELIVERY_TIMEOUT_SECS",
+    ] {
+        std::env::remove_var(name);
+    }
+
+    let config = OutboundHttpConfig::from_env();
+    assert_eq!(config.proxy, None);
+    assert_eq!(config.no_proxy, None);
+    assert!(!config.tls_insecure);
+    assert_eq!(config.connect_timeout, Duration::from_secs(10));
+    assert_eq!(config.read_timeout, Duration::from_secs(30));
+    assert_eq!(config.delivery_timeout, Duration::from_secs(60));
+
+    std::env::set_var("OUTBOUND_HTTP_PROXY", "http://proxy.corp:3128");
+    std::env::set_var("OUTBOUND_NO_PROXY", "localhost,.internal");
+    std::env::set_var("OUTBOUND_TLS_INSECURE", "true");
+    std::env::set_var("OUTBOUND_CONNECT_TIMEOUT_SECS", "3");
+    std::env::set_var("OUTBOUND_READ_TIMEOUT_SECS", "5");
+    std::env::set_var("OUTBOUND_DELIVERY_TIMEOUT_SECS", "15");
+
+    let config = AlertConfig::from_env().outbound;
+    assert_eq!(config.proxy.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(config.no_proxy.as_deref(), Some("localhost,.internal"));
+    assert!(config.tls_insecure);
+    assert_eq!(config.connect_timeout, Duration::from_secs(3));
+    assert_eq!(config.read_timeout, Duration::from_secs(5));
+    assert_eq!(config.delivery_timeout, Duration::from_secs(15));
+
+    // Unusable proxies and zero timeouts fall back to the defaults
+    std::env::set_var("OUTBOUND_HTTP_PROXY", "not a url");
+    std::env::set_var("OUTBOUND_CONNECT_TIMEOUT_SECS", "0");
+    let config = OutboundHttpConfig::from_env();
+    assert_eq!(config.proxy, None);
+    assert_eq!(config.connect_timeout, Duration::from_secs(10));
+
+    for name in [
+        "OUTBOUND_HTTP_PROXY",
+        "OUTBOUND_NO_PROXY",
+        "OUTBOUND_TLS_INSECURE",
+        "OUTBOUND_CONNECT_TIMEOUT_SECS",
+        "OUTBOUND_READ_TIMEOUT_SECS",
+        "OUTBOUND_DELIVERY_TIMEOUT_SECS",
+    ] {
+        std::env::remove_var(name);
+    }
+}
+
+// =============================================================================
+// API Rate Limit Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_api_rate_limit_config_defaults() {
+    std::env::remove_var("AUTH_RATE_LIMIT_PER_MINUTE");
+    std::env::remove_var("TOKEN_RATE_LIMIT_PER_MINUTE");
+
+    let config = ApiRateLimitConfig::from_env();
+
+    assert_eq!(config.auth_requests_per_minute, 30);
+    assert_eq!(config.token_requests_per_minute, 60);
+}
+
+#[test]
+#[serial]
+fn test_api_rate_limit_config_custom_and_invalid_values() {
+    std::env::set_var("AUTH_RATE_LIMIT_PER_MINUTE", "5");
+    std::env::set_var("TOKEN_RATE_LIMIT_PER_MINUTE", "-1");
+
+    let config = ApiRateLimitConfig::from_env();
+    assert_eq!(config.auth_requests_per_minute, 5);
+    assert_eq!(config.token_requests_per_minute, 60);
+
+    // Clean up
+    std::env::remove_var("AUTH_RATE_LIMIT_PER_MINUTE");
+    std::env::remove_var("TOKEN_RATE_LIMIT_PER_MINUTE");
+}
+
+// =============================================================================
+// Database Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_database_config_connect_retry_defaults() {
+    std::env::set_var("DATABASE_URL", "postgres://localhost/rustrak");
+    std::env::remove_var("DB_CONNECT_RETRIES");
+    std::env::remove_var("DB_CONNECT_BACKOFF_MS");
+
+    let config = DatabaseConfig::from_env().unwrap();
+    assert_eq!(config.connect_retries, 10);
+    assert_eq!(config.connect_backoff, Duration::from_millis(500));
+
+    std::env::remove_var("DATABASE_URL");
+}
+
+#[test]
+#[serial]
+fn test_database_config_connect_retry_custom_and_invalid_values() {
+    std::env::set_var("DATABASE_URL", "postgres://localhost/rustrak");
+    std::env::set_var("DB_CONNECT_RETRIES", "0");
+    std::env::set_var("DB_CONNECT_BACKOFF_MS", "fast");
+
+    let config = DatabaseConfig::from_env().unwrap();
+    assert_eq!(config.connect_retries, 0);
+    assert_eq!(config.connect_backoff, Duration::from_millis(500));
+
+    // Clean up
+    std::env::remove_var("DATABASE_URL");
+    std::env::remove_var("DB_CONNECT_RETRIES");
+    std::env::remove_var("DB_CONNECT_BACKOFF_MS");
+}
+
+#[test]
+#[serial]
+fn test_database_config_statement_timeout() {
+    std::env::set_var("DATABASE_URL", "postgres://localhost/rustrak");
+    std::env::remove_var("DATABASE_STATEMENT_TIMEOUT_SECS");
+
+    let config = DatabaseConfig::from_env().unwrap();
+    assert_eq!(config.statement_timeout, Duration::from_secs(30));
+
+    // Zero disables the limit
+    std::env::set_var("DATABASE_STATEMENT_TIMEOUT_SECS", "0");
+    let config = DatabaseConfig::from_env().unwrap();
+    assert_eq!(config.statement_timeout, Duration::ZERO);
+
+    std::env::set_var("DATABASE_STATEMENT_TIMEOUT_SECS", "soon");
+    let config = DatabaseConfig::from_env().unwrap();
+    assert_eq!(config.statement_timeout, Duration::from_secs(30));
+
+    std::env::remove_var("DATABASE_URL");
+    std::env::remove_var("DATABASE_STATEMENT_TIMEOUT_SECS");
+}
+
+// =============================================================================
+// Security Config Tests
+// =============================================================================
+
+#[test]
+#[serial]
+fn test_security_config_session_store() {
+    std::env::remove_var("SSL_PROXY");
+    std::env::remove_var("SESSION_STORE");
+    std::env::remove_var("REDIS_URL");
+    assert_eq!(
+        SecurityConfig::from_env().unwrap().session_store,
+        SessionBackend::Cookie
+    );
+
+    std::env::set_var("SESSION_STORE", "Redis");
+    std::env::set_var("REDIS_URL", "redis://localhost:6379");
+    assert_eq!(
+        SecurityConfig::from_env().unwrap().session_store,
+        SessionBackend::Redis {
+            url: "redis://localhost:6379".to_string()
+        }
+    );
+
+    // Redis needs a URL, and unknown stores are rejected rather than ignored
+    std::env::remove_var("REDIS_URL");
+    let err = SecurityConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("REDIS_URL"));
+
+    std::env::set_var("SESSION_STORE", "memcached");
+    let err = SecurityConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("memcached"));
+
+    std::env::remove_var("SESSION_STORE");
+}
+
+#[test]
+#[serial]
+fn test_security_config_environment() {
+    std::env::remove_var("SSL_PROXY");
+    std::env::remove_var("SESSION_STORE");
+    std::env::remove_var("RUSTRAK_ENV");
+    let environment = SecurityConfig::from_env().unwrap().environment;
+    assert_eq!(environment, Environment::Development);
+    assert!(!environment.is_production());
+
+    std::env::set_var("RUSTRAK_ENV", "Production");
+    let environment = SecurityConfig::from_env().unwrap().environment;
+    assert_eq!(environment, Environment::Production);
+    assert!(environment.is_production());
+
+    std::env::set_var("RUSTRAK_ENV", "staging");
+    let err = SecurityConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("staging"));
+
+    std::env::remove_var("RUSTRAK_ENV");
+}
+
+#[test]
+#[serial]
+fn test_security_config_cors_allowed_origins() {
+    std::env::remove_var("SSL_PROXY");
+    std::env::remove_var("SESSION_STORE");
+    std::env::remove_var("CORS_ALLOWED_ORIGINS");
+    assert!(SecurityConfig::from_env()
+        .unwrap()
+        .cors_allowed_origins
+        .is_empty());
+
+    std::env::set_var(
+        "CORS_ALLOWED_ORIGINS",
+        "https://dash.example.com/, http://localhost:3000,,",
+    );
+    assert_eq!(
+        SecurityConfig::from_env().unwrap().cors_allowed_origins,
+        vec![
+            "https://dash.example.com".to_string(),
+            "http://localhost:3000".to_string()
+        ]
+    );
+
+    // Credentialed responses must name the exact origin
+    for invalid in ["*", "https://*.example.com", "dash.example.com", "https://"] {
+        std::env::set_var("CORS_ALLOWED_ORIGINS", invalid);
+        let err = SecurityConfig::from_env().unwrap_err();
+        assert!(
+            err.to_string().contains("CORS_ALLOWED_ORIGINS"),
+            "{invalid}"
+        );
+    }
+
+    std::env::remove_var("CORS_ALLOWED_ORIGINS");
+}
+
+#[test]
+#[serial]
+fn test_security_config_oidc() {
+    for name in [
+        "SSL_PROXY",
+        "SESSION_STORE",
+        "RUSTRAK_ENV",
+        "PASSWORD_LOGIN_ENABLED",
+        "OIDC_ISSUER_URL",
+        "OIDC_CLIENT_ID",
+        "OIDC_CLIENT_SECRET",
+        "OIDC_REDIRECT_URL",
+        "OIDC_ADMIN_GROUPS",
+        "OIDC_GROUPS_CLAIM",
+        "DASHBOARD_URL",
+    ] {
+        std::env::remove_var(name);
+    }
+    let config = SecurityConfig::from_env().unwrap();
+    assert_eq!(config.oidc, None);
+    assert!(config.password_login_enabled);
+
+    // Password login is the only way in without a provider
+    std::env::set_var("PASSWORD_LOGIN_ENABLED", "false");
+    let err = SecurityConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("PASSWORD_LOGIN_ENABLED"));
+
+    // The issuer enables OIDC and requires the client settings
+    std::env::set_var("OIDC_ISSUER_URL", "https://sso.example.com/");
+    let err = SecurityConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("OIDC_CLIENT_ID"));
+
+    std::env::set_var("OIDC_CLIENT_ID", "rustrak");
+    std::env::set_var("OIDC_CLIENT_SECRET", "secret");
+    std::env::set_var(
+        "OIDC_REDIRECT_URL",
+        "https://rustrak.example.com/auth/oidc/callback",
+    );
+    std::env::set_var("OIDC_ADMIN_GROUPS", "ops, rustrak-admins,,");
+    let config = SecurityConfig::from_env().unwrap();
+    assert!(!config.password_login_enabled);
+    assert_eq!(
+        config.oidc,
+        Some(OidcConfig {
+            issuer_url: "https://sso.example.com".to_string(),
+            client_id: "rustrak".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_url: "https://rustrak.example.com/auth/oidc/callback".to_string(),
+            admin_groups: vec!["ops".to_string(), "rustrak-admins".to_string()],
+            groups_claim: "groups".to_string(),
+            dashboard_url: "http://localhost:3000".to_string(),
+        })
+    );
+
+    for name in [
+        "PASSWORD_LOGIN_ENABLED",
+        "OIDC_ISSUER_URL",
+        "OIDC_CLIENT_ID",
+        "OIDC_CLIENT_SECRET",
+        "OIDC_REDIRECT_URL",
+        "OIDC_ADMIN_GROUPS",
+    ] {
+        std::env::remove_var(name);
+    }
+}
@@ -0,0 +1,109 @@
+//! Unit tests for issue context extraction
+//!
+//! Tests reading OS, browser and runtime contexts and normalizing versions.
+
+use rustrak::digest::contexts::{
+    extract_contexts, extract_dist, normalize_version, MAX_CONTEXT_VALUE_LENGTH, MAX_DIST_LENGTH,
+};
+use rustrak::digest::ContextDimension;
+use serde_json::json;
+
+#[test]
+fn test_normalize_version() {
+    assert_eq!(
+        normalize_version("120.0.6099.109").as_deref(),
+        Some("120.0")
+    );
+    assert_eq!(normalize_version("22.04.3 LTS").as_deref(), Some("22.04"));
+    assert_eq!(normalize_version("14").as_deref(), Some("14"));
+    assert_eq!(normalize_version("1.75.0-nightly").as_deref(), Some("1.75"));
+    assert_eq!(normalize_version("  "), None);
+}
+
+#[test]
+fn test_extract_contexts() {
+    let event = json!({
+        "contexts": {
+            "os": { "name": "macOS", "version": "14.2.1" },
+            "browser": { "name": "Chrome", "version": "120.0.6099.109" },
+            "runtime": { "name": "node", "version": "v20.10.0" },
+            "device": { "name": "MacBook" }
+        }
+    });
+
+    assert_eq!(
+        extract_contexts(&event),
+        vec![
+            (ContextDimension::Os, "macOS 14.2".to_string()),
+            (ContextDimension::Browser, "Chrome 120.0".to_string()),
+            (ContextDimension::Runtime, "node v20.10".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_contexts_without_version() {
+    let event = json!({ "contexts": { "os": { "name": "Windows" } } });
+
+    assert_eq!(
+        extract_contexts(&event),
+        vec![(ContextDimension::Os, "Windows".to_string())]
+    );
+}
+
+#[test]
+fn test_extract_contexts_skips_unnamed() {
+    let event = json!({
+        "contexts": {
+            "os": { "version": "14.2" },
+            "browser": { "name": "", "version": "120" },
+            "runtime": "node"
+        }
+    });
+
+    assert!(extract_contexts(&event).is_empty());
+    assert!(extract_contexts(&json!({ "message": "no contexts" })).is_empty());
+}
+
+#[test]
+fn test_extract_contexts_truncates_long_values() {
+    let event = json!({ "contexts": { "runtime": { "name": "x".repeat(1000) } } });
+
+    let contexts = extract_contexts(&event);
+    assert_eq!(contexts[0].1.len(), MAX_CONTEXT_VALUE_LENGTH);
+}
+
+#[test]
+fn test_extract_contexts_includes_dist() {
+    let event = json!({
+        "release": "app@2.0.0",
+        "dist": "412",
+        "contexts": { "os": { "name": "iOS", "version": "17.2.1" } }
+    });
+
+    assert_eq!(
+        extract_contexts(&event),
+        vec![
+            (ContextDimension::Os, "iOS 17.2".to_string()),
+            (ContextDimension::Dist, "412".to_string()),
+        ]
+    );
+    assert_eq!(
+        extract_contexts(&json!({ "dist": "7" })),
+        vec![(ContextDimension::Dist, "7".to_string())]
+    );
+}
+
+#[test]
+fn test_extract_dist() {
+    assert_eq!(
+        extract_dist(&json!({ "dist": " 412 " })).as_deref(),
+        Some("412")
+    );
+    assert_eq!(extract_dist(&json!({ "dist": "" })), None);
+    assert_eq!(extract_dist(&json!({ "dist": 412 })), None);
+    assert_eq!(extract_dist(&json!({})), None);
+
+    let long = extract_dist(&json!({ "dist": "9".repeat(100) })).unwrap();
+    assert_eq!(long.len(), MAX_DIST_LENGTH);
+}
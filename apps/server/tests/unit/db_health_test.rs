@@ -0,0 +1,76 @@
+//! Unit tests for database outage tracking
+//!
+//! Tests the backoff between attempts and which errors count as an outage.
+
+use rustrak::db::health::{INITIAL_BACKOFF, MAX_BACKOFF};
+use rustrak::db::{is_unavailable, DbHealth};
+use std::time::Duration;
+
+#[test]
+fn test_healthy_database_is_tried() {
+    let health = DbHealth::new();
+    assert_eq!(health.retry_in(), None);
+
+    health.record_success();
+    assert_eq!(health.retry_in(), None);
+}
+
+#[test]
+fn test_failure_starts_backoff() {
+    let health = DbHealth::new();
+
+    assert_eq!(health.record_failure(), INITIAL_BACKOFF);
+    let wait = health.retry_in().expect("Should back off after a failure");
+    assert!(wait <= INITIAL_BACKOFF);
+}
+
+#[test]
+fn test_concurrent_failures_do_not_extend_backoff() {
+    let health = DbHealth::new();
+    health.record_failure();
+
+    // Attempts that were already underway fail within the same window
+    for _ in 0..100 {
+        assert!(health.record_failure() <= INITIAL_BACKOFF);
+    }
+    assert!(health.retry_in().unwrap() <= INITIAL_BACKOFF);
+}
+
+#[test]
+fn test_backoff_doubles_after_each_wait() {
+    let health = DbHealth::new();
+    health.record_failure();
+
+    std::thread::sleep(INITIAL_BACKOFF + Duration::from_millis(50));
+    assert_eq!(health.retry_in(), None);
+    assert_eq!(health.record_failure(), INITIAL_BACKOFF * 2);
+    assert!(INITIAL_BACKOFF * 2 <= MAX_BACKOFF);
+}
+
+#[test]
+fn test_success_ends_outage() {
+    let health = DbHealth::new();
+    health.record_failure();
+
+    health.record_success();
+    assert_eq!(health.retry_in(), None);
+    assert_eq!(health.record_failure(), INITIAL_BACKOFF);
+}
+
+#[test]
+fn test_connection_errors_are_unavailable() {
+    assert!(is_unavailable(&sqlx::Error::PoolTimedOut));
+    assert!(is_unavailable(&sqlx::Error::PoolClosed));
+    assert!(is_unavailable(&sqlx::Error::Io(std::io::Error::new(
+        std::io::ErrorKind::ConnectionRefused,
+        "connection refused"
+    ))));
+}
+
+#[test]
+fn test_query_errors_are_not_unavailable() {
+    assert!(!is_unavailable(&sqlx::Error::RowNotFound));
+    assert!(!is_unavailable(&sqlx::Error::Protocol(
+        "bad message".to_string()
+    )));
+}
@@ -0,0 +1,85 @@
+//! Unit tests for the ingest-to-stored latency histogram
+
+use std::time::Duration;
+
+use chrono::Utc;
+use rustrak::digest::latency::{bucket_index, since_ingest, LATENCY_BUCKETS};
+use rustrak::digest::DigestLatency;
+
+#[test]
+fn test_bucket_index() {
+    assert_eq!(bucket_index(0.0), 0);
+    // Bounds are inclusive, like Prometheus' `le`
+    assert_eq!(bucket_index(0.1), 0);
+    assert_eq!(bucket_index(0.2), 1);
+    assert_eq!(
+        bucket_index(45.0),
+        LATENCY_BUCKETS.iter().position(|&b| b == 60.0).unwrap()
+    );
+    assert_eq!(bucket_index(3600.0), LATENCY_BUCKETS.len());
+}
+
+#[test]
+fn test_snapshot_is_cumulative() {
+    let latency = DigestLatency::new();
+    latency.record(Duration::from_millis(50));
+    latency.record(Duration::from_secs(3));
+    latency.record(Duration::from_secs(7200));
+
+    let snapshot = latency.snapshot();
+    assert_eq!(snapshot.count, 3);
+    assert!((snapshot.sum - 7203.05).abs() < 1e-9);
+    assert_eq!(snapshot.buckets.len(), LATENCY_BUCKETS.len() + 1);
+    assert_eq!(snapshot.buckets[0], (0.1, 1));
+    assert_eq!(snapshot.buckets[bucket_index(3.0)], (5.0, 2));
+    assert_eq!(snapshot.buckets[LATENCY_BUCKETS.len() - 1], (600.0, 2));
+    assert_eq!(snapshot.buckets.last(), Some(&(f64::INFINITY, 3)));
+}
+
+#[test]
+fn test_summary_percentiles() {
+    let latency = DigestLatency::new();
+    let summary = latency.summary();
+    assert_eq!(summary.samples, 0);
+    assert_eq!(summary.p50_secs, None);
+    assert_eq!(summary.p95_secs, None);
+
+    for secs in (1..=100).rev() {
+        latency.record(Duration::from_secs(secs));
+    }
+    let summary = latency.summary();
+    assert_eq!(summary.samples, 100);
+    assert_eq!(summary.p50_secs, Some(50.0));
+    assert_eq!(summary.p95_secs, Some(95.0));
+}
+
+#[test]
+fn test_summary_keeps_recent_samples_only() {
+    let latency = DigestLatency::new();
+    for _ in 0..2000 {
+        latency.record(Duration::from_secs(100));
+    }
+    for _ in 0..1000 {
+        latency.record(Duration::from_secs(1));
+    }
+
+    let summary = latency.summary();
+    assert_eq!(summary.samples, 1000);
+    assert_eq!(summary.p95_secs, Some(1.0));
+    // The histogram still counts every event
+    assert_eq!(latency.snapshot().count, 3000);
+}
+
+#[test]
+fn test_since_ingest() {
+    let now = Utc::now();
+    assert_eq!(
+        since_ingest(now - chrono::Duration::seconds(45), now),
+        Duration::from_secs(45)
+    );
+    // A clock that went backwards doesn't make a negative latency
+    assert_eq!(
+        since_ingest(now + chrono::Duration::seconds(5), now),
+        Duration::ZERO
+    );
+}
@@ -0,0 +1,104 @@
+//! Unit tests for the digest concurrency limit and drain jobs
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustrak::digest::{DigestRunner, DrainJob, DrainState};
+use rustrak::error::AppError;
+
+async fn wait_for_drain(runner: &DigestRunner) -> DrainJob {
+    for _ in 0..100 {
+        let job = runner.drain_job().expect("no drain job");
+        if job.state != DrainState::Running {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("drain didn't finish");
+}
+
+#[tokio::test]
+async fn test_set_concurrency_resizes_permits() {
+    let runner = DigestRunner::new(4);
+    assert_eq!(runner.concurrency(), 4);
+    assert_eq!(runner.available(), 4);
+
+    runner.set_concurrency(1);
+    assert_eq!(runner.concurrency(), 1);
+    assert_eq!(runner.available(), 1);
+
+    // Zero pauses digestion
+    runner.set_concurrency(0);
+    assert_eq!(runner.available(), 0);
+
+    runner.set_concurrency(6);
+    assert_eq!(runner.concurrency(), 6);
+    assert_eq!(runner.available(), 6);
+}
+
+#[test]
+fn test_status_starts_empty() {
+    let status = DigestRunner::new(3).status();
+    assert_eq!(status.backlog, 0);
+    assert_eq!(status.queued, 0);
+    assert_eq!(status.processed_last_minute, 0);
+    assert_eq!(status.concurrency, 3);
+    assert!(status.last_error.is_none());
+    assert!(status.drain.is_none());
+}
+
+#[tokio::test]
+async fn test_drain_raises_then_restores_concurrency() {
+    let runner = Arc::new(DigestRunner::new(2));
+
+    let job = runner
+        .start_drain(16, Duration::from_secs(60))
+        .expect("Failed to start drain");
+    assert_eq!(job.concurrency, 16);
+    assert_eq!(job.previous_concurrency, 2);
+    assert_eq!(runner.drain_job().unwrap().id, job.id);
+
+    // Nothing to digest, so it's done right away
+    let finished = wait_for_drain(&runner).await;
+    assert_eq!(finished.id, job.id);
+    assert_eq!(finished.state, DrainState::Completed);
+    assert_eq!(finished.processed, 0);
+    assert!(finished.finished_at.is_some());
+    assert_eq!(runner.concurrency(), 2);
+    assert_eq!(runner.available(), 2);
+
+    // A finished drain doesn't block the next one
+    let next = runner.start_drain(4, Duration::from_secs(60)).unwrap();
+    assert_ne!(next.id, job.id);
+    wait_for_drain(&runner).await;
+}
+
+#[tokio::test]
+async fn test_only_one_drain_runs_at_a_time() {
+    let runner = Arc::new(DigestRunner::new(0));
+    // The drain task can't run before the next await, so it's still running
+    runner.start_drain(4, Duration::from_secs(60)).unwrap();
+
+    let result = runner.start_drain(8, Duration::from_secs(60));
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+    assert_eq!(runner.concurrency(), 4);
+}
+
+#[tokio::test]
+async fn test_drain_waits_for_queued_events() {
+    let runner = Arc::new(DigestRunner::new(1));
+    runner.add_queued();
+    assert_eq!(runner.status().queued, 1);
+
+    let job = runner.start_drain(4, Duration::from_secs(60)).unwrap();
+    assert_eq!(job.backlog_at_start, 1);
+
+    // Acknowledged events not written yet keep the drain going
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(runner.drain_job().unwrap().state, DrainState::Running);
+
+    runner.remove_queued();
+    let finished = wait_for_drain(&runner).await;
+    assert_eq!(finished.state, DrainState::Completed);
+    assert_eq!(runner.status().queued, 0);
+}
@@ -2,7 +2,13 @@
 //!
 //! Tests the parsing of Sentry SDK envelopes including headers, items, and edge cases.
 
-use rustrak::ingest::parser::EnvelopeParser;
+use actix_web::{http::StatusCode, ResponseError};
+use proptest::prelude::*;
+use rustrak::error::AppError;
+use rustrak::ingest::parser::{EnvelopeParser, PayloadFormat, MAX_EVENT_SIZE};
+use serde_json::{json, Value};
+
+use crate::common::{envelope_with_oversized_header, MALFORMED_ENVELOPES};
 
 // =============================================================================
 // Basic Parsing Tests (moved from inline tests)
@@ -140,6 +146,107 @@ fn test_parse_item_missing_type() {
     assert!(result.is_err());
 }
 
+// =============================================================================
+// Payload Content Type Tests
+// =============================================================================
+
+/// Builds an envelope with one item of `item_type` and `content_type`
+fn envelope_with_item(item_type: &str, content_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut envelope = format!(
+        "{{\"event_id\":\"abc\"}}\n{{\"type\":\"{}\",\"length\":{},\"content_type\":\"{}\"}}\n",
+        item_type,
+        payload.len(),
+        content_type
+    )
+    .into_bytes();
+    envelope.extend_from_slice(payload);
+    envelope.push(b'\n');
+    envelope
+}
+
+#[test]
+fn test_parse_msgpack_event_item() {
+    let event = json!({
+        "level": "error",
+        "platform": "python",
+        "exception": {"values": [{"type": "ValueError", "value": "bad input"}]},
+        "extra": {"retries": 3, "ratio": 0.5, "cached": false, "parent": null}
+    });
+    let payload = rmp_serde::to_vec_named(&event).unwrap();
+    let envelope = envelope_with_item("event", "application/x-msgpack", &payload);
+
+    let mut parser = EnvelopeParser::new(&envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items.len(), 1);
+    let decoded: Value = serde_json::from_slice(&result.items[0].payload).unwrap();
+    assert_eq!(decoded, event);
+}
+
+#[test]
+fn test_parse_msgpack_invalid_payload() {
+    // 0xc1 is never used in MessagePack
+    let envelope = envelope_with_item("event", "application/x-msgpack", &[0xc1]);
+    let mut parser = EnvelopeParser::new(&envelope);
+    let err = parser.parse().unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains("Invalid MessagePack event payload"));
+}
+
+#[test]
+fn test_parse_json_content_type_with_charset() {
+    let envelope = envelope_with_item("event", "application/json; charset=utf-8", b"{}");
+    let mut parser = EnvelopeParser::new(&envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items[0].payload, b"{}");
+}
+
+#[test]
+fn test_parse_unknown_content_type_rejected() {
+    let envelope = envelope_with_item("event", "application/cbor", b"{}");
+    let mut parser = EnvelopeParser::new(&envelope);
+    let err = parser.parse().unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains("Unsupported item content_type 'application/cbor'"));
+}
+
+#[test]
+fn test_parse_attachment_content_type_not_decoded() {
+    let envelope = envelope_with_item("attachment", "image/png", b"\x89PNG");
+    let mut parser = EnvelopeParser::new(&envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items[0].payload, b"\x89PNG");
+}
+
+#[test]
+fn test_payload_format_from_content_type() {
+    assert_eq!(
+        PayloadFormat::from_content_type(None).unwrap(),
+        PayloadFormat::Json
+    );
+    assert_eq!(
+        PayloadFormat::from_content_type(Some("Application/JSON")).unwrap(),
+        PayloadFormat::Json
+    );
+    for content_type in [
+        "application/x-msgpack",
+        "application/msgpack",
+        "application/vnd.msgpack",
+    ] {
+        assert_eq!(
+            PayloadFormat::from_content_type(Some(content_type)).unwrap(),
+            PayloadFormat::MessagePack
+        );
+    }
+    assert!(PayloadFormat::from_content_type(Some("text/plain")).is_err());
+}
+
 // =============================================================================
 // Item Types Tests
 // =============================================================================
@@ -212,14 +319,14 @@ fn test_parse_length_zero() {
 #[test]
 fn test_parse_length_mismatch_more_data() {
     // Length says 5 bytes but we have more
-    // Parser reads exactly 5 bytes, then remaining "56789\n" becomes the next item header
-    // which fails to parse. This is expected behavior - the envelope is malformed.
+    // The payload doesn't end at a newline, so the length can't be trusted
     let envelope = b"{\"event_id\":\"abc\"}\n{\"type\":\"event\",\"length\":5}\n0123456789\n";
     let mut parser = EnvelopeParser::new(envelope);
     let result = parser.parse();
 
-    // This envelope is malformed - the remaining data is invalid JSON
     assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("doesn't match its payload"));
 }
 
 #[test]
@@ -325,6 +432,86 @@ fn test_parse_mixed_length_items() {
     assert_eq!(result.items[1].payload, b"{\"s\":1}");
 }
 
+fn event_payload(message: &str) -> String {
+    json!({"message": message}).to_string()
+}
+
+#[test]
+fn test_parse_two_events_with_length() {
+    let (first, second) = (event_payload("first"), event_payload("second"));
+    let envelope = format!(
+        "{{\"event_id\":\"abc\"}}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n",
+        first.len(),
+        first,
+        second.len(),
+        second
+    );
+    let mut parser = EnvelopeParser::new(envelope.as_bytes());
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items.len(), 2);
+    assert!(result.items.iter().all(|i| i.headers.item_type == "event"));
+    assert_eq!(result.items[0].payload, first.as_bytes());
+    assert_eq!(result.items[1].payload, second.as_bytes());
+}
+
+#[test]
+fn test_parse_three_events_without_length() {
+    let events = ["one", "two", "three"].map(event_payload);
+    let envelope = format!(
+        "{{\"event_id\":\"abc\"}}\n{{\"type\":\"event\"}}\n{}\n{{\"type\":\"event\"}}\n{}\n{{\"type\":\"event\"}}\n{}",
+        events[0], events[1], events[2]
+    );
+    let mut parser = EnvelopeParser::new(envelope.as_bytes());
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items.len(), 3);
+    for (item, event) in result.items.iter().zip(&events) {
+        assert_eq!(item.headers.item_type, "event");
+        assert_eq!(item.payload, event.as_bytes());
+    }
+}
+
+#[test]
+fn test_parse_three_events_mixed_length() {
+    let events = ["one", "two", "three"].map(event_payload);
+    let envelope = format!(
+        "{{\"event_id\":\"abc\"}}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n{{\"type\":\"event\"}}\n{}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n",
+        events[0].len(),
+        events[0],
+        events[1],
+        events[2].len(),
+        events[2]
+    );
+    let mut parser = EnvelopeParser::new(envelope.as_bytes());
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items.len(), 3);
+    for (item, event) in result.items.iter().zip(&events) {
+        assert_eq!(item.payload, event.as_bytes());
+    }
+}
+
+#[test]
+fn test_parse_wrong_length_does_not_swallow_next_event() {
+    // The first length overshoots into the second item's headers
+    let (first, second) = (event_payload("first"), event_payload("second"));
+    let envelope = format!(
+        "{{\"event_id\":\"abc\"}}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n{{\"type\":\"event\"}}\n{}\n",
+        first.len() + 5,
+        first,
+        second
+    );
+    let mut parser = EnvelopeParser::new(envelope.as_bytes());
+    let result = parser.parse();
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("doesn't match its payload"));
+}
+
 // =============================================================================
 // Edge Cases and Error Handling
 // =============================================================================
@@ -506,3 +693,201 @@ fn test_parse_sentry_javascript_style_envelope() {
     assert_eq!(result.headers.event_id, Some("abc123def456".to_string()));
     assert!(result.headers.dsn.is_some());
 }
+
+// =============================================================================
+// Encoding Robustness Tests
+// =============================================================================
+
+#[test]
+fn test_parse_leading_byte_order_mark() {
+    let envelope = b"\xEF\xBB\xBF{\"event_id\":\"abc\"}\n{\"type\":\"event\",\"length\":2}\n{}\n";
+    let mut parser = EnvelopeParser::new(envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.headers.event_id, Some("abc".to_string()));
+    assert_eq!(result.items.len(), 1);
+}
+
+#[test]
+fn test_parse_byte_order_mark_in_event_payload() {
+    let envelope = b"{}\n{\"type\":\"event\"}\n\xEF\xBB\xBF{\"level\":\"error\"}\n";
+    let mut parser = EnvelopeParser::new(envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items[0].payload, b"{\"level\":\"error\"}");
+}
+
+#[test]
+fn test_parse_crlf_line_endings() {
+    let envelope = b"{\"event_id\":\"abc\"}\r\n{\"type\":\"event\",\"length\":2}\r\n{}\r\n{\"type\":\"session\"}\r\n{\"sid\":\"x\"}\r\n";
+    let mut parser = EnvelopeParser::new(envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.headers.event_id, Some("abc".to_string()));
+    assert_eq!(result.items.len(), 2);
+    assert_eq!(result.items[0].payload, b"{}");
+    assert_eq!(result.items[1].headers.item_type, "session");
+    assert_eq!(result.items[1].payload, b"{\"sid\":\"x\"}");
+}
+
+#[test]
+fn test_parse_crlf_kept_inside_length_payload() {
+    let payload = b"line1\r\nline2";
+    let header = format!(
+        "{{}}\r\n{{\"type\":\"attachment\",\"length\":{}}}\r\n",
+        payload.len()
+    );
+    let mut envelope = header.into_bytes();
+    envelope.extend_from_slice(payload);
+    envelope.extend_from_slice(b"\r\n");
+
+    let mut parser = EnvelopeParser::new(&envelope);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items[0].payload, payload);
+}
+
+#[test]
+fn test_parse_emoji_in_headers() {
+    let envelope = "{\"event_id\":\"abc\",\"sdk\":{\"name\":\"sentry.🦀\",\"version\":\"1.0\"}}\n{\"type\":\"event\",\"filename\":\"día.json\"}\n{}\n";
+    let mut parser = EnvelopeParser::new(envelope.as_bytes());
+    let result = parser.parse().unwrap();
+
+    assert_eq!(
+        result.headers.sdk.unwrap().name.as_deref(),
+        Some("sentry.🦀")
+    );
+}
+
+#[test]
+fn test_parse_invalid_utf8_attachment_kept_as_binary() {
+    let payload = b"\xC3\x28\xA0\xA1\xFF";
+    let envelope_bytes = [
+        b"{}\n{\"type\":\"attachment\"}\n".as_slice(),
+        payload,
+        b"\n",
+    ]
+    .concat();
+    let mut parser = EnvelopeParser::new(&envelope_bytes);
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.items[0].payload, payload);
+}
+
+#[test]
+fn test_parse_oversized_header_is_bad_request() {
+    let envelope = envelope_with_oversized_header();
+    let mut parser = EnvelopeParser::new(&envelope);
+    let err = parser.parse().unwrap_err();
+
+    assert!(matches!(err, AppError::Validation(_)));
+    assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    assert!(err.to_string().contains("Header line at byte 3 exceeds"));
+}
+
+#[test]
+fn test_parse_oversized_envelope_headers_is_bad_request() {
+    let mut envelope = format!("{{\"event_id\":\"{}\"}}", "a".repeat(9000)).into_bytes();
+    envelope.extend_from_slice(b"\n{\"type\":\"event\"}\n{}\n");
+    let mut parser = EnvelopeParser::new(&envelope);
+    let err = parser.parse().unwrap_err();
+
+    assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    assert!(err.to_string().contains("Header line at byte 0 exceeds"));
+}
+
+// =============================================================================
+// Error Position Tests
+// =============================================================================
+
+#[test]
+fn test_error_position_invalid_item_headers() {
+    // Item headers start at byte 3; the bad token is 1 byte in
+    let envelope = b"{}\n{type:event}\n{}";
+    let err = EnvelopeParser::new(envelope).parse().unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Invalid item headers JSON at byte 4"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_error_position_truncated_payload() {
+    let envelope = b"{}\n{\"type\":\"event\",\"length\":100}\n{}";
+    let err = EnvelopeParser::new(envelope).parse().unwrap_err();
+
+    assert!(err.to_string().contains("at byte 33"), "{}", err);
+    assert!(err.to_string().contains("expected 100 bytes, found 2"));
+}
+
+#[test]
+fn test_error_position_length_mismatch() {
+    let envelope = b"{}\n{\"type\":\"event\",\"length\":1}\n{}\n";
+    let err = EnvelopeParser::new(envelope).parse().unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("doesn't match its payload at byte 32"),
+        "{}",
+        err
+    );
+}
+
+// =============================================================================
+// Malformed Envelope Corpus
+// =============================================================================
+
+#[test]
+fn test_malformed_envelopes_are_rejected() {
+    for (name, envelope) in MALFORMED_ENVELOPES {
+        let result = EnvelopeParser::new(envelope).parse();
+        assert!(result.is_err(), "{} was accepted", name);
+    }
+}
+
+#[test]
+fn test_truncated_envelopes_never_panic() {
+    let envelope = b"\xEF\xBB\xBF{\"event_id\":\"abc\"}\r\n{\"type\":\"event\",\"length\":2}\r\n{}\r\n{\"type\":\"attachment\"}\n\xFF\xFE\n";
+    for end in 0..=envelope.len() {
+        let _ = EnvelopeParser::new(&envelope[..end]).parse();
+    }
+}
+
+fn valid_envelope() -> Vec<u8> {
+    let event = event_payload("boom");
+    format!(
+        "{{\"event_id\":\"abc\"}}\n{{\"type\":\"event\",\"length\":{}}}\n{}\n{{\"type\":\"attachment\"}}\nhello\n",
+        event.len(),
+        event
+    )
+    .into_bytes()
+}
+
+proptest! {
+    #[test]
+    fn prop_parser_never_panics_on_arbitrary_bytes(data in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let _ = EnvelopeParser::new(&data).parse();
+    }
+
+    #[test]
+    fn prop_parser_handles_mutated_envelopes(
+        mutations in proptest::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+        truncate in any::<prop::sample::Index>(),
+    ) {
+        let mut envelope = valid_envelope();
+        for (index, byte) in mutations {
+            let i = index.index(envelope.len());
+            envelope[i] = byte;
+        }
+        envelope.truncate(truncate.index(envelope.len() + 1));
+
+        if let Ok(parsed) = EnvelopeParser::new(&envelope).parse() {
+            for item in parsed.items {
+                prop_assert!(item.payload.len() <= MAX_EVENT_SIZE);
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Unit tests for the structured error responses
+
+use std::collections::HashSet;
+
+use actix_web::{body::to_bytes, http::StatusCode, ResponseError};
+use rustrak::config::BodyLimitConfig;
+use rustrak::error::{codes, AppError, ERROR_CODES};
+use serde_json::json;
+
+async fn body_json(err: &AppError) -> serde_json::Value {
+    let body = to_bytes(err.error_response().into_body()).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn test_error_codes_are_unique() {
+    let unique: HashSet<_> = ERROR_CODES.iter().collect();
+    assert_eq!(unique.len(), ERROR_CODES.len());
+}
+
+#[test]
+fn test_every_variant_code_is_listed() {
+    let errors = [
+        AppError::NotFound("x".into()),
+        AppError::Validation("x".into()),
+        AppError::Conflict("x".into()),
+        AppError::InUse {
+            message: "x".into(),
+            dependents: vec![],
+        },
+        AppError::Unauthorized("x".into()),
+        AppError::Forbidden("x".into()),
+        AppError::PayloadTooLarge("x".into()),
+        AppError::UnsupportedMediaType("x".into()),
+        AppError::RateLimited(1),
+        AppError::Database(sqlx::Error::RowNotFound),
+        AppError::Internal("x".into()),
+    ];
+    for err in errors {
+        assert!(ERROR_CODES.contains(&err.code()), "{} missing", err.code());
+    }
+}
+
+#[actix_web::test]
+async fn test_error_body_shape() {
+    let err = AppError::NotFound("Project 1".into());
+    assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+
+    let json = body_json(&err).await;
+    assert_eq!(json["error"]["code"], "not_found");
+    assert_eq!(json["error"]["message"], "Resource not found: Project 1");
+    assert!(json["error"].get("field").is_none());
+    assert!(json["error"].get("retry_after").is_none());
+    assert!(json["error"].get("dependents").is_none());
+}
+
+#[actix_web::test]
+async fn test_field_errors_include_field() {
+    let err = AppError::invalid_field("name", codes::VALIDATION_TOO_LONG, "Name is too long");
+    assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    let json = body_json(&err).await;
+    assert_eq!(json["error"]["code"], "validation.too_long");
+    assert_eq!(json["error"]["field"], "name");
+    assert_eq!(
+        json["error"]["message"],
+        "Validation error: Name is too long"
+    );
+
+    let err = AppError::field_conflict("slug", codes::CONFLICT_SLUG_TAKEN, "Slug is taken");
+    assert_eq!(err.status_code(), StatusCode::CONFLICT);
+    let json = body_json(&err).await;
+    assert_eq!(json["error"]["code"], "conflict.slug_taken");
+    assert_eq!(json["error"]["field"], "slug");
+}
+
+#[test]
+fn test_for_field_only_converts_validation() {
+    let err = AppError::Validation("Bad URL".into()).for_field("config");
+    assert_eq!(err.code(), codes::VALIDATION);
+    assert_eq!(err.field(), Some("config"));
+    assert_eq!(err.to_string(), "Validation error: Bad URL");
+
+    let err = AppError::NotFound("x".into()).for_field("config");
+    assert_eq!(err.code(), codes::NOT_FOUND);
+    assert_eq!(err.field(), None);
+}
+
+#[actix_web::test]
+async fn test_rate_limited_sets_retry_after() {
+    let err = AppError::RateLimited(42);
+    let response = err.error_response();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get("Retry-After").unwrap(), "42");
+
+    let json = body_json(&err).await;
+    assert_eq!(json["error"]["code"], "rate_limited");
+    assert_eq!(json["error"]["retry_after"], 42);
+}
+
+#[actix_web::test]
+async fn test_unavailable_sets_retry_after() {
+    let err = AppError::Unavailable {
+        message: "Ingestion is paused for maintenance".into(),
+        retry_after: 60,
+    };
+    let response = err.error_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers().get("Retry-After").unwrap(), "60");
+
+    let json = body_json(&err).await;
+    assert_eq!(json["error"]["code"], "unavailable");
+    assert_eq!(json["error"]["retry_after"], 60);
+}
+
+#[actix_web::test]
+async fn test_in_use_lists_dependents() {
+    let err = AppError::InUse {
+        message: "Channel 'Ops' is used by 2 alert rule(s)".into(),
+        dependents: vec!["Errors".into(), "Regressions".into()],
+    };
+    assert_eq!(err.status_code(), StatusCode::CONFLICT);
+
+    let json = body_json(&err).await;
+    assert_eq!(json["error"]["code"], "conflict.in_use");
+    assert_eq!(
+        json["error"]["message"],
+        "Conflict: Channel 'Ops' is used by 2 alert rule(s)"
+    );
+    assert_eq!(
+        json["error"]["dependents"],
+        json!(["Errors", "Regressions"])
+    );
+}
+
+#[derive(serde::Deserialize)]
+struct Body {
+    #[allow(dead_code)]
+    name: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Query {
+    #[allow(dead_code)]
+    limit: u32,
+}
+
+async fn extractor_error(req: actix_web::test::TestRequest) -> (StatusCode, serde_json::Value) {
+    use actix_web::{test, web, App, HttpResponse};
+
+    let app = test::init_service(
+        App::new()
+            .configure(rustrak::error::configure_extractors(
+                BodyLimitConfig::default(),
+            ))
+            .route(
+                "/items/{id}",
+                web::post().to(|_: web::Path<i32>, _: web::Json<Body>| async {
+                    HttpResponse::Ok().finish()
+                }),
+            )
+            .route(
+                "/items",
+                web::get().to(|_: web::Query<Query>| async { HttpResponse::Ok().finish() }),
+            )
+            .default_service(web::to(rustrak::error::not_found)),
+    )
+    .await;
+
+    let resp = test::call_service(&app, req.to_request()).await;
+    let status = resp.status();
+    let body = test::read_body(resp).await;
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[actix_web::test]
+async fn test_malformed_json_body_uses_envelope() {
+    let (status, json) = extractor_error(
+        actix_web::test::TestRequest::post()
+            .uri("/items/1")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload("{\"name\": 1"),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"]["code"], "validation.invalid");
+}
+
+#[actix_web::test]
+async fn test_wrong_content_type_uses_envelope() {
+    let (status, json) = extractor_error(
+        actix_web::test::TestRequest::post()
+            .uri("/items/1")
+            .insert_header(("Content-Type", "text/plain"))
+            .set_payload("name"),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(json["error"]["code"], "unsupported_media_type");
+}
+
+#[actix_web::test]
+async fn test_malformed_query_uses_envelope() {
+    let (status, json) =
+        extractor_error(actix_web::test::TestRequest::get().uri("/items?limit=many")).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(json["error"]["code"], "validation.invalid");
+}
+
+#[actix_web::test]
+async fn test_malformed_path_and_unknown_route_are_not_found() {
+    let (status, json) = extractor_error(
+        actix_web::test::TestRequest::post()
+            .uri("/items/abc")
+            .set_json(json!({ "name": "x" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(json["error"]["code"], "not_found");
+
+    let (status, json) = extractor_error(actix_web::test::TestRequest::get().uri("/nothing")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(json["error"]["code"], "not_found");
+}
+
+#[actix_web::test]
+async fn test_api_body_limit_applies_to_every_body() {
+    use actix_web::{test, web, App, HttpResponse};
+
+    let limits = BodyLimitConfig {
+        api: 64,
+        ingest: 1024,
+    };
+    let app = test::init_service(
+        App::new()
+            .configure(rustrak::error::configure_extractors(limits))
+            .route(
+                "/items/{id}",
+                web::patch().to(|_: web::Json<Body>| async { HttpResponse::Ok().finish() }),
+            )
+            .route(
+                "/raw",
+                web::post().to(|_: web::Bytes| async { HttpResponse::Ok().finish() }),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::patch()
+        .uri("/items/1")
+        .set_json(json!({ "name": "x".repeat(100) }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["error"]["code"], "payload_too_large");
+
+    let req = test::TestRequest::patch()
+        .uri("/items/1")
+        .set_json(json!({ "name": "x" }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    // Raw bodies outside the ingest routes get the API limit too
+    let req = test::TestRequest::post()
+        .uri("/raw")
+        .set_payload(vec![b'x'; 32])
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::post()
+        .uri("/raw")
+        .set_payload(vec![b'x'; 512])
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
@@ -0,0 +1,52 @@
+//! Unit tests for event merging
+//!
+//! Tests how an update sent under a digested event_id is merged into the
+//! stored event.
+
+use rustrak::digest::merge_event_data;
+use serde_json::json;
+
+#[test]
+fn test_merge_adds_missing_fields() {
+    let mut stored = json!({"message": "boom", "tags": {"region": "eu"}});
+    let update = json!({"user": {"id": "42"}, "tags": {"plan": "pro"}});
+
+    assert!(merge_event_data(&mut stored, &update));
+    assert_eq!(
+        stored,
+        json!({
+            "message": "boom",
+            "tags": {"region": "eu", "plan": "pro"},
+            "user": {"id": "42"}
+        })
+    );
+}
+
+#[test]
+fn test_merge_replaces_set_fields() {
+    let mut stored = json!({"level": "error", "fingerprint": ["a", "b"]});
+    let update = json!({"level": "fatal", "fingerprint": ["c"]});
+
+    assert!(merge_event_data(&mut stored, &update));
+    assert_eq!(stored, json!({"level": "fatal", "fingerprint": ["c"]}));
+}
+
+#[test]
+fn test_merge_ignores_nulls() {
+    let mut stored = json!({"release": "1.0.0", "contexts": {"os": {"name": "Linux"}}});
+    let update = json!({"release": null, "contexts": {"os": null}});
+
+    assert!(!merge_event_data(&mut stored, &update));
+    assert_eq!(
+        stored,
+        json!({"release": "1.0.0", "contexts": {"os": {"name": "Linux"}}})
+    );
+}
+
+#[test]
+fn test_merge_identical_payload_is_unchanged() {
+    let mut stored = json!({"message": "boom", "extra": {"attempt": 1}});
+    let update = stored.clone();
+
+    assert!(!merge_event_data(&mut stored, &update));
+}
@@ -0,0 +1,95 @@
+//! Unit tests for the ingest fsync policies
+//!
+//! Tests when each policy syncs written files, the batch task interval, and
+//! that events written in every mode are readable after a shutdown flush.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustrak::config::FsyncPolicy;
+use rustrak::ingest::fsync::FsyncWriter;
+use tempfile::TempDir;
+
+const POLICIES: [FsyncPolicy; 3] = [FsyncPolicy::Always, FsyncPolicy::Batch, FsyncPolicy::Never];
+
+#[actix_web::test]
+async fn test_only_batch_leaves_files_pending() {
+    for policy in POLICIES {
+        let dir = TempDir::new().unwrap();
+        let writer = FsyncWriter::new(policy);
+
+        writer
+            .write(&dir.path().join("event.json"), b"{}")
+            .await
+            .unwrap();
+
+        let expected = if policy == FsyncPolicy::Batch { 1 } else { 0 };
+        assert_eq!(writer.pending(), expected, "{:?}", policy);
+    }
+}
+
+#[actix_web::test]
+async fn test_batch_flush_skips_deleted_files() {
+    let dir = TempDir::new().unwrap();
+    let writer = FsyncWriter::new(FsyncPolicy::Batch);
+
+    let kept = dir.path().join("kept.json");
+    let digested = dir.path().join("digested.json");
+    writer.write(&kept, b"{}").await.unwrap();
+    writer.write(&digested, b"{}").await.unwrap();
+    std::fs::remove_file(&digested).unwrap();
+
+    assert_eq!(writer.flush().await, 1);
+    assert_eq!(writer.pending(), 0);
+    assert_eq!(writer.flush().await, 0);
+}
+
+#[actix_web::test]
+async fn test_batch_flusher_syncs_on_interval() {
+    let dir = TempDir::new().unwrap();
+    let writer = Arc::new(FsyncWriter::new(FsyncPolicy::Batch));
+    let flusher = writer.clone().spawn_flusher(Duration::from_millis(20));
+
+    // Let the first tick, which fires right away, pass
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    writer
+        .write(&dir.path().join("event.json"), b"{}")
+        .await
+        .unwrap();
+    assert_eq!(writer.pending(), 1);
+
+    for _ in 0..50 {
+        if writer.pending() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(writer.pending(), 0);
+    flusher.abort();
+}
+
+#[actix_web::test]
+async fn test_events_survive_shutdown_in_every_mode() {
+    for policy in POLICIES {
+        let dir = TempDir::new().unwrap();
+        let writer = FsyncWriter::new(policy);
+
+        let paths: Vec<_> = (0..5)
+            .map(|i| dir.path().join(format!("event-{}.json", i)))
+            .collect();
+        for (i, path) in paths.iter().enumerate() {
+            let contents = format!(r#"{{"n":{}}}"#, i);
+            writer.write(path, contents.as_bytes()).await.unwrap();
+        }
+
+        // What main does once the server and the ingest writer have stopped
+        writer.flush().await;
+        assert_eq!(writer.pending(), 0);
+        drop(writer);
+
+        for (i, path) in paths.iter().enumerate() {
+            let contents = std::fs::read_to_string(path).unwrap();
+            assert_eq!(contents, format!(r#"{{"n":{}}}"#, i), "{:?}", policy);
+        }
+    }
+}
@@ -2,9 +2,17 @@
 //!
 //! Tests event grouping logic, hash generation, and denormalized field extraction.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::common::fixtures::events;
+use crate::common::{LIBCRASH_BASE, SYMBOL_DEBUG_ID, SYMBOL_FILE};
+use rustrak::digest::breakpad::SymbolFile;
+use rustrak::digest::{apply_in_app_rules, symbolicate_event};
 use rustrak::services::grouping::{
-    calculate_grouping_key, get_denormalized_fields, get_title, get_type_and_value,
-    hash_grouping_key,
+    calculate_fallback_grouping_key, calculate_grouping_key, get_denormalized_fields,
+    get_exception_chain_types, get_title, get_type_and_value, hash_grouping_key, is_unhandled,
+    truncate_with_ellipsis, GROUPING_VALUE_LENGTH,
 };
 use serde_json::json;
 
@@ -77,10 +85,10 @@ fn test_exception_with_values_array() {
         }
     });
 
-    // Should use the LAST exception (most important in the chain)
+    // SDKs send the root cause first; that's what the issue is about
     let (type_, value) = get_type_and_value(&event);
-    assert_eq!(type_, "OuterError");
-    assert_eq!(value, "outer cause");
+    assert_eq!(type_, "InnerError");
+    assert_eq!(value, "inner");
 }
 
 #[test]
@@ -94,8 +102,145 @@ fn test_exception_direct_array() {
     });
 
     let (type_, value) = get_type_and_value(&event);
-    assert_eq!(type_, "LastError");
-    assert_eq!(value, "last");
+    assert_eq!(type_, "FirstError");
+    assert_eq!(value, "first");
+}
+
+#[test]
+fn test_two_deep_chain_uses_root_cause() {
+    let event = json!({
+        "exception": {
+            "values": [
+                { "type": "KeyError", "value": "'user_id'" },
+                { "type": "RuntimeError", "value": "handler failed" }
+            ]
+        },
+        "transaction": "/api/users"
+    });
+
+    let fields = get_denormalized_fields(&event);
+    assert_eq!(fields.calculated_type, "KeyError");
+    assert_eq!(fields.calculated_value, "'user_id'");
+    assert_eq!(fields.exception_chain_length, 2);
+    assert_eq!(
+        get_title(&fields.calculated_type, &fields.calculated_value),
+        "KeyError: 'user_id'"
+    );
+
+    let key = calculate_grouping_key(&event);
+    assert!(key.starts_with("KeyError: 'user_id'"));
+    assert!(key.ends_with("KeyError > RuntimeError"));
+}
+
+#[test]
+fn test_three_deep_chain_uses_root_cause() {
+    let event = json!({
+        "exception": {
+            "values": [
+                { "type": "ConnectionRefused", "value": "port 5432" },
+                { "type": "DatabaseError", "value": "query failed" },
+                { "type": "HTTPError", "value": "request failed" }
+            ]
+        }
+    });
+
+    let (type_, value) = get_type_and_value(&event);
+    assert_eq!(type_, "ConnectionRefused");
+    assert_eq!(value, "port 5432");
+    assert_eq!(get_exception_chain_types(&event).len(), 3);
+    assert_eq!(get_denormalized_fields(&event).exception_chain_length, 3);
+    assert!(
+        calculate_grouping_key(&event).ends_with("ConnectionRefused > DatabaseError > HTTPError")
+    );
+}
+
+#[test]
+fn test_linked_chain_follows_mechanism_ids() {
+    // The raised exception is listed first here, but the links say which
+    // one caused which
+    let event = json!({
+        "exception": {
+            "values": [
+                {
+                    "type": "RuntimeError",
+                    "value": "handler failed",
+                    "mechanism": { "type": "generic", "exception_id": 0 }
+                },
+                {
+                    "type": "KeyError",
+                    "value": "'user_id'",
+                    "mechanism": { "type": "chained", "exception_id": 1, "parent_id": 0 }
+                }
+            ]
+        }
+    });
+
+    let (type_, value) = get_type_and_value(&event);
+    assert_eq!(type_, "KeyError");
+    assert_eq!(value, "'user_id'");
+
+    // Links that go in circles still end on one of the chain
+    let cyclic = json!({
+        "exception": { "values": [
+            { "type": "A", "mechanism": { "exception_id": 0 } },
+            { "type": "B", "mechanism": { "exception_id": 1, "parent_id": 0 } },
+            { "type": "C", "mechanism": { "exception_id": 0, "parent_id": 1 } }
+        ] }
+    });
+    assert!(["A", "B", "C"].contains(&get_type_and_value(&cyclic).0.as_str()));
+}
+
+#[test]
+fn test_distinct_chains_with_same_root_do_not_collapse() {
+    let root = json!({ "type": "KeyError", "value": "'user_id'" });
+    let via_handler = json!({
+        "exception": { "values": [root.clone(), { "type": "HandlerError", "value": "x" }] }
+    });
+    let via_worker = json!({
+        "exception": { "values": [root.clone(), { "type": "WorkerError", "value": "x" }] }
+    });
+    let root_only = json!({ "exception": { "values": [root] } });
+
+    let keys = [
+        calculate_grouping_key(&via_handler),
+        calculate_grouping_key(&via_worker),
+        calculate_grouping_key(&root_only),
+    ];
+    assert_ne!(keys[0], keys[1]);
+    assert_ne!(keys[0], keys[2]);
+    assert_ne!(keys[1], keys[2]);
+}
+
+#[test]
+fn test_single_exception_key_has_no_chain_suffix() {
+    let event = json!({
+        "exception": { "values": [{ "type": "TypeError", "value": "bad" }] },
+        "transaction": "/api/users"
+    });
+
+    assert_eq!(
+        calculate_grouping_key(&event),
+        "TypeError: bad ⋄ /api/users"
+    );
+    assert_eq!(get_denormalized_fields(&event).exception_chain_length, 1);
+}
+
+#[test]
+fn test_empty_exception_values_falls_back() {
+    let event = json!({
+        "exception": { "values": [] },
+        "message": "Something happened"
+    });
+
+    let (type_, value) = get_type_and_value(&event);
+    assert_eq!(type_, "Log Message");
+    assert_eq!(value, "Something happened");
+    assert!(get_exception_chain_types(&event).is_empty());
+    assert_eq!(get_denormalized_fields(&event).exception_chain_length, 0);
+
+    let bare = json!({ "exception": [] });
+    assert_eq!(get_type_and_value(&bare).0, "Unknown");
+    assert!(calculate_grouping_key(&bare).starts_with("Unknown"));
 }
 
 #[test]
@@ -186,7 +331,7 @@ fn test_logentry_prefers_message_over_formatted() {
         }
     });
 
-    let (type_, value) = get_type_and_value(&event);
+    let (_type, value) = get_type_and_value(&event);
     // Should prefer 'message' (parameterized) for grouping
     assert_eq!(value, "User %s logged in");
 }
@@ -505,6 +650,64 @@ fn test_denormalized_fields_no_stacktrace() {
     assert_eq!(fields.last_frame_function, "");
 }
 
+#[test]
+fn test_unhandled_when_mechanism_not_handled() {
+    let event = json!({
+        "exception": {
+            "values": [{
+                "type": "TypeError",
+                "value": "x is undefined",
+                "mechanism": { "type": "onerror", "handled": false }
+            }]
+        }
+    });
+
+    assert!(is_unhandled(&event));
+    assert!(get_denormalized_fields(&event).is_unhandled);
+}
+
+#[test]
+fn test_handled_mechanism_is_not_unhandled() {
+    let event = json!({
+        "exception": {
+            "values": [{
+                "type": "ValueError",
+                "value": "bad amount",
+                "mechanism": { "type": "generic", "handled": true }
+            }]
+        }
+    });
+
+    assert!(!is_unhandled(&event));
+}
+
+#[test]
+fn test_missing_mechanism_defaults_to_handled() {
+    let event = json!({
+        "exception": {
+            "values": [
+                { "type": "Error", "value": "no mechanism" },
+                { "type": "Error", "value": "no flag", "mechanism": { "type": "generic" } }
+            ]
+        }
+    });
+
+    assert!(!is_unhandled(&event));
+    assert!(!is_unhandled(&json!({ "message": "just a message" })));
+}
+
+#[test]
+fn test_unhandled_anywhere_in_chain() {
+    let event = json!({
+        "exception": [
+            { "type": "KeyError", "value": "cause", "mechanism": { "handled": false } },
+            { "type": "RuntimeError", "value": "wrapper", "mechanism": { "handled": true } }
+        ]
+    });
+
+    assert!(is_unhandled(&event));
+}
+
 // =============================================================================
 // Truncation Tests
 // =============================================================================
@@ -554,6 +757,60 @@ fn test_transaction_truncation() {
     assert!(key.len() < long_transaction.len() + 200);
 }
 
+#[test]
+fn test_stored_value_capped_with_ellipsis() {
+    let huge_value = "é".repeat(1024 * 1024);
+    let event = json!({
+        "exception": { "values": [{
+            "type": "RequestError",
+            "value": huge_value,
+            "stacktrace": { "frames": [{ "function": "f".repeat(300), "in_app": true }] }
+        }] },
+        "transaction": "/".to_string() + &"t".repeat(300)
+    });
+
+    let fields = get_denormalized_fields(&event).capped(500);
+    assert_eq!(fields.calculated_value.chars().count(), 500);
+    assert!(fields.calculated_value.ends_with("é…"));
+    assert_eq!(fields.transaction.chars().count(), 200);
+    assert_eq!(fields.last_frame_function.chars().count(), 255);
+
+    let fields = get_denormalized_fields(&event).capped(100);
+    assert_eq!(fields.transaction.chars().count(), 100);
+    assert!(fields.last_frame_function.ends_with('…'));
+}
+
+#[test]
+fn test_grouping_ignores_value_past_grouping_length() {
+    // Grouping only sees the first GROUPING_VALUE_LENGTH characters of the
+    // value, whatever is stored, so events sent before and after the stored
+    // value was capped keep grouping into the same issue
+    let event_with = |value: String| {
+        json!({
+            "exception": { "values": [{ "type": "RequestError", "value": value }] },
+            "transaction": "/api/upload"
+        })
+    };
+    let prefix = "x".repeat(GROUPING_VALUE_LENGTH);
+    let huge = event_with(prefix.clone() + &"y".repeat(1024 * 1024));
+    let other_tail = event_with(prefix.clone() + "z");
+    let at_limit = event_with(prefix);
+
+    let key = calculate_grouping_key(&huge);
+    assert_eq!(key, calculate_grouping_key(&other_tail));
+    assert_eq!(key, calculate_grouping_key(&at_limit));
+    assert!(key.len() < GROUPING_VALUE_LENGTH + 100);
+}
+
+#[test]
+fn test_truncate_with_ellipsis() {
+    assert_eq!(truncate_with_ellipsis("short", 10), "short");
+    assert_eq!(truncate_with_ellipsis("exactly10!", 10), "exactly10!");
+    assert_eq!(truncate_with_ellipsis("cut this string", 8), "cut thi…");
+    // Cuts at character boundaries, never inside a multi-byte character
+    assert_eq!(truncate_with_ellipsis("日本語のテキスト", 4), "日本語…");
+}
+
 // =============================================================================
 // Unicode and Special Characters
 // =============================================================================
@@ -607,3 +864,179 @@ fn test_newlines_and_tabs_in_value() {
     assert!(value.contains('\n'));
     assert!(value.contains('\t'));
 }
+
+// =============================================================================
+// Native Grouping
+// =============================================================================
+
+fn symbolicated(mut event: serde_json::Value, with_symbols: bool) -> serde_json::Value {
+    let mut symbols = HashMap::new();
+    if with_symbols {
+        symbols.insert(
+            SYMBOL_DEBUG_ID.to_string(),
+            Arc::new(SymbolFile::parse(SYMBOL_FILE).unwrap()),
+        );
+    }
+    symbolicate_event(&mut event, &symbols);
+    event
+}
+
+#[test]
+fn test_native_grouping_without_symbols_uses_module_offset() {
+    let event = symbolicated(events::native_crash(&[LIBCRASH_BASE + 0x1024]), false);
+
+    let key = calculate_grouping_key(&event);
+    assert!(key.ends_with(" ⋄ libcrash.so+0x1024"));
+    assert_eq!(calculate_fallback_grouping_key(&event), None);
+}
+
+#[test]
+fn test_native_grouping_prefers_function_name() {
+    let event = symbolicated(events::native_crash(&[LIBCRASH_BASE + 0x1024]), true);
+
+    let key = calculate_grouping_key(&event);
+    assert!(key.ends_with(" ⋄ crash_handler"));
+
+    // The fallback is the key the same crash had before symbols were uploaded
+    let before = symbolicated(events::native_crash(&[LIBCRASH_BASE + 0x1024]), false);
+    assert_eq!(
+        calculate_fallback_grouping_key(&event),
+        Some(calculate_grouping_key(&before))
+    );
+}
+
+#[test]
+fn test_native_grouping_same_function_different_offsets() {
+    let a = symbolicated(events::native_crash(&[LIBCRASH_BASE + 0x1004]), true);
+    let b = symbolicated(events::native_crash(&[LIBCRASH_BASE + 0x1030]), true);
+
+    assert_eq!(calculate_grouping_key(&a), calculate_grouping_key(&b));
+    assert_ne!(
+        calculate_fallback_grouping_key(&a),
+        calculate_fallback_grouping_key(&b)
+    );
+}
+
+#[test]
+fn test_native_grouping_respects_fingerprint() {
+    let mut event = events::native_crash(&[LIBCRASH_BASE + 0x1024]);
+    event["fingerprint"] = json!(["custom"]);
+    let event = symbolicated(event, true);
+
+    assert_eq!(calculate_grouping_key(&event), "custom");
+    assert_eq!(calculate_fallback_grouping_key(&event), None);
+}
+// =============================================================================
+// In-App Rules Tests
+// =============================================================================
+
+fn prefixes(prefixes: &[&str]) -> Vec<String> {
+    prefixes.iter().map(|p| p.to_string()).collect()
+}
+
+/// A Django error whose SDK marked the framework's frames as in-app
+fn framework_event() -> serde_json::Value {
+    json!({
+        "exception": {
+            "values": [{
+                "type": "KeyError",
+                "value": "'user'",
+                "stacktrace": {
+                    "frames": [
+                        { "module": "shop.views", "function": "checkout", "in_app": true },
+                        { "module": "django.core.handlers", "function": "inner", "in_app": true },
+                        { "module": "django.template.base", "function": "render", "in_app": true }
+                    ]
+                }
+            }]
+        }
+    })
+}
+
+#[test]
+fn test_excluding_framework_moves_culprit_frame() {
+    let mut event = framework_event();
+    assert_eq!(
+        get_denormalized_fields(&event).last_frame_module,
+        "django.template.base"
+    );
+
+    assert!(apply_in_app_rules(&mut event, &[], &prefixes(&["django."])));
+
+    let fields = get_denormalized_fields(&event);
+    assert_eq!(fields.last_frame_module, "shop.views");
+    assert_eq!(fields.last_frame_function, "checkout");
+    let frames = &event["exception"]["values"][0]["stacktrace"]["frames"];
+    assert_eq!(frames[1]["in_app"], false);
+    assert_eq!(frames[2]["in_app"], false);
+}
+
+#[test]
+fn test_in_app_include_wins_over_exclude() {
+    let mut event = framework_event();
+    apply_in_app_rules(
+        &mut event,
+        &prefixes(&["django.core."]),
+        &prefixes(&["django."]),
+    );
+
+    let fields = get_denormalized_fields(&event);
+    assert_eq!(fields.last_frame_module, "django.core.handlers");
+}
+
+#[test]
+fn test_in_app_rules_match_filename_without_module() {
+    let mut event = json!({
+        "exception": {
+            "values": [{
+                "type": "TypeError",
+                "value": "x is undefined",
+                "stacktrace": {
+                    "frames": [
+                        { "filename": "src/cart.js", "function": "total" },
+                        { "filename": "node_modules/lodash/map.js", "function": "map", "in_app": true }
+                    ]
+                }
+            }]
+        }
+    });
+
+    apply_in_app_rules(
+        &mut event,
+        &prefixes(&["src/"]),
+        &prefixes(&["node_modules/"]),
+    );
+
+    let fields = get_denormalized_fields(&event);
+    assert_eq!(fields.last_frame_filename, "src/cart.js");
+}
+
+#[test]
+fn test_no_in_app_rules_keep_sdk_flags() {
+    let mut event = framework_event();
+    let original = event.clone();
+
+    assert!(!apply_in_app_rules(&mut event, &[], &[]));
+    // Rules matching no frame change nothing either
+    assert!(!apply_in_app_rules(&mut event, &[], &prefixes(&["flask."])));
+    assert_eq!(event, original);
+}
+
+#[test]
+fn test_in_app_rules_cover_threads() {
+    let mut event = json!({
+        "threads": {
+            "values": [{
+                "stacktrace": {
+                    "frames": [{ "module": "django.db", "function": "execute", "in_app": true }]
+                }
+            }]
+        }
+    });
+
+    assert!(apply_in_app_rules(&mut event, &[], &prefixes(&["django."])));
+    assert_eq!(
+        event["threads"]["values"][0]["stacktrace"]["frames"][0]["in_app"],
+        false
+    );
+}
@@ -0,0 +1,214 @@
+//! Unit tests for the Issue model
+//!
+//! Tests the culprit shown under issue titles, the priority of new issues
+//! and the fix references given when resolving.
+
+use chrono::Utc;
+use rustrak::models::issue::NO_TRANSACTION;
+use rustrak::models::{Issue, IssueBundleQuery, IssueDetailQuery, IssuePriority, ResolveIssue};
+use uuid::Uuid;
+
+fn issue(transaction: &str, filename: &str, module: &str, function: &str) -> Issue {
+    Issue {
+        id: Uuid::new_v4(),
+        project_id: 1,
+        digest_order: 1,
+        first_seen: Utc::now(),
+        last_seen: Utc::now(),
+        digested_event_count: 1,
+        stored_event_count: 1,
+        last_event_order: 1,
+        last_event_id: None,
+        calculated_type: "ValueError".to_string(),
+        calculated_value: "bad amount".to_string(),
+        transaction: transaction.to_string(),
+        last_frame_filename: filename.to_string(),
+        last_frame_module: module.to_string(),
+        last_frame_function: function.to_string(),
+        level: Some("error".to_string()),
+        platform: Some("python".to_string()),
+        is_resolved: false,
+        is_muted: false,
+        deleted_at: None,
+        resolved_at: None,
+        resolved_by: None,
+        auto_resolved: false,
+        resolved_commit: None,
+        resolved_pr_url: None,
+        exception_chain_length: 1,
+        is_unhandled: false,
+        sample_rate: None,
+        regression_count: 0,
+        priority: IssuePriority::Medium,
+        priority_is_manual: false,
+        spike_window_start: None,
+        spike_base_event_count: 0,
+    }
+}
+
+#[test]
+fn test_culprit_prefers_transaction() {
+    let issue = issue(
+        "/checkout",
+        "app/views/checkout.py",
+        "app.views.checkout",
+        "process_payment",
+    );
+    assert_eq!(issue.culprit().as_deref(), Some("/checkout"));
+}
+
+#[test]
+fn test_culprit_falls_back_to_module_in_function() {
+    let issue = issue(
+        NO_TRANSACTION,
+        "app/views/checkout.py",
+        "app.views.checkout",
+        "process_payment",
+    );
+    assert_eq!(
+        issue.culprit().as_deref(),
+        Some("app.views.checkout in process_payment")
+    );
+
+    // An empty transaction is skipped too
+    let issue = self::issue("", "", "app.views.checkout", "process_payment");
+    assert_eq!(
+        issue.culprit().as_deref(),
+        Some("app.views.checkout in process_payment")
+    );
+}
+
+#[test]
+fn test_culprit_falls_back_to_filename() {
+    // Module without function isn't enough for `module in function`
+    let issue = issue(NO_TRANSACTION, "src/checkout.rs", "app.views.checkout", "");
+    assert_eq!(issue.culprit().as_deref(), Some("src/checkout.rs"));
+
+    let issue = self::issue(NO_TRANSACTION, "src/checkout.rs", "", "process_payment");
+    assert_eq!(issue.culprit().as_deref(), Some("src/checkout.rs"));
+}
+
+#[test]
+fn test_culprit_none_without_location() {
+    let issue = issue(NO_TRANSACTION, "", "", "");
+    assert_eq!(issue.culprit(), None);
+    assert!(issue.to_response("proj", 100).culprit.is_none());
+}
+
+#[test]
+fn test_culprit_in_response() {
+    let issue = issue("/checkout", "", "", "");
+    assert_eq!(
+        issue.to_response("proj", 100).culprit.as_deref(),
+        Some("/checkout")
+    );
+}
+
+#[test]
+fn test_detail_query_includes() {
+    let query = IssueDetailQuery {
+        include: Some("seen, grouping".to_string()),
+    };
+    assert!(query.includes("grouping"));
+    assert!(!query.includes("group"));
+    assert!(!IssueDetailQuery::default().includes("grouping"));
+}
+
+#[test]
+fn test_bundle_query_event_count() {
+    assert_eq!(
+        IssueBundleQuery::default().event_count(),
+        IssueBundleQuery::DEFAULT_EVENTS
+    );
+    let count = |events| {
+        IssueBundleQuery {
+            events: Some(events),
+        }
+        .event_count()
+    };
+    assert_eq!(count(3), 3);
+    assert_eq!(count(0), 0);
+    assert_eq!(count(-5), 0);
+    assert_eq!(count(1000), IssueBundleQuery::MAX_EVENTS);
+}
+
+#[test]
+fn test_priority_from_event() {
+    assert_eq!(
+        IssuePriority::from_event(Some("fatal"), true),
+        IssuePriority::High
+    );
+    // Handled fatal errors and unhandled errors aren't high by themselves
+    assert_eq!(
+        IssuePriority::from_event(Some("fatal"), false),
+        IssuePriority::Medium
+    );
+    assert_eq!(
+        IssuePriority::from_event(Some("error"), true),
+        IssuePriority::Medium
+    );
+    assert_eq!(
+        IssuePriority::from_event(None, false),
+        IssuePriority::Medium
+    );
+    for level in ["warning", "info", "debug"] {
+        assert_eq!(
+            IssuePriority::from_event(Some(level), true),
+            IssuePriority::Low
+        );
+    }
+}
+
+#[test]
+fn test_priority_raised() {
+    assert_eq!(IssuePriority::Low.raised(), Some(IssuePriority::Medium));
+    assert_eq!(IssuePriority::Medium.raised(), Some(IssuePriority::High));
+    assert_eq!(IssuePriority::High.raised(), None);
+    assert!(IssuePriority::Low < IssuePriority::High);
+}
+
+fn resolution(commit: Option<&str>, pr_url: Option<&str>) -> ResolveIssue {
+    ResolveIssue {
+        commit: commit.map(str::to_string),
+        pr_url: pr_url.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_resolve_issue_references() {
+    let valid = resolution(
+        Some(" a1b2c3d "),
+        Some("https://github.com/acme/app/pull/42"),
+    )
+    .validate()
+    .unwrap();
+    assert_eq!(valid.commit.as_deref(), Some("a1b2c3d"));
+    assert_eq!(
+        valid.pr_url.as_deref(),
+        Some("https://github.com/acme/app/pull/42")
+    );
+
+    // Blank references are left out
+    let blank = resolution(Some("  "), Some("")).validate().unwrap();
+    assert_eq!(blank.commit, None);
+    assert_eq!(blank.pr_url, None);
+}
+
+#[test]
+fn test_resolve_issue_rejects_bad_references() {
+    let long_commit = "a".repeat(ResolveIssue::MAX_COMMIT_LEN + 1);
+    for (commit, pr_url) in [
+        (Some(long_commit.as_str()), None),
+        (Some("fix the bug"), None),
+        (None, Some("not a url")),
+        (None, Some("ftp://example.com/pull/1")),
+        (None, Some("javascript:alert(1)")),
+    ] {
+        assert!(
+            resolution(commit, pr_url).validate().is_err(),
+            "{:?} {:?}",
+            commit,
+            pr_url
+        );
+    }
+}
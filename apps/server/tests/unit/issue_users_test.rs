@@ -0,0 +1,94 @@
+//! Unit tests for affected users
+//!
+//! Tests reading the event's user and writing the export CSV.
+
+use chrono::{TimeZone, Utc};
+use rustrak::digest::users::MAX_USER_FIELD_LENGTH;
+use rustrak::digest::{extract_user, EventUser};
+use rustrak::models::IssueUser;
+use rustrak::services::issue_user::{csv_field, csv_rows};
+use serde_json::json;
+
+#[test]
+fn test_extract_user() {
+    let user = extract_user(&json!({
+        "user": { "id": "42", "email": " ada@example.com ", "username": "ada", "ip_address": "10.0.0.1" }
+    }))
+    .unwrap();
+    assert_eq!(
+        user,
+        EventUser {
+            id: Some("42".to_string()),
+            email: Some("ada@example.com".to_string()),
+            username: Some("ada".to_string()),
+        }
+    );
+    assert_eq!(user.key(), "42");
+}
+
+#[test]
+fn test_extract_user_keys_without_id() {
+    let numeric = extract_user(&json!({ "user": { "id": 1234 } })).unwrap();
+    assert_eq!(numeric.key(), "1234");
+
+    let by_email = extract_user(&json!({ "user": { "email": "grace@example.com" } })).unwrap();
+    assert_eq!(by_email.key(), "grace@example.com");
+
+    let by_username = extract_user(&json!({ "user": { "id": "", "username": "lin" } })).unwrap();
+    assert_eq!(by_username.id, None);
+    assert_eq!(by_username.key(), "lin");
+}
+
+#[test]
+fn test_extract_user_needs_an_identity() {
+    assert_eq!(extract_user(&json!({})), None);
+    assert_eq!(
+        extract_user(&json!({ "user": { "ip_address": "10.0.0.1" } })),
+        None
+    );
+    assert_eq!(extract_user(&json!({ "user": "42" })), None);
+}
+
+#[test]
+fn test_extract_user_truncates_fields() {
+    let long = "x".repeat(MAX_USER_FIELD_LENGTH + 10);
+    let user = extract_user(&json!({ "user": { "id": long } })).unwrap();
+    assert_eq!(user.key().len(), MAX_USER_FIELD_LENGTH);
+}
+
+#[test]
+fn test_csv_field_quoting() {
+    assert_eq!(csv_field("ada"), "ada");
+    assert_eq!(csv_field(""), "");
+    assert_eq!(csv_field("Lovelace, Ada"), "\"Lovelace, Ada\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+}
+
+#[test]
+fn test_csv_field_neutralizes_formulas() {
+    assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+    assert_eq!(csv_field("+1"), "'+1");
+    assert_eq!(csv_field("-1"), "'-1");
+    assert_eq!(csv_field("@cmd"), "'@cmd");
+    assert_eq!(csv_field("=a,b"), "\"'=a,b\"");
+}
+
+#[test]
+fn test_csv_rows() {
+    let first_seen = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+    let users = [IssueUser {
+        user_key: "device-1".to_string(),
+        user_id: Some("device-1".to_string()),
+        email: None,
+        username: None,
+        first_seen,
+        last_seen: first_seen,
+        event_count: 3,
+    }];
+
+    assert_eq!(
+        csv_rows(&users),
+        "device-1,,,2026-01-02T03:04:05+00:00,2026-01-02T03:04:05+00:00,3\n"
+    );
+}
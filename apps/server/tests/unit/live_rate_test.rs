@@ -0,0 +1,88 @@
+//! Unit tests for the live event rates
+//!
+//! Tests the sliding window: bursts show up in both windows and decay as
+//! their seconds fall out of them.
+
+use rustrak::services::{LiveRates, RateWindows};
+
+#[test]
+fn test_burst_shows_in_both_windows() {
+    let rates = LiveRates::new();
+    for _ in 0..50 {
+        rates.record_accepted_at(1, 100);
+    }
+    for _ in 0..20 {
+        rates.record_digested_at(1, 101);
+    }
+
+    let rate = rates.project_at(1, 101);
+    assert_eq!(rate.project_id, 1);
+    assert_eq!(
+        rate.accepted,
+        RateWindows {
+            last_10s: 5.0,
+            last_60s: 50.0 / 60.0,
+        }
+    );
+    assert_eq!(rate.digested.last_10s, 2.0);
+}
+
+#[test]
+fn test_burst_decays() {
+    let rates = LiveRates::new();
+    for _ in 0..60 {
+        rates.record_accepted_at(1, 100);
+    }
+
+    // Still in the short window 9 seconds later, out of it after 10
+    assert_eq!(rates.project_at(1, 109).accepted.last_10s, 6.0);
+    assert_eq!(rates.project_at(1, 110).accepted.last_10s, 0.0);
+    assert_eq!(rates.project_at(1, 110).accepted.last_60s, 1.0);
+
+    // Gone from the full window after a minute
+    assert_eq!(rates.project_at(1, 160).accepted, RateWindows::default());
+}
+
+#[test]
+fn test_reused_bucket_starts_over() {
+    let rates = LiveRates::new();
+    rates.record_accepted_at(1, 100);
+    rates.record_accepted_at(1, 100);
+
+    // Second 160 lands in the bucket of second 100
+    rates.record_accepted_at(1, 160);
+
+    assert_eq!(rates.project_at(1, 160).accepted.last_60s, 1.0 / 60.0);
+}
+
+#[test]
+fn test_projects_are_counted_apart() {
+    let rates = LiveRates::new();
+    rates.record_accepted_at(1, 100);
+    for _ in 0..10 {
+        rates.record_accepted_at(2, 100);
+    }
+
+    assert_eq!(rates.project_at(1, 100).accepted.last_10s, 0.1);
+    assert_eq!(rates.project_at(2, 100).accepted.last_10s, 1.0);
+    assert_eq!(rates.project_at(3, 100).accepted, RateWindows::default());
+}
+
+#[test]
+fn test_all_drops_quiet_projects() {
+    let rates = LiveRates::new();
+    rates.record_accepted_at(1, 100);
+    rates.record_digested_at(2, 130);
+
+    let all = rates.all_at(150);
+    assert_eq!(
+        all.iter().map(|r| r.project_id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    let all = rates.all_at(165);
+    assert_eq!(
+        all.iter().map(|r| r.project_id).collect::<Vec<_>>(),
+        vec![2]
+    );
+}
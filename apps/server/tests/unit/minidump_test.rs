@@ -0,0 +1,151 @@
+//! Unit tests for minidump parsing
+//!
+//! Tests reading the exception, OS and module list from minidump headers, and
+//! the event built from them.
+
+use crate::common::MinidumpBuilder;
+use rustrak::error::AppError;
+use rustrak::ingest::minidump::{build_event, parse_minidump, MinidumpOs};
+use serde_json::json;
+
+const SIGSEGV: u32 = 11;
+const EXCEPTION_ACCESS_VIOLATION: u32 = 0xc000_0005;
+
+fn linux_crash() -> Vec<u8> {
+    MinidumpBuilder::new()
+        .with_exception(SIGSEGV, 0x5000_1234)
+        .with_module("/usr/bin/app", 0x4000_0000, 0x1000)
+        .with_module("/usr/lib/libcrash.so", 0x5000_0000, 0x2000)
+        .build()
+}
+
+// =============================================================================
+// Parsing Tests
+// =============================================================================
+
+#[test]
+fn test_parse_exception_and_modules() {
+    let info = parse_minidump(&linux_crash()).unwrap();
+
+    assert_eq!(info.os, Some(MinidumpOs::Linux));
+    let exception = info.exception.as_ref().unwrap();
+    assert_eq!(exception.code, SIGSEGV);
+    assert_eq!(exception.address, 0x5000_1234);
+
+    assert_eq!(info.modules.len(), 2);
+    assert_eq!(info.modules[0].name, "/usr/bin/app");
+    assert_eq!(info.modules[0].base_address, 0x4000_0000);
+    assert_eq!(info.modules[0].size, 0x1000);
+    assert!(info.modules[0].debug_id.is_none());
+}
+
+#[test]
+fn test_crashing_module_and_exception_name() {
+    let info = parse_minidump(&linux_crash()).unwrap();
+
+    assert_eq!(info.exception_name().as_deref(), Some("SIGSEGV"));
+    assert_eq!(info.crashing_module().unwrap().file_name(), "libcrash.so");
+}
+
+#[test]
+fn test_crash_outside_any_module() {
+    let dump = MinidumpBuilder::new()
+        .with_exception(SIGSEGV, 0x10)
+        .with_module("/usr/bin/app", 0x4000_0000, 0x1000)
+        .build();
+    let info = parse_minidump(&dump).unwrap();
+
+    assert!(info.crashing_module().is_none());
+}
+
+#[test]
+fn test_windows_exception_and_debug_id() {
+    let guid = [
+        0x78, 0x56, 0x34, 0x12, 0xbc, 0x9a, 0xf0, 0xde, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08,
+    ];
+    let dump = MinidumpBuilder::new()
+        .windows()
+        .with_exception(EXCEPTION_ACCESS_VIOLATION, 0x1400_0010)
+        .with_pdb_module("C:\\app\\crash.exe", 0x1400_0000, 0x1000, guid)
+        .build();
+    let info = parse_minidump(&dump).unwrap();
+
+    assert_eq!(info.os, Some(MinidumpOs::Windows));
+    assert_eq!(
+        info.exception_name().as_deref(),
+        Some("EXCEPTION_ACCESS_VIOLATION")
+    );
+    assert_eq!(info.crashing_module().unwrap().file_name(), "crash.exe");
+    assert_eq!(
+        info.modules[0].debug_id.as_deref(),
+        Some("12345678-9abc-def0-0102-030405060708-1")
+    );
+}
+
+#[test]
+fn test_unknown_exception_code_as_hex() {
+    let dump = MinidumpBuilder::new().with_exception(0x42, 0).build();
+    let info = parse_minidump(&dump).unwrap();
+
+    assert_eq!(info.exception_name().as_deref(), Some("0x00000042"));
+}
+
+#[test]
+fn test_rejects_missing_signature() {
+    let result = parse_minidump(b"NOPE0000000000000000000000000000");
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+#[test]
+fn test_rejects_truncated_minidump() {
+    let dump = linux_crash();
+    let result = parse_minidump(&dump[..dump.len() / 2]);
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
+
+// =============================================================================
+// Event Tests
+// =============================================================================
+
+#[test]
+fn test_build_event() {
+    let info = parse_minidump(&linux_crash()).unwrap();
+    let event = build_event(&info, "abc", None);
+
+    assert_eq!(event["event_id"], "abc");
+    assert_eq!(event["platform"], "native");
+    assert_eq!(event["level"], "fatal");
+    assert_eq!(event["exception"]["values"][0]["type"], "SIGSEGV");
+    assert_eq!(
+        event["exception"]["values"][0]["value"],
+        "Fatal Error: SIGSEGV in libcrash.so"
+    );
+    assert_eq!(
+        event["fingerprint"],
+        json!(["minidump", "SIGSEGV", "libcrash.so"])
+    );
+    assert_eq!(event["contexts"]["os"]["name"], "Linux");
+
+    let images = event["debug_meta"]["images"].as_array().unwrap();
+    assert_eq!(images.len(), 2);
+    assert_eq!(images[1]["code_file"], "/usr/lib/libcrash.so");
+    assert_eq!(images[1]["image_addr"], "0x50000000");
+    assert_eq!(images[1]["type"], "elf");
+}
+
+#[test]
+fn test_build_event_keeps_sentry_fields() {
+    let info = parse_minidump(&linux_crash()).unwrap();
+    let sentry = json!({
+        "release": "app@1.0.0",
+        "fingerprint": ["custom"],
+        "contexts": { "os": { "name": "Ubuntu" } },
+    });
+    let event = build_event(&info, "abc", Some(sentry));
+
+    assert_eq!(event["release"], "app@1.0.0");
+    assert_eq!(event["fingerprint"], json!(["custom"]));
+    assert_eq!(event["contexts"]["os"]["name"], "Ubuntu");
+    assert_eq!(event["platform"], "native");
+}
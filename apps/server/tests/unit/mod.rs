@@ -2,9 +2,38 @@
 //!
 //! Contains tests for individual components in isolation.
 
+mod alert_digest_test;
+mod alert_dispatch_test;
+mod alert_filters_test;
+mod api_rate_limit_test;
 mod auth_test;
+mod build_info_test;
+mod compress_test;
 mod config_test;
+mod contexts_test;
+mod db_health_test;
 mod decompression_test;
+mod digest_latency_test;
+mod digest_runner_test;
 mod envelope_parser_test;
+mod error_test;
+mod event_merge_test;
+mod fsync_test;
 mod grouping_test;
+mod issue_test;
+mod issue_users_test;
+mod live_rate_test;
+mod minidump_test;
 mod notification_test;
+mod origin_test;
+mod outbound_http_test;
+mod pagination_test;
+mod project_key_test;
+mod rate_limit_algorithm_test;
+mod remote_addr_test;
+mod request_id_test;
+mod sampling_test;
+mod self_monitoring_test;
+mod session_store_test;
+mod symbolication_test;
+mod tunnel_test;
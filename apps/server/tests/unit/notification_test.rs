@@ -2,8 +2,14 @@
 //!
 //! Tests the public validate_config API for webhook, slack, and email notifiers.
 
-use rustrak::models::ChannelType;
+use chrono::Utc;
+use rustrak::error::AppError;
+use rustrak::models::{
+    AlertPayload, ChannelType, DigestAlert, DigestIssue, IssueInfo, NotificationChannel,
+    WebhookConfig, WebhookMethod, WebhookPayloadStyle, MASKED_HEADER_VALUE,
+};
 use rustrak::services::create_dispatcher;
+use rustrak::services::notification::template::{self, TemplateFormat};
 use serde_json::json;
 use std::sync::Mutex;
 
@@ -79,6 +85,169 @@ fn test_webhook_validate_config_invalid_scheme() {
     assert!(dispatcher.validate_config(&config).is_err());
 }
 
+#[test]
+fn test_webhook_config_verify_tls() {
+    let dispatcher = create_dispatcher(ChannelType::Webhook);
+    let config = json!({
+        "url": "https://hooks.internal/alerts",
+        "verify_tls": false
+    });
+    assert!(dispatcher.validate_config(&config).is_ok());
+
+    let parsed: WebhookConfig = serde_json::from_value(config).unwrap();
+    assert!(!parsed.verify_tls);
+
+    // Certificates are checked unless a channel opts out
+    let parsed: WebhookConfig =
+        serde_json::from_value(json!({ "url": "https://example.com/webhook" })).unwrap();
+    assert!(parsed.verify_tls);
+
+    // Must be a boolean
+    let config = json!({
+        "url": "https://example.com/webhook",
+        "verify_tls": "no"
+    });
+    assert!(dispatcher.validate_config(&config).is_err());
+}
+
+#[test]
+fn test_webhook_config_timeout() {
+    let dispatcher = create_dispatcher(ChannelType::Webhook);
+    let config = json!({
+        "url": "https://example.com/webhook",
+        "timeout_secs": 5
+    });
+    assert!(dispatcher.validate_config(&config).is_ok());
+
+    let parsed: WebhookConfig = serde_json::from_value(config).unwrap();
+    assert_eq!(parsed.timeout_secs, Some(5));
+
+    // The global delivery timeout applies unless a channel sets its own
+    let parsed: WebhookConfig =
+        serde_json::from_value(json!({ "url": "https://example.com/webhook" })).unwrap();
+    assert_eq!(parsed.timeout_secs, None);
+
+    let config = json!({
+        "url": "https://example.com/webhook",
+        "timeout_secs": 0
+    });
+    assert!(dispatcher.validate_config(&config).is_err());
+}
+
+#[test]
+fn test_webhook_config_method_and_payload_style() {
+    let dispatcher = create_dispatcher(ChannelType::Webhook);
+    let parsed: WebhookConfig =
+        serde_json::from_value(json!({ "url": "https://example.com/webhook" })).unwrap();
+    assert_eq!(parsed.method, WebhookMethod::Post);
+    assert_eq!(parsed.payload_style, WebhookPayloadStyle::Full);
+
+    let config = json!({
+        "url": "https://example.com/webhook",
+        "method": "PUT",
+        "payload_style": "flat"
+    });
+    assert!(dispatcher.validate_config(&config).is_ok());
+    let parsed: WebhookConfig = serde_json::from_value(config).unwrap();
+    assert_eq!(parsed.method, WebhookMethod::Put);
+    assert_eq!(parsed.payload_style, WebhookPayloadStyle::Flat);
+
+    for (field, value) in [
+        ("method", "PATCH"),
+        ("method", "put"),
+        ("payload_style", "nested"),
+    ] {
+        let mut config = json!({ "url": "https://example.com/webhook" });
+        config[field] = json!(value);
+        assert!(
+            dispatcher.validate_config(&config).is_err(),
+            "{} {}",
+            field,
+            value
+        );
+    }
+}
+
+#[test]
+fn test_webhook_validate_config_headers() {
+    let dispatcher = create_dispatcher(ChannelType::Webhook);
+    let config = json!({
+        "url": "https://example.com/webhook",
+        "headers": { "X-Api-Key": "secret", "Authorization": "Bearer abc" }
+    });
+    assert!(dispatcher.validate_config(&config).is_ok());
+
+    // Hop-by-hop and notifier headers, invalid names and values
+    for headers in [
+        json!({ "Connection": "close" }),
+        json!({ "transfer-encoding": "chunked" }),
+        json!({ "Host": "evil.example.com" }),
+        json!({ "Content-Type": "text/plain" }),
+        json!({ "X-Rustrak-Signature": "sha256=forged" }),
+        json!({ "x-rustrak-timestamp": "0" }),
+        json!({ "X-Rustrak-Request-ID": "replayed" }),
+        json!({ "X Api Key": "secret" }),
+        json!({ "": "secret" }),
+        json!({ "X-Api-Key": "line\nbreak" }),
+    ] {
+        let config = json!({ "url": "https://example.com/webhook", "headers": headers });
+        assert!(
+            dispatcher.validate_config(&config).is_err(),
+            "{}",
+            config["headers"]
+        );
+    }
+}
+
+#[test]
+fn test_webhook_channel_secrets_masked() {
+    let channel = NotificationChannel {
+        id: 1,
+        name: "n8n".to_string(),
+        channel_type: ChannelType::Webhook,
+        config: json!({
+            "url": "https://example.com/webhook",
+            "secret": "signing-key",
+            "headers": { "X-Api-Key": "secret", "X-Team": "core" }
+        }),
+        is_enabled: true,
+        failure_count: 0,
+        last_failure_at: None,
+        last_failure_message: None,
+        last_success_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    let masked = serde_json::to_value(channel.clone().masked()).unwrap();
+    assert_eq!(
+        masked["config"]["headers"],
+        json!({ "X-Api-Key": MASKED_HEADER_VALUE, "X-Team": MASKED_HEADER_VALUE })
+    );
+    assert_eq!(masked["config"]["secret"], MASKED_HEADER_VALUE);
+    assert_eq!(masked["config"]["url"], "https://example.com/webhook");
+
+    // Masked values sent back keep the stored ones; new values replace them
+    let mut update = masked["config"].clone();
+    update["headers"]["X-Team"] = json!("platform");
+    update["headers"]["X-New"] = json!(MASKED_HEADER_VALUE);
+    channel.unmask(&mut update);
+    assert_eq!(update["secret"], "signing-key");
+    assert_eq!(
+        update["headers"],
+        json!({
+            "X-Api-Key": "secret",
+            "X-Team": "platform",
+            "X-New": MASKED_HEADER_VALUE
+        })
+    );
+
+    let mut update = masked["config"].clone();
+    update["secret"] = json!("rotated-key");
+    channel.unmask(&mut update);
+    assert_eq!(update["secret"], "rotated-key");
+}
+
 // =============================================================================
 // Slack Config Validation Tests
 // =============================================================================
@@ -133,6 +302,128 @@ fn test_slack_validate_config_rejects_http() {
     assert!(dispatcher.validate_config(&config).is_err());
 }
 
+// =============================================================================
+// Template Tests
+// =============================================================================
+
+const SLACK_URL: &str = "https://hooks.slack.com/services/T00000000/B00000000/XXXXXXXX";
+
+#[test]
+fn test_template_renders_nested_fields() {
+    let rendered = template::render(
+        "Nuevo error en {{project.name}}: {{issue.title}} ({{issue.short_id}}, {{issue.event_count}} eventos)",
+        TemplateFormat::SlackText,
+        &AlertPayload::sample(),
+    )
+    .unwrap();
+    assert_eq!(
+        rendered,
+        "Nuevo error en Test Project: This is a test alert from Rustrak (TEST-1, 1 eventos)"
+    );
+
+    let dispatcher = create_dispatcher(ChannelType::Slack);
+    let config = json!({
+        "webhook_url": SLACK_URL,
+        "template": "{{issue.short_id}} in {{project.slug}}: <{{issue_url}}|{{issue.title}}>"
+    });
+    assert!(dispatcher.validate_config(&config).is_ok());
+}
+
+#[test]
+fn test_template_handles_digests() {
+    let template =
+        "{{#if issues}}{{#each issues}}{{short_id}} {{/each}}{{else}}{{issue.short_id}}{{/if}}";
+    let dispatcher = create_dispatcher(ChannelType::Slack);
+    let config = json!({ "webhook_url": SLACK_URL, "template": template });
+    assert!(dispatcher.validate_config(&config).is_ok());
+
+    let AlertPayload::Issue(alert) = AlertPayload::sample() else {
+        unreachable!()
+    };
+    let digest = AlertPayload::Digest(DigestAlert {
+        alert_id: "1-digest-1".to_string(),
+        alert_type: "new_issue".to_string(),
+        triggered_at: alert.triggered_at,
+        window_start: alert.triggered_at,
+        project: alert.project.clone(),
+        issues: ["API-1", "API-2"]
+            .map(|short_id| DigestIssue {
+                issue: IssueInfo {
+                    short_id: short_id.to_string(),
+                    ..alert.issue.clone()
+                },
+                issue_url: alert.issue_url.clone(),
+            })
+            .to_vec(),
+        project_url: alert.issue_url.clone(),
+        actor: alert.actor.clone(),
+    });
+    assert_eq!(
+        template::render(template, TemplateFormat::SlackText, &digest).unwrap(),
+        "API-1 API-2 "
+    );
+}
+
+#[test]
+fn test_template_escapes_for_format() {
+    let mut payload = AlertPayload::sample();
+    if let AlertPayload::Issue(ref mut alert) = payload {
+        alert.issue.title = r#"KeyError: "<id>" & more"#.to_string();
+    }
+
+    let slack = template::render("{{issue.title}}", TemplateFormat::SlackText, &payload).unwrap();
+    assert_eq!(slack, r#"KeyError: "&lt;id&gt;" &amp; more"#);
+
+    let body = template::render(
+        r#"{"text": "{{issue.title}}"}"#,
+        TemplateFormat::Json,
+        &payload,
+    )
+    .unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["text"], r#"KeyError: "<id>" & more"#);
+}
+
+#[test]
+fn test_template_missing_field_fails_validation() {
+    let dispatcher = create_dispatcher(ChannelType::Slack);
+    let config = json!({
+        "webhook_url": SLACK_URL,
+        "template": "{{issue.short_id}}: {{issue.nmae}}"
+    });
+
+    match dispatcher.validate_config(&config) {
+        Err(AppError::Validation(msg)) => {
+            assert!(msg.contains("issue.nmae"), "{}", msg);
+            // The sample output shows where the template went wrong
+            assert!(msg.contains("Sample output: TEST-1: "), "{}", msg);
+        }
+        other => panic!("Expected validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_webhook_template_must_render_json() {
+    let dispatcher = create_dispatcher(ChannelType::Webhook);
+    let config = |template: &str| {
+        json!({
+            "url": "https://example.com/webhook",
+            "template": template
+        })
+    };
+
+    assert!(dispatcher
+        .validate_config(&config(r#"{"summary": "{{issue.title}}"}"#))
+        .is_ok());
+    match dispatcher.validate_config(&config("Alert: {{issue.title}}")) {
+        Err(AppError::Validation(msg)) => {
+            assert!(msg.contains("must render JSON"), "{}", msg);
+            assert!(msg.contains("Alert: This is a test alert"), "{}", msg);
+        }
+        other => panic!("Expected validation error, got {:?}", other),
+    }
+}
+
 // =============================================================================
 // Email Config Validation Tests
 // =============================================================================
@@ -0,0 +1,145 @@
+//! Unit tests for allowed domains
+//!
+//! Tests origin pattern matching, which events are checked and pattern
+//! validation.
+
+use actix_web::test::TestRequest;
+use rustrak::error::AppError;
+use rustrak::ingest::origin::{
+    is_event_allowed, is_host_allowed, matches_domain, request_origin, validate_allowed_domains,
+};
+use serde_json::json;
+
+fn domains(patterns: &[&str]) -> Vec<String> {
+    patterns.iter().map(|p| p.to_string()).collect()
+}
+
+#[test]
+fn test_matches_domain() {
+    assert!(matches_domain("example.com", "example.com"));
+    assert!(!matches_domain("example.com", "www.example.com"));
+    assert!(!matches_domain("example.com", "example.com.evil.io"));
+
+    assert!(matches_domain("*.example.com", "app.example.com"));
+    assert!(matches_domain("*.example.com", "a.b.example.com"));
+    assert!(matches_domain("*.example.com", "example.com"));
+    assert!(!matches_domain("*.example.com", "badexample.com"));
+
+    assert!(matches_domain(
+        "app-*.example.com",
+        "app-staging.example.com"
+    ));
+    assert!(!matches_domain("app-*.example.com", "api.example.com"));
+    assert!(matches_domain("*", "anything.io"));
+}
+
+#[test]
+fn test_is_host_allowed_with_ports() {
+    let allowed = domains(&["example.com", "localhost:3000"]);
+
+    // Patterns without a port match any port
+    assert!(is_host_allowed(&allowed, "example.com:8443"));
+    assert!(is_host_allowed(&allowed, "EXAMPLE.com"));
+    assert!(is_host_allowed(&allowed, "localhost:3000"));
+    assert!(!is_host_allowed(&allowed, "localhost:4000"));
+    assert!(!is_host_allowed(&allowed, "localhost"));
+}
+
+#[test]
+fn test_browser_events_are_checked() {
+    let allowed = domains(&["*.example.com"]);
+    let event =
+        json!({"platform": "javascript", "request": {"url": "https://app.example.com/home"}});
+
+    assert!(is_event_allowed(&allowed, Some("app.example.com"), &event));
+    assert!(!is_event_allowed(&allowed, Some("evil.io"), &event));
+
+    // Without an Origin header the event's own URL is checked
+    assert!(is_event_allowed(&allowed, None, &event));
+    let foreign = json!({"platform": "javascript", "request": {"url": "https://evil.io/"}});
+    assert!(!is_event_allowed(&allowed, None, &foreign));
+
+    // Both hosts must be allowed
+    assert!(!is_event_allowed(
+        &allowed,
+        Some("app.example.com"),
+        &foreign
+    ));
+}
+
+#[test]
+fn test_server_side_events_bypass_check() {
+    let allowed = domains(&["example.com"]);
+    let event = json!({"platform": "python", "request": {"url": "https://internal.local/api"}});
+
+    assert!(is_event_allowed(&allowed, None, &event));
+    // An Origin header marks the event as coming from a browser
+    assert!(!is_event_allowed(&allowed, Some("evil.io"), &event));
+}
+
+#[test]
+fn test_empty_list_allows_everything() {
+    let event = json!({"platform": "javascript", "request": {"url": "https://evil.io/"}});
+    assert!(is_event_allowed(&[], Some("evil.io"), &event));
+}
+
+#[test]
+fn test_request_origin() {
+    let req = TestRequest::default()
+        .insert_header(("Origin", "https://App.Example.com:8443"))
+        .insert_header(("Referer", "https://other.io/page"))
+        .to_http_request();
+    assert_eq!(
+        request_origin(&req).as_deref(),
+        Some("app.example.com:8443")
+    );
+
+    // `Origin: null` falls back to the Referer
+    let req = TestRequest::default()
+        .insert_header(("Origin", "null"))
+        .insert_header(("Referer", "https://other.io/page"))
+        .to_http_request();
+    assert_eq!(request_origin(&req).as_deref(), Some("other.io"));
+
+    assert_eq!(
+        request_origin(&TestRequest::default().to_http_request()),
+        None
+    );
+}
+
+#[test]
+fn test_validate_allowed_domains_normalizes() {
+    let normalized = validate_allowed_domains(
+        &domains(&[" Example.com ", "*.example.com", "example.com"]),
+        false,
+    )
+    .unwrap();
+    assert_eq!(normalized, ["example.com", "*.example.com"]);
+}
+
+#[test]
+fn test_validate_allowed_domains_rejects_invalid() {
+    for pattern in [
+        "",
+        "https://example.com",
+        "example.com/path",
+        "exa mple.com",
+    ] {
+        let err = validate_allowed_domains(&domains(&[pattern]), false).unwrap_err();
+        assert!(
+            matches!(err, AppError::InvalidField { .. }),
+            "{:?}",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn test_validate_allowed_domains_wildcard_needs_confirmation() {
+    assert!(validate_allowed_domains(&domains(&["*"]), false).is_err());
+    assert!(validate_allowed_domains(&domains(&["*.*"]), false).is_err());
+    assert_eq!(
+        validate_allowed_domains(&domains(&["*"]), true).unwrap(),
+        ["*"]
+    );
+}
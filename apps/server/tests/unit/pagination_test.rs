@@ -0,0 +1,525 @@
+//! Unit tests for pagination
+//!
+//! Tests the opaque cursor format (round trips, signing, and rejection of
+//! tampered, truncated or misplaced cursors) and page size bounds.
+
+use actix_web::{http::StatusCode, ResponseError};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{TimeZone, Utc};
+use rustrak::config::PaginationConfig;
+use rustrak::error::AppError;
+use rustrak::models::{AlertStatus, IssuePriority};
+use rustrak::pagination::{
+    AggregateIssuesQuery, CursorError, CursorSigner, EventCursor, IssueCursor, IssueFilter,
+    IssueGroupBy, IssueSort, ListAlertHistoryQuery, ListEventsQuery, ListFormat, ListIssuesQuery,
+    ListProjectsQuery, ListTokensQuery,
+};
+use uuid::Uuid;
+
+const PROJECT_ID: i32 = 1;
+const ISSUE_ID: Uuid = Uuid::from_u128(0x42);
+
+/// Encodes JSON the way cursors were before they were signed
+fn encode_json(json: &str) -> String {
+    URL_SAFE_NO_PAD.encode(json.as_bytes())
+}
+
+// =============================================================================
+// Round Trip Tests
+// =============================================================================
+
+#[test]
+fn test_issue_cursor_digest_order_round_trip() {
+    let cursor = IssueCursor::new("digest_order", "asc").with_digest_order(7);
+
+    let decoded = IssueCursor::decode(&cursor.encode(PROJECT_ID).unwrap(), PROJECT_ID).unwrap();
+
+    assert_eq!(decoded.sort, "digest_order");
+    assert_eq!(decoded.order, "asc");
+    assert_eq!(decoded.last_digest_order, Some(7));
+    assert!(decoded.last_seen.is_none());
+    assert!(decoded.last_id.is_none());
+}
+
+#[test]
+fn test_issue_cursor_last_seen_round_trip() {
+    let last_seen = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap();
+    let id = Uuid::new_v4();
+    let cursor = IssueCursor::new("last_seen", "desc").with_last_seen(last_seen, id);
+
+    let decoded = IssueCursor::decode(&cursor.encode(PROJECT_ID).unwrap(), PROJECT_ID).unwrap();
+
+    assert_eq!(decoded.sort, "last_seen");
+    assert_eq!(decoded.last_seen, Some(last_seen));
+    assert_eq!(decoded.last_id, Some(id));
+}
+
+#[test]
+fn test_event_cursor_round_trip() {
+    let cursor = EventCursor::new("desc", 250);
+
+    let decoded = EventCursor::decode(&cursor.encode(ISSUE_ID).unwrap(), ISSUE_ID).unwrap();
+
+    assert_eq!(decoded.by, "received");
+    assert_eq!(decoded.order, "desc");
+    assert_eq!(decoded.last_digest_order, 250);
+    assert!(decoded.last_timestamp.is_none());
+}
+
+#[test]
+fn test_event_cursor_timestamp_round_trip() {
+    let timestamp = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap();
+    let cursor = EventCursor::new("asc", 12).with_timestamp(timestamp);
+
+    let decoded = EventCursor::decode(&cursor.encode(ISSUE_ID).unwrap(), ISSUE_ID).unwrap();
+
+    assert_eq!(decoded.by, "timestamp");
+    assert_eq!(decoded.order, "asc");
+    assert_eq!(decoded.last_digest_order, 12);
+    assert_eq!(decoded.last_timestamp, Some(timestamp));
+}
+
+#[test]
+fn test_event_cursor_without_ordering_is_received() {
+    let encoded = encode_json(r#"{"order":"desc","last_digest_order":3}"#);
+
+    let decoded = EventCursor::decode(&encoded, ISSUE_ID).unwrap();
+
+    assert_eq!(decoded.by, "received");
+    assert_eq!(decoded.last_digest_order, 3);
+}
+
+#[test]
+fn test_encoded_cursor_is_url_safe() {
+    let cursor = IssueCursor::new("last_seen", "desc").with_last_seen(Utc::now(), Uuid::new_v4());
+    let encoded = cursor.encode(PROJECT_ID).unwrap();
+
+    assert!(encoded
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'));
+}
+
+// =============================================================================
+// Rejection Tests
+// =============================================================================
+
+#[test]
+fn test_invalid_base64_rejected() {
+    assert_eq!(
+        IssueCursor::decode("not-valid-base64!!!", PROJECT_ID).unwrap_err(),
+        CursorError::Encoding
+    );
+    assert!(EventCursor::decode("invalid_cursor", ISSUE_ID).is_err());
+}
+
+#[test]
+fn test_truncated_cursor_rejected() {
+    let encoded = EventCursor::new("asc", 100).encode(ISSUE_ID).unwrap();
+    let truncated = &encoded[..encoded.len() / 2];
+
+    assert!(EventCursor::decode(truncated, ISSUE_ID).is_err());
+    assert!(EventCursor::decode("", ISSUE_ID).is_err());
+}
+
+#[test]
+fn test_non_utf8_cursor_rejected() {
+    let encoded = URL_SAFE_NO_PAD.encode([0xff, 0xfe, 0xfd]);
+    assert_eq!(
+        EventCursor::decode(&encoded, ISSUE_ID).unwrap_err(),
+        CursorError::Encoding
+    );
+}
+
+#[test]
+fn test_cursor_of_wrong_shape_rejected() {
+    let encoded = encode_json(r#"{"order":"asc","last_digest_order":"ten"}"#);
+    assert_eq!(
+        EventCursor::decode(&encoded, ISSUE_ID).unwrap_err(),
+        CursorError::Format
+    );
+
+    // An event cursor is not an issue cursor
+    let event_cursor = encode_json(r#"{"order":"asc","last_digest_order":1}"#);
+    assert_eq!(
+        IssueCursor::decode(&event_cursor, PROJECT_ID).unwrap_err(),
+        CursorError::Format
+    );
+}
+
+#[test]
+fn test_tampered_cursor_rejected() {
+    let cases = [
+        r#"{"sort":"digest_order","order":"sideways","last_digest_order":1}"#,
+        r#"{"sort":"random","order":"asc","last_digest_order":1}"#,
+        r#"{"sort":"digest_order","order":"asc"}"#,
+        r#"{"sort":"last_seen","order":"desc","last_digest_order":1}"#,
+    ];
+
+    for json in cases {
+        assert!(
+            matches!(
+                IssueCursor::decode(&encode_json(json), PROJECT_ID),
+                Err(CursorError::Invalid(_))
+            ),
+            "expected {} to be rejected",
+            json
+        );
+    }
+
+    let event_cases = [
+        r#"{"order":"up","last_digest_order":1}"#,
+        r#"{"by":"random","order":"asc","last_digest_order":1}"#,
+        r#"{"by":"timestamp","order":"asc","last_digest_order":1}"#,
+    ];
+
+    for json in event_cases {
+        assert!(
+            matches!(
+                EventCursor::decode(&encode_json(json), ISSUE_ID),
+                Err(CursorError::Invalid(_))
+            ),
+            "expected {} to be rejected",
+            json
+        );
+    }
+}
+
+// =============================================================================
+// Signing Tests
+// =============================================================================
+
+#[test]
+fn test_signed_cursor_verifies() {
+    let signer = CursorSigner::new(b"test-key", false);
+
+    let signed = signer.sign("payload", "issue:1");
+
+    assert!(signed.starts_with("payload."));
+    assert_eq!(signer.verify(&signed, "issue:1"), Ok("payload"));
+}
+
+#[test]
+fn test_cursor_from_another_scope_rejected() {
+    let signer = CursorSigner::new(b"test-key", false);
+    let signed = signer.sign("payload", "issue:1");
+
+    assert_eq!(
+        signer.verify(&signed, "issue:2"),
+        Err(CursorError::Signature)
+    );
+    assert_eq!(
+        signer.verify(&signed, "project:1"),
+        Err(CursorError::Signature)
+    );
+
+    // Through the cursor types: an event cursor of one issue is rejected on another
+    let cursor = EventCursor::new("desc", 10).encode(ISSUE_ID).unwrap();
+    assert_eq!(
+        EventCursor::decode(&cursor, Uuid::new_v4()).unwrap_err(),
+        CursorError::Signature
+    );
+    let cursor = IssueCursor::new("digest_order", "desc")
+        .with_digest_order(10)
+        .encode(PROJECT_ID)
+        .unwrap();
+    assert_eq!(
+        IssueCursor::decode(&cursor, PROJECT_ID + 1).unwrap_err(),
+        CursorError::Signature
+    );
+}
+
+#[test]
+fn test_cursor_signed_with_another_key_rejected() {
+    let signed = CursorSigner::new(b"other-key", false).sign("payload", "issue:1");
+
+    assert_eq!(
+        CursorSigner::new(b"test-key", false).verify(&signed, "issue:1"),
+        Err(CursorError::Signature)
+    );
+}
+
+#[test]
+fn test_tampered_signed_cursor_rejected() {
+    let signer = CursorSigner::new(b"test-key", false);
+    let signed = signer.sign("eyJvcmRlciI6ImFzYyJ9", "issue:1");
+
+    // Truncated anywhere: in the signature, at the separator, or in the payload
+    for len in 1..signed.len() {
+        assert_eq!(
+            signer.verify(&signed[..len], "issue:1"),
+            Err(CursorError::Signature),
+            "expected {} to be rejected",
+            &signed[..len]
+        );
+    }
+
+    // Payload swapped under a valid signature
+    let (_, signature) = signed.split_once('.').unwrap();
+    let forged = format!("eyJvcmRlciI6ImRlc2MifQ.{}", signature);
+    assert_eq!(
+        signer.verify(&forged, "issue:1"),
+        Err(CursorError::Signature)
+    );
+}
+
+#[test]
+fn test_unsigned_cursor_only_accepted_while_allowed() {
+    let legacy = encode_json(r#"{"order":"asc","last_digest_order":1}"#);
+
+    assert_eq!(
+        CursorSigner::new(b"test-key", true).verify(&legacy, "issue:1"),
+        Ok(legacy.as_str())
+    );
+    assert_eq!(
+        CursorSigner::new(b"test-key", false).verify(&legacy, "issue:1"),
+        Err(CursorError::Signature)
+    );
+}
+
+#[test]
+fn test_signed_cursors_stay_under_200_bytes() {
+    let last_seen = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap()
+        + chrono::Duration::nanoseconds(123_456_789);
+    let cursor = IssueCursor::new("last_seen", "desc")
+        .with_last_seen(last_seen, Uuid::new_v4())
+        .encode(i32::MAX)
+        .unwrap();
+    assert!(cursor.len() < 200, "{} bytes", cursor.len());
+
+    let cursor = EventCursor::new("desc", i32::MIN).encode(ISSUE_ID).unwrap();
+    assert!(cursor.len() < 200, "{} bytes", cursor.len());
+}
+
+#[test]
+fn test_decode_errors_map_to_bad_request() {
+    for err in [
+        CursorError::Encoding,
+        CursorError::Format,
+        CursorError::Invalid("unknown order"),
+        CursorError::Signature,
+    ] {
+        let app_error: AppError = err.into();
+        assert_eq!(app_error.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    let app_error: AppError = CursorError::Serialization("boom".to_string()).into();
+    assert_eq!(app_error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+// =============================================================================
+// Page Size Tests
+// =============================================================================
+
+fn issues_query(query: &str) -> ListIssuesQuery {
+    actix_web::web::Query::<ListIssuesQuery>::from_query(query)
+        .unwrap()
+        .into_inner()
+}
+
+#[test]
+fn test_per_page_defaults_to_configured_default() {
+    let config = PaginationConfig {
+        default_page_size: 30,
+        max_page_size: 100,
+        ..Default::default()
+    };
+
+    assert_eq!(issues_query("").per_page(&config).unwrap(), 30);
+    assert_eq!(issues_query("per_page=45").per_page(&config).unwrap(), 45);
+}
+
+#[test]
+fn test_per_page_over_max_rejected() {
+    let config = PaginationConfig::default();
+
+    assert_eq!(issues_query("per_page=100").per_page(&config).unwrap(), 100);
+    for per_page in ["101", "1000000"] {
+        let err = issues_query(&format!("per_page={}", per_page))
+            .per_page(&config)
+            .unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert!(err.to_string().contains("at most 100"), "{}", err);
+    }
+}
+
+#[test]
+fn test_issues_query_dist() {
+    assert_eq!(issues_query("dist=412").dist.as_deref(), Some("412"));
+    assert_eq!(issues_query("").dist, None);
+}
+
+#[test]
+fn test_issues_query_sort_defaults_to_project() {
+    assert_eq!(issues_query("").sort, None);
+    assert_eq!(
+        issues_query("").sort(IssueSort::LastSeen),
+        IssueSort::LastSeen
+    );
+    assert_eq!(
+        issues_query("sort=digest_order").sort(IssueSort::LastSeen),
+        IssueSort::DigestOrder
+    );
+    assert!(actix_web::web::Query::<ListIssuesQuery>::from_query("sort=title").is_err());
+}
+
+#[test]
+fn test_issues_query_priority() {
+    let query = issues_query("priority=high&sort=priority");
+    assert_eq!(query.priority, Some(IssuePriority::High));
+    assert_eq!(query.sort(IssueSort::DigestOrder), IssueSort::Priority);
+    assert_eq!(issues_query("").priority, None);
+    assert!(actix_web::web::Query::<ListIssuesQuery>::from_query("priority=urgent").is_err());
+}
+
+#[test]
+fn test_issues_query_handled() {
+    assert_eq!(issues_query("handled=false").handled, Some(false));
+    assert_eq!(issues_query("handled=true").handled, Some(true));
+    assert_eq!(issues_query("").handled, None);
+    assert!(actix_web::web::Query::<ListIssuesQuery>::from_query("handled=maybe").is_err());
+}
+
+#[test]
+fn test_issues_query_includes() {
+    assert!(issues_query("include=sparkline").includes("sparkline"));
+    assert!(issues_query("include=latest_event,%20sparkline").includes("sparkline"));
+    assert!(!issues_query("include=sparklines").includes("sparkline"));
+    assert!(!issues_query("").includes("sparkline"));
+}
+
+#[test]
+fn test_per_page_clamped_to_one() {
+    let config = PaginationConfig::default();
+
+    assert_eq!(issues_query("per_page=1").per_page(&config).unwrap(), 1);
+    assert_eq!(issues_query("per_page=0").per_page(&config).unwrap(), 1);
+    assert_eq!(issues_query("per_page=-5").per_page(&config).unwrap(), 1);
+}
+
+#[test]
+fn test_projects_per_page_bounds() {
+    let config = PaginationConfig {
+        default_page_size: 20,
+        max_page_size: 50,
+        ..Default::default()
+    };
+    let query = |q: &str| {
+        actix_web::web::Query::<ListProjectsQuery>::from_query(q)
+            .unwrap()
+            .into_inner()
+    };
+
+    assert_eq!(query("").per_page(&config).unwrap(), 20);
+    assert_eq!(query("per_page=0").per_page(&config).unwrap(), 1);
+    assert!(query("per_page=51").per_page(&config).is_err());
+}
+
+#[test]
+fn test_events_per_page_defaults_to_events_page_size() {
+    let config = PaginationConfig {
+        default_page_size: 20,
+        max_page_size: 50,
+        events_page_size: 40,
+        ..Default::default()
+    };
+    let query = |q: &str| {
+        actix_web::web::Query::<ListEventsQuery>::from_query(q)
+            .unwrap()
+            .into_inner()
+    };
+
+    assert_eq!(query("").per_page(&config).unwrap(), 40);
+    assert_eq!(query("per_page=10").per_page(&config).unwrap(), 10);
+    assert_eq!(query("per_page=0").per_page(&config).unwrap(), 1);
+    assert_eq!(query("per_page=50").per_page(&config).unwrap(), 50);
+
+    let err = query("per_page=1000").per_page(&config).unwrap_err();
+    assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_projects_query_include() {
+    let query = |q: &str| {
+        actix_web::web::Query::<ListProjectsQuery>::from_query(q)
+            .unwrap()
+            .into_inner()
+    };
+
+    assert!(query("include=stats").includes("stats"));
+    assert!(query("include=keys,%20stats").includes("stats"));
+    assert!(!query("include=statistics").includes("stats"));
+    assert!(!query("").includes("stats"));
+}
+
+#[test]
+fn test_alert_history_query_filters() {
+    let query = actix_web::web::Query::<ListAlertHistoryQuery>::from_query(
+        "page=2&per_page=500&status=failed&channel_id=3",
+    )
+    .unwrap()
+    .into_inner();
+
+    assert_eq!(query.page, 2);
+    assert!(query.per_page(&PaginationConfig::default()).is_err());
+    assert_eq!(query.status, Some(AlertStatus::Failed));
+    assert_eq!(query.channel_id, Some(3));
+
+    assert!(actix_web::web::Query::<ListAlertHistoryQuery>::from_query("status=bogus").is_err());
+}
+
+#[test]
+fn test_tokens_query_format() {
+    let query = |q: &str| {
+        actix_web::web::Query::<ListTokensQuery>::from_query(q)
+            .unwrap()
+            .into_inner()
+    };
+
+    assert_eq!(query("").format, ListFormat::Paginated);
+    assert_eq!(query("format=flat").format, ListFormat::Flat);
+    assert_eq!(query("page=3").page, 3);
+    assert!(query("per_page=500")
+        .per_page(&PaginationConfig::default())
+        .is_err());
+
+    assert!(actix_web::web::Query::<ListTokensQuery>::from_query("format=csv").is_err());
+}
+
+fn aggregate_query(q: &str) -> AggregateIssuesQuery {
+    actix_web::web::Query::<AggregateIssuesQuery>::from_query(q)
+        .unwrap()
+        .into_inner()
+}
+
+#[test]
+fn test_aggregate_query_params() {
+    let query = aggregate_query("group_by=transaction&filter=all&dist=412");
+    assert_eq!(query.group_by, IssueGroupBy::Transaction);
+    assert_eq!(query.filter, IssueFilter::All);
+    assert_eq!(query.dist.as_deref(), Some("412"));
+
+    let query = aggregate_query("group_by=calculated_type");
+    assert_eq!(query.group_by, IssueGroupBy::CalculatedType);
+    assert_eq!(query.filter, IssueFilter::Open);
+
+    // group_by is required
+    assert!(actix_web::web::Query::<AggregateIssuesQuery>::from_query("").is_err());
+    assert!(actix_web::web::Query::<AggregateIssuesQuery>::from_query("group_by=level").is_err());
+}
+
+#[test]
+fn test_aggregate_period() {
+    let period = |p: &str| aggregate_query(&format!("group_by=transaction&period={}", p)).period();
+
+    assert_eq!(
+        aggregate_query("group_by=transaction").period().unwrap(),
+        chrono::Duration::days(7)
+    );
+    assert_eq!(period("24h").unwrap(), chrono::Duration::hours(24));
+    assert_eq!(period("90d").unwrap(), chrono::Duration::days(90));
+
+    for invalid in ["0h", "91d", "7w", "d", "-1d", "1.5d", "7dd"] {
+        let err = period(invalid).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST, "{}", invalid);
+    }
+}
@@ -0,0 +1,201 @@
+//! Unit tests for project keys
+//!
+//! Tests DSN building, key masking, last_used_at throttling and the per-key
+//! project cache used by ingest authentication.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Utc};
+use rustrak::auth::{CachedAuth, ProjectCache};
+use rustrak::error::AppError;
+use rustrak::models::{Project, ProjectKey};
+use rustrak::pagination::IssueSort;
+use uuid::Uuid;
+
+fn key(last_used_at: Option<chrono::DateTime<Utc>>) -> ProjectKey {
+    ProjectKey {
+        id: 1,
+        project_id: 42,
+        key: "9ec79c33-ec99-42ab-8353-589fcb2e04dc".parse().unwrap(),
+        label: "Default".to_string(),
+        is_active: true,
+        created_at: Utc::now(),
+        last_used_at,
+    }
+}
+
+fn project(id: i32) -> Project {
+    Project {
+        id,
+        name: format!("Project {}", id),
+        slug: format!("project-{}", id),
+        stored_event_count: 0,
+        digested_event_count: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        quota_exceeded_until: None,
+        quota_exceeded_reason: None,
+        next_quota_check: 0,
+        team_id: None,
+        allowed_domains: Vec::new(),
+        auto_resolve_days: None,
+        in_app_include: Vec::new(),
+        in_app_exclude: Vec::new(),
+        default_issue_sort: IssueSort::DigestOrder,
+        timezone: None,
+    }
+}
+
+#[test]
+fn test_dsn_uses_simple_key_and_project_id() {
+    let key = key(None);
+    assert_eq!(
+        key.dsn("https://errors.example.com"),
+        "https://9ec79c33ec9942ab8353589fcb2e04dc@errors.example.com/42"
+    );
+    assert_eq!(
+        key.dsn("localhost:8080"),
+        "http://9ec79c33ec9942ab8353589fcb2e04dc@localhost:8080/42"
+    );
+}
+
+#[test]
+fn test_response_masks_key() {
+    let response = serde_json::to_value(key(None).to_response()).unwrap();
+    assert_eq!(response["key_prefix"], "9ec79c33...");
+    assert!(response.get("key").is_none());
+    assert!(response.get("dsn").is_none());
+
+    let created = serde_json::to_value(key(None).to_created_response("localhost:8080")).unwrap();
+    assert_eq!(created["key"], "9ec79c33-ec99-42ab-8353-589fcb2e04dc");
+    assert!(created["dsn"]
+        .as_str()
+        .unwrap()
+        .starts_with("http://9ec79c33"));
+}
+
+#[test]
+fn test_last_used_outdated() {
+    let now = Utc::now();
+    assert!(key(None).last_used_outdated(now));
+    assert!(!key(Some(now - Duration::seconds(10))).last_used_outdated(now));
+    assert!(key(Some(now - ProjectKey::LAST_USED_INTERVAL)).last_used_outdated(now));
+}
+
+fn key_for(project_id: i32, key: Uuid) -> ProjectKey {
+    ProjectKey {
+        id: 1,
+        project_id,
+        key,
+        label: "Default".to_string(),
+        is_active: true,
+        created_at: Utc::now(),
+        last_used_at: Some(Utc::now()),
+    }
+}
+
+#[test]
+fn test_cache_is_per_key() {
+    let cache = ProjectCache::new();
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+    cache.insert(&project(1), &key_for(1, first));
+    cache.insert(&project(1), &key_for(1, second));
+
+    assert!(cache.get(1, &first).is_some());
+    assert!(cache.get(1, &Uuid::new_v4()).is_none());
+    assert!(cache.get(2, &first).is_none());
+
+    cache.remove_key(1, &first);
+    assert!(cache.get(1, &first).is_none());
+    assert!(cache.get(1, &second).is_some());
+
+    cache.remove(1);
+    assert!(cache.get(1, &second).is_none());
+}
+
+#[test]
+fn test_cache_get_by_key() {
+    let cache = ProjectCache::new();
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+    cache.insert(&project(1), &key_for(1, first));
+    cache.insert(&project(2), &key_for(2, second));
+
+    assert_eq!(cache.get_by_key(&first).unwrap().id, 1);
+    assert_eq!(cache.get_by_key(&second).unwrap().id, 2);
+    assert!(cache.get_by_key(&Uuid::new_v4()).is_none());
+
+    cache.remove_key(1, &first);
+    assert!(cache.get_by_key(&first).is_none());
+}
+
+#[test]
+fn test_cache_without_ttl_only_serves_outages() {
+    let cache = ProjectCache::new();
+    let sentry_key = Uuid::new_v4();
+    cache.insert(&project(1), &key_for(1, sentry_key));
+    cache.reject(Some(2), &sentry_key, &AppError::Unauthorized("x".into()));
+
+    assert!(cache.lookup(Some(1), &sentry_key).is_none());
+    assert!(cache.lookup(Some(2), &sentry_key).is_none());
+    assert!(cache.get(1, &sentry_key).is_some());
+    assert_eq!((cache.hits(), cache.misses()), (0, 2));
+}
+
+#[test]
+fn test_cache_lookup_within_ttl() {
+    let cache = ProjectCache::with_ttl(StdDuration::from_secs(30));
+    let sentry_key = Uuid::new_v4();
+    cache.insert(&project(1), &key_for(1, sentry_key));
+
+    match cache.lookup(Some(1), &sentry_key) {
+        Some(CachedAuth::Accepted(project, key)) => {
+            assert_eq!(project.id, 1);
+            assert_eq!(key.key, sentry_key);
+        }
+        other => panic!("Expected a cached project, got {:?}", other),
+    }
+    assert!(matches!(
+        cache.lookup(None, &sentry_key),
+        Some(CachedAuth::Accepted(..))
+    ));
+    assert!(cache.lookup(Some(2), &sentry_key).is_none());
+    assert_eq!((cache.hits(), cache.misses()), (2, 1));
+
+    // Invalidation takes effect immediately
+    cache.remove_key(1, &sentry_key);
+    assert!(cache.lookup(Some(1), &sentry_key).is_none());
+}
+
+#[test]
+fn test_cache_remembers_rejections() {
+    let cache = ProjectCache::with_ttl(StdDuration::from_secs(30));
+    let sentry_key = Uuid::new_v4();
+
+    cache.reject(
+        Some(1),
+        &sentry_key,
+        &AppError::Unauthorized("Bad key".into()),
+    );
+    cache.reject(
+        Some(2),
+        &sentry_key,
+        &AppError::NotFound("No project".into()),
+    );
+    cache.reject(Some(3), &sentry_key, &AppError::Internal("Oops".into()));
+
+    assert!(matches!(
+        cache.lookup(Some(1), &sentry_key),
+        Some(CachedAuth::Rejected(AppError::Unauthorized(_)))
+    ));
+    assert!(matches!(
+        cache.lookup(Some(2), &sentry_key),
+        Some(CachedAuth::Rejected(AppError::NotFound(_)))
+    ));
+    assert!(cache.lookup(Some(3), &sentry_key).is_none());
+
+    // Activating the key forgets it was rejected
+    cache.remove_key(1, &sentry_key);
+    assert!(cache.lookup(Some(1), &sentry_key).is_none());
+}
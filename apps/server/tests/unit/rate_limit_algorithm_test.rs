@@ -0,0 +1,209 @@
+//! Unit tests for the ingest rate limit algorithms
+//!
+//! Tests how trailing, fixed and sliding windows charge events against a
+//! limit, including the burst allowed across a fixed window boundary.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rustrak::config::RateLimitAlgorithm;
+use rustrak::services::{RateWindow, WindowCounts};
+
+fn minute_window(limit: i64) -> RateWindow {
+    RateWindow::new(Duration::minutes(1), limit)
+}
+
+fn at(hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, hour, minute, second)
+        .unwrap()
+}
+
+/// Digests events at `now` until the window reports the limit as reached,
+/// returning how many got through
+fn admit_burst(
+    window: &RateWindow,
+    algorithm: RateLimitAlgorithm,
+    mut counts: WindowCounts,
+    now: DateTime<Utc>,
+) -> i64 {
+    let mut admitted = 0;
+    loop {
+        // Counts include the event being digested, as in RateLimitService
+        counts.current += 1;
+        admitted += 1;
+        if window.exceeded_until(algorithm, counts, now).is_some() || admitted > window.limit {
+            return admitted;
+        }
+    }
+}
+
+#[test]
+fn test_window_start_is_clock_aligned() {
+    let window = minute_window(10);
+    assert_eq!(window.start(at(12, 34, 56)), at(12, 34, 0));
+    assert_eq!(window.start(at(12, 34, 0)), at(12, 34, 0));
+
+    let hour = RateWindow::new(Duration::hours(1), 10);
+    assert_eq!(hour.start(at(12, 34, 56)), at(12, 0, 0));
+}
+
+#[test]
+fn test_trailing_window_counts_up_to_now() {
+    let window = minute_window(10);
+    let now = at(12, 34, 56);
+    assert_eq!(
+        window.current_start(RateLimitAlgorithm::Trailing, now),
+        at(12, 33, 56)
+    );
+    assert_eq!(
+        window.current_start(RateLimitAlgorithm::Fixed, now),
+        at(12, 34, 0)
+    );
+
+    // A reached limit holds for a full window from now, not until :00
+    let counts = WindowCounts {
+        previous: 0,
+        current: 9,
+    };
+    assert_eq!(
+        window.exceeded_until(RateLimitAlgorithm::Trailing, counts, now),
+        Some(at(12, 35, 56))
+    );
+    assert_eq!(
+        window.exceeded_until(RateLimitAlgorithm::Fixed, counts, now),
+        Some(at(12, 35, 0))
+    );
+
+    let counts = WindowCounts {
+        previous: 0,
+        current: 5,
+    };
+    assert_eq!(
+        window.exceeded_until(RateLimitAlgorithm::Trailing, counts, now),
+        None
+    );
+    assert_eq!(
+        window.headroom(RateLimitAlgorithm::Trailing, counts, now),
+        4
+    );
+}
+
+#[test]
+fn test_fixed_window_counts_current_window_only() {
+    let window = minute_window(100);
+    let counts = WindowCounts {
+        previous: 500,
+        current: 10,
+    };
+
+    assert_eq!(
+        window.estimate(RateLimitAlgorithm::Fixed, counts, at(12, 0, 30)),
+        10.0
+    );
+    assert!(window
+        .exceeded_until(RateLimitAlgorithm::Fixed, counts, at(12, 0, 30))
+        .is_none());
+}
+
+#[test]
+fn test_fixed_window_blocks_until_window_end() {
+    let window = minute_window(100);
+    let counts = WindowCounts {
+        previous: 0,
+        current: 99,
+    };
+
+    assert_eq!(
+        window.exceeded_until(RateLimitAlgorithm::Fixed, counts, at(12, 0, 20)),
+        Some(at(12, 1, 0))
+    );
+}
+
+#[test]
+fn test_sliding_window_weights_previous_window() {
+    let window = minute_window(100);
+    let counts = WindowCounts {
+        previous: 60,
+        current: 10,
+    };
+
+    // A quarter of the way in, three quarters of the previous window overlap
+    assert_eq!(
+        window.estimate(RateLimitAlgorithm::Sliding, counts, at(12, 0, 15)),
+        55.0
+    );
+    assert_eq!(
+        window.estimate(RateLimitAlgorithm::Sliding, counts, at(12, 0, 0)),
+        70.0
+    );
+}
+
+#[test]
+fn test_sliding_window_blocks_until_previous_share_decays() {
+    let window = minute_window(100);
+    let counts = WindowCounts {
+        previous: 100,
+        current: 49,
+    };
+
+    // 100 * 0.5 + 49 = 99 at the half minute; back under 99 just after
+    let until = window
+        .exceeded_until(RateLimitAlgorithm::Sliding, counts, at(12, 0, 10))
+        .unwrap();
+    assert_eq!(until, at(12, 0, 30));
+}
+
+#[test]
+fn test_sliding_window_blocks_into_next_window() {
+    let window = minute_window(100);
+    let counts = WindowCounts {
+        previous: 0,
+        current: 198,
+    };
+
+    // In the next window 198 * (1 - f) drops below 99 at f = 0.5
+    let until = window
+        .exceeded_until(RateLimitAlgorithm::Sliding, counts, at(12, 0, 59))
+        .unwrap();
+    assert_eq!(until, at(12, 1, 30));
+}
+
+#[test]
+fn test_boundary_burst_allowed_by_fixed_but_not_sliding() {
+    let window = minute_window(100);
+
+    // A burst right before the minute ends fills the window...
+    for algorithm in [RateLimitAlgorithm::Fixed, RateLimitAlgorithm::Sliding] {
+        let first = admit_burst(&window, algorithm, WindowCounts::default(), at(12, 0, 59));
+        assert_eq!(first, 99, "{:?}", algorithm);
+    }
+
+    // ...and a second burst lands a second after it resets
+    let counts = WindowCounts {
+        previous: 99,
+        current: 0,
+    };
+    let fixed = admit_burst(&window, RateLimitAlgorithm::Fixed, counts, at(12, 1, 1));
+    let sliding = admit_burst(&window, RateLimitAlgorithm::Sliding, counts, at(12, 1, 1));
+
+    // Fixed lets nearly twice the limit through within two seconds
+    assert_eq!(fixed, 99);
+    // Sliding still charges most of the first burst
+    assert!(sliding <= 2, "sliding admitted {}", sliding);
+}
+
+#[test]
+fn test_headroom_follows_algorithm_estimate() {
+    let window = minute_window(100);
+    let counts = WindowCounts {
+        previous: 40,
+        current: 30,
+    };
+
+    assert_eq!(
+        window.headroom(RateLimitAlgorithm::Fixed, counts, at(12, 0, 30)),
+        69
+    );
+    assert_eq!(
+        window.headroom(RateLimitAlgorithm::Sliding, counts, at(12, 0, 30)),
+        49
+    );
+}
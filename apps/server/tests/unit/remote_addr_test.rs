@@ -0,0 +1,78 @@
+//! Unit tests for client IP resolution
+//!
+//! Tests reading X-Forwarded-For only when proxy headers are trusted.
+
+use actix_web::test::TestRequest;
+use rustrak::ingest::remote_addr::{client_ip_from_forwarded_for, remote_addr};
+
+fn ip(value: &str) -> Option<String> {
+    client_ip_from_forwarded_for(value).map(|ip| ip.to_string())
+}
+
+#[test]
+fn test_forwarded_for_skips_internal_proxies() {
+    assert_eq!(ip("203.0.113.7").as_deref(), Some("203.0.113.7"));
+    assert_eq!(ip("203.0.113.7, 10.0.0.1").as_deref(), Some("203.0.113.7"));
+    assert_eq!(
+        ip("203.0.113.7, 172.16.0.4, 127.0.0.1").as_deref(),
+        Some("203.0.113.7")
+    );
+    assert_eq!(
+        ip("2001:db8::1, fd00::1, ::1").as_deref(),
+        Some("2001:db8::1")
+    );
+}
+
+#[test]
+fn test_forwarded_for_ignores_spoofed_entries() {
+    // Entries left of the first external hop were set by the client
+    assert_eq!(
+        ip("1.2.3.4, 203.0.113.7, 10.0.0.1").as_deref(),
+        Some("203.0.113.7")
+    );
+}
+
+#[test]
+fn test_forwarded_for_internal_clients() {
+    // Every hop internal: the leftmost one is the client
+    assert_eq!(
+        ip("192.168.1.20, 10.0.0.1").as_deref(),
+        Some("192.168.1.20")
+    );
+}
+
+#[test]
+fn test_forwarded_for_with_ports_and_garbage() {
+    assert_eq!(ip("203.0.113.7:5123").as_deref(), Some("203.0.113.7"));
+    assert_eq!(ip("[2001:db8::1]:443").as_deref(), Some("2001:db8::1"));
+    assert_eq!(ip("unknown, 203.0.113.7").as_deref(), Some("203.0.113.7"));
+    assert_eq!(ip("unknown"), None);
+    assert_eq!(ip(""), None);
+}
+
+#[test]
+fn test_remote_addr_without_trust_uses_peer() {
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.5:50000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "203.0.113.7"))
+        .to_http_request();
+
+    assert_eq!(remote_addr(&req, false).as_deref(), Some("10.0.0.5"));
+}
+
+#[test]
+fn test_remote_addr_with_trust_uses_forwarded_for() {
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.5:50000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "203.0.113.7"))
+        .to_http_request();
+
+    assert_eq!(remote_addr(&req, true).as_deref(), Some("203.0.113.7"));
+
+    // Falls back to the peer without a usable header
+    let req = TestRequest::default()
+        .peer_addr("10.0.0.5:50000".parse().unwrap())
+        .to_http_request();
+
+    assert_eq!(remote_addr(&req, true).as_deref(), Some("10.0.0.5"));
+}
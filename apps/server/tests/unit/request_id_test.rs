@@ -0,0 +1,140 @@
+//! Unit tests for request id propagation
+
+use actix_web::body::{to_bytes, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::middleware::{from_fn, Next};
+use actix_web::test::{
+    call_service, init_service, read_body, read_body_json, try_call_service, TestRequest,
+};
+use actix_web::{web, App, Error, HttpResponse};
+use rustrak::error::{AppError, AppResult};
+use rustrak::middleware::request_id::{
+    accept_or_generate, current, request_id, MAX_REQUEST_ID_LEN, REQUEST_ID_HEADER,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Answers with the request id seen by the handler
+async fn echo() -> HttpResponse {
+    HttpResponse::Ok().body(current().unwrap_or_default())
+}
+
+async fn missing() -> AppResult<HttpResponse> {
+    Err(AppError::NotFound("Issue 42 not found".to_string()))
+}
+
+/// Rejects every request before it reaches a handler, like the auth middleware
+async fn reject(
+    _req: ServiceRequest,
+    _next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    Err::<ServiceResponse, _>(AppError::Unauthorized("Missing token".to_string()).into())
+}
+
+#[test]
+fn test_accept_or_generate() {
+    assert_eq!(accept_or_generate(Some("req-42")), "req-42");
+
+    // Unusable ids are replaced with a UUID
+    let too_long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+    for incoming in [
+        None,
+        Some(""),
+        Some("has space"),
+        Some("new\nline"),
+        Some(&*too_long),
+    ] {
+        let id = accept_or_generate(incoming);
+        assert!(
+            Uuid::parse_str(&id).is_ok(),
+            "{:?} kept as {}",
+            incoming,
+            id
+        );
+    }
+}
+
+#[test]
+fn test_no_current_id_outside_requests() {
+    assert_eq!(current(), None);
+}
+
+#[actix_web::test]
+async fn test_request_id_echoed_and_visible_to_handlers() {
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .route("/echo", web::get().to(echo)),
+    )
+    .await;
+
+    let req = TestRequest::get()
+        .uri("/echo")
+        .insert_header((REQUEST_ID_HEADER, "client-id-1"))
+        .to_request();
+    let resp = call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get(REQUEST_ID_HEADER).unwrap(),
+        "client-id-1"
+    );
+    let body = read_body(resp).await;
+    assert_eq!(body, "client-id-1");
+
+    // Without one, a new id is generated and echoed
+    let resp = call_service(&app, TestRequest::get().uri("/echo").to_request()).await;
+    let id = resp
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(Uuid::parse_str(id).is_ok());
+}
+
+#[actix_web::test]
+async fn test_request_id_in_error_body() {
+    let app = init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .route("/missing", web::get().to(missing))
+            .service(
+                web::scope("/private")
+                    .wrap(from_fn(reject))
+                    .route("", web::get().to(echo)),
+            ),
+    )
+    .await;
+
+    let request = |uri| {
+        TestRequest::get()
+            .uri(uri)
+            .insert_header((REQUEST_ID_HEADER, "client-id-2"))
+            .to_request()
+    };
+
+    // Handler errors are rendered by the handler
+    let resp = call_service(&app, request("/missing")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        resp.headers().get(REQUEST_ID_HEADER).unwrap(),
+        "client-id-2"
+    );
+    let body: Value = read_body_json(resp).await;
+    assert_eq!(body["error"]["request_id"], "client-id-2");
+
+    // Middleware errors reach the server as errors, already rendered
+    let err = try_call_service(&app, request("/private"))
+        .await
+        .err()
+        .unwrap();
+    let resp = err.error_response();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        resp.headers().get(REQUEST_ID_HEADER).unwrap(),
+        "client-id-2"
+    );
+    let body = to_bytes(resp.into_body()).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["request_id"], "client-id-2");
+}
@@ -0,0 +1,42 @@
+//! Unit tests for issue sampling
+//!
+//! Tests which events of a sampled issue are kept.
+
+use rustrak::digest::keeps_event;
+use uuid::Uuid;
+
+#[test]
+fn test_unsampled_issue_keeps_every_event() {
+    for _ in 0..100 {
+        assert!(keeps_event(Uuid::new_v4(), None));
+    }
+}
+
+#[test]
+fn test_same_event_gets_same_answer() {
+    for _ in 0..100 {
+        let event_id = Uuid::new_v4();
+        assert_eq!(
+            keeps_event(event_id, Some(0.5)),
+            keeps_event(event_id, Some(0.5))
+        );
+    }
+}
+
+#[test]
+fn test_keeps_about_the_rate() {
+    let kept = (0..10_000)
+        .filter(|_| keeps_event(Uuid::new_v4(), Some(0.1)))
+        .count();
+    assert!((800..=1200).contains(&kept), "kept {} of 10000", kept);
+}
+
+#[test]
+fn test_event_kept_at_a_rate_is_kept_at_higher_rates() {
+    for _ in 0..100 {
+        let event_id = Uuid::new_v4();
+        if keeps_event(event_id, Some(0.1)) {
+            assert!(keeps_event(event_id, Some(0.5)));
+        }
+    }
+}
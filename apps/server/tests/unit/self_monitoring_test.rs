@@ -0,0 +1,46 @@
+//! Unit tests for self-monitoring
+//!
+//! Tests the synthetic events built for Rustrak's own errors.
+
+use rustrak::services::self_monitoring::{error_event, is_capturing, CapturedRequest};
+
+#[test]
+fn test_error_event_for_request() {
+    let request = CapturedRequest {
+        method: "GET".to_string(),
+        path: "/api/projects/7".to_string(),
+        route: Some("/api/projects/{id}".to_string()),
+    };
+
+    let event = error_event("DatabaseError", "pool timed out", Some(&request));
+
+    assert_eq!(event["level"], "error");
+    assert_eq!(event["platform"], "rust");
+    assert_eq!(event["tags"]["component"], "server");
+    assert_eq!(event["transaction"], "/api/projects/{id}");
+    assert_eq!(event["request"]["url"], "/api/projects/7");
+    assert_eq!(event["exception"]["values"][0]["type"], "DatabaseError");
+    assert_eq!(event["exception"]["values"][0]["value"], "pool timed out");
+    assert!(event["release"].as_str().unwrap().starts_with("rustrak@"));
+}
+
+#[test]
+fn test_error_event_without_route_uses_path() {
+    let request = CapturedRequest {
+        method: "POST".to_string(),
+        path: "/api/unknown".to_string(),
+        route: None,
+    };
+
+    let event = error_event("ServerError", "503 response", Some(&request));
+    assert_eq!(event["transaction"], "/api/unknown");
+
+    let event = error_event("InternalError", "boom", None);
+    assert!(event.get("transaction").is_none());
+    assert!(event.get("request").is_none());
+}
+
+#[test]
+fn test_not_capturing_outside_self_monitoring() {
+    assert!(!is_capturing());
+}
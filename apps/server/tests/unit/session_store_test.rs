@@ -0,0 +1,69 @@
+//! Unit tests for the session store
+//!
+//! Tests sessions kept in the cookie through the configurable store. The
+//! Redis store needs a server and isn't covered here.
+
+use actix_session::{Session, SessionMiddleware};
+use actix_web::{cookie::Key, test, web, App, HttpResponse};
+use rustrak::auth::{clear_session, get_user_id_from_session, set_user_session, SessionStorage};
+use rustrak::config::SessionBackend;
+
+async fn login(session: Session) -> HttpResponse {
+    set_user_session(&session, 42).unwrap();
+    HttpResponse::Ok().finish()
+}
+
+async fn whoami(session: Session) -> HttpResponse {
+    match get_user_id_from_session(&session) {
+        Some(user_id) => HttpResponse::Ok().body(user_id.to_string()),
+        None => HttpResponse::Unauthorized().finish(),
+    }
+}
+
+async fn logout(session: Session) -> HttpResponse {
+    clear_session(&session);
+    HttpResponse::NoContent().finish()
+}
+
+#[actix_web::test]
+async fn test_cookie_store_round_trip() {
+    let storage = SessionStorage::connect(&SessionBackend::Cookie)
+        .await
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .wrap(
+                SessionMiddleware::builder(storage, Key::from(&[0u8; 64]))
+                    .cookie_secure(false)
+                    .build(),
+            )
+            .route("/login", web::post().to(login))
+            .route("/whoami", web::get().to(whoami))
+            .route("/logout", web::post().to(logout)),
+    )
+    .await;
+
+    let resp = test::call_service(&app, test::TestRequest::post().uri("/login").to_request()).await;
+    let cookie = resp
+        .response()
+        .cookies()
+        .next()
+        .expect("No session cookie")
+        .into_owned();
+
+    let req = test::TestRequest::get()
+        .uri("/whoami")
+        .cookie(cookie.clone())
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "42");
+
+    // Logging out expires the cookie
+    let req = test::TestRequest::post()
+        .uri("/logout")
+        .cookie(cookie)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let removal = resp.response().cookies().next().expect("No removal cookie");
+    assert_eq!(removal.value(), "");
+}
@@ -0,0 +1,218 @@
+//! Unit tests for native symbolication
+//!
+//! Tests parsing Breakpad symbol files, resolving addresses, and writing
+//! the resolved frames into event data, and caching parsed files.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use crate::common::fixtures::events;
+use crate::common::{LIBCRASH_BASE, SYMBOL_DEBUG_ID, SYMBOL_FILE};
+use rustrak::digest::breakpad::{debug_id_from_breakpad, SymbolFile};
+use rustrak::digest::{referenced_debug_ids, symbolicate_event, SymbolCache};
+use rustrak::error::AppError;
+
+fn symbols() -> HashMap<String, Arc<SymbolFile>> {
+    HashMap::from([(
+        SYMBOL_DEBUG_ID.to_string(),
+        Arc::new(SymbolFile::parse(SYMBOL_FILE).unwrap()),
+    )])
+}
+
+// =============================================================================
+// Symbol File Parsing
+// =============================================================================
+
+#[test]
+fn test_parse_module_record() {
+    let file = SymbolFile::parse(SYMBOL_FILE).unwrap();
+
+    assert_eq!(file.module.os, "Linux");
+    assert_eq!(file.module.arch, "x86_64");
+    assert_eq!(file.module.name, "libcrash.so");
+    assert_eq!(file.module.debug_id, SYMBOL_DEBUG_ID);
+}
+
+#[test]
+fn test_debug_id_from_breakpad() {
+    assert_eq!(
+        debug_id_from_breakpad("6EDC6ACDB282125843FD59DA9C81BD830").as_deref(),
+        Some("6edc6acd-b282-1258-43fd-59da9c81bd83")
+    );
+    assert_eq!(
+        debug_id_from_breakpad("3F2504E04F8911D39A0C0305E82C3301A").as_deref(),
+        Some("3f2504e0-4f89-11d3-9a0c-0305e82c3301-a")
+    );
+    assert_eq!(debug_id_from_breakpad("3F2504E0"), None);
+    assert_eq!(
+        debug_id_from_breakpad("XYZ504E04F8911D39A0C0305E82C33010"),
+        None
+    );
+}
+
+#[test]
+fn test_lookup_function_with_line() {
+    let file = SymbolFile::parse(SYMBOL_FILE).unwrap();
+
+    let symbol = file.lookup(0x1024).unwrap();
+    assert_eq!(symbol.function, "crash_handler");
+    assert_eq!(symbol.symbol_addr, 0x1000);
+    assert_eq!(symbol.filename, Some("/src/crash.c"));
+    assert_eq!(symbol.lineno, Some(12));
+
+    let symbol = file.lookup(0x1110).unwrap();
+    assert_eq!(symbol.function, "do_work");
+    assert_eq!(symbol.filename, Some("/src/work.c"));
+    assert_eq!(symbol.lineno, Some(25));
+}
+
+#[test]
+fn test_lookup_public_symbol() {
+    let file = SymbolFile::parse(SYMBOL_FILE).unwrap();
+
+    let symbol = file.lookup(0x1280).unwrap();
+    assert_eq!(symbol.function, "exported_helper");
+    assert_eq!(symbol.filename, None);
+    assert_eq!(symbol.lineno, None);
+}
+
+#[test]
+fn test_lookup_outside_functions() {
+    let file = SymbolFile::parse(SYMBOL_FILE).unwrap();
+
+    assert!(file.lookup(0x500).is_none());
+    // Between the end of crash_handler and do_work
+    assert!(file.lookup(0x1050).is_none());
+}
+
+#[test]
+fn test_parse_rejects_invalid_files() {
+    for text in [
+        "",
+        "not a symbol file",
+        "MODULE Linux x86_64 NOTHEX libcrash.so",
+        "MODULE Linux x86_64 3F2504E04F8911D39A0C0305E82C33011 libcrash.so\nFUNC zz 10 0 f",
+        "MODULE Linux x86_64 3F2504E04F8911D39A0C0305E82C33011 libcrash.so\n1000 10 1 0",
+    ] {
+        let result = SymbolFile::parse(text);
+        assert!(
+            matches!(result, Err(AppError::Validation(_))),
+            "accepted {:?}",
+            text
+        );
+    }
+}
+
+// =============================================================================
+// Event Symbolication
+// =============================================================================
+
+#[test]
+fn test_referenced_debug_ids() {
+    let event = events::native_crash(&[LIBCRASH_BASE + 0x1110, 0x1234]);
+    assert_eq!(referenced_debug_ids(&event), vec![SYMBOL_DEBUG_ID]);
+
+    let event = events::native_crash(&[0x1234]);
+    assert!(referenced_debug_ids(&event).is_empty());
+}
+
+#[test]
+fn test_symbolicate_resolves_frames() {
+    let mut event = events::native_crash(&[LIBCRASH_BASE + 0x1111, LIBCRASH_BASE + 0x1024]);
+
+    assert!(symbolicate_event(&mut event, &symbols()));
+
+    let frames = &event["exception"]["values"][0]["stacktrace"]["frames"];
+    assert_eq!(frames[0]["function"], "do_work");
+    assert_eq!(frames[0]["lineno"], 25);
+    assert_eq!(frames[1]["function"], "crash_handler");
+    assert_eq!(frames[1]["filename"], "/src/crash.c");
+    assert_eq!(frames[1]["lineno"], 12);
+    assert_eq!(frames[1]["package"], "/usr/lib/libcrash.so");
+    assert_eq!(frames[1]["symbol_addr"], "0x7f0000001000");
+    assert_eq!(frames[1]["instruction_addr"], "0x7f0000001024");
+    assert_eq!(frames[1]["data"]["module_offset"], "0x1024");
+    assert_eq!(frames[1]["data"]["symbolicator_status"], "symbolicated");
+}
+
+#[test]
+fn test_caller_frames_use_return_address() {
+    // A return address right after the end of crash_handler still belongs to it
+    let mut event = events::native_crash(&[LIBCRASH_BASE + 0x1040, LIBCRASH_BASE + 0x1110]);
+
+    symbolicate_event(&mut event, &symbols());
+
+    let frames = &event["exception"]["values"][0]["stacktrace"]["frames"];
+    assert_eq!(frames[0]["function"], "crash_handler");
+    assert_eq!(frames[1]["function"], "do_work");
+}
+
+#[test]
+fn test_unresolved_frames_keep_addresses() {
+    let mut event = events::native_crash(&[0x1234, LIBCRASH_BASE + 0x1024]);
+
+    // No symbols uploaded yet
+    assert!(!symbolicate_event(&mut event, &HashMap::new()));
+
+    let frames = &event["exception"]["values"][0]["stacktrace"]["frames"];
+    assert_eq!(frames[0]["instruction_addr"], "0x1234");
+    assert_eq!(frames[0]["data"]["symbolicator_status"], "unknown_image");
+    assert!(frames[0].get("package").is_none());
+
+    assert_eq!(frames[1]["instruction_addr"], "0x7f0000001024");
+    assert_eq!(frames[1]["package"], "/usr/lib/libcrash.so");
+    assert_eq!(frames[1]["data"]["module_offset"], "0x1024");
+    assert_eq!(frames[1]["data"]["symbolicator_status"], "missing");
+    assert!(frames[1].get("function").is_none());
+}
+
+#[test]
+fn test_events_without_images_untouched() {
+    let mut event = events::runtime_error_with_stack();
+    let original = event.clone();
+
+    assert!(!symbolicate_event(&mut event, &symbols()));
+    assert_eq!(event, original);
+}
+
+// =============================================================================
+// Symbol Cache
+// =============================================================================
+
+#[test]
+fn test_symbol_cache_evicts_least_recently_used() {
+    let cache = SymbolCache::new(2);
+    let uploaded_at = Utc::now();
+    let file = Arc::new(SymbolFile::parse(SYMBOL_FILE).unwrap());
+
+    cache.insert(1, "a", uploaded_at, file.clone());
+    cache.insert(1, "b", uploaded_at, file.clone());
+    // Using "a" makes "b" the one to go
+    assert!(cache.get(1, "a", uploaded_at).is_some());
+    cache.insert(1, "c", uploaded_at, file);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(1, "a", uploaded_at).is_some());
+    assert!(cache.get(1, "b", uploaded_at).is_none());
+    assert!(cache.get(1, "c", uploaded_at).is_some());
+}
+
+#[test]
+fn test_symbol_cache_misses_replaced_and_removed_files() {
+    let cache = SymbolCache::new(4);
+    let uploaded_at = Utc::now();
+    let file = Arc::new(SymbolFile::parse(SYMBOL_FILE).unwrap());
+    cache.insert(1, SYMBOL_DEBUG_ID, uploaded_at, file);
+
+    // Re-uploaded on another instance
+    let replaced_at = uploaded_at + chrono::Duration::seconds(1);
+    assert!(cache.get(1, SYMBOL_DEBUG_ID, replaced_at).is_none());
+    // Same debug ID in another project
+    assert!(cache.get(2, SYMBOL_DEBUG_ID, uploaded_at).is_none());
+
+    cache.remove(1, SYMBOL_DEBUG_ID);
+    assert!(cache.get(1, SYMBOL_DEBUG_ID, uploaded_at).is_none());
+    assert!(cache.is_empty());
+}
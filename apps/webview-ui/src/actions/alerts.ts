@@ -5,7 +5,9 @@ import type {
   AlertRule,
   CreateAlertRule,
   CreateNotificationChannel,
+  ListAlertHistoryOptions,
   NotificationChannel,
+  OffsetPaginatedResponse,
   TestChannelResponse,
   UpdateAlertRule,
   UpdateNotificationChannel,
@@ -172,13 +174,13 @@ export async function deleteAlertRule(
  * List alert history for a project.
  *
  * @param projectId - The project ID
- * @param options - Query options (limit)
- * @returns List of alert history entries
+ * @param options - Query options (page, per_page, status, channel_id)
+ * @returns Paginated alert history entries
  */
 export async function listAlertHistory(
   projectId: number,
-  options?: { limit?: number },
-): Promise<AlertHistory[]> {
+  options?: ListAlertHistoryOptions,
+): Promise<OffsetPaginatedResponse<AlertHistory>> {
   const client = await createClient();
   return client.alertRules.listHistory(projectId, options);
 }
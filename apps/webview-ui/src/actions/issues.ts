@@ -2,6 +2,7 @@
 
 import type {
   Issue,
+  IssueContexts,
   ListIssuesOptions,
   OffsetPaginatedResponse,
   UpdateIssueState,
@@ -38,6 +39,21 @@ export async function getIssue(
   return client.issues.get(projectId, issueId);
 }
 
+/**
+ * Get the OS, browser and runtime breakdowns of an issue's events.
+ *
+ * @param projectId - The project ID
+ * @param issueId - The issue UUID
+ * @returns The top values per context with their share of events
+ */
+export async function getIssueContexts(
+  projectId: number,
+  issueId: string,
+): Promise<IssueContexts> {
+  const client = await createClient();
+  return client.issues.getContexts(projectId, issueId);
+}
+
 /**
  * Update an issue's state (resolve, mute, etc.).
  *
@@ -2,9 +2,12 @@
 
 import type {
   CreateProject,
+  CreateProjectKey,
   ListProjectsOptions,
   OffsetPaginatedResponse,
   Project,
+  ProjectKey,
+  ProjectKeyCreated,
   UpdateProject,
 } from '@rustrak/client';
 import { createClient } from '@/lib/rustrak';
@@ -38,9 +41,10 @@ export async function getProject(id: number): Promise<Project> {
 
 /**
  * Create a new project.
+ * The default key's DSN is only returned here - show it immediately.
  *
  * @param input - Project data (name, optional slug)
- * @returns The created project
+ * @returns The created project, with the default key's DSN
  * @throws BadRequestError if validation fails
  * @throws AuthenticationError if not authenticated
  */
@@ -78,3 +82,54 @@ export async function deleteProject(id: number): Promise<void> {
   const client = await createClient();
   return client.projects.delete(id);
 }
+
+/**
+ * Create a new key for a project.
+ * The full key and DSN are only returned once during creation.
+ *
+ * @param projectId - Project ID
+ * @param input - Optional label for the key
+ * @returns The created key with its DSN (shown only once)
+ * @throws NotFoundError if project doesn't exist
+ */
+export async function createProjectKey(
+  projectId: number,
+  input: CreateProjectKey,
+): Promise<ProjectKeyCreated> {
+  const client = await createClient();
+  return client.projects.createKey(projectId, input);
+}
+
+/**
+ * Activate or deactivate a project key.
+ * Deactivated keys are rejected by ingest right away.
+ *
+ * @param projectId - Project ID
+ * @param keyId - Key ID
+ * @param isActive - Whether SDKs may use the key
+ * @returns The updated key (masked)
+ * @throws NotFoundError if the key doesn't exist
+ */
+export async function setProjectKeyActive(
+  projectId: number,
+  keyId: number,
+  isActive: boolean,
+): Promise<ProjectKey> {
+  const client = await createClient();
+  return client.projects.updateKey(projectId, keyId, { is_active: isActive });
+}
+
+/**
+ * Delete a project key.
+ *
+ * @param projectId - Project ID
+ * @param keyId - Key ID
+ * @throws NotFoundError if the key doesn't exist
+ */
+export async function deleteProjectKey(
+  projectId: number,
+  keyId: number,
+): Promise<void> {
+  const client = await createClient();
+  await client.projects.deleteKey(projectId, keyId);
+}
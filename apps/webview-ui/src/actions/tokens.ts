@@ -8,14 +8,22 @@ import type {
 import { createClient } from '@/lib/rustrak';
 
 /**
- * List all auth tokens (masked).
+ * List all auth tokens (masked), walking every page of the list endpoint.
  * The full token is never returned after creation.
  *
  * @returns List of auth tokens with masked token values
  */
 export async function listTokens(): Promise<AuthToken[]> {
   const client = await createClient();
-  return client.tokens.list();
+  const tokens: AuthToken[] = [];
+
+  for (let page = 1; ; page++) {
+    const result = await client.tokens.list({ page, per_page: 100 });
+    tokens.push(...result.items);
+    if (page >= result.total_pages) {
+      return tokens;
+    }
+  }
 }
 
 /**
@@ -246,13 +246,21 @@ export function IssuesList({
                     className="block group-hover:text-primary transition-colors"
                   >
                     <div className="flex items-center gap-2 mb-1">
+                      {!issue.is_seen && (
+                        <span
+                          className="size-2 rounded-full bg-primary shrink-0"
+                          title="You haven't opened this issue yet"
+                        />
+                      )}
                       {issue.is_resolved && (
                         <Check className="size-4 text-primary shrink-0" />
                       )}
                       {issue.is_muted && (
                         <BellOff className="size-4 text-muted-foreground shrink-0" />
                       )}
-                      <span className="font-semibold truncate">
+                      <span
+                        className={`truncate ${issue.is_seen ? 'font-medium' : 'font-semibold'}`}
+                      >
                         {issue.title}
                       </span>
                     </div>
@@ -412,8 +420,8 @@ export function IssuesList({
             </AlertDialogTitle>
             <AlertDialogDescription>
               {isBatchDelete
-                ? `This will permanently delete ${selectedIds.size} issue${selectedIds.size > 1 ? 's' : ''} and all associated events. This action cannot be undone.`
-                : 'This will permanently delete this issue and all associated events. This action cannot be undone.'}
+                ? `This will move ${selectedIds.size} issue${selectedIds.size > 1 ? 's' : ''} and all associated events to the trash. They are deleted for good once the trash retention period ends.`
+                : 'This will move this issue and all associated events to the trash. They are deleted for good once the trash retention period ends.'}
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
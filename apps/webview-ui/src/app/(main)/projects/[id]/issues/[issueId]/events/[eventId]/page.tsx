@@ -2,7 +2,7 @@ import { format } from 'date-fns';
 import type { Metadata } from 'next';
 import { notFound } from 'next/navigation';
 import { getEventDetail, getEventNavigation } from '@/actions/events';
-import { getIssue } from '@/actions/issues';
+import { getIssue, getIssueContexts } from '@/actions/issues';
 import { getProject } from '@/actions/projects';
 import { Badge } from '@/components/ui/badge';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
@@ -14,6 +14,8 @@ import { EventDetails } from './event-details';
 import { EventNavigationBar } from './event-navigation';
 import { EventTags } from './event-tags';
 import { RawJson } from './raw-json';
+import { SeenBy } from './seen-by';
+import { SeenOn } from './seen-on';
 import { StackTrace } from './stack-trace';
 
 interface EventPageProps {
@@ -45,11 +47,12 @@ export default async function EventPage({ params }: EventPageProps) {
   const { id, issueId, eventId } = await params;
   const projectId = parseInt(id, 10);
 
-  const [project, issue, event, navigation] = await Promise.all([
+  const [project, issue, event, navigation, seenOn] = await Promise.all([
     getProject(projectId),
     getIssue(projectId, issueId),
     getEventDetail(projectId, issueId, eventId),
     getEventNavigation(projectId, issueId, eventId),
+    getIssueContexts(projectId, issueId),
   ]);
 
   if (!project || !issue || !event) {
@@ -208,6 +211,12 @@ export default async function EventPage({ params }: EventPageProps) {
                 </div>
               </div>
 
+              {/* Seen On */}
+              <SeenOn contexts={seenOn} />
+
+              {/* Seen By */}
+              <SeenBy seenBy={issue.seen_by ?? []} />
+
               {/* Tags */}
               {tags && Object.keys(tags).length > 0 && (
                 <div className="bg-card rounded-xl border p-6 space-y-4">
@@ -0,0 +1,35 @@
+import type { IssueSeenBy } from '@rustrak/client';
+import { formatDistanceToNow } from 'date-fns';
+
+interface SeenByProps {
+  seenBy: IssueSeenBy[];
+}
+
+/**
+ * Team members who have already looked at the issue.
+ */
+export function SeenBy({ seenBy }: SeenByProps) {
+  if (seenBy.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="bg-card rounded-xl border p-6 space-y-4">
+      <h4 className="text-[10px] font-black uppercase tracking-[0.2em] text-muted-foreground">
+        Seen By
+      </h4>
+      <div className="space-y-2">
+        {seenBy.map((viewer) => (
+          <div key={viewer.user_id} className="flex justify-between text-sm">
+            <span className="truncate">{viewer.email}</span>
+            <span className="text-muted-foreground text-xs ml-2 shrink-0">
+              {formatDistanceToNow(new Date(viewer.seen_at), {
+                addSuffix: true,
+              })}
+            </span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,55 @@
+import type { IssueContexts } from '@rustrak/client';
+
+interface SeenOnProps {
+  contexts: IssueContexts;
+}
+
+/**
+ * Breakdown of the OS, browser and runtime the issue's events were seen on.
+ */
+export function SeenOn({ contexts }: SeenOnProps) {
+  const sections = [
+    { key: 'browser', label: 'Browser', breakdown: contexts.browser },
+    { key: 'os', label: 'OS', breakdown: contexts.os },
+    { key: 'runtime', label: 'Runtime', breakdown: contexts.runtime },
+  ].filter((section) => section.breakdown.values.length > 0);
+
+  if (sections.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="bg-card rounded-xl border p-6 space-y-4">
+      <h4 className="text-[10px] font-black uppercase tracking-[0.2em] text-muted-foreground">
+        Seen On
+      </h4>
+      <div className="space-y-4">
+        {sections.map((section) => (
+          <div key={section.key} className="space-y-2">
+            <p className="text-[10px] text-muted-foreground font-bold uppercase">
+              {section.label}
+            </p>
+            {section.breakdown.values.map((item) => (
+              <div key={item.value} className="space-y-1">
+                <div className="flex justify-between text-sm">
+                  <span className="font-mono text-xs truncate">
+                    {item.value}
+                  </span>
+                  <span className="text-muted-foreground text-xs ml-2">
+                    {item.percentage}%
+                  </span>
+                </div>
+                <div className="h-1 bg-muted rounded-full overflow-hidden">
+                  <div
+                    className="h-full bg-primary"
+                    style={{ width: `${item.percentage}%` }}
+                  />
+                </div>
+              </div>
+            ))}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -116,8 +116,8 @@ export function IssueActions({ issue, projectId }: IssueActionsProps) {
           <AlertDialogHeader>
             <AlertDialogTitle>Delete this issue?</AlertDialogTitle>
             <AlertDialogDescription>
-              This will permanently delete this issue and all associated events.
-              This action cannot be undone.
+              This will move this issue and all associated events to the trash.
+              They are deleted for good once the trash retention period ends.
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
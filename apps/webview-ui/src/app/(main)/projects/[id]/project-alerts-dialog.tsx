@@ -89,6 +89,7 @@ const alertRuleFormSchema = z.object({
   channel_ids: z.array(z.number()).min(1, 'Select at least one channel'),
   is_enabled: z.boolean(),
   cooldown_minutes: z.number().int().min(0),
+  digest_minutes: z.number().int().min(0).max(1440),
 });
 
 type AlertRuleFormData = z.infer<typeof alertRuleFormSchema>;
@@ -399,6 +400,7 @@ function AlertRuleForm({
       channel_ids: [],
       is_enabled: true,
       cooldown_minutes: 0,
+      digest_minutes: 0,
     },
   });
 
@@ -411,6 +413,7 @@ function AlertRuleForm({
         channel_ids: existingRule.channel_ids,
         is_enabled: existingRule.is_enabled,
         cooldown_minutes: existingRule.cooldown_minutes,
+        digest_minutes: existingRule.digest_minutes,
       });
     } else {
       // Compute default type inside effect to avoid dependency on availableTypes array
@@ -423,6 +426,7 @@ function AlertRuleForm({
         channel_ids: [],
         is_enabled: true,
         cooldown_minutes: 0,
+        digest_minutes: 0,
       });
     }
   }, [existingRule, existingRuleTypes, form]);
@@ -436,6 +440,7 @@ function AlertRuleForm({
             is_enabled: data.is_enabled,
             channel_ids: data.channel_ids,
             cooldown_minutes: data.cooldown_minutes,
+            digest_minutes: data.digest_minutes,
           });
           toast.success('Alert rule updated');
         } else {
@@ -445,6 +450,7 @@ function AlertRuleForm({
             channel_ids: data.channel_ids,
             is_enabled: data.is_enabled,
             cooldown_minutes: data.cooldown_minutes,
+            digest_minutes: data.digest_minutes,
           });
           toast.success('Alert rule created');
         }
@@ -601,6 +607,36 @@ function AlertRuleForm({
             )}
           />
 
+          <FormField
+            control={form.control}
+            name="digest_minutes"
+            render={({ field }) => (
+              <FormItem>
+                <FormLabel className="text-xs font-bold uppercase tracking-widest text-muted-foreground">
+                  Digest (minutes)
+                </FormLabel>
+                <FormControl>
+                  <Input
+                    type="number"
+                    min={0}
+                    max={1440}
+                    placeholder="0"
+                    disabled={isPending}
+                    {...field}
+                    onChange={(e) =>
+                      field.onChange(parseInt(e.target.value, 10) || 0)
+                    }
+                  />
+                </FormControl>
+                <FormDescription>
+                  Batch alerts into one summary per window (0 = send each alert
+                  immediately)
+                </FormDescription>
+                <FormMessage />
+              </FormItem>
+            )}
+          />
+
           <FormField
             control={form.control}
             name="is_enabled"
@@ -0,0 +1,191 @@
+'use client';
+
+import type { ProjectKey } from '@rustrak/client';
+import { formatDistanceToNow } from 'date-fns';
+import { Check, Copy, Loader2, Plus, Trash2 } from 'lucide-react';
+import { useRouter } from 'next/navigation';
+import { useState, useTransition } from 'react';
+import { toast } from 'sonner';
+import {
+  createProjectKey,
+  deleteProjectKey,
+  setProjectKeyActive,
+} from '@/actions/projects';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Switch } from '@/components/ui/switch';
+
+const KEY_LABEL_MAX_LENGTH = 255;
+
+interface ProjectKeysProps {
+  projectId: number;
+  keys: ProjectKey[];
+  /** Called with the DSN of a newly created key */
+  onKeyCreated?: (dsn: string) => void;
+}
+
+export function ProjectKeys({
+  projectId,
+  keys,
+  onKeyCreated,
+}: ProjectKeysProps) {
+  const router = useRouter();
+  const [isPending, startTransition] = useTransition();
+  const [label, setLabel] = useState('');
+  const [newDsn, setNewDsn] = useState<string | null>(null);
+  const [copied, setCopied] = useState(false);
+
+  const handleCreate = () => {
+    startTransition(async () => {
+      try {
+        const key = await createProjectKey(projectId, {
+          label: label.trim() || undefined,
+        });
+        setNewDsn(key.dsn);
+        setLabel('');
+        onKeyCreated?.(key.dsn);
+        toast.success('Key created', {
+          description: 'Make sure to copy its DSN now.',
+        });
+        router.refresh();
+      } catch (err) {
+        const message =
+          err instanceof Error ? err.message : 'Failed to create key';
+        toast.error('Failed to create key', { description: message });
+      }
+    });
+  };
+
+  const handleToggleActive = (key: ProjectKey) => {
+    startTransition(async () => {
+      try {
+        await setProjectKeyActive(projectId, key.id, !key.is_active);
+        toast.success(key.is_active ? 'Key deactivated' : 'Key activated');
+        router.refresh();
+      } catch (err) {
+        const message =
+          err instanceof Error ? err.message : 'Failed to update key';
+        toast.error('Failed to update key', { description: message });
+      }
+    });
+  };
+
+  const handleDelete = (key: ProjectKey) => {
+    startTransition(async () => {
+      try {
+        await deleteProjectKey(projectId, key.id);
+        toast.success('Key deleted');
+        router.refresh();
+      } catch (err) {
+        const message =
+          err instanceof Error ? err.message : 'Failed to delete key';
+        toast.error('Failed to delete key', { description: message });
+      }
+    });
+  };
+
+  const copyDsn = async () => {
+    if (newDsn) {
+      await navigator.clipboard.writeText(newDsn);
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    }
+  };
+
+  return (
+    <div className="space-y-3">
+      <div className="divide-y rounded-lg border">
+        {keys.map((key) => (
+          <div key={key.id} className="flex items-center gap-3 p-3">
+            <div className="flex-1 min-w-0">
+              <div className="flex items-center gap-2">
+                <span className="text-sm font-medium truncate">
+                  {key.label}
+                </span>
+                <code className="text-xs font-mono bg-muted px-1.5 py-0.5 rounded">
+                  {key.key_prefix}
+                </code>
+              </div>
+              <p className="text-xs text-muted-foreground mt-0.5">
+                {key.last_used_at
+                  ? `Last used ${formatDistanceToNow(new Date(key.last_used_at), { addSuffix: true })}`
+                  : 'Never used'}
+              </p>
+            </div>
+            <Switch
+              checked={key.is_active}
+              onCheckedChange={() => handleToggleActive(key)}
+              disabled={isPending}
+              size="sm"
+              aria-label={`${key.is_active ? 'Deactivate' : 'Activate'} key ${key.label}`}
+            />
+            <Button
+              variant="ghost"
+              size="icon"
+              onClick={() => handleDelete(key)}
+              disabled={isPending}
+              className="text-destructive hover:text-destructive"
+              aria-label={`Delete key ${key.label}`}
+            >
+              <Trash2 className="size-4" />
+            </Button>
+          </div>
+        ))}
+        {keys.length === 0 && (
+          <p className="p-3 text-sm text-muted-foreground">
+            No keys. SDKs can&apos;t send events to this project.
+          </p>
+        )}
+      </div>
+
+      <div className="flex items-center gap-2">
+        <Input
+          placeholder="Label (optional)"
+          value={label}
+          onChange={(e) => setLabel(e.target.value)}
+          onKeyDown={(e) => e.key === 'Enter' && handleCreate()}
+          maxLength={KEY_LABEL_MAX_LENGTH}
+          disabled={isPending}
+        />
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={handleCreate}
+          disabled={isPending}
+        >
+          {isPending ? (
+            <Loader2 className="size-4 animate-spin" />
+          ) : (
+            <>
+              <Plus className="mr-1 size-4" />
+              New Key
+            </>
+          )}
+        </Button>
+      </div>
+
+      {newDsn && (
+        <div className="space-y-1">
+          <div className="flex items-center gap-2 p-3 bg-muted rounded-lg border">
+            <code className="flex-1 text-xs font-mono break-all">{newDsn}</code>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={copyDsn}
+              className="shrink-0"
+            >
+              {copied ? (
+                <Check className="size-4 text-primary" />
+              ) : (
+                <Copy className="size-4" />
+              )}
+            </Button>
+          </div>
+          <p className="text-xs text-destructive">
+            Make sure to copy this DSN now. It will not be shown again.
+          </p>
+        </div>
+      )}
+    </div>
+  );
+}
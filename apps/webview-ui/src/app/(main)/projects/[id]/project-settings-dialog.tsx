@@ -19,6 +19,7 @@ import {
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { Separator } from '@/components/ui/separator';
+import { ProjectKeys } from './project-keys';
 
 interface ProjectSettingsDialogProps {
   project: Project;
@@ -34,7 +35,8 @@ export function ProjectSettingsDialog({ project }: ProjectSettingsDialogProps) {
   const [isPending, startTransition] = useTransition();
   const [open, setOpen] = useState(false);
   const [name, setName] = useState(project.name);
-  const [copiedDsn, setCopiedDsn] = useState(false);
+  // Only known right after a key is created
+  const [dsn, setDsn] = useState<string | null>(null);
   const [copiedCode, setCopiedCode] = useState(false);
   const [Highlighter, setHighlighter] = useState<HighlighterComponent | null>(
     null,
@@ -73,16 +75,10 @@ export function ProjectSettingsDialog({ project }: ProjectSettingsDialogProps) {
     }
   }, [isDark, Highlighter]);
 
-  const copyDsn = async () => {
-    await navigator.clipboard.writeText(project.dsn);
-    setCopiedDsn(true);
-    setTimeout(() => setCopiedDsn(false), 2000);
-  };
-
   const codeExample = `import * as Sentry from "@sentry/browser";
 
 Sentry.init({
-  dsn: "${project.dsn}",
+  dsn: "${dsn ?? '<your DSN>'}",
 });`;
 
   const copyCode = async () => {
@@ -112,6 +108,7 @@ Sentry.init({
     if (!newOpen) {
       // Reset name to current value when closing
       setName(project.name);
+      setDsn(null);
     }
     setOpen(newOpen);
   };
@@ -164,31 +161,20 @@ Sentry.init({
 
           <Separator />
 
-          {/* DSN */}
+          {/* Keys */}
           <div className="space-y-2">
-            <label
-              htmlFor="dsn"
-              className="text-xs font-bold uppercase tracking-widest text-muted-foreground"
-            >
-              DSN
-            </label>
-            <div className="flex items-center gap-2 p-3 bg-muted rounded-lg border">
-              <code className="flex-1 text-xs font-mono truncate">
-                {project.dsn}
-              </code>
-              <Button
-                variant="ghost"
-                size="sm"
-                onClick={copyDsn}
-                className="shrink-0"
-              >
-                {copiedDsn ? (
-                  <Check className="size-4 text-primary" />
-                ) : (
-                  <Copy className="size-4" />
-                )}
-              </Button>
-            </div>
+            <p className="text-xs font-bold uppercase tracking-widest text-muted-foreground">
+              Keys
+            </p>
+            <p className="text-xs text-muted-foreground">
+              SDKs authenticate with any active key. A key&apos;s DSN is only
+              shown when it&apos;s created.
+            </p>
+            <ProjectKeys
+              projectId={project.id}
+              keys={project.keys}
+              onKeyCreated={setDsn}
+            />
           </div>
 
           {/* Code Example */}
@@ -1,6 +1,6 @@
 'use client';
 
-import { Plus } from 'lucide-react';
+import { Check, Copy, Plus } from 'lucide-react';
 import { useRouter } from 'next/navigation';
 import { useState, useTransition } from 'react';
 import { toast } from 'sonner';
@@ -27,6 +27,8 @@ export function ProjectsHeader() {
   const [isCreateOpen, setIsCreateOpen] = useState(false);
   const [newProjectName, setNewProjectName] = useState('');
   const [validationError, setValidationError] = useState<string | null>(null);
+  const [createdDsn, setCreatedDsn] = useState<string | null>(null);
+  const [copied, setCopied] = useState(false);
 
   const trimmedName = newProjectName.trim();
   const isValidLength =
@@ -54,13 +56,13 @@ export function ProjectsHeader() {
 
     startTransition(async () => {
       try {
-        await createProject({ name: trimmedName });
+        const project = await createProject({ name: trimmedName });
         toast.success('Project created', {
           description: `"${trimmedName}" has been created successfully.`,
         });
         setNewProjectName('');
         setValidationError(null);
-        setIsCreateOpen(false);
+        setCreatedDsn(project.dsn ?? null);
         router.refresh();
       } catch (err) {
         const message =
@@ -70,6 +72,19 @@ export function ProjectsHeader() {
     });
   };
 
+  const handleOpenChange = (open: boolean) => {
+    setIsCreateOpen(open);
+    if (!open) setCreatedDsn(null);
+  };
+
+  const copyDsn = async () => {
+    if (createdDsn) {
+      await navigator.clipboard.writeText(createdDsn);
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    }
+  };
+
   return (
     <div className="flex items-start justify-between gap-4">
       <div>
@@ -79,7 +94,7 @@ export function ProjectsHeader() {
         </p>
       </div>
 
-      <Dialog open={isCreateOpen} onOpenChange={setIsCreateOpen}>
+      <Dialog open={isCreateOpen} onOpenChange={handleOpenChange}>
         <DialogTrigger asChild>
           <Button>
             <Plus className="mr-2 size-4" />
@@ -87,50 +102,91 @@ export function ProjectsHeader() {
           </Button>
         </DialogTrigger>
         <DialogContent>
-          <DialogHeader>
-            <DialogTitle>Create Project</DialogTitle>
-            <DialogDescription>
-              Create a new project to start tracking errors.
-            </DialogDescription>
-          </DialogHeader>
-          <div className="space-y-4 py-4">
-            <div className="space-y-2">
-              <Label htmlFor="name">Project Name</Label>
-              <Input
-                id="name"
-                placeholder="My Application"
-                value={newProjectName}
-                onChange={(e) => handleNameChange(e.target.value)}
-                onKeyDown={(e) => e.key === 'Enter' && handleCreate()}
-                maxLength={PROJECT_NAME_MAX_LENGTH + 10}
-                aria-invalid={!!validationError}
-                aria-describedby={validationError ? 'name-error' : undefined}
-              />
-              {validationError && (
-                <p id="name-error" className="text-sm text-destructive">
-                  {validationError}
+          {!createdDsn ? (
+            <>
+              <DialogHeader>
+                <DialogTitle>Create Project</DialogTitle>
+                <DialogDescription>
+                  Create a new project to start tracking errors.
+                </DialogDescription>
+              </DialogHeader>
+              <div className="space-y-4 py-4">
+                <div className="space-y-2">
+                  <Label htmlFor="name">Project Name</Label>
+                  <Input
+                    id="name"
+                    placeholder="My Application"
+                    value={newProjectName}
+                    onChange={(e) => handleNameChange(e.target.value)}
+                    onKeyDown={(e) => e.key === 'Enter' && handleCreate()}
+                    maxLength={PROJECT_NAME_MAX_LENGTH + 10}
+                    aria-invalid={!!validationError}
+                    aria-describedby={
+                      validationError ? 'name-error' : undefined
+                    }
+                  />
+                  {validationError && (
+                    <p id="name-error" className="text-sm text-destructive">
+                      {validationError}
+                    </p>
+                  )}
+                  <p className="text-xs text-muted-foreground">
+                    {trimmedName.length}/{PROJECT_NAME_MAX_LENGTH} characters
+                  </p>
+                </div>
+              </div>
+              <DialogFooter>
+                <Button
+                  variant="outline"
+                  onClick={() => handleOpenChange(false)}
+                  disabled={isPending}
+                >
+                  Cancel
+                </Button>
+                <Button
+                  onClick={handleCreate}
+                  disabled={isPending || !trimmedName || !isValidLength}
+                >
+                  {isPending ? 'Creating...' : 'Create'}
+                </Button>
+              </DialogFooter>
+            </>
+          ) : (
+            <>
+              <DialogHeader>
+                <DialogTitle>Project Created</DialogTitle>
+                <DialogDescription>
+                  Configure your SDK with this DSN. You won&apos;t be able to
+                  see it again!
+                </DialogDescription>
+              </DialogHeader>
+              <div className="py-4">
+                <div className="flex items-center gap-2 p-3 bg-card border rounded-lg">
+                  <code className="flex-1 text-sm font-mono break-all">
+                    {createdDsn}
+                  </code>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    onClick={copyDsn}
+                    className="shrink-0"
+                  >
+                    {copied ? (
+                      <Check className="size-4 text-primary" />
+                    ) : (
+                      <Copy className="size-4" />
+                    )}
+                  </Button>
+                </div>
+                <p className="text-xs text-muted-foreground mt-2">
+                  New keys can be created in the project settings.
                 </p>
-              )}
-              <p className="text-xs text-muted-foreground">
-                {trimmedName.length}/{PROJECT_NAME_MAX_LENGTH} characters
-              </p>
-            </div>
-          </div>
-          <DialogFooter>
-            <Button
-              variant="outline"
-              onClick={() => setIsCreateOpen(false)}
-              disabled={isPending}
-            >
-              Cancel
-            </Button>
-            <Button
-              onClick={handleCreate}
-              disabled={isPending || !trimmedName || !isValidLength}
-            >
-              {isPending ? 'Creating...' : 'Create'}
-            </Button>
-          </DialogFooter>
+              </div>
+              <DialogFooter>
+                <Button onClick={() => handleOpenChange(false)}>Done</Button>
+              </DialogFooter>
+            </>
+          )}
         </DialogContent>
       </Dialog>
     </div>
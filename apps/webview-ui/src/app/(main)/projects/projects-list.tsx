@@ -224,8 +224,8 @@ export function ProjectsList({
                     <div className="flex items-center gap-3 text-xs text-muted-foreground">
                       <span className="font-mono">{project.slug}</span>
                       <span className="text-muted-foreground/30">•</span>
-                      <span className="font-mono text-muted-foreground/70 truncate max-w-[400px]">
-                        {project.dsn}
+                      <span className="text-muted-foreground/70">
+                        {activeKeysLabel(project)}
                       </span>
                     </div>
                   </Link>
@@ -340,3 +340,8 @@ export function ProjectsList({
     </div>
   );
 }
+
+function activeKeysLabel(project: Project): string {
+  const count = project.keys.filter((key) => key.is_active).length;
+  return `${count} active ${count === 1 ? 'key' : 'keys'}`;
+}
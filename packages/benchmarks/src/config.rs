@@ -25,6 +25,9 @@ pub enum ScenarioType {
     Sustained,
     /// Stress test to find limits
     Stress,
+    /// Management API reads (issue list, issue and event detail)
+    #[serde(rename = "read_api")]
+    ReadApi,
 }
 
 impl Default for ScenarioType {
@@ -40,6 +43,7 @@ impl std::fmt::Display for ScenarioType {
             Self::Burst => write!(f, "burst"),
             Self::Sustained => write!(f, "sustained"),
             Self::Stress => write!(f, "stress"),
+            Self::ReadApi => write!(f, "read_api"),
         }
     }
 }
@@ -220,6 +224,60 @@ impl Default for StressConfig {
     }
 }
 
+/// Read API scenario configuration
+///
+/// Weights are relative: 6/3/1 sends 60% of the requests to the issue list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadApiConfig {
+    /// Weight of issue list requests
+    #[serde(default = "default_issue_list_weight")]
+    pub issue_list_weight: u32,
+    /// Weight of issue detail requests
+    #[serde(default = "default_issue_detail_weight")]
+    pub issue_detail_weight: u32,
+    /// Weight of event detail requests
+    #[serde(default = "default_event_detail_weight")]
+    pub event_detail_weight: u32,
+    /// Issues per page of list requests
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    /// Highest page list requests ask for
+    #[serde(default = "default_max_page")]
+    pub max_page: u32,
+}
+
+fn default_issue_list_weight() -> u32 {
+    6
+}
+
+fn default_issue_detail_weight() -> u32 {
+    3
+}
+
+fn default_event_detail_weight() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    25
+}
+
+fn default_max_page() -> u32 {
+    5
+}
+
+impl Default for ReadApiConfig {
+    fn default() -> Self {
+        Self {
+            issue_list_weight: default_issue_list_weight(),
+            issue_detail_weight: default_issue_detail_weight(),
+            event_detail_weight: default_event_detail_weight(),
+            per_page: default_per_page(),
+            max_page: default_max_page(),
+        }
+    }
+}
+
 /// Benchmark scenario configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScenarioConfig {
@@ -255,6 +313,9 @@ pub struct ScenarioConfig {
     /// Stress test configuration
     #[serde(default)]
     pub stress: StressConfig,
+    /// Read API configuration
+    #[serde(default)]
+    pub read_api: ReadApiConfig,
 }
 
 fn default_duration() -> u64 {
@@ -287,6 +348,7 @@ impl Default for ScenarioConfig {
             docker: DockerLimits::default(),
             burst: BurstConfig::default(),
             stress: StressConfig::default(),
+            read_api: ReadApiConfig::default(),
         }
     }
 }
@@ -367,6 +429,20 @@ impl ScenarioConfig {
         }
     }
 
+    /// Get read API scenario configuration
+    pub fn read_api() -> Self {
+        Self {
+            name: "read_api".to_string(),
+            description: "Load the issue list, issue detail and event detail endpoints".to_string(),
+            scenario_type: ScenarioType::ReadApi,
+            duration_secs: 120,
+            target_rps: 50,
+            concurrency: 10,
+            warmup_secs: 0, // Warmup sends events, not reads
+            ..Default::default()
+        }
+    }
+
     /// Get a predefined scenario by name
     pub fn from_name(name: &str) -> Option<Self> {
         match name.to_lowercase().as_str() {
@@ -374,6 +450,7 @@ impl ScenarioConfig {
             "burst" => Some(Self::burst()),
             "sustained" => Some(Self::sustained()),
             "stress" => Some(Self::stress()),
+            "read_api" | "read-api" => Some(Self::read_api()),
             _ => None,
         }
     }
@@ -409,6 +486,7 @@ mod tests {
     fn test_from_name() {
         assert!(ScenarioConfig::from_name("baseline").is_some());
         assert!(ScenarioConfig::from_name("SUSTAINED").is_some());
+        assert!(ScenarioConfig::from_name("read_api").is_some());
         assert!(ScenarioConfig::from_name("unknown").is_none());
     }
 
@@ -437,4 +515,25 @@ mod tests {
         assert_eq!(config.event.breadcrumb_count, 3);
         assert!(!config.event.include_tags);
     }
+
+    #[test]
+    fn test_parse_read_api_toml() {
+        let toml_str = r#"
+            name = "reads"
+            scenario_type = "read_api"
+            target_rps = 20
+
+            [read_api]
+            issue_list_weight = 1
+            event_detail_weight = 0
+        "#;
+
+        let config: ScenarioConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.scenario_type, ScenarioType::ReadApi);
+        assert_eq!(config.scenario_type.to_string(), "read_api");
+        assert_eq!(config.read_api.issue_list_weight, 1);
+        assert_eq!(config.read_api.issue_detail_weight, 3);
+        assert_eq!(config.read_api.event_detail_weight, 0);
+        assert_eq!(config.read_api.per_page, 25);
+    }
 }
@@ -349,6 +349,19 @@ impl EnvelopeGenerator {
     /// Generate a complete envelope (uncompressed)
     pub fn generate_envelope(&mut self, dsn: Option<&str>) -> Vec<u8> {
         let event = self.generate_event();
+        self.envelope_for(&event, dsn)
+    }
+
+    /// Generate a gzip-compressed envelope whose event is grouped into the
+    /// `issue`-th synthetic issue, to seed a project with many issues
+    pub fn generate_compressed_issue_envelope(&mut self, issue: u64) -> Vec<u8> {
+        let mut event = self.generate_event();
+        event.fingerprint = Some(vec![format!("bench-issue-{}", issue)]);
+        let envelope = self.envelope_for(&event, None);
+        compress(&envelope)
+    }
+
+    fn envelope_for(&self, event: &Event, dsn: Option<&str>) -> Vec<u8> {
         let event_json = serde_json::to_string(&event).expect("Failed to serialize event");
 
         let now: DateTime<Utc> = Utc::now();
@@ -385,12 +398,7 @@ impl EnvelopeGenerator {
     /// Generate a gzip-compressed envelope
     pub fn generate_compressed_envelope(&mut self, dsn: Option<&str>) -> Vec<u8> {
         let envelope = self.generate_envelope(dsn);
-
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
-        encoder
-            .write_all(&envelope)
-            .expect("Failed to compress envelope");
-        encoder.finish().expect("Failed to finish compression")
+        compress(&envelope)
     }
 
     /// Get the current counter value (number of events generated)
@@ -399,6 +407,14 @@ impl EnvelopeGenerator {
     }
 }
 
+fn compress(envelope: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(envelope)
+        .expect("Failed to compress envelope");
+    encoder.finish().expect("Failed to finish compression")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,6 +459,18 @@ mod tests {
         assert_eq!(compressed[1], 0x8b);
     }
 
+    #[test]
+    fn test_generate_issue_envelope() {
+        let mut generator = EnvelopeGenerator::new(EventConfig::default());
+
+        let compressed = generator.generate_compressed_issue_envelope(7);
+
+        let mut decoder = flate2::read::GzDecoder::new(compressed.as_slice());
+        let mut envelope = String::new();
+        std::io::Read::read_to_string(&mut decoder, &mut envelope).unwrap();
+        assert!(envelope.contains("\"fingerprint\":[\"bench-issue-7\"]"));
+    }
+
     #[test]
     fn test_counter_increments() {
         let config = EventConfig::default();
@@ -25,7 +25,7 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Scenario to run (baseline, burst, sustained, stress)
+    /// Scenario to run (baseline, burst, sustained, stress, read_api)
     #[arg(short, long, default_value = "sustained")]
     scenario: String,
 
@@ -45,6 +45,14 @@ struct Cli {
     #[arg(long, env = "SENTRY_KEY")]
     sentry_key: Option<String>,
 
+    /// API token for management API scenarios (read_api)
+    #[arg(long, env = "RUSTRAK_API_TOKEN")]
+    api_token: Option<String>,
+
+    /// Create this many synthetic issues before a read_api run
+    #[arg(long, default_value = "0")]
+    seed_issues: u64,
+
     /// Docker container name for metrics collection
     #[arg(long)]
     container: Option<String>,
@@ -113,6 +121,7 @@ fn list_scenarios() {
         ("burst", "Test handling of traffic spikes (10k events, pause, repeat)"),
         ("sustained", "Sustained load for memory stability testing (1k req/s)"),
         ("stress", "Find server limits by ramping up load until errors"),
+        ("read_api", "Load issue list, issue detail and event detail endpoints"),
     ];
 
     for (name, description) in scenarios {
@@ -161,6 +170,10 @@ async fn run_benchmark(cli: &Cli) -> anyhow::Result<()> {
     if let Some(ref container) = cli.container {
         runner = runner.with_container(container);
     }
+    if let Some(ref api_token) = cli.api_token {
+        runner = runner.with_api_token(api_token);
+    }
+    runner = runner.with_seed_issues(cli.seed_issues);
 
     // Wait for server
     if !cli.no_wait {
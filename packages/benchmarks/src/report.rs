@@ -8,6 +8,7 @@ use colored::Colorize;
 use hdrhistogram::Histogram;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use std::time::Duration;
@@ -42,6 +43,56 @@ pub struct LatencyMetrics {
     pub mean: f64,
 }
 
+impl LatencyMetrics {
+    /// Percentiles of a histogram of microseconds, in milliseconds
+    pub fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        if histogram.is_empty() {
+            return Self {
+                p50: 0.0,
+                p95: 0.0,
+                p99: 0.0,
+                max: 0.0,
+                min: 0.0,
+                mean: 0.0,
+            };
+        }
+
+        Self {
+            p50: histogram.value_at_percentile(50.0) as f64 / 1000.0,
+            p95: histogram.value_at_percentile(95.0) as f64 / 1000.0,
+            p99: histogram.value_at_percentile(99.0) as f64 / 1000.0,
+            max: histogram.max() as f64 / 1000.0,
+            min: histogram.min() as f64 / 1000.0,
+            mean: histogram.mean() / 1000.0,
+        }
+    }
+}
+
+/// Requests and latency of one endpoint class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointMetrics {
+    /// Total requests sent
+    pub total_requests: u64,
+    /// Successful requests (2xx)
+    pub successful: u64,
+    /// Failed requests
+    pub failed: u64,
+    /// Latency of the endpoint's requests
+    pub latency_ms: LatencyMetrics,
+}
+
+impl EndpointMetrics {
+    /// Create endpoint metrics from raw data
+    pub fn new(stats: &StatsSnapshot, histogram: &Histogram<u64>) -> Self {
+        Self {
+            total_requests: stats.total_requests,
+            successful: stats.successful,
+            failed: stats.failed,
+            latency_ms: LatencyMetrics::from_histogram(histogram),
+        }
+    }
+}
+
 /// Memory metrics in megabytes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryMetricsReport {
@@ -124,6 +175,9 @@ pub struct ResultsSection {
     pub errors: ErrorMetrics,
     /// Actual test duration
     pub actual_duration_secs: f64,
+    /// Breakdown per endpoint class, for scenarios hitting several endpoints
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub endpoints: BTreeMap<String, EndpointMetrics>,
 }
 
 impl BenchmarkResults {
@@ -150,25 +204,7 @@ impl BenchmarkResults {
         };
 
         // Convert histogram values from microseconds to milliseconds
-        let latency = if histogram.len() > 0 {
-            LatencyMetrics {
-                p50: histogram.value_at_percentile(50.0) as f64 / 1000.0,
-                p95: histogram.value_at_percentile(95.0) as f64 / 1000.0,
-                p99: histogram.value_at_percentile(99.0) as f64 / 1000.0,
-                max: histogram.max() as f64 / 1000.0,
-                min: histogram.min() as f64 / 1000.0,
-                mean: histogram.mean() / 1000.0,
-            }
-        } else {
-            LatencyMetrics {
-                p50: 0.0,
-                p95: 0.0,
-                p99: 0.0,
-                max: 0.0,
-                min: 0.0,
-                mean: 0.0,
-            }
-        };
+        let latency = LatencyMetrics::from_histogram(histogram);
 
         let (memory_mb, cpu_percent) = if let Some(ref metrics) = container_metrics {
             (
@@ -217,6 +253,7 @@ impl BenchmarkResults {
                         .saturating_sub(stats.server_errors),
                 },
                 actual_duration_secs: duration_secs,
+                endpoints: BTreeMap::new(),
             },
         }
     }
@@ -236,6 +273,12 @@ impl BenchmarkResults {
         self
     }
 
+    /// Add the per-endpoint breakdown to results
+    pub fn with_endpoints(mut self, endpoints: BTreeMap<String, EndpointMetrics>) -> Self {
+        self.results.endpoints = endpoints;
+        self
+    }
+
     /// Set server version
     pub fn with_server_version(mut self, version: &str) -> Self {
         self.server_version = Some(version.to_string());
@@ -318,6 +361,20 @@ impl BenchmarkResults {
             format!("{:.2}ms", self.results.latency_ms.mean).white()
         );
 
+        if !self.results.endpoints.is_empty() {
+            println!("\n{}", "Endpoints".yellow().bold());
+            for (name, endpoint) in &self.results.endpoints {
+                println!(
+                    "  {:<18} {} req | P50 {} | P95 {} | P99 {}",
+                    name,
+                    endpoint.total_requests.to_string().white(),
+                    format!("{:.2}ms", endpoint.latency_ms.p50).white(),
+                    format!("{:.2}ms", endpoint.latency_ms.p95).white(),
+                    format!("{:.2}ms", endpoint.latency_ms.p99).yellow().bold()
+                );
+            }
+        }
+
         if let Some(ref memory) = self.results.memory_mb {
             println!("\n{}", "Memory".yellow().bold());
             println!(
@@ -432,6 +489,34 @@ pub fn compare(old: &BenchmarkResults, new: &BenchmarkResults) {
         format_change(latency_change, false)
     );
 
+    // Endpoint classes only in one of the runs have nothing to compare to
+    let shared_endpoints: Vec<_> = new
+        .results
+        .endpoints
+        .iter()
+        .filter_map(|(name, new_endpoint)| {
+            old.results
+                .endpoints
+                .get(name)
+                .map(|old_endpoint| (name, old_endpoint, new_endpoint))
+        })
+        .collect();
+    if !shared_endpoints.is_empty() {
+        println!("\n{}", "Endpoint Latency P99".yellow().bold());
+        for (name, old_endpoint, new_endpoint) in shared_endpoints {
+            println!(
+                "  {:<18} {:.2}ms → {:.2}ms ({})",
+                name,
+                old_endpoint.latency_ms.p99,
+                new_endpoint.latency_ms.p99,
+                format_change(
+                    percent_change(old_endpoint.latency_ms.p99, new_endpoint.latency_ms.p99),
+                    false
+                )
+            );
+        }
+    }
+
     if let (Some(old_mem), Some(new_mem)) = (&old.results.memory_mb, &new.results.memory_mb) {
         let memory_change = (new_mem.peak_mb - old_mem.peak_mb) / old_mem.peak_mb * 100.0;
 
@@ -447,6 +532,14 @@ pub fn compare(old: &BenchmarkResults, new: &BenchmarkResults) {
     println!("{}", "═".repeat(60).cyan());
 }
 
+fn percent_change(old: f64, new: f64) -> f64 {
+    if old > 0.0 {
+        (new - old) / old * 100.0
+    } else {
+        0.0
+    }
+}
+
 fn format_change(percent: f64, higher_is_better: bool) -> colored::ColoredString {
     let is_improvement = if higher_is_better {
         percent > 0.0
@@ -510,4 +603,48 @@ mod tests {
         assert!(json.contains("throughput"));
         assert!(json.contains("latency_ms"));
     }
+
+    #[test]
+    fn test_endpoint_breakdown() {
+        let config = ScenarioConfig::read_api();
+        let stats = StatsSnapshot {
+            total_requests: 10,
+            successful: 9,
+            failed: 1,
+            ..Default::default()
+        };
+        let mut histogram = Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap();
+        histogram.record(20_000).unwrap();
+
+        let mut endpoints = BTreeMap::new();
+        endpoints.insert(
+            "issue_list".to_string(),
+            EndpointMetrics::new(&stats, &histogram),
+        );
+        let results =
+            BenchmarkResults::new(&config, stats, &histogram, Duration::from_secs(1), None)
+                .with_endpoints(endpoints);
+
+        let json = serde_json::to_string(&results).unwrap();
+        let parsed: BenchmarkResults = serde_json::from_str(&json).unwrap();
+        let issue_list = &parsed.results.endpoints["issue_list"];
+        assert_eq!(issue_list.failed, 1);
+        assert!((issue_list.latency_ms.p99 - 20.0).abs() < 0.1);
+
+        // Results of ingest scenarios have no breakdown, and compare with
+        // those that do
+        let old = BenchmarkResults::new(
+            &ScenarioConfig::sustained(),
+            StatsSnapshot::default(),
+            &Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            Duration::from_secs(1),
+            None,
+        );
+        let json = serde_json::to_string(&old).unwrap();
+        assert!(!json.contains("endpoints"));
+        let old: BenchmarkResults = serde_json::from_str(&json).unwrap();
+        assert!(old.results.endpoints.is_empty());
+        compare(&old, &parsed);
+        compare(&parsed, &parsed);
+    }
 }
@@ -1,14 +1,16 @@
 //! Benchmark runner for executing load tests.
 
-use crate::config::{ScenarioConfig, ScenarioType};
+use crate::config::{ReadApiConfig, ScenarioConfig, ScenarioType};
 use crate::envelope::{EnvelopeGenerator, EventConfig};
 use crate::metrics::MetricsCollector;
-use crate::report::BenchmarkResults;
+use crate::report::{BenchmarkResults, EndpointMetrics};
 use colored::Colorize;
 use futures::stream::{self, StreamExt};
 use hdrhistogram::Histogram;
 use indicatif::{ProgressBar, ProgressStyle};
+use rand::Rng;
 use reqwest::Client;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -27,6 +29,12 @@ pub enum RunnerError {
     ServerNotReady(u64),
     #[error("Invalid server URL: {0}")]
     InvalidUrl(String),
+    #[error("The read_api scenario needs an API token (--api-token)")]
+    MissingApiToken,
+    #[error("Invalid scenario configuration: {0}")]
+    InvalidConfig(String),
+    #[error("No issues with events found in project {0}; seed some with --seed-issues")]
+    NoReadTargets(u32),
 }
 
 /// Request result
@@ -88,6 +96,103 @@ pub struct StatsSnapshot {
     pub server_errors: u64,
 }
 
+/// Endpoint classes requested by the read API scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointClass {
+    /// Issue list, with varying filters, sorts and pages
+    IssueList,
+    /// Issue detail
+    IssueDetail,
+    /// Event detail
+    EventDetail,
+}
+
+impl EndpointClass {
+    pub const ALL: [Self; 3] = [Self::IssueList, Self::IssueDetail, Self::EventDetail];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IssueList => "issue_list",
+            Self::IssueDetail => "issue_detail",
+            Self::EventDetail => "event_detail",
+        }
+    }
+
+    fn weight(&self, config: &ReadApiConfig) -> u32 {
+        match self {
+            Self::IssueList => config.issue_list_weight,
+            Self::IssueDetail => config.issue_detail_weight,
+            Self::EventDetail => config.event_detail_weight,
+        }
+    }
+
+    /// Sum of the weights of all classes
+    pub fn total_weight(config: &ReadApiConfig) -> u32 {
+        Self::ALL.iter().map(|class| class.weight(config)).sum()
+    }
+
+    /// Class a roll in `0..total_weight` falls on
+    pub fn pick(config: &ReadApiConfig, roll: u32) -> Option<Self> {
+        let mut roll = roll;
+        for class in Self::ALL {
+            let weight = class.weight(config);
+            if roll < weight {
+                return Some(class);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+/// Issues and events of the project the read API scenario requests
+#[derive(Debug, Default)]
+struct ReadTargets {
+    issue_ids: Vec<String>,
+    /// Issue ID and event ID pairs
+    events: Vec<(String, String)>,
+}
+
+impl ReadTargets {
+    /// Path of a random request of `class`, below the project's API
+    fn path(&self, class: EndpointClass, config: &ReadApiConfig, rng: &mut impl Rng) -> String {
+        match class {
+            EndpointClass::IssueList => {
+                let filter = ["open", "resolved", "muted", "all"][rng.random_range(0..4)];
+                let sort = ["digest_order", "last_seen"][rng.random_range(0..2)];
+                let page = rng.random_range(1..=config.max_page.max(1));
+                format!(
+                    "issues?filter={}&sort={}&page={}&per_page={}",
+                    filter, sort, page, config.per_page
+                )
+            }
+            EndpointClass::IssueDetail => {
+                let issue_id = &self.issue_ids[rng.random_range(0..self.issue_ids.len())];
+                format!("issues/{}", issue_id)
+            }
+            EndpointClass::EventDetail => {
+                let (issue_id, event_id) = &self.events[rng.random_range(0..self.events.len())];
+                format!("issues/{}/events/{}", issue_id, event_id)
+            }
+        }
+    }
+}
+
+/// Statistics of one endpoint class
+struct EndpointRecorder {
+    stats: LiveStats,
+    histogram: Mutex<Histogram<u64>>,
+}
+
+impl EndpointRecorder {
+    fn new() -> Self {
+        Self {
+            stats: LiveStats::default(),
+            histogram: Mutex::new(Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap()),
+        }
+    }
+}
+
 /// Benchmark runner
 pub struct BenchmarkRunner {
     config: ScenarioConfig,
@@ -96,6 +201,8 @@ pub struct BenchmarkRunner {
     sentry_key: String,
     client: Client,
     container_name: Option<String>,
+    api_token: Option<String>,
+    seed_issues: u64,
 }
 
 impl BenchmarkRunner {
@@ -123,6 +230,8 @@ impl BenchmarkRunner {
             sentry_key: sentry_key.to_string(),
             client,
             container_name: None,
+            api_token: None,
+            seed_issues: 0,
         })
     }
 
@@ -132,6 +241,23 @@ impl BenchmarkRunner {
         self
     }
 
+    /// Set the API token for management API scenarios
+    pub fn with_api_token(mut self, api_token: &str) -> Self {
+        self.api_token = Some(api_token.to_string());
+        self
+    }
+
+    /// Create this many synthetic issues before a read API run
+    pub fn with_seed_issues(mut self, seed_issues: u64) -> Self {
+        self.seed_issues = seed_issues;
+        self
+    }
+
+    /// Get the project's management API URL
+    fn project_api_url(&self) -> String {
+        format!("{}/api/projects/{}", self.server_url, self.project_id)
+    }
+
     /// Get the envelope endpoint URL
     fn envelope_url(&self) -> String {
         format!(
@@ -150,7 +276,19 @@ impl BenchmarkRunner {
         while start.elapsed() < Duration::from_secs(timeout_secs) {
             match self.client.get(&health_url).send().await {
                 Ok(resp) if resp.status().is_success() => {
-                    println!("{}", "Server is ready!".green());
+                    let version = resp
+                        .json::<serde_json::Value>()
+                        .await
+                        .ok()
+                        .and_then(|body| body["version"].as_str().map(str::to_string));
+                    match version {
+                        Some(version) => println!(
+                            "{} {}",
+                            "Server is ready!".green(),
+                            format!("(version {})", version).dimmed()
+                        ),
+                        None => println!("{}", "Server is ready!".green()),
+                    }
                     return Ok(());
                 }
                 _ => {
@@ -651,6 +789,261 @@ impl BenchmarkRunner {
         BenchmarkResults::new(&self.config, snapshot, &hist, total_duration, None)
     }
 
+    /// Create `seed_issues` issues through the ingest endpoint
+    async fn seed(&self, generator: &Mutex<EnvelopeGenerator>) {
+        let pb = ProgressBar::new(self.seed_issues);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.yellow} Seeding issues [{bar:40.yellow}] {pos}/{len}")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+
+        let failed = stream::iter(0..self.seed_issues)
+            .map(|issue| async move {
+                let envelope = generator
+                    .lock()
+                    .await
+                    .generate_compressed_issue_envelope(issue);
+                self.send_request(envelope).await
+            })
+            .buffer_unordered(self.config.concurrency as usize)
+            .inspect(|_| pb.inc(1))
+            .filter(|result| futures::future::ready(!result.success))
+            .count()
+            .await;
+
+        pb.finish();
+        if failed > 0 {
+            println!(
+                "{}",
+                format!("Warning: {} seed events were rejected", failed).yellow()
+            );
+        }
+    }
+
+    /// Find the issues and events to request
+    ///
+    /// Seeded events are digested in the background, so this waits up to a
+    /// minute for the project to have that many issues.
+    async fn discover_read_targets(&self, token: &str) -> Result<ReadTargets, RunnerError> {
+        let list_url = format!("{}/issues?filter=all&per_page=100", self.project_api_url());
+        let deadline = Instant::now() + Duration::from_secs(60);
+
+        let page = loop {
+            let page: serde_json::Value = self
+                .client
+                .get(&list_url)
+                .bearer_auth(token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let total_count = page["total_count"].as_u64().unwrap_or(0);
+            if total_count >= self.seed_issues.max(1) || Instant::now() >= deadline {
+                break page;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        };
+
+        let mut targets = ReadTargets::default();
+        for item in page["items"].as_array().into_iter().flatten() {
+            if let Some(id) = item["id"].as_str() {
+                targets.issue_ids.push(id.to_string());
+            }
+        }
+
+        // A few issues' events are enough to spread event detail reads
+        for issue_id in targets.issue_ids.iter().take(20) {
+            let events: serde_json::Value = self
+                .client
+                .get(format!(
+                    "{}/issues/{}/events",
+                    self.project_api_url(),
+                    issue_id
+                ))
+                .bearer_auth(token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            for item in events["items"].as_array().into_iter().flatten() {
+                if let Some(id) = item["id"].as_str() {
+                    targets.events.push((issue_id.clone(), id.to_string()));
+                }
+            }
+        }
+
+        if targets.issue_ids.is_empty() || targets.events.is_empty() {
+            return Err(RunnerError::NoReadTargets(self.project_id));
+        }
+        Ok(targets)
+    }
+
+    /// Run read API scenario
+    async fn run_read_api(
+        &self,
+        generator: Arc<Mutex<EnvelopeGenerator>>,
+    ) -> Result<BenchmarkResults, RunnerError> {
+        let token = self.api_token.clone().ok_or(RunnerError::MissingApiToken)?;
+        let read_config = self.config.read_api.clone();
+        let total_weight = EndpointClass::total_weight(&read_config);
+        if total_weight == 0 {
+            return Err(RunnerError::InvalidConfig(
+                "read_api weights add up to 0".to_string(),
+            ));
+        }
+
+        if self.seed_issues > 0 {
+            self.seed(&generator).await;
+        }
+        let targets = Arc::new(self.discover_read_targets(&token).await?);
+        println!(
+            "Reading {} issues and {} events",
+            targets.issue_ids.len().to_string().cyan(),
+            targets.events.len().to_string().cyan()
+        );
+
+        let stats = Arc::new(LiveStats::default());
+        let histogram = Arc::new(Mutex::new(
+            Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+        ));
+        let recorders: Arc<Vec<EndpointRecorder>> = Arc::new(
+            EndpointClass::ALL
+                .iter()
+                .map(|_| EndpointRecorder::new())
+                .collect(),
+        );
+
+        let duration = Duration::from_secs(self.config.duration_secs);
+        // Default to 1 RPS if misconfigured
+        let interval_ns = 1_000_000_000u64
+            .checked_div(self.config.target_rps)
+            .unwrap_or(1_000_000_000);
+
+        let pb = ProgressBar::new(self.config.duration_secs);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} {msg} [{bar:40.green}] {pos}/{len}s")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        pb.set_message("Running read load");
+
+        let start = Instant::now();
+
+        // Spawn worker tasks
+        let mut handles = Vec::new();
+
+        for _ in 0..self.config.concurrency {
+            let client = self.client.clone();
+            let base_url = self.project_api_url();
+            let token = token.clone();
+            let read_config = read_config.clone();
+            let targets = targets.clone();
+            let stats = stats.clone();
+            let histogram = histogram.clone();
+            let recorders = recorders.clone();
+            let rate_limit = Duration::from_nanos(interval_ns * self.config.concurrency as u64);
+
+            let handle = tokio::spawn(async move {
+                let mut interval = interval(rate_limit);
+
+                while start.elapsed() < duration {
+                    interval.tick().await;
+
+                    let (class, path) = {
+                        let mut rng = rand::rng();
+                        let roll = rng.random_range(0..total_weight);
+                        let class = EndpointClass::pick(&read_config, roll)
+                            .expect("roll is below the total weight");
+                        (class, targets.path(class, &read_config, &mut rng))
+                    };
+
+                    let req_start = Instant::now();
+                    let result = client
+                        .get(format!("{}/{}", base_url, path))
+                        .bearer_auth(&token)
+                        .send()
+                        .await;
+
+                    let latency_us = req_start.elapsed().as_micros() as u64;
+
+                    let request_result = match result {
+                        Ok(resp) => {
+                            let status = resp.status().as_u16();
+                            RequestResult {
+                                latency_us,
+                                status,
+                                success: resp.status().is_success(),
+                            }
+                        }
+                        Err(_) => RequestResult {
+                            latency_us,
+                            status: 0,
+                            success: false,
+                        },
+                    };
+
+                    stats.record(&request_result);
+                    if let Ok(mut hist) = histogram.try_lock() {
+                        let _ = hist.record(latency_us);
+                    }
+
+                    let recorder = &recorders[class as usize];
+                    recorder.stats.record(&request_result);
+                    if let Ok(mut hist) = recorder.histogram.try_lock() {
+                        let _ = hist.record(latency_us);
+                    }
+                }
+            });
+
+            handles.push(handle);
+        }
+
+        // Progress updates
+        while start.elapsed() < duration {
+            pb.set_position(start.elapsed().as_secs());
+            let snapshot = stats.snapshot();
+            pb.set_message(format!(
+                "OK: {} | Fail: {}",
+                snapshot.successful, snapshot.failed
+            ));
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        for handle in handles {
+            handle.abort();
+        }
+
+        pb.finish_with_message("Read load complete");
+
+        let mut endpoints = BTreeMap::new();
+        for class in EndpointClass::ALL {
+            let recorder = &recorders[class as usize];
+            let snapshot = recorder.stats.snapshot();
+            if snapshot.total_requests == 0 {
+                continue;
+            }
+            let hist = recorder.histogram.lock().await;
+            endpoints.insert(
+                class.as_str().to_string(),
+                EndpointMetrics::new(&snapshot, &hist),
+            );
+        }
+
+        let total_duration = start.elapsed();
+        let snapshot = stats.snapshot();
+        let hist = histogram.lock().await;
+
+        Ok(
+            BenchmarkResults::new(&self.config, snapshot, &hist, total_duration, None)
+                .with_endpoints(endpoints),
+        )
+    }
+
     /// Run the benchmark scenario
     pub async fn run(&self) -> Result<BenchmarkResults, RunnerError> {
         println!(
@@ -706,6 +1099,7 @@ impl BenchmarkRunner {
             ScenarioType::Burst => self.run_burst(generator).await,
             ScenarioType::Sustained => self.run_sustained(generator).await,
             ScenarioType::Stress => self.run_stress(generator).await,
+            ScenarioType::ReadApi => self.run_read_api(generator).await?,
         };
 
         // Stop metrics collection and get results
@@ -717,3 +1111,61 @@ impl BenchmarkRunner {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_class_pick() {
+        let config = ReadApiConfig {
+            issue_list_weight: 2,
+            issue_detail_weight: 0,
+            event_detail_weight: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(EndpointClass::total_weight(&config), 3);
+        assert_eq!(
+            EndpointClass::pick(&config, 0),
+            Some(EndpointClass::IssueList)
+        );
+        assert_eq!(
+            EndpointClass::pick(&config, 1),
+            Some(EndpointClass::IssueList)
+        );
+        assert_eq!(
+            EndpointClass::pick(&config, 2),
+            Some(EndpointClass::EventDetail)
+        );
+        assert_eq!(EndpointClass::pick(&config, 3), None);
+    }
+
+    #[test]
+    fn test_read_target_paths() {
+        let targets = ReadTargets {
+            issue_ids: vec!["i1".to_string()],
+            events: vec![("i1".to_string(), "e1".to_string())],
+        };
+        let config = ReadApiConfig {
+            max_page: 3,
+            ..Default::default()
+        };
+        let mut rng = rand::rng();
+
+        assert_eq!(
+            targets.path(EndpointClass::IssueDetail, &config, &mut rng),
+            "issues/i1"
+        );
+        assert_eq!(
+            targets.path(EndpointClass::EventDetail, &config, &mut rng),
+            "issues/i1/events/e1"
+        );
+        for _ in 0..20 {
+            let path = targets.path(EndpointClass::IssueList, &config, &mut rng);
+            assert!(path.starts_with("issues?filter="));
+            assert!(path.ends_with("&per_page=25"));
+            assert!(!path.contains("page=4"));
+        }
+    }
+}
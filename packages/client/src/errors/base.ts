@@ -1,3 +1,28 @@
+/**
+ * Machine-readable details sent by the server in the error body
+ */
+export interface ErrorDetails {
+  /**
+   * Stable error code (e.g. `validation.required`, `conflict.name_taken`)
+   */
+  code?: string;
+
+  /**
+   * Request field the error is about
+   */
+  field?: string;
+
+  /**
+   * Resources still using the one being deleted (`conflict.in_use`)
+   */
+  dependents?: string[];
+
+  /**
+   * Id of the failed request, for finding it in the server logs
+   */
+  requestId?: string;
+}
+
 /**
  * Base error class for all Rustrak client errors
  */
@@ -17,19 +42,43 @@ export class RustrakError extends Error {
    */
   public readonly cause?: Error;
 
+  /**
+   * Stable error code from the server, if any
+   */
+  public readonly code?: string;
+
+  /**
+   * Request field the error is about, if any
+   */
+  public readonly field?: string;
+
+  /**
+   * Resources blocking a delete, if any
+   */
+  public readonly dependents?: string[];
+
+  /**
+   * Server-side id of the failed request, if any
+   */
+  public readonly requestId?: string;
+
   constructor(
     message: string,
     options?: {
       retryable?: boolean;
       statusCode?: number;
       cause?: Error;
-    },
+    } & ErrorDetails,
   ) {
     super(message);
     this.name = this.constructor.name;
     this.retryable = options?.retryable ?? false;
     this.statusCode = options?.statusCode;
     this.cause = options?.cause;
+    this.code = options?.code;
+    this.field = options?.field;
+    this.dependents = options?.dependents;
+    this.requestId = options?.requestId;
 
     // Maintains proper stack trace for where our error was thrown (only available on V8)
     if (Error.captureStackTrace) {
@@ -1,4 +1,4 @@
-import { RustrakError } from './base.js';
+import { type ErrorDetails, RustrakError } from './base.js';
 
 /**
  * Network error (connection issues, timeouts)
@@ -15,8 +15,8 @@ export class NetworkError extends RustrakError {
  * Not retryable - requires new credentials
  */
 export class AuthenticationError extends RustrakError {
-  constructor(message = 'Authentication failed') {
-    super(message, { retryable: false, statusCode: 401 });
+  constructor(message = 'Authentication failed', details?: ErrorDetails) {
+    super(message, { retryable: false, statusCode: 401, ...details });
   }
 }
 
@@ -25,8 +25,8 @@ export class AuthenticationError extends RustrakError {
  * Not retryable - requires different permissions
  */
 export class AuthorizationError extends RustrakError {
-  constructor(message = 'Insufficient permissions') {
-    super(message, { retryable: false, statusCode: 403 });
+  constructor(message = 'Insufficient permissions', details?: ErrorDetails) {
+    super(message, { retryable: false, statusCode: 403, ...details });
   }
 }
 
@@ -35,10 +35,11 @@ export class AuthorizationError extends RustrakError {
  * Not retryable
  */
 export class NotFoundError extends RustrakError {
-  constructor(resource: string) {
+  constructor(resource: string, details?: ErrorDetails) {
     super(`Resource not found: ${resource}`, {
       retryable: false,
       statusCode: 404,
+      ...details,
     });
   }
 }
@@ -53,8 +54,12 @@ export class RateLimitError extends RustrakError {
    */
   public readonly retryAfter?: number;
 
-  constructor(message = 'Rate limit exceeded', retryAfter?: string | number) {
-    super(message, { retryable: true, statusCode: 429 });
+  constructor(
+    message = 'Rate limit exceeded',
+    retryAfter?: string | number,
+    details?: ErrorDetails,
+  ) {
+    super(message, { retryable: true, statusCode: 429, ...details });
 
     if (retryAfter !== undefined) {
       this.retryAfter =
@@ -68,8 +73,8 @@ export class RateLimitError extends RustrakError {
  * Retryable by default
  */
 export class ServerError extends RustrakError {
-  constructor(message: string, statusCode = 500) {
-    super(message, { retryable: true, statusCode });
+  constructor(message: string, statusCode = 500, details?: ErrorDetails) {
+    super(message, { retryable: true, statusCode, ...details });
   }
 }
 
@@ -78,7 +83,7 @@ export class ServerError extends RustrakError {
  * Not retryable - client error
  */
 export class BadRequestError extends RustrakError {
-  constructor(message: string) {
-    super(message, { retryable: false, statusCode: 400 });
+  constructor(message: string, details?: ErrorDetails) {
+    super(message, { retryable: false, statusCode: 400, ...details });
   }
 }
@@ -1,4 +1,4 @@
-export { RustrakError } from './base.js';
+export { type ErrorDetails, RustrakError } from './base.js';
 export {
   AuthenticationError,
   AuthorizationError,
@@ -16,6 +16,7 @@ export {
   AuthenticationError,
   AuthorizationError,
   BadRequestError,
+  type ErrorDetails,
   NetworkError,
   NotFoundError,
   RateLimitError,
@@ -25,6 +26,7 @@ export {
 } from './errors/index.js';
 // Types
 export type {
+  AddProjectMember,
   // Alerts
   AlertHistory,
   AlertRule,
@@ -36,22 +38,42 @@ export type {
   // Tokens
   AuthToken,
   AuthTokenCreated,
+  ChannelHealth,
+  ChannelStats,
+  ChannelStatsOptions,
   ChannelType,
   CreateAlertRule,
   CreateAuthToken,
   CreateNotificationChannel,
   CreateProject,
+  CreateProjectKey,
+  DeleteChannelOptions,
   // Events
   Event,
   EventDetail,
+  EventSort,
+  GetDailyStatsOptions,
+  GetIssueBundleOptions,
+  GetIssueOptions,
   // Issues
   Issue,
+  IssueBundle,
+  IssueContexts,
   IssueFilter,
+  IssueGrouping,
+  IssueLevelBreakdown,
+  IssuePriority,
+  IssuePriorityChange,
+  IssueSeenBy,
   IssueSort,
+  IssueTransactionBreakdown,
   ListAlertHistoryOptions,
   ListEventsOptions,
   ListIssuesOptions,
   ListProjectsOptions,
+  ListTokensOptions,
+  ListTrashOptions,
+  LoginMethods,
   LoginRequest,
   LoginResult,
   NotificationChannel,
@@ -60,12 +82,25 @@ export type {
   PaginatedResponse,
   // Projects
   Project,
+  ProjectDailyStats,
+  ProjectKey,
+  ProjectKeyCreated,
+  ProjectLiveStats,
+  ProjectMember,
+  ProjectOverview,
+  ProjectRole,
+  ProjectStats,
+  RateWindows,
   RegisterRequest,
+  ResolveIssue,
   SortOrder,
   TestChannelResponse,
   UpdateAlertRule,
+  UpdateAuthToken,
   UpdateIssueState,
   UpdateNotificationChannel,
   UpdateProject,
+  UpdateProjectKey,
   User,
+  UserOverview,
 } from './types/index.js';
@@ -1,12 +1,18 @@
 import { z } from 'zod';
 import {
+  channelHealthSchema,
+  channelStatsSchema,
   createNotificationChannelSchema,
   notificationChannelSchema,
   testChannelResponseSchema,
   updateNotificationChannelSchema,
 } from '../schemas/alert.js';
 import type {
+  ChannelHealth,
+  ChannelStats,
+  ChannelStatsOptions,
   CreateNotificationChannel,
+  DeleteChannelOptions,
   NotificationChannel,
   TestChannelResponse,
   UpdateNotificationChannel,
@@ -70,9 +76,53 @@ export class AlertChannelsResource extends BaseResource {
 
   /**
    * Delete a notification channel
+   *
+   * Fails with a 409 listing the alert rules using the channel in
+   * `dependents`, unless `force` is set to detach it from them.
    */
-  async delete(id: number): Promise<void> {
-    await this.http.delete(`api/alert-channels/${id}`);
+  async delete(id: number, options?: DeleteChannelOptions): Promise<void> {
+    const searchParams: Record<string, string> = {};
+    if (options?.force) {
+      searchParams.force = 'true';
+    }
+    await this.http.delete(`api/alert-channels/${id}`, { searchParams });
+  }
+
+  /**
+   * Delivery health of every channel: failure streak, last success and
+   * failure, and whether it was disabled for failing
+   */
+  async health(): Promise<ChannelHealth[]> {
+    const data = await this.http.get('api/alert-channels/health').json();
+    return this.validate(data, z.array(channelHealthSchema));
+  }
+
+  /**
+   * Delivery stats of a channel over a period: attempts, successes,
+   * failures and success rate
+   */
+  async stats(
+    id: number,
+    options?: ChannelStatsOptions,
+  ): Promise<ChannelStats> {
+    const searchParams: Record<string, string> = {};
+    if (options?.period) {
+      searchParams.period = options.period;
+    }
+    const data = await this.http
+      .get(`api/alert-channels/${id}/stats`, { searchParams })
+      .json();
+    return this.validate(data, channelStatsSchema);
+  }
+
+  /**
+   * Clear a channel's failure streak and enable it again
+   */
+  async resetFailures(id: number): Promise<NotificationChannel> {
+    const data = await this.http
+      .post(`api/alert-channels/${id}/reset-failures`)
+      .json();
+    return this.validate(data, notificationChannelSchema);
   }
 
   /**
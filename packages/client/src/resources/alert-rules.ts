@@ -5,6 +5,7 @@ import {
   createAlertRuleSchema,
   updateAlertRuleSchema,
 } from '../schemas/alert.js';
+import { offsetPaginatedResponseSchema } from '../schemas/common.js';
 import type {
   AlertHistory,
   AlertRule,
@@ -12,6 +13,7 @@ import type {
   ListAlertHistoryOptions,
   UpdateAlertRule,
 } from '../types/alert.js';
+import type { OffsetPaginatedResponse } from '../types/common.js';
 import { BaseResource } from './base.js';
 
 /**
@@ -78,16 +80,25 @@ export class AlertRulesResource extends BaseResource {
   }
 
   /**
-   * List alert history for a project
+   * List alert history for a project with pagination
    */
   async listHistory(
     projectId: number,
     options?: ListAlertHistoryOptions,
-  ): Promise<AlertHistory[]> {
+  ): Promise<OffsetPaginatedResponse<AlertHistory>> {
     const searchParams = new URLSearchParams();
 
-    if (options?.limit !== undefined) {
-      searchParams.set('limit', options.limit.toString());
+    if (options?.page !== undefined) {
+      searchParams.set('page', options.page.toString());
+    }
+    if (options?.per_page !== undefined) {
+      searchParams.set('per_page', options.per_page.toString());
+    }
+    if (options?.status) {
+      searchParams.set('status', options.status);
+    }
+    if (options?.channel_id !== undefined) {
+      searchParams.set('channel_id', options.channel_id.toString());
     }
 
     const query = searchParams.toString();
@@ -96,6 +107,9 @@ export class AlertRulesResource extends BaseResource {
       : `api/projects/${projectId}/alert-history`;
 
     const data = await this.http.get(url).json();
-    return this.validate(data, z.array(alertHistorySchema));
+    return this.validate(
+      data,
+      offsetPaginatedResponseSchema(alertHistorySchema),
+    );
   }
 }
@@ -1,14 +1,18 @@
 import {
   authResponseSchema,
+  loginMethodsSchema,
   loginRequestSchema,
   registerRequestSchema,
+  userOverviewSchema,
   userSchema,
 } from '../schemas/user.js';
 import type {
+  LoginMethods,
   LoginRequest,
   LoginResult,
   RegisterRequest,
   User,
+  UserOverview,
 } from '../types/user.js';
 import { BaseResource } from './base.js';
 
@@ -88,4 +92,26 @@ export class AuthResource extends BaseResource {
     const data = await this.http.get('auth/me').json();
     return this.validate(data, userSchema);
   }
+
+  /**
+   * Get the login methods enabled on the server
+   * Password login can be disabled when single sign-on is configured; SSO
+   * logins start by sending the browser to `auth/oidc/login`
+   * @returns Whether password and OIDC login are available
+   */
+  async getLoginMethods(): Promise<LoginMethods> {
+    const data = await this.http.get('auth/methods').json();
+    return this.validate(data, loginMethodsSchema);
+  }
+
+  /**
+   * Get the dashboard overview of the caller
+   * With a session, includes the user's projects; with an API token, only
+   * the recent open issues of every project
+   * @returns Recent open issues and, for users, their projects
+   */
+  async getOverview(): Promise<UserOverview> {
+    const data = await this.http.get('api/me/overview').json();
+    return this.validate(data, userOverviewSchema);
+  }
 }
@@ -1,3 +1,4 @@
+import type { AfterResponseHook } from 'ky';
 import {
   eventDetailSchema,
   eventSchema,
@@ -11,6 +12,15 @@ import type {
 } from '../types/index.js';
 import { BaseResource } from './base.js';
 
+/**
+ * Events whose payload was pruned answer 410 Gone with their metadata;
+ * hand those over like any other event instead of throwing
+ */
+const acceptPrunedEvent: AfterResponseHook = (_request, _options, response) =>
+  response.status === 410
+    ? new Response(response.body, { status: 200, headers: response.headers })
+    : response;
+
 /**
  * Events API resource
  */
@@ -28,9 +38,18 @@ export class EventsResource extends BaseResource {
     if (options?.order) {
       searchParams.order = options.order;
     }
+    if (options?.by) {
+      searchParams.by = options.by;
+    }
     if (options?.cursor) {
       searchParams.cursor = options.cursor;
     }
+    if (options?.per_page) {
+      searchParams.per_page = options.per_page.toString();
+    }
+    if (options?.dist) {
+      searchParams.dist = options.dist;
+    }
 
     const data = await this.http
       .get(`api/projects/${projectId}/issues/${issueId}/events`, {
@@ -43,6 +62,9 @@ export class EventsResource extends BaseResource {
 
   /**
    * Get a single event by ID with full details
+   *
+   * Once its payload was pruned by payload retention, `payload_pruned` is
+   * true and `data` is null.
    */
   async get(
     projectId: number,
@@ -50,7 +72,24 @@ export class EventsResource extends BaseResource {
     eventId: string,
   ): Promise<EventDetail> {
     const data = await this.http
-      .get(`api/projects/${projectId}/issues/${issueId}/events/${eventId}`)
+      .get(`api/projects/${projectId}/issues/${issueId}/events/${eventId}`, {
+        hooks: { afterResponse: [acceptPrunedEvent] },
+      })
+      .json();
+
+    return this.validate(data, eventDetailSchema);
+  }
+
+  /**
+   * Find an event by its SDK event ID without knowing the issue
+   *
+   * The response includes the owning `issue_id`.
+   */
+  async getByEventId(projectId: number, eventId: string): Promise<EventDetail> {
+    const data = await this.http
+      .get(`api/projects/${projectId}/events/${eventId}`, {
+        hooks: { afterResponse: [acceptPrunedEvent] },
+      })
       .json();
 
     return this.validate(data, eventDetailSchema);
@@ -1,12 +1,28 @@
 import {
+  issueAggregateResponseSchema,
+  issueBundleSchema,
+  issueContextsSchema,
+  issueLevelBreakdownSchema,
   issueSchema,
+  issueTransactionBreakdownSchema,
   offsetPaginatedResponseSchema,
+  resolveIssueSchema,
   updateIssueStateSchema,
 } from '../schemas/index.js';
 import type {
+  AggregateIssuesOptions,
+  GetIssueBundleOptions,
+  GetIssueOptions,
   Issue,
+  IssueAggregateResponse,
+  IssueBundle,
+  IssueContexts,
+  IssueLevelBreakdown,
+  IssueTransactionBreakdown,
   ListIssuesOptions,
+  ListTrashOptions,
   OffsetPaginatedResponse,
+  ResolveIssue,
   UpdateIssueState,
 } from '../types/index.js';
 import { BaseResource } from './base.js';
@@ -39,6 +55,15 @@ export class IssuesResource extends BaseResource {
     if (options?.filter) {
       searchParams.filter = options.filter;
     }
+    if (options?.dist) {
+      searchParams.dist = options.dist;
+    }
+    if (options?.handled !== undefined) {
+      searchParams.handled = options.handled.toString();
+    }
+    if (options?.priority) {
+      searchParams.priority = options.priority;
+    }
 
     const data = await this.http
       .get(`api/projects/${projectId}/issues`, { searchParams })
@@ -47,19 +72,119 @@ export class IssuesResource extends BaseResource {
     return this.validate(data, offsetPaginatedResponseSchema(issueSchema));
   }
 
+  /**
+   * Count issues and their events in a period per error type or transaction
+   */
+  async aggregate(
+    projectId: number,
+    groupBy: 'calculated_type' | 'transaction',
+    options?: AggregateIssuesOptions,
+  ): Promise<IssueAggregateResponse> {
+    const searchParams: Record<string, string> = { group_by: groupBy };
+
+    if (options?.period) {
+      searchParams.period = options.period;
+    }
+    if (options?.filter) {
+      searchParams.filter = options.filter;
+    }
+    if (options?.dist) {
+      searchParams.dist = options.dist;
+    }
+
+    const data = await this.http
+      .get(`api/projects/${projectId}/issues/aggregate`, { searchParams })
+      .json();
+
+    return this.validate(data, issueAggregateResponseSchema);
+  }
+
   /**
    * Get a single issue by ID
    */
-  async get(projectId: number, issueId: string): Promise<Issue> {
+  async get(
+    projectId: number,
+    issueId: string,
+    options?: GetIssueOptions,
+  ): Promise<Issue> {
+    const searchParams: Record<string, string> = {};
+    if (options?.include?.length) {
+      searchParams.include = options.include.join(',');
+    }
+
     const data = await this.http
-      .get(`api/projects/${projectId}/issues/${issueId}`)
+      .get(`api/projects/${projectId}/issues/${issueId}`, { searchParams })
       .json();
 
     return this.validate(data, issueSchema);
   }
 
   /**
-   * Update issue state (resolve, mute, etc.)
+   * Download an issue and its latest events as one JSON document
+   */
+  async getBundle(
+    projectId: number,
+    issueId: string,
+    options?: GetIssueBundleOptions,
+  ): Promise<IssueBundle> {
+    const searchParams: Record<string, string> = {};
+    if (options?.events !== undefined) {
+      searchParams.events = options.events.toString();
+    }
+
+    const data = await this.http
+      .get(`api/projects/${projectId}/issues/${issueId}/bundle`, {
+        searchParams,
+      })
+      .json();
+
+    return this.validate(data, issueBundleSchema);
+  }
+
+  /**
+   * Get the top OS, browser and runtime values of an issue's events
+   */
+  async getContexts(
+    projectId: number,
+    issueId: string,
+  ): Promise<IssueContexts> {
+    const data = await this.http
+      .get(`api/projects/${projectId}/issues/${issueId}/contexts`)
+      .json();
+
+    return this.validate(data, issueContextsSchema);
+  }
+
+  /**
+   * Count an issue's events per level (error, warning, fatal, ...)
+   */
+  async getLevelBreakdown(
+    projectId: number,
+    issueId: string,
+  ): Promise<IssueLevelBreakdown> {
+    const data = await this.http
+      .get(`api/projects/${projectId}/issues/${issueId}/level-breakdown`)
+      .json();
+
+    return this.validate(data, issueLevelBreakdownSchema);
+  }
+
+  /**
+   * Count an issue's events per transaction, most affected first
+   */
+  async getTransactions(
+    projectId: number,
+    issueId: string,
+  ): Promise<IssueTransactionBreakdown> {
+    const data = await this.http
+      .get(`api/projects/${projectId}/issues/${issueId}/transactions`)
+      .json();
+
+    return this.validate(data, issueTransactionBreakdownSchema);
+  }
+
+  /**
+   * Update issue state (resolve, mute, etc.) and its sample rate
    */
   async updateState(
     projectId: number,
@@ -79,9 +204,63 @@ export class IssuesResource extends BaseResource {
   }
 
   /**
-   * Delete an issue
+   * Resolve an issue, optionally recording the commit or pull request
+   * that fixed it
+   */
+  async resolve(
+    projectId: number,
+    issueId: string,
+    input: ResolveIssue = {},
+  ): Promise<Issue> {
+    const validatedInput = this.validate(input, resolveIssueSchema);
+
+    const data = await this.http
+      .post(`api/projects/${projectId}/issues/${issueId}/resolve`, {
+        json: validatedInput,
+      })
+      .json();
+
+    return this.validate(data, issueSchema);
+  }
+
+  /**
+   * Move an issue to the trash
    */
   async delete(projectId: number, issueId: string): Promise<void> {
     await this.http.delete(`api/projects/${projectId}/issues/${issueId}`);
   }
+
+  /**
+   * List deleted issues that can still be restored, most recently deleted first
+   */
+  async listTrash(
+    projectId: number,
+    options?: ListTrashOptions,
+  ): Promise<OffsetPaginatedResponse<Issue>> {
+    const searchParams: Record<string, string> = {};
+
+    if (options?.page !== undefined) {
+      searchParams.page = options.page.toString();
+    }
+    if (options?.per_page !== undefined) {
+      searchParams.per_page = options.per_page.toString();
+    }
+
+    const data = await this.http
+      .get(`api/projects/${projectId}/issues/trash`, { searchParams })
+      .json();
+
+    return this.validate(data, offsetPaginatedResponseSchema(issueSchema));
+  }
+
+  /**
+   * Restore an issue from the trash
+   */
+  async restore(projectId: number, issueId: string): Promise<Issue> {
+    const data = await this.http
+      .post(`api/projects/${projectId}/issues/${issueId}/restore`)
+      .json();
+
+    return this.validate(data, issueSchema);
+  }
 }
@@ -1,15 +1,36 @@
+import { z } from 'zod';
 import {
+  addProjectMemberSchema,
+  createProjectKeySchema,
   createProjectSchema,
   offsetPaginatedResponseSchema,
+  projectDailyStatsSchema,
+  projectKeyCreatedSchema,
+  projectKeySchema,
+  projectLiveStatsSchema,
+  projectMemberSchema,
+  projectOnboardingSchema,
   projectSchema,
+  updateProjectKeySchema,
   updateProjectSchema,
 } from '../schemas/index.js';
 import type {
+  AddProjectMember,
   CreateProject,
+  CreateProjectKey,
+  GetDailyStatsOptions,
+  GetOnboardingOptions,
   ListProjectsOptions,
   OffsetPaginatedResponse,
   Project,
+  ProjectDailyStats,
+  ProjectKey,
+  ProjectKeyCreated,
+  ProjectLiveStats,
+  ProjectMember,
+  ProjectOnboarding,
   UpdateProject,
+  UpdateProjectKey,
 } from '../types/index.js';
 import { BaseResource } from './base.js';
 
@@ -34,6 +55,9 @@ export class ProjectsResource extends BaseResource {
     if (options?.order) {
       searchParams.set('order', options.order);
     }
+    if (options?.include?.length) {
+      searchParams.set('include', options.include.join(','));
+    }
 
     const query = searchParams.toString();
     const url = query ? `api/projects?${query}` : 'api/projects';
@@ -50,6 +74,59 @@ export class ProjectsResource extends BaseResource {
     return this.validate(data, projectSchema);
   }
 
+  /**
+   * Get whether a project has received its first event
+   *
+   * With `wait`, the server answers as soon as the first event arrives, or
+   * after `wait` seconds.
+   */
+  async getOnboarding(
+    id: number,
+    options?: GetOnboardingOptions,
+  ): Promise<ProjectOnboarding> {
+    const wait = options?.wait;
+    const url = wait
+      ? `api/projects/${id}/onboarding?wait=${wait}`
+      : `api/projects/${id}/onboarding`;
+
+    // Leave room for the server to hold the request
+    const data = await this.http
+      .get(url, wait ? { timeout: (Math.min(wait, 60) + 10) * 1000 } : {})
+      .json();
+    return this.validate(data, projectOnboardingSchema);
+  }
+
+  /**
+   * Get the events per second a project is sending right now
+   *
+   * The rates are those of the server instance that answers the request.
+   */
+  async getLiveStats(id: number): Promise<ProjectLiveStats> {
+    const data = await this.http.get(`api/projects/${id}/stats/live`).json();
+    return this.validate(data, projectLiveStatsSchema);
+  }
+
+  /**
+   * Get the events digested per day, bucketed in the project's timezone
+   */
+  async getDailyStats(
+    id: number,
+    options?: GetDailyStatsOptions,
+  ): Promise<ProjectDailyStats> {
+    const searchParams: Record<string, string> = {};
+    if (options?.days !== undefined) {
+      searchParams.days = options.days.toString();
+    }
+    if (options?.tz) {
+      searchParams.tz = options.tz;
+    }
+
+    const data = await this.http
+      .get(`api/projects/${id}/stats/daily`, { searchParams })
+      .json();
+    return this.validate(data, projectDailyStatsSchema);
+  }
+
   /**
    * Create a new project
    */
@@ -84,4 +161,84 @@ export class ProjectsResource extends BaseResource {
   async delete(id: number): Promise<void> {
     await this.http.delete(`api/projects/${id}`);
   }
+
+  /**
+   * List a project's keys (masked)
+   */
+  async listKeys(id: number): Promise<ProjectKey[]> {
+    const data = await this.http.get(`api/projects/${id}/keys`).json();
+    return this.validate(data, z.array(projectKeySchema));
+  }
+
+  /**
+   * Create a new key for a project
+   *
+   * The full key and DSN are only returned here.
+   */
+  async createKey(
+    id: number,
+    input: CreateProjectKey = {},
+  ): Promise<ProjectKeyCreated> {
+    const validatedInput = this.validate(input, createProjectKeySchema);
+
+    const data = await this.http
+      .post(`api/projects/${id}/keys`, { json: validatedInput })
+      .json();
+
+    return this.validate(data, projectKeyCreatedSchema);
+  }
+
+  /**
+   * Activate or deactivate a project key
+   */
+  async updateKey(
+    id: number,
+    keyId: number,
+    input: UpdateProjectKey,
+  ): Promise<ProjectKey> {
+    const validatedInput = this.validate(input, updateProjectKeySchema);
+
+    const data = await this.http
+      .patch(`api/projects/${id}/keys/${keyId}`, { json: validatedInput })
+      .json();
+
+    return this.validate(data, projectKeySchema);
+  }
+
+  /**
+   * Delete a project key
+   */
+  async deleteKey(id: number, keyId: number): Promise<void> {
+    await this.http.delete(`api/projects/${id}/keys/${keyId}`);
+  }
+
+  /**
+   * List the users who can see a project
+   */
+  async listMembers(id: number): Promise<ProjectMember[]> {
+    const data = await this.http.get(`api/projects/${id}/members`).json();
+    return this.validate(data, z.array(projectMemberSchema));
+  }
+
+  /**
+   * Add a user to a project
+   *
+   * Only admins and the project's admins can manage its members.
+   */
+  async addMember(id: number, input: AddProjectMember): Promise<ProjectMember> {
+    const validatedInput = this.validate(input, addProjectMemberSchema);
+
+    const data = await this.http
+      .post(`api/projects/${id}/members`, { json: validatedInput })
+      .json();
+
+    return this.validate(data, projectMemberSchema);
+  }
+
+  /**
+   * Remove a user from a project
+   */
+  async removeMember(id: number, userId: number): Promise<void> {
+    await this.http.delete(`api/projects/${id}/members/${userId}`);
+  }
 }
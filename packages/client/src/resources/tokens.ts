@@ -2,11 +2,16 @@ import {
   authTokenCreatedSchema,
   authTokenSchema,
   createAuthTokenSchema,
+  offsetPaginatedResponseSchema,
+  updateAuthTokenSchema,
 } from '../schemas/index.js';
 import type {
   AuthToken,
   AuthTokenCreated,
   CreateAuthToken,
+  ListTokensOptions,
+  OffsetPaginatedResponse,
+  UpdateAuthToken,
 } from '../types/index.js';
 import { BaseResource } from './base.js';
 
@@ -15,11 +20,25 @@ import { BaseResource } from './base.js';
  */
 export class TokensResource extends BaseResource {
   /**
-   * List all auth tokens (masked)
+   * List auth tokens with pagination (masked), newest first
    */
-  async list(): Promise<AuthToken[]> {
-    const data = await this.http.get('api/tokens').json();
-    return this.validate(data, authTokenSchema.array());
+  async list(
+    options?: ListTokensOptions,
+  ): Promise<OffsetPaginatedResponse<AuthToken>> {
+    const searchParams = new URLSearchParams();
+
+    if (options?.page !== undefined) {
+      searchParams.set('page', options.page.toString());
+    }
+    if (options?.per_page !== undefined) {
+      searchParams.set('per_page', options.per_page.toString());
+    }
+
+    const query = searchParams.toString();
+    const url = query ? `api/tokens?${query}` : 'api/tokens';
+
+    const data = await this.http.get(url).json();
+    return this.validate(data, offsetPaginatedResponseSchema(authTokenSchema));
   }
 
   /**
@@ -45,6 +64,20 @@ export class TokensResource extends BaseResource {
     return this.validate(data, authTokenCreatedSchema);
   }
 
+  /**
+   * Update an auth token's description
+   */
+  async update(id: number, input: UpdateAuthToken): Promise<AuthToken> {
+    // Validate input
+    const validatedInput = this.validate(input, updateAuthTokenSchema);
+
+    const data = await this.http
+      .patch(`api/tokens/${id}`, { json: validatedInput })
+      .json();
+
+    return this.validate(data, authTokenSchema);
+  }
+
   /**
    * Delete an auth token
    */
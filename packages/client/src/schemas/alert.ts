@@ -38,6 +38,35 @@ export const notificationChannelSchema = z.object({
   updated_at: dateTimeSchema,
 });
 
+/**
+ * Notification channel delivery health schema
+ */
+export const channelHealthSchema = z.object({
+  id: z.number().int(),
+  name: z.string(),
+  channel_type: channelTypeSchema,
+  is_enabled: z.boolean(),
+  failure_streak: z.number().int(),
+  last_success_at: dateTimeSchema.nullable(),
+  last_failure_at: dateTimeSchema.nullable(),
+  last_failure_message: z.string().nullable(),
+  auto_disabled_at: dateTimeSchema.nullable(),
+});
+
+/**
+ * Notification channel delivery stats schema
+ */
+export const channelStatsSchema = z.object({
+  channel_id: z.number().int(),
+  period: z.string(),
+  attempts: z.number().int(),
+  successes: z.number().int(),
+  failures: z.number().int(),
+  failure_count: z.number().int(),
+  last_success_at: dateTimeSchema.nullable(),
+  success_rate: z.number().nullable(),
+});
+
 /**
  * Create notification channel request schema
  */
@@ -68,6 +97,7 @@ export const alertRuleSchema = z.object({
   is_enabled: z.boolean(),
   conditions: z.record(z.string(), z.unknown()),
   cooldown_minutes: z.number().int(),
+  digest_minutes: z.number().int(),
   last_triggered_at: dateTimeSchema.nullable(),
   created_at: dateTimeSchema,
   updated_at: dateTimeSchema,
@@ -84,6 +114,7 @@ export const createAlertRuleSchema = z.object({
   is_enabled: z.boolean().optional(),
   conditions: z.record(z.string(), z.unknown()).optional(),
   cooldown_minutes: z.number().int().min(0).optional(),
+  digest_minutes: z.number().int().min(0).max(1440).optional(),
 });
 
 /**
@@ -94,6 +125,7 @@ export const updateAlertRuleSchema = z.object({
   is_enabled: z.boolean().optional(),
   conditions: z.record(z.string(), z.unknown()).optional(),
   cooldown_minutes: z.number().int().min(0).optional(),
+  digest_minutes: z.number().int().min(0).max(1440).optional(),
   channel_ids: z.array(z.number().int()).optional(),
 });
 
@@ -34,7 +34,17 @@ export const sortOrderSchema = z.enum(['asc', 'desc']);
 /**
  * Issue sort field enum
  */
-export const issueSortSchema = z.enum(['digest_order', 'last_seen']);
+export const issueSortSchema = z.enum(['digest_order', 'last_seen', 'priority']);
+
+/**
+ * Issue priority enum
+ */
+export const issuePrioritySchema = z.enum(['low', 'medium', 'high']);
+
+/**
+ * Event ordering enum: processing order or the event's own timestamp
+ */
+export const eventSortSchema = z.enum(['received', 'timestamp']);
 
 /**
  * Issue filter enum
@@ -13,7 +13,13 @@ export const eventSchema = z.object({
   level: z.string(),
   platform: z.string(),
   release: z.string(),
+  /** Distribution (build) of the release; empty if unset */
+  dist: z.string(),
   environment: z.string(),
+  /** Whether the SDK reported the error as unhandled */
+  is_unhandled: z.boolean(),
+  /** Whether the raw payload was dropped by payload retention */
+  payload_pruned: z.boolean(),
 });
 
 /**
@@ -29,9 +35,16 @@ export const eventDetailSchema = z.object({
   level: z.string(),
   platform: z.string(),
   release: z.string(),
+  /** Distribution (build) of the release; empty if unset */
+  dist: z.string(),
   environment: z.string(),
+  /** Whether the SDK reported the error as unhandled */
+  is_unhandled: z.boolean(),
   server_name: z.string(),
   sdk_name: z.string(),
   sdk_version: z.string(),
-  data: z.record(z.string(), z.any()), // Full Sentry event JSON
+  /** Whether the raw payload was dropped by payload retention */
+  payload_pruned: z.boolean(),
+  /** Full Sentry event JSON; null once the payload was pruned */
+  data: z.record(z.string(), z.any()).nullable(),
 });
@@ -1,5 +1,37 @@
 import { z } from 'zod';
-import { dateTimeSchema, uuidSchema } from './common.js';
+import { dateTimeSchema, issuePrioritySchema, uuidSchema } from './common.js';
+import { eventDetailSchema } from './event.js';
+
+/**
+ * A user who has opened an issue
+ */
+export const issueSeenBySchema = z.object({
+  user_id: z.number().int(),
+  email: z.string(),
+  seen_at: dateTimeSchema,
+});
+
+/**
+ * A grouping key routing events to an issue
+ */
+export const issueGroupingSchema = z.object({
+  grouping_key: z.string(),
+  components: z.array(z.string()),
+  grouping_key_hash: z.string(),
+  created_at: dateTimeSchema,
+});
+
+/**
+ * A change of an issue's priority
+ */
+export const issuePriorityChangeSchema = z.object({
+  old_priority: issuePrioritySchema,
+  new_priority: issuePrioritySchema,
+  /** Set by a user rather than raised on an event spike */
+  is_manual: z.boolean(),
+  changed_by: z.number().int().nullable(),
+  changed_at: dateTimeSchema,
+});
 
 /**
  * Issue response schema from API
@@ -10,13 +42,140 @@ export const issueSchema = z.object({
   short_id: z.string(),
   title: z.string(),
   value: z.string(),
+  culprit: z.string().nullable(),
   first_seen: dateTimeSchema,
   last_seen: dateTimeSchema,
+  /** Events digested into the issue, including those sampled out */
   event_count: z.number().int(),
+  /** Events of the issue that are stored */
+  stored_event_count: z.number().int(),
+  /** Latest stored event; null when none is stored */
+  last_event_id: uuidSchema.nullable(),
+  /** Share of new events that are stored; null stores them all */
+  sample_rate: z.number().nullable(),
   level: z.string().nullable(),
   platform: z.string().nullable(),
   is_resolved: z.boolean(),
   is_muted: z.boolean(),
+  /** Resolved after going quiet for the project's `auto_resolve_days` */
+  auto_resolved: z.boolean(),
+  /** Commit that fixed the issue, as given when resolving it */
+  resolved_commit: z.string().nullable(),
+  /** Pull request that fixed the issue, as given when resolving it */
+  resolved_pr_url: z.string().nullable(),
+  /** Times the issue was reopened after being auto-resolved */
+  regression_count: z.number().int(),
+  /** When the issue was reopened, most recent first; only returned by `get` */
+  regressed_at: z.array(dateTimeSchema).optional(),
+  priority: issuePrioritySchema,
+  /** Set by a user, after which it's never changed automatically */
+  priority_is_manual: z.boolean(),
+  /** Priority changes, most recent first; only returned by `get` */
+  priority_history: z.array(issuePriorityChangeSchema).optional(),
+  /** Whether any event of the issue was unhandled, i.e. a crash */
+  is_unhandled: z.boolean(),
+  is_seen: z.boolean(),
+  seen_by: z.array(issueSeenBySchema).optional(),
+  /** Only returned by `get` with `include: ['grouping']` */
+  groupings: z.array(issueGroupingSchema).optional(),
+  /** Only returned by `get` with `include: ['latest_event']` */
+  latest_event: eventDetailSchema.optional(),
+  deleted_at: dateTimeSchema.optional(),
+});
+
+/**
+ * Count of an issue's events seen with one context value
+ */
+export const issueContextValueSchema = z.object({
+  value: z.string(),
+  count: z.number().int(),
+  percentage: z.number(),
+});
+
+/**
+ * Top values of one context dimension
+ */
+export const issueContextBreakdownSchema = z.object({
+  total: z.number().int(),
+  values: z.array(issueContextValueSchema),
+});
+
+/**
+ * An issue and its latest events as one self-contained document
+ */
+export const issueBundleSchema = z.object({
+  exported_at: dateTimeSchema,
+  issue: issueSchema,
+  events: z.array(eventDetailSchema),
+});
+
+/**
+ * OS, browser, runtime and dist breakdowns of an issue's events
+ */
+export const issueContextsSchema = z.object({
+  os: issueContextBreakdownSchema,
+  browser: issueContextBreakdownSchema,
+  runtime: issueContextBreakdownSchema,
+  dist: issueContextBreakdownSchema,
+});
+
+/**
+ * Number of an issue's events at one level
+ */
+export const issueLevelCountSchema = z.object({
+  level: z.string(),
+  count: z.number().int(),
+});
+
+/**
+ * An issue's events broken down by level, most frequent first
+ */
+export const issueLevelBreakdownSchema = z.object({
+  total: z.number().int(),
+  levels: z.array(issueLevelCountSchema),
+});
+
+/**
+ * Number of an issue's events in one transaction
+ */
+export const issueTransactionCountSchema = z.object({
+  transaction: z.string(),
+  count: z.number().int(),
+});
+
+/**
+ * Transactions an issue's events occurred in, most affected first
+ */
+export const issueTransactionBreakdownSchema = z.object({
+  total: z.number().int(),
+  transactions: z.array(issueTransactionCountSchema),
+});
+
+/**
+ * Issues sharing a type or transaction, with their events in a period
+ */
+export const issueAggregateSchema = z.object({
+  value: z.string(),
+  issue_count: z.number().int(),
+  event_count: z.number().int(),
+  latest_issue_id: z.string().uuid(),
+});
+
+/**
+ * Issue groups with the most events in a period
+ */
+export const issueAggregateResponseSchema = z.object({
+  group_by: z.enum(['calculated_type', 'transaction']),
+  period: z.string(),
+  groups: z.array(issueAggregateSchema),
+});
+
+/**
+ * Resolve issue request schema: the change that fixed it
+ */
+export const resolveIssueSchema = z.object({
+  commit: z.string().max(100).optional(),
+  pr_url: z.string().url().max(2048).optional(),
 });
 
 /**
@@ -25,4 +184,8 @@ export const issueSchema = z.object({
 export const updateIssueStateSchema = z.object({
   is_resolved: z.boolean().optional(),
   is_muted: z.boolean().optional(),
+  /** Share of new events to store; 1 stores them all again */
+  sample_rate: z.number().gt(0).lte(1).optional(),
+  /** Sets the priority, which then stays as set */
+  priority: issuePrioritySchema.optional(),
 });
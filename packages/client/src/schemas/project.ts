@@ -1,5 +1,47 @@
 import { z } from 'zod';
-import { dateTimeSchema, uuidSchema } from './common.js';
+import { dateTimeSchema, issueSortSchema, uuidSchema } from './common.js';
+
+/**
+ * Project key response schema (key masked)
+ */
+export const projectKeySchema = z.object({
+  id: z.number().int(),
+  label: z.string(),
+  key_prefix: z.string(),
+  is_active: z.boolean(),
+  created_at: dateTimeSchema,
+  last_used_at: dateTimeSchema.nullable(),
+});
+
+/**
+ * Project key created response schema (full key and DSN shown once)
+ */
+export const projectKeyCreatedSchema = z.object({
+  id: z.number().int(),
+  label: z.string(),
+  key: uuidSchema,
+  dsn: z.string(),
+  is_active: z.boolean(),
+  created_at: dateTimeSchema,
+});
+
+/**
+ * Project activity summary schema, only included with `include: ['stats']`
+ */
+export const projectStatsSchema = z.object({
+  open_issue_count: z.number().int(),
+  events_24h: z.number().int(),
+});
+
+/**
+ * Project on the dashboard overview, with its activity
+ */
+export const projectOverviewSchema = z.object({
+  id: z.number().int(),
+  name: z.string(),
+  slug: z.string(),
+  stats: projectStatsSchema,
+});
 
 /**
  * Project response schema from API
@@ -8,12 +50,81 @@ export const projectSchema = z.object({
   id: z.number().int(),
   name: z.string(),
   slug: z.string(),
-  sentry_key: uuidSchema,
-  dsn: z.string(),
+  keys: z.array(projectKeySchema),
+  /** DSN of the default key, only returned when the project is created */
+  dsn: z.string().optional(),
   stored_event_count: z.number().int(),
   digested_event_count: z.number().int(),
+  /** Origins browser events are accepted from; empty accepts any origin */
+  allowed_domains: z.array(z.string()),
+  /** Days without events after which open issues are resolved */
+  auto_resolve_days: z.number().int().nullable(),
+  /** Module prefixes whose frames are always in-app */
+  in_app_include: z.array(z.string()),
+  /** Module prefixes whose frames are never in-app, unless included */
+  in_app_exclude: z.array(z.string()),
+  /** Issue list order when a request doesn't pass `sort` */
+  default_issue_sort: issueSortSchema,
+  /** IANA timezone for daily event counts; `null` means UTC */
+  timezone: z.string().nullable(),
   created_at: dateTimeSchema,
   updated_at: dateTimeSchema,
+  /** Issue and event counts, only included when listing with `include: ['stats']` */
+  stats: projectStatsSchema.optional(),
+});
+
+/**
+ * Project onboarding state schema
+ */
+export const projectOnboardingSchema = z.object({
+  received_first_event: z.boolean(),
+  first_event_at: dateTimeSchema.nullable(),
+  sdk: z
+    .object({
+      name: z.string(),
+      version: z.string(),
+    })
+    .nullable(),
+});
+
+/**
+ * Events per second over the last 10 and 60 seconds
+ */
+export const rateWindowsSchema = z.object({
+  last_10s: z.number(),
+  last_60s: z.number(),
+});
+
+/**
+ * Live event rates of a project, as measured by one server instance
+ */
+export const projectLiveStatsSchema = z.object({
+  project_id: z.number().int(),
+  /** Instance that measured the rates; other replicas aren't included */
+  instance_id: z.string(),
+  /** Events accepted by the ingest endpoints */
+  accepted: rateWindowsSchema,
+  /** Events digested into issues */
+  digested: rateWindowsSchema,
+});
+
+/**
+ * Events digested on one local day
+ */
+export const dailyEventCountSchema = z.object({
+  /** Local date, e.g. `2026-02-19` */
+  date: z.string(),
+  events: z.number().int(),
+});
+
+/**
+ * Daily event counts of a project
+ */
+export const projectDailyStatsSchema = z.object({
+  /** Timezone the days are bucketed in */
+  timezone: z.string(),
+  /** One entry per day, oldest first, including days without events */
+  days: z.array(dailyEventCountSchema),
 });
 
 /**
@@ -29,4 +140,57 @@ export const createProjectSchema = z.object({
  */
 export const updateProjectSchema = z.object({
   name: z.string().min(1).optional(),
+  /** Replaces the allowed domains, e.g. `*.example.com` */
+  allowed_domains: z.array(z.string()).optional(),
+  /** Required to accept a pattern matching every origin, like `*` */
+  confirm_wildcard: z.boolean().optional(),
+  /** Days without events after which open issues are resolved; 0 turns it off */
+  auto_resolve_days: z.number().int().min(0).optional(),
+  /** Replaces the module prefixes whose frames are always in-app */
+  in_app_include: z.array(z.string()).max(50).optional(),
+  /** Replaces the module prefixes whose frames are never in-app */
+  in_app_exclude: z.array(z.string()).max(50).optional(),
+  /** Issue list order when a request doesn't pass `sort` */
+  default_issue_sort: issueSortSchema.optional(),
+  /** IANA timezone like `Europe/Madrid`; an empty string resets it to UTC */
+  timezone: z.string().optional(),
+});
+
+/**
+ * Create project key request schema
+ */
+export const createProjectKeySchema = z.object({
+  label: z.string().optional(),
+});
+
+/**
+ * Update project key request schema
+ */
+export const updateProjectKeySchema = z.object({
+  is_active: z.boolean(),
+});
+
+/**
+ * Role of a user in a project; admins can also manage its members
+ */
+export const projectRoleSchema = z.enum(['admin', 'member']);
+
+/**
+ * Project member response schema
+ */
+export const projectMemberSchema = z.object({
+  project_id: z.number().int(),
+  user_id: z.number().int(),
+  email: z.string(),
+  role: projectRoleSchema,
+  created_at: dateTimeSchema,
+});
+
+/**
+ * Add project member request schema
+ */
+export const addProjectMemberSchema = z.object({
+  user_id: z.number().int(),
+  /** Defaults to `member` */
+  role: projectRoleSchema.optional(),
 });
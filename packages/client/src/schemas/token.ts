@@ -28,3 +28,10 @@ export const authTokenCreatedSchema = z.object({
 export const createAuthTokenSchema = z.object({
   description: z.string().optional(),
 });
+
+/**
+ * Update auth token request schema (an empty description clears it)
+ */
+export const updateAuthTokenSchema = z.object({
+  description: z.string().max(255).optional(),
+});
@@ -1,4 +1,6 @@
 import { z } from 'zod';
+import { issueSchema } from './issue.js';
+import { projectOverviewSchema } from './project.js';
 
 /**
  * User schema - authenticated user information
@@ -39,3 +41,23 @@ export const registerRequestSchema = z.object({
   email: z.string().email(),
   password: z.string().min(8),
 });
+
+/**
+ * Login methods schema - which ways to log in the server offers
+ */
+export const loginMethodsSchema = z.object({
+  /** Email and password login and registration */
+  password: z.boolean(),
+  /** Single sign-on through the OpenID Connect provider at `auth/oidc/login` */
+  oidc: z.boolean(),
+});
+
+/**
+ * Dashboard overview schema
+ */
+export const userOverviewSchema = z.object({
+  /** Most recently seen open issues of the projects the caller can see */
+  issues: z.array(issueSchema),
+  /** Projects the user is a member of; not included for API tokens */
+  projects: z.array(projectOverviewSchema).optional(),
+});
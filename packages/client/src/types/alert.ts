@@ -4,6 +4,8 @@ import type {
   alertRuleSchema,
   alertStatusSchema,
   alertTypeSchema,
+  channelHealthSchema,
+  channelStatsSchema,
   channelTypeSchema,
   createAlertRuleSchema,
   createNotificationChannelSchema,
@@ -33,6 +35,16 @@ export type AlertStatus = z.infer<typeof alertStatusSchema>;
  */
 export type NotificationChannel = z.infer<typeof notificationChannelSchema>;
 
+/**
+ * Notification channel delivery health
+ */
+export type ChannelHealth = z.infer<typeof channelHealthSchema>;
+
+/**
+ * Notification channel delivery stats over a period
+ */
+export type ChannelStats = z.infer<typeof channelStatsSchema>;
+
 /**
  * Create notification channel request
  */
@@ -72,9 +84,32 @@ export type AlertHistory = z.infer<typeof alertHistorySchema>;
  */
 export type TestChannelResponse = z.infer<typeof testChannelResponseSchema>;
 
+/**
+ * Delete notification channel options
+ */
+export interface DeleteChannelOptions {
+  /**
+   * Detach the channel from the alert rules using it instead of failing
+   */
+  force?: boolean;
+}
+
+/**
+ * Channel stats options
+ */
+export interface ChannelStatsOptions {
+  /**
+   * Window deliveries are counted in, e.g. `24h` or `7d` (default: 24h)
+   */
+  period?: string;
+}
+
 /**
  * List alert history options
  */
 export interface ListAlertHistoryOptions {
-  limit?: number;
+  page?: number;
+  per_page?: number;
+  status?: AlertStatus;
+  channel_id?: number;
 }
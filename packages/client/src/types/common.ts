@@ -1,7 +1,9 @@
 import type { z } from 'zod';
 import type {
   apiErrorSchema,
+  eventSortSchema,
   issueFilterSchema,
+  issuePrioritySchema,
   issueSortSchema,
   sortOrderSchema,
 } from '../schemas/common.js';
@@ -36,6 +38,16 @@ export type SortOrder = z.infer<typeof sortOrderSchema>;
  */
 export type IssueSort = z.infer<typeof issueSortSchema>;
 
+/**
+ * How much attention an issue needs
+ */
+export type IssuePriority = z.infer<typeof issuePrioritySchema>;
+
+/**
+ * Ordering for event queries
+ */
+export type EventSort = z.infer<typeof eventSortSchema>;
+
 /**
  * Filter for issue queries
  */
@@ -55,6 +67,31 @@ export interface ListIssuesOptions {
   sort?: IssueSort;
   order?: SortOrder;
   filter?: IssueFilter;
+  /** Only issues with events of this distribution */
+  dist?: string;
+  /** `false` for crashes only, `true` for issues that were always handled */
+  handled?: boolean;
+  /** Only issues of this priority */
+  priority?: IssuePriority;
+}
+
+/**
+ * Options for aggregating issues
+ */
+export interface AggregateIssuesOptions {
+  /** Hours or days to count events over, e.g. `24h` (default: `7d`) */
+  period?: string;
+  filter?: IssueFilter;
+  /** Only issues with events of this distribution */
+  dist?: string;
+}
+
+/**
+ * List options for the issue trash (offset-based pagination)
+ */
+export interface ListTrashOptions {
+  page?: number;
+  per_page?: number;
 }
 
 /**
@@ -62,7 +99,21 @@ export interface ListIssuesOptions {
  */
 export interface ListEventsOptions {
   order?: SortOrder;
+  /** Order by processing order (`received`, default) or event `timestamp` */
+  by?: EventSort;
   cursor?: string;
+  /** Events per page; defaults to the server's `EVENTS_PAGE_SIZE` */
+  per_page?: number;
+  /** Only events of this distribution */
+  dist?: string;
+}
+
+/**
+ * List options for tokens endpoint (offset-based pagination)
+ */
+export interface ListTokensOptions {
+  page?: number;
+  per_page?: number;
 }
 
 /**
@@ -72,4 +123,6 @@ export interface ListProjectsOptions {
   page?: number;
   per_page?: number;
   order?: SortOrder;
+  /** Extra fields to include; `stats` adds each project's issue and event counts */
+  include?: 'stats'[];
 }
@@ -1,11 +1,97 @@
 import type { z } from 'zod';
-import type { issueSchema, updateIssueStateSchema } from '../schemas/issue.js';
+import type {
+  issueAggregateResponseSchema,
+  issueAggregateSchema,
+  issueBundleSchema,
+  issueContextsSchema,
+  issueGroupingSchema,
+  issueLevelBreakdownSchema,
+  issuePriorityChangeSchema,
+  issueSchema,
+  issueSeenBySchema,
+  issueTransactionBreakdownSchema,
+  resolveIssueSchema,
+  updateIssueStateSchema,
+} from '../schemas/issue.js';
 
 /**
  * Issue resource from the API
  */
 export type Issue = z.infer<typeof issueSchema>;
 
+/**
+ * A user who has opened an issue
+ */
+export type IssueSeenBy = z.infer<typeof issueSeenBySchema>;
+
+/**
+ * A change of an issue's priority
+ */
+export type IssuePriorityChange = z.infer<typeof issuePriorityChangeSchema>;
+
+/**
+ * A grouping key routing events to an issue
+ */
+export type IssueGrouping = z.infer<typeof issueGroupingSchema>;
+
+/**
+ * Options for fetching a single issue
+ */
+export interface GetIssueOptions {
+  /**
+   * Extra fields to include; `grouping` adds the issue's grouping keys,
+   * `latest_event` its latest stored event
+   */
+  include?: ('grouping' | 'latest_event')[];
+}
+
+/**
+ * An issue and its latest events, for download
+ */
+export type IssueBundle = z.infer<typeof issueBundleSchema>;
+
+/**
+ * Options for downloading an issue bundle
+ */
+export interface GetIssueBundleOptions {
+  /** Events to embed, newest first (default 10, max 50) */
+  events?: number;
+}
+
+/**
+ * OS, browser, runtime and dist breakdowns of an issue's events
+ */
+export type IssueContexts = z.infer<typeof issueContextsSchema>;
+
+/**
+ * An issue's events broken down by level
+ */
+export type IssueLevelBreakdown = z.infer<typeof issueLevelBreakdownSchema>;
+
+/**
+ * An issue's events broken down by transaction
+ */
+export type IssueTransactionBreakdown = z.infer<
+  typeof issueTransactionBreakdownSchema
+>;
+
+/**
+ * Issues sharing a type or transaction, with their events in a period
+ */
+export type IssueAggregate = z.infer<typeof issueAggregateSchema>;
+
+/**
+ * Issue groups with the most events in a period
+ */
+export type IssueAggregateResponse = z.infer<
+  typeof issueAggregateResponseSchema
+>;
+
+/**
+ * Request payload for resolving an issue with the change that fixed it
+ */
+export type ResolveIssue = z.infer<typeof resolveIssueSchema>;
+
 /**
  * Request payload for updating issue state
  */
@@ -1,7 +1,20 @@
 import type { z } from 'zod';
 import type {
+  addProjectMemberSchema,
+  createProjectKeySchema,
   createProjectSchema,
+  projectKeyCreatedSchema,
+  projectDailyStatsSchema,
+  projectKeySchema,
+  projectLiveStatsSchema,
+  projectMemberSchema,
+  projectOnboardingSchema,
+  projectOverviewSchema,
+  projectRoleSchema,
   projectSchema,
+  projectStatsSchema,
+  updateProjectKeySchema,
+  rateWindowsSchema,
   updateProjectSchema,
 } from '../schemas/project.js';
 
@@ -10,6 +23,54 @@ import type {
  */
 export type Project = z.infer<typeof projectSchema>;
 
+/**
+ * Open issues and events of the last 24 hours of a project
+ */
+export type ProjectStats = z.infer<typeof projectStatsSchema>;
+
+/**
+ * Project on the dashboard overview, with its open issues and recent events
+ */
+export type ProjectOverview = z.infer<typeof projectOverviewSchema>;
+
+/**
+ * Whether a project has received its first event
+ */
+export type ProjectOnboarding = z.infer<typeof projectOnboardingSchema>;
+
+/**
+ * Events per second over the last 10 and 60 seconds
+ */
+export type RateWindows = z.infer<typeof rateWindowsSchema>;
+
+/**
+ * Events per second a project is sending right now
+ */
+export type ProjectLiveStats = z.infer<typeof projectLiveStatsSchema>;
+
+/**
+ * Events per local day of a project
+ */
+export type ProjectDailyStats = z.infer<typeof projectDailyStatsSchema>;
+
+/**
+ * Options for fetching a project's daily event counts
+ */
+export interface GetDailyStatsOptions {
+  /** Number of days, counting today (1 to 90, default 14) */
+  days?: number;
+  /** IANA timezone overriding the project's own */
+  tz?: string;
+}
+
+/**
+ * Options for fetching a project's onboarding state
+ */
+export interface GetOnboardingOptions {
+  /** Seconds to wait for the first event (at most 60) */
+  wait?: number;
+}
+
 /**
  * Request payload for creating a project
  */
@@ -19,3 +80,38 @@ export type CreateProject = z.infer<typeof createProjectSchema>;
  * Request payload for updating a project
  */
 export type UpdateProject = z.infer<typeof updateProjectSchema>;
+
+/**
+ * Project key resource (key masked)
+ */
+export type ProjectKey = z.infer<typeof projectKeySchema>;
+
+/**
+ * Project key resource when created (full key and DSN shown once)
+ */
+export type ProjectKeyCreated = z.infer<typeof projectKeyCreatedSchema>;
+
+/**
+ * Request payload for creating a project key
+ */
+export type CreateProjectKey = z.infer<typeof createProjectKeySchema>;
+
+/**
+ * Request payload for activating or deactivating a project key
+ */
+export type UpdateProjectKey = z.infer<typeof updateProjectKeySchema>;
+
+/**
+ * Role of a user in a project
+ */
+export type ProjectRole = z.infer<typeof projectRoleSchema>;
+
+/**
+ * A user who can see a project
+ */
+export type ProjectMember = z.infer<typeof projectMemberSchema>;
+
+/**
+ * Request payload for adding a user to a project
+ */
+export type AddProjectMember = z.infer<typeof addProjectMemberSchema>;
@@ -3,6 +3,7 @@ import type {
   authTokenCreatedSchema,
   authTokenSchema,
   createAuthTokenSchema,
+  updateAuthTokenSchema,
 } from '../schemas/token.js';
 
 /**
@@ -19,3 +20,8 @@ export type AuthTokenCreated = z.infer<typeof authTokenCreatedSchema>;
  * Request payload for creating an auth token
  */
 export type CreateAuthToken = z.infer<typeof createAuthTokenSchema>;
+
+/**
+ * Request payload for updating an auth token
+ */
+export type UpdateAuthToken = z.infer<typeof updateAuthTokenSchema>;
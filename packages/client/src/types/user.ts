@@ -1,9 +1,11 @@
 import type { z } from 'zod';
 import type {
   authResponseSchema,
+  loginMethodsSchema,
   loginRequestSchema,
   loginResultSchema,
   registerRequestSchema,
+  userOverviewSchema,
   userSchema,
 } from '../schemas/user.js';
 
@@ -32,3 +34,13 @@ export type LoginRequest = z.infer<typeof loginRequestSchema>;
  * RegisterRequest - data needed to create a new user account
  */
 export type RegisterRequest = z.infer<typeof registerRequestSchema>;
+
+/**
+ * LoginMethods - ways to log in offered by the server
+ */
+export type LoginMethods = z.infer<typeof loginMethodsSchema>;
+
+/**
+ * UserOverview - dashboard home page of the caller
+ */
+export type UserOverview = z.infer<typeof userOverviewSchema>;
@@ -4,6 +4,7 @@ import {
   AuthenticationError,
   AuthorizationError,
   BadRequestError,
+  type ErrorDetails,
   NetworkError,
   NotFoundError,
   RateLimitError,
@@ -18,14 +19,34 @@ async function transformHttpError(error: HTTPError): Promise<RustrakError> {
   const { response } = error;
   const status = response.status;
 
-  // Try to extract error message from response body
+  // Extract message and details from `{ error: { code, message, field } }`,
+  // falling back to the older `{ error: "message" }` shape
   let errorMessage = `HTTP ${status} error`;
+  const details: ErrorDetails = {
+    requestId: response.headers.get('X-Request-Id') ?? undefined,
+  };
   try {
     const body = (await response.json()) as {
-      error?: string;
+      error?:
+        | string
+        | {
+            code?: string;
+            message?: string;
+            field?: string;
+            dependents?: string[];
+            request_id?: string;
+          };
       message?: string;
     };
-    errorMessage = body.error || body.message || errorMessage;
+    if (body.error && typeof body.error === 'object') {
+      errorMessage = body.error.message || errorMessage;
+      details.code = body.error.code;
+      details.field = body.error.field;
+      details.dependents = body.error.dependents;
+      details.requestId = body.error.request_id ?? details.requestId;
+    } else {
+      errorMessage = body.error || body.message || errorMessage;
+    }
   } catch {
     // Unable to parse body, use default message
   }
@@ -33,24 +54,24 @@ async function transformHttpError(error: HTTPError): Promise<RustrakError> {
   // Map status codes to specific error types
   switch (status) {
     case 400:
-      return new BadRequestError(errorMessage);
+      return new BadRequestError(errorMessage, details);
     case 401:
-      return new AuthenticationError(errorMessage);
+      return new AuthenticationError(errorMessage, details);
     case 403:
-      return new AuthorizationError(errorMessage);
+      return new AuthorizationError(errorMessage, details);
     case 404:
-      return new NotFoundError(errorMessage);
+      return new NotFoundError(errorMessage, details);
     case 429: {
       const retryAfter = response.headers.get('Retry-After');
-      return new RateLimitError(errorMessage, retryAfter ?? undefined);
+      return new RateLimitError(errorMessage, retryAfter ?? undefined, details);
     }
     case 500:
     case 502:
     case 503:
     case 504:
-      return new ServerError(errorMessage, status);
+      return new ServerError(errorMessage, status, details);
     default:
-      return new RustrakError(errorMessage, { statusCode: status });
+      return new RustrakError(errorMessage, { statusCode: status, ...details });
   }
 }
 
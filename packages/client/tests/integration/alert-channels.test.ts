@@ -1,7 +1,11 @@
 import { HttpResponse, http } from 'msw';
 import { beforeEach, describe, expect, it } from 'vitest';
 import { RustrakClient } from '../../src/client.js';
-import { NotFoundError, ValidationError } from '../../src/errors/index.js';
+import {
+  NotFoundError,
+  RustrakError,
+  ValidationError,
+} from '../../src/errors/index.js';
 import { server } from '../setup.js';
 
 describe('AlertChannelsResource Integration', () => {
@@ -221,8 +225,19 @@ describe('AlertChannelsResource Integration', () => {
   });
 
   describe('delete()', () => {
-    it('should delete channel successfully', async () => {
-      await expect(client.alertChannels.delete(1)).resolves.toBeUndefined();
+    it('should refuse to delete a channel used by alert rules', async () => {
+      const error = await client.alertChannels.delete(2).catch((e) => e);
+
+      expect(error).toBeInstanceOf(RustrakError);
+      expect(error.statusCode).toBe(409);
+      expect(error.code).toBe('conflict.in_use');
+      expect(error.dependents).toEqual(['New Issue Alert']);
+    });
+
+    it('should delete channel with force', async () => {
+      await expect(
+        client.alertChannels.delete(2, { force: true }),
+      ).resolves.toBeUndefined();
     });
 
     it('should throw NotFoundError for non-existent channel', async () => {
@@ -232,6 +247,53 @@ describe('AlertChannelsResource Integration', () => {
     });
   });
 
+  describe('health()', () => {
+    it('should return the health of every channel', async () => {
+      const health = await client.alertChannels.health();
+
+      expect(health).toHaveLength(2);
+      expect(health[0].failure_streak).toBe(0);
+      expect(health[0].auto_disabled_at).toBeNull();
+    });
+  });
+
+  describe('stats()', () => {
+    it('should return the delivery stats of a channel', async () => {
+      const stats = await client.alertChannels.stats(1);
+
+      expect(stats.channel_id).toBe(1);
+      expect(stats.period).toBe('24h');
+      expect(stats.success_rate).toBe(0.8);
+    });
+
+    it('should pass the period', async () => {
+      const stats = await client.alertChannels.stats(1, { period: '7d' });
+
+      expect(stats.period).toBe('7d');
+    });
+
+    it('should throw NotFoundError for non-existent channel', async () => {
+      await expect(client.alertChannels.stats(999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
+
+  describe('resetFailures()', () => {
+    it('should clear the streak and enable the channel', async () => {
+      const channel = await client.alertChannels.resetFailures(1);
+
+      expect(channel.is_enabled).toBe(true);
+      expect(channel.failure_count).toBe(0);
+    });
+
+    it('should throw NotFoundError for non-existent channel', async () => {
+      await expect(client.alertChannels.resetFailures(999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
+
   describe('test()', () => {
     it('should send test notification successfully', async () => {
       const result = await client.alertChannels.test(1);
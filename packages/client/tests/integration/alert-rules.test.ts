@@ -246,6 +246,14 @@ describe('AlertRulesResource Integration', () => {
       expect(updated.cooldown_minutes).toBe(120);
     });
 
+    it('should update digest window', async () => {
+      const updated = await client.alertRules.update(projectId, 1, {
+        digest_minutes: 30,
+      });
+
+      expect(updated.digest_minutes).toBe(30);
+    });
+
     it('should update conditions', async () => {
       const updated = await client.alertRules.update(projectId, 1, {
         conditions: { min_events: 10 },
@@ -290,27 +298,35 @@ describe('AlertRulesResource Integration', () => {
     it('should fetch alert history for a project', async () => {
       const history = await client.alertRules.listHistory(projectId);
 
-      expect(history).toHaveLength(2);
-      expect(history[0]?.alert_type).toBe('new_issue');
-      expect(history[0]?.status).toBe('sent');
-      expect(history[1]?.status).toBe('failed');
+      expect(history.items).toHaveLength(2);
+      expect(history.total_count).toBe(2);
+      expect(history.page).toBe(1);
+      expect(history.items[0]?.alert_type).toBe('new_issue');
+      expect(history.items[0]?.status).toBe('sent');
+      expect(history.items[1]?.status).toBe('failed');
     });
 
     it('should validate response schema', async () => {
       server.use(
         http.get('http://localhost:8080/api/projects/1/alert-history', () => {
-          return HttpResponse.json([
-            {
-              id: 1,
-              alert_type: 'new_issue',
-              channel_type: 'webhook',
-              channel_name: 'Test',
-              status: 'invalid_status', // Invalid status
-              attempt_count: 1,
-              idempotency_key: 'key-1',
-              created_at: '2026-01-20T10:00:00.000Z',
-            },
-          ]);
+          return HttpResponse.json({
+            items: [
+              {
+                id: 1,
+                alert_type: 'new_issue',
+                channel_type: 'webhook',
+                channel_name: 'Test',
+                status: 'invalid_status', // Invalid status
+                attempt_count: 1,
+                idempotency_key: 'key-1',
+                created_at: '2026-01-20T10:00:00.000Z',
+              },
+            ],
+            total_count: 1,
+            page: 1,
+            per_page: 20,
+            total_pages: 1,
+          });
         }),
       );
 
@@ -322,25 +338,50 @@ describe('AlertRulesResource Integration', () => {
     it('should handle empty history', async () => {
       server.use(
         http.get('http://localhost:8080/api/projects/1/alert-history', () => {
-          return HttpResponse.json([]);
+          return HttpResponse.json({
+            items: [],
+            total_count: 0,
+            page: 1,
+            per_page: 20,
+            total_pages: 0,
+          });
         }),
       );
 
       const history = await client.alertRules.listHistory(projectId);
-      expect(history).toHaveLength(0);
+      expect(history.items).toHaveLength(0);
+      expect(history.total_count).toBe(0);
     });
 
-    it('should respect limit parameter', async () => {
+    it('should respect pagination parameters', async () => {
       const history = await client.alertRules.listHistory(projectId, {
-        limit: 1,
+        page: 2,
+        per_page: 1,
+      });
+
+      expect(history.items).toHaveLength(1);
+      expect(history.items[0]?.id).toBe(2);
+      expect(history.page).toBe(2);
+      expect(history.total_pages).toBe(2);
+    });
+
+    it('should filter by status and channel', async () => {
+      const failed = await client.alertRules.listHistory(projectId, {
+        status: 'failed',
       });
+      expect(failed.items).toHaveLength(1);
+      expect(failed.items[0]?.status).toBe('failed');
 
-      expect(history).toHaveLength(1);
+      const byChannel = await client.alertRules.listHistory(projectId, {
+        channel_id: 1,
+      });
+      expect(byChannel.items).toHaveLength(1);
+      expect(byChannel.items[0]?.channel_id).toBe(1);
     });
 
     it('should include all required fields', async () => {
       const history = await client.alertRules.listHistory(projectId);
-      const item = history[0];
+      const item = history.items[0];
 
       expect(item).toBeDefined();
       expect(item?.id).toBeDefined();
@@ -355,8 +396,8 @@ describe('AlertRulesResource Integration', () => {
 
     it('should include optional fields when present', async () => {
       const history = await client.alertRules.listHistory(projectId);
-      const sentItem = history.find((h) => h.status === 'sent');
-      const failedItem = history.find((h) => h.status === 'failed');
+      const sentItem = history.items.find((h) => h.status === 'sent');
+      const failedItem = history.items.find((h) => h.status === 'failed');
 
       expect(sentItem?.sent_at).toBeDefined();
       expect(sentItem?.http_status_code).toBe(200);
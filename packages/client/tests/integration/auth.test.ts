@@ -280,6 +280,14 @@ describe('AuthResource Integration', () => {
     });
   });
 
+  describe('getLoginMethods()', () => {
+    it('should get the enabled login methods', async () => {
+      const methods = await client.auth.getLoginMethods();
+
+      expect(methods).toEqual({ password: true, oidc: true });
+    });
+  });
+
   describe('Error Handling', () => {
     it('should handle malformed response from register', async () => {
       server.use(
@@ -529,4 +537,26 @@ describe('AuthResource Integration', () => {
       ).rejects.toThrow();
     });
   });
+
+  describe('getOverview()', () => {
+    it('should include projects for session users', async () => {
+      const overview = await client.auth.getOverview();
+
+      expect(overview.issues.length).toBeGreaterThan(0);
+      expect(overview.projects).toHaveLength(2);
+      expect(overview.projects?.[0].stats.open_issue_count).toBe(1);
+    });
+
+    it('should omit projects for API tokens', async () => {
+      const tokenClient = new RustrakClient({
+        baseUrl: 'http://localhost:8080',
+        token: 'a'.repeat(40),
+      });
+
+      const overview = await tokenClient.auth.getOverview();
+
+      expect(overview.issues.length).toBeGreaterThan(0);
+      expect(overview.projects).toBeUndefined();
+    });
+  });
 });
@@ -36,6 +36,52 @@ describe('Error Handling', () => {
       await expect(client.projects.list()).rejects.toThrow(BadRequestError);
     });
 
+    it('should expose code and field from structured error bodies', async () => {
+      server.use(
+        http.get('http://localhost:8080/api/projects', () => {
+          return HttpResponse.json(
+            {
+              error: {
+                code: 'validation.required',
+                message: 'Validation error: Project name cannot be empty',
+                field: 'name',
+              },
+            },
+            { status: 400 },
+          );
+        }),
+      );
+
+      const error = await client.projects.list().catch((e) => e);
+      expect(error).toBeInstanceOf(BadRequestError);
+      expect(error.message).toBe(
+        'Validation error: Project name cannot be empty',
+      );
+      expect(error.code).toBe('validation.required');
+      expect(error.field).toBe('name');
+    });
+
+    it('should expose the request id of failed requests', async () => {
+      server.use(
+        http.get('http://localhost:8080/api/projects', () => {
+          return HttpResponse.json(
+            {
+              error: {
+                code: 'internal',
+                message: 'Internal error',
+                request_id: 'req-42',
+              },
+            },
+            { status: 500, headers: { 'X-Request-Id': 'req-42' } },
+          );
+        }),
+      );
+
+      const error = await client.projects.list().catch((e) => e);
+      expect(error).toBeInstanceOf(ServerError);
+      expect(error.requestId).toBe('req-42');
+    });
+
     it('should throw AuthenticationError for 401', async () => {
       server.use(
         http.get('http://localhost:8080/api/projects', () => {
@@ -219,8 +265,7 @@ describe('Error Handling', () => {
                 id: 1,
                 name: 'Test',
                 slug: 'test',
-                sentry_key: 'invalid-uuid',
-                dsn: 'http://localhost:8080/1',
+                keys: 'invalid-keys',
                 stored_event_count: 0,
                 digested_event_count: 0,
                 created_at: 'invalid-date',
@@ -237,7 +282,7 @@ describe('Error Handling', () => {
 
       const error = await client.projects.list().catch((e) => e);
       expect(error).toBeInstanceOf(ValidationError);
-      expect(error.getValidationDetails()).toContain('sentry_key');
+      expect(error.getValidationDetails()).toContain('keys');
     });
   });
 
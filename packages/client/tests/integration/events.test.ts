@@ -1,6 +1,9 @@
+import { HttpResponse, http } from 'msw';
 import { beforeEach, describe, expect, it } from 'vitest';
 import { RustrakClient } from '../../src/client.js';
 import { NotFoundError } from '../../src/errors/index.js';
+import { mockEventDetail } from '../mocks/handlers.js';
+import { server } from '../setup.js';
 
 describe('EventsResource Integration', () => {
   let client: RustrakClient;
@@ -33,6 +36,27 @@ describe('EventsResource Integration', () => {
       expect(response.items).toBeDefined();
     });
 
+    it('should pass the ordering field to the server', async () => {
+      let requested: URLSearchParams | null = null;
+      server.use(
+        http.get(
+          'http://localhost:8080/api/projects/:projectId/issues/:issueId/events',
+          ({ request }) => {
+            requested = new URL(request.url).searchParams;
+            return HttpResponse.json({ items: [], has_more: false });
+          },
+        ),
+      );
+
+      await client.events.list(1, '323e4567-e89b-12d3-a456-426614174000', {
+        by: 'timestamp',
+        order: 'asc',
+      });
+
+      expect(requested?.get('by')).toBe('timestamp');
+      expect(requested?.get('order')).toBe('asc');
+    });
+
     it('should support cursor pagination', async () => {
       const response = await client.events.list(
         1,
@@ -99,7 +123,7 @@ describe('EventsResource Integration', () => {
       );
 
       expect(event.data).toHaveProperty('exception');
-      expect(event.data.exception).toHaveProperty('values');
+      expect(event.data?.exception).toHaveProperty('values');
     });
 
     it('should throw NotFoundError for non-existent event', async () => {
@@ -112,6 +136,29 @@ describe('EventsResource Integration', () => {
       ).rejects.toThrow(NotFoundError);
     });
 
+    it('should return the metadata of an event whose payload was pruned', async () => {
+      server.use(
+        http.get(
+          'http://localhost:8080/api/projects/:projectId/issues/:issueId/events/:eventId',
+          () =>
+            HttpResponse.json(
+              { ...mockEventDetail, payload_pruned: true, data: null },
+              { status: 410 },
+            ),
+        ),
+      );
+
+      const event = await client.events.get(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        '523e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(event.payload_pruned).toBe(true);
+      expect(event.data).toBeNull();
+      expect(event.title).toBe('TypeError: Cannot read property');
+    });
+
     it('should validate datetime formats', async () => {
       const event = await client.events.get(
         1,
@@ -123,4 +170,23 @@ describe('EventsResource Integration', () => {
       expect(new Date(event.ingested_at).toISOString()).toBe(event.ingested_at);
     });
   });
+
+  describe('getByEventId()', () => {
+    it('should find event and its issue by SDK event ID', async () => {
+      const event = await client.events.getByEventId(
+        1,
+        '623e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(event.id).toBe('523e4567-e89b-12d3-a456-426614174000');
+      expect(event.issue_id).toBe('323e4567-e89b-12d3-a456-426614174000');
+      expect(event.data).toBeDefined();
+    });
+
+    it('should throw NotFoundError for unknown event ID', async () => {
+      await expect(
+        client.events.getByEventId(1, '999e4567-e89b-12d3-a456-426614174000'),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
 });
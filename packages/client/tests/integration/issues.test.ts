@@ -1,6 +1,6 @@
 import { beforeEach, describe, expect, it } from 'vitest';
 import { RustrakClient } from '../../src/client.js';
-import { NotFoundError } from '../../src/errors/index.js';
+import { NotFoundError, ValidationError } from '../../src/errors/index.js';
 
 describe('IssuesResource Integration', () => {
   let client: RustrakClient;
@@ -56,6 +56,26 @@ describe('IssuesResource Integration', () => {
       expect(response.items).toBeDefined();
     });
 
+    it('should filter crashes with handled parameter', async () => {
+      const crashes = await client.issues.list(1, { handled: false });
+      const handled = await client.issues.list(1, { handled: true });
+
+      expect(crashes.items).toHaveLength(1);
+      expect(crashes.items[0].is_unhandled).toBe(true);
+      expect(handled.items).toHaveLength(1);
+      expect(handled.items[0].is_unhandled).toBe(false);
+    });
+
+    it('should filter by priority', async () => {
+      const response = await client.issues.list(1, {
+        priority: 'high',
+        sort: 'priority',
+      });
+
+      expect(response.items).toHaveLength(1);
+      expect(response.items[0].priority).toBe('high');
+    });
+
     it('should handle empty results', async () => {
       const response = await client.issues.list(1, { page: 99 });
 
@@ -91,6 +111,33 @@ describe('IssuesResource Integration', () => {
 
       expect(issue.id).toBe('323e4567-e89b-12d3-a456-426614174000');
       expect(issue.title).toBe('TypeError: Cannot read property');
+      expect(issue.culprit).toBe('src/components/Profile.tsx');
+    });
+
+    it('should include who has seen the issue', async () => {
+      const issue = await client.issues.get(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(issue.is_seen).toBe(true);
+      expect(issue.seen_by).toHaveLength(1);
+      expect(issue.seen_by?.[0].email).toBe('admin@example.com');
+      expect(issue.regression_count).toBe(1);
+      expect(issue.regressed_at).toHaveLength(1);
+      expect(issue.priority_history?.[0].new_priority).toBe('high');
+      expect(issue.priority_history?.[0].is_manual).toBe(false);
+    });
+
+    it('should include the latest event when asked', async () => {
+      const issue = await client.issues.get(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        { include: ['latest_event'] },
+      );
+
+      expect(issue.latest_event?.id).toBe(issue.last_event_id);
+      expect(issue.latest_event?.data).toHaveProperty('exception');
     });
 
     it('should throw NotFoundError for non-existent issue', async () => {
@@ -100,6 +147,143 @@ describe('IssuesResource Integration', () => {
     });
   });
 
+  describe('getBundle()', () => {
+    it('should fetch the issue with its latest events', async () => {
+      const bundle = await client.issues.getBundle(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(bundle.issue.id).toBe('323e4567-e89b-12d3-a456-426614174000');
+      expect(bundle.events).toHaveLength(1);
+      expect(bundle.events[0].data).toHaveProperty('exception');
+    });
+
+    it('should pass the event count', async () => {
+      const bundle = await client.issues.getBundle(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        { events: 0 },
+      );
+
+      expect(bundle.events).toHaveLength(0);
+    });
+
+    it('should throw NotFoundError for non-existent issue', async () => {
+      await expect(
+        client.issues.getBundle(1, '999e4567-e89b-12d3-a456-426614174000'),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
+
+  describe('getContexts()', () => {
+    it('should fetch context breakdowns', async () => {
+      const contexts = await client.issues.getContexts(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(contexts.os.total).toBe(5);
+      expect(contexts.os.values[0]).toEqual({
+        value: 'macOS 14.2',
+        count: 3,
+        percentage: 60,
+      });
+      expect(contexts.browser.values).toHaveLength(1);
+      expect(contexts.runtime.values).toHaveLength(0);
+    });
+
+    it('should throw NotFoundError for non-existent issue', async () => {
+      await expect(
+        client.issues.getContexts(1, '999e4567-e89b-12d3-a456-426614174000'),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
+
+  describe('getLevelBreakdown()', () => {
+    it('should fetch event counts per level', async () => {
+      const breakdown = await client.issues.getLevelBreakdown(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(breakdown.total).toBe(12);
+      expect(breakdown.levels).toHaveLength(3);
+      expect(breakdown.levels[0]).toEqual({ level: 'error', count: 9 });
+    });
+
+    it('should throw NotFoundError for non-existent issue', async () => {
+      await expect(
+        client.issues.getLevelBreakdown(
+          1,
+          '999e4567-e89b-12d3-a456-426614174000',
+        ),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
+
+  describe('getTransactions()', () => {
+    it('should fetch event counts per transaction', async () => {
+      const breakdown = await client.issues.getTransactions(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(breakdown.total).toBe(10);
+      expect(breakdown.transactions).toHaveLength(2);
+      expect(breakdown.transactions[0]).toEqual({
+        transaction: '/api/orders',
+        count: 7,
+      });
+    });
+
+    it('should throw NotFoundError for non-existent issue', async () => {
+      await expect(
+        client.issues.getTransactions(
+          1,
+          '999e4567-e89b-12d3-a456-426614174000',
+        ),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
+
+  describe('resolve()', () => {
+    it('should resolve with the fixing change', async () => {
+      const resolved = await client.issues.resolve(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        {
+          commit: '9fceb02',
+          pr_url: 'https://github.com/acme/app/pull/42',
+        },
+      );
+
+      expect(resolved.is_resolved).toBe(true);
+      expect(resolved.resolved_commit).toBe('9fceb02');
+      expect(resolved.resolved_pr_url).toBe(
+        'https://github.com/acme/app/pull/42',
+      );
+    });
+
+    it('should resolve without references', async () => {
+      const resolved = await client.issues.resolve(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(resolved.is_resolved).toBe(true);
+      expect(resolved.resolved_commit).toBeNull();
+    });
+
+    it('should reject an invalid pull request URL', async () => {
+      await expect(
+        client.issues.resolve(1, '323e4567-e89b-12d3-a456-426614174000', {
+          pr_url: 'not a url',
+        }),
+      ).rejects.toThrow(ValidationError);
+    });
+  });
+
   describe('updateState()', () => {
     it('should resolve issue', async () => {
       const updated = await client.issues.updateState(
@@ -135,6 +319,42 @@ describe('IssuesResource Integration', () => {
       expect(updated.is_muted).toBe(true);
     });
 
+    it('should set the sample rate', async () => {
+      const sampled = await client.issues.updateState(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        { sample_rate: 0.1 },
+      );
+      expect(sampled.sample_rate).toBe(0.1);
+      expect(sampled.stored_event_count).toBe(5);
+
+      const restored = await client.issues.updateState(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        { sample_rate: 1 },
+      );
+      expect(restored.sample_rate).toBeNull();
+    });
+
+    it('should set the priority by hand', async () => {
+      const updated = await client.issues.updateState(
+        1,
+        '323e4567-e89b-12d3-a456-426614174000',
+        { priority: 'low' },
+      );
+
+      expect(updated.priority).toBe('low');
+      expect(updated.priority_is_manual).toBe(true);
+    });
+
+    it('should reject a sample rate outside (0, 1]', async () => {
+      await expect(
+        client.issues.updateState(1, '323e4567-e89b-12d3-a456-426614174000', {
+          sample_rate: 0,
+        }),
+      ).rejects.toThrow(ValidationError);
+    });
+
     it('should throw NotFoundError for non-existent issue', async () => {
       await expect(
         client.issues.updateState(1, '999e4567-e89b-12d3-a456-426614174000', {
@@ -157,4 +377,46 @@ describe('IssuesResource Integration', () => {
       ).rejects.toThrow(NotFoundError);
     });
   });
+
+  describe('aggregate()', () => {
+    it('should fetch issue groups', async () => {
+      const result = await client.issues.aggregate(1, 'transaction', {
+        period: '24h',
+      });
+
+      expect(result.group_by).toBe('transaction');
+      expect(result.period).toBe('24h');
+      expect(result.groups).toHaveLength(1);
+      expect(result.groups[0].value).toBe('/checkout');
+      expect(result.groups[0].event_count).toBe(15);
+    });
+  });
+
+  describe('listTrash()', () => {
+    it('should fetch deleted issues', async () => {
+      const result = await client.issues.listTrash(1);
+
+      expect(result.items).toHaveLength(1);
+      expect(result.items[0].id).toBe('723e4567-e89b-12d3-a456-426614174000');
+      expect(result.items[0].deleted_at).toBe('2026-01-21T08:00:00.000Z');
+    });
+  });
+
+  describe('restore()', () => {
+    it('should restore issue from the trash', async () => {
+      const issue = await client.issues.restore(
+        1,
+        '723e4567-e89b-12d3-a456-426614174000',
+      );
+
+      expect(issue.id).toBe('723e4567-e89b-12d3-a456-426614174000');
+      expect(issue.deleted_at).toBeUndefined();
+    });
+
+    it('should throw NotFoundError for issue not in the trash', async () => {
+      await expect(
+        client.issues.restore(1, '323e4567-e89b-12d3-a456-426614174000'),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
 });
@@ -34,6 +34,7 @@ describe('Pagination', () => {
                     short_id: 'TEST-1',
                     title: 'Issue 1',
                     value: 'Issue 1 value',
+                    culprit: null,
                     first_seen: '2026-01-20T10:00:00.000Z',
                     last_seen: '2026-01-20T11:00:00.000Z',
                     event_count: 5,
@@ -41,6 +42,7 @@ describe('Pagination', () => {
                     platform: 'javascript',
                     is_resolved: false,
                     is_muted: false,
+                    is_seen: false,
                   },
                 ],
                 total_count: 2,
@@ -57,6 +59,7 @@ describe('Pagination', () => {
                     short_id: 'TEST-2',
                     title: 'Issue 2',
                     value: 'Issue 2 value',
+                    culprit: null,
                     first_seen: '2026-01-20T09:00:00.000Z',
                     last_seen: '2026-01-20T10:00:00.000Z',
                     event_count: 3,
@@ -64,6 +67,7 @@ describe('Pagination', () => {
                     platform: 'javascript',
                     is_resolved: false,
                     is_muted: false,
+                    is_seen: false,
                   },
                 ],
                 total_count: 2,
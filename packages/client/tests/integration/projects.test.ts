@@ -23,6 +23,17 @@ describe('ProjectsResource Integration', () => {
       expect(response.items[1]?.name).toBe('Another Project');
       expect(response.total_count).toBe(2);
       expect(response.page).toBe(1);
+      expect(response.items[0]?.stats).toBeUndefined();
+    });
+
+    it('should include stats when asked', async () => {
+      const response = await client.projects.list({ include: ['stats'] });
+
+      expect(response.items[0]?.stats).toEqual({
+        open_issue_count: 3,
+        events_24h: 0,
+      });
+      expect(response.items[1]?.stats?.open_issue_count).toBe(0);
     });
 
     it('should validate response schema', async () => {
@@ -34,8 +45,7 @@ describe('ProjectsResource Integration', () => {
                 id: 1,
                 name: 'Invalid',
                 slug: 'invalid',
-                sentry_key: 'not-a-uuid', // Invalid UUID
-                dsn: 'http://localhost:8080/1',
+                keys: 'none', // Not an array
                 stored_event_count: 0,
                 digested_event_count: 0,
                 created_at: '2026-01-20T10:00:00.000Z',
@@ -84,12 +94,12 @@ describe('ProjectsResource Integration', () => {
       await expect(client.projects.get(999)).rejects.toThrow(NotFoundError);
     });
 
-    it('should validate UUID format', async () => {
+    it('should list masked keys without a DSN', async () => {
       const project = await client.projects.get(1);
 
-      expect(project.sentry_key).toMatch(
-        /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i,
-      );
+      expect(project.keys).toHaveLength(2);
+      expect(project.keys[0]?.key_prefix).toBe('123e4567...');
+      expect(project.dsn).toBeUndefined();
     });
 
     it('should validate datetime format', async () => {
@@ -116,6 +126,15 @@ describe('ProjectsResource Integration', () => {
       expect(project.id).toBe(3);
     });
 
+    it('should reveal the default key DSN', async () => {
+      const project = await client.projects.create({ name: 'DSN Project' });
+
+      expect(project.keys).toHaveLength(1);
+      expect(project.dsn).toBe(
+        'http://923e4567e89b12d3a456426614174000@localhost:8080/3',
+      );
+    });
+
     it('should create project without optional slug', async () => {
       const project = await client.projects.create({
         name: 'Auto Slug Project',
@@ -149,6 +168,46 @@ describe('ProjectsResource Integration', () => {
       expect(updated.id).toBe(1);
     });
 
+    it('should update auto-resolve days', async () => {
+      const updated = await client.projects.update(1, {
+        auto_resolve_days: 14,
+      });
+
+      expect(updated.auto_resolve_days).toBe(14);
+    });
+
+    it('should update in-app rules', async () => {
+      const updated = await client.projects.update(1, {
+        in_app_include: ['shop.'],
+        in_app_exclude: ['django.'],
+      });
+
+      expect(updated.in_app_include).toEqual(['shop.']);
+      expect(updated.in_app_exclude).toEqual(['django.']);
+    });
+
+    it('should update the default issue sort', async () => {
+      const updated = await client.projects.update(1, {
+        default_issue_sort: 'last_seen',
+      });
+
+      expect(updated.default_issue_sort).toBe('last_seen');
+    });
+
+    it('should update the timezone', async () => {
+      const updated = await client.projects.update(1, {
+        timezone: 'Europe/Madrid',
+      });
+
+      expect(updated.timezone).toBe('Europe/Madrid');
+    });
+
+    it('should reject negative auto-resolve days', async () => {
+      await expect(
+        client.projects.update(1, { auto_resolve_days: -1 }),
+      ).rejects.toThrow(ValidationError);
+    });
+
     it('should throw NotFoundError for non-existent project', async () => {
       await expect(
         client.projects.update(999, { name: 'New Name' }),
@@ -180,4 +239,160 @@ describe('ProjectsResource Integration', () => {
       await expect(client.projects.delete(999)).rejects.toThrow(NotFoundError);
     });
   });
+
+  describe('getOnboarding()', () => {
+    it('should fetch onboarding state', async () => {
+      const onboarding = await client.projects.getOnboarding(1);
+
+      expect(onboarding.received_first_event).toBe(true);
+      expect(onboarding.sdk?.name).toBe('sentry.javascript.browser');
+    });
+
+    it('should pass wait to the server', async () => {
+      let requestedWait: string | null = null;
+      server.use(
+        http.get(
+          'http://localhost:8080/api/projects/:id/onboarding',
+          ({ request }) => {
+            requestedWait = new URL(request.url).searchParams.get('wait');
+            return HttpResponse.json({
+              received_first_event: false,
+              first_event_at: null,
+              sdk: null,
+            });
+          },
+        ),
+      );
+
+      const onboarding = await client.projects.getOnboarding(2, { wait: 30 });
+
+      expect(requestedWait).toBe('30');
+      expect(onboarding.received_first_event).toBe(false);
+      expect(onboarding.sdk).toBeNull();
+    });
+
+    it('should throw NotFoundError for non-existent project', async () => {
+      await expect(client.projects.getOnboarding(999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
+
+  describe('getLiveStats()', () => {
+    it('should fetch live event rates', async () => {
+      const stats = await client.projects.getLiveStats(1);
+
+      expect(stats.project_id).toBe(1);
+      expect(stats.instance_id).toBe('rustrak-1');
+      expect(stats.accepted.last_10s).toBe(4.2);
+      expect(stats.digested.last_60s).toBe(1.5);
+    });
+
+    it('should throw NotFoundError for non-existent project', async () => {
+      await expect(client.projects.getLiveStats(999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
+
+  describe('getDailyStats()', () => {
+    it('should fetch two weeks of daily counts by default', async () => {
+      const stats = await client.projects.getDailyStats(1);
+
+      expect(stats.timezone).toBe('UTC');
+      expect(stats.days).toHaveLength(14);
+      expect(stats.days[13]?.events).toBe(3);
+    });
+
+    it('should pass days and timezone', async () => {
+      const stats = await client.projects.getDailyStats(1, {
+        days: 7,
+        tz: 'Asia/Tokyo',
+      });
+
+      expect(stats.timezone).toBe('Asia/Tokyo');
+      expect(stats.days).toHaveLength(7);
+      expect(stats.days[6]?.date).toBe('2026-02-20');
+    });
+
+    it('should throw NotFoundError for non-existent project', async () => {
+      await expect(client.projects.getDailyStats(999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
+
+  describe('keys', () => {
+    it('should list project keys', async () => {
+      const keys = await client.projects.listKeys(1);
+
+      expect(keys).toHaveLength(2);
+      expect(keys[1]?.label).toBe('Backend');
+      expect(keys[1]?.is_active).toBe(false);
+    });
+
+    it('should throw NotFoundError for non-existent project', async () => {
+      await expect(client.projects.listKeys(999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+
+    it('should create key with full key and DSN', async () => {
+      const key = await client.projects.createKey(1, { label: 'Frontend' });
+
+      expect(key.label).toBe('Frontend');
+      expect(key.key).toBe('723e4567-e89b-12d3-a456-426614174000');
+      expect(key.dsn).toContain('@localhost:8080/1');
+    });
+
+    it('should deactivate key', async () => {
+      const key = await client.projects.updateKey(1, 1, { is_active: false });
+
+      expect(key.id).toBe(1);
+      expect(key.is_active).toBe(false);
+    });
+
+    it('should delete key', async () => {
+      await expect(client.projects.deleteKey(1, 2)).resolves.toBeUndefined();
+    });
+
+    it('should throw NotFoundError for non-existent key', async () => {
+      await expect(client.projects.deleteKey(1, 999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
+
+  describe('members', () => {
+    it('should list project members', async () => {
+      const members = await client.projects.listMembers(1);
+
+      expect(members).toHaveLength(1);
+      expect(members[0]?.email).toBe('test@example.com');
+      expect(members[0]?.role).toBe('admin');
+    });
+
+    it('should add member with default role', async () => {
+      const member = await client.projects.addMember(1, { user_id: 2 });
+
+      expect(member.user_id).toBe(2);
+      expect(member.role).toBe('member');
+    });
+
+    it('should reject a user who is already a member', async () => {
+      await expect(
+        client.projects.addMember(1, { user_id: 1, role: 'admin' }),
+      ).rejects.toMatchObject({ statusCode: 409 });
+    });
+
+    it('should remove member', async () => {
+      await expect(client.projects.removeMember(1, 1)).resolves.toBeUndefined();
+    });
+
+    it('should throw NotFoundError for non-member', async () => {
+      await expect(client.projects.removeMember(1, 999)).rejects.toThrow(
+        NotFoundError,
+      );
+    });
+  });
 });
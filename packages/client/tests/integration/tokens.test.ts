@@ -13,25 +13,27 @@ describe('TokensResource Integration', () => {
   });
 
   describe('list()', () => {
-    it('should fetch all tokens (masked)', async () => {
-      const tokens = await client.tokens.list();
-
-      expect(tokens).toHaveLength(1);
-      expect(tokens[0]?.token_prefix).toBe('abc12345...');
-      expect(tokens[0]?.description).toBe('Test Token');
+    it('should fetch paginated tokens (masked)', async () => {
+      const result = await client.tokens.list();
+
+      expect(result.items).toHaveLength(1);
+      expect(result.total_count).toBe(1);
+      expect(result.page).toBe(1);
+      expect(result.items[0]?.token_prefix).toBe('abc12345...');
+      expect(result.items[0]?.description).toBe('Test Token');
     });
 
     it('should validate datetime format', async () => {
-      const tokens = await client.tokens.list();
-      const token = tokens[0];
+      const { items } = await client.tokens.list();
+      const token = items[0];
 
       expect(token).toBeDefined();
       expect(new Date(token!.created_at).toISOString()).toBe(token!.created_at);
     });
 
     it('should handle null fields', async () => {
-      const tokens = await client.tokens.list();
-      const token = tokens[0];
+      const { items } = await client.tokens.list({ page: 1, per_page: 20 });
+      const token = items[0];
 
       expect(token).toBeDefined();
       // description and last_used_at can be null
@@ -87,6 +89,30 @@ describe('TokensResource Integration', () => {
     });
   });
 
+  describe('update()', () => {
+    it('should update token description', async () => {
+      const token = await client.tokens.update(1, {
+        description: 'Renamed Token',
+      });
+
+      expect(token.id).toBe(1);
+      expect(token.description).toBe('Renamed Token');
+      expect(token.token_prefix).toBe('abc12345...');
+    });
+
+    it('should clear description when empty', async () => {
+      const token = await client.tokens.update(1, { description: '' });
+
+      expect(token.description).toBeNull();
+    });
+
+    it('should throw NotFoundError for non-existent token', async () => {
+      await expect(
+        client.tokens.update(999, { description: 'Nope' }),
+      ).rejects.toThrow(NotFoundError);
+    });
+  });
+
   describe('delete()', () => {
     it('should delete token successfully', async () => {
       await expect(client.tokens.delete(1)).resolves.toBeUndefined();
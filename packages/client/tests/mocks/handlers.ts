@@ -3,15 +3,39 @@ import { HttpResponse, http } from 'msw';
 const BASE_URL = 'http://localhost:8080';
 
 // Mock data
+export const mockProjectKeys = [
+  {
+    id: 1,
+    label: 'Default',
+    key_prefix: '123e4567...',
+    is_active: true,
+    created_at: '2026-01-20T10:00:00.000Z',
+    last_used_at: '2026-01-20T11:00:00.000Z',
+  },
+  {
+    id: 2,
+    label: 'Backend',
+    key_prefix: '623e4567...',
+    is_active: false,
+    created_at: '2026-01-20T12:00:00.000Z',
+    last_used_at: null,
+  },
+];
+
 export const mockProjects = [
   {
     id: 1,
     name: 'Test Project',
     slug: 'test-project',
-    sentry_key: '123e4567-e89b-12d3-a456-426614174000',
-    dsn: 'http://123e4567-e89b-12d3-a456-426614174000@localhost:8080/1',
+    keys: mockProjectKeys,
     stored_event_count: 100,
     digested_event_count: 95,
+    allowed_domains: [],
+    auto_resolve_days: null,
+    in_app_include: [],
+    in_app_exclude: [],
+    default_issue_sort: 'digest_order',
+    timezone: null,
     created_at: '2026-01-20T10:00:00.000Z',
     updated_at: '2026-01-20T10:00:00.000Z',
   },
@@ -19,10 +43,24 @@ export const mockProjects = [
     id: 2,
     name: 'Another Project',
     slug: 'another-project',
-    sentry_key: '223e4567-e89b-12d3-a456-426614174000',
-    dsn: 'http://223e4567-e89b-12d3-a456-426614174000@localhost:8080/2',
+    keys: [
+      {
+        id: 3,
+        label: 'Default',
+        key_prefix: '223e4567...',
+        is_active: true,
+        created_at: '2026-01-19T10:00:00.000Z',
+        last_used_at: null,
+      },
+    ],
     stored_event_count: 50,
     digested_event_count: 48,
+    allowed_domains: [],
+    auto_resolve_days: null,
+    in_app_include: [],
+    in_app_exclude: [],
+    default_issue_sort: 'digest_order',
+    timezone: null,
     created_at: '2026-01-19T10:00:00.000Z',
     updated_at: '2026-01-19T10:00:00.000Z',
   },
@@ -35,13 +73,42 @@ export const mockIssues = [
     short_id: 'TEST-1',
     title: 'TypeError: Cannot read property',
     value: "Cannot read property 'x' of undefined",
+    culprit: 'src/components/Profile.tsx',
     first_seen: '2026-01-20T10:00:00.000Z',
     last_seen: '2026-01-20T11:00:00.000Z',
     event_count: 5,
+    stored_event_count: 5,
+    last_event_id: '523e4567-e89b-12d3-a456-426614174000',
+    sample_rate: null,
     level: 'error',
     platform: 'javascript',
     is_resolved: false,
     is_muted: false,
+    auto_resolved: false,
+    resolved_commit: null,
+    resolved_pr_url: null,
+    regression_count: 1,
+    regressed_at: ['2026-01-20T10:30:00.000Z'],
+    priority: 'high',
+    priority_is_manual: false,
+    priority_history: [
+      {
+        old_priority: 'medium',
+        new_priority: 'high',
+        is_manual: false,
+        changed_by: null,
+        changed_at: '2026-01-20T10:45:00.000Z',
+      },
+    ],
+    is_unhandled: true,
+    is_seen: true,
+    seen_by: [
+      {
+        user_id: 1,
+        email: 'admin@example.com',
+        seen_at: '2026-01-20T12:00:00.000Z',
+      },
+    ],
   },
   {
     id: '423e4567-e89b-12d3-a456-426614174000',
@@ -49,13 +116,55 @@ export const mockIssues = [
     short_id: 'TEST-2',
     title: 'ReferenceError: foo is not defined',
     value: 'foo is not defined',
+    culprit: '/dashboard',
     first_seen: '2026-01-20T09:00:00.000Z',
     last_seen: '2026-01-20T10:00:00.000Z',
     event_count: 3,
+    stored_event_count: 3,
+    last_event_id: '823e4567-e89b-12d3-a456-426614174000',
+    sample_rate: null,
+    level: 'error',
+    platform: 'javascript',
+    is_resolved: false,
+    is_muted: false,
+    auto_resolved: false,
+    resolved_commit: null,
+    resolved_pr_url: null,
+    regression_count: 0,
+    priority: 'medium',
+    priority_is_manual: false,
+    is_unhandled: false,
+    is_seen: false,
+  },
+];
+
+export const mockDeletedIssues = [
+  {
+    id: '723e4567-e89b-12d3-a456-426614174000',
+    project_id: 1,
+    short_id: 'TEST-3',
+    title: 'RangeError: Invalid array length',
+    value: 'Invalid array length',
+    culprit: null,
+    first_seen: '2026-01-19T09:00:00.000Z',
+    last_seen: '2026-01-19T10:00:00.000Z',
+    event_count: 2,
+    stored_event_count: 2,
+    last_event_id: '923e4567-e89b-12d3-a456-426614174000',
+    sample_rate: null,
     level: 'error',
     platform: 'javascript',
     is_resolved: false,
     is_muted: false,
+    auto_resolved: false,
+    resolved_commit: null,
+    resolved_pr_url: null,
+    regression_count: 0,
+    priority: 'medium',
+    priority_is_manual: false,
+    is_unhandled: false,
+    is_seen: false,
+    deleted_at: '2026-01-21T08:00:00.000Z',
   },
 ];
 
@@ -69,7 +178,10 @@ export const mockEvents = [
     level: 'error',
     platform: 'javascript',
     release: '1.0.0',
+    dist: '',
     environment: 'production',
+    is_unhandled: true,
+    payload_pruned: false,
   },
 ];
 
@@ -83,10 +195,13 @@ export const mockEventDetail = {
   level: 'error',
   platform: 'javascript',
   release: '1.0.0',
+  dist: '',
   environment: 'production',
+  is_unhandled: true,
   server_name: 'web-1',
   sdk_name: '@sentry/browser',
   sdk_version: '7.0.0',
+  payload_pruned: false,
   data: {
     exception: {
       values: [
@@ -121,6 +236,16 @@ export const mockAdminUser = {
   is_admin: true,
 };
 
+export const mockProjectMembers = [
+  {
+    project_id: 1,
+    user_id: 1,
+    email: 'test@example.com',
+    role: 'admin',
+    created_at: '2026-01-19T10:00:00.000Z',
+  },
+];
+
 export const mockNotificationChannels = [
   {
     id: 1,
@@ -165,6 +290,7 @@ export const mockAlertRules = [
     is_enabled: true,
     conditions: {},
     cooldown_minutes: 0,
+    digest_minutes: 0,
     last_triggered_at: '2026-01-20T11:00:00.000Z',
     channel_ids: [1, 2],
     created_at: '2026-01-20T10:00:00.000Z',
@@ -178,6 +304,7 @@ export const mockAlertRules = [
     is_enabled: false,
     conditions: {},
     cooldown_minutes: 60,
+    digest_minutes: 15,
     last_triggered_at: null,
     channel_ids: [1],
     created_at: '2026-01-19T10:00:00.000Z',
@@ -226,9 +353,17 @@ export const mockAlertHistory = [
 
 export const handlers = [
   // Projects
-  http.get(`${BASE_URL}/api/projects`, () => {
+  http.get(`${BASE_URL}/api/projects`, ({ request }) => {
+    const include = new URL(request.url).searchParams.get('include');
+    const items = include?.split(',').includes('stats')
+      ? mockProjects.map((p) => ({
+          ...p,
+          stats: { open_issue_count: p.id === 1 ? 3 : 0, events_24h: 0 },
+        }))
+      : mockProjects;
+
     return HttpResponse.json({
-      items: mockProjects,
+      items,
       total_count: mockProjects.length,
       page: 1,
       per_page: 20,
@@ -254,10 +389,25 @@ export const handlers = [
       id: 3,
       name: body.name,
       slug: body.slug ?? body.name.toLowerCase().replace(/\s+/g, '-'),
-      sentry_key: '923e4567-e89b-12d3-a456-426614174000',
-      dsn: 'http://923e4567-e89b-12d3-a456-426614174000@localhost:8080/3',
+      keys: [
+        {
+          id: 4,
+          label: 'Default',
+          key_prefix: '923e4567...',
+          is_active: true,
+          created_at: new Date().toISOString(),
+          last_used_at: null,
+        },
+      ],
+      dsn: 'http://923e4567e89b12d3a456426614174000@localhost:8080/3',
       stored_event_count: 0,
       digested_event_count: 0,
+      allowed_domains: [],
+      auto_resolve_days: null,
+      in_app_include: [],
+      in_app_exclude: [],
+      default_issue_sort: 'digest_order',
+      timezone: null,
       created_at: new Date().toISOString(),
       updated_at: new Date().toISOString(),
     };
@@ -294,16 +444,203 @@ export const handlers = [
     return new HttpResponse(null, { status: 204 });
   }),
 
+  http.get(`${BASE_URL}/api/projects/:id/onboarding`, ({ params }) => {
+    const project = mockProjects.find((p) => p.id === Number(params.id));
+
+    if (!project) {
+      return HttpResponse.json({ error: 'Project not found' }, { status: 404 });
+    }
+
+    return HttpResponse.json({
+      received_first_event: true,
+      first_event_at: '2026-01-20T10:05:00.000Z',
+      sdk: { name: 'sentry.javascript.browser', version: '8.0.0' },
+    });
+  }),
+
+  http.get(`${BASE_URL}/api/projects/:id/stats/live`, ({ params }) => {
+    const project = mockProjects.find((p) => p.id === Number(params.id));
+
+    if (!project) {
+      return HttpResponse.json({ error: 'Project not found' }, { status: 404 });
+    }
+
+    return HttpResponse.json({
+      project_id: project.id,
+      instance_id: 'rustrak-1',
+      accepted: { last_10s: 4.2, last_60s: 1.5 },
+      digested: { last_10s: 4.0, last_60s: 1.5 },
+    });
+  }),
+
+  http.get(
+    `${BASE_URL}/api/projects/:id/stats/daily`,
+    ({ params, request }) => {
+      const project = mockProjects.find((p) => p.id === Number(params.id));
+
+      if (!project) {
+        return HttpResponse.json(
+          { error: 'Project not found' },
+          { status: 404 },
+        );
+      }
+
+      const url = new URL(request.url);
+      const days = Number(url.searchParams.get('days') ?? 14);
+      const timezone = url.searchParams.get('tz') ?? project.timezone ?? 'UTC';
+
+      return HttpResponse.json({
+        timezone,
+        days: Array.from({ length: days }, (_, i) => ({
+          date: new Date(Date.UTC(2026, 1, 20 - days + 1 + i))
+            .toISOString()
+            .slice(0, 10),
+          events: i === days - 1 ? 3 : 0,
+        })),
+      });
+    },
+  ),
+
+  // Project Keys
+  http.get(`${BASE_URL}/api/projects/:id/keys`, ({ params }) => {
+    const project = mockProjects.find((p) => p.id === Number(params.id));
+
+    if (!project) {
+      return HttpResponse.json({ error: 'Project not found' }, { status: 404 });
+    }
+
+    return HttpResponse.json(project.keys);
+  }),
+
+  http.post(
+    `${BASE_URL}/api/projects/:id/keys`,
+    async ({ params, request }) => {
+      const { id } = params;
+      const body = (await request.json()) as { label?: string };
+
+      if (!mockProjects.some((p) => p.id === Number(id))) {
+        return HttpResponse.json(
+          { error: 'Project not found' },
+          { status: 404 },
+        );
+      }
+
+      const newKey = {
+        id: 5,
+        label: body.label ?? 'Default',
+        key: '723e4567-e89b-12d3-a456-426614174000',
+        dsn: `http://723e4567e89b12d3a456426614174000@localhost:8080/${id}`,
+        is_active: true,
+        created_at: new Date().toISOString(),
+      };
+
+      return HttpResponse.json(newKey, { status: 201 });
+    },
+  ),
+
+  http.patch(
+    `${BASE_URL}/api/projects/:id/keys/:keyId`,
+    async ({ params, request }) => {
+      const { id, keyId } = params;
+      const body = (await request.json()) as { is_active: boolean };
+      const key = mockProjects
+        .find((p) => p.id === Number(id))
+        ?.keys.find((k) => k.id === Number(keyId));
+
+      if (!key) {
+        return HttpResponse.json({ error: 'Key not found' }, { status: 404 });
+      }
+
+      return HttpResponse.json({ ...key, is_active: body.is_active });
+    },
+  ),
+
+  http.delete(`${BASE_URL}/api/projects/:id/keys/:keyId`, ({ params }) => {
+    const { id, keyId } = params;
+    const key = mockProjects
+      .find((p) => p.id === Number(id))
+      ?.keys.find((k) => k.id === Number(keyId));
+
+    if (!key) {
+      return HttpResponse.json({ error: 'Key not found' }, { status: 404 });
+    }
+
+    return new HttpResponse(null, { status: 204 });
+  }),
+
+  // Project Members
+  http.get(`${BASE_URL}/api/projects/:id/members`, ({ params }) => {
+    if (!mockProjects.some((p) => p.id === Number(params.id))) {
+      return HttpResponse.json({ error: 'Project not found' }, { status: 404 });
+    }
+
+    return HttpResponse.json(
+      mockProjectMembers.filter((m) => m.project_id === Number(params.id)),
+    );
+  }),
+
+  http.post(
+    `${BASE_URL}/api/projects/:id/members`,
+    async ({ params, request }) => {
+      const body = (await request.json()) as { user_id: number; role?: string };
+
+      if (mockProjectMembers.some((m) => m.user_id === body.user_id)) {
+        return HttpResponse.json(
+          { error: 'User is already a member' },
+          { status: 409 },
+        );
+      }
+
+      return HttpResponse.json(
+        {
+          project_id: Number(params.id),
+          user_id: body.user_id,
+          email: mockAdminUser.email,
+          role: body.role ?? 'member',
+          created_at: new Date().toISOString(),
+        },
+        { status: 201 },
+      );
+    },
+  ),
+
+  http.delete(
+    `${BASE_URL}/api/projects/:id/members/:userId`,
+    ({ params }) => {
+      const member = mockProjectMembers.find(
+        (m) =>
+          m.project_id === Number(params.id) &&
+          m.user_id === Number(params.userId),
+      );
+
+      if (!member) {
+        return HttpResponse.json(
+          { error: 'Member not found' },
+          { status: 404 },
+        );
+      }
+
+      return new HttpResponse(null, { status: 204 });
+    },
+  ),
+
   // Issues
   http.get(`${BASE_URL}/api/projects/:projectId/issues`, ({ request }) => {
     const url = new URL(request.url);
     const page = parseInt(url.searchParams.get('page') ?? '1', 10);
+    const handled = url.searchParams.get('handled');
+    const priority = url.searchParams.get('priority');
+    const issues = mockIssues
+      .filter(
+        (i) => handled === null || i.is_unhandled === (handled === 'false'),
+      )
+      .filter((i) => priority === null || i.priority === priority);
 
     // Simple pagination mock - page 2 returns empty
     if (page > 1) {
       return HttpResponse.json({
         items: [],
-        total_count: mockIssues.length,
+        total_count: issues.length,
         page: page,
         per_page: 20,
         total_pages: 1,
@@ -311,8 +648,39 @@ export const handlers = [
     }
 
     return HttpResponse.json({
-      items: mockIssues,
-      total_count: mockIssues.length,
+      items: issues,
+      total_count: issues.length,
+      page: 1,
+      per_page: 20,
+      total_pages: 1,
+    });
+  }),
+
+  // Before /issues/:issueId, which would match these paths too
+  http.get(
+    `${BASE_URL}/api/projects/:projectId/issues/aggregate`,
+    ({ request }) => {
+      const url = new URL(request.url);
+
+      return HttpResponse.json({
+        group_by: url.searchParams.get('group_by'),
+        period: url.searchParams.get('period') ?? '7d',
+        groups: [
+          {
+            value: '/checkout',
+            issue_count: 2,
+            event_count: 15,
+            latest_issue_id: mockIssues[0].id,
+          },
+        ],
+      });
+    },
+  ),
+
+  http.get(`${BASE_URL}/api/projects/:projectId/issues/trash`, () => {
+    return HttpResponse.json({
+      items: mockDeletedIssues,
+      total_count: mockDeletedIssues.length,
       page: 1,
       per_page: 20,
       total_pages: 1,
@@ -321,7 +689,7 @@ export const handlers = [
 
   http.get(
     `${BASE_URL}/api/projects/:projectId/issues/:issueId`,
-    ({ params }) => {
+    ({ params, request }) => {
       const { issueId } = params;
       const issue = mockIssues.find((i) => i.id === issueId);
 
@@ -329,17 +697,117 @@ export const handlers = [
         return HttpResponse.json({ error: 'Issue not found' }, { status: 404 });
       }
 
+      const include = new URL(request.url).searchParams.get('include') ?? '';
+      if (
+        include.split(',').includes('latest_event') &&
+        issue.last_event_id === mockEventDetail.id
+      ) {
+        return HttpResponse.json({ ...issue, latest_event: mockEventDetail });
+      }
+
       return HttpResponse.json(issue);
     },
   ),
 
+  http.get(
+    `${BASE_URL}/api/projects/:projectId/issues/:issueId/bundle`,
+    ({ params, request }) => {
+      const { issueId } = params;
+      const issue = mockIssues.find((i) => i.id === issueId);
+
+      if (!issue) {
+        return HttpResponse.json({ error: 'Issue not found' }, { status: 404 });
+      }
+
+      const events = Number(
+        new URL(request.url).searchParams.get('events') ?? '10',
+      );
+      return HttpResponse.json({
+        exported_at: '2026-01-20T12:00:00.000Z',
+        issue: { ...issue, groupings: [] },
+        events: events > 0 ? [mockEventDetail] : [],
+      });
+    },
+  ),
+
+  http.get(
+    `${BASE_URL}/api/projects/:projectId/issues/:issueId/contexts`,
+    ({ params }) => {
+      const { issueId } = params;
+      const issue = mockIssues.find((i) => i.id === issueId);
+
+      if (!issue) {
+        return HttpResponse.json({ error: 'Issue not found' }, { status: 404 });
+      }
+
+      return HttpResponse.json({
+        os: {
+          total: 5,
+          values: [
+            { value: 'macOS 14.2', count: 3, percentage: 60.0 },
+            { value: 'Ubuntu 22.04', count: 2, percentage: 40.0 },
+          ],
+        },
+        browser: {
+          total: 5,
+          values: [{ value: 'Chrome 120.0', count: 5, percentage: 100.0 }],
+        },
+        runtime: { total: 0, values: [] },
+        dist: { total: 0, values: [] },
+      });
+    },
+  ),
+
+  http.get(
+    `${BASE_URL}/api/projects/:projectId/issues/:issueId/level-breakdown`,
+    ({ params }) => {
+      const { issueId } = params;
+      const issue = mockIssues.find((i) => i.id === issueId);
+
+      if (!issue) {
+        return HttpResponse.json({ error: 'Issue not found' }, { status: 404 });
+      }
+
+      return HttpResponse.json({
+        total: 12,
+        levels: [
+          { level: 'error', count: 9 },
+          { level: 'warning', count: 2 },
+          { level: 'fatal', count: 1 },
+        ],
+      });
+    },
+  ),
+
+  http.get(
+    `${BASE_URL}/api/projects/:projectId/issues/:issueId/transactions`,
+    ({ params }) => {
+      const { issueId } = params;
+      const issue = mockIssues.find((i) => i.id === issueId);
+
+      if (!issue) {
+        return HttpResponse.json({ error: 'Issue not found' }, { status: 404 });
+      }
+
+      return HttpResponse.json({
+        total: 10,
+        transactions: [
+          { transaction: '/api/orders', count: 7 },
+          { transaction: '/api/users', count: 3 },
+        ],
+      });
+    },
+  ),
+
   http.patch(
     `${BASE_URL}/api/projects/:projectId/issues/:issueId`,
     async ({ params, request }) => {
       const { issueId } = params;
-      const body = (await request.json()) as {
+      const { sample_rate, ...body } = (await request.json()) as {
         is_resolved?: boolean;
         is_muted?: boolean;
+        sample_rate?: number;
+        priority?: string;
       };
       const issue = mockIssues.find((i) => i.id === issueId);
 
@@ -350,6 +818,10 @@ export const handlers = [
       const updated = {
         ...issue,
         ...body,
+        ...(sample_rate !== undefined && {
+          sample_rate: sample_rate < 1 ? sample_rate : null,
+        }),
+        ...(body.priority !== undefined && { priority_is_manual: true }),
       };
 
       return HttpResponse.json(updated);
@@ -370,6 +842,46 @@ export const handlers = [
     },
   ),
 
+  http.post(
+    `${BASE_URL}/api/projects/:projectId/issues/:issueId/resolve`,
+    async ({ params, request }) => {
+      const { issueId } = params;
+      const body = (await request.json()) as {
+        commit?: string;
+        pr_url?: string;
+      };
+      const issue = mockIssues.find((i) => i.id === issueId);
+
+      if (!issue) {
+        return HttpResponse.json({ error: 'Issue not found' }, { status: 404 });
+      }
+
+      return HttpResponse.json({
+        ...issue,
+        is_resolved: true,
+        resolved_commit: body.commit ?? null,
+        resolved_pr_url: body.pr_url ?? null,
+      });
+    },
+  ),
+
+  http.post(
+    `${BASE_URL}/api/projects/:projectId/issues/:issueId/restore`,
+    ({ params }) => {
+      const { issueId } = params;
+      const issue = mockDeletedIssues.find((i) => i.id === issueId);
+
+      if (!issue) {
+        return HttpResponse.json(
+          { error: { code: 'not_found', message: 'Deleted issue not found' } },
+          { status: 404 },
+        );
+      }
+
+      return HttpResponse.json({ ...issue, deleted_at: undefined });
+    },
+  ),
+
   // Events
   http.get(`${BASE_URL}/api/projects/:projectId/issues/:issueId/events`, () => {
     return HttpResponse.json({
@@ -391,9 +903,28 @@ export const handlers = [
     },
   ),
 
+  http.get(
+    `${BASE_URL}/api/projects/:projectId/events/:eventId`,
+    ({ params }) => {
+      const { eventId } = params;
+
+      if (eventId !== mockEventDetail.event_id) {
+        return HttpResponse.json({ error: 'Event not found' }, { status: 404 });
+      }
+
+      return HttpResponse.json(mockEventDetail);
+    },
+  ),
+
   // Auth Tokens
   http.get(`${BASE_URL}/api/tokens`, () => {
-    return HttpResponse.json(mockTokens);
+    return HttpResponse.json({
+      items: mockTokens,
+      total_count: mockTokens.length,
+      page: 1,
+      per_page: 20,
+      total_pages: 1,
+    });
   }),
 
   http.get(`${BASE_URL}/api/tokens/:id`, ({ params }) => {
@@ -420,6 +951,23 @@ export const handlers = [
     return HttpResponse.json(newToken, { status: 201 });
   }),
 
+  http.patch(`${BASE_URL}/api/tokens/:id`, async ({ params, request }) => {
+    const { id } = params;
+    const token = mockTokens.find((t) => t.id === Number(id));
+
+    if (!token) {
+      return HttpResponse.json({ error: 'Token not found' }, { status: 404 });
+    }
+
+    const body = (await request.json()) as { description?: string };
+    const description =
+      body.description === undefined
+        ? token.description
+        : body.description.trim() || null;
+
+    return HttpResponse.json({ ...token, description });
+  }),
+
   http.delete(`${BASE_URL}/api/tokens/:id`, ({ params }) => {
     const { id } = params;
     const token = mockTokens.find((t) => t.id === Number(id));
@@ -534,6 +1082,10 @@ export const handlers = [
     });
   }),
 
+  http.get(`${BASE_URL}/auth/methods`, () => {
+    return HttpResponse.json({ password: true, oidc: true });
+  }),
+
   http.get(`${BASE_URL}/auth/me`, ({ request }) => {
     const cookieHeader = request.headers.get('Cookie');
 
@@ -549,11 +1101,46 @@ export const handlers = [
     return HttpResponse.json(mockUser);
   }),
 
+  http.get(`${BASE_URL}/api/me/overview`, ({ request }) => {
+    const issues = mockIssues.filter((i) => !i.is_resolved && !i.is_muted);
+
+    // API tokens don't belong to a user, so they get no project list
+    if (request.headers.get('Authorization')) {
+      return HttpResponse.json({ issues });
+    }
+
+    return HttpResponse.json({
+      issues,
+      projects: mockProjects.map((p) => ({
+        id: p.id,
+        name: p.name,
+        slug: p.slug,
+        stats: { open_issue_count: 1, events_24h: 12 },
+      })),
+    });
+  }),
+
   // Alert Channels (Global)
   http.get(`${BASE_URL}/api/alert-channels`, () => {
     return HttpResponse.json(mockNotificationChannels);
   }),
 
+  http.get(`${BASE_URL}/api/alert-channels/health`, () => {
+    return HttpResponse.json(
+      mockNotificationChannels.map((c) => ({
+        id: c.id,
+        name: c.name,
+        channel_type: c.channel_type,
+        is_enabled: c.is_enabled,
+        failure_streak: c.failure_count,
+        last_success_at: c.last_success_at,
+        last_failure_at: c.last_failure_at,
+        last_failure_message: c.last_failure_message,
+        auto_disabled_at: null,
+      })),
+    );
+  }),
+
   http.get(`${BASE_URL}/api/alert-channels/:id`, ({ params }) => {
     const { id } = params;
     const channel = mockNotificationChannels.find((c) => c.id === Number(id));
@@ -618,7 +1205,7 @@ export const handlers = [
     },
   ),
 
-  http.delete(`${BASE_URL}/api/alert-channels/:id`, ({ params }) => {
+  http.delete(`${BASE_URL}/api/alert-channels/:id`, ({ params, request }) => {
     const { id } = params;
     const channel = mockNotificationChannels.find((c) => c.id === Number(id));
 
@@ -626,9 +1213,79 @@ export const handlers = [
       return HttpResponse.json({ error: 'Channel not found' }, { status: 404 });
     }
 
+    const force = new URL(request.url).searchParams.get('force') === 'true';
+    const rules = mockAlertRules.filter((r) =>
+      r.channel_ids.includes(channel.id),
+    );
+    if (rules.length > 0 && !force) {
+      return HttpResponse.json(
+        {
+          error: {
+            code: 'conflict.in_use',
+            message: `Conflict: Channel '${channel.name}' is used by ${rules.length} alert rule(s); pass force=true to detach and delete it`,
+            dependents: rules.map((r) => r.name),
+          },
+        },
+        { status: 409 },
+      );
+    }
+
     return new HttpResponse(null, { status: 204 });
   }),
 
+  http.get(
+    `${BASE_URL}/api/alert-channels/:id/stats`,
+    ({ params, request }) => {
+      const { id } = params;
+      const channel = mockNotificationChannels.find(
+        (c) => c.id === Number(id),
+      );
+
+      if (!channel) {
+        return HttpResponse.json(
+          { error: 'Channel not found' },
+          { status: 404 },
+        );
+      }
+
+      const url = new URL(request.url);
+      return HttpResponse.json({
+        channel_id: channel.id,
+        period: url.searchParams.get('period') ?? '24h',
+        attempts: 5,
+        successes: 4,
+        failures: 1,
+        failure_count: channel.failure_count,
+        last_success_at: channel.last_success_at,
+        success_rate: 0.8,
+      });
+    },
+  ),
+
+  http.post(
+    `${BASE_URL}/api/alert-channels/:id/reset-failures`,
+    ({ params }) => {
+      const { id } = params;
+      const channel = mockNotificationChannels.find(
+        (c) => c.id === Number(id),
+      );
+
+      if (!channel) {
+        return HttpResponse.json(
+          { error: 'Channel not found' },
+          { status: 404 },
+        );
+      }
+
+      return HttpResponse.json({
+        ...channel,
+        is_enabled: true,
+        failure_count: 0,
+        updated_at: new Date().toISOString(),
+      });
+    },
+  ),
+
   http.post(`${BASE_URL}/api/alert-channels/:id/test`, ({ params }) => {
     const { id } = params;
     const channel = mockNotificationChannels.find((c) => c.id === Number(id));
@@ -679,6 +1336,7 @@ export const handlers = [
         is_enabled?: boolean;
         conditions?: Record<string, unknown>;
         cooldown_minutes?: number;
+        digest_minutes?: number;
         channel_ids: number[];
       };
 
@@ -690,6 +1348,7 @@ export const handlers = [
         is_enabled: body.is_enabled ?? true,
         conditions: body.conditions ?? {},
         cooldown_minutes: body.cooldown_minutes ?? 0,
+        digest_minutes: body.digest_minutes ?? 0,
         last_triggered_at: null,
         channel_ids: body.channel_ids,
         created_at: new Date().toISOString(),
@@ -709,6 +1368,7 @@ export const handlers = [
         is_enabled?: boolean;
         conditions?: Record<string, unknown>;
         cooldown_minutes?: number;
+        digest_minutes?: number;
         channel_ids?: number[];
       };
       const rule = mockAlertRules.find(
@@ -751,13 +1411,23 @@ export const handlers = [
     ({ params, request }) => {
       const { projectId } = params;
       const url = new URL(request.url);
-      const limit = parseInt(url.searchParams.get('limit') ?? '50', 10);
+      const page = parseInt(url.searchParams.get('page') ?? '1', 10);
+      const perPage = parseInt(url.searchParams.get('per_page') ?? '20', 10);
+      const status = url.searchParams.get('status');
+      const channelId = url.searchParams.get('channel_id');
 
       const history = mockAlertHistory
         .filter((h) => h.project_id === Number(projectId))
-        .slice(0, limit);
+        .filter((h) => !status || h.status === status)
+        .filter((h) => !channelId || h.channel_id === Number(channelId));
 
-      return HttpResponse.json(history);
+      return HttpResponse.json({
+        items: history.slice((page - 1) * perPage, page * perPage),
+        total_count: history.length,
+        page,
+        per_page: perPage,
+        total_pages: Math.ceil(history.length / perPage),
+      });
     },
   ),
 ];
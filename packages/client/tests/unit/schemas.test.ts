@@ -9,6 +9,15 @@ import {
   projectSchema,
 } from '../../src/schemas/index.js';
 
+const projectKey = {
+  id: 1,
+  label: 'Default',
+  key_prefix: '123e4567...',
+  is_active: true,
+  created_at: '2026-01-20T10:00:00.000Z',
+  last_used_at: null,
+};
+
 describe('Schema Validation', () => {
   describe('projectSchema', () => {
     it('should validate valid project data', () => {
@@ -16,8 +25,7 @@ describe('Schema Validation', () => {
         id: 1,
         name: 'Test Project',
         slug: 'test-project',
-        sentry_key: '123e4567-e89b-12d3-a456-426614174000',
-        dsn: 'http://123e4567-e89b-12d3-a456-426614174000@localhost:8080/1',
+        keys: [projectKey],
         stored_event_count: 100,
         digested_event_count: 95,
         created_at: '2026-01-20T10:00:00.000Z',
@@ -28,13 +36,12 @@ describe('Schema Validation', () => {
       expect(result.success).toBe(true);
     });
 
-    it('should reject project with invalid UUID', () => {
+    it('should reject project with invalid key', () => {
       const invalidProject = {
         id: 1,
         name: 'Test Project',
         slug: 'test-project',
-        sentry_key: 'not-a-uuid',
-        dsn: 'http://localhost:8080/1',
+        keys: [{ ...projectKey, is_active: 'yes' }],
         stored_event_count: 100,
         digested_event_count: 95,
         created_at: '2026-01-20T10:00:00.000Z',
@@ -50,8 +57,7 @@ describe('Schema Validation', () => {
         id: 1,
         name: 'Test Project',
         slug: 'test-project',
-        sentry_key: '123e4567-e89b-12d3-a456-426614174000',
-        dsn: 'http://localhost:8080/1',
+        keys: [projectKey],
         stored_event_count: 100,
         digested_event_count: 95,
         created_at: 'not-a-date',
@@ -111,6 +117,7 @@ describe('Schema Validation', () => {
         short_id: 'TEST-1',
         title: 'TypeError: Cannot read property',
         value: "Cannot read property 'x' of undefined",
+        culprit: 'src/components/Profile.tsx',
         first_seen: '2026-01-20T10:00:00.000Z',
         last_seen: '2026-01-20T11:00:00.000Z',
         event_count: 5,
@@ -124,13 +131,14 @@ describe('Schema Validation', () => {
       expect(result.success).toBe(true);
     });
 
-    it('should allow null level and platform', () => {
+    it('should allow null level, platform and culprit', () => {
       const issue = {
         id: '123e4567-e89b-12d3-a456-426614174000',
         project_id: 1,
         short_id: 'TEST-1',
         title: 'Error',
         value: 'Something went wrong',
+        culprit: null,
         first_seen: '2026-01-20T10:00:00.000Z',
         last_seen: '2026-01-20T11:00:00.000Z',
         event_count: 5,
@@ -215,8 +223,7 @@ describe('Schema Validation', () => {
             id: 1,
             name: 'Project 1',
             slug: 'project-1',
-            sentry_key: '123e4567-e89b-12d3-a456-426614174000',
-            dsn: 'http://localhost:8080/1',
+            keys: [projectKey],
             stored_event_count: 100,
             digested_event_count: 95,
             created_at: '2026-01-20T10:00:00.000Z',